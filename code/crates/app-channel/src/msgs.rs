@@ -19,7 +19,7 @@ use malachitebft_engine::util::events::TxEvent;
 use crate::app::types::core::{CommitCertificate, Context, Round, ValueId, VoteExtensions};
 use crate::app::types::streaming::StreamMessage;
 use crate::app::types::sync::RawDecidedValue;
-use crate::app::types::{LocallyProposedValue, PeerId, ProposedValue};
+use crate::app::types::{LocallyProposedValue, MisbehaviorEvidence, PeerId, ProposedValue};
 
 pub type Reply<T> = oneshot::Sender<T>;
 
@@ -248,7 +248,9 @@ pub enum AppMsg<Ctx: Context> {
     /// This message includes a commit certificate containing the ID of
     /// the value that was decided on, the height and round at which it was decided,
     /// and the aggregated signatures of the validators that committed to it.
-    /// It also includes to the vote extensions received for that height.
+    /// It also includes to the vote extensions received for that height,
+    /// as well as the evidence of misbehavior (equivocating proposals and votes)
+    /// observed by consensus during that height.
     ///
     /// In response to this message, the application MUST send a [`Next`]
     /// message back to consensus, instructing it to either start the next height if
@@ -263,6 +265,9 @@ pub enum AppMsg<Ctx: Context> {
         /// The vote extensions received for that height
         extensions: VoteExtensions<Ctx>,
 
+        /// The evidence of misbehavior collected during that height
+        evidence: MisbehaviorEvidence<Ctx>,
+
         /// Channel for instructing consensus to start the next height, if desired
         reply: Reply<Next<Ctx>>,
     },
@@ -6,7 +6,8 @@ use eyre::Result;
 use crate::app::metrics::{Metrics, SharedRegistry};
 use crate::app::node::{self, EngineHandle, NodeConfig};
 use crate::app::spawn::{
-    spawn_consensus_actor, spawn_node_actor, spawn_sync_actor, spawn_wal_actor,
+    spawn_consensus_actor, spawn_evidence_pool_actor, spawn_node_actor, spawn_sync_actor,
+    spawn_wal_actor,
 };
 use crate::app::types::codec;
 use crate::app::types::core::Context;
@@ -30,7 +31,7 @@ pub async fn start_engine<Node, Ctx, WalCodec, NetCodec>(
 where
     Ctx: Context,
     Node: node::Node<Context = Ctx>,
-    WalCodec: codec::WalCodec<Ctx> + Clone,
+    WalCodec: codec::WalCodec<Ctx> + codec::EvidenceCodec<Ctx> + Clone,
     NetCodec: codec::ConsensusCodec<Ctx>,
     NetCodec: codec::SyncCodec<Ctx>,
     NetCodec: codec::HasEncodedLen<sync::Response<Ctx>>,
@@ -40,18 +41,24 @@ where
     let registry = SharedRegistry::global().with_moniker(cfg.moniker());
     let metrics = Metrics::register(&registry);
 
-    let private_key_file = node.load_private_key_file()?;
-    let private_key = node.load_private_key(private_key_file);
-    let public_key = node.get_public_key(&private_key);
+    let public_key = node.load_public_key()?;
     let address = node.get_address(&public_key);
-    let keypair = node.get_keypair(private_key.clone());
-    let signing_provider = node.get_signing_provider(private_key);
+    let keypair = node.load_keypair()?;
+    let signing_provider = node.get_signing_provider()?;
 
     // Spawn consensus gossip
-    let (network, tx_network) =
-        spawn_network_actor(cfg.consensus(), keypair, &registry, net_codec).await?;
+    let (network, tx_network) = spawn_network_actor(
+        cfg.consensus(),
+        keypair,
+        &node.get_home_dir(),
+        &registry,
+        net_codec,
+    )
+    .await?;
 
-    let wal = spawn_wal_actor(&ctx, wal_codec, &node.get_home_dir(), &registry).await?;
+    let wal = spawn_wal_actor(&ctx, wal_codec.clone(), &node.get_home_dir(), &registry).await?;
+
+    let evidence_pool = spawn_evidence_pool_actor(wal_codec, &node.get_home_dir()).await?;
 
     // Spawn the host actor
     let (connector, rx_consensus) = spawn_host_actor(metrics.clone()).await?;
@@ -79,14 +86,23 @@ where
         network.clone(),
         connector.clone(),
         wal.clone(),
+        evidence_pool.clone(),
         sync.clone(),
         metrics,
         tx_event.clone(),
     )
     .await?;
 
-    let (node, handle) =
-        spawn_node_actor(ctx, network, consensus.clone(), wal, sync, connector).await?;
+    let (node, handle) = spawn_node_actor(
+        ctx,
+        network,
+        consensus.clone(),
+        wal,
+        evidence_pool,
+        sync,
+        connector,
+    )
+    .await?;
 
     let (tx_request, rx_request) = tokio::sync::mpsc::channel(100);
     spawn_request_task(rx_request, consensus);
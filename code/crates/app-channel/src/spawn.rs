@@ -1,5 +1,7 @@
 //! Utility functions for spawning the actor system and connecting it to the application.
 
+use std::path::Path;
+
 use crate::app;
 use crate::app::config::ConsensusConfig;
 use crate::app::metrics::Metrics;
@@ -31,6 +33,7 @@ where
 pub async fn spawn_network_actor<Ctx, Codec>(
     cfg: &ConsensusConfig,
     keypair: Keypair,
+    home_dir: &Path,
     registry: &SharedRegistry,
     codec: Codec,
 ) -> Result<(NetworkRef<Ctx>, mpsc::Sender<NetworkMsg<Ctx>>)>
@@ -42,7 +45,8 @@ where
 {
     let (tx, mut rx) = mpsc::channel::<NetworkMsg<Ctx>>(1);
 
-    let actor_ref = app::spawn::spawn_network_actor(cfg, keypair, registry, codec).await?;
+    let actor_ref =
+        app::spawn::spawn_network_actor(cfg, keypair, home_dir, registry, codec).await?;
 
     tokio::spawn({
         let actor_ref = actor_ref.clone();
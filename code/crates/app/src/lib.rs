@@ -21,5 +21,6 @@ pub use malachitebft_config as config;
 pub use malachitebft_core_consensus as consensus;
 pub use malachitebft_engine as engine;
 pub use malachitebft_engine::util::streaming;
+pub use malachitebft_keystore as keystore;
 pub use malachitebft_metrics as metrics;
 pub use malachitebft_wal as wal;
@@ -68,12 +68,36 @@ pub trait Node {
 
     fn load_private_key(&self, file: Self::PrivateKeyFile) -> PrivateKey<Self::Context>;
 
+    /// Load the private key file of the node.
+    ///
+    /// Implementations should use [`keystore::load_key_file`](crate::keystore::load_key_file),
+    /// so that the key file can be encrypted with a passphrase.
     fn load_private_key_file(&self) -> eyre::Result<Self::PrivateKeyFile>;
 
     fn load_genesis(&self) -> eyre::Result<Self::Genesis>;
 
-    fn get_signing_provider(&self, private_key: PrivateKey<Self::Context>)
-        -> Self::SigningProvider;
+    /// Load the public key of the validator of the node.
+    ///
+    /// Defaults to the public key of the private key held in the private key file.
+    /// Nodes which sign with eg. a remote signer must override this to not load the private key.
+    fn load_public_key(&self) -> eyre::Result<PublicKey<Self::Context>> {
+        let private_key = self.load_private_key(self.load_private_key_file()?);
+        Ok(self.get_public_key(&private_key))
+    }
+
+    /// Load the keypair identifying the node on the network.
+    ///
+    /// Defaults to the keypair of the private key held in the private key file.
+    /// Nodes which sign with eg. a remote signer must override this to not load the private key.
+    fn load_keypair(&self) -> eyre::Result<Keypair> {
+        let private_key = self.load_private_key(self.load_private_key_file()?);
+        Ok(self.get_keypair(private_key))
+    }
+
+    /// Create the signing provider of the node, which may sign with the private key
+    /// held in the private key file, or eg. ask a remote signer to sign on behalf of the node,
+    /// in which case the private key must not be loaded.
+    fn get_signing_provider(&self) -> eyre::Result<Self::SigningProvider>;
 }
 
 #[derive(Copy, Clone, Debug)]
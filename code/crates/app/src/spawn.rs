@@ -3,24 +3,28 @@
 use std::path::Path;
 use std::time::Duration;
 
-use eyre::Result;
+use eyre::{eyre, Result};
 use malachitebft_codec::HasEncodedLen;
 use malachitebft_engine::consensus::{Consensus, ConsensusCodec, ConsensusParams, ConsensusRef};
+use malachitebft_engine::evidence::{EvidenceCodec, EvidencePool, EvidencePoolRef};
 use malachitebft_engine::host::HostRef;
 use malachitebft_engine::network::{Network, NetworkRef};
 use malachitebft_engine::node::{Node, NodeRef};
 use malachitebft_engine::sync::{Params as SyncParams, Sync, SyncCodec, SyncRef};
 use malachitebft_engine::util::events::TxEvent;
 use malachitebft_engine::wal::{Wal, WalCodec, WalRef};
+use malachitebft_network::rate_limit::RateLimit;
 use malachitebft_network::{
-    ChannelNames, Config as NetworkConfig, DiscoveryConfig, GossipSubConfig, Keypair,
+    AddressBookConfig, BootstrapProtocol, ChannelNames, Config as NetworkConfig,
+    ConnectionGaterConfig, DiscoveryConfig, GossipSubConfig, Keypair, RateLimitConfig,
+    ReputationConfig, Selector,
 };
 use malachitebft_signing::SigningProvider;
 use malachitebft_sync as sync;
 use tokio::task::JoinHandle;
 use tracing::Span;
 
-use crate::config::{ConsensusConfig, PubSubProtocol, ValueSyncConfig};
+use crate::config::{self, ConsensusConfig, PubSubProtocol, ValueSyncConfig};
 use crate::metrics::{Metrics, SharedRegistry};
 use crate::types::core::Context;
 use crate::types::ValuePayload;
@@ -30,6 +34,7 @@ pub async fn spawn_node_actor<Ctx>(
     network: NetworkRef<Ctx>,
     consensus: ConsensusRef<Ctx>,
     wal: WalRef<Ctx>,
+    evidence_pool: EvidencePoolRef<Ctx>,
     sync: Option<SyncRef<Ctx>>,
     host: HostRef<Ctx>,
 ) -> Result<(NodeRef, JoinHandle<()>)>
@@ -42,6 +47,7 @@ where
         network,
         consensus,
         wal,
+        evidence_pool,
         sync,
         host,
         tracing::Span::current(),
@@ -54,6 +60,7 @@ where
 pub async fn spawn_network_actor<Ctx, Codec>(
     cfg: &ConsensusConfig,
     keypair: Keypair,
+    home_dir: &Path,
     registry: &SharedRegistry,
     codec: Codec,
 ) -> Result<NetworkRef<Ctx>>
@@ -63,7 +70,7 @@ where
     Codec: SyncCodec<Ctx>,
     Codec: HasEncodedLen<sync::Response<Ctx>>,
 {
-    let config = make_gossip_config(cfg);
+    let config = make_gossip_config(cfg, home_dir);
 
     Network::spawn(keypair, config, registry.clone(), codec, Span::current())
         .await
@@ -82,6 +89,7 @@ pub async fn spawn_consensus_actor<Ctx>(
     network: NetworkRef<Ctx>,
     host: HostRef<Ctx>,
     wal: WalRef<Ctx>,
+    evidence_pool: EvidencePoolRef<Ctx>,
     sync: Option<SyncRef<Ctx>>,
     metrics: Metrics,
     tx_event: TxEvent<Ctx>,
@@ -97,13 +105,18 @@ where
         config::ValuePayload::ProposalAndParts => ValuePayload::ProposalAndParts,
     };
 
+    cfg.threshold_params
+        .validate()
+        .map_err(|e| eyre!("Invalid consensus threshold parameters: {e}"))?;
+
     let consensus_params = ConsensusParams {
         initial_height,
         initial_validator_set,
         address,
-        threshold_params: Default::default(),
+        threshold_params: cfg.threshold_params,
         value_payload,
         enabled: cfg.enabled,
+        accountability: cfg.accountability,
     };
 
     // Derive the consensus queue capacity from `sync.parallel_requests` and `sync.batch_size`
@@ -117,6 +130,7 @@ where
         network,
         host,
         wal,
+        evidence_pool,
         sync,
         metrics,
         tx_event,
@@ -146,6 +160,24 @@ where
         .map_err(Into::into)
 }
 
+pub async fn spawn_evidence_pool_actor<Ctx, Codec>(
+    codec: Codec,
+    home_dir: &Path,
+) -> Result<EvidencePoolRef<Ctx>>
+where
+    Ctx: Context,
+    Codec: EvidenceCodec<Ctx>,
+{
+    let evidence_dir = home_dir.join("evidence");
+    std::fs::create_dir_all(&evidence_dir)?;
+
+    let evidence_file = evidence_dir.join("evidence.log");
+
+    EvidencePool::spawn(codec, evidence_file, Span::current())
+        .await
+        .map_err(Into::into)
+}
+
 pub async fn spawn_sync_actor<Ctx>(
     ctx: Ctx,
     network: NetworkRef<Ctx>,
@@ -197,14 +229,15 @@ where
     Ok(Some(actor_ref))
 }
 
-fn make_gossip_config(cfg: &ConsensusConfig) -> NetworkConfig {
+/// Build the configuration of the network from the consensus configuration,
+/// persisting the state of the network in the given home directory.
+pub fn make_gossip_config(cfg: &ConsensusConfig, home_dir: &Path) -> NetworkConfig {
     NetworkConfig {
         listen_addr: cfg.p2p.listen_addr.clone(),
         persistent_peers: cfg.p2p.persistent_peers.clone(),
-        discovery: DiscoveryConfig {
-            enabled: cfg.p2p.discovery.enabled,
-            ..Default::default()
-        },
+        private_peer_ids: cfg.p2p.private_peer_ids.clone(),
+        discovery: make_discovery_config(&cfg.p2p.discovery),
+        address_book: make_address_book_config(&cfg.p2p.address_book, home_dir),
         idle_connection_timeout: Duration::from_secs(15 * 60),
         transport: malachitebft_network::TransportProtocol::from_multiaddr(&cfg.p2p.listen_addr)
             .unwrap_or_else(|| {
@@ -237,5 +270,79 @@ fn make_gossip_config(cfg: &ConsensusConfig) -> NetworkConfig {
             discovery_regres: cfg.p2p.protocol_names.discovery_regres.clone(),
             sync: cfg.p2p.protocol_names.sync.clone(),
         },
+        reputation: ReputationConfig {
+            enabled: cfg.p2p.reputation.enabled,
+            ban_threshold: cfg.p2p.reputation.ban_threshold,
+            ban_duration: cfg.p2p.reputation.ban_duration,
+            score_recovery_per_minute: cfg.p2p.reputation.score_recovery_per_minute,
+            ban_file: Some(home_dir.join("network").join("peer_bans.json")),
+        },
+        rate_limits: make_rate_limits(&cfg.p2p.rate_limits),
+        connection_gater: make_connection_gater(&cfg.p2p.connection_gater),
+        validator_mesh: cfg.p2p.validator_mesh.enabled,
+        sentry_peer_ids: cfg.p2p.validator_mesh.sentry_peer_ids.clone(),
+    }
+}
+
+fn make_discovery_config(cfg: &config::DiscoveryConfig) -> DiscoveryConfig {
+    let bootstrap_protocol = match cfg.bootstrap_protocol {
+        config::BootstrapProtocol::Kademlia => BootstrapProtocol::Kademlia,
+        config::BootstrapProtocol::Full => BootstrapProtocol::Full,
+        config::BootstrapProtocol::Pex => BootstrapProtocol::Pex,
+    };
+
+    let selector = match cfg.selector {
+        config::Selector::Kademlia => Selector::Kademlia,
+        config::Selector::Random => Selector::Random,
+        config::Selector::Latency => Selector::Latency,
+    };
+
+    DiscoveryConfig {
+        enabled: cfg.enabled,
+        bootstrap_protocol,
+        selector,
+        num_outbound_peers: cfg.num_outbound_peers,
+        num_inbound_peers: cfg.num_inbound_peers,
+        max_connections_per_peer: cfg.max_connections_per_peer,
+        max_outbound_peers_per_subnet: cfg.max_outbound_peers_per_subnet,
+        ephemeral_connection_timeout: cfg.ephemeral_connection_timeout,
+        pex_interval: cfg.pex_interval,
+        require_signed_peer_records: cfg.require_signed_peer_records,
+        ..Default::default()
+    }
+}
+
+fn make_connection_gater(cfg: &config::ConnectionGaterConfig) -> ConnectionGaterConfig {
+    ConnectionGaterConfig {
+        allowed_peers: cfg.allowed_peers.iter().copied().collect(),
+        denied_peers: cfg.denied_peers.iter().copied().collect(),
+        allowed_ips: cfg.allowed_ips.clone(),
+        denied_ips: cfg.denied_ips.clone(),
+    }
+}
+
+fn make_address_book_config(cfg: &config::AddressBookConfig, home_dir: &Path) -> AddressBookConfig {
+    AddressBookConfig {
+        file: cfg
+            .enabled
+            .then(|| home_dir.join("network").join("address_book.json")),
+        max_age: cfg.max_age,
+        max_failures: cfg.max_failures,
+        max_entries: cfg.max_entries,
+    }
+}
+
+fn make_rate_limits(cfg: &config::RateLimitConfig) -> RateLimitConfig {
+    let limit = |limit: config::RateLimit| {
+        RateLimit::new(limit.messages_per_sec, limit.bytes_per_sec.as_u64())
+    };
+
+    RateLimitConfig {
+        enabled: cfg.enabled,
+        consensus: limit(cfg.consensus),
+        proposal_parts: limit(cfg.proposal_parts),
+        sync: limit(cfg.sync),
+        liveness: limit(cfg.liveness),
+        evidence: limit(cfg.evidence),
     }
 }
@@ -3,7 +3,7 @@
 pub use libp2p_identity::Keypair;
 
 pub use malachitebft_core_consensus::{
-    ConsensusMsg, ProposedValue, SignedConsensusMsg, ValuePayload,
+    ConsensusMsg, MisbehaviorEvidence, ProposedValue, SignedConsensusMsg, ValuePayload,
 };
 pub use malachitebft_engine::host::LocallyProposedValue;
 pub use malachitebft_peer::PeerId;
@@ -24,6 +24,11 @@ pub mod codec {
     pub use malachitebft_codec::Codec;
     pub use malachitebft_codec::HasEncodedLen;
     pub use malachitebft_engine::consensus::ConsensusCodec;
+    pub use malachitebft_engine::evidence::EvidenceCodec;
     pub use malachitebft_engine::sync::SyncCodec;
     pub use malachitebft_engine::wal::WalCodec;
 }
+
+pub mod signing {
+    pub use malachitebft_signing::{SigningProvider, SigningProviderExt};
+}
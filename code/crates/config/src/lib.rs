@@ -1,10 +1,14 @@
 use core::fmt;
 use std::net::{IpAddr, SocketAddr};
+use std::num::NonZeroU64;
+use std::path::PathBuf;
 use std::str::FromStr;
 use std::time::Duration;
 
 use bytesize::ByteSize;
-use malachitebft_core_types::TimeoutKind;
+use ipnet::IpNet;
+use malachitebft_core_types::{ThresholdParams, TimeoutKind};
+use malachitebft_peer::PeerId;
 use multiaddr::Multiaddr;
 use serde::{Deserialize, Serialize};
 
@@ -39,6 +43,12 @@ pub struct P2pConfig {
     /// List of nodes to keep persistent connections to
     pub persistent_peers: Vec<Multiaddr>,
 
+    /// IDs of the peers which must never be advertised to other peers, through Kademlia
+    /// or peers requests, eg. the validators behind this node when it acts as a sentry.
+    /// See [`ValidatorMeshConfig::sentry_peer_ids`] for using sentries with the validator mesh
+    #[serde(default)]
+    pub private_peer_ids: Vec<PeerId>,
+
     /// Peer discovery
     #[serde(default)]
     pub discovery: DiscoveryConfig,
@@ -55,6 +65,26 @@ pub struct P2pConfig {
     /// Protocol name configuration
     #[serde(default)]
     pub protocol_names: ProtocolNames,
+
+    /// Persistent address book of known peers
+    #[serde(default)]
+    pub address_book: AddressBookConfig,
+
+    /// Peer reputation and banning
+    #[serde(default)]
+    pub reputation: ReputationConfig,
+
+    /// Per-peer inbound rate limits
+    #[serde(default)]
+    pub rate_limits: RateLimitConfig,
+
+    /// Validator-only gossip mesh
+    #[serde(default)]
+    pub validator_mesh: ValidatorMeshConfig,
+
+    /// Peers and IP addresses allowed or denied to connect
+    #[serde(default)]
+    pub connection_gater: ConnectionGaterConfig,
 }
 
 impl Default for P2pConfig {
@@ -62,11 +92,189 @@ impl Default for P2pConfig {
         P2pConfig {
             listen_addr: Multiaddr::empty(),
             persistent_peers: vec![],
+            private_peer_ids: vec![],
             discovery: Default::default(),
             protocol: Default::default(),
             rpc_max_size: ByteSize::mib(10),
             pubsub_max_size: ByteSize::mib(4),
             protocol_names: Default::default(),
+            address_book: Default::default(),
+            reputation: Default::default(),
+            rate_limits: Default::default(),
+            validator_mesh: Default::default(),
+            connection_gater: Default::default(),
+        }
+    }
+}
+
+/// Address book configuration options
+///
+/// The addresses of the peers this node connects to are persisted in an address book,
+/// from which a restarted node dials peers to rejoin the network without depending
+/// on its persistent peers being up. Peers which have not been seen for a while,
+/// or which could not be dialed too many times in a row, are removed from it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AddressBookConfig {
+    /// Persist the address book across restarts
+    pub enabled: bool,
+
+    /// How long a peer is kept after it was last seen
+    #[serde(with = "humantime_serde")]
+    pub max_age: Duration,
+
+    /// Number of failed dials in a row after which a peer is removed
+    pub max_failures: u32,
+
+    /// Maximum number of peers, inbound peers being evicted before discovered peers and bootstrap
+    /// nodes, and the least recently seen peers from the most represented subnets first
+    pub max_entries: usize,
+}
+
+impl Default for AddressBookConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_age: Duration::from_secs(3 * 24 * 60 * 60),
+            max_failures: 5,
+            max_entries: 1000,
+        }
+    }
+}
+
+/// Peer reputation configuration options
+///
+/// Peers lose score when they misbehave, eg. by sending undecodable messages or messages with
+/// invalid signatures, and slowly regain it over time. Peers whose score falls to the ban
+/// threshold are disconnected and banned for a while, even across restarts.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReputationConfig {
+    /// Ban misbehaving peers
+    pub enabled: bool,
+
+    /// Score at or below which a peer is banned (scores start at zero)
+    pub ban_threshold: f64,
+
+    /// How long a peer is banned for
+    #[serde(with = "humantime_serde")]
+    pub ban_duration: Duration,
+
+    /// Amount by which the score of a peer recovers every minute, up to zero
+    pub score_recovery_per_minute: f64,
+}
+
+impl Default for ReputationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ban_threshold: -100.0,
+            ban_duration: Duration::from_secs(60 * 60),
+            score_recovery_per_minute: 10.0,
+        }
+    }
+}
+
+/// Per-peer inbound rate limits, for each channel
+///
+/// Messages received from a peer in excess of the limits of a channel are dropped
+/// before they reach consensus or sync, so that a single peer cannot flood them.
+/// Peers may burst up to one second worth of traffic.
+/// GossipSub messages count against the peer which signed them, not the peer which relayed them.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    /// Rate limit inbound messages
+    pub enabled: bool,
+
+    /// Limit for votes and proposals
+    pub consensus: RateLimit,
+
+    /// Limit for proposal parts
+    pub proposal_parts: RateLimit,
+
+    /// Limit for status messages and inbound sync requests
+    pub sync: RateLimit,
+
+    /// Limit for certificates and rebroadcast votes
+    pub liveness: RateLimit,
+
+    /// Limit for evidence of misbehavior
+    pub evidence: RateLimit,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            consensus: RateLimit::new(200, ByteSize::mib(4)),
+            proposal_parts: RateLimit::new(2000, ByteSize::mib(64)),
+            sync: RateLimit::new(100, ByteSize::mib(4)),
+            liveness: RateLimit::new(100, ByteSize::mib(16)),
+            evidence: RateLimit::new(20, ByteSize::mib(1)),
+        }
+    }
+}
+
+/// Validator-only gossip mesh configuration options
+///
+/// When enabled, votes and proposals are only accepted from the peers which prove that they
+/// are operated by a validator of the current validator set, by signing their peer ID with their
+/// consensus key, or from the listed sentry nodes. Other channels are not restricted.
+///
+/// Requires the GossipSub pub-sub protocol.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ValidatorMeshConfig {
+    /// Restrict consensus gossip to validators
+    pub enabled: bool,
+
+    /// IDs of the sentry nodes this node is connected to, which may gossip votes and proposals
+    /// without being validators, eg. the sentries of this validator, or of other validators
+    /// when this node is itself a sentry
+    pub sentry_peer_ids: Vec<PeerId>,
+}
+
+/// Connection gating configuration options
+///
+/// A peer or IP address is denied if it is in the corresponding deny list, or if the
+/// corresponding allow list is not empty and does not contain it. Connections from denied
+/// IP addresses are refused before the handshake, and connections to or from denied peers
+/// as soon as the handshake authenticates them.
+///
+/// When restricting the allowed peers, the persistent peers must be allowed too.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConnectionGaterConfig {
+    /// IDs of the peers allowed to connect, or any peer if empty
+    pub allowed_peers: Vec<PeerId>,
+
+    /// IDs of the peers never allowed to connect
+    pub denied_peers: Vec<PeerId>,
+
+    /// IP ranges in CIDR notation allowed to connect from or be dialed, or any IP address if empty.
+    /// DNS addresses are never dialed if not empty.
+    pub allowed_ips: Vec<IpNet>,
+
+    /// IP ranges in CIDR notation never allowed to connect from or be dialed
+    pub denied_ips: Vec<IpNet>,
+}
+
+/// Rate limit of a single channel, for each peer
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimit {
+    /// Maximum number of messages per second, or zero for no limit
+    pub messages_per_sec: u32,
+
+    /// Maximum number of bytes per second, or zero for no limit
+    pub bytes_per_sec: ByteSize,
+}
+
+impl RateLimit {
+    pub const fn new(messages_per_sec: u32, bytes_per_sec: ByteSize) -> Self {
+        Self {
+            messages_per_sec,
+            bytes_per_sec,
         }
     }
 }
@@ -98,10 +306,28 @@ pub struct DiscoveryConfig {
     #[serde(default)]
     pub max_connections_per_peer: usize,
 
+    /// Maximum number of outbound peers in the same subnet (/16 for IPv4, /32 for IPv6)
+    /// with the latency selector, or zero for no limit.
+    /// Peers on loopback, private or link-local addresses are not limited.
+    #[serde(default = "default_max_outbound_peers_per_subnet")]
+    pub max_outbound_peers_per_subnet: usize,
+
     /// Ephemeral connection timeout
     #[serde(default)]
     #[serde(with = "humantime_serde")]
     pub ephemeral_connection_timeout: Duration,
+
+    /// Interval at which a connected peer is asked for more peers,
+    /// with the peer exchange bootstrap protocol, or zero to only ask
+    /// peers when connecting to them
+    #[serde(default = "default_pex_interval")]
+    #[serde(with = "humantime_serde")]
+    pub pex_interval: Duration,
+
+    /// Require peers to identify with a signed peer record,
+    /// disconnecting from the peers which do not
+    #[serde(default = "default_require_signed_peer_records")]
+    pub require_signed_peer_records: bool,
 }
 
 impl Default for DiscoveryConfig {
@@ -113,17 +339,33 @@ impl Default for DiscoveryConfig {
             num_outbound_peers: 0,
             num_inbound_peers: 20,
             max_connections_per_peer: 5,
+            max_outbound_peers_per_subnet: default_max_outbound_peers_per_subnet(),
             ephemeral_connection_timeout: Default::default(),
+            pex_interval: default_pex_interval(),
+            require_signed_peer_records: default_require_signed_peer_records(),
         }
     }
 }
 
+fn default_pex_interval() -> Duration {
+    Duration::from_secs(30)
+}
+
+fn default_max_outbound_peers_per_subnet() -> usize {
+    3
+}
+
+fn default_require_signed_peer_records() -> bool {
+    true
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum BootstrapProtocol {
     #[default]
     Kademlia,
     Full,
+    Pex,
 }
 
 impl BootstrapProtocol {
@@ -131,6 +373,7 @@ impl BootstrapProtocol {
         match self {
             Self::Kademlia => "kademlia",
             Self::Full => "full",
+            Self::Pex => "pex",
         }
     }
 }
@@ -142,8 +385,9 @@ impl FromStr for BootstrapProtocol {
         match s {
             "kademlia" => Ok(Self::Kademlia),
             "full" => Ok(Self::Full),
+            "pex" => Ok(Self::Pex),
             e => Err(format!(
-                "unknown bootstrap protocol: {e}, available: kademlia, full"
+                "unknown bootstrap protocol: {e}, available: kademlia, full, pex"
             )),
         }
     }
@@ -155,6 +399,7 @@ pub enum Selector {
     #[default]
     Kademlia,
     Random,
+    Latency,
 }
 
 impl Selector {
@@ -162,6 +407,7 @@ impl Selector {
         match self {
             Self::Kademlia => "kademlia",
             Self::Random => "random",
+            Self::Latency => "latency",
         }
     }
 }
@@ -173,8 +419,9 @@ impl FromStr for Selector {
         match s {
             "kademlia" => Ok(Self::Kademlia),
             "random" => Ok(Self::Random),
+            "latency" => Ok(Self::Latency),
             e => Err(format!(
-                "unknown selector: {e}, available: kademlia, random"
+                "unknown selector: {e}, available: kademlia, random, latency"
             )),
         }
     }
@@ -523,6 +770,14 @@ fn default_consensus_enabled() -> bool {
     true
 }
 
+fn default_validator_set_update_delay() -> NonZeroU64 {
+    NonZeroU64::MIN
+}
+
+fn default_max_evidence_age() -> u64 {
+    100
+}
+
 /// Consensus configuration options
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ConsensusConfig {
@@ -533,6 +788,50 @@ pub struct ConsensusConfig {
     #[serde(default = "default_consensus_enabled")]
     pub enabled: bool,
 
+    /// Enable accountability mode
+    ///
+    /// When enabled, consensus tracks the votes of every validator across
+    /// the rounds of a height in order to detect lock violations (amnesia attacks)
+    #[serde(default)]
+    pub accountability: bool,
+
+    /// Number of heights for which evidence of misbehavior is accepted and kept
+    ///
+    /// Evidence received from peers for a height more than `max_evidence_age` heights
+    /// below the current height is ignored, and such evidence is pruned from the evidence pool.
+    #[serde(default = "default_max_evidence_age")]
+    pub max_evidence_age: u64,
+
+    /// Number of heights after which a validator set update takes effect
+    ///
+    /// An update returned by the application when deciding height `h`
+    /// applies to the validator set of height `h + validator_set_update_delay`.
+    /// Must be at least 1, a delay of 0 is rejected when loading the configuration.
+    #[serde(default = "default_validator_set_update_delay")]
+    pub validator_set_update_delay: NonZeroU64,
+
+    /// Aggregate the precommit signatures of decided commit certificates into a single signature
+    ///
+    /// When enabled, the certificate passed to the application upon a decision, which it stores
+    /// and serves to syncing peers, carries a single aggregated signature instead of one signature
+    /// per precommit. This requires a signing provider which supports signature aggregation, eg. BLS.
+    /// If aggregation fails, the certificate is passed to the application as is.
+    ///
+    /// Verifying an aggregated certificate requires the signing provider to have admitted the
+    /// public key of every signer along with its proof of possession, eg. through
+    /// `BlsSigningProvider::admit_public_key`. This applies to the genesis validators as well as
+    /// to validators added by an update to the validator set, otherwise the certificates they
+    /// sign, including those received through value sync, are rejected.
+    #[serde(default)]
+    pub aggregate_commit_certificates: bool,
+
+    /// Quorum and honest thresholds, as fractions of the total voting power
+    ///
+    /// Defaults to a quorum of more than 2/3 and an honest threshold of more than 1/3.
+    /// See [`ThresholdParams::validate`] for the constraints these must satisfy.
+    #[serde(default)]
+    pub threshold_params: ThresholdParams,
+
     /// Timeouts
     #[serde(flatten)]
     pub timeouts: TimeoutConfig,
@@ -556,6 +855,11 @@ impl Default for ConsensusConfig {
     fn default() -> Self {
         Self {
             enabled: true,
+            accountability: false,
+            max_evidence_age: default_max_evidence_age(),
+            validator_set_update_delay: default_validator_set_update_delay(),
+            aggregate_commit_certificates: false,
+            threshold_params: ThresholdParams::default(),
             timeouts: TimeoutConfig::default(),
             p2p: P2pConfig::default(),
             value_payload: ValuePayload::default(),
@@ -706,6 +1010,52 @@ impl RuntimeConfig {
     }
 }
 
+/// How the node signs votes, proposals, proposal parts and vote extensions
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SignerConfig {
+    /// Sign with the private key of the validator, loaded from the private key file of the node
+    #[default]
+    Local,
+
+    /// Ask a remote signer to sign on behalf of the node
+    Remote(RemoteSignerConfig),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RemoteSignerConfig {
+    /// Address of the remote signer, eg. `tcp://127.0.0.1:26659` or `unix:///var/run/signer.sock`
+    pub address: String,
+
+    /// Identity public key of the remote signer, as 64 hexadecimal characters
+    ///
+    /// The node refuses to talk to a signer with any other identity.
+    pub signer_public_key: String,
+
+    /// Path to the file holding the identity key of the node, which is generated if it does not exist
+    ///
+    /// The remote signer must be configured with the public part of this key.
+    pub identity_key_file: PathBuf,
+
+    /// Public key of the validator which the remote signer signs for, as hexadecimal characters
+    ///
+    /// The node never loads the private key of the validator, so it cannot derive it.
+    pub validator_public_key: String,
+
+    /// Path to the file holding the key identifying the node on the network, which is generated if it does not exist
+    ///
+    /// Without a remote signer, the node uses the private key of the validator instead.
+    pub network_key_file: PathBuf,
+
+    /// How long to wait for the remote signer to respond to a request
+    #[serde(with = "humantime_serde", default = "default_remote_signer_timeout")]
+    pub timeout: Duration,
+}
+
+fn default_remote_signer_timeout() -> Duration {
+    Duration::from_secs(5)
+}
+
 #[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct VoteExtensionsConfig {
     pub enabled: bool,
@@ -724,6 +1074,8 @@ pub struct TestConfig {
     pub vote_extensions: VoteExtensionsConfig,
     #[serde(default)]
     pub stable_block_times: bool,
+    #[serde(default)]
+    pub proposer_selection: ProposerSelection,
 }
 
 impl Default for TestConfig {
@@ -736,10 +1088,22 @@ impl Default for TestConfig {
             max_retain_blocks: 1000,
             vote_extensions: VoteExtensionsConfig::default(),
             stable_block_times: false,
+            proposer_selection: ProposerSelection::default(),
         }
     }
 }
 
+/// How the test application selects the proposer of each round
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProposerSelection {
+    /// Rotate through the validators, regardless of their voting power
+    #[default]
+    RoundRobin,
+    /// Select validators in proportion to their voting power, as in Tendermint and CometBFT
+    Weighted,
+}
+
 #[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct LoggingConfig {
     pub log_level: LogLevel,
@@ -996,4 +1360,180 @@ mod tests {
         // Should use defaults when protocol_names section is missing
         assert_eq!(config.p2p.protocol_names, ProtocolNames::default());
     }
+
+    #[test]
+    fn threshold_params_toml_deserialization() {
+        use malachitebft_core_types::ThresholdParam;
+
+        let toml_content = r#"
+        timeout_propose = "3s"
+        timeout_propose_delta = "500ms"
+        timeout_prevote = "1s"
+        timeout_prevote_delta = "500ms"
+        timeout_precommit = "1s"
+        timeout_precommit_delta = "500ms"
+        timeout_rebroadcast = "5s"
+        value_payload = "parts-only"
+
+        [threshold_params]
+        quorum = { numerator = 3, denominator = 4 }
+        honest = { numerator = 1, denominator = 4 }
+
+        [p2p]
+        listen_addr = "/ip4/0.0.0.0/tcp/0"
+        persistent_peers = []
+        pubsub_max_size = "4 MiB"
+        rpc_max_size = "10 MiB"
+
+        [p2p.protocol]
+        type = "gossipsub"
+        "#;
+
+        let config: ConsensusConfig = toml::from_str(toml_content).unwrap();
+
+        assert_eq!(config.threshold_params.quorum, ThresholdParam::new(3, 4));
+        assert_eq!(config.threshold_params.honest, ThresholdParam::new(1, 4));
+        assert_eq!(config.threshold_params.validate(), Ok(()));
+
+        // Should use defaults when threshold_params section is missing
+        let toml_content = toml_content.replace("[threshold_params]", "[other]");
+        let config: ConsensusConfig = toml::from_str(&toml_content).unwrap();
+
+        assert_eq!(config.threshold_params, ThresholdParams::default());
+    }
+
+    #[test]
+    fn validator_set_update_delay_toml_deserialization() {
+        let toml_content = r#"
+        timeout_propose = "3s"
+        timeout_propose_delta = "500ms"
+        timeout_prevote = "1s"
+        timeout_prevote_delta = "500ms"
+        timeout_precommit = "1s"
+        timeout_precommit_delta = "500ms"
+        timeout_rebroadcast = "5s"
+        value_payload = "parts-only"
+        validator_set_update_delay = 3
+
+        [p2p]
+        listen_addr = "/ip4/0.0.0.0/tcp/0"
+        persistent_peers = []
+        pubsub_max_size = "4 MiB"
+        rpc_max_size = "10 MiB"
+
+        [p2p.protocol]
+        type = "gossipsub"
+        "#;
+
+        let config: ConsensusConfig = toml::from_str(toml_content).unwrap();
+        assert_eq!(config.validator_set_update_delay.get(), 3);
+
+        // A delay of 0 is rejected
+        let toml_content = toml_content.replace(
+            "validator_set_update_delay = 3",
+            "validator_set_update_delay = 0",
+        );
+        assert!(toml::from_str::<ConsensusConfig>(&toml_content).is_err());
+
+        // Should use the default when the delay is missing
+        let toml_content = toml_content.replace("validator_set_update_delay = 0", "");
+        let config: ConsensusConfig = toml::from_str(&toml_content).unwrap();
+        assert_eq!(config.validator_set_update_delay, NonZeroU64::MIN);
+    }
+
+    #[test]
+    fn connection_gater_toml_deserialization() {
+        let toml_content = r#"
+        timeout_propose = "3s"
+        timeout_propose_delta = "500ms"
+        timeout_prevote = "1s"
+        timeout_prevote_delta = "500ms"
+        timeout_precommit = "1s"
+        timeout_precommit_delta = "500ms"
+        timeout_rebroadcast = "5s"
+        value_payload = "parts-only"
+
+        [p2p]
+        listen_addr = "/ip4/0.0.0.0/tcp/0"
+        persistent_peers = []
+        pubsub_max_size = "4 MiB"
+        rpc_max_size = "10 MiB"
+
+        [p2p.protocol]
+        type = "gossipsub"
+
+        [p2p.connection_gater]
+        allowed_peers = ["12D3KooWJbMdaFr9Y5wh8ifoQoX87y2tsRmrwJyCnbww5JHSB5ds"]
+        allowed_ips = ["10.0.0.0/8", "fd00::/8"]
+        denied_ips = ["10.1.2.3/32"]
+        "#;
+
+        let config: ConsensusConfig = toml::from_str(toml_content).unwrap();
+        let gater = &config.p2p.connection_gater;
+
+        assert_eq!(
+            gater.allowed_peers,
+            vec![PeerId::from_str("12D3KooWJbMdaFr9Y5wh8ifoQoX87y2tsRmrwJyCnbww5JHSB5ds").unwrap()]
+        );
+        assert!(gater.denied_peers.is_empty());
+        assert_eq!(
+            gater.allowed_ips,
+            vec![
+                IpNet::from_str("10.0.0.0/8").unwrap(),
+                IpNet::from_str("fd00::/8").unwrap()
+            ]
+        );
+        assert_eq!(
+            gater.denied_ips,
+            vec![IpNet::from_str("10.1.2.3/32").unwrap()]
+        );
+
+        // Should allow everything when the connection_gater section is missing
+        let toml_content = toml_content.replace("[p2p.connection_gater]", "[other]");
+        let config: ConsensusConfig = toml::from_str(&toml_content).unwrap();
+
+        assert_eq!(
+            config.p2p.connection_gater,
+            ConnectionGaterConfig::default()
+        );
+    }
+
+    #[test]
+    fn signer_toml_deserialization() {
+        let toml_content = r#"
+        type = "remote"
+        address = "tcp://127.0.0.1:26659"
+        signer_public_key = "7d8f5a1c2b3e4f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4b5c6d7e8"
+        identity_key_file = "config/signer_identity_key"
+        validator_public_key = "0a1b2c3d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4b5c6d7e8f9"
+        network_key_file = "config/network_key"
+        timeout = "2s"
+        "#;
+
+        let config: SignerConfig = toml::from_str(toml_content).unwrap();
+
+        assert_eq!(
+            config,
+            SignerConfig::Remote(RemoteSignerConfig {
+                address: "tcp://127.0.0.1:26659".to_string(),
+                signer_public_key:
+                    "7d8f5a1c2b3e4f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4b5c6d7e8".to_string(),
+                identity_key_file: PathBuf::from("config/signer_identity_key"),
+                validator_public_key:
+                    "0a1b2c3d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4b5c6d7e8f9".to_string(),
+                network_key_file: PathBuf::from("config/network_key"),
+                timeout: Duration::from_secs(2),
+            })
+        );
+
+        // Should use the default timeout when it is missing
+        let toml_content = toml_content.replace("timeout = \"2s\"", "");
+        let SignerConfig::Remote(config) = toml::from_str(&toml_content).unwrap() else {
+            panic!("expected a remote signer configuration");
+        };
+        assert_eq!(config.timeout, Duration::from_secs(5));
+
+        let config: SignerConfig = toml::from_str(r#"type = "local""#).unwrap();
+        assert_eq!(config, SignerConfig::Local);
+    }
 }
@@ -2,7 +2,7 @@ use derive_where::derive_where;
 
 use malachitebft_core_types::*;
 
-use crate::types::{LivenessMsg, SignedConsensusMsg};
+use crate::types::{LivenessMsg, MisbehaviorEvidence, SignedConsensusMsg};
 use crate::{ConsensusMsg, Error, PeerId, Role, VoteExtensionError, WalEntry};
 
 /// Provides a way to construct the appropriate [`Resume`] value to
@@ -158,12 +158,14 @@ where
     /// the value that was decided on, the height and round at which it was decided,
     /// and the aggregated signatures of the validators that committed to it.
     ///
-    /// It also includes the vote extensions that were received for this height.
+    /// It also includes the vote extensions that were received for this height,
+    /// as well as the evidence of misbehavior that was collected during this height.
     ///
     /// Resume with: [`resume::Continue`]
     Decide(
         CommitCertificate<Ctx>,
         VoteExtensions<Ctx>,
+        MisbehaviorEvidence<Ctx>,
         resume::Continue,
     ),
 
@@ -1,3 +1,4 @@
+use crate::types::MisbehaviorEvidence;
 use crate::{handle::signature::verify_commit_certificate, prelude::*};
 
 #[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
@@ -102,9 +103,19 @@ where
         }
     }
 
+    let evidence = MisbehaviorEvidence::new(
+        state.driver.proposals().evidence().clone(),
+        state.driver.votes().evidence().clone(),
+        state.driver.amnesia_evidence().cloned().unwrap_or_default(),
+    );
+
+    if !evidence.is_empty() {
+        warn!(%height, "Misbehavior evidence was collected during this height");
+    }
+
     perform!(
         co,
-        Effect::Decide(certificate, extensions, Default::default())
+        Effect::Decide(certificate, extensions, evidence, Default::default())
     );
 
     Ok(())
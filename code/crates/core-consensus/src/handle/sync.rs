@@ -37,7 +37,7 @@ where
 
     info!(
         certificate.height = %cert_height,
-        signatures = value.certificate.commit_signatures.len(),
+        signatures = value.certificate.signature_count(),
         "Processing value response"
     );
 
@@ -71,7 +71,7 @@ where
 {
     debug!(
         certificate.height = %certificate.height,
-        signatures = certificate.commit_signatures.len(),
+        signatures = certificate.signature_count(),
         "Processing certificate"
     );
 
@@ -28,4 +28,8 @@ pub struct Params<Ctx: Context> {
 
     /// Whether consensus is enabled for this node
     pub enabled: bool,
+
+    /// Whether to track the votes of every validator across the rounds of a height
+    /// in order to detect lock violations (amnesia attacks)
+    pub accountability: bool,
 }
@@ -51,7 +51,8 @@ where
             params.initial_validator_set.clone(),
             params.address.clone(),
             params.threshold_params,
-        );
+        )
+        .with_accountability(params.accountability);
 
         Self {
             ctx,
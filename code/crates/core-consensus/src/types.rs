@@ -2,11 +2,14 @@ use derive_where::derive_where;
 use thiserror::Error;
 
 use malachitebft_core_types::{
-    Context, PolkaCertificate, Proposal, Round, RoundCertificate, Signature, SignedProposal,
-    SignedVote, Timeout, Validity, Vote,
+    Context, Evidence, PolkaCertificate, Proposal, Round, RoundCertificate, Signature,
+    SignedProposal, SignedVote, Timeout, Validity, Vote,
 };
 
+pub use malachitebft_core_driver::accountability::EvidenceMap as AmnesiaEvidenceMap;
+pub use malachitebft_core_driver::proposal_keeper::EvidenceMap as ProposalEvidenceMap;
 pub use malachitebft_core_types::ValuePayload;
+pub use malachitebft_core_votekeeper::evidence::EvidenceMap as VoteEvidenceMap;
 
 pub use malachitebft_peer::PeerId;
 pub use multiaddr::Multiaddr;
@@ -94,6 +97,10 @@ pub enum WalEntry<Ctx: Context> {
     ConsensusMsg(SignedConsensusMsg<Ctx>),
     Timeout(Timeout),
     ProposedValue(ProposedValue<Ctx>),
+
+    /// Validator sets resulting from updates which have not taken effect yet,
+    /// along with the height at which each of them takes effect
+    PendingValidatorSets(Vec<(Ctx::Height, Ctx::ValidatorSet)>),
 }
 
 impl<Ctx: Context> WalEntry<Ctx> {
@@ -133,3 +140,100 @@ pub enum LivenessMsg<Ctx: Context> {
     PolkaCertificate(PolkaCertificate<Ctx>),
     SkipRoundCertificate(RoundCertificate<Ctx>),
 }
+
+/// Evidence of misbehavior collected by consensus over the course of a height.
+///
+/// Contains the conflicting proposals and votes that were observed,
+/// grouped by the address of the equivocating validator.
+#[derive_where(Clone, Debug, Default)]
+pub struct MisbehaviorEvidence<Ctx: Context> {
+    /// Evidence of equivocating proposals, ie. two different proposals for the same height and round
+    pub proposals: ProposalEvidenceMap<Ctx>,
+    /// Evidence of equivocating votes, ie. two different votes of the same type for the same height and round
+    pub votes: VoteEvidenceMap<Ctx>,
+    /// Evidence of lock violations, ie. a prevote contradicting an earlier precommit without a justifying polka.
+    /// Only gathered when accountability is enabled, see [`Params::accountability`](crate::Params::accountability).
+    pub amnesia: AmnesiaEvidenceMap<Ctx>,
+}
+
+impl<Ctx: Context> MisbehaviorEvidence<Ctx> {
+    pub fn new(
+        proposals: ProposalEvidenceMap<Ctx>,
+        votes: VoteEvidenceMap<Ctx>,
+        amnesia: AmnesiaEvidenceMap<Ctx>,
+    ) -> Self {
+        Self {
+            proposals,
+            votes,
+            amnesia,
+        }
+    }
+
+    /// Whether or not any misbehavior was observed.
+    pub fn is_empty(&self) -> bool {
+        self.proposals.is_empty() && self.votes.is_empty() && self.amnesia.is_empty()
+    }
+
+    /// Iterate over all the evidence of misbehavior, regardless of its kind.
+    pub fn iter(&self) -> impl Iterator<Item = Evidence<Ctx>> + '_ {
+        let proposals = self
+            .proposals
+            .iter()
+            .flat_map(|(_, entries)| entries.iter().cloned().map(Evidence::DoubleProposal));
+
+        let votes = (self.votes.iter())
+            .flat_map(|(_, entries)| entries.iter().cloned().map(Evidence::DoubleVote));
+
+        let amnesia = (self.amnesia.iter())
+            .flat_map(|(_, entries)| entries.iter().cloned().map(Evidence::Amnesia));
+
+        proposals.chain(votes).chain(amnesia)
+    }
+
+    /// Add evidence of misbehavior, eg. received from a peer, unless there already is evidence
+    /// of the same misbehavior, ie. by the same validator at the same height and round,
+    /// and for the same vote type, even if made of other messages.
+    pub fn add(&mut self, evidence: Evidence<Ctx>) {
+        let address = evidence.validator_address().clone();
+
+        match evidence {
+            Evidence::DoubleProposal(evidence) => {
+                let known = self.proposals.get(&address).is_some_and(|entries| {
+                    entries
+                        .iter()
+                        .any(|e| e.height() == evidence.height() && e.round() == evidence.round())
+                });
+
+                if !known {
+                    self.proposals.add(evidence.first, evidence.second);
+                }
+            }
+            Evidence::DoubleVote(evidence) => {
+                let known = self.votes.get(&address).is_some_and(|entries| {
+                    entries.iter().any(|e| {
+                        e.height() == evidence.height()
+                            && e.round() == evidence.round()
+                            && e.vote_type() == evidence.vote_type()
+                    })
+                });
+
+                if !known {
+                    self.votes.add(evidence.first, evidence.second);
+                }
+            }
+            Evidence::Amnesia(evidence) => {
+                let known = self.amnesia.get(&address).is_some_and(|entries| {
+                    entries.iter().any(|e| {
+                        e.height() == evidence.height()
+                            && e.locked_round() == evidence.locked_round()
+                            && e.prevote_round() == evidence.prevote_round()
+                    })
+                });
+
+                if !known {
+                    self.amnesia.add(evidence);
+                }
+            }
+        }
+    }
+}
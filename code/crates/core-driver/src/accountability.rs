@@ -0,0 +1,205 @@
+//! Detection of lock violations (amnesia attacks).
+//!
+//! When accountability is enabled, the driver records the latest lock of every validator at the
+//! current height, ie. its highest-round non-nil precommit, along with its non-nil prevotes in later
+//! rounds and the polkas it has observed, and flags prevotes which contradict that lock without
+//! a justifying polka.
+
+use alloc::collections::btree_map::BTreeMap;
+use alloc::{vec, vec::Vec};
+
+use derive_where::derive_where;
+
+use malachitebft_core_types::{
+    AmnesiaEvidence, Context, Round, SignedVote, ValueId, Vote, VoteType,
+};
+
+/// Keeps track of evidence of lock violations.
+#[derive_where(Clone, Debug, Default)]
+pub struct EvidenceMap<Ctx>
+where
+    Ctx: Context,
+{
+    map: BTreeMap<Ctx::Address, Vec<AmnesiaEvidence<Ctx>>>,
+}
+
+impl<Ctx> EvidenceMap<Ctx>
+where
+    Ctx: Context,
+{
+    /// Create a new `EvidenceMap` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return whether or not there is any evidence of lock violation.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Return the evidence of lock violation for a given address, if any.
+    pub fn get(&self, address: &Ctx::Address) -> Option<&Vec<AmnesiaEvidence<Ctx>>> {
+        self.map.get(address)
+    }
+
+    /// Iterate over all the evidence of lock violation, grouped by validator address.
+    pub fn iter(&self) -> impl Iterator<Item = (&Ctx::Address, &Vec<AmnesiaEvidence<Ctx>>)> {
+        self.map.iter()
+    }
+
+    /// Add evidence of lock violation, unless it is already present.
+    pub fn add(&mut self, evidence: AmnesiaEvidence<Ctx>) {
+        let address = evidence.validator_address().clone();
+
+        if let Some(entries) = self.map.get_mut(&address) {
+            if !entries.contains(&evidence) {
+                entries.push(evidence);
+            }
+        } else {
+            self.map.insert(address, vec![evidence]);
+        }
+    }
+
+    /// Only keep the evidence for which the given predicate holds.
+    fn retain(&mut self, mut f: impl FnMut(&AmnesiaEvidence<Ctx>) -> bool) {
+        self.map.retain(|_, entries| {
+            entries.retain(&mut f);
+            !entries.is_empty()
+        });
+    }
+}
+
+/// Records the votes of each validator across the rounds of a height
+/// in order to detect lock violations.
+#[derive_where(Clone, Debug, Default)]
+pub struct AmnesiaDetector<Ctx>
+where
+    Ctx: Context,
+{
+    /// The highest-round non-nil precommit of each validator, ie. the value it is locked on.
+    locks: BTreeMap<Ctx::Address, SignedVote<Ctx>>,
+
+    /// The first non-nil prevote of each validator in each round after the one it is locked at.
+    prevotes: BTreeMap<Ctx::Address, BTreeMap<Round, SignedVote<Ctx>>>,
+
+    /// The rounds and values for which a polka was observed.
+    ///
+    /// These are kept separately from the polka certificates held by the driver,
+    /// since the latter can be pruned.
+    polkas: Vec<(Round, ValueId<Ctx>)>,
+
+    /// The evidence of lock violations gathered so far.
+    evidence: EvidenceMap<Ctx>,
+}
+
+impl<Ctx> AmnesiaDetector<Ctx>
+where
+    Ctx: Context,
+{
+    /// Create a new `AmnesiaDetector` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the evidence of lock violations gathered so far.
+    pub fn evidence(&self) -> &EvidenceMap<Ctx> {
+        &self.evidence
+    }
+
+    /// Record a vote, and check whether it conflicts with a vote
+    /// previously cast by the same validator in another round.
+    ///
+    /// Nil votes are ignored, since a nil precommit does not lock any value
+    /// and a nil prevote can never violate a lock.
+    ///
+    /// Only the latest lock of each validator is kept, along with its prevotes in later rounds,
+    /// so a prevote contradicting an earlier lock is not flagged if a later lock was seen first.
+    pub fn record_vote(&mut self, vote: &SignedVote<Ctx>) {
+        if vote.value().is_nil() {
+            return;
+        }
+
+        match vote.vote_type() {
+            VoteType::Prevote => self.record_prevote(vote),
+            VoteType::Precommit => self.record_precommit(vote),
+        }
+    }
+
+    fn record_prevote(&mut self, prevote: &SignedVote<Ctx>) {
+        let address = prevote.validator_address();
+        let lock = self.locks.get(address);
+
+        if lock.is_some_and(|lock| lock.round() >= prevote.round()) {
+            return;
+        }
+
+        let prevotes = self.prevotes.entry(address.clone()).or_default();
+        if prevotes.contains_key(&prevote.round()) {
+            return;
+        }
+        prevotes.insert(prevote.round(), prevote.clone());
+
+        if let Some(lock) = lock {
+            let evidence = AmnesiaEvidence::new(lock.clone(), prevote.clone());
+            self.check(evidence);
+        }
+    }
+
+    fn record_precommit(&mut self, precommit: &SignedVote<Ctx>) {
+        let address = precommit.validator_address();
+
+        if let Some(lock) = self.locks.get(address) {
+            if lock.round() >= precommit.round() {
+                return;
+            }
+        }
+
+        self.locks.insert(address.clone(), precommit.clone());
+
+        let Some(prevotes) = self.prevotes.get_mut(address) else {
+            return;
+        };
+
+        // Prevotes up to the round of the lock can never violate it, nor any later lock
+        prevotes.retain(|round, _| *round > precommit.round());
+
+        let evidence = prevotes
+            .values()
+            .map(|prevote| AmnesiaEvidence::new(precommit.clone(), prevote.clone()))
+            .collect::<Vec<_>>();
+
+        for evidence in evidence {
+            self.check(evidence);
+        }
+    }
+
+    /// Add the given evidence if it shows an actual lock violation, not justified by any polka.
+    fn check(&mut self, evidence: AmnesiaEvidence<Ctx>) {
+        if evidence.validate().is_ok() && !self.is_justified(&evidence) {
+            self.evidence.add(evidence);
+        }
+    }
+
+    /// Record a polka for the given value at the given round,
+    /// and discard any evidence which is justified by it.
+    pub fn record_polka(&mut self, round: Round, value_id: &ValueId<Ctx>) {
+        if self
+            .polkas
+            .iter()
+            .any(|(r, v)| *r == round && v == value_id)
+        {
+            return;
+        }
+
+        self.polkas.push((round, value_id.clone()));
+
+        self.evidence
+            .retain(|evidence| !evidence.is_justified_by_polka_at(round, value_id));
+    }
+
+    fn is_justified(&self, evidence: &AmnesiaEvidence<Ctx>) -> bool {
+        self.polkas
+            .iter()
+            .any(|(round, value_id)| evidence.is_justified_by_polka_at(*round, value_id))
+    }
+}
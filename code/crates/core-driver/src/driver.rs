@@ -15,6 +15,7 @@ use malachitebft_core_types::{
 use malachitebft_core_votekeeper::keeper::Output as VKOutput;
 use malachitebft_core_votekeeper::keeper::VoteKeeper;
 
+use crate::accountability::{AmnesiaDetector, EvidenceMap as AmnesiaEvidenceMap};
 use crate::input::Input;
 use crate::output::Output;
 use crate::proposal_keeper::ProposalKeeper;
@@ -67,6 +68,9 @@ where
 
     /// The certificate that justifies moving to the `enter_round` specified in the `EnterRoundCertificate.
     pub round_certificate: Option<EnterRoundCertificate<Ctx>>,
+
+    /// Detector for lock violations, only present if accountability is enabled.
+    pub(crate) amnesia_detector: Option<AmnesiaDetector<Ctx>>,
 }
 
 impl<Ctx> Driver<Ctx>
@@ -103,9 +107,25 @@ where
             last_prevote: None,
             last_precommit: None,
             round_certificate: None,
+            amnesia_detector: None,
         }
     }
 
+    /// Enable or disable accountability mode.
+    ///
+    /// When enabled, the driver records the votes of every validator across
+    /// all rounds of a height and gathers evidence of lock violations (amnesia attacks),
+    /// see [`Driver::amnesia_evidence`].
+    pub fn with_accountability(mut self, enabled: bool) -> Self {
+        self.amnesia_detector = enabled.then(AmnesiaDetector::new);
+        self
+    }
+
+    /// Whether accountability mode is enabled.
+    pub fn is_accountable(&self) -> bool {
+        self.amnesia_detector.is_some()
+    }
+
     /// Reset votes, round state, pending input
     /// and move to new height with the given validator set.
     pub fn move_to_height(&mut self, height: Ctx::Height, validator_set: Ctx::ValidatorSet) {
@@ -127,6 +147,10 @@ where
         self.polka_certificates = vec![];
         self.last_prevote = None;
         self.last_precommit = None;
+
+        if let Some(detector) = &mut self.amnesia_detector {
+            *detector = AmnesiaDetector::new();
+        }
     }
 
     /// Return the height of the consensus.
@@ -232,6 +256,16 @@ where
             .find(|c| c.round == round && c.value_id == value_id)
     }
 
+    /// Return the evidence of lock violations gathered at this height,
+    /// or `None` if accountability is not enabled.
+    ///
+    /// Note that a piece of evidence may still be justified by a polka which this node
+    /// has not observed, it should therefore be checked against the polka certificates
+    /// available elsewhere before being acted upon.
+    pub fn amnesia_evidence(&self) -> Option<&AmnesiaEvidenceMap<Ctx>> {
+        self.amnesia_detector.as_ref().map(|d| d.evidence())
+    }
+
     /// Get all polka certificates
     pub fn polka_certificates(&self) -> &[PolkaCertificate<Ctx>] {
         &self.polka_certificates
@@ -471,6 +505,10 @@ where
             return Err(Error::ValidatorNotFound(vote.validator_address().clone()));
         }
 
+        if let Some(detector) = &mut self.amnesia_detector {
+            detector.record_vote(&vote);
+        }
+
         let vote_round = vote.round();
         let this_round = self.round();
 
@@ -502,6 +540,10 @@ where
     }
 
     fn store_polka_certificate(&mut self, vote_round: Round, value_id: &ValueId<Ctx>) {
+        if let Some(detector) = &mut self.amnesia_detector {
+            detector.record_polka(vote_round, value_id);
+        }
+
         let Some(per_round) = self.vote_keeper.per_round(vote_round) else {
             return;
         };
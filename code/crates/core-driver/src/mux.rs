@@ -247,6 +247,10 @@ where
         let certificate_round = certificate.round;
         let certificate_value_id = certificate.value_id.clone();
 
+        if let Some(detector) = &mut self.amnesia_detector {
+            detector.record_polka(certificate_round, &certificate_value_id);
+        }
+
         // Only add if an identical certificate isn't already present
         if !self.polka_certificates.iter().any(|existing| {
             existing.round == certificate.round && existing.value_id == certificate.value_id
@@ -7,7 +7,9 @@ use alloc::vec::Vec;
 use derive_where::derive_where;
 use thiserror::Error;
 
-use malachitebft_core_types::{Context, Proposal, Round, SignedProposal, Validity, Value, ValueId};
+use malachitebft_core_types::{
+    Context, DoubleProposalEvidence, Proposal, Round, SignedProposal, Validity, Value, ValueId,
+};
 
 /// Errors can that be yielded when recording a proposal.
 #[derive_where(Debug)]
@@ -186,8 +188,7 @@ pub struct EvidenceMap<Ctx>
 where
     Ctx: Context,
 {
-    #[allow(clippy::type_complexity)]
-    map: BTreeMap<Ctx::Address, Vec<(SignedProposal<Ctx>, SignedProposal<Ctx>)>>,
+    map: BTreeMap<Ctx::Address, Vec<DoubleProposalEvidence<Ctx>>>,
 }
 
 impl<Ctx> EvidenceMap<Ctx>
@@ -205,23 +206,25 @@ where
     }
 
     /// Return the evidence of equivocation for a given address, if any.
-    pub fn get(
-        &self,
-        address: &Ctx::Address,
-    ) -> Option<&Vec<(SignedProposal<Ctx>, SignedProposal<Ctx>)>> {
+    pub fn get(&self, address: &Ctx::Address) -> Option<&Vec<DoubleProposalEvidence<Ctx>>> {
         self.map.get(address)
     }
 
+    /// Iterate over all the evidence of equivocation, grouped by validator address.
+    pub fn iter(&self) -> impl Iterator<Item = (&Ctx::Address, &Vec<DoubleProposalEvidence<Ctx>>)> {
+        self.map.iter()
+    }
+
     /// Add evidence of equivocating proposals, ie. two proposals submitted by the same validator,
     /// but with different values but for the same height and round.
-    pub(crate) fn add(&mut self, existing: SignedProposal<Ctx>, conflicting: SignedProposal<Ctx>) {
-        if let Some(evidence) = self.map.get_mut(conflicting.validator_address()) {
-            evidence.push((existing, conflicting));
+    pub fn add(&mut self, existing: SignedProposal<Ctx>, conflicting: SignedProposal<Ctx>) {
+        let address = conflicting.validator_address().clone();
+        let evidence = DoubleProposalEvidence::new(existing, conflicting);
+
+        if let Some(entries) = self.map.get_mut(&address) {
+            entries.push(evidence);
         } else {
-            self.map.insert(
-                conflicting.validator_address().clone(),
-                vec![(existing, conflicting)],
-            );
+            self.map.insert(address, vec![evidence]);
         }
     }
 }
@@ -0,0 +1,281 @@
+use malachitebft_core_types::{AmnesiaEvidence, NilOrVal, Round, SignedVote};
+use malachitebft_test::utils::validators::make_validators;
+use malachitebft_test::{
+    Address, Height, Signature, TestContext, Validator, ValidatorSet, Value, Vote,
+};
+
+use informalsystems_malachitebft_core_driver::{Driver, Input};
+
+use crate::utils::*;
+
+fn setup(accountability: bool) -> (Driver<TestContext>, [Validator; 4]) {
+    let [(v1, _), (v2, _), (v3, _), (v4, sk4)] = make_validators([1, 1, 1, 1]);
+    let (_my_sk, my_addr) = (sk4, v4.address);
+
+    let height = Height::new(1);
+    let ctx = TestContext::new();
+    let vs = ValidatorSet::new(vec![v1.clone(), v2.clone(), v3.clone(), v4.clone()]);
+
+    let mut driver = Driver::new(ctx, height, vs, my_addr, Default::default())
+        .with_accountability(accountability);
+
+    driver
+        .process(new_round_input(Round::new(0), v1.address))
+        .expect("process succeeded");
+
+    (driver, [v1, v2, v3, v4])
+}
+
+fn signed_precommit(round: Round, value: &Value, addr: &Address) -> SignedVote<TestContext> {
+    SignedVote::new(
+        Vote::new_precommit(Height::new(1), round, NilOrVal::Val(value.id()), *addr),
+        Signature::test(),
+    )
+}
+
+fn signed_prevote(round: Round, value: &Value, addr: &Address) -> SignedVote<TestContext> {
+    SignedVote::new(
+        Vote::new_prevote(Height::new(1), round, NilOrVal::Val(value.id()), *addr),
+        Signature::test(),
+    )
+}
+
+fn apply(driver: &mut Driver<TestContext>, input: Input<TestContext>) {
+    driver.process(input).expect("process succeeded");
+}
+
+fn evidence_for(driver: &Driver<TestContext>, addr: &Address) -> Vec<AmnesiaEvidence<TestContext>> {
+    driver
+        .amnesia_evidence()
+        .expect("accountability is enabled")
+        .get(addr)
+        .cloned()
+        .unwrap_or_default()
+}
+
+#[test]
+fn accountability_disabled_by_default() {
+    let (mut driver, [_, v2, _, _]) = setup(false);
+
+    apply(
+        &mut driver,
+        precommit_input_at(Round::new(0), Value::new(1), &v2.address),
+    );
+    apply(
+        &mut driver,
+        prevote_input_at(Round::new(1), Value::new(2), &v2.address),
+    );
+
+    assert!(!driver.is_accountable());
+    assert!(driver.amnesia_evidence().is_none());
+}
+
+#[test]
+fn prevote_contradicting_lock_is_flagged() {
+    let (mut driver, [_, v2, _, _]) = setup(true);
+    let (locked, other) = (Value::new(1), Value::new(2));
+
+    apply(
+        &mut driver,
+        precommit_input_at(Round::new(0), locked.clone(), &v2.address),
+    );
+    apply(
+        &mut driver,
+        prevote_input_at(Round::new(1), other.clone(), &v2.address),
+    );
+
+    let evidence = evidence_for(&driver, &v2.address);
+
+    assert_eq!(
+        evidence,
+        vec![AmnesiaEvidence::new(
+            signed_precommit(Round::new(0), &locked, &v2.address),
+            signed_prevote(Round::new(1), &other, &v2.address),
+        )]
+    );
+    assert_eq!(evidence[0].validate(), Ok(()));
+    assert_eq!(evidence[0].locked_round(), Round::new(0));
+    assert_eq!(evidence[0].prevote_round(), Round::new(1));
+}
+
+#[test]
+fn prevote_contradicting_lock_received_out_of_order_is_flagged() {
+    let (mut driver, [_, v2, _, _]) = setup(true);
+
+    apply(
+        &mut driver,
+        prevote_input_at(Round::new(2), Value::new(2), &v2.address),
+    );
+    apply(
+        &mut driver,
+        precommit_input_at(Round::new(1), Value::new(1), &v2.address),
+    );
+
+    assert_eq!(evidence_for(&driver, &v2.address).len(), 1);
+}
+
+#[test]
+fn only_latest_lock_and_first_prevote_per_round_are_checked() {
+    let (mut driver, [_, v2, _, _]) = setup(true);
+
+    apply(
+        &mut driver,
+        precommit_input_at(Round::new(0), Value::new(1), &v2.address),
+    );
+    apply(
+        &mut driver,
+        prevote_input_at(Round::new(1), Value::new(2), &v2.address),
+    );
+
+    // Another prevote in the same round is evidence of a double vote, not of another lock violation
+    apply(
+        &mut driver,
+        prevote_input_at(Round::new(1), Value::new(3), &v2.address),
+    );
+
+    assert_eq!(evidence_for(&driver, &v2.address).len(), 1);
+
+    // The lock moves on to a later round, and later prevotes are checked against it
+    apply(
+        &mut driver,
+        precommit_input_at(Round::new(2), Value::new(2), &v2.address),
+    );
+    apply(
+        &mut driver,
+        prevote_input_at(Round::new(3), Value::new(1), &v2.address),
+    );
+
+    // Precommits in earlier rounds than the lock are ignored
+    apply(
+        &mut driver,
+        precommit_input_at(Round::new(1), Value::new(4), &v2.address),
+    );
+
+    let evidence = evidence_for(&driver, &v2.address);
+
+    assert_eq!(evidence.len(), 2);
+    assert_eq!(evidence[1].locked_round(), Round::new(2));
+    assert_eq!(evidence[1].prevote_round(), Round::new(3));
+}
+
+#[test]
+fn prevote_consistent_with_lock_is_not_flagged() {
+    let (mut driver, [_, v2, v3, _]) = setup(true);
+    let value = Value::new(1);
+
+    // Same value as the lock
+    apply(
+        &mut driver,
+        precommit_input_at(Round::new(0), value.clone(), &v2.address),
+    );
+    apply(
+        &mut driver,
+        prevote_input_at(Round::new(1), value.clone(), &v2.address),
+    );
+
+    // Prevote for a different value in an earlier round than the lock
+    apply(
+        &mut driver,
+        prevote_input_at(Round::new(0), Value::new(2), &v3.address),
+    );
+    apply(
+        &mut driver,
+        precommit_input_at(Round::new(1), value.clone(), &v3.address),
+    );
+
+    // Nil prevote after a lock
+    apply(&mut driver, precommit_nil_input(Round::new(1), &v2.address));
+
+    assert!(driver.amnesia_evidence().unwrap().is_empty());
+}
+
+#[test]
+fn prevote_justified_by_polka_is_not_flagged() {
+    let (mut driver, [v1, v2, v3, _]) = setup(true);
+    let (locked, other) = (Value::new(1), Value::new(2));
+
+    apply(
+        &mut driver,
+        precommit_input_at(Round::new(0), locked, &v2.address),
+    );
+    apply(
+        &mut driver,
+        polka_certificate_input_at(
+            Round::new(1),
+            other.clone(),
+            &[v1.address, v2.address, v3.address],
+        ),
+    );
+    apply(
+        &mut driver,
+        prevote_input_at(Round::new(2), other, &v2.address),
+    );
+
+    assert!(driver.amnesia_evidence().unwrap().is_empty());
+}
+
+#[test]
+fn evidence_is_discarded_when_justifying_polka_is_observed() {
+    let (mut driver, [v1, v2, v3, _]) = setup(true);
+    let (locked, other) = (Value::new(1), Value::new(2));
+
+    apply(
+        &mut driver,
+        precommit_input_at(Round::new(0), locked, &v2.address),
+    );
+    apply(
+        &mut driver,
+        prevote_input_at(Round::new(2), other.clone(), &v2.address),
+    );
+
+    assert_eq!(evidence_for(&driver, &v2.address).len(), 1);
+
+    // A polka for another value, or outside of the range [locked_round, prevote_round), does not justify the prevote
+    apply(
+        &mut driver,
+        polka_certificate_input_at(
+            Round::new(1),
+            Value::new(3),
+            &[v1.address, v2.address, v3.address],
+        ),
+    );
+    apply(
+        &mut driver,
+        polka_certificate_input_at(
+            Round::new(2),
+            other.clone(),
+            &[v1.address, v2.address, v3.address],
+        ),
+    );
+
+    assert_eq!(evidence_for(&driver, &v2.address).len(), 1);
+
+    apply(
+        &mut driver,
+        polka_certificate_input_at(Round::new(1), other, &[v1.address, v2.address, v3.address]),
+    );
+
+    assert!(driver.amnesia_evidence().unwrap().is_empty());
+}
+
+#[test]
+fn evidence_is_reset_when_moving_to_next_height() {
+    let (mut driver, [_, v2, _, _]) = setup(true);
+
+    apply(
+        &mut driver,
+        precommit_input_at(Round::new(0), Value::new(1), &v2.address),
+    );
+    apply(
+        &mut driver,
+        prevote_input_at(Round::new(1), Value::new(2), &v2.address),
+    );
+
+    assert!(!driver.amnesia_evidence().unwrap().is_empty());
+
+    let vs = driver.validator_set().clone();
+    driver.move_to_height(Height::new(2), vs);
+
+    assert!(driver.is_accountable());
+    assert!(driver.amnesia_evidence().unwrap().is_empty());
+}
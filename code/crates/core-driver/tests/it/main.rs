@@ -1,3 +1,4 @@
+pub mod accountability;
 pub mod basic;
 pub mod extra;
 
@@ -25,7 +25,109 @@ impl<Ctx: Context> CommitSignature<Ctx> {
     }
 }
 
+/// A bitmap of the validators which contributed to an aggregated signature,
+/// where bit `i` is set if the validator at index `i` in the validator set signed.
+///
+/// Bits are stored in little-endian order within each byte, ie. the validator at index `i`
+/// corresponds to bit `i % 8` of byte `i / 8`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "borsh",
+    derive(::borsh::BorshSerialize, ::borsh::BorshDeserialize)
+)]
+pub struct SignerBitmap {
+    bits: Vec<u8>,
+}
+
+impl SignerBitmap {
+    /// Create an empty bitmap, with no signers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a bitmap from its byte representation.
+    pub fn from_bytes(bits: Vec<u8>) -> Self {
+        Self { bits }
+    }
+
+    /// The byte representation of the bitmap.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bits
+    }
+
+    /// Mark the validator at the given index as a signer.
+    pub fn insert(&mut self, index: usize) {
+        let byte = index / 8;
+
+        if byte >= self.bits.len() {
+            self.bits.resize(byte + 1, 0);
+        }
+
+        self.bits[byte] |= 1 << (index % 8);
+    }
+
+    /// Whether the validator at the given index is a signer.
+    pub fn contains(&self, index: usize) -> bool {
+        self.bits
+            .get(index / 8)
+            .is_some_and(|byte| byte & (1 << (index % 8)) != 0)
+    }
+
+    /// The number of signers.
+    pub fn count(&self) -> usize {
+        self.bits
+            .iter()
+            .map(|byte| byte.count_ones() as usize)
+            .sum()
+    }
+
+    /// Whether there are no signers.
+    pub fn is_empty(&self) -> bool {
+        self.bits.iter().all(|byte| *byte == 0)
+    }
+
+    /// The indices of the signers, in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.bits.len() * 8).filter(|index| self.contains(*index))
+    }
+}
+
+impl FromIterator<usize> for SignerBitmap {
+    fn from_iter<I: IntoIterator<Item = usize>>(indices: I) -> Self {
+        let mut bitmap = Self::new();
+        for index in indices {
+            bitmap.insert(index);
+        }
+        bitmap
+    }
+}
+
+/// A single signature aggregated from the commit signatures of many validators,
+/// along with the bitmap of the validators which signed.
+///
+/// The signers are identified by their index in the validator set of the height of the certificate,
+/// so the certificate can only be verified against that validator set.
+#[derive_where(Clone, Debug, PartialEq, Eq)]
+pub struct AggregatedSignature<Ctx: Context> {
+    /// The validators which signed.
+    pub signers: SignerBitmap,
+    /// The aggregate of their signatures.
+    pub signature: Signature<Ctx>,
+}
+
+impl<Ctx: Context> AggregatedSignature<Ctx> {
+    /// Create a new `AggregatedSignature` from a bitmap of signers and an aggregate signature.
+    pub fn new(signers: SignerBitmap, signature: Signature<Ctx>) -> Self {
+        Self { signers, signature }
+    }
+}
+
 /// Represents a certificate containing the message (height, round, value_id) and the commit signatures.
+///
+/// The commit signatures are either listed one by one in `commit_signatures`,
+/// or, when the signing scheme supports it, aggregated into a single `aggregated_signature`,
+/// in which case `commit_signatures` is empty.
 #[derive_where(Clone, Debug, PartialEq, Eq)]
 pub struct CommitCertificate<Ctx: Context> {
     /// The height of the certificate.
@@ -36,6 +138,10 @@ pub struct CommitCertificate<Ctx: Context> {
     pub value_id: ValueId<Ctx>,
     /// A vector of signatures that make up the certificate.
     pub commit_signatures: Vec<CommitSignature<Ctx>>,
+    /// The aggregate of the commit signatures, if the certificate is aggregated.
+    ///
+    /// This is part of the Borsh encoding of the certificate, after the commit signatures.
+    pub aggregated_signature: Option<AggregatedSignature<Ctx>>,
 }
 
 impl<Ctx: Context> CommitCertificate<Ctx> {
@@ -68,6 +174,36 @@ impl<Ctx: Context> CommitCertificate<Ctx> {
             round,
             value_id,
             commit_signatures,
+            aggregated_signature: None,
+        }
+    }
+
+    /// Creates a new `CommitCertificate` from an aggregated signature.
+    pub fn new_aggregated(
+        height: Ctx::Height,
+        round: Round,
+        value_id: ValueId<Ctx>,
+        aggregated_signature: AggregatedSignature<Ctx>,
+    ) -> Self {
+        Self {
+            height,
+            round,
+            value_id,
+            commit_signatures: Vec::new(),
+            aggregated_signature: Some(aggregated_signature),
+        }
+    }
+
+    /// Whether the commit signatures of this certificate are aggregated.
+    pub fn is_aggregated(&self) -> bool {
+        self.aggregated_signature.is_some()
+    }
+
+    /// The number of commit signatures in this certificate, whether aggregated or not.
+    pub fn signature_count(&self) -> usize {
+        match &self.aggregated_signature {
+            Some(aggregated) => aggregated.signers.count(),
+            None => self.commit_signatures.len(),
         }
     }
 }
@@ -177,6 +313,18 @@ pub enum CertificateError<Ctx: Context> {
     #[error("Prevote received in precommit round certificate from validator: {0}")]
     InvalidVoteType(Ctx::Address),
 
+    /// The aggregated signature of the certificate is invalid.
+    #[error("Invalid aggregated signature")]
+    InvalidAggregatedSignature,
+
+    /// A signer in the bitmap of an aggregated signature is not in the validator set.
+    #[error("Signer at index {0} is not in the validator set")]
+    UnknownSignerIndex(usize),
+
+    /// The certificate has both an aggregated signature and individual commit signatures.
+    #[error("Certificate has both an aggregated signature and individual commit signatures")]
+    MixedSignatures,
+
     /// An error occurred while verifying the certificate.
     #[error("Signature verification error: {}", .0.as_ref().map(|e| e.to_string()).unwrap_or_default())]
     VerificationError(Option<BoxError>),
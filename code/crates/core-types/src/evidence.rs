@@ -0,0 +1,421 @@
+use alloc::string::ToString;
+use derive_where::derive_where;
+use thiserror::Error;
+
+use crate::{
+    BoxError, Context, NilOrVal, PolkaCertificate, Proposal, Round, SignedProposal, SignedVote,
+    ValueId, Vote, VoteType,
+};
+
+/// Evidence that a validator signed two conflicting votes,
+/// ie. two votes of the same type for the same height and round,
+/// but for different values (where `nil` is considered a value).
+#[derive_where(Clone, Debug, PartialEq, Eq)]
+pub struct DoubleVoteEvidence<Ctx: Context> {
+    /// The vote that was received first.
+    pub first: SignedVote<Ctx>,
+    /// The conflicting vote.
+    pub second: SignedVote<Ctx>,
+}
+
+impl<Ctx: Context> DoubleVoteEvidence<Ctx> {
+    /// Create a new `DoubleVoteEvidence` from two conflicting votes.
+    ///
+    /// This does not check that the votes actually conflict,
+    /// use [`DoubleVoteEvidence::validate`] for that.
+    pub fn new(first: SignedVote<Ctx>, second: SignedVote<Ctx>) -> Self {
+        Self { first, second }
+    }
+
+    /// The address of the equivocating validator.
+    pub fn validator_address(&self) -> &Ctx::Address {
+        self.first.validator_address()
+    }
+
+    /// The height at which the equivocation happened.
+    pub fn height(&self) -> Ctx::Height {
+        self.first.height()
+    }
+
+    /// The round at which the equivocation happened.
+    pub fn round(&self) -> Round {
+        self.first.round()
+    }
+
+    /// The type of the conflicting votes.
+    pub fn vote_type(&self) -> VoteType {
+        self.first.vote_type()
+    }
+
+    /// Check that both votes were cast by the same validator, for the same height,
+    /// round and vote type, and that they are for different values.
+    ///
+    /// This does NOT verify the signatures of the votes.
+    pub fn validate(&self) -> Result<(), EvidenceError<Ctx>> {
+        let (first, second) = (&self.first, &self.second);
+
+        if first.validator_address() != second.validator_address() {
+            return Err(EvidenceError::MismatchedValidator {
+                first: first.validator_address().clone(),
+                second: second.validator_address().clone(),
+            });
+        }
+
+        if first.height() != second.height() {
+            return Err(EvidenceError::MismatchedHeight {
+                first: first.height(),
+                second: second.height(),
+            });
+        }
+
+        if first.round() != second.round() {
+            return Err(EvidenceError::MismatchedRound {
+                first: first.round(),
+                second: second.round(),
+            });
+        }
+
+        if first.vote_type() != second.vote_type() {
+            return Err(EvidenceError::MismatchedVoteType {
+                first: first.vote_type(),
+                second: second.vote_type(),
+            });
+        }
+
+        if first.value() == second.value() {
+            return Err(EvidenceError::NotConflicting);
+        }
+
+        Ok(())
+    }
+}
+
+/// Evidence that a validator signed two conflicting proposals,
+/// ie. two different proposals for the same height and round.
+#[derive_where(Clone, Debug, PartialEq, Eq)]
+pub struct DoubleProposalEvidence<Ctx: Context> {
+    /// The proposal that was received first.
+    pub first: SignedProposal<Ctx>,
+    /// The conflicting proposal.
+    pub second: SignedProposal<Ctx>,
+}
+
+impl<Ctx: Context> DoubleProposalEvidence<Ctx> {
+    /// Create a new `DoubleProposalEvidence` from two conflicting proposals.
+    ///
+    /// This does not check that the proposals actually conflict,
+    /// use [`DoubleProposalEvidence::validate`] for that.
+    pub fn new(first: SignedProposal<Ctx>, second: SignedProposal<Ctx>) -> Self {
+        Self { first, second }
+    }
+
+    /// The address of the equivocating proposer.
+    pub fn validator_address(&self) -> &Ctx::Address {
+        self.first.validator_address()
+    }
+
+    /// The height at which the equivocation happened.
+    pub fn height(&self) -> Ctx::Height {
+        self.first.height()
+    }
+
+    /// The round at which the equivocation happened.
+    pub fn round(&self) -> Round {
+        self.first.round()
+    }
+
+    /// Check that both proposals were signed by the same validator,
+    /// for the same height and round, and that they differ.
+    ///
+    /// This does NOT verify the signatures of the proposals.
+    pub fn validate(&self) -> Result<(), EvidenceError<Ctx>> {
+        let (first, second) = (&self.first, &self.second);
+
+        if first.validator_address() != second.validator_address() {
+            return Err(EvidenceError::MismatchedValidator {
+                first: first.validator_address().clone(),
+                second: second.validator_address().clone(),
+            });
+        }
+
+        if first.height() != second.height() {
+            return Err(EvidenceError::MismatchedHeight {
+                first: first.height(),
+                second: second.height(),
+            });
+        }
+
+        if first.round() != second.round() {
+            return Err(EvidenceError::MismatchedRound {
+                first: first.round(),
+                second: second.round(),
+            });
+        }
+
+        if first.message == second.message {
+            return Err(EvidenceError::NotConflicting);
+        }
+
+        Ok(())
+    }
+}
+
+/// Evidence that a validator violated its lock, also known as an amnesia attack.
+///
+/// The validator precommitted a value in some round, and thus locked on it,
+/// then prevoted for a different value in a later round of the same height.
+/// This is only a misbehavior if there was no polka for the new value
+/// in a round `vr` such that `locked_round <= vr < prevote_round`,
+/// which the validator could have used to release its lock.
+///
+/// The evidence must therefore be checked against the polka certificates
+/// available for that height, see [`AmnesiaEvidence::is_justified_by`].
+#[derive_where(Clone, Debug, PartialEq, Eq)]
+pub struct AmnesiaEvidence<Ctx: Context> {
+    /// The precommit by which the validator locked on a value.
+    pub precommit: SignedVote<Ctx>,
+    /// The prevote for a different value in a later round.
+    pub prevote: SignedVote<Ctx>,
+}
+
+impl<Ctx: Context> AmnesiaEvidence<Ctx> {
+    /// Create a new `AmnesiaEvidence` from a precommit and a later, conflicting, prevote.
+    ///
+    /// This does not check that the votes actually conflict,
+    /// use [`AmnesiaEvidence::validate`] for that.
+    pub fn new(precommit: SignedVote<Ctx>, prevote: SignedVote<Ctx>) -> Self {
+        Self { precommit, prevote }
+    }
+
+    /// The address of the validator who violated its lock.
+    pub fn validator_address(&self) -> &Ctx::Address {
+        self.precommit.validator_address()
+    }
+
+    /// The height at which the lock was violated.
+    pub fn height(&self) -> Ctx::Height {
+        self.precommit.height()
+    }
+
+    /// The round at which the validator locked on a value.
+    pub fn locked_round(&self) -> Round {
+        self.precommit.round()
+    }
+
+    /// The round at which the validator prevoted for a different value.
+    pub fn prevote_round(&self) -> Round {
+        self.prevote.round()
+    }
+
+    /// Check that both votes were cast by the same validator for the same height,
+    /// that the first one is a precommit for a value and the second one a prevote
+    /// for a different value in a later round.
+    ///
+    /// This does NOT verify the signatures of the votes,
+    /// nor does it check whether the prevote was justified by a polka.
+    pub fn validate(&self) -> Result<(), EvidenceError<Ctx>> {
+        let (precommit, prevote) = (&self.precommit, &self.prevote);
+
+        if precommit.validator_address() != prevote.validator_address() {
+            return Err(EvidenceError::MismatchedValidator {
+                first: precommit.validator_address().clone(),
+                second: prevote.validator_address().clone(),
+            });
+        }
+
+        if precommit.height() != prevote.height() {
+            return Err(EvidenceError::MismatchedHeight {
+                first: precommit.height(),
+                second: prevote.height(),
+            });
+        }
+
+        if precommit.vote_type() != VoteType::Precommit {
+            return Err(EvidenceError::UnexpectedVoteType {
+                expected: VoteType::Precommit,
+                actual: precommit.vote_type(),
+            });
+        }
+
+        if prevote.vote_type() != VoteType::Prevote {
+            return Err(EvidenceError::UnexpectedVoteType {
+                expected: VoteType::Prevote,
+                actual: prevote.vote_type(),
+            });
+        }
+
+        if prevote.round() <= precommit.round() {
+            return Err(EvidenceError::NotConflicting);
+        }
+
+        // A nil precommit does not lock any value, and a nil prevote never violates a lock
+        match (precommit.value(), prevote.value()) {
+            (NilOrVal::Val(locked), NilOrVal::Val(voted)) if locked != voted => Ok(()),
+            _ => Err(EvidenceError::NotConflicting),
+        }
+    }
+
+    /// Whether the given polka certificate justifies the prevote,
+    /// ie. whether it is a polka for the prevoted value at the same height,
+    /// in a round `vr` such that `locked_round <= vr < prevote_round`.
+    pub fn is_justified_by(&self, certificate: &PolkaCertificate<Ctx>) -> bool {
+        certificate.height == self.height()
+            && self.is_justified_by_polka_at(certificate.round, &certificate.value_id)
+    }
+
+    /// Whether a polka for the given value at the given round of this height
+    /// justifies the prevote, see [`AmnesiaEvidence::is_justified_by`].
+    pub fn is_justified_by_polka_at(&self, round: Round, value_id: &ValueId<Ctx>) -> bool {
+        self.locked_round() <= round
+            && round < self.prevote_round()
+            && self.prevote.value().as_ref() == NilOrVal::Val(value_id)
+    }
+
+    /// Check that the evidence is valid and that none of the given polka certificates
+    /// justifies the prevote.
+    ///
+    /// This does NOT verify the signatures of the votes.
+    pub fn validate_against(
+        &self,
+        certificates: &[PolkaCertificate<Ctx>],
+    ) -> Result<(), EvidenceError<Ctx>> {
+        self.validate()?;
+
+        match certificates.iter().find(|c| self.is_justified_by(c)) {
+            Some(certificate) => Err(EvidenceError::JustifiedByPolka(certificate.round)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Any kind of evidence of misbehavior by a validator,
+/// eg. as gossiped between peers.
+#[derive_where(Clone, Debug, PartialEq, Eq)]
+pub enum Evidence<Ctx: Context> {
+    /// Evidence that a validator signed two conflicting votes.
+    DoubleVote(DoubleVoteEvidence<Ctx>),
+    /// Evidence that a validator signed two conflicting proposals.
+    DoubleProposal(DoubleProposalEvidence<Ctx>),
+    /// Evidence that a validator violated its lock.
+    Amnesia(AmnesiaEvidence<Ctx>),
+}
+
+impl<Ctx: Context> Evidence<Ctx> {
+    /// The address of the misbehaving validator.
+    pub fn validator_address(&self) -> &Ctx::Address {
+        match self {
+            Self::DoubleVote(evidence) => evidence.validator_address(),
+            Self::DoubleProposal(evidence) => evidence.validator_address(),
+            Self::Amnesia(evidence) => evidence.validator_address(),
+        }
+    }
+
+    /// The height at which the misbehavior happened.
+    pub fn height(&self) -> Ctx::Height {
+        match self {
+            Self::DoubleVote(evidence) => evidence.height(),
+            Self::DoubleProposal(evidence) => evidence.height(),
+            Self::Amnesia(evidence) => evidence.height(),
+        }
+    }
+
+    /// Check that the evidence is well-formed, see the `validate` method of each kind of evidence.
+    ///
+    /// This does NOT verify the signatures of the messages,
+    /// nor whether amnesia evidence is justified by a polka.
+    pub fn validate(&self) -> Result<(), EvidenceError<Ctx>> {
+        match self {
+            Self::DoubleVote(evidence) => evidence.validate(),
+            Self::DoubleProposal(evidence) => evidence.validate(),
+            Self::Amnesia(evidence) => evidence.validate(),
+        }
+    }
+}
+
+impl<Ctx: Context> From<DoubleVoteEvidence<Ctx>> for Evidence<Ctx> {
+    fn from(evidence: DoubleVoteEvidence<Ctx>) -> Self {
+        Self::DoubleVote(evidence)
+    }
+}
+
+impl<Ctx: Context> From<DoubleProposalEvidence<Ctx>> for Evidence<Ctx> {
+    fn from(evidence: DoubleProposalEvidence<Ctx>) -> Self {
+        Self::DoubleProposal(evidence)
+    }
+}
+
+impl<Ctx: Context> From<AmnesiaEvidence<Ctx>> for Evidence<Ctx> {
+    fn from(evidence: AmnesiaEvidence<Ctx>) -> Self {
+        Self::Amnesia(evidence)
+    }
+}
+
+/// Represents an error that can occur when verifying evidence of misbehavior.
+#[derive(Error)]
+#[derive_where(Debug, PartialEq)]
+pub enum EvidenceError<Ctx: Context> {
+    /// The two messages were not signed by the same validator.
+    #[error("Messages were signed by different validators: {first} and {second}")]
+    MismatchedValidator {
+        /// Address of the validator who signed the first message
+        first: Ctx::Address,
+        /// Address of the validator who signed the second message
+        second: Ctx::Address,
+    },
+
+    /// The two messages are not for the same height.
+    #[error("Messages are for different heights: {first} and {second}")]
+    MismatchedHeight {
+        /// Height of the first message
+        first: Ctx::Height,
+        /// Height of the second message
+        second: Ctx::Height,
+    },
+
+    /// The two messages are not for the same round.
+    #[error("Messages are for different rounds: {first} and {second}")]
+    MismatchedRound {
+        /// Round of the first message
+        first: Round,
+        /// Round of the second message
+        second: Round,
+    },
+
+    /// The two votes are not of the same type.
+    #[error("Votes are of different types: {first:?} and {second:?}")]
+    MismatchedVoteType {
+        /// Type of the first vote
+        first: VoteType,
+        /// Type of the second vote
+        second: VoteType,
+    },
+
+    /// A vote is not of the expected type.
+    #[error("Unexpected vote type: expected {expected:?}, got {actual:?}")]
+    UnexpectedVoteType {
+        /// The expected vote type
+        expected: VoteType,
+        /// The actual vote type
+        actual: VoteType,
+    },
+
+    /// The two messages do not conflict with each other.
+    #[error("Messages do not conflict with each other")]
+    NotConflicting,
+
+    /// The prevote was justified by a polka, so the validator was allowed to release its lock.
+    #[error("Prevote is justified by a polka at round {0}")]
+    JustifiedByPolka(Round),
+
+    /// The validator who signed the messages is not in the validator set.
+    #[error("Validator is not in the validator set: {0}")]
+    UnknownValidator(Ctx::Address),
+
+    /// The signature of one of the messages is invalid.
+    #[error("Invalid signature for message signed by {0}")]
+    InvalidSignature(Ctx::Address),
+
+    /// An error occurred while verifying the signatures.
+    #[error("Signature verification error: {}", .0.as_ref().map(|e| e.to_string()).unwrap_or_default())]
+    VerificationError(Option<BoxError>),
+}
@@ -16,9 +16,11 @@ extern crate alloc;
 mod certificate;
 mod context;
 mod error;
+mod evidence;
 mod height;
 mod proposal;
 mod proposal_part;
+mod proposer;
 mod round;
 mod ser;
 mod signed_message;
@@ -26,6 +28,7 @@ mod signing;
 mod threshold;
 mod timeout;
 mod validator_set;
+mod validator_set_update;
 mod value;
 mod vote;
 mod vote_extension;
@@ -55,20 +58,29 @@ pub type SignedProposalPart<Ctx> = SignedMessage<Ctx, <Ctx as Context>::Proposal
 pub type SignedExtension<Ctx> = SignedMessage<Ctx, <Ctx as Context>::Extension>;
 
 pub use certificate::{
-    CertificateError, CommitCertificate, CommitSignature, EnterRoundCertificate, PolkaCertificate,
-    PolkaSignature, RoundCertificate, RoundCertificateType, RoundSignature, ValueResponse,
+    AggregatedSignature, CertificateError, CommitCertificate, CommitSignature,
+    EnterRoundCertificate, PolkaCertificate, PolkaSignature, RoundCertificate,
+    RoundCertificateType, RoundSignature, SignerBitmap, ValueResponse,
 };
 pub use context::Context;
 pub use error::BoxError;
+pub use evidence::{
+    AmnesiaEvidence, DoubleProposalEvidence, DoubleVoteEvidence, Evidence, EvidenceError,
+};
 pub use height::Height;
 pub use proposal::{Proposal, Validity};
 pub use proposal_part::ProposalPart;
+pub use proposer::ProposerPriorities;
 pub use round::Round;
 pub use signed_message::SignedMessage;
 pub use signing::SigningScheme;
-pub use threshold::{Threshold, ThresholdParam, ThresholdParams};
+pub use threshold::{Threshold, ThresholdParam, ThresholdParams, ThresholdParamsError};
 pub use timeout::{Timeout, TimeoutKind};
 pub use validator_set::{Address, Validator, ValidatorSet, VotingPower};
+pub use validator_set_update::{ValidatorSetUpdate, ValidatorSetUpdateError};
 pub use value::{NilOrVal, Value, ValueOrigin, ValuePayload};
 pub use vote::{Vote, VoteType};
 pub use vote_extension::{Extension, VoteExtensions};
+
+#[cfg(feature = "protobuf")]
+pub use ser::proto;
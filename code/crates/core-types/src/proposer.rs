@@ -0,0 +1,275 @@
+//! Voting-power-weighted round-robin proposer selection, as in Tendermint and CometBFT.
+//!
+//! Each validator is assigned a proposer priority. Selecting the next proposer increases
+//! the priority of every validator by its voting power, picks the validator with the highest
+//! priority as proposer, and decreases the priority of the latter by the total voting power.
+//! Over time, each validator is therefore selected a number of times proportional
+//! to its voting power.
+//!
+//! The priorities are advanced once per height, and the proposer for round `r` of a height
+//! is obtained by advancing a copy of the priorities of that height `r` more times.
+//! The priorities thus only depend on the sequence of validator sets, and never on the round
+//! at which a height was decided, which keeps the selection deterministic across nodes.
+
+use alloc::vec::Vec;
+
+use derive_where::derive_where;
+
+use crate::{Context, Round, Validator, ValidatorSet, VotingPower};
+
+/// Bound on the difference between the highest and the lowest priorities,
+/// as a multiple of the total voting power of the validator set.
+const PRIORITY_WINDOW_SIZE_FACTOR: i64 = 2;
+
+#[derive_where(Clone, Debug, PartialEq, Eq)]
+struct Entry<Ctx: Context> {
+    address: Ctx::Address,
+    voting_power: i64,
+    priority: i64,
+}
+
+/// The proposer priorities of the validators in a validator set,
+/// used to select proposers in a voting-power-weighted round-robin fashion.
+///
+/// The priorities must be carried over from one height to the next
+/// with [`ProposerPriorities::next_height`], see the [module-level documentation](self).
+#[derive_where(Clone, Debug, PartialEq, Eq)]
+pub struct ProposerPriorities<Ctx: Context> {
+    /// The priorities at round 0 of the current height.
+    height: Priorities<Ctx>,
+
+    /// The priorities advanced to the latest round of the current height a proposer was selected
+    /// for, so that the proposer for the next round does not have to be selected from round 0.
+    #[derive_where(skip(EqHashOrd))]
+    round: Option<(u32, Priorities<Ctx>)>,
+}
+
+impl<Ctx: Context> ProposerPriorities<Ctx> {
+    /// Create the proposer priorities for the initial validator set,
+    /// and select the proposer for round 0 of the initial height.
+    pub fn new(validator_set: &Ctx::ValidatorSet) -> Self {
+        let entries = validators::<Ctx>(validator_set)
+            .map(|validator| Entry {
+                address: validator.address().clone(),
+                voting_power: to_i64(validator.voting_power()),
+                priority: 0,
+            })
+            .collect();
+
+        let mut height = Priorities {
+            entries,
+            proposer: 0,
+        };
+
+        height.increment(1);
+
+        Self {
+            height,
+            round: None,
+        }
+    }
+
+    /// The proposer priority of the validator with the given address, if it is in the validator set.
+    pub fn priority(&self, address: &Ctx::Address) -> Option<i64> {
+        self.height.priority(address)
+    }
+
+    /// The address of the proposer for round 0 of the current height,
+    /// or `None` if the validator set is empty.
+    pub fn proposer(&self) -> Option<&Ctx::Address> {
+        self.height.proposer()
+    }
+
+    /// The address of the proposer for the given round of the current height,
+    /// or `None` if the validator set is empty.
+    ///
+    /// The priorities of the latest round are cached, so that selecting the proposers
+    /// of successive rounds only advances them by one round each time.
+    /// A nil round is treated as round 0.
+    pub fn proposer_at(&mut self, round: Round) -> Option<Ctx::Address> {
+        let round = round.as_u32().unwrap_or(0);
+
+        if round == 0 {
+            return self.proposer().cloned();
+        }
+
+        let priorities = match self.round.take() {
+            Some((cached, mut priorities)) if cached <= round => {
+                priorities.advance(round - cached);
+                priorities
+            }
+            _ => {
+                let mut priorities = self.height.clone();
+                priorities.increment(round);
+                priorities
+            }
+        };
+
+        let proposer = priorities.proposer().cloned();
+        self.round = Some((round, priorities));
+        proposer
+    }
+
+    /// Select the proposer for the given round of the current height amongst the given validator set,
+    /// which must be the validator set these priorities were computed for.
+    pub fn select_proposer<'a>(
+        &mut self,
+        validator_set: &'a Ctx::ValidatorSet,
+        round: Round,
+    ) -> Option<&'a Ctx::Validator> {
+        let address = self.proposer_at(round)?;
+        validator_set.get_by_address(&address)
+    }
+
+    /// Move to the next height, with the given validator set.
+    ///
+    /// Validators that remain in the set keep their priority and get their voting power updated,
+    /// validators that left the set are dropped, and validators that joined the set start with
+    /// a priority of `-1.125` times the total voting power, so that they cannot be selected
+    /// right away and cannot gain an advantage by leaving and re-joining the set.
+    pub fn next_height(&mut self, validator_set: &Ctx::ValidatorSet) {
+        let total = to_i64(validator_set.total_voting_power());
+        let initial_priority = total.saturating_add(total / 8).saturating_neg();
+
+        let entries = validators::<Ctx>(validator_set)
+            .map(|validator| Entry {
+                address: validator.address().clone(),
+                voting_power: to_i64(validator.voting_power()),
+                priority: self
+                    .priority(validator.address())
+                    .unwrap_or(initial_priority),
+            })
+            .collect();
+
+        self.height = Priorities {
+            entries,
+            proposer: 0,
+        };
+        self.round = None;
+
+        self.height.increment(1);
+    }
+}
+
+#[derive_where(Clone, Debug, PartialEq, Eq)]
+struct Priorities<Ctx: Context> {
+    entries: Vec<Entry<Ctx>>,
+    proposer: usize,
+}
+
+impl<Ctx: Context> Priorities<Ctx> {
+    fn priority(&self, address: &Ctx::Address) -> Option<i64> {
+        self.entries
+            .iter()
+            .find(|entry| &entry.address == address)
+            .map(|entry| entry.priority)
+    }
+
+    fn proposer(&self) -> Option<&Ctx::Address> {
+        self.entries.get(self.proposer).map(|entry| &entry.address)
+    }
+
+    fn total_voting_power(&self) -> i64 {
+        self.entries
+            .iter()
+            .fold(0, |total, entry| total.saturating_add(entry.voting_power))
+    }
+
+    /// Advance the priorities the given number of times, selecting a new proposer each time.
+    fn increment(&mut self, times: u32) {
+        if self.entries.is_empty() {
+            return;
+        }
+
+        let total = self.total_voting_power();
+
+        self.rescale(PRIORITY_WINDOW_SIZE_FACTOR.saturating_mul(total));
+        self.center();
+
+        self.advance(times);
+    }
+
+    /// Advance the priorities the given number of times further, without rescaling them,
+    /// so that incrementing them by `a` and then advancing them by `b` is the same
+    /// as incrementing them by `a + b`.
+    fn advance(&mut self, times: u32) {
+        if self.entries.is_empty() {
+            return;
+        }
+
+        let total = self.total_voting_power();
+
+        for _ in 0..times {
+            self.increment_once(total);
+        }
+    }
+
+    fn increment_once(&mut self, total: i64) {
+        for entry in &mut self.entries {
+            entry.priority = entry.priority.saturating_add(entry.voting_power);
+        }
+
+        // Ties are broken in favor of the validator with the lowest address
+        let mut proposer = 0;
+        for (index, entry) in self.entries.iter().enumerate().skip(1) {
+            let current = &self.entries[proposer];
+
+            if entry.priority > current.priority
+                || (entry.priority == current.priority && entry.address < current.address)
+            {
+                proposer = index;
+            }
+        }
+
+        let entry = &mut self.entries[proposer];
+        entry.priority = entry.priority.saturating_sub(total);
+
+        self.proposer = proposer;
+    }
+
+    /// Scale down the priorities so that the difference between
+    /// the highest and the lowest one is at most `max_diff`.
+    fn rescale(&mut self, max_diff: i64) {
+        if max_diff <= 0 {
+            return;
+        }
+
+        let max = self.entries.iter().map(|e| e.priority).max().unwrap_or(0);
+        let min = self.entries.iter().map(|e| e.priority).min().unwrap_or(0);
+        let diff = max.saturating_sub(min);
+
+        if diff > max_diff {
+            let ratio = diff.saturating_add(max_diff - 1) / max_diff;
+
+            for entry in &mut self.entries {
+                entry.priority /= ratio;
+            }
+        }
+    }
+
+    /// Shift the priorities so that they are centered around zero.
+    fn center(&mut self) {
+        let count = self.entries.len() as i128;
+        let sum = self
+            .entries
+            .iter()
+            .map(|entry| i128::from(entry.priority))
+            .sum::<i128>();
+
+        let average = i64::try_from(sum.div_euclid(count)).unwrap_or(0);
+
+        for entry in &mut self.entries {
+            entry.priority = entry.priority.saturating_sub(average);
+        }
+    }
+}
+
+fn validators<Ctx: Context>(
+    validator_set: &Ctx::ValidatorSet,
+) -> impl Iterator<Item = &Ctx::Validator> {
+    (0..validator_set.count()).filter_map(|index| validator_set.get_by_index(index))
+}
+
+fn to_i64(voting_power: VotingPower) -> i64 {
+    i64::try_from(voting_power).unwrap_or(i64::MAX)
+}
@@ -1,2 +1,5 @@
 #[cfg(feature = "borsh")]
 mod borsh;
+
+#[cfg(feature = "protobuf")]
+pub mod proto;
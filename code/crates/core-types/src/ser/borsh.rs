@@ -1,8 +1,9 @@
 use {
     crate::{
-        CommitCertificate, CommitSignature, Context, NilOrVal, PolkaCertificate, PolkaSignature,
-        Round, RoundCertificate, RoundCertificateType, RoundSignature, Signature, SignedMessage,
-        ValueId, VoteType,
+        AggregatedSignature, AmnesiaEvidence, CommitCertificate, CommitSignature, Context,
+        DoubleProposalEvidence, DoubleVoteEvidence, Evidence, NilOrVal, PolkaCertificate,
+        PolkaSignature, Round, RoundCertificate, RoundCertificateType, RoundSignature, Signature,
+        SignedMessage, SignedProposal, SignedVote, SignerBitmap, ValueId, VoteType,
     },
     ::borsh::BorshSerialize,
     alloc::vec::Vec,
@@ -146,12 +147,14 @@ where
     Ctx::Height: borsh::BorshSerialize,
     ValueId<Ctx>: borsh::BorshSerialize,
     CommitSignature<Ctx>: borsh::BorshSerialize,
+    AggregatedSignature<Ctx>: borsh::BorshSerialize,
 {
     fn serialize<W: borsh::io::Write>(&self, writer: &mut W) -> borsh::io::Result<()> {
         self.height.serialize(writer)?;
         self.round.serialize(writer)?;
         self.value_id.serialize(writer)?;
         self.commit_signatures.serialize(writer)?;
+        self.aggregated_signature.serialize(writer)?;
         Ok(())
     }
 }
@@ -161,21 +164,46 @@ where
     Ctx::Height: borsh::BorshDeserialize,
     ValueId<Ctx>: borsh::BorshDeserialize,
     CommitSignature<Ctx>: borsh::BorshDeserialize,
+    AggregatedSignature<Ctx>: borsh::BorshDeserialize,
 {
     fn deserialize_reader<R: borsh::io::Read>(reader: &mut R) -> borsh::io::Result<Self> {
         let height = Ctx::Height::deserialize_reader(reader)?;
         let round = Round::deserialize_reader(reader)?;
         let value_id = ValueId::<Ctx>::deserialize_reader(reader)?;
         let commit_signatures = Vec::<CommitSignature<Ctx>>::deserialize_reader(reader)?;
+        let aggregated_signature = Option::<AggregatedSignature<Ctx>>::deserialize_reader(reader)?;
         Ok(CommitCertificate {
             height,
             round,
             value_id,
             commit_signatures,
+            aggregated_signature,
         })
     }
 }
 
+impl<Ctx: Context> ::borsh::BorshSerialize for AggregatedSignature<Ctx>
+where
+    Signature<Ctx>: borsh::BorshSerialize,
+{
+    fn serialize<W: borsh::io::Write>(&self, writer: &mut W) -> borsh::io::Result<()> {
+        self.signers.serialize(writer)?;
+        self.signature.serialize(writer)?;
+        Ok(())
+    }
+}
+
+impl<Ctx: Context> ::borsh::BorshDeserialize for AggregatedSignature<Ctx>
+where
+    Signature<Ctx>: borsh::BorshDeserialize,
+{
+    fn deserialize_reader<R: borsh::io::Read>(reader: &mut R) -> borsh::io::Result<Self> {
+        let signers = SignerBitmap::deserialize_reader(reader)?;
+        let signature = Signature::<Ctx>::deserialize_reader(reader)?;
+        Ok(AggregatedSignature { signers, signature })
+    }
+}
+
 impl<Ctx: Context> ::borsh::BorshSerialize for CommitSignature<Ctx>
 where
     Ctx::Address: borsh::BorshSerialize,
@@ -227,3 +255,116 @@ where
         })
     }
 }
+
+impl<Ctx: Context> ::borsh::BorshSerialize for DoubleVoteEvidence<Ctx>
+where
+    SignedVote<Ctx>: borsh::BorshSerialize,
+{
+    fn serialize<W: borsh::io::Write>(&self, writer: &mut W) -> borsh::io::Result<()> {
+        self.first.serialize(writer)?;
+        self.second.serialize(writer)?;
+        Ok(())
+    }
+}
+
+impl<Ctx: Context> ::borsh::BorshDeserialize for DoubleVoteEvidence<Ctx>
+where
+    SignedVote<Ctx>: borsh::BorshDeserialize,
+{
+    fn deserialize_reader<R: borsh::io::Read>(reader: &mut R) -> borsh::io::Result<Self> {
+        let first = SignedVote::<Ctx>::deserialize_reader(reader)?;
+        let second = SignedVote::<Ctx>::deserialize_reader(reader)?;
+        Ok(DoubleVoteEvidence { first, second })
+    }
+}
+
+impl<Ctx: Context> ::borsh::BorshSerialize for DoubleProposalEvidence<Ctx>
+where
+    SignedProposal<Ctx>: borsh::BorshSerialize,
+{
+    fn serialize<W: borsh::io::Write>(&self, writer: &mut W) -> borsh::io::Result<()> {
+        self.first.serialize(writer)?;
+        self.second.serialize(writer)?;
+        Ok(())
+    }
+}
+
+impl<Ctx: Context> ::borsh::BorshDeserialize for DoubleProposalEvidence<Ctx>
+where
+    SignedProposal<Ctx>: borsh::BorshDeserialize,
+{
+    fn deserialize_reader<R: borsh::io::Read>(reader: &mut R) -> borsh::io::Result<Self> {
+        let first = SignedProposal::<Ctx>::deserialize_reader(reader)?;
+        let second = SignedProposal::<Ctx>::deserialize_reader(reader)?;
+        Ok(DoubleProposalEvidence { first, second })
+    }
+}
+
+impl<Ctx: Context> ::borsh::BorshSerialize for AmnesiaEvidence<Ctx>
+where
+    SignedVote<Ctx>: borsh::BorshSerialize,
+{
+    fn serialize<W: borsh::io::Write>(&self, writer: &mut W) -> borsh::io::Result<()> {
+        self.precommit.serialize(writer)?;
+        self.prevote.serialize(writer)?;
+        Ok(())
+    }
+}
+
+impl<Ctx: Context> ::borsh::BorshDeserialize for AmnesiaEvidence<Ctx>
+where
+    SignedVote<Ctx>: borsh::BorshDeserialize,
+{
+    fn deserialize_reader<R: borsh::io::Read>(reader: &mut R) -> borsh::io::Result<Self> {
+        let precommit = SignedVote::<Ctx>::deserialize_reader(reader)?;
+        let prevote = SignedVote::<Ctx>::deserialize_reader(reader)?;
+        Ok(AmnesiaEvidence { precommit, prevote })
+    }
+}
+
+impl<Ctx: Context> ::borsh::BorshSerialize for Evidence<Ctx>
+where
+    SignedVote<Ctx>: borsh::BorshSerialize,
+    SignedProposal<Ctx>: borsh::BorshSerialize,
+{
+    fn serialize<W: borsh::io::Write>(&self, writer: &mut W) -> borsh::io::Result<()> {
+        match self {
+            Evidence::DoubleVote(evidence) => {
+                0u8.serialize(writer)?;
+                evidence.serialize(writer)
+            }
+            Evidence::DoubleProposal(evidence) => {
+                1u8.serialize(writer)?;
+                evidence.serialize(writer)
+            }
+            Evidence::Amnesia(evidence) => {
+                2u8.serialize(writer)?;
+                evidence.serialize(writer)
+            }
+        }
+    }
+}
+
+impl<Ctx: Context> ::borsh::BorshDeserialize for Evidence<Ctx>
+where
+    SignedVote<Ctx>: borsh::BorshDeserialize,
+    SignedProposal<Ctx>: borsh::BorshDeserialize,
+{
+    fn deserialize_reader<R: borsh::io::Read>(reader: &mut R) -> borsh::io::Result<Self> {
+        match u8::deserialize_reader(reader)? {
+            0 => Ok(Evidence::DoubleVote(
+                DoubleVoteEvidence::deserialize_reader(reader)?,
+            )),
+            1 => Ok(Evidence::DoubleProposal(
+                DoubleProposalEvidence::deserialize_reader(reader)?,
+            )),
+            2 => Ok(Evidence::Amnesia(AmnesiaEvidence::deserialize_reader(
+                reader,
+            )?)),
+            tag => Err(borsh::io::Error::new(
+                borsh::io::ErrorKind::InvalidData,
+                alloc::format!("Invalid evidence tag: {tag}"),
+            )),
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! Protobuf encoding of double vote and double proposal evidence.
+//!
+//! The signed messages are encoded with the Protobuf encoding of the context's
+//! votes, proposals and signatures, wrapped in `Any`:
+//!
+//! ```protobuf
+//! package malachitebft.core;
+//!
+//! import "google/protobuf/any.proto";
+//!
+//! message SignedMessage {
+//!     google.protobuf.Any message = 1;
+//!     google.protobuf.Any signature = 2;
+//! }
+//!
+//! message DoubleVoteEvidence {
+//!     SignedMessage first = 1;
+//!     SignedMessage second = 2;
+//! }
+//!
+//! message DoubleProposalEvidence {
+//!     SignedMessage first = 1;
+//!     SignedMessage second = 2;
+//! }
+//! ```
+
+use malachitebft_proto::{Error as ProtoError, Protobuf};
+use prost_types::Any;
+
+use crate::{Context, Signature, SignedProposal, SignedVote};
+
+/// Protobuf message for a signed vote or proposal.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SignedMessage {
+    /// The vote or proposal, as encoded by the context
+    #[prost(message, optional, tag = "1")]
+    pub message: Option<Any>,
+    /// The signature of the message, as encoded by the context
+    #[prost(message, optional, tag = "2")]
+    pub signature: Option<Any>,
+}
+
+impl prost::Name for SignedMessage {
+    const NAME: &'static str = "SignedMessage";
+    const PACKAGE: &'static str = "malachitebft.core";
+}
+
+/// Protobuf message for evidence of a validator voting twice in the same round.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct DoubleVoteEvidence {
+    /// The first signed message
+    #[prost(message, optional, tag = "1")]
+    pub first: Option<SignedMessage>,
+    /// The second, conflicting, signed message
+    #[prost(message, optional, tag = "2")]
+    pub second: Option<SignedMessage>,
+}
+
+impl prost::Name for DoubleVoteEvidence {
+    const NAME: &'static str = "DoubleVoteEvidence";
+    const PACKAGE: &'static str = "malachitebft.core";
+}
+
+/// Protobuf message for evidence of a validator proposing twice in the same round.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct DoubleProposalEvidence {
+    /// The first signed message
+    #[prost(message, optional, tag = "1")]
+    pub first: Option<SignedMessage>,
+    /// The second, conflicting, signed message
+    #[prost(message, optional, tag = "2")]
+    pub second: Option<SignedMessage>,
+}
+
+impl prost::Name for DoubleProposalEvidence {
+    const NAME: &'static str = "DoubleProposalEvidence";
+    const PACKAGE: &'static str = "malachitebft.core";
+}
+
+impl<Ctx, Msg> Protobuf for crate::SignedMessage<Ctx, Msg>
+where
+    Ctx: Context,
+    Msg: Protobuf,
+    Signature<Ctx>: Protobuf,
+{
+    type Proto = SignedMessage;
+
+    fn from_proto(proto: Self::Proto) -> Result<Self, ProtoError> {
+        let message = proto
+            .message
+            .ok_or_else(|| ProtoError::missing_field::<SignedMessage>("message"))?;
+
+        let signature = proto
+            .signature
+            .ok_or_else(|| ProtoError::missing_field::<SignedMessage>("signature"))?;
+
+        Ok(Self::new(
+            Msg::from_any(&message)?,
+            Signature::<Ctx>::from_any(&signature)?,
+        ))
+    }
+
+    fn to_proto(&self) -> Result<Self::Proto, ProtoError> {
+        Ok(SignedMessage {
+            message: Some(self.message.to_any()?),
+            signature: Some(self.signature.to_any()?),
+        })
+    }
+}
+
+impl<Ctx> Protobuf for crate::DoubleVoteEvidence<Ctx>
+where
+    Ctx: Context,
+    Ctx::Vote: Protobuf,
+    Signature<Ctx>: Protobuf,
+{
+    type Proto = DoubleVoteEvidence;
+
+    fn from_proto(proto: Self::Proto) -> Result<Self, ProtoError> {
+        let first = proto
+            .first
+            .ok_or_else(|| ProtoError::missing_field::<DoubleVoteEvidence>("first"))?;
+
+        let second = proto
+            .second
+            .ok_or_else(|| ProtoError::missing_field::<DoubleVoteEvidence>("second"))?;
+
+        Ok(Self::new(
+            SignedVote::<Ctx>::from_proto(first)?,
+            SignedVote::<Ctx>::from_proto(second)?,
+        ))
+    }
+
+    fn to_proto(&self) -> Result<Self::Proto, ProtoError> {
+        Ok(DoubleVoteEvidence {
+            first: Some(self.first.to_proto()?),
+            second: Some(self.second.to_proto()?),
+        })
+    }
+}
+
+impl<Ctx> Protobuf for crate::DoubleProposalEvidence<Ctx>
+where
+    Ctx: Context,
+    Ctx::Proposal: Protobuf,
+    Signature<Ctx>: Protobuf,
+{
+    type Proto = DoubleProposalEvidence;
+
+    fn from_proto(proto: Self::Proto) -> Result<Self, ProtoError> {
+        let first = proto
+            .first
+            .ok_or_else(|| ProtoError::missing_field::<DoubleProposalEvidence>("first"))?;
+
+        let second = proto
+            .second
+            .ok_or_else(|| ProtoError::missing_field::<DoubleProposalEvidence>("second"))?;
+
+        Ok(Self::new(
+            SignedProposal::<Ctx>::from_proto(first)?,
+            SignedProposal::<Ctx>::from_proto(second)?,
+        ))
+    }
+
+    fn to_proto(&self) -> Result<Self::Proto, ProtoError> {
+        Ok(DoubleProposalEvidence {
+            first: Some(self.first.to_proto()?),
+            second: Some(self.second.to_proto()?),
+        })
+    }
+}
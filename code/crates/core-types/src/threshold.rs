@@ -1,3 +1,5 @@
+use thiserror::Error;
+
 use crate::VotingPower;
 
 /// Represents the different quorum thresholds.
@@ -22,6 +24,7 @@ pub enum Threshold<ValueId> {
 /// - The quorum threshold, which is the minimum number of votes required for a quorum.
 /// - The honest threshold, which is the minimum number of votes required for a quorum of honest nodes.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ThresholdParams {
     /// Threshold for a quorum (default: 2f+1)
     pub quorum: ThresholdParam,
@@ -39,8 +42,92 @@ impl Default for ThresholdParams {
     }
 }
 
+impl ThresholdParams {
+    /// Check that the thresholds are well-formed and preserve safety.
+    ///
+    /// Both thresholds must be reachable, ie. strictly below the total voting power,
+    /// and the honest threshold must not exceed the quorum threshold.
+    ///
+    /// Moreover, with a quorum threshold `q` and an honest threshold `h`, we must have
+    /// `2q - 1 >= h`. Since meeting a threshold requires strictly more voting power than it,
+    /// any two quorums have more than `2q - 1` of the total voting power in common, and
+    /// therefore more than the honest threshold, ie. at least one correct validator.
+    /// This rules out conflicting decisions as long as the faulty voting power does not
+    /// exceed the honest threshold. For instance, a quorum of 3/5 only tolerates an honest
+    /// threshold of up to 1/5, even though 3/5 and 2/5 add up to the total voting power.
+    pub fn validate(&self) -> Result<(), ThresholdParamsError> {
+        for (name, param) in [("quorum", self.quorum), ("honest", self.honest)] {
+            if param.denominator == 0 {
+                return Err(ThresholdParamsError::ZeroDenominator(name));
+            }
+
+            if param.numerator >= param.denominator {
+                return Err(ThresholdParamsError::Unreachable(name, param));
+            }
+        }
+
+        let (q, h) = (self.quorum, self.honest);
+
+        // Cross-multiply to compare the fractions without loss of precision
+        let (qn, qd) = (u128::from(q.numerator), u128::from(q.denominator));
+        let (hn, hd) = (u128::from(h.numerator), u128::from(h.denominator));
+
+        if hn * qd > qn * hd {
+            return Err(ThresholdParamsError::HonestAboveQuorum {
+                quorum: q,
+                honest: h,
+            });
+        }
+
+        // 2q - 1 >= h  <=>  2 * qn * hd >= hn * qd + qd * hd
+        if 2 * qn * hd < hn * qd + qd * hd {
+            return Err(ThresholdParamsError::Unsafe {
+                quorum: q,
+                honest: h,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Represents an error that can occur when validating threshold parameters.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Error)]
+pub enum ThresholdParamsError {
+    /// A threshold has a zero denominator.
+    #[error("The {0} threshold has a zero denominator")]
+    ZeroDenominator(&'static str),
+
+    /// A threshold can never be met, as it is not below the total voting power.
+    #[error("The {0} threshold {1} can never be met, it must be below 1")]
+    Unreachable(&'static str, ThresholdParam),
+
+    /// The honest threshold is above the quorum threshold.
+    #[error("The honest threshold {honest} is above the quorum threshold {quorum}")]
+    HonestAboveQuorum {
+        /// The quorum threshold
+        quorum: ThresholdParam,
+        /// The honest threshold
+        honest: ThresholdParam,
+    },
+
+    /// Two quorums may have less than the honest threshold of voting power in common,
+    /// ie. possibly no correct validator, as `2 * quorum - 1` is below the honest threshold.
+    #[error(
+        "The quorum threshold {quorum} is too low for the honest threshold {honest}, \
+         two quorums must have more than the honest threshold in common"
+    )]
+    Unsafe {
+        /// The quorum threshold
+        quorum: ThresholdParam,
+        /// The honest threshold
+        honest: ThresholdParam,
+    },
+}
+
 /// Represents the different quorum thresholds.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ThresholdParam {
     /// Numerator of the threshold
     pub numerator: u64,
@@ -87,6 +174,12 @@ impl ThresholdParam {
     }
 }
 
+impl core::fmt::Display for ThresholdParam {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}/{}", self.numerator, self.denominator)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -103,6 +196,48 @@ mod tests {
         assert!(ThresholdParam::TWO_F_PLUS_ONE.is_met(7, 10));
     }
 
+    #[test]
+    fn threshold_params_validate() {
+        let params = |quorum: (u64, u64), honest: (u64, u64)| ThresholdParams {
+            quorum: ThresholdParam::new(quorum.0, quorum.1),
+            honest: ThresholdParam::new(honest.0, honest.1),
+        };
+
+        assert_eq!(ThresholdParams::default().validate(), Ok(()));
+        assert_eq!(params((3, 4), (1, 4)).validate(), Ok(()));
+        assert_eq!(params((3, 4), (1, 3)).validate(), Ok(()));
+
+        assert_eq!(
+            params((2, 0), (1, 3)).validate(),
+            Err(ThresholdParamsError::ZeroDenominator("quorum"))
+        );
+        assert_eq!(
+            params((3, 3), (1, 3)).validate(),
+            Err(ThresholdParamsError::Unreachable(
+                "quorum",
+                ThresholdParam::new(3, 3)
+            ))
+        );
+        assert!(matches!(
+            params((1, 3), (2, 3)).validate(),
+            Err(ThresholdParamsError::HonestAboveQuorum { .. })
+        ));
+        assert!(matches!(
+            params((1, 2), (1, 3)).validate(),
+            Err(ThresholdParamsError::Unsafe { .. })
+        ));
+        assert!(matches!(
+            params((3, 5), (1, 3)).validate(),
+            Err(ThresholdParamsError::Unsafe { .. })
+        ));
+        // Adds up to 1, but two quorums of more than 3/5 only have more than 1/5 in common
+        assert!(matches!(
+            params((3, 5), (2, 5)).validate(),
+            Err(ThresholdParamsError::Unsafe { .. })
+        ));
+        assert_eq!(params((3, 5), (1, 5)).validate(), Ok(()));
+    }
+
     #[test]
     #[should_panic(expected = "attempt to multiply with overflow")]
     fn threshold_param_is_met_overflow() {
@@ -1,3 +1,4 @@
+use alloc::vec::Vec;
 use core::fmt::{Debug, Display};
 
 use crate::{Context, PublicKey};
@@ -56,4 +57,11 @@ where
 
     /// Get the validator at the given index.
     fn get_by_index(&self, index: usize) -> Option<&Ctx::Validator>;
+
+    /// Build a validator set out of the given validators,
+    /// eg. after applying a [`ValidatorSetUpdate`](crate::ValidatorSetUpdate).
+    ///
+    /// The validators are unique but in no particular order,
+    /// the implementation is responsible for sorting them as described above.
+    fn from_validators(validators: Vec<Ctx::Validator>) -> Self;
 }
@@ -0,0 +1,172 @@
+//! Changes to a validator set, to be applied after a decision.
+
+use alloc::vec::Vec;
+
+use derive_where::derive_where;
+use thiserror::Error;
+
+use crate::{Context, Validator, ValidatorSet, VotingPower};
+
+/// A change to a validator set, expressed as a diff against the current validator set.
+///
+/// Validators in `upserts` are either added to the set, or replace the validator
+/// with the same address, eg. to update its voting power or public key.
+/// Validators whose address is in `removals` are removed from the set.
+#[derive_where(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ValidatorSetUpdate<Ctx: Context> {
+    /// Validators to add to the set, or to replace in the set.
+    pub upserts: Vec<Ctx::Validator>,
+
+    /// Addresses of the validators to remove from the set.
+    pub removals: Vec<Ctx::Address>,
+}
+
+impl<Ctx: Context> ValidatorSetUpdate<Ctx> {
+    /// Create an empty update, which leaves the validator set unchanged.
+    pub fn new() -> Self {
+        Self {
+            upserts: Vec::new(),
+            removals: Vec::new(),
+        }
+    }
+
+    /// Add the given validator to the set, or replace the validator with the same address.
+    pub fn upsert(mut self, validator: Ctx::Validator) -> Self {
+        self.upserts.push(validator);
+        self
+    }
+
+    /// Remove the validator with the given address from the set.
+    pub fn remove(mut self, address: Ctx::Address) -> Self {
+        self.removals.push(address);
+        self
+    }
+
+    /// Whether this update leaves the validator set unchanged.
+    pub fn is_empty(&self) -> bool {
+        self.upserts.is_empty() && self.removals.is_empty()
+    }
+
+    /// The amount of voting power that this update moves when applied to the given validator set,
+    /// ie. the sum over every affected validator of the difference between its voting power
+    /// before and after the update.
+    pub fn voting_power_change(&self, validator_set: &Ctx::ValidatorSet) -> VotingPower {
+        let power_of = |address: &Ctx::Address| {
+            validator_set
+                .get_by_address(address)
+                .map_or(0, |v| v.voting_power())
+        };
+
+        let upserted = self.upserts.iter().map(|validator| {
+            let (old, new) = (power_of(validator.address()), validator.voting_power());
+            old.abs_diff(new)
+        });
+
+        let removed = self.removals.iter().map(power_of);
+
+        upserted
+            .chain(removed)
+            .fold(0, |total: VotingPower, change| total.saturating_add(change))
+    }
+
+    /// Check that this update can be applied to the given validator set.
+    ///
+    /// The update is rejected if it is malformed, if it would leave the validator set empty,
+    /// or if it would change more than 1/3 of the total voting power of the validator set at once,
+    /// as the trust assumption of the current validator set would then not carry over to the new one.
+    pub fn validate(
+        &self,
+        validator_set: &Ctx::ValidatorSet,
+    ) -> Result<(), ValidatorSetUpdateError<Ctx>> {
+        for (i, validator) in self.upserts.iter().enumerate() {
+            let address = validator.address();
+
+            if validator.voting_power() == 0 {
+                return Err(ValidatorSetUpdateError::ZeroVotingPower(address.clone()));
+            }
+
+            if self.upserts[..i].iter().any(|v| v.address() == address)
+                || self.removals.contains(address)
+            {
+                return Err(ValidatorSetUpdateError::DuplicateValidator(address.clone()));
+            }
+        }
+
+        for (i, address) in self.removals.iter().enumerate() {
+            if self.removals[..i].contains(address) {
+                return Err(ValidatorSetUpdateError::DuplicateValidator(address.clone()));
+            }
+
+            if validator_set.get_by_address(address).is_none() {
+                return Err(ValidatorSetUpdateError::UnknownValidator(address.clone()));
+            }
+        }
+
+        if self.removals.len() == validator_set.count() && self.upserts.is_empty() {
+            return Err(ValidatorSetUpdateError::EmptyValidatorSet);
+        }
+
+        let change = self.voting_power_change(validator_set);
+        let total = validator_set.total_voting_power();
+
+        if u128::from(change) * 3 > u128::from(total) {
+            return Err(ValidatorSetUpdateError::TooMuchVotingPowerChange { change, total });
+        }
+
+        Ok(())
+    }
+
+    /// Apply this update to the given validator set, after checking that it is valid
+    /// with [`ValidatorSetUpdate::validate`].
+    pub fn apply(
+        &self,
+        validator_set: &Ctx::ValidatorSet,
+    ) -> Result<Ctx::ValidatorSet, ValidatorSetUpdateError<Ctx>> {
+        self.validate(validator_set)?;
+
+        let is_updated = |address: &Ctx::Address| {
+            self.removals.contains(address) || self.upserts.iter().any(|v| v.address() == address)
+        };
+
+        let validators = (0..validator_set.count())
+            .filter_map(|index| validator_set.get_by_index(index))
+            .filter(|validator| !is_updated(validator.address()))
+            .chain(self.upserts.iter())
+            .cloned()
+            .collect();
+
+        Ok(Ctx::ValidatorSet::from_validators(validators))
+    }
+}
+
+/// Represents an error that can occur when applying a validator set update.
+#[derive(Error)]
+#[derive_where(Debug, PartialEq, Eq)]
+pub enum ValidatorSetUpdateError<Ctx: Context> {
+    /// A validator was added or updated with no voting power.
+    #[error("Validator {0} has no voting power, it must be removed instead")]
+    ZeroVotingPower(Ctx::Address),
+
+    /// A validator appears more than once in the update.
+    #[error("Validator {0} appears more than once in the update")]
+    DuplicateValidator(Ctx::Address),
+
+    /// A validator to remove is not part of the validator set.
+    #[error("Validator {0} to remove is not in the validator set")]
+    UnknownValidator(Ctx::Address),
+
+    /// The update would remove every validator from the set.
+    #[error("Update would leave the validator set empty")]
+    EmptyValidatorSet,
+
+    /// The update changes more than 1/3 of the total voting power of the validator set.
+    #[error(
+        "Update changes {change} voting power, more than 1/3 of the total voting power {total}"
+    )]
+    TooMuchVotingPowerChange {
+        /// Voting power changed by the update
+        change: VotingPower,
+        /// Total voting power of the validator set the update applies to
+        total: VotingPower,
+    },
+}
@@ -5,7 +5,7 @@ use alloc::{vec, vec::Vec};
 
 use derive_where::derive_where;
 
-use malachitebft_core_types::{Context, SignedVote, Vote};
+use malachitebft_core_types::{Context, DoubleVoteEvidence, SignedVote, Vote};
 
 /// Keeps track of evidence of equivocation.
 #[derive_where(Clone, Debug, Default)]
@@ -13,8 +13,7 @@ pub struct EvidenceMap<Ctx>
 where
     Ctx: Context,
 {
-    #[allow(clippy::type_complexity)]
-    map: BTreeMap<Ctx::Address, Vec<(SignedVote<Ctx>, SignedVote<Ctx>)>>,
+    map: BTreeMap<Ctx::Address, Vec<DoubleVoteEvidence<Ctx>>>,
 }
 
 impl<Ctx> EvidenceMap<Ctx>
@@ -32,19 +31,26 @@ where
     }
 
     /// Return the evidence of equivocation for a given address, if any.
-    pub fn get(&self, address: &Ctx::Address) -> Option<&Vec<(SignedVote<Ctx>, SignedVote<Ctx>)>> {
+    pub fn get(&self, address: &Ctx::Address) -> Option<&Vec<DoubleVoteEvidence<Ctx>>> {
         self.map.get(address)
     }
 
+    /// Iterate over all the evidence of equivocation, grouped by validator address.
+    pub fn iter(&self) -> impl Iterator<Item = (&Ctx::Address, &Vec<DoubleVoteEvidence<Ctx>>)> {
+        self.map.iter()
+    }
+
     /// Add evidence of equivocation.
     pub fn add(&mut self, existing: SignedVote<Ctx>, vote: SignedVote<Ctx>) {
         debug_assert_eq!(existing.validator_address(), vote.validator_address());
 
-        if let Some(evidence) = self.map.get_mut(vote.validator_address()) {
-            evidence.push((existing, vote));
+        let address = vote.validator_address().clone();
+        let evidence = DoubleVoteEvidence::new(existing, vote);
+
+        if let Some(entries) = self.map.get_mut(&address) {
+            entries.push(evidence);
         } else {
-            self.map
-                .insert(vote.validator_address().clone(), vec![(existing, vote)]);
+            self.map.insert(address, vec![evidence]);
         }
     }
 }
@@ -1,4 +1,4 @@
-use malachitebft_core_types::{NilOrVal, Round, SignedVote};
+use malachitebft_core_types::{DoubleVoteEvidence, NilOrVal, Round, SignedVote};
 
 use informalsystems_malachitebft_core_votekeeper::keeper::{Output, VoteKeeper};
 
@@ -290,7 +290,10 @@ fn equivocation() {
     assert_eq!(msg, None);
 
     assert!(!keeper.evidence().is_empty());
-    assert_eq!(keeper.evidence().get(&addr1), Some(&vec![(vote11, vote12)]));
+    assert_eq!(
+        keeper.evidence().get(&addr1),
+        Some(&vec![DoubleVoteEvidence::new(vote11, vote12)])
+    );
 
     let vote21 = new_signed_prevote(height, round, val1, addr2);
     let msg = keeper.apply_vote(vote21.clone(), round);
@@ -303,5 +306,17 @@ fn equivocation() {
     let msg = keeper.apply_vote(vote22.clone(), round);
     assert_eq!(msg, None);
 
-    assert_eq!(keeper.evidence().get(&addr2), Some(&vec![(vote21, vote22)]));
+    assert_eq!(
+        keeper.evidence().get(&addr2),
+        Some(&vec![DoubleVoteEvidence::new(vote21, vote22)])
+    );
+
+    let offenders = keeper
+        .evidence()
+        .iter()
+        .map(|(addr, _)| *addr)
+        .collect::<Vec<_>>();
+    assert_eq!(offenders.len(), 2);
+    assert!(offenders.contains(&addr1));
+    assert!(offenders.contains(&addr2));
 }
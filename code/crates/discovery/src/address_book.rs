@@ -0,0 +1,327 @@
+//! Persistent address book of the peers this node has connected to.
+//!
+//! The address book remembers the addresses of the peers which were successfully connected to,
+//! along with when they were last seen and how many times in a row dialing them failed.
+//! It is persisted to a file, if configured, so that a restarted node can rejoin the network
+//! by dialing the peers it knew about, without depending on its bootstrap nodes being up.
+//!
+//! Entries which have not been seen for a while, or which could not be dialed too many times
+//! in a row, are aged out.
+//!
+//! When the address book is full, entries are evicted by source first, so that peers which
+//! dialed this node can never push out bootstrap nodes or peers this node chose to dial.
+//! Within a source, entries are bucketed by subnet, and the least recently seen entry of the
+//! largest bucket is evicted first, so that peers from a single network cannot take over
+//! the address book.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use libp2p::{Multiaddr, PeerId};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use crate::file::write_atomically;
+use crate::selection::latency::Subnet;
+
+/// Address book configuration.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Config {
+    /// File in which the address book is persisted across restarts, if any.
+    pub file: Option<PathBuf>,
+
+    /// How long a peer is kept in the address book after it was last seen.
+    pub max_age: Duration,
+
+    /// Number of dial failures in a row after which a peer is removed from the address book.
+    pub max_failures: u32,
+
+    /// Maximum number of peers in the address book.
+    ///
+    /// Inbound peers are evicted first, then discovered peers, and bootstrap nodes last,
+    /// the least recently seen peers from the most represented subnets going first.
+    pub max_entries: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            file: None,
+            max_age: Duration::from_secs(3 * 24 * 60 * 60),
+            max_failures: 5,
+            max_entries: 1000,
+        }
+    }
+}
+
+/// How the address of a peer was learned.
+///
+/// Sources are ordered from the least to the most trusted,
+/// which is the order in which their entries are evicted.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Source {
+    /// The peer dialed this node
+    Inbound,
+
+    /// The peer was dialed after being discovered through another peer
+    Discovered,
+
+    /// The peer is one of the configured bootstrap nodes
+    Bootstrap,
+}
+
+/// A peer in the address book.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    pub peer_id: PeerId,
+    pub addrs: Vec<Multiaddr>,
+    /// Seconds since the Unix epoch
+    pub last_seen: u64,
+    /// Number of dial failures since the peer was last seen
+    pub failures: u32,
+    pub source: Source,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct AddressBookFile {
+    peers: Vec<Entry>,
+}
+
+/// The peers this node has connected to, and their addresses.
+#[derive(Debug)]
+pub struct AddressBook {
+    config: Config,
+    entries: HashMap<PeerId, Entry>,
+    dirty: bool,
+}
+
+impl AddressBook {
+    /// Create an empty address book, which does not load the entries of the address book file.
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            entries: HashMap::new(),
+            dirty: false,
+        }
+    }
+
+    /// Create an address book with the entries persisted in the address book file, if any,
+    /// dropping those which have aged out by `now`.
+    pub fn load(config: Config, now: SystemTime) -> io::Result<Self> {
+        let mut address_book = Self::new(config);
+
+        let Some(path) = address_book.config.file.clone() else {
+            return Ok(address_book);
+        };
+
+        let file: AddressBookFile = match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => AddressBookFile::default(),
+            Err(e) => return Err(e),
+        };
+
+        for entry in file.peers {
+            address_book.entries.insert(entry.peer_id, entry);
+        }
+
+        address_book.prune(now);
+        address_book.dirty = false;
+
+        info!(
+            file = %path.display(),
+            peers = %address_book.len(),
+            "Loaded address book"
+        );
+
+        Ok(address_book)
+    }
+
+    /// The address book configuration.
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&self, peer_id: &PeerId) -> Option<&Entry> {
+        self.entries.get(peer_id)
+    }
+
+    /// Record that a peer was connected to at the given addresses, resetting its failures.
+    ///
+    /// A peer learned as a bootstrap node keeps that source.
+    pub fn record_seen(
+        &mut self,
+        peer_id: PeerId,
+        addrs: Vec<Multiaddr>,
+        source: Source,
+        now: SystemTime,
+    ) {
+        if addrs.is_empty() {
+            return;
+        }
+
+        let last_seen = unix_secs(now);
+
+        let entry = self.entries.entry(peer_id).or_insert_with(|| Entry {
+            peer_id,
+            addrs: Vec::new(),
+            last_seen,
+            failures: 0,
+            source,
+        });
+
+        entry.addrs = addrs;
+        entry.last_seen = last_seen;
+        entry.failures = 0;
+
+        if entry.source != Source::Bootstrap {
+            entry.source = source;
+        }
+
+        self.dirty = true;
+    }
+
+    /// Record that dialing a peer failed, removing it once it failed too many times in a row.
+    ///
+    /// Returns whether the peer was removed.
+    pub fn record_failure(&mut self, peer_id: &PeerId) -> bool {
+        let Some(entry) = self.entries.get_mut(peer_id) else {
+            return false;
+        };
+
+        entry.failures += 1;
+        self.dirty = true;
+
+        if entry.failures >= self.config.max_failures {
+            debug!(peer = %peer_id, failures = %entry.failures, "Removing unreachable peer from address book");
+
+            self.entries.remove(peer_id);
+            return true;
+        }
+
+        false
+    }
+
+    /// Up to `n` peers to dial on startup, the most reliable and recently seen ones first.
+    pub fn seeds(&self, n: usize) -> Vec<&Entry> {
+        let mut entries = self.entries.values().collect::<Vec<_>>();
+
+        entries.sort_by(|a, b| {
+            a.failures
+                .cmp(&b.failures)
+                .then(b.last_seen.cmp(&a.last_seen))
+        });
+
+        entries.truncate(n);
+        entries
+    }
+
+    /// Remove the peers which have not been seen for longer than the maximum age by `now`,
+    /// or which failed too many times in a row, and evict peers in excess of the maximum
+    /// number of entries.
+    ///
+    /// Returns the number of peers which were removed.
+    pub fn prune(&mut self, now: SystemTime) -> usize {
+        let before = self.entries.len();
+        let oldest = unix_secs(now).saturating_sub(self.config.max_age.as_secs());
+
+        self.entries.retain(|_, entry| {
+            entry.last_seen >= oldest && entry.failures < self.config.max_failures
+        });
+
+        while self.entries.len() > self.config.max_entries {
+            let Some(peer_id) = self.eviction_candidate() else {
+                break;
+            };
+
+            debug!(peer = %peer_id, "Evicting peer from full address book");
+            self.entries.remove(&peer_id);
+        }
+
+        let removed = before - self.entries.len();
+        if removed > 0 {
+            self.dirty = true;
+        }
+
+        removed
+    }
+
+    /// The peer to evict when the address book is full: the least recently seen peer
+    /// of the largest subnet bucket among the peers of the least trusted source.
+    fn eviction_candidate(&self) -> Option<PeerId> {
+        let source = self.entries.values().map(|entry| entry.source).min()?;
+
+        // Peers without a globally routable address share the `None` bucket
+        let mut buckets = HashMap::<Option<Subnet>, Vec<&Entry>>::new();
+        for entry in self.entries.values().filter(|e| e.source == source) {
+            buckets
+                .entry(Subnet::from_addrs(&entry.addrs))
+                .or_default()
+                .push(entry);
+        }
+
+        // Among the largest buckets, evict from the one holding the least recently seen peer
+        buckets
+            .values()
+            .filter_map(|bucket| {
+                let oldest = bucket
+                    .iter()
+                    .min_by_key(|entry| (entry.last_seen, entry.peer_id))?;
+
+                Some((Reverse(bucket.len()), oldest.last_seen, oldest.peer_id))
+            })
+            .min()
+            .map(|(_, _, peer_id)| peer_id)
+    }
+
+    /// Prune the address book, and persist it if it changed since it was last persisted.
+    pub fn tick(&mut self, now: SystemTime) {
+        self.prune(now);
+        self.persist();
+    }
+
+    /// Persist the address book if it changed since it was last persisted.
+    pub fn persist(&mut self) {
+        if !self.dirty {
+            return;
+        }
+
+        let Some(path) = &self.config.file else {
+            return;
+        };
+
+        match save_entries(path, &self.entries) {
+            Ok(()) => {
+                debug!(file = %path.display(), peers = %self.entries.len(), "Saved address book");
+                self.dirty = false;
+            }
+            Err(e) => warn!(file = %path.display(), "Failed to save address book: {e}"),
+        }
+    }
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+fn save_entries(path: &Path, entries: &HashMap<PeerId, Entry>) -> io::Result<()> {
+    let mut peers = entries.values().cloned().collect::<Vec<_>>();
+    peers.sort_by_key(|entry| Reverse(entry.last_seen));
+
+    let contents = serde_json::to_vec_pretty(&AddressBookFile { peers })?;
+    write_atomically(path, &contents)
+}
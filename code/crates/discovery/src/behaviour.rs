@@ -20,12 +20,16 @@ use crate::Config;
 pub enum Request {
     Peers(HashSet<(Option<PeerId>, Vec<Multiaddr>)>),
     Connect(),
+    /// Maximum number of signed peer records to send back
+    Pex(usize),
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Response {
     Peers(HashSet<(Option<PeerId>, Vec<Multiaddr>)>),
     Connect(bool),
+    /// Protobuf-encoded signed peer records
+    Pex(Vec<Vec<u8>>),
 }
 
 #[derive(Debug)]
@@ -141,6 +145,8 @@ impl Behaviour {
 pub trait DiscoveryClient: NetworkBehaviour {
     fn add_address(&mut self, peer: &PeerId, address: Multiaddr) -> RoutingUpdate;
 
+    fn remove_peer(&mut self, peer: &PeerId);
+
     fn kbuckets(&mut self) -> impl Iterator<Item = KBucketRef<'_, KBucketKey<PeerId>, Addresses>>;
 
     fn send_request(&mut self, peer_id: &PeerId, req: Request) -> OutboundRequestId;
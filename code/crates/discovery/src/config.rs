@@ -7,6 +7,12 @@ const DEFAULT_MAX_CONNECTIONS_PER_PEER: usize = 5;
 
 const DEFAULT_EPHEMERAL_CONNECTION_TIMEOUT: Duration = Duration::from_secs(15);
 
+const DEFAULT_MAX_OUTBOUND_PEERS_PER_SUBNET: usize = 3;
+
+const DEFAULT_PEX_INTERVAL: Duration = Duration::from_secs(30);
+
+const DEFAULT_REQUIRE_SIGNED_PEER_RECORDS: bool = true;
+
 const DEFAULT_DIAL_MAX_RETRIES: usize = 5;
 const DEFAULT_PEERS_REQUEST_MAX_RETRIES: usize = 5;
 const DEFAULT_CONNECT_REQUEST_MAX_RETRIES: usize = 0;
@@ -16,6 +22,7 @@ pub enum BootstrapProtocol {
     #[default]
     Kademlia,
     Full,
+    Pex,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
@@ -23,6 +30,7 @@ pub enum Selector {
     #[default]
     Kademlia,
     Random,
+    Latency,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -37,8 +45,14 @@ pub struct Config {
 
     pub max_connections_per_peer: usize,
 
+    pub max_outbound_peers_per_subnet: usize,
+
     pub ephemeral_connection_timeout: Duration,
 
+    pub pex_interval: Duration,
+
+    pub require_signed_peer_records: bool,
+
     pub dial_max_retries: usize,
     pub request_max_retries: usize,
     pub connect_request_max_retries: usize,
@@ -60,9 +74,14 @@ impl Default for Config {
             num_inbound_peers: DEFAULT_NUM_INBOUND_PEERS,
 
             max_connections_per_peer: DEFAULT_MAX_CONNECTIONS_PER_PEER,
+            max_outbound_peers_per_subnet: DEFAULT_MAX_OUTBOUND_PEERS_PER_SUBNET,
 
             ephemeral_connection_timeout: DEFAULT_EPHEMERAL_CONNECTION_TIMEOUT,
 
+            pex_interval: DEFAULT_PEX_INTERVAL,
+
+            require_signed_peer_records: DEFAULT_REQUIRE_SIGNED_PEER_RECORDS,
+
             dial_max_retries: DEFAULT_DIAL_MAX_RETRIES,
             request_max_retries: DEFAULT_PEERS_REQUEST_MAX_RETRIES,
             connect_request_max_retries: DEFAULT_CONNECT_REQUEST_MAX_RETRIES,
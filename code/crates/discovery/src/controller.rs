@@ -14,6 +14,8 @@ const DEFAULT_DIAL_CONCURRENT_FACTOR: usize = 20;
 const DEFAULT_PEERS_REQUEST_CONCURRENT_FACTOR: usize = 20;
 const DEFAULT_CONNECT_REQUEST_CONCURRENT_FACTOR: usize = 100;
 const DEFAULT_CLOSE_CONCURRENT_FACTOR: usize = usize::MAX;
+const DEFAULT_PROBE_CONCURRENT_FACTOR: usize = 20;
+const DEFAULT_PEERS_RESPONSE_CONCURRENT_FACTOR: usize = usize::MAX;
 
 #[derive(Debug)]
 pub struct Action<T, U, V> {
@@ -123,6 +125,8 @@ pub struct Controller {
     pub peers_request: Action<PeerId, OutboundRequestId, RequestData>,
     pub connect_request: Action<PeerId, OutboundRequestId, RequestData>,
     pub close: Action<(), (), (PeerId, ConnectionId)>,
+    pub probe: Action<ConnectionId, ConnectionId, (PeerId, Multiaddr)>,
+    pub peers_response: Action<(), (), ()>,
 }
 
 impl Controller {
@@ -132,6 +136,8 @@ impl Controller {
             peers_request: Action::new(DEFAULT_PEERS_REQUEST_CONCURRENT_FACTOR),
             connect_request: Action::new(DEFAULT_CONNECT_REQUEST_CONCURRENT_FACTOR),
             close: Action::new(DEFAULT_CLOSE_CONCURRENT_FACTOR),
+            probe: Action::new(DEFAULT_PROBE_CONCURRENT_FACTOR),
+            peers_response: Action::new(DEFAULT_PEERS_RESPONSE_CONCURRENT_FACTOR),
         }
     }
 
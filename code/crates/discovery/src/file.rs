@@ -0,0 +1,31 @@
+//! Durable writes of the files persisted across restarts, eg. the address book.
+
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Write the contents to a temporary file first, which is synced to disk and then moved over
+/// the file at the given path, so that the file is never left half-written, even on a crash.
+///
+/// The parent directory is created if it does not exist yet.
+pub fn write_atomically(path: &Path, contents: &[u8]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let tmp_path = path.with_extension("tmp");
+
+    let mut file = File::create(&tmp_path)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+
+    fs::rename(&tmp_path, path)?;
+
+    // Make sure the rename itself is durable
+    #[cfg(unix)]
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        File::open(dir)?.sync_all()?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,119 @@
+use std::time::SystemTime;
+
+use libp2p::{identify, PeerId, Swarm};
+use tracing::{debug, info};
+
+use crate::address_book::Source;
+use crate::config::BootstrapProtocol;
+use crate::dial::DialData;
+use crate::{Discovery, DiscoveryClient};
+
+impl<C> Discovery<C>
+where
+    C: DiscoveryClient,
+{
+    /// Seed Kademlia with the peers of the address book, and dial the most reliable ones
+    /// so that they can be selected as outbound peers, even if no bootstrap node is reachable.
+    pub fn dial_address_book(&mut self, swarm: &mut Swarm<C>) {
+        if !self.is_enabled() || self.address_book.is_empty() {
+            return;
+        }
+
+        let seeds = self
+            .address_book
+            .seeds(self.config.num_outbound_peers)
+            .into_iter()
+            .filter(|entry| !self.is_private_peer(&entry.peer_id))
+            .filter(|entry| {
+                // Bootstrap nodes are dialed anyway
+                !self
+                    .bootstrap_nodes
+                    .iter()
+                    .any(|(_, addrs)| entry.addrs.iter().any(|addr| addrs.contains(addr)))
+            })
+            .map(|entry| (entry.peer_id, entry.addrs.clone()))
+            .collect::<Vec<_>>();
+
+        info!(
+            "Dialing {} peers out of {} from the address book",
+            seeds.len(),
+            self.address_book.len()
+        );
+
+        for (peer_id, addrs) in seeds {
+            if self.config.bootstrap_protocol == BootstrapProtocol::Kademlia {
+                for addr in &addrs {
+                    swarm.behaviour_mut().add_address(&peer_id, addr.clone());
+                }
+            }
+
+            self.add_to_dial_queue(swarm, DialData::new(Some(peer_id), addrs));
+        }
+    }
+
+    /// Record the addresses of a newly connected peer at which it was reached in the address book.
+    ///
+    /// The other addresses are recorded once probed, since the address book seeds Kademlia
+    /// on the next start.
+    pub(crate) fn record_in_address_book(
+        &mut self,
+        peer_id: PeerId,
+        info: &identify::Info,
+        dialed: bool,
+    ) {
+        if self.is_private_peer(&peer_id) {
+            return;
+        }
+
+        let source = if self
+            .bootstrap_nodes
+            .iter()
+            .any(|(id, _)| id == &Some(peer_id))
+        {
+            Source::Bootstrap
+        } else if dialed {
+            Source::Discovered
+        } else {
+            Source::Inbound
+        };
+
+        self.address_book.record_seen(
+            peer_id,
+            self.verified_addrs(&peer_id, &info.listen_addrs),
+            source,
+            SystemTime::now(),
+        );
+    }
+
+    /// Record the addresses of a connected peer in the address book after one was verified.
+    pub(crate) fn record_verified_in_address_book(&mut self, peer_id: PeerId) {
+        let Some(info) = self.discovered_peers.get(&peer_id).cloned() else {
+            return;
+        };
+
+        // Peers which were not recorded yet were not reached at a dialed address
+        let dialed = self
+            .address_book
+            .get(&peer_id)
+            .is_some_and(|entry| entry.source != Source::Inbound);
+
+        self.record_in_address_book(peer_id, &info, dialed);
+    }
+
+    /// Record that a peer of the address book could not be dialed.
+    pub(crate) fn record_dial_failure(&mut self, peer_id: &PeerId) {
+        if self.address_book.record_failure(peer_id) {
+            debug!(peer = %peer_id, "Peer removed from the address book after too many failed dials");
+        }
+    }
+
+    /// Age out the dead entries of the address book, and persist it.
+    pub fn tick_address_book(&mut self, now: SystemTime) {
+        self.address_book.tick(now);
+    }
+
+    /// Persist the address book, eg. before shutting down.
+    pub fn persist_address_book(&mut self) {
+        self.address_book.persist();
+    }
+}
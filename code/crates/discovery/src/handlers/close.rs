@@ -44,6 +44,10 @@ where
         peer_id: PeerId,
         connection_id: ConnectionId,
     ) {
+        if self.controller.probe.remove_done_on(&connection_id) {
+            return;
+        }
+
         let mut was_last_connection = false;
 
         if let Some(connection_ids) = self.active_connections.get_mut(&peer_id) {
@@ -96,6 +100,10 @@ where
     /// Clean up peer state and dial history when the last connection to a peer is closed
     fn cleanup_peer_on_disconnect(&mut self, peer_id: PeerId) {
         let peer_info = self.discovered_peers.remove(&peer_id);
+        self.verified_addrs.remove(&peer_id);
+        self.probed_addrs.remove(&peer_id);
+        self.local_peers.remove(&peer_id);
+        self.selector.on_disconnection(&peer_id);
 
         // Find and reset the bootstrap node peer_id to allow re-identification
         // This handles the case where a bootstrap node restarts with a different peer_id
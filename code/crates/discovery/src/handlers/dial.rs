@@ -5,6 +5,7 @@ use libp2p::{
 };
 use tracing::{debug, error, warn};
 
+use crate::handlers::verification::is_global_addr;
 use crate::{controller::PeerData, dial::DialData, Discovery, DiscoveryClient};
 
 impl<C> Discovery<C>
@@ -88,9 +89,23 @@ where
         connection_id: ConnectionId,
         endpoint: ConnectedPoint,
     ) {
+        if self.handle_probe_connection(swarm, peer_id, connection_id) {
+            return;
+        }
+
+        self.selector
+            .on_connection(peer_id, endpoint.get_remote_address());
+
+        if !is_global_addr(endpoint.get_remote_address()) {
+            self.local_peers.insert(peer_id);
+        }
+
         match endpoint {
-            ConnectedPoint::Dialer { .. } => {
+            ConnectedPoint::Dialer { address, .. } => {
                 debug!(peer = %peer_id, %connection_id, "Connected to peer");
+
+                // The peer was reached at the dialed address
+                self.record_verified_addr(peer_id, &address);
             }
             ConnectedPoint::Listener { .. } => {
                 debug!(peer = %peer_id, %connection_id, "Accepted incoming connection from peer");
@@ -126,6 +141,10 @@ where
         connection_id: ConnectionId,
         error: DialError,
     ) {
+        if self.handle_failed_probe(swarm, connection_id) {
+            return;
+        }
+
         if let Some(mut dial_data) = self.controller.dial.remove_in_progress(&connection_id) {
             // Skip retrying for errors that will occur again
             if matches!(
@@ -133,7 +152,12 @@ where
                 DialError::LocalPeerId { .. }
                     | DialError::NoAddresses
                     | DialError::WrongPeerId { .. }
+                    | DialError::Denied { .. }
             ) {
+                if let Some(peer_id) = dial_data.peer_id() {
+                    self.record_dial_failure(&peer_id);
+                }
+
                 self.make_extension_step(swarm);
                 return;
             }
@@ -158,6 +182,10 @@ where
 
                 self.metrics.increment_total_failed_dials();
 
+                if let Some(peer_id) = dial_data.peer_id() {
+                    self.record_dial_failure(&peer_id);
+                }
+
                 // For bootstrap nodes, clear the done_on flag so they can be retried
                 // by the periodic timer. We check and clear by address since bootstrap
                 // nodes may not have peer_id
@@ -97,6 +97,11 @@ where
         // Return true every time another connection to the peer already exists.
         let mut is_already_connected = true;
 
+        // Ignore connections established to probe the addresses of the peer
+        if self.is_probe_connection(&connection_id) {
+            return is_already_connected;
+        }
+
         // Ignore identify intervals
         if self
             .active_connections
@@ -106,20 +111,39 @@ where
             return is_already_connected;
         }
 
+        if self.is_enabled() && !self.has_valid_peer_record(&peer_id, &info) {
+            warn!(
+                peer = %peer_id, %connection_id,
+                "Peer did not identify with a valid signed peer record, closing connection"
+            );
+
+            self.controller.dial.remove_in_progress(&connection_id);
+            swarm.close_connection(connection_id);
+
+            if let State::Extending(_) = self.state {
+                self.make_extension_step(swarm);
+            }
+
+            return is_already_connected;
+        }
+
         // Match peer against bootstrap nodes
         self.update_bootstrap_node_peer_id(peer_id);
 
-        if self
+        let dialed = self
             .controller
             .dial
             .remove_in_progress(&connection_id)
-            .is_none()
-        {
+            .is_some();
+
+        if !dialed {
             // Remove any matching in progress connections to avoid dangling data
             self.controller
                 .dial_remove_matching_in_progress_connections(&peer_id);
         }
 
+        self.record_in_address_book(peer_id, &info, dialed);
+
         match self.discovered_peers.insert(peer_id, info.clone()) {
             Some(_) => {
                 info!(
@@ -165,7 +189,17 @@ where
             is_already_connected = false;
         }
 
-        if self.is_enabled() {
+        if self.is_private_peer(&peer_id) {
+            // Private peers, eg. validators behind this sentry node, are always kept connected,
+            // and never added to the Kademlia routing table so that they are not advertised
+            debug!(peer = %peer_id, %connection_id, "Connection is to a private peer");
+
+            self.inbound_peers.insert(peer_id);
+
+            if self.is_enabled() && self.config.bootstrap_protocol == BootstrapProtocol::Kademlia {
+                swarm.behaviour_mut().remove_peer(&peer_id);
+            }
+        } else if self.is_enabled() {
             if self.outbound_peers.contains_key(&peer_id) {
                 debug!(
                     peer = %peer_id, %connection_id,
@@ -212,12 +246,15 @@ where
                     self.make_extension_step(swarm);
                 }
             }
-            // Add the address to the Kademlia routing table
+            // Add the addresses at which the peer was reached to the Kademlia routing table,
+            // the other ones are added once probed
             if self.config.bootstrap_protocol == BootstrapProtocol::Kademlia {
-                swarm
-                    .behaviour_mut()
-                    .add_address(&peer_id, info.listen_addrs.first().unwrap().clone());
+                for addr in self.verified_addrs(&peer_id, &info.listen_addrs) {
+                    swarm.behaviour_mut().add_address(&peer_id, addr);
+                }
             }
+
+            self.probe_unverified_addrs(peer_id, &info.listen_addrs);
         } else {
             // If discovery is disabled, all peers are inbound. The
             // maximum number of inbound peers is enforced by the
@@ -1,5 +1,6 @@
 pub mod selection;
 
+pub mod address_book;
 pub mod bootstrap;
 pub mod close;
 pub mod connect_request;
@@ -9,3 +10,5 @@ pub mod helpers;
 pub mod identify;
 pub mod peers_management;
 pub mod peers_request;
+pub mod pex;
+pub mod verification;
@@ -18,6 +18,7 @@ where
         let peers = match self.selector.try_select_n_outbound_candidates(
             swarm,
             &self.discovered_peers,
+            self.outbound_peers.keys().cloned().collect(),
             self.get_excluded_peers(),
             n,
         ) {
@@ -115,6 +116,7 @@ where
         match self.selector.try_select_n_outbound_candidates(
             swarm,
             &self.discovered_peers,
+            self.outbound_peers.keys().cloned().collect(),
             self.get_excluded_peers(),
             1,
         ) {
@@ -1,6 +1,8 @@
 use std::collections::HashSet;
+use std::time::Duration;
 
 use libp2p::{
+    multiaddr::Protocol,
     request_response::{OutboundRequestId, ResponseChannel},
     Multiaddr, PeerId, Swarm,
 };
@@ -9,10 +11,15 @@ use tracing::{debug, error, trace};
 use crate::{
     behaviour::{self, Response},
     dial::DialData,
-    request::RequestData,
+    handlers::pex::PEX_MAX_RECORDS,
+    request::{DeferredPeersRequest, RequestData},
     Discovery, DiscoveryClient,
 };
 
+/// Maximum time a peers request is left unanswered while probing addresses,
+/// below the request timeout
+const PEERS_RESPONSE_MAX_DELAY: Duration = Duration::from_secs(2);
+
 impl<C> Discovery<C>
 where
     C: DiscoveryClient,
@@ -50,10 +57,15 @@ where
             request_data.retry.count()
         );
 
-        let request_id = swarm.behaviour_mut().send_request(
-            &request_data.peer_id(),
-            behaviour::Request::Peers(self.get_all_peers_except(request_data.peer_id())),
-        );
+        let request = if self.is_pex() {
+            behaviour::Request::Pex(PEX_MAX_RECORDS)
+        } else {
+            behaviour::Request::Peers(self.get_all_peers_except(request_data.peer_id()))
+        };
+
+        let request_id = swarm
+            .behaviour_mut()
+            .send_request(&request_data.peer_id(), request);
 
         self.controller
             .peers_request
@@ -66,6 +78,40 @@ where
         peer: PeerId,
         channel: ResponseChannel<Response>,
         peers: HashSet<(Option<PeerId>, Vec<Multiaddr>)>,
+    ) {
+        // Wait for the addresses being probed to be verified, so that they can be advertised
+        if self.is_probing() {
+            debug!(%peer, "Deferring peers response until the pending probes are done");
+
+            self.deferred_peers_requests.push(DeferredPeersRequest {
+                peer,
+                channel,
+                peers,
+            });
+
+            self.controller
+                .peers_response
+                .add_to_queue((), Some(PEERS_RESPONSE_MAX_DELAY));
+
+            return;
+        }
+
+        self.send_peers_response(swarm, peer, channel, peers);
+    }
+
+    /// Respond to the peers requests deferred while probing addresses.
+    pub fn respond_to_deferred_peers_requests(&mut self, swarm: &mut Swarm<C>) {
+        for request in std::mem::take(&mut self.deferred_peers_requests) {
+            self.send_peers_response(swarm, request.peer, request.channel, request.peers);
+        }
+    }
+
+    fn send_peers_response(
+        &mut self,
+        swarm: &mut Swarm<C>,
+        peer: PeerId,
+        channel: ResponseChannel<Response>,
+        peers: HashSet<(Option<PeerId>, Vec<Multiaddr>)>,
     ) {
         // Compute the difference between the discovered peers and the requested peers
         // to avoid sending the requesting peer the peers it already knows.
@@ -143,7 +189,8 @@ where
         }
     }
 
-    /// Returns all discovered peers, including bootstrap nodes, except the given peer.
+    /// Returns all discovered peers, including bootstrap nodes, except the given peer
+    /// and private peers.
     fn get_all_peers_except(&self, peer: PeerId) -> HashSet<(Option<PeerId>, Vec<Multiaddr>)> {
         let mut remaining_bootstrap_nodes: Vec<_> = self.bootstrap_nodes.clone();
 
@@ -161,18 +208,39 @@ where
                         .all(|addr| !info.listen_addrs.contains(addr))
                 });
 
-                if peer_id == &peer {
+                if peer_id == &peer || self.is_private_peer(peer_id) {
                     return None;
                 }
 
-                Some((Some(*peer_id), info.listen_addrs.clone()))
+                // Only advertise the addresses at which the peer was reached
+                let verified_addrs = self.verified_addrs(peer_id, &info.listen_addrs);
+                if verified_addrs.is_empty() {
+                    return None;
+                }
+
+                Some((Some(*peer_id), verified_addrs))
             })
             .collect();
 
         for (peer_id, listen_addrs) in remaining_bootstrap_nodes {
-            peers.insert((peer_id, listen_addrs.clone()));
+            if !self.is_private_bootstrap_node(peer_id.as_ref(), &listen_addrs) {
+                peers.insert((peer_id, listen_addrs));
+            }
         }
 
         peers
     }
+
+    /// Whether a bootstrap node is a private peer, either because it was identified as one,
+    /// or because one of its addresses ends with the ID of a private peer.
+    fn is_private_bootstrap_node(
+        &self,
+        peer_id: Option<&PeerId>,
+        listen_addrs: &[Multiaddr],
+    ) -> bool {
+        peer_id.is_some_and(|peer_id| self.is_private_peer(peer_id))
+            || listen_addrs.iter().any(|addr| {
+                matches!(addr.iter().last(), Some(Protocol::P2p(peer_id)) if self.is_private_peer(&peer_id))
+            })
+    }
 }
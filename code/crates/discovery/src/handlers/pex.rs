@@ -0,0 +1,150 @@
+//! Peer exchange (PEX), a lightweight alternative to Kademlia for discovering peers.
+//!
+//! Peers exchange the signed peer records they received from the peers they are connected to
+//! through the identify protocol, over the discovery request-response protocol.
+//! Each record is signed by the peer it describes, so that a peer cannot make others dial
+//! arbitrary addresses on behalf of another peer.
+//!
+//! After the initial discovery, a connected peer is sampled periodically for more records,
+//! so that the node keeps a fresh view of the network to select outbound peers from.
+
+use libp2p::core::{PeerRecord, SignedEnvelope};
+use libp2p::request_response::{OutboundRequestId, ResponseChannel};
+use libp2p::{PeerId, Swarm};
+use rand::seq::{IteratorRandom, SliceRandom};
+use tracing::{debug, error, trace, warn};
+
+use crate::behaviour::{self, Response};
+use crate::config::BootstrapProtocol;
+use crate::dial::DialData;
+use crate::request::RequestData;
+use crate::{Discovery, DiscoveryClient, State};
+
+/// Maximum number of signed peer records sent in a single response
+pub const PEX_MAX_RECORDS: usize = 32;
+
+impl<C> Discovery<C>
+where
+    C: DiscoveryClient,
+{
+    pub(crate) fn is_pex(&self) -> bool {
+        self.is_enabled() && self.config.bootstrap_protocol == BootstrapProtocol::Pex
+    }
+
+    /// Ask a random connected peer for the records of the peers it knows about.
+    ///
+    /// Only done with the PEX bootstrap protocol, once the initial discovery is over.
+    pub fn pex_tick(&mut self, swarm: &Swarm<C>) {
+        if !self.is_pex() || self.state != State::Idle {
+            return;
+        }
+
+        let Some(peer_id) = self
+            .active_connections
+            .keys()
+            .filter(|peer_id| !self.is_private_peer(peer_id))
+            .choose(&mut rand::thread_rng())
+            .copied()
+        else {
+            return;
+        };
+
+        if !swarm.is_connected(&peer_id) {
+            return;
+        }
+
+        debug!(%peer_id, "Sampling peer records from peer");
+
+        // Allow asking a peer again
+        self.controller.peers_request.remove_done_on(&peer_id);
+
+        self.controller
+            .peers_request
+            .add_to_queue(RequestData::new(peer_id), None);
+    }
+
+    pub(crate) fn handle_pex_request(
+        &mut self,
+        swarm: &mut Swarm<C>,
+        peer: PeerId,
+        channel: ResponseChannel<Response>,
+        max_records: usize,
+    ) {
+        let mut records = self
+            .discovered_peers
+            .iter()
+            .filter(|(peer_id, _)| **peer_id != peer && !self.is_private_peer(peer_id))
+            // Only advertise the peers which were reached at one of their addresses
+            .filter(|(peer_id, info)| !self.verified_addrs(peer_id, &info.listen_addrs).is_empty())
+            .filter_map(|(_, info)| info.signed_peer_record.clone())
+            .collect::<Vec<_>>();
+
+        records.shuffle(&mut rand::thread_rng());
+        records.truncate(max_records.min(PEX_MAX_RECORDS));
+
+        let records = records
+            .into_iter()
+            .map(SignedEnvelope::into_protobuf_encoding)
+            .collect::<Vec<_>>();
+
+        let count = records.len();
+
+        if swarm
+            .behaviour_mut()
+            .send_response(channel, behaviour::Response::Pex(records))
+            .is_err()
+        {
+            error!("Error sending peer records to {peer}");
+        } else {
+            trace!("Sent {count} peer records to {peer}");
+        }
+    }
+
+    pub(crate) fn handle_pex_response(
+        &mut self,
+        swarm: &mut Swarm<C>,
+        request_id: OutboundRequestId,
+        peer: PeerId,
+        records: Vec<Vec<u8>>,
+    ) {
+        self.controller
+            .peers_request
+            .remove_in_progress(&request_id);
+
+        if records.len() > PEX_MAX_RECORDS {
+            warn!(%peer, count = records.len(), "Peer sent too many peer records, ignoring them");
+        } else {
+            for bytes in records {
+                match decode_peer_record(&bytes) {
+                    Some(record) => self.process_peer_record(swarm, record),
+                    None => warn!(%peer, "Peer sent an invalid peer record"),
+                }
+            }
+        }
+
+        self.make_extension_step(swarm);
+    }
+
+    fn process_peer_record(&mut self, swarm: &Swarm<C>, record: PeerRecord) {
+        let peer_id = record.peer_id();
+
+        if &peer_id == swarm.local_peer_id() || self.is_private_peer(&peer_id) {
+            return;
+        }
+
+        trace!(%peer_id, addrs = ?record.addresses(), "Received peer record");
+
+        self.add_to_dial_queue(
+            swarm,
+            DialData::new(Some(peer_id), record.addresses().to_vec()),
+        );
+    }
+}
+
+/// Decode a signed peer record, and verify that it was signed by the peer it describes.
+fn decode_peer_record(bytes: &[u8]) -> Option<PeerRecord> {
+    let envelope = SignedEnvelope::from_protobuf_encoding(bytes).ok()?;
+    let record = PeerRecord::from_signed_envelope(envelope).ok()?;
+
+    (!record.addresses().is_empty()).then_some(record)
+}
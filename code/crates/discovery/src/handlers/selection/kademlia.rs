@@ -8,7 +8,7 @@ use crate::DiscoveryClient;
 
 use super::selector::{Selection, Selector};
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct KademliaSelector {}
 
 impl KademliaSelector {
@@ -40,6 +40,7 @@ where
         &mut self,
         swarm: &mut Swarm<C>,
         discovered: &HashMap<PeerId, identify::Info>,
+        _outbound: Vec<PeerId>,
         excluded: Vec<PeerId>,
         n: usize,
     ) -> Selection<PeerId> {
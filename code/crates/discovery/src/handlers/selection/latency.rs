@@ -0,0 +1,236 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::time::Duration;
+
+use libp2p::multiaddr::Protocol;
+use libp2p::{identify, Multiaddr, PeerId, Swarm};
+use rand::seq::SliceRandom;
+use tracing::debug;
+
+use crate::DiscoveryClient;
+
+use super::selector::{Selection, Selector};
+
+/// Weight of a new round-trip time measurement in the smoothed round-trip time of a peer
+const RTT_SMOOTHING: f64 = 0.2;
+
+/// Network prefix shared by peers which are likely operated from the same network,
+/// ie. the /16 prefix of IPv4 addresses and the /32 prefix of IPv6 addresses,
+/// which approximate the allocations of autonomous systems.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Subnet {
+    V4([u8; 2]),
+    V6([u16; 2]),
+}
+
+impl Subnet {
+    /// The subnet of the first globally routable IP address of the given addresses, if any.
+    ///
+    /// Peers on loopback, private or link-local addresses are not assigned a subnet,
+    /// so that diversity limits do not apply to local networks.
+    pub fn from_addrs(addrs: &[Multiaddr]) -> Option<Self> {
+        addrs.iter().find_map(Self::from_addr)
+    }
+
+    /// The subnet of the IP address of the given address, if it is globally routable.
+    pub fn from_addr(addr: &Multiaddr) -> Option<Self> {
+        addr.iter()
+            .find_map(|protocol| match protocol {
+                Protocol::Ip4(ip) => Some(IpAddr::V4(ip)),
+                Protocol::Ip6(ip) => Some(IpAddr::V6(ip)),
+                _ => None,
+            })
+            .and_then(Self::from_ip)
+    }
+
+    pub fn from_ip(ip: IpAddr) -> Option<Self> {
+        match ip {
+            IpAddr::V4(ip) if is_global_v4(&ip) => {
+                let [a, b, _, _] = ip.octets();
+                Some(Self::V4([a, b]))
+            }
+            IpAddr::V6(ip) if is_global_v6(&ip) => {
+                let [a, b, ..] = ip.segments();
+                Some(Self::V6([a, b]))
+            }
+            _ => None,
+        }
+    }
+}
+
+fn is_global_v4(ip: &Ipv4Addr) -> bool {
+    !(ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_documentation())
+}
+
+fn is_global_v6(ip: &Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+
+    let is_unique_local = first & 0xfe00 == 0xfc00;
+    let is_link_local = first & 0xffc0 == 0xfe80;
+
+    !(ip.is_loopback() || ip.is_unspecified() || is_unique_local || is_link_local)
+}
+
+/// Selects the outbound peers with the lowest round-trip times, as measured by the
+/// `ping` protocol, while limiting the number of outbound peers in the same subnet,
+/// so that an attacker controlling a single network cannot eclipse the node.
+///
+/// The subnet of a peer is the one of the remote address of our connection to it,
+/// rather than of the listen addresses it reports, which it could pick at will.
+#[derive(Debug)]
+pub struct LatencySelector {
+    /// Maximum number of outbound peers in the same subnet, or zero for no limit
+    max_peers_per_subnet: usize,
+
+    /// Smoothed round-trip times of the connected peers
+    rtts: HashMap<PeerId, Duration>,
+
+    /// Subnets of the remote addresses of the connected peers
+    subnets: HashMap<PeerId, Option<Subnet>>,
+}
+
+impl LatencySelector {
+    pub fn new(max_peers_per_subnet: usize) -> Self {
+        LatencySelector {
+            max_peers_per_subnet,
+            rtts: HashMap::new(),
+            subnets: HashMap::new(),
+        }
+    }
+
+    /// The subnet of the remote address of the connection to the given peer, if it is connected
+    /// and that address is globally routable.
+    pub fn subnet(&self, peer_id: &PeerId) -> Option<Subnet> {
+        self.subnets.get(peer_id).copied().flatten()
+    }
+
+    /// Record the remote address of a connection to a peer.
+    pub fn record_connection(&mut self, peer_id: PeerId, remote_addr: &Multiaddr) {
+        self.subnets.insert(peer_id, Subnet::from_addr(remote_addr));
+    }
+
+    /// Forget about a peer once its last connection is closed.
+    pub fn forget(&mut self, peer_id: &PeerId) {
+        self.rtts.remove(peer_id);
+        self.subnets.remove(peer_id);
+    }
+
+    /// The smoothed round-trip time of the given peer, if it was ever measured.
+    pub fn rtt(&self, peer_id: &PeerId) -> Option<Duration> {
+        self.rtts.get(peer_id).copied()
+    }
+
+    /// Record a round-trip time measurement to a peer, smoothing it with the previous ones.
+    pub fn record_rtt(&mut self, peer_id: PeerId, rtt: Duration) {
+        let smoothed = match self.rtts.get(&peer_id) {
+            Some(previous) => previous.mul_f64(1.0 - RTT_SMOOTHING) + rtt.mul_f64(RTT_SMOOTHING),
+            None => rtt,
+        };
+
+        self.rtts.insert(peer_id, smoothed);
+    }
+
+    /// Select up to `n` of the given candidates, with the lowest round-trip times first,
+    /// without exceeding the maximum number of outbound peers per subnet, given the current
+    /// outbound peers.
+    pub fn select(
+        &self,
+        mut candidates: Vec<PeerId>,
+        outbound: &[PeerId],
+        n: usize,
+        subnet_of: impl Fn(&PeerId) -> Option<Subnet>,
+    ) -> Selection<PeerId> {
+        if n == 0 {
+            return Selection::None;
+        }
+
+        // Subnets already taken by the current outbound peers
+        let mut counts: HashMap<Subnet, usize> = HashMap::new();
+        for subnet in outbound.iter().filter_map(&subnet_of) {
+            *counts.entry(subnet).or_default() += 1;
+        }
+
+        // Shuffle first so that peers with the same or unknown round-trip times are picked at random,
+        // and then favor the peers with the lowest round-trip times, the ones never measured last
+        candidates.shuffle(&mut rand::thread_rng());
+        candidates.sort_by_key(|peer_id| match self.rtt(peer_id) {
+            Some(rtt) => (false, rtt),
+            None => (true, Duration::ZERO),
+        });
+
+        let mut selected = Vec::with_capacity(n);
+
+        for peer_id in candidates {
+            if selected.len() >= n {
+                break;
+            }
+
+            let subnet = subnet_of(&peer_id);
+
+            if !self.subnet_has_room(subnet, &counts) {
+                debug!(%peer_id, ?subnet, "Skipping outbound candidate, too many outbound peers in its subnet");
+                continue;
+            }
+
+            if let Some(subnet) = subnet {
+                *counts.entry(subnet).or_default() += 1;
+            }
+
+            selected.push(peer_id);
+        }
+
+        match selected.len() {
+            0 => Selection::None,
+            len if len < n => Selection::Only(selected),
+            _ => Selection::Exactly(selected),
+        }
+    }
+
+    fn subnet_has_room(&self, subnet: Option<Subnet>, counts: &HashMap<Subnet, usize>) -> bool {
+        match subnet {
+            Some(subnet) if self.max_peers_per_subnet > 0 => {
+                counts.get(&subnet).copied().unwrap_or(0) < self.max_peers_per_subnet
+            }
+            _ => true,
+        }
+    }
+}
+
+impl<C> Selector<C> for LatencySelector
+where
+    C: DiscoveryClient,
+{
+    fn try_select_n_outbound_candidates(
+        &mut self,
+        _swarm: &mut Swarm<C>,
+        discovered: &HashMap<PeerId, identify::Info>,
+        outbound: Vec<PeerId>,
+        excluded: Vec<PeerId>,
+        n: usize,
+    ) -> Selection<PeerId> {
+        let candidates = discovered
+            .keys()
+            .filter(|peer_id| !excluded.contains(peer_id) && !outbound.contains(peer_id))
+            .cloned()
+            .collect();
+
+        self.select(candidates, &outbound, n, |peer_id| self.subnet(peer_id))
+    }
+
+    fn on_connection(&mut self, peer_id: PeerId, remote_addr: &Multiaddr) {
+        self.record_connection(peer_id, remote_addr);
+    }
+
+    fn on_disconnection(&mut self, peer_id: &PeerId) {
+        self.forget(peer_id);
+    }
+
+    fn on_rtt(&mut self, peer_id: PeerId, rtt: Duration) {
+        self.record_rtt(peer_id, rtt);
+    }
+}
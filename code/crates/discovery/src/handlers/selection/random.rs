@@ -7,7 +7,7 @@ use crate::DiscoveryClient;
 
 use super::selector::{Selection, Selector};
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct RandomSelector {}
 
 impl RandomSelector {
@@ -24,6 +24,7 @@ where
         &mut self,
         _swarm: &mut Swarm<C>,
         discovered: &HashMap<PeerId, identify::Info>,
+        _outbound: Vec<PeerId>,
         excluded: Vec<PeerId>,
         n: usize,
     ) -> Selection<PeerId> {
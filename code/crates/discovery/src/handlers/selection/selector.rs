@@ -1,30 +1,27 @@
-use std::{collections::HashMap, fmt::Debug};
+use std::{collections::HashMap, fmt::Debug, time::Duration};
 
-use libp2p::{identify, PeerId, Swarm};
+use libp2p::{identify, Multiaddr, PeerId, Swarm};
 use tracing::info;
 
 use crate::config;
 use crate::{Discovery, DiscoveryClient};
 
 use super::kademlia::KademliaSelector;
+use super::latency::LatencySelector;
 use super::random::RandomSelector;
 
 impl<C> Discovery<C>
 where
     C: DiscoveryClient,
 {
-    pub(crate) fn get_selector(
-        is_enabled: bool,
-        bootstrap_protocol: config::BootstrapProtocol,
-        selector: config::Selector,
-    ) -> Box<dyn Selector<C>> {
-        if !is_enabled {
+    pub(crate) fn get_selector(config: &config::Config) -> Box<dyn Selector<C>> {
+        if !config.enabled {
             return Box::new(RandomSelector::new());
         }
 
-        match selector {
+        match config.selector {
             config::Selector::Kademlia => {
-                if bootstrap_protocol != config::BootstrapProtocol::Kademlia {
+                if config.bootstrap_protocol != config::BootstrapProtocol::Kademlia {
                     panic!(
                         "Kademlia selector is only available with the Kademlia bootstrap protocol"
                     );
@@ -38,23 +35,33 @@ where
                 info!("Using Random selector");
                 Box::new(RandomSelector::new())
             }
+
+            config::Selector::Latency => {
+                info!(
+                    max_outbound_peers_per_subnet = config.max_outbound_peers_per_subnet,
+                    "Using Latency selector"
+                );
+                Box::new(LatencySelector::new(config.max_outbound_peers_per_subnet))
+            }
         }
     }
 
     /// Excluded peers are those that are already outbound or have already
-    /// been requested to be so.
+    /// been requested to be so, and private peers, which are always kept connected.
     pub(crate) fn get_excluded_peers(&self) -> Vec<PeerId> {
         self.discovered_peers
             .keys()
             .filter(|peer_id| {
                 self.outbound_peers.contains_key(peer_id)
                     || self.controller.connect_request.is_done_on(peer_id)
+                    || self.is_private_peer(peer_id)
             })
             .cloned()
             .collect()
     }
 }
 
+#[derive(Debug, PartialEq, Eq)]
 pub enum Selection<T> {
     Exactly(Vec<T>),
     Only(Vec<T>),
@@ -71,7 +78,17 @@ where
         &mut self,
         swarm: &mut Swarm<C>,
         discovered: &HashMap<PeerId, identify::Info>,
+        outbound: Vec<PeerId>,
         excluded: Vec<PeerId>,
         n: usize,
     ) -> Selection<PeerId>;
+
+    /// Record a connection to a peer, established at the given remote address.
+    fn on_connection(&mut self, _peer_id: PeerId, _remote_addr: &Multiaddr) {}
+
+    /// Forget about a peer once its last connection is closed.
+    fn on_disconnection(&mut self, _peer_id: &PeerId) {}
+
+    /// Record a round-trip time measurement to a peer.
+    fn on_rtt(&mut self, _peer_id: PeerId, _rtt: Duration) {}
 }
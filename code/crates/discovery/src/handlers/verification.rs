@@ -0,0 +1,237 @@
+//! Verification of the addresses of the peers, before they are used or advertised.
+//!
+//! Peers must identify with a signed peer record, so that the addresses they advertise
+//! were chosen by the peer itself and not by whoever relayed them.
+//! Each advertised address is then probed by dialing the peer at that address, and only
+//! the addresses at which the peer was actually reached are added to the Kademlia routing
+//! table, recorded in the address book and advertised to other peers. This prevents a
+//! malicious peer from poisoning routing tables with addresses which do not belong to
+//! the peer they are advertised for.
+//!
+//! So that a peer cannot use probes to make the node dial arbitrary hosts, only a few
+//! addresses are probed per peer, and addresses on local networks are only probed for
+//! peers which connected from a local network themselves.
+
+use libp2p::core::PeerRecord;
+use libp2p::multiaddr::Protocol;
+use libp2p::swarm::dial_opts::{DialOpts, PeerCondition};
+use libp2p::swarm::ConnectionId;
+use libp2p::{identify, Multiaddr, PeerId, Swarm};
+use tracing::{debug, warn};
+
+use crate::config::BootstrapProtocol;
+use crate::selection::latency::Subnet;
+use crate::{Discovery, DiscoveryClient};
+
+/// Maximum number of listen addresses probed per connected peer
+const MAX_PROBED_ADDRS_PER_PEER: usize = 8;
+
+impl<C> Discovery<C>
+where
+    C: DiscoveryClient,
+{
+    /// Whether the peer identified with a signed peer record matching its identity
+    /// and its listen addresses, or signed peer records are not required.
+    pub(crate) fn has_valid_peer_record(&self, peer_id: &PeerId, info: &identify::Info) -> bool {
+        let Some(envelope) = &info.signed_peer_record else {
+            return !self.config.require_signed_peer_records;
+        };
+
+        match PeerRecord::from_signed_envelope(envelope.clone()) {
+            Ok(record) => record.peer_id() == *peer_id && record.addresses() == info.listen_addrs,
+            Err(e) => {
+                warn!(peer = %peer_id, "Invalid signed peer record: {e}");
+                false
+            }
+        }
+    }
+
+    /// Record that the peer was reached at the given address.
+    pub(crate) fn record_verified_addr(&mut self, peer_id: PeerId, addr: &Multiaddr) {
+        self.verified_addrs
+            .entry(peer_id)
+            .or_default()
+            .insert(without_peer_id(addr));
+    }
+
+    /// The listen addresses of the peer at which it was reached.
+    pub(crate) fn verified_addrs(
+        &self,
+        peer_id: &PeerId,
+        listen_addrs: &[Multiaddr],
+    ) -> Vec<Multiaddr> {
+        let Some(verified) = self.verified_addrs.get(peer_id) else {
+            return Vec::new();
+        };
+
+        listen_addrs
+            .iter()
+            .filter(|addr| verified.contains(*addr))
+            .cloned()
+            .collect()
+    }
+
+    /// Queue a probe of each listen address of the peer at which it was not reached
+    /// nor probed yet, up to [`MAX_PROBED_ADDRS_PER_PEER`] addresses per peer.
+    ///
+    /// Addresses on local networks are skipped, unless the peer connected from one.
+    pub(crate) fn probe_unverified_addrs(&mut self, peer_id: PeerId, listen_addrs: &[Multiaddr]) {
+        let is_local_peer = self.local_peers.contains(&peer_id);
+        let verified = self.verified_addrs.get(&peer_id);
+        let probed = self.probed_addrs.entry(peer_id).or_default();
+
+        for addr in listen_addrs {
+            if probed.len() >= MAX_PROBED_ADDRS_PER_PEER {
+                debug!(peer = %peer_id, "Too many addresses probed for peer, not probing the other ones");
+                break;
+            }
+
+            if verified.is_some_and(|verified| verified.contains(addr))
+                || probed.contains(addr)
+                || (!is_local_peer && !is_global_addr(addr))
+            {
+                continue;
+            }
+
+            probed.insert(addr.clone());
+
+            self.controller
+                .probe
+                .add_to_queue((peer_id, addr.clone()), None);
+        }
+    }
+
+    pub fn can_probe(&self) -> bool {
+        self.controller.probe.can_perform()
+    }
+
+    /// Whether some probes are queued or in progress.
+    pub(crate) fn is_probing(&self) -> bool {
+        self.controller.probe.queue_len() > 0 || !self.controller.probe.is_idle().0
+    }
+
+    /// Respond to the deferred peers requests once no probe is pending anymore.
+    fn on_probe_done(&mut self, swarm: &mut Swarm<C>) {
+        if !self.is_probing() {
+            self.respond_to_deferred_peers_requests(swarm);
+        }
+    }
+
+    /// Dial the peer at the given address, in addition to the existing connections to the peer.
+    ///
+    /// The address is verified once the connection is established, since the transport
+    /// authenticates the peer, and the connection is then closed right away.
+    pub fn probe_addr(&mut self, swarm: &mut Swarm<C>, peer_id: PeerId, addr: Multiaddr) {
+        // The peer disconnected, or the address was verified since the probe was queued
+        if !self.discovered_peers.contains_key(&peer_id)
+            || self
+                .verified_addrs
+                .get(&peer_id)
+                .is_some_and(|verified| verified.contains(&addr))
+        {
+            self.on_probe_done(swarm);
+            return;
+        }
+
+        let dial_opts = DialOpts::peer_id(peer_id)
+            .addresses(vec![addr.clone()])
+            .condition(PeerCondition::Always)
+            .allocate_new_port()
+            .build();
+
+        let connection_id = dial_opts.connection_id();
+
+        debug!(peer = %peer_id, %connection_id, %addr, "Probing address of peer");
+
+        self.controller
+            .probe
+            .register_in_progress(connection_id, (peer_id, addr));
+
+        if let Err(e) = swarm.dial(dial_opts) {
+            debug!(peer = %peer_id, %connection_id, "Error probing address of peer: {e}");
+
+            self.controller.probe.remove_in_progress(&connection_id);
+            self.on_probe_done(swarm);
+        }
+    }
+
+    /// Handle the connection established by a probe, if it is one.
+    ///
+    /// Returns whether the connection was established by a probe.
+    pub(crate) fn handle_probe_connection(
+        &mut self,
+        swarm: &mut Swarm<C>,
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+    ) -> bool {
+        let Some((_, addr)) = self.controller.probe.remove_in_progress(&connection_id) else {
+            return false;
+        };
+
+        debug!(peer = %peer_id, %connection_id, %addr, "Verified address of peer");
+
+        self.record_verified_addr(peer_id, &addr);
+        self.record_verified_in_address_book(peer_id);
+
+        if self.is_enabled()
+            && self.config.bootstrap_protocol == BootstrapProtocol::Kademlia
+            && !self.is_private_peer(&peer_id)
+        {
+            swarm.behaviour_mut().add_address(&peer_id, addr);
+        }
+
+        // Remember the connection until it is closed, so that it is not mistaken for a new one
+        self.controller.probe.register_done_on(connection_id);
+        swarm.close_connection(connection_id);
+
+        self.on_probe_done(swarm);
+
+        true
+    }
+
+    /// Handle the failure of a probe, if the connection was established by one.
+    ///
+    /// Returns whether the connection was established by a probe.
+    pub(crate) fn handle_failed_probe(
+        &mut self,
+        swarm: &mut Swarm<C>,
+        connection_id: ConnectionId,
+    ) -> bool {
+        let Some((peer_id, addr)) = self.controller.probe.remove_in_progress(&connection_id) else {
+            return false;
+        };
+
+        debug!(peer = %peer_id, %connection_id, %addr, "Address of peer is unreachable, not advertising it");
+
+        self.on_probe_done(swarm);
+
+        true
+    }
+
+    /// Whether the connection was established by a probe.
+    pub(crate) fn is_probe_connection(&self, connection_id: &ConnectionId) -> bool {
+        self.controller.probe.is_done_on(connection_id)
+    }
+
+    /// Whether the connection is being established by a probe.
+    pub fn is_probe_in_progress(&self, connection_id: &ConnectionId) -> bool {
+        self.controller.probe.is_in_progress(connection_id)
+    }
+}
+
+/// Whether the address has a globally routable IP address,
+/// ie. not on a loopback, private or link-local network, nor a DNS name.
+pub(crate) fn is_global_addr(addr: &Multiaddr) -> bool {
+    Subnet::from_addr(addr).is_some()
+}
+
+/// The address without the trailing peer ID, as found in the listen addresses of peers.
+fn without_peer_id(addr: &Multiaddr) -> Multiaddr {
+    let mut addr = addr.clone();
+
+    if let Some(Protocol::P2p(_)) = addr.iter().last() {
+        addr.pop();
+    }
+
+    addr
+}
@@ -1,4 +1,5 @@
 use std::collections::{HashMap, HashSet};
+use std::time::Duration;
 
 use tracing::{debug, error, info, warn};
 
@@ -8,6 +9,11 @@ use libp2p::{identify, kad, request_response, swarm::ConnectionId, Multiaddr, Pe
 
 mod util;
 
+pub mod address_book;
+use address_book::AddressBook;
+
+pub mod file;
+
 mod behaviour;
 pub use behaviour::*;
 
@@ -21,12 +27,14 @@ mod controller;
 use controller::Controller;
 
 mod handlers;
+pub use handlers::selection;
 use handlers::selection::selector::Selector;
 
 mod metrics;
 use metrics::Metrics;
 
 mod request;
+use request::DeferredPeersRequest;
 
 #[derive(Debug, PartialEq)]
 enum State {
@@ -52,7 +60,13 @@ where
     selector: Box<dyn Selector<C>>,
 
     bootstrap_nodes: Vec<(Option<PeerId>, Vec<Multiaddr>)>,
+    private_peers: HashSet<PeerId>,
+    address_book: AddressBook,
     discovered_peers: HashMap<PeerId, identify::Info>,
+    verified_addrs: HashMap<PeerId, HashSet<Multiaddr>>,
+    probed_addrs: HashMap<PeerId, HashSet<Multiaddr>>,
+    local_peers: HashSet<PeerId>,
+    deferred_peers_requests: Vec<DeferredPeersRequest>,
     active_connections: HashMap<PeerId, Vec<ConnectionId>>,
     outbound_peers: HashMap<PeerId, OutboundState>,
     inbound_peers: HashSet<PeerId>,
@@ -65,7 +79,13 @@ impl<C> Discovery<C>
 where
     C: DiscoveryClient,
 {
-    pub fn new(config: Config, bootstrap_nodes: Vec<Multiaddr>, registry: &mut Registry) -> Self {
+    pub fn new(
+        config: Config,
+        bootstrap_nodes: Vec<Multiaddr>,
+        private_peers: HashSet<PeerId>,
+        address_book: AddressBook,
+        registry: &mut Registry,
+    ) -> Self {
         info!(
             "Discovery is {}",
             if config.enabled {
@@ -75,8 +95,10 @@ where
             }
         );
 
-        let state = if config.enabled && bootstrap_nodes.is_empty() {
-            warn!("No bootstrap nodes provided");
+        let no_known_peers = bootstrap_nodes.is_empty() && address_book.is_empty();
+
+        let state = if config.enabled && no_known_peers {
+            warn!("No bootstrap nodes provided and no peers in the address book");
             info!("Discovery found 0 peers in 0ms");
             State::Idle
         } else if config.enabled {
@@ -92,6 +114,12 @@ where
 
                     State::Extending(config.num_outbound_peers)
                 }
+
+                config::BootstrapProtocol::Pex => {
+                    debug!("Using peer exchange bootstrap");
+
+                    State::Extending(config.num_outbound_peers)
+                }
             }
         } else {
             State::Idle
@@ -101,24 +129,26 @@ where
             config,
             state,
 
-            selector: Discovery::get_selector(
-                config.enabled,
-                config.bootstrap_protocol,
-                config.selector,
-            ),
+            selector: Discovery::get_selector(&config),
 
             bootstrap_nodes: bootstrap_nodes
                 .clone()
                 .into_iter()
                 .map(|addr| (None, vec![addr]))
                 .collect(),
+            private_peers,
+            address_book,
             discovered_peers: HashMap::new(),
+            verified_addrs: HashMap::new(),
+            probed_addrs: HashMap::new(),
+            local_peers: HashSet::new(),
+            deferred_peers_requests: Vec::new(),
             active_connections: HashMap::new(),
             outbound_peers: HashMap::new(),
             inbound_peers: HashSet::new(),
 
             controller: Controller::new(),
-            metrics: Metrics::new(registry, !config.enabled || bootstrap_nodes.is_empty()),
+            metrics: Metrics::new(registry, !config.enabled || no_known_peers),
         }
     }
 
@@ -126,6 +156,17 @@ where
         self.config.enabled
     }
 
+    /// Whether the given peer must never be advertised to other peers,
+    /// eg. because it is a validator hidden behind this sentry node.
+    pub fn is_private_peer(&self, peer_id: &PeerId) -> bool {
+        self.private_peers.contains(peer_id)
+    }
+
+    /// Record a round-trip time measurement to a peer, made by the `ping` protocol.
+    pub fn on_rtt(&mut self, peer_id: PeerId, rtt: Duration) {
+        self.selector.on_rtt(peer_id, rtt);
+    }
+
     pub fn on_network_event(
         &mut self,
         swarm: &mut Swarm<C>,
@@ -156,6 +197,14 @@ where
                 _ => {}
             },
 
+            behaviour::NetworkEvent::Kademlia(kad::Event::RoutingUpdated { peer, .. })
+                if self.is_private_peer(&peer) =>
+            {
+                debug!(%peer, "Removing private peer from the Kademlia routing table");
+
+                swarm.behaviour_mut().remove_peer(&peer);
+            }
+
             behaviour::NetworkEvent::Kademlia(_) => {}
 
             behaviour::NetworkEvent::RequestResponse(event) => {
@@ -179,6 +228,12 @@ where
 
                             self.handle_connect_request(swarm, channel, peer);
                         }
+
+                        behaviour::Request::Pex(max_records) => {
+                            debug!(peer_id = %peer, %connection_id, max_records, "Received peer exchange request");
+
+                            self.handle_pex_request(swarm, peer, channel, max_records);
+                        }
                     },
 
                     request_response::Event::Message {
@@ -202,6 +257,12 @@ where
 
                             self.handle_connect_response(swarm, request_id, peer, accepted);
                         }
+
+                        behaviour::Response::Pex(records) => {
+                            debug!(%peer, %connection_id, count = records.len(), "Received peer exchange response");
+
+                            self.handle_pex_response(swarm, request_id, peer, records);
+                        }
                     },
 
                     request_response::Event::OutboundFailure {
@@ -1,4 +1,9 @@
-use libp2p::PeerId;
+use std::collections::HashSet;
+
+use libp2p::request_response::ResponseChannel;
+use libp2p::{Multiaddr, PeerId};
+
+use crate::behaviour::Response;
 
 use crate::util::Retry;
 
@@ -20,3 +25,11 @@ impl RequestData {
         self.peer_id
     }
 }
+
+/// A peers request which is answered once the pending address probes are done.
+#[derive(Debug)]
+pub struct DeferredPeersRequest {
+    pub peer: PeerId,
+    pub channel: ResponseChannel<Response>,
+    pub peers: HashSet<(Option<PeerId>, Vec<Multiaddr>)>,
+}
@@ -1,5 +1,6 @@
 use core::fmt;
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
+use std::num::NonZeroU64;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -17,8 +18,9 @@ use malachitebft_core_consensus::{
     Effect, LivenessMsg, PeerId, Resumable, Resume, SignedConsensusMsg, VoteExtensionError,
 };
 use malachitebft_core_types::{
-    Context, Height, Proposal, Round, Timeout, TimeoutKind, ValidatorSet, Validity, Value, ValueId,
-    ValueOrigin, ValueResponse as CoreValueResponse, Vote,
+    Context, Evidence, Height, PolkaCertificate, Proposal, Round, SigningScheme, Timeout,
+    TimeoutKind, ValidatorSet, ValidatorSetUpdate, ValidatorSetUpdateError, Validity, Value,
+    ValueId, ValueOrigin, ValueResponse as CoreValueResponse, Vote,
 };
 use malachitebft_metrics::Metrics;
 use malachitebft_signing::{SigningProvider, SigningProviderExt};
@@ -26,8 +28,13 @@ use malachitebft_sync::{
     self as sync, HeightStartType, OutboundRequestId, Response, ValueResponse,
 };
 
-use crate::host::{HostMsg, HostRef, LocallyProposedValue, Next, ProposedValue};
-use crate::network::{NetworkEvent, NetworkMsg, NetworkRef};
+use crate::evidence::{
+    misbehavior_of_invalid_evidence, EvidenceOrigin, EvidencePoolRef, Msg as EvidencePoolMsg,
+};
+use crate::host::{
+    HostMsg, HostRef, LocallyProposedValue, MisbehaviorEvidence, Next, ProposedValue,
+};
+use crate::network::{Handshake, Misbehavior, NetworkEvent, NetworkMsg, NetworkRef};
 use crate::sync::Msg as SyncMsg;
 use crate::sync::SyncRef;
 use crate::util::events::{Event, TxEvent};
@@ -43,6 +50,9 @@ pub use malachitebft_core_consensus::State as ConsensusState;
 pub mod state_dump;
 use state_dump::StateDump;
 
+pub mod validator_mesh;
+use validator_mesh::ValidatorPeers;
+
 /// Codec for consensus messages.
 ///
 /// This trait is automatically implemented for any type that implements:
@@ -50,6 +60,7 @@ use state_dump::StateDump;
 /// - [`codec::Codec<SignedConsensusMsg<Ctx>>`]
 /// - [`codec::Codec<PolkaCertificate<Ctx>>`]
 /// - [`codec::Codec<StreamMessage<Ctx::ProposalPart>>`]
+/// - [`codec::Codec<Evidence<Ctx>>`]
 pub trait ConsensusCodec<Ctx>
 where
     Ctx: Context,
@@ -57,6 +68,7 @@ where
     Self: codec::Codec<SignedConsensusMsg<Ctx>>,
     Self: codec::Codec<LivenessMsg<Ctx>>,
     Self: codec::Codec<StreamMessage<Ctx::ProposalPart>>,
+    Self: codec::Codec<Evidence<Ctx>>,
 {
 }
 
@@ -67,6 +79,7 @@ where
     Self: codec::Codec<SignedConsensusMsg<Ctx>>,
     Self: codec::Codec<LivenessMsg<Ctx>>,
     Self: codec::Codec<StreamMessage<Ctx::ProposalPart>>,
+    Self: codec::Codec<Evidence<Ctx>>,
 {
 }
 
@@ -83,6 +96,7 @@ where
     network: NetworkRef<Ctx>,
     host: HostRef<Ctx>,
     wal: WalRef<Ctx>,
+    evidence_pool: EvidencePoolRef<Ctx>,
     sync: Option<SyncRef<Ctx>>,
     metrics: Metrics,
     tx_event: TxEvent<Ctx>,
@@ -96,6 +110,16 @@ pub enum Msg<Ctx: Context> {
     /// Start consensus for the given height with the given validator set
     StartHeight(Ctx::Height, Ctx::ValidatorSet),
 
+    /// Schedule the given update to the validator set of the height that was just decided,
+    /// and start consensus for the given height with the validator set in effect at that height
+    ///
+    /// Whether the update was accepted is sent back on the given reply port.
+    StartHeightWithUpdate(
+        Ctx::Height,
+        ValidatorSetUpdate<Ctx>,
+        RpcReplyPort<Result<(), ValidatorSetUpdateError<Ctx>>>,
+    ),
+
     /// Received an event from the gossip layer
     NetworkEvent(NetworkEvent<Ctx>),
 
@@ -130,6 +154,9 @@ impl<Ctx: Context> fmt::Display for Msg<Ctx> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Msg::StartHeight(height, _) => write!(f, "StartHeight(height={height})"),
+            Msg::StartHeightWithUpdate(height, _, _) => {
+                write!(f, "StartHeightWithUpdate(height={height})")
+            }
             Msg::NetworkEvent(event) => match event {
                 NetworkEvent::Proposal(_, proposal) => write!(
                     f,
@@ -146,6 +173,12 @@ impl<Ctx: Context> fmt::Display for Msg<Ctx> {
                     vote.height(),
                     vote.round()
                 ),
+                NetworkEvent::Evidence(_, evidence) => write!(
+                    f,
+                    "NetworkEvent(Evidence height={} address={})",
+                    evidence.height(),
+                    evidence.validator_address()
+                ),
                 _ => write!(f, "NetworkEvent"),
             },
             Msg::TimeoutElapsed(timeout) => write!(f, "TimeoutElapsed({})", timeout.display_key()),
@@ -237,6 +270,15 @@ enum Phase {
 /// in the `Unstarted` or `Recovering` phase
 const MAX_BUFFER_SIZE: usize = 1024;
 
+/// What is needed to verify evidence of misbehavior for a height that was already decided.
+struct RecentHeight<Ctx: Context> {
+    /// The validator set of that height
+    validator_set: Ctx::ValidatorSet,
+
+    /// The polka certificates observed at that height
+    polka_certificates: Vec<PolkaCertificate<Ctx>>,
+}
+
 pub struct State<Ctx: Context> {
     /// Scheduler for timers
     timers: Timers,
@@ -256,6 +298,21 @@ pub struct State<Ctx: Context> {
     /// A buffer of messages that were received while
     /// consensus was `Unstarted` or in the `Recovering` phase
     msg_buffer: MessageBuffer<Ctx>,
+
+    /// The validator set of the current height
+    validator_set: Ctx::ValidatorSet,
+
+    /// Validator sets resulting from updates which have not taken effect yet,
+    /// indexed by the height at which they take effect
+    pending_validator_sets: BTreeMap<Ctx::Height, Ctx::ValidatorSet>,
+
+    /// The heights this node took part in, within the maximum evidence age
+    /// of the current height, against which evidence for those heights is verified
+    recent_heights: BTreeMap<Ctx::Height, RecentHeight<Ctx>>,
+
+    /// The peers which proved that they belong to a validator of the current validator set,
+    /// when the validator-only gossip mesh is enabled
+    validator_peers: ValidatorPeers<Ctx>,
 }
 
 impl<Ctx> State<Ctx>
@@ -266,6 +323,79 @@ where
         self.consensus.height()
     }
 
+    /// Schedule the given validator set update, decided at the current height,
+    /// to take effect `delay` heights later.
+    ///
+    /// The update applies to the validator set in effect at the height right before that,
+    /// taking into account the updates that are already scheduled.
+    fn schedule_validator_set_update(
+        &mut self,
+        update: ValidatorSetUpdate<Ctx>,
+        delay: NonZeroU64,
+    ) -> Result<(), ValidatorSetUpdateError<Ctx>> {
+        if update.is_empty() {
+            return Ok(());
+        }
+
+        let decided_height = self.height();
+        let effective_height = decided_height.increment_by(delay.get());
+
+        let validator_set = self
+            .pending_validator_sets
+            .range(..effective_height)
+            .next_back()
+            .map(|(_, validator_set)| validator_set)
+            .unwrap_or_else(|| self.consensus.validator_set());
+
+        let validator_set = update.apply(validator_set)?;
+
+        info!(
+            %decided_height, %effective_height,
+            upserts = update.upserts.len(), removals = update.removals.len(),
+            "Scheduled validator set update"
+        );
+
+        self.pending_validator_sets
+            .insert(effective_height, validator_set);
+
+        Ok(())
+    }
+
+    /// The validator set in effect at the given height, taking into account
+    /// the validator set updates which have taken effect by then.
+    ///
+    /// Scheduled updates which have taken effect are removed from the queue.
+    fn take_validator_set(&mut self, height: Ctx::Height) -> Ctx::ValidatorSet {
+        let later = self.pending_validator_sets.split_off(&height.increment());
+        let taken = std::mem::replace(&mut self.pending_validator_sets, later);
+
+        taken
+            .into_values()
+            .next_back()
+            .unwrap_or_else(|| self.consensus.validator_set().clone())
+    }
+
+    /// Remember the validator set and polka certificates of the current height before moving
+    /// on to the given height, and forget about the heights beyond the maximum evidence age.
+    fn record_recent_height(&mut self, next_height: Ctx::Height, max_evidence_age: u64) {
+        let height = self.height();
+
+        // Only keep track of heights which were actually running
+        if self.phase == Phase::Running && height < next_height {
+            self.recent_heights.insert(
+                height,
+                RecentHeight {
+                    validator_set: self.consensus.validator_set().clone(),
+                    polka_certificates: self.consensus.driver.polka_certificates().to_vec(),
+                },
+            );
+        }
+
+        if let Some(min_height) = next_height.decrement_by(max_evidence_age) {
+            self.recent_heights = self.recent_heights.split_off(&min_height);
+        }
+    }
+
     fn set_phase(&mut self, phase: Phase) {
         if self.phase != phase {
             info!(prev = ?self.phase, new = ?phase, "Phase transition");
@@ -274,10 +404,14 @@ where
     }
 }
 
-struct HandlerState<'a> {
+struct HandlerState<'a, Ctx: Context> {
     phase: Phase,
     timers: &'a mut Timers,
     timeouts: &'a mut Timeouts,
+    /// The validator set of the current height
+    validator_set: &'a Ctx::ValidatorSet,
+    /// The peer the input being processed was received from, if any
+    source: Option<PeerId>,
 }
 
 impl<Ctx> Consensus<Ctx>
@@ -293,6 +427,7 @@ where
         network: NetworkRef<Ctx>,
         host: HostRef<Ctx>,
         wal: WalRef<Ctx>,
+        evidence_pool: EvidencePoolRef<Ctx>,
         sync: Option<SyncRef<Ctx>>,
         metrics: Metrics,
         tx_event: TxEvent<Ctx>,
@@ -306,6 +441,7 @@ where
             network,
             host,
             wal,
+            evidence_pool,
             sync,
             metrics,
             tx_event,
@@ -321,6 +457,18 @@ where
         myself: &ActorRef<Msg<Ctx>>,
         state: &mut State<Ctx>,
         input: ConsensusInput<Ctx>,
+    ) -> Result<(), ConsensusError<Ctx>> {
+        self.process_input_from(myself, state, input, None).await
+    }
+
+    /// Process an input received from the given peer, so that the peer
+    /// can be reported if the input turns out to carry an invalid signature.
+    async fn process_input_from(
+        &self,
+        myself: &ActorRef<Msg<Ctx>>,
+        state: &mut State<Ctx>,
+        input: ConsensusInput<Ctx>,
+        source: Option<PeerId>,
     ) -> Result<(), ConsensusError<Ctx>> {
         malachitebft_core_consensus::process!(
             input: input,
@@ -331,6 +479,8 @@ where
                     phase: state.phase,
                     timers: &mut state.timers,
                     timeouts: &mut state.timeouts,
+                    validator_set: &state.validator_set,
+                    source,
                 };
 
                 self.handle_effect(myself, handler_state, effect).await
@@ -364,70 +514,132 @@ where
         }
     }
 
-    async fn handle_msg(
+    async fn start_height(
         &self,
-        myself: ActorRef<Msg<Ctx>>,
+        myself: &ActorRef<Msg<Ctx>>,
         state: &mut State<Ctx>,
-        msg: Msg<Ctx>,
+        height: Ctx::Height,
+        validator_set: Ctx::ValidatorSet,
+        is_restart: bool,
     ) -> Result<(), ActorProcessingErr> {
-        let is_restart = matches!(msg, Msg::RestartHeight(_, _));
+        // Check that the validator set is not empty
+        if validator_set.count() == 0 {
+            return Err(eyre!("Validator set for height {height} is empty").into());
+        }
 
-        match msg {
-            Msg::StartHeight(height, validator_set) | Msg::RestartHeight(height, validator_set) => {
-                // Check that the validator set is not empty
-                if validator_set.count() == 0 {
-                    return Err(eyre!("Validator set for height {height} is empty").into());
-                }
+        state.record_recent_height(height, self.consensus_config.max_evidence_age);
 
-                self.tx_event
-                    .send(|| Event::StartedHeight(height, is_restart));
+        if let Some(min_height) = height.decrement_by(self.consensus_config.max_evidence_age) {
+            self.evidence_pool
+                .cast(EvidencePoolMsg::Prune(min_height))?;
+        }
 
-                // Fetch entries from the WAL or reset the WAL if this is a restart
-                let wal_entries = if is_restart {
-                    self.wal_reset(height).await?;
-                    vec![]
-                } else {
-                    self.wal_fetch(height).await?
-                };
+        self.tx_event
+            .send(|| Event::StartedHeight(height, is_restart));
+
+        // Fetch entries from the WAL or reset the WAL if this is a restart
+        let wal_entries = if is_restart {
+            self.wal_reset(height).await?;
+            vec![]
+        } else {
+            self.wal_fetch(height).await?
+        };
 
-                if !wal_entries.is_empty() {
-                    // Set the phase to `Recovering` while we replay the WAL
-                    state.set_phase(Phase::Recovering);
-                }
+        if !wal_entries.is_empty() {
+            // Set the phase to `Recovering` while we replay the WAL
+            state.set_phase(Phase::Recovering);
+        } else if !state.pending_validator_sets.is_empty() {
+            // Record the updates which have not taken effect yet,
+            // so that they are not lost if the node restarts before they do
+            let pending = state
+                .pending_validator_sets
+                .iter()
+                .map(|(height, validator_set)| (*height, validator_set.clone()))
+                .collect();
+
+            self.wal_append(height, WalEntry::PendingValidatorSets(pending), state.phase)
+                .await?;
+            self.wal_flush(state.phase).await?;
+        }
 
-                // Start consensus for the given height
-                let result = self
-                    .process_input(
-                        &myself,
-                        state,
-                        ConsensusInput::StartHeight(height, validator_set, is_restart),
-                    )
-                    .await;
+        // Start consensus for the given height
+        let result = self
+            .process_input(
+                myself,
+                state,
+                ConsensusInput::StartHeight(height, validator_set, is_restart),
+            )
+            .await;
+
+        if let Err(e) = result {
+            error!(%height, "Error when starting height: {e}");
+        }
 
-                if let Err(e) = result {
-                    error!(%height, "Error when starting height: {e}");
-                }
+        state.validator_set = state.consensus.validator_set().clone();
 
-                // Notify the sync actor that we have started a new height
-                if let Some(sync) = &self.sync {
-                    let start_type = HeightStartType::from_is_restart(is_restart);
+        // Check which peers belong to the validators of this height
+        if self.consensus_config.p2p.validator_mesh.enabled {
+            let validator_set = state.validator_set.clone();
+            self.update_validator_peers(state, &validator_set).await?;
+        }
 
-                    if let Err(e) = sync.cast(SyncMsg::StartedHeight(height, start_type)) {
-                        error!(%height, "Error when notifying sync of started height: {e}")
-                    }
+        // Notify the sync actor that we have started a new height
+        if let Some(sync) = &self.sync {
+            let start_type = HeightStartType::from_is_restart(is_restart);
+
+            if let Err(e) = sync.cast(SyncMsg::StartedHeight(height, start_type)) {
+                error!(%height, "Error when notifying sync of started height: {e}")
+            }
+        }
+
+        if !wal_entries.is_empty() {
+            self.wal_replay(myself, state, height, wal_entries).await;
+        }
+
+        // Set the phase to `Running` now that we have replayed the WAL
+        state.set_phase(Phase::Running);
+
+        // Process any buffered messages, now that we are in the `Running` phase
+        self.process_buffered_msgs(myself, state, is_restart).await;
+
+        Ok(())
+    }
+
+    async fn handle_msg(
+        &self,
+        myself: ActorRef<Msg<Ctx>>,
+        state: &mut State<Ctx>,
+        msg: Msg<Ctx>,
+    ) -> Result<(), ActorProcessingErr> {
+        let is_restart = matches!(msg, Msg::RestartHeight(_, _));
+
+        match msg {
+            Msg::StartHeightWithUpdate(height, update, reply_to) => {
+                let delay = self.consensus_config.validator_set_update_delay;
+                let result = state.schedule_validator_set_update(update, delay);
+
+                if let Err(e) = &result {
+                    error!(decided_height = %state.height(), "Rejected validator set update: {e}");
                 }
 
-                if !wal_entries.is_empty() {
-                    self.wal_replay(&myself, state, height, wal_entries).await;
+                if reply_to.send(result).is_err() {
+                    warn!("Failed to reply to validator set update, host is not listening");
                 }
 
-                // Set the phase to `Running` now that we have replayed the WAL
-                state.set_phase(Phase::Running);
+                let validator_set = state.take_validator_set(height);
 
-                // Process any buffered messages, now that we are in the `Running` phase
-                self.process_buffered_msgs(&myself, state, is_restart).await;
+                self.start_height(&myself, state, height, validator_set, false)
+                    .await
+            }
 
-                Ok(())
+            Msg::StartHeight(height, validator_set) | Msg::RestartHeight(height, validator_set) => {
+                // The application provided the validator set for this height, which supersedes
+                // the updates scheduled up to this height, but not the ones taking effect later
+                state.pending_validator_sets =
+                    state.pending_validator_sets.split_off(&height.increment());
+
+                self.start_height(&myself, state, height, validator_set, is_restart)
+                    .await
             }
 
             Msg::ProposeValue(value) => {
@@ -489,6 +701,12 @@ where
                         if state.connected_peers.remove(&peer_id) {
                             self.metrics.connected_peers.dec();
                         }
+
+                        if state.validator_peers.on_disconnection(&peer_id) {
+                            self.network.cast(NetworkMsg::SetValidatorPeers(
+                                state.validator_peers.peers(),
+                            ))?;
+                        }
                     }
 
                     NetworkEvent::Vote(from, vote) => {
@@ -496,7 +714,12 @@ where
                             .send(|| Event::Received(SignedConsensusMsg::Vote(vote.clone())));
 
                         if let Err(e) = self
-                            .process_input(&myself, state, ConsensusInput::Vote(vote))
+                            .process_input_from(
+                                &myself,
+                                state,
+                                ConsensusInput::Vote(vote),
+                                Some(from),
+                            )
                             .await
                         {
                             error!(%from, "Error when processing vote: {e}");
@@ -514,7 +737,12 @@ where
                         }
 
                         if let Err(e) = self
-                            .process_input(&myself, state, ConsensusInput::Proposal(proposal))
+                            .process_input_from(
+                                &myself,
+                                state,
+                                ConsensusInput::Proposal(proposal),
+                                Some(from),
+                            )
                             .await
                         {
                             error!(%from, "Error when processing proposal: {e}");
@@ -571,6 +799,15 @@ where
                             })?;
                     }
 
+                    NetworkEvent::Evidence(from, evidence) => {
+                        self.received_evidence(state, from, evidence).await?;
+                    }
+
+                    NetworkEvent::ValidatorHandshake(from, handshake) => {
+                        self.received_validator_handshake(state, from, handshake)
+                            .await?;
+                    }
+
                     _ => {}
                 }
 
@@ -703,6 +940,219 @@ where
         Ok(())
     }
 
+    /// Sign our own peer ID with our consensus key, and have the network send it to every peer
+    /// as proof that our node belongs to a validator.
+    ///
+    /// If the signing provider cannot sign peer identities, the node still takes part in
+    /// consensus, but the other validators will not let it into the gossip mesh.
+    async fn send_validator_handshake(&self) -> Result<(), ActorProcessingErr> {
+        let peer_id = ractor::call!(self.network, |reply| NetworkMsg::GetLocalPeerId { reply })?;
+
+        let signature = match self
+            .signing_provider
+            .sign_peer_identity(&peer_id.to_bytes())
+            .await
+        {
+            Ok(signature) => signature,
+            Err(e) => {
+                error!("Failed to sign validator handshake, other validators will not gossip with us: {e}");
+                return Ok(());
+            }
+        };
+
+        let handshake = Handshake {
+            validator: self.params.address.to_string(),
+            proof: Ctx::SigningScheme::encode_signature(&signature),
+        };
+
+        self.network
+            .cast(NetworkMsg::SetValidatorHandshake(handshake))?;
+
+        Ok(())
+    }
+
+    /// Check whether the handshake received from a peer was made by a validator of the
+    /// current validator set, and if so, let the network gossip with that peer.
+    async fn received_validator_handshake(
+        &self,
+        state: &mut State<Ctx>,
+        from: PeerId,
+        handshake: Handshake,
+    ) -> Result<(), ActorProcessingErr> {
+        let signature = match Ctx::SigningScheme::decode_signature(&handshake.proof) {
+            Ok(signature) => signature,
+            Err(e) => {
+                warn!(%from, "Failed to decode validator handshake: {e}");
+
+                self.network
+                    .cast(NetworkMsg::ReportPeer(from, Misbehavior::InvalidMessage))?;
+
+                return Ok(());
+            }
+        };
+
+        let result = state
+            .validator_peers
+            .on_handshake(
+                self.signing_provider.as_ref(),
+                from,
+                handshake.validator,
+                signature,
+            )
+            .await;
+
+        let changed = match result {
+            Ok(changed) => changed,
+            Err(_) => {
+                warn!(%from, "Received validator handshake with an invalid signature");
+
+                self.network
+                    .cast(NetworkMsg::ReportPeer(from, Misbehavior::InvalidSignature))?;
+
+                // The peer may have belonged to a validator until now
+                true
+            }
+        };
+
+        if changed {
+            self.network
+                .cast(NetworkMsg::SetValidatorPeers(state.validator_peers.peers()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Verify the handshakes of the connected peers against the given validator set.
+    async fn update_validator_peers(
+        &self,
+        state: &mut State<Ctx>,
+        validator_set: &Ctx::ValidatorSet,
+    ) -> Result<(), ActorProcessingErr> {
+        let changed = state
+            .validator_peers
+            .update_validator_set(self.signing_provider.as_ref(), validator_set)
+            .await;
+
+        if changed {
+            self.network
+                .cast(NetworkMsg::SetValidatorPeers(state.validator_peers.peers()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Verify evidence of misbehavior received from a peer and add it to the evidence pool,
+    /// so that it can be reported to the application on the next decision.
+    ///
+    /// Evidence is verified against the validator set of its height, and evidence of a lock
+    /// violation against the polka certificates observed at that height. Evidence for a height
+    /// this node did not take part in, eg. before a restart, or for a height beyond the maximum
+    /// evidence age cannot be verified and is ignored.
+    async fn received_evidence(
+        &self,
+        state: &mut State<Ctx>,
+        from: PeerId,
+        evidence: Evidence<Ctx>,
+    ) -> Result<(), ActorProcessingErr> {
+        let height = state.consensus.height();
+
+        if evidence.height() > height {
+            debug!(
+                %from, %height, evidence.height = %evidence.height(),
+                "Ignoring evidence for a future height"
+            );
+            return Ok(());
+        }
+
+        let (validator_set, polka_certificates) = if evidence.height() == height {
+            (
+                state.consensus.validator_set(),
+                state.consensus.driver.polka_certificates(),
+            )
+        } else if let Some(recent) = state.recent_heights.get(&evidence.height()) {
+            (&recent.validator_set, recent.polka_certificates.as_slice())
+        } else {
+            debug!(
+                %from, %height, evidence.height = %evidence.height(),
+                "Ignoring evidence for a height which cannot be verified"
+            );
+            return Ok(());
+        };
+
+        if ractor::call!(
+            self.evidence_pool,
+            EvidencePoolMsg::Contains,
+            evidence.clone()
+        )? {
+            debug!(%from, address = %evidence.validator_address(), "Evidence already known, ignoring");
+            return Ok(());
+        }
+
+        let result = self
+            .signing_provider
+            .verify_evidence(&evidence, polka_certificates, validator_set)
+            .await;
+
+        if let Err(e) = result {
+            match misbehavior_of_invalid_evidence(&e) {
+                Some(misbehavior) => {
+                    warn!(%from, address = %evidence.validator_address(), "Received invalid evidence: {e}");
+
+                    self.network
+                        .cast(NetworkMsg::ReportPeer(from, misbehavior))?;
+                }
+                None => {
+                    debug!(%from, address = %evidence.validator_address(), "Dropping evidence which cannot be verified: {e}");
+                }
+            }
+
+            return Ok(());
+        }
+
+        let address = evidence.validator_address().clone();
+        let evidence_height = evidence.height();
+
+        if ractor::call!(
+            self.evidence_pool,
+            EvidencePoolMsg::Insert,
+            evidence,
+            EvidenceOrigin::Peer
+        )? {
+            info!(%from, %address, height = %evidence_height, "Received evidence of misbehavior");
+        }
+
+        Ok(())
+    }
+
+    /// Add the evidence detected locally to the evidence pool and publish the evidence
+    /// that was not known yet to our peers, then merge in the evidence received from peers
+    /// which has not been reported to the application yet.
+    async fn gather_evidence(
+        &self,
+        mut evidence: MisbehaviorEvidence<Ctx>,
+    ) -> Result<MisbehaviorEvidence<Ctx>, ActorProcessingErr> {
+        for item in evidence.iter() {
+            let is_new = ractor::call!(
+                self.evidence_pool,
+                EvidencePoolMsg::Insert,
+                item.clone(),
+                EvidenceOrigin::Local
+            )?;
+
+            if is_new {
+                self.network.cast(NetworkMsg::PublishEvidence(item))?;
+            }
+        }
+
+        let pending = ractor::call!(self.evidence_pool, EvidencePoolMsg::TakePending)?;
+
+        for item in pending {
+            evidence.add(item);
+        }
+
+        Ok(evidence)
+    }
+
     async fn wal_reset(&self, height: Ctx::Height) -> Result<(), ActorProcessingErr> {
         let result = ractor::call!(self.wal, WalMsg::Reset, height);
 
@@ -811,6 +1261,19 @@ where
                     }
                 }
 
+                WalEntry::PendingValidatorSets(validator_sets) => {
+                    info!(
+                        count = validator_sets.len(),
+                        "Replaying pending validator sets"
+                    );
+
+                    state.pending_validator_sets.extend(
+                        validator_sets
+                            .into_iter()
+                            .filter(|(effective_height, _)| *effective_height > height),
+                    );
+                }
+
                 WalEntry::ProposedValue(value) => {
                     info!("Replaying proposed value: {value:?}");
 
@@ -942,7 +1405,7 @@ where
     async fn handle_effect(
         &self,
         myself: &ActorRef<Msg<Ctx>>,
-        state: HandlerState<'_>,
+        state: HandlerState<'_, Ctx>,
         effect: Effect<Ctx>,
     ) -> Result<Resume<Ctx>, ActorProcessingErr> {
         match effect {
@@ -1036,6 +1499,15 @@ where
                     .signature_verification_time
                     .observe(start.elapsed().as_secs_f64());
 
+                if result.is_invalid() {
+                    if let Some(peer_id) = state.source {
+                        self.network.cast(NetworkMsg::ReportPeer(
+                            peer_id,
+                            Misbehavior::InvalidSignature,
+                        ))?;
+                    }
+                }
+
                 Ok(r.resume_with(result.is_valid()))
             }
 
@@ -1193,8 +1665,8 @@ where
                 Ok(r.resume_with(()))
             }
 
-            Effect::Decide(certificate, extensions, r) => {
-                assert!(!certificate.commit_signatures.is_empty());
+            Effect::Decide(certificate, extensions, evidence, r) => {
+                assert!(certificate.signature_count() > 0);
 
                 self.wal_flush(state.phase).await?;
 
@@ -1202,16 +1674,46 @@ where
 
                 let height = certificate.height;
 
+                // Failing to aggregate the certificate must not prevent the decision from reaching the host
+                let certificate = if self.consensus_config.aggregate_commit_certificates {
+                    match self
+                        .signing_provider
+                        .aggregate_commit_certificate(&certificate, state.validator_set)
+                        .await
+                    {
+                        Ok(aggregated) => aggregated,
+                        Err(e) => {
+                            warn!(%height, "Error when aggregating commit certificate: {e}");
+                            certificate
+                        }
+                    }
+                } else {
+                    certificate
+                };
+
+                // Failing to gather evidence must not prevent the decision from reaching the host
+                let evidence = match self.gather_evidence(evidence.clone()).await {
+                    Ok(evidence) => evidence,
+                    Err(e) => {
+                        error!(%height, "Error when gathering evidence of misbehavior: {e}");
+                        evidence
+                    }
+                };
+
                 self.host
                     .call_and_forward(
                         |reply_to| HostMsg::Decided {
                             certificate,
                             extensions,
+                            evidence,
                             reply_to,
                         },
                         myself,
                         |next| match next {
                             Next::Start(h, vs) => Msg::StartHeight(h, vs),
+                            Next::StartWithUpdate(h, update, reply_to) => {
+                                Msg::StartHeightWithUpdate(h, update, reply_to)
+                            }
                             Next::Restart(h, vs) => Msg::RestartHeight(h, vs),
                         },
                         None,
@@ -1330,6 +1832,10 @@ where
             connected_peers: BTreeSet::new(),
             phase: Phase::Unstarted,
             msg_buffer: MessageBuffer::new(MAX_BUFFER_SIZE),
+            validator_set: self.params.initial_validator_set.clone(),
+            pending_validator_sets: BTreeMap::new(),
+            recent_heights: BTreeMap::new(),
+            validator_peers: ValidatorPeers::default(),
         })
     }
 
@@ -1360,6 +1866,10 @@ where
             .map_err(|e| eyre!("Failed to set consensus actor: {e:?}"))?;
         }
 
+        if self.consensus_config.p2p.validator_mesh.enabled {
+            self.send_validator_handshake().await?;
+        }
+
         Ok(())
     }
 
@@ -1415,6 +1925,7 @@ fn should_buffer<Ctx: Context>(msg: &Msg<Ctx>) -> bool {
     !matches!(
         msg,
         Msg::StartHeight(..)
+            | Msg::StartHeightWithUpdate(..)
             | Msg::NetworkEvent(NetworkEvent::Listening(..))
             | Msg::NetworkEvent(NetworkEvent::PeerConnected(..))
             | Msg::NetworkEvent(NetworkEvent::PeerDisconnected(..))
@@ -1424,19 +1935,17 @@ fn should_buffer<Ctx: Context>(msg: &Msg<Ctx>) -> bool {
 /// Use the height we are about to start instead of the consensus state height
 /// for the tracing span of the Consensus actor when starting a new height.
 fn span_height<Ctx: Context>(height: Ctx::Height, msg: &Msg<Ctx>) -> Ctx::Height {
-    if let Msg::StartHeight(h, _) = msg {
-        *h
-    } else {
-        height
+    match msg {
+        Msg::StartHeight(h, _) | Msg::StartHeightWithUpdate(h, _, _) => *h,
+        _ => height,
     }
 }
 
 /// Use round 0 instead of the consensus state round for the tracing span of
 /// the Consensus actor when starting a new height.
 fn span_round<Ctx: Context>(round: Round, msg: &Msg<Ctx>) -> Round {
-    if let Msg::StartHeight(_, _) = msg {
-        Round::new(0)
-    } else {
-        round
+    match msg {
+        Msg::StartHeight(..) | Msg::StartHeightWithUpdate(..) => Round::new(0),
+        _ => round,
     }
 }
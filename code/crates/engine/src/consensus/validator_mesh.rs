@@ -0,0 +1,202 @@
+//! Verification of the handshakes sent by the peers which claim to be operated by a validator,
+//! for the validator-only gossip mesh.
+//!
+//! Each handshake names the validator which signed it, by the string representation of its
+//! address, so that verifying a handshake costs a single signature verification,
+//! whatever the size of the validator set.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use derive_where::derive_where;
+use tracing::{debug, warn};
+
+use malachitebft_core_consensus::PeerId;
+use malachitebft_core_types::{Context, Signature, Validator, ValidatorSet};
+use malachitebft_signing::SigningProvider;
+
+/// The signature of a handshake does not match the validator named in it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct InvalidSignature;
+
+/// Keeps track of the handshakes received from connected peers,
+/// and of the validators of the current validator set they belong to.
+#[derive_where(Debug)]
+pub struct ValidatorPeers<Ctx: Context> {
+    /// The validators named in the handshakes of the connected peers,
+    /// along with their signatures of the IDs of these peers
+    proofs: BTreeMap<PeerId, (String, Signature<Ctx>)>,
+
+    /// Peers whose handshake was made by a validator of the current validator set,
+    /// along with the address of that validator
+    validators: BTreeMap<PeerId, Ctx::Address>,
+
+    /// The validator set against which the handshakes were last verified
+    validator_set: Option<Ctx::ValidatorSet>,
+
+    /// The addresses of the validators of that set, by their string representation
+    addresses: BTreeMap<String, Ctx::Address>,
+}
+
+impl<Ctx: Context> Default for ValidatorPeers<Ctx> {
+    fn default() -> Self {
+        Self {
+            proofs: BTreeMap::new(),
+            validators: BTreeMap::new(),
+            validator_set: None,
+            addresses: BTreeMap::new(),
+        }
+    }
+}
+
+impl<Ctx: Context> ValidatorPeers<Ctx> {
+    /// The peers which belong to a validator of the current validator set.
+    pub fn peers(&self) -> BTreeSet<PeerId> {
+        self.validators.keys().copied().collect()
+    }
+
+    /// The address of the validator the given peer belongs to, if any.
+    pub fn validator_of(&self, peer_id: &PeerId) -> Option<&Ctx::Address> {
+        self.validators.get(peer_id)
+    }
+
+    /// Record the handshake of a peer, and check whether it was made by the given validator,
+    /// named by the string representation of its address, and whether that validator
+    /// is part of the current validator set.
+    ///
+    /// Returns whether the set of validator peers changed, or an error if the handshake
+    /// was not signed by the validator named in it, in which case the handshake is discarded
+    /// and the peer no longer belongs to any validator.
+    pub async fn on_handshake(
+        &mut self,
+        signing_provider: &dyn SigningProvider<Ctx>,
+        peer_id: PeerId,
+        validator: String,
+        proof: Signature<Ctx>,
+    ) -> Result<bool, InvalidSignature> {
+        match self
+            .verify(signing_provider, &peer_id, &validator, &proof)
+            .await
+        {
+            Ok(signer) => {
+                self.proofs.insert(peer_id, (validator, proof));
+                Ok(self.set_validator(peer_id, signer))
+            }
+            Err(e) => {
+                self.on_disconnection(&peer_id);
+                Err(e)
+            }
+        }
+    }
+
+    /// Forget about a peer which is no longer connected.
+    ///
+    /// Returns whether the set of validator peers changed.
+    pub fn on_disconnection(&mut self, peer_id: &PeerId) -> bool {
+        self.proofs.remove(peer_id);
+        self.validators.remove(peer_id).is_some()
+    }
+
+    /// Verify the handshakes of all peers against a new validator set.
+    ///
+    /// Peers which belong to a validator that is still part of the set with the same public key
+    /// are not verified again, and nothing is verified if the validator set did not change.
+    ///
+    /// Returns whether the set of validator peers changed.
+    pub async fn update_validator_set(
+        &mut self,
+        signing_provider: &dyn SigningProvider<Ctx>,
+        validator_set: &Ctx::ValidatorSet,
+    ) -> bool {
+        if self.validator_set.as_ref() == Some(validator_set) {
+            return false;
+        }
+
+        let previous_set = self.validator_set.replace(validator_set.clone());
+
+        self.addresses = (0..validator_set.count())
+            .filter_map(|index| validator_set.get_by_index(index))
+            .map(|validator| (validator.address().to_string(), validator.address().clone()))
+            .collect();
+        let mut changed = false;
+
+        let peers = self.proofs.keys().copied().collect::<Vec<_>>();
+
+        for peer_id in peers {
+            let unchanged = self
+                .validators
+                .get(&peer_id)
+                .zip(previous_set.as_ref())
+                .and_then(|(address, previous_set)| {
+                    let previous = previous_set.get_by_address(address)?;
+                    let current = validator_set.get_by_address(address)?;
+                    (previous.public_key() == current.public_key()).then(|| address.clone())
+                });
+
+            let validator = match unchanged {
+                Some(address) => Some(address),
+                None => {
+                    let (validator, proof) = &self.proofs[&peer_id];
+                    self.verify(signing_provider, &peer_id, validator, proof)
+                        .await
+                        .unwrap_or_default()
+                }
+            };
+
+            changed |= self.set_validator(peer_id, validator);
+        }
+
+        changed
+    }
+
+    /// Check whether the given validator is part of the current validator set,
+    /// and whether it signed the ID of the given peer, returning its address if so.
+    async fn verify(
+        &self,
+        signing_provider: &dyn SigningProvider<Ctx>,
+        peer_id: &PeerId,
+        validator: &str,
+        proof: &Signature<Ctx>,
+    ) -> Result<Option<Ctx::Address>, InvalidSignature> {
+        let Some(validator_set) = self.validator_set.as_ref() else {
+            return Ok(None);
+        };
+
+        let Some(validator) = self
+            .addresses
+            .get(validator)
+            .and_then(|address| validator_set.get_by_address(address))
+        else {
+            debug!(%peer_id, %validator, "Peer does not belong to any validator");
+            return Ok(None);
+        };
+
+        let result = signing_provider
+            .verify_peer_identity(&peer_id.to_bytes(), proof, validator.public_key())
+            .await;
+
+        match result {
+            Ok(result) if result.is_valid() => {
+                debug!(%peer_id, address = %validator.address(), "Peer belongs to a validator");
+                Ok(Some(validator.address().clone()))
+            }
+            Ok(_) => {
+                debug!(%peer_id, address = %validator.address(), "Invalid validator handshake");
+                Err(InvalidSignature)
+            }
+            Err(e) => {
+                warn!(%peer_id, "Failed to verify validator handshake: {e}");
+                Ok(None)
+            }
+        }
+    }
+
+    fn set_validator(&mut self, peer_id: PeerId, validator: Option<Ctx::Address>) -> bool {
+        match validator {
+            Some(address) => {
+                let previous = self.validators.insert(peer_id, address.clone());
+                previous.as_ref() != Some(&address)
+            }
+            None => self.validators.remove(&peer_id).is_some(),
+        }
+    }
+}
@@ -0,0 +1,543 @@
+//! Pool of evidence of misbehavior, either detected locally or received from peers.
+//!
+//! The pool deduplicates evidence and persists it to disk, so that evidence received from peers
+//! is not lost on restart and can be reported to the application once, on the next decision.
+//!
+//! Evidence is deduplicated by misbehavior rather than by content, so that eg. the same double
+//! vote is only stored once whichever order its votes come in. The pool also keeps at most
+//! [`MAX_EVIDENCE_PER_VALIDATOR_AND_HEIGHT`] pieces of evidence for each validator and height,
+//! since a single one is enough to prove that the validator misbehaved at that height.
+//!
+//! Evidence is persisted in an append-only log where each entry is made of a tag followed by
+//! the encoded evidence. A pending entry is later followed by a reported entry for the same
+//! evidence once it has been taken out of the pool, so that the status of each piece of evidence
+//! can be recovered by replaying the log.
+//!
+//! Evidence older than the maximum evidence age is pruned from the pool as consensus moves on,
+//! at which point the log is rewritten with the remaining entries only.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, Read};
+use std::marker::PhantomData;
+use std::path::PathBuf;
+
+use bytes::Bytes;
+use derive_where::derive_where;
+use ractor::{async_trait, Actor, ActorProcessingErr, ActorRef, RpcReplyPort, SpawnErr};
+use tracing::{debug, error, info};
+
+use malachitebft_codec::Codec;
+use malachitebft_core_types::{Context, Evidence, EvidenceError, Round, VoteType};
+use malachitebft_wal as wal;
+
+use crate::network::Misbehavior;
+
+/// Maximum number of pieces of evidence kept in the pool for a given validator and height.
+pub const MAX_EVIDENCE_PER_VALIDATOR_AND_HEIGHT: usize = 4;
+
+/// Codec for encoding and decoding evidence of misbehavior.
+///
+/// This trait is automatically implemented for any type that implements:
+/// - [`Codec<Evidence<Ctx>>`]
+pub trait EvidenceCodec<Ctx>
+where
+    Ctx: Context,
+    Self: Codec<Evidence<Ctx>>,
+{
+}
+
+impl<Ctx, C> EvidenceCodec<Ctx> for C
+where
+    Ctx: Context,
+    C: Codec<Evidence<Ctx>>,
+{
+}
+
+pub type EvidencePoolRef<Ctx> = ActorRef<Msg<Ctx>>;
+
+/// The misbehavior to report a peer for when it sends evidence which fails verification
+/// with the given error, if any.
+///
+/// Only evidence with an invalid signature, or which is malformed, is the fault of its sender.
+/// Evidence of amnesia may be justified by a polka which its sender has not observed,
+/// the validator may only be unknown to us, and a verification error is a failure of our own
+/// signing provider, so such evidence is dropped without reporting anyone.
+pub fn misbehavior_of_invalid_evidence<Ctx: Context>(
+    error: &EvidenceError<Ctx>,
+) -> Option<Misbehavior> {
+    match error {
+        EvidenceError::InvalidSignature(_) => Some(Misbehavior::InvalidSignature),
+
+        EvidenceError::MismatchedValidator { .. }
+        | EvidenceError::MismatchedHeight { .. }
+        | EvidenceError::MismatchedRound { .. }
+        | EvidenceError::MismatchedVoteType { .. }
+        | EvidenceError::UnexpectedVoteType { .. }
+        | EvidenceError::NotConflicting => Some(Misbehavior::InvalidMessage),
+
+        EvidenceError::JustifiedByPolka(_)
+        | EvidenceError::UnknownValidator(_)
+        | EvidenceError::VerificationError(_) => None,
+    }
+}
+
+/// Where a piece of evidence comes from.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum EvidenceOrigin {
+    /// The evidence was detected by this node, and is reported to the application
+    /// by consensus as part of the decision for its height.
+    Local,
+
+    /// The evidence was received from a peer, and is pending until it is taken
+    /// out of the pool to be reported to the application.
+    Peer,
+}
+
+pub enum Msg<Ctx: Context> {
+    /// Check whether the pool already contains the given evidence
+    Contains(Evidence<Ctx>, RpcReplyPort<bool>),
+
+    /// Add evidence to the pool, replying with `true` if it was added, ie. if it was
+    /// not already present and the pool does not hold too much evidence for the same
+    /// validator and height yet
+    Insert(Evidence<Ctx>, EvidenceOrigin, RpcReplyPort<bool>),
+
+    /// Take all the evidence received from peers which has not been reported yet,
+    /// marking it as reported
+    TakePending(RpcReplyPort<Vec<Evidence<Ctx>>>),
+
+    /// Remove the evidence for heights below the given height
+    Prune(Ctx::Height),
+}
+
+pub struct Args<Codec> {
+    pub path: PathBuf,
+    pub codec: Codec,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Status {
+    Pending,
+    Reported,
+}
+
+impl Status {
+    const TAG_PENDING: u8 = 0x01;
+    const TAG_REPORTED: u8 = 0x02;
+
+    fn tag(self) -> u8 {
+        match self {
+            Status::Pending => Self::TAG_PENDING,
+            Status::Reported => Self::TAG_REPORTED,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            Self::TAG_PENDING => Some(Status::Pending),
+            Self::TAG_REPORTED => Some(Status::Reported),
+            _ => None,
+        }
+    }
+}
+
+/// Identifies a misbehavior independently of the messages proving it.
+#[derive_where(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Key<Ctx: Context> {
+    DoubleVote {
+        address: Ctx::Address,
+        height: Ctx::Height,
+        round: Round,
+        vote_type: VoteType,
+    },
+    DoubleProposal {
+        address: Ctx::Address,
+        height: Ctx::Height,
+        round: Round,
+    },
+    Amnesia {
+        address: Ctx::Address,
+        height: Ctx::Height,
+        locked_round: Round,
+        prevote_round: Round,
+    },
+}
+
+impl<Ctx: Context> Key<Ctx> {
+    fn new(evidence: &Evidence<Ctx>) -> Self {
+        match evidence {
+            Evidence::DoubleVote(e) => Key::DoubleVote {
+                address: e.validator_address().clone(),
+                height: e.height(),
+                round: e.round(),
+                vote_type: e.vote_type(),
+            },
+            Evidence::DoubleProposal(e) => Key::DoubleProposal {
+                address: e.validator_address().clone(),
+                height: e.height(),
+                round: e.round(),
+            },
+            Evidence::Amnesia(e) => Key::Amnesia {
+                address: e.validator_address().clone(),
+                height: e.height(),
+                locked_round: e.locked_round(),
+                prevote_round: e.prevote_round(),
+            },
+        }
+    }
+}
+
+struct Entry<Ctx: Context> {
+    key: Key<Ctx>,
+    evidence: Evidence<Ctx>,
+    bytes: Bytes,
+    status: Status,
+}
+
+pub struct State<Ctx: Context, Codec> {
+    log: wal::Log,
+    codec: Codec,
+
+    /// Evidence in the pool, in insertion order
+    entries: Vec<Entry<Ctx>>,
+
+    /// Misbehaviors for which the pool holds evidence, for fast lookups
+    index: BTreeSet<Key<Ctx>>,
+
+    /// Number of pieces of evidence in the pool for each validator and height
+    counts: BTreeMap<(Ctx::Address, Ctx::Height), usize>,
+}
+
+impl<Ctx, Codec> State<Ctx, Codec>
+where
+    Ctx: Context,
+    Codec: EvidenceCodec<Ctx>,
+{
+    fn encode(&self, evidence: &Evidence<Ctx>) -> io::Result<Bytes> {
+        self.codec
+            .encode(evidence)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+    }
+
+    fn contains(&self, evidence: &Evidence<Ctx>) -> bool {
+        self.index.contains(&Key::new(evidence))
+    }
+
+    fn is_full_for(&self, evidence: &Evidence<Ctx>) -> bool {
+        let count = self
+            .counts
+            .get(&(evidence.validator_address().clone(), evidence.height()))
+            .copied()
+            .unwrap_or(0);
+
+        count >= MAX_EVIDENCE_PER_VALIDATOR_AND_HEIGHT
+    }
+
+    fn insert(&mut self, evidence: Evidence<Ctx>, bytes: Bytes, status: Status) {
+        let key = Key::new(&evidence);
+
+        self.index.insert(key.clone());
+
+        *self
+            .counts
+            .entry((evidence.validator_address().clone(), evidence.height()))
+            .or_default() += 1;
+
+        self.entries.push(Entry {
+            key,
+            evidence,
+            bytes,
+            status,
+        });
+    }
+
+    fn reindex(&mut self) {
+        self.index.clear();
+        self.counts.clear();
+
+        for entry in &self.entries {
+            self.index.insert(entry.key.clone());
+
+            *self
+                .counts
+                .entry((
+                    entry.evidence.validator_address().clone(),
+                    entry.evidence.height(),
+                ))
+                .or_default() += 1;
+        }
+    }
+
+    fn load(&mut self) -> io::Result<()> {
+        if self.log.is_empty() {
+            return Ok(());
+        }
+
+        let mut records = Vec::new();
+        for bytes in self.log.iter()? {
+            records.push(decode_record(&self.codec, bytes?)?);
+        }
+
+        let mut positions = BTreeMap::<Key<Ctx>, usize>::new();
+
+        for (evidence, bytes, status) in records {
+            match positions.get(&Key::new(&evidence)) {
+                Some(&position) => self.entries[position].status = status,
+                None => {
+                    positions.insert(Key::new(&evidence), self.entries.len());
+                    self.insert(evidence, bytes, status);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn persist(&mut self, bytes: &[u8], status: Status) -> io::Result<()> {
+        self.append(bytes, status)?;
+        self.log.flush()
+    }
+
+    fn append(&mut self, bytes: &[u8], status: Status) -> io::Result<()> {
+        let mut buf = Vec::with_capacity(1 + bytes.len());
+        buf.push(status.tag());
+        buf.extend_from_slice(bytes);
+
+        self.log.append(&buf)
+    }
+
+    /// Remove the evidence for heights below `min_height`, and rewrite the log
+    /// with the remaining entries if any evidence was removed.
+    ///
+    /// Returns the number of pieces of evidence that were removed.
+    fn prune(&mut self, min_height: Ctx::Height) -> io::Result<usize> {
+        let before = self.entries.len();
+
+        self.entries
+            .retain(|entry| entry.evidence.height() >= min_height);
+
+        let pruned = before - self.entries.len();
+        if pruned == 0 {
+            return Ok(0);
+        }
+
+        self.reindex();
+
+        let records = self
+            .entries
+            .iter()
+            .map(|e| (e.bytes.clone(), e.status))
+            .collect::<Vec<_>>();
+
+        self.log.restart(0)?;
+
+        for (bytes, status) in records {
+            self.append(&bytes, status)?;
+        }
+
+        self.log.flush()?;
+
+        Ok(pruned)
+    }
+}
+
+fn decode_record<Ctx, Codec>(
+    codec: &Codec,
+    bytes: Vec<u8>,
+) -> io::Result<(Evidence<Ctx>, Bytes, Status)>
+where
+    Ctx: Context,
+    Codec: EvidenceCodec<Ctx>,
+{
+    let mut buf = io::Cursor::new(bytes);
+
+    let mut tag = [0; 1];
+    buf.read_exact(&mut tag)?;
+
+    let status = Status::from_tag(tag[0])
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid tag"))?;
+
+    let mut data = Vec::new();
+    buf.read_to_end(&mut data)?;
+
+    let data = Bytes::from(data);
+
+    let evidence = codec
+        .decode(data.clone())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+
+    Ok((evidence, data, status))
+}
+
+pub struct EvidencePool<Ctx, Codec> {
+    span: tracing::Span,
+    _marker: PhantomData<(Ctx, Codec)>,
+}
+
+impl<Ctx, Codec> EvidencePool<Ctx, Codec>
+where
+    Ctx: Context,
+    Codec: EvidenceCodec<Ctx>,
+{
+    pub fn new(span: tracing::Span) -> Self {
+        Self {
+            span,
+            _marker: PhantomData,
+        }
+    }
+
+    pub async fn spawn(
+        codec: Codec,
+        path: PathBuf,
+        span: tracing::Span,
+    ) -> Result<EvidencePoolRef<Ctx>, SpawnErr> {
+        let (actor_ref, _) = Actor::spawn(None, Self::new(span), Args { path, codec }).await?;
+        Ok(actor_ref)
+    }
+
+    // NOTE: Evidence is rare, so we write it to disk directly from the actor
+    //       instead of going through a dedicated thread like the WAL does.
+    fn handle_msg(
+        &self,
+        msg: Msg<Ctx>,
+        state: &mut State<Ctx, Codec>,
+    ) -> Result<(), ActorProcessingErr> {
+        match msg {
+            Msg::Contains(evidence, reply_to) => {
+                reply_to.send(state.contains(&evidence))?;
+            }
+
+            Msg::Insert(evidence, origin, reply_to) => {
+                if state.contains(&evidence) {
+                    reply_to.send(false)?;
+                    return Ok(());
+                }
+
+                if state.is_full_for(&evidence) {
+                    debug!(
+                        address = %evidence.validator_address(), height = %evidence.height(), ?origin,
+                        "Ignoring evidence, the pool already holds enough evidence for this validator and height"
+                    );
+
+                    reply_to.send(false)?;
+                    return Ok(());
+                }
+
+                let bytes = state.encode(&evidence)?;
+
+                debug!(
+                    address = %evidence.validator_address(), height = %evidence.height(), ?origin,
+                    "Adding evidence to the pool"
+                );
+
+                let status = match origin {
+                    EvidenceOrigin::Local => Status::Reported,
+                    EvidenceOrigin::Peer => Status::Pending,
+                };
+
+                state.persist(&bytes, status)?;
+                state.insert(evidence, bytes, status);
+
+                reply_to.send(true)?;
+            }
+
+            Msg::TakePending(reply_to) => {
+                let reported = state
+                    .entries
+                    .iter()
+                    .filter(|entry| entry.status == Status::Pending)
+                    .map(|entry| entry.bytes.clone())
+                    .collect::<Vec<_>>();
+
+                // Only mark the evidence as reported once that has been persisted,
+                // so that it is not lost if the log cannot be written to
+                for bytes in &reported {
+                    state.append(bytes, Status::Reported)?;
+                }
+
+                state.log.flush()?;
+
+                let mut pending = Vec::new();
+
+                for entry in state.entries.iter_mut() {
+                    if entry.status == Status::Pending {
+                        entry.status = Status::Reported;
+                        pending.push(entry.evidence.clone());
+                    }
+                }
+
+                reply_to.send(pending)?;
+            }
+
+            Msg::Prune(min_height) => match state.prune(min_height) {
+                Ok(0) => {}
+                Ok(pruned) => {
+                    debug!(%pruned, %min_height, "Pruned old evidence from the pool");
+                }
+                Err(e) => {
+                    error!("Failed to prune evidence: {e}");
+                }
+            },
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<Ctx, Codec> Actor for EvidencePool<Ctx, Codec>
+where
+    Ctx: Context,
+    Codec: EvidenceCodec<Ctx>,
+{
+    type Msg = Msg<Ctx>;
+    type Arguments = Args<Codec>;
+    type State = State<Ctx, Codec>;
+
+    #[tracing::instrument(
+        name = "evidence.pre_start",
+        parent = &self.span,
+        skip_all,
+    )]
+    async fn pre_start(
+        &self,
+        _myself: EvidencePoolRef<Ctx>,
+        args: Self::Arguments,
+    ) -> Result<Self::State, ActorProcessingErr> {
+        let log = wal::Log::open(&args.path)?;
+
+        let mut state = State {
+            log,
+            codec: args.codec,
+            entries: Vec::new(),
+            index: BTreeSet::new(),
+            counts: BTreeMap::new(),
+        };
+
+        if let Err(e) = state.load() {
+            error!("Failed to load evidence from {}: {e}", args.path.display());
+        }
+
+        info!(
+            entries = state.entries.len(),
+            "Opened evidence pool at {}",
+            args.path.display()
+        );
+
+        Ok(state)
+    }
+
+    #[tracing::instrument(name = "evidence", parent = &self.span, skip_all)]
+    async fn handle(
+        &self,
+        _myself: EvidencePoolRef<Ctx>,
+        msg: Self::Msg,
+        state: &mut Self::State,
+    ) -> Result<(), ActorProcessingErr> {
+        if let Err(e) = self.handle_msg(msg, state) {
+            error!("Failed to handle evidence pool message: {e}");
+        }
+
+        Ok(())
+    }
+}
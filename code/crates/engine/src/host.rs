@@ -5,12 +5,15 @@ use derive_where::derive_where;
 use ractor::{ActorRef, RpcReplyPort};
 
 use malachitebft_core_consensus::{Role, VoteExtensionError};
-use malachitebft_core_types::{CommitCertificate, Context, Round, ValueId, VoteExtensions};
+use malachitebft_core_types::{
+    CommitCertificate, Context, Round, ValidatorSetUpdate, ValidatorSetUpdateError, ValueId,
+    VoteExtensions,
+};
 use malachitebft_sync::{PeerId, RawDecidedValue};
 
 use crate::util::streaming::StreamMessage;
 
-pub use malachitebft_core_consensus::{LocallyProposedValue, ProposedValue};
+pub use malachitebft_core_consensus::{LocallyProposedValue, MisbehaviorEvidence, ProposedValue};
 
 /// A reference to the host actor.
 pub type HostRef<Ctx> = ActorRef<HostMsg<Ctx>>;
@@ -19,8 +22,29 @@ pub type HostRef<Ctx> = ActorRef<HostMsg<Ctx>>;
 #[derive_where(Debug)]
 pub enum Next<Ctx: Context> {
     /// Start at the given height with the given validator set.
+    ///
+    /// The validator set supersedes any validator set update scheduled with
+    /// [`Next::StartWithUpdate`] to take effect at or before that height,
+    /// while the updates taking effect at later heights are kept.
     Start(Ctx::Height, Ctx::ValidatorSet),
 
+    /// Start at the given height, and schedule the given update to the validator set
+    /// of the decided height to take effect `consensus.validator_set_update_delay` heights
+    /// after the decided height.
+    ///
+    /// The update is rejected if it is invalid for the validator set it applies to,
+    /// in particular if it changes more than 1/3 of its total voting power,
+    /// see [`ValidatorSetUpdate::validate`]. Whether the update was accepted is sent back
+    /// on the given reply port. A rejected update leaves the validator sets unchanged.
+    ///
+    /// Scheduled updates are recorded in the WAL, and are therefore restored
+    /// when the node restarts before they take effect.
+    StartWithUpdate(
+        Ctx::Height,
+        ValidatorSetUpdate<Ctx>,
+        RpcReplyPort<Result<(), ValidatorSetUpdateError<Ctx>>>,
+    ),
+
     /// Restart at the given height with the given validator set.
     Restart(Ctx::Height, Ctx::ValidatorSet),
 }
@@ -141,7 +165,9 @@ pub enum HostMsg<Ctx: Context> {
     /// This message includes a commit certificate containing the ID of
     /// the value that was decided on, the height and round at which it was decided,
     /// and the aggregated signatures of the validators that committed to it.
-    /// It also includes to the vote extensions received for that height.
+    /// It also includes to the vote extensions received for that height,
+    /// as well as the evidence of misbehavior (equivocating proposals and votes)
+    /// observed by consensus during that height.
     ///
     /// In response to this message, the application MUST send a [`Next`]
     /// message back to consensus, instructing it to either start the next height if
@@ -158,6 +184,9 @@ pub enum HostMsg<Ctx: Context> {
         /// Vote extensions that were received for this height.
         extensions: VoteExtensions<Ctx>,
 
+        /// Evidence of misbehavior collected during this height.
+        evidence: MisbehaviorEvidence<Ctx>,
+
         /// Use this reply port to instruct consensus to start the next height.
         reply_to: RpcReplyPort<Next<Ctx>>,
     },
@@ -1,4 +1,5 @@
 pub mod consensus;
+pub mod evidence;
 pub mod host;
 pub mod network;
 pub mod node;
@@ -16,12 +16,15 @@ use malachitebft_sync::{
 use malachitebft_codec as codec;
 use malachitebft_core_consensus::{LivenessMsg, SignedConsensusMsg};
 use malachitebft_core_types::{
-    Context, PolkaCertificate, RoundCertificate, SignedProposal, SignedVote,
+    Context, Evidence, PolkaCertificate, RoundCertificate, SignedProposal, SignedVote,
 };
 use malachitebft_metrics::SharedRegistry;
 use malachitebft_network::handle::CtrlHandle;
+pub use malachitebft_network::validator_mesh::Handshake;
 use malachitebft_network::{Channel, Config, Event, Multiaddr, PeerId};
 
+pub use malachitebft_network::{ConnectionGaterConfig, Misbehavior};
+
 use crate::consensus::ConsensusCodec;
 use crate::sync::SyncCodec;
 use crate::util::output_port::{OutputPort, OutputPortSubscriberTrait};
@@ -112,10 +115,16 @@ pub enum NetworkEvent<Ctx: Context> {
 
     RoundCertificate(PeerId, RoundCertificate<Ctx>),
 
+    Evidence(PeerId, Evidence<Ctx>),
+
     Status(PeerId, Status<Ctx>),
 
     SyncRequest(InboundRequestId, PeerId, Request<Ctx>),
     SyncResponse(OutboundRequestId, PeerId, Option<Response<Ctx>>),
+
+    /// A peer sent a handshake proving that it belongs to a validator,
+    /// see [`malachitebft_network::validator_mesh`]
+    ValidatorHandshake(PeerId, Handshake),
 }
 
 pub enum State<Ctx: Context> {
@@ -158,6 +167,9 @@ pub enum Msg<Ctx: Context> {
     /// Publish a proposal part
     PublishProposalPart(StreamMessage<Ctx::ProposalPart>),
 
+    /// Publish evidence of misbehavior
+    PublishEvidence(Evidence<Ctx>),
+
     /// Broadcast status to all direct peers
     BroadcastStatus(Status<Ctx>),
 
@@ -173,6 +185,22 @@ pub enum Msg<Ctx: Context> {
     /// Request for number of peers from gossip
     GetState { reply: RpcReplyPort<usize> },
 
+    /// Report a peer for misbehaving, lowering its reputation
+    ReportPeer(PeerId, Misbehavior),
+
+    /// Request the ID of our own peer
+    GetLocalPeerId { reply: RpcReplyPort<PeerId> },
+
+    /// Set the handshake proving that our peer belongs to a validator, sent to every peer
+    SetValidatorHandshake(Handshake),
+
+    /// Set the peers which belong to validators of the current validator set
+    SetValidatorPeers(BTreeSet<PeerId>),
+
+    /// Replace the peers and IP addresses allowed or denied to connect,
+    /// closing the connections which are not allowed anymore
+    SetConnectionGater(ConnectionGaterConfig),
+
     // Event emitted by the gossip layer
     #[doc(hidden)]
     NewEvent(Event),
@@ -190,6 +218,7 @@ where
     Codec: codec::Codec<sync::Request<Ctx>>,
     Codec: codec::Codec<sync::Response<Ctx>>,
     Codec: codec::Codec<LivenessMsg<Ctx>>,
+    Codec: codec::Codec<Evidence<Ctx>>,
     Codec: codec::HasEncodedLen<sync::Response<Ctx>>,
 {
     type Msg = Msg<Ctx>;
@@ -288,6 +317,11 @@ where
                 }
             }
 
+            Msg::PublishEvidence(evidence) => match self.codec.encode(&evidence) {
+                Ok(data) => ctrl_handle.publish(Channel::Evidence, data).await?,
+                Err(e) => error!("Failed to encode evidence: {e:?}"),
+            },
+
             Msg::BroadcastStatus(status) => {
                 let status = sync::Status {
                     peer_id: ctrl_handle.peer_id(),
@@ -366,6 +400,9 @@ where
                     Ok(msg) => msg,
                     Err(e) => {
                         error!(%from, "Failed to decode liveness message: {e:?}");
+                        ctrl_handle
+                            .report_peer(from, Misbehavior::InvalidMessage)
+                            .await?;
                         return Ok(());
                     }
                 };
@@ -385,6 +422,9 @@ where
 
             Msg::NewEvent(Event::LivenessMessage(channel, from, _)) => {
                 error!(%from, "Unexpected liveness message on {channel} channel");
+                ctrl_handle
+                    .report_peer(from, Misbehavior::UnexpectedMessage)
+                    .await?;
                 return Ok(());
             }
 
@@ -393,6 +433,9 @@ where
                     Ok(msg) => msg,
                     Err(e) => {
                         error!(%from, "Failed to decode consensus message: {e:?}");
+                        ctrl_handle
+                            .report_peer(from, Misbehavior::InvalidMessage)
+                            .await?;
                         return Ok(());
                     }
                 };
@@ -412,6 +455,9 @@ where
                     Ok(stream_msg) => stream_msg,
                     Err(e) => {
                         error!(%from, "Failed to decode stream message: {e:?}");
+                        ctrl_handle
+                            .report_peer(from, Misbehavior::InvalidMessage)
+                            .await?;
                         return Ok(());
                     }
                 };
@@ -431,12 +477,18 @@ where
                     Ok(status) => status,
                     Err(e) => {
                         error!(%from, "Failed to decode status message: {e:?}");
+                        ctrl_handle
+                            .report_peer(from, Misbehavior::InvalidMessage)
+                            .await?;
                         return Ok(());
                     }
                 };
 
                 if from != status.peer_id {
                     error!(%from, %status.peer_id, "Mismatched peer ID in status message");
+                    ctrl_handle
+                        .report_peer(from, Misbehavior::UnexpectedMessage)
+                        .await?;
                     return Ok(());
                 }
 
@@ -448,11 +500,35 @@ where
                 ));
             }
 
+            Msg::NewEvent(Event::ConsensusMessage(Channel::Evidence, from, data)) => {
+                let evidence: Evidence<Ctx> = match self.codec.decode(data) {
+                    Ok(evidence) => evidence,
+                    Err(e) => {
+                        error!(%from, "Failed to decode evidence: {e:?}");
+                        ctrl_handle
+                            .report_peer(from, Misbehavior::InvalidMessage)
+                            .await?;
+                        return Ok(());
+                    }
+                };
+
+                trace!(%from, address = %evidence.validator_address(), "Received evidence");
+
+                output_port.send(NetworkEvent::Evidence(from, evidence));
+            }
+
             Msg::NewEvent(Event::ConsensusMessage(channel, from, _)) => {
                 error!(%from, "Unexpected consensus message on {channel} channel");
+                ctrl_handle
+                    .report_peer(from, Misbehavior::UnexpectedMessage)
+                    .await?;
                 return Ok(());
             }
 
+            Msg::NewEvent(Event::ValidatorHandshake(peer_id, handshake)) => {
+                output_port.send(NetworkEvent::ValidatorHandshake(peer_id, handshake));
+            }
+
             Msg::NewEvent(Event::Sync(raw_msg)) => match raw_msg {
                 RawMessage::Request {
                     request_id,
@@ -463,6 +539,9 @@ where
                         Ok(request) => request,
                         Err(e) => {
                             error!(%peer, "Failed to decode sync request: {e:?}");
+                            ctrl_handle
+                                .report_peer(peer, Misbehavior::InvalidMessage)
+                                .await?;
                             return Ok(());
                         }
                     };
@@ -485,6 +564,9 @@ where
                         Ok(response) => Some(response),
                         Err(e) => {
                             error!(%peer, "Failed to decode sync response: {e:?}");
+                            ctrl_handle
+                                .report_peer(peer, Misbehavior::InvalidMessage)
+                                .await?;
                             None
                         }
                     };
@@ -497,6 +579,28 @@ where
                 }
             },
 
+            Msg::ReportPeer(peer_id, misbehavior) => {
+                ctrl_handle.report_peer(peer_id, misbehavior).await?;
+            }
+
+            Msg::GetLocalPeerId { reply } => {
+                reply.send(ctrl_handle.peer_id())?;
+            }
+
+            Msg::SetValidatorHandshake(handshake) => {
+                ctrl_handle.set_validator_handshake(handshake).await?;
+            }
+
+            Msg::SetValidatorPeers(validators) => {
+                ctrl_handle
+                    .set_validator_peers(validators.into_iter().collect())
+                    .await?;
+            }
+
+            Msg::SetConnectionGater(config) => {
+                ctrl_handle.set_connection_gater(config).await?;
+            }
+
             Msg::GetState { reply } => {
                 let number_peers = match state {
                     State::Stopped => 0,
@@ -6,6 +6,7 @@ use tracing::{error, info, warn};
 use malachitebft_core_types::Context;
 
 use crate::consensus::ConsensusRef;
+use crate::evidence::EvidencePoolRef;
 use crate::host::HostRef;
 use crate::network::NetworkRef;
 use crate::sync::SyncRef;
@@ -19,6 +20,7 @@ pub struct Node<Ctx: Context> {
     network: NetworkRef<Ctx>,
     consensus: ConsensusRef<Ctx>,
     wal: WalRef<Ctx>,
+    evidence_pool: EvidencePoolRef<Ctx>,
     sync: Option<SyncRef<Ctx>>,
     host: HostRef<Ctx>,
     span: tracing::Span,
@@ -34,6 +36,7 @@ where
         network: NetworkRef<Ctx>,
         consensus: ConsensusRef<Ctx>,
         wal: WalRef<Ctx>,
+        evidence_pool: EvidencePoolRef<Ctx>,
         sync: Option<SyncRef<Ctx>>,
         host: HostRef<Ctx>,
         span: tracing::Span,
@@ -43,6 +46,7 @@ where
             network,
             consensus,
             wal,
+            evidence_pool,
             sync,
             host,
             span,
@@ -73,6 +77,7 @@ where
         self.consensus.link(myself.get_cell());
         self.host.link(myself.get_cell());
         self.wal.link(myself.get_cell());
+        self.evidence_pool.link(myself.get_cell());
 
         if let Some(actor) = &self.sync {
             actor.link(myself.get_cell());
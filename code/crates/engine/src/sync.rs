@@ -14,7 +14,7 @@ use tracing::{debug, error, info, warn, Instrument};
 
 use crate::consensus::{ConsensusMsg, ConsensusRef};
 use crate::host::{HostMsg, HostRef};
-use crate::network::{NetworkEvent, NetworkMsg, NetworkRef, Status};
+use crate::network::{Misbehavior, NetworkEvent, NetworkMsg, NetworkRef, Status};
 use crate::util::ticker::ticker;
 use crate::util::timers::{TimeoutElapsed, TimerScheduler};
 use malachitebft_codec as codec;
@@ -483,6 +483,11 @@ where
             }
 
             Msg::InvalidValue(peer, height) => {
+                self.gossip.cast(NetworkMsg::ReportPeer(
+                    peer,
+                    Misbehavior::InvalidSyncResponse,
+                ))?;
+
                 self.process_input(&myself, state, sync::Input::InvalidValue(peer, height))
                     .await?
             }
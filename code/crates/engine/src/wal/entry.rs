@@ -4,17 +4,20 @@ use byteorder::{ReadBytesExt, WriteBytesExt, BE};
 
 use malachitebft_codec::Codec;
 use malachitebft_core_consensus::{ProposedValue, SignedConsensusMsg};
-use malachitebft_core_types::{Context, Round, Timeout};
+use malachitebft_core_types::{Context, Height, Round, Timeout};
 
 /// Codec for encoding and decoding WAL entries.
 ///
 /// This trait is automatically implemented for any type that implements:
 /// - [`Codec<SignedConsensusMsg<Ctx>>`]
+/// - [`Codec<ProposedValue<Ctx>>`]
+/// - [`Codec<Ctx::ValidatorSet>`]
 pub trait WalCodec<Ctx>
 where
     Ctx: Context,
     Self: Codec<SignedConsensusMsg<Ctx>>,
     Self: Codec<ProposedValue<Ctx>>,
+    Self: Codec<Ctx::ValidatorSet>,
 {
 }
 
@@ -23,6 +26,7 @@ where
     Ctx: Context,
     C: Codec<SignedConsensusMsg<Ctx>>,
     C: Codec<ProposedValue<Ctx>>,
+    C: Codec<Ctx::ValidatorSet>,
 {
 }
 
@@ -31,6 +35,7 @@ pub use malachitebft_core_consensus::WalEntry;
 const TAG_CONSENSUS: u8 = 0x01;
 const TAG_TIMEOUT: u8 = 0x02;
 const TAG_PROPOSED_VALUE: u8 = 0x04;
+const TAG_PENDING_VALIDATOR_SETS: u8 = 0x05;
 
 pub fn encode_entry<Ctx, C, W>(entry: &WalEntry<Ctx>, codec: &C, buf: W) -> io::Result<()>
 where
@@ -44,6 +49,9 @@ where
         WalEntry::ProposedValue(value) => {
             encode_proposed_value(TAG_PROPOSED_VALUE, value, codec, buf)
         }
+        WalEntry::PendingValidatorSets(validator_sets) => {
+            encode_validator_sets(TAG_PENDING_VALIDATOR_SETS, validator_sets, codec, buf)
+        }
     }
 }
 
@@ -59,6 +67,9 @@ where
         TAG_CONSENSUS => decode_consensus_msg(codec, buf).map(WalEntry::ConsensusMsg),
         TAG_TIMEOUT => decode_timeout(buf).map(WalEntry::Timeout),
         TAG_PROPOSED_VALUE => decode_proposed_value(codec, buf).map(WalEntry::ProposedValue),
+        TAG_PENDING_VALIDATOR_SETS => {
+            decode_validator_sets(codec, buf).map(WalEntry::PendingValidatorSets)
+        }
         _ => Err(io::Error::new(io::ErrorKind::InvalidData, "invalid tag")),
     }
 }
@@ -226,3 +237,74 @@ where
         )
     })
 }
+
+// Pending validator sets helpers
+fn encode_validator_sets<Ctx, C, W>(
+    tag: u8,
+    validator_sets: &[(Ctx::Height, Ctx::ValidatorSet)],
+    codec: &C,
+    mut buf: W,
+) -> io::Result<()>
+where
+    Ctx: Context,
+    C: WalCodec<Ctx>,
+    W: Write,
+{
+    // Write tag
+    buf.write_u8(tag)?;
+
+    // Write number of validator sets
+    buf.write_u64::<BE>(validator_sets.len() as u64)?;
+
+    for (height, validator_set) in validator_sets {
+        let bytes = codec.encode(validator_set).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("failed to encode validator set: {e}"),
+            )
+        })?;
+
+        // Write height at which the validator set takes effect
+        buf.write_u64::<BE>(height.as_u64())?;
+
+        // Write encoded length
+        buf.write_u64::<BE>(bytes.len() as u64)?;
+
+        // Write encoded bytes
+        buf.write_all(&bytes)?;
+    }
+
+    Ok(())
+}
+
+fn decode_validator_sets<Ctx, C, R>(
+    codec: &C,
+    mut buf: R,
+) -> io::Result<Vec<(Ctx::Height, Ctx::ValidatorSet)>>
+where
+    Ctx: Context,
+    C: WalCodec<Ctx>,
+    R: Read,
+{
+    let count = buf.read_u64::<BE>()?;
+    let mut validator_sets = Vec::new();
+
+    for _ in 0..count {
+        let height = Ctx::Height::ZERO.increment_by(buf.read_u64::<BE>()?);
+
+        let len = buf.read_u64::<BE>()?;
+        let mut bytes = vec![0; len as usize];
+        buf.read_exact(&mut bytes)?;
+
+        let validator_set = codec.decode(bytes.into()).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("failed to decode validator set: {e}"),
+            )
+        })?;
+
+        validator_sets.push((height, validator_set));
+    }
+
+    Ok(validator_sets)
+}
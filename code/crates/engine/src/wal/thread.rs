@@ -261,5 +261,6 @@ fn wal_entry_type<Ctx: Context>(entry: &WalEntry<Ctx>) -> &'static str {
         },
         WalEntry::ProposedValue(_) => "LocallyProposedValue",
         WalEntry::Timeout(_) => "Timeout",
+        WalEntry::PendingValidatorSets(_) => "PendingValidatorSets",
     }
 }
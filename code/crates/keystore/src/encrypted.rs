@@ -0,0 +1,181 @@
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use rand::{CryptoRng, RngCore};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use crate::scrypt::{scrypt, ScryptParams};
+use crate::{Error, Passphrase};
+
+/// Version of the encrypted key file format.
+pub const VERSION: u32 = 1;
+
+const KDF_SCRYPT: &str = "scrypt";
+const CIPHER_XCHACHA20_POLY1305: &str = "xchacha20-poly1305";
+
+const SALT_LEN: usize = 32;
+const NONCE_LEN: usize = 24;
+
+/// A key encrypted with a passphrase, as stored in an encrypted key file.
+///
+/// The encryption key is derived from the passphrase with scrypt, and the key is encrypted
+/// with XChaCha20-Poly1305, which also authenticates it: decrypting a key with the wrong
+/// passphrase, or one which was tampered with, fails with [`Error::Decryption`].
+///
+/// ```json
+/// {
+///   "version": 1,
+///   "kdf": { "function": "scrypt", "log_n": 17, "r": 8, "p": 1, "salt": "..." },
+///   "cipher": { "function": "xchacha20-poly1305", "nonce": "..." },
+///   "ciphertext": "..."
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncryptedKey {
+    version: u32,
+    kdf: Kdf,
+    cipher: Cipher,
+    #[serde(with = "hex::serde")]
+    ciphertext: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Kdf {
+    function: String,
+    #[serde(flatten)]
+    params: ScryptParams,
+    #[serde(with = "hex::serde")]
+    salt: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Cipher {
+    function: String,
+    #[serde(with = "hex::serde")]
+    nonce: Vec<u8>,
+}
+
+impl EncryptedKey {
+    /// Encrypt the given bytes with a key derived from the passphrase with the given parameters,
+    /// using a fresh salt and nonce drawn from the given RNG.
+    pub fn encrypt<R>(
+        plaintext: &[u8],
+        passphrase: &Passphrase,
+        params: ScryptParams,
+        rng: &mut R,
+    ) -> Result<Self, Error>
+    where
+        R: RngCore + CryptoRng,
+    {
+        let mut salt = vec![0; SALT_LEN];
+        rng.fill_bytes(&mut salt);
+
+        let mut nonce = vec![0; NONCE_LEN];
+        rng.fill_bytes(&mut nonce);
+
+        let key = derive_key(passphrase, &salt, &params)?;
+        let ciphertext = XChaCha20Poly1305::new(Key::from_slice(key.as_ref()))
+            .encrypt(XNonce::from_slice(&nonce), plaintext)
+            .expect("encrypting a key in memory cannot fail");
+
+        Ok(Self {
+            version: VERSION,
+            kdf: Kdf {
+                function: KDF_SCRYPT.to_string(),
+                params,
+                salt,
+            },
+            cipher: Cipher {
+                function: CIPHER_XCHACHA20_POLY1305.to_string(),
+                nonce,
+            },
+            ciphertext,
+        })
+    }
+
+    /// Serialize the given value to JSON, and encrypt it.
+    pub fn encrypt_json<T, R>(
+        value: &T,
+        passphrase: &Passphrase,
+        params: ScryptParams,
+        rng: &mut R,
+    ) -> Result<Self, Error>
+    where
+        T: Serialize,
+        R: RngCore + CryptoRng,
+    {
+        let plaintext = Zeroizing::new(serde_json::to_vec(value)?);
+        Self::encrypt(&plaintext, passphrase, params, rng)
+    }
+
+    /// Decrypt the key with the given passphrase.
+    pub fn decrypt(&self, passphrase: &Passphrase) -> Result<Zeroizing<Vec<u8>>, Error> {
+        if self.version != VERSION {
+            return Err(Error::UnsupportedVersion(self.version));
+        }
+
+        if self.kdf.function != KDF_SCRYPT {
+            return Err(Error::UnsupportedAlgorithm {
+                kind: "key derivation function",
+                name: self.kdf.function.clone(),
+            });
+        }
+
+        if self.cipher.function != CIPHER_XCHACHA20_POLY1305 {
+            return Err(Error::UnsupportedAlgorithm {
+                kind: "cipher",
+                name: self.cipher.function.clone(),
+            });
+        }
+
+        check_length("salt", &self.kdf.salt, SALT_LEN)?;
+        check_length("nonce", &self.cipher.nonce, NONCE_LEN)?;
+
+        let key = derive_key(passphrase, &self.kdf.salt, &self.kdf.params)?;
+        let plaintext = XChaCha20Poly1305::new(Key::from_slice(key.as_ref()))
+            .decrypt(
+                XNonce::from_slice(&self.cipher.nonce),
+                self.ciphertext.as_slice(),
+            )
+            .map_err(|_| Error::Decryption)?;
+
+        Ok(Zeroizing::new(plaintext))
+    }
+
+    /// Decrypt the key with the given passphrase, and deserialize it from JSON.
+    pub fn decrypt_json<T>(&self, passphrase: &Passphrase) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+    {
+        let plaintext = self.decrypt(passphrase)?;
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
+
+    /// The parameters used to derive the encryption key from the passphrase.
+    pub fn params(&self) -> &ScryptParams {
+        &self.kdf.params
+    }
+}
+
+fn derive_key(
+    passphrase: &Passphrase,
+    salt: &[u8],
+    params: &ScryptParams,
+) -> Result<Zeroizing<[u8; 32]>, Error> {
+    let mut key = Zeroizing::new([0; 32]);
+    scrypt(passphrase.as_bytes(), salt, params, key.as_mut())?;
+    Ok(key)
+}
+
+fn check_length(field: &'static str, bytes: &[u8], expected: usize) -> Result<(), Error> {
+    if bytes.len() != expected {
+        return Err(Error::InvalidLength {
+            field,
+            expected,
+            actual: bytes.len(),
+        });
+    }
+
+    Ok(())
+}
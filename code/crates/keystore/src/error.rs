@@ -0,0 +1,61 @@
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+/// Represents an error that can occur when encrypting, decrypting or loading a key file.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// A file could not be read or written.
+    #[error("Failed to access {path}: {source}")]
+    Io {
+        /// Path to the file
+        path: PathBuf,
+        /// The underlying I/O error
+        source: std::io::Error,
+    },
+
+    /// A key file, or the key it contains, could not be serialized or deserialized.
+    #[error("Invalid key file: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// The encrypted key file has a version we do not know about.
+    #[error("Unsupported encrypted key file version {0}, expected {version}", version = crate::VERSION)]
+    UnsupportedVersion(u32),
+
+    /// The encrypted key file uses a key derivation function or cipher we do not support.
+    #[error("Unsupported {kind} '{name}'")]
+    UnsupportedAlgorithm {
+        /// Whether this is the key derivation function or the cipher
+        kind: &'static str,
+        /// Name of the algorithm
+        name: String,
+    },
+
+    /// The parameters of the key derivation function are invalid.
+    #[error("Invalid key derivation parameters: {0}")]
+    InvalidParams(String),
+
+    /// The salt or nonce of the encrypted key file has the wrong length.
+    #[error("Invalid {field} length: expected {expected} bytes, got {actual}")]
+    InvalidLength {
+        /// Name of the field
+        field: &'static str,
+        /// Expected length, in bytes
+        expected: usize,
+        /// Actual length, in bytes
+        actual: usize,
+    },
+
+    /// The key could not be decrypted, either because the passphrase is wrong
+    /// or because the file was tampered with.
+    #[error("Failed to decrypt key: wrong passphrase or corrupted file")]
+    Decryption,
+
+    /// The key file is encrypted, but no passphrase was provided.
+    #[error("Key file is encrypted, but no passphrase was provided in ${0} or ${0}_FILE")]
+    MissingPassphrase(String),
+
+    /// The passphrase is empty.
+    #[error("Passphrase must not be empty")]
+    EmptyPassphrase,
+}
@@ -0,0 +1,83 @@
+//! Encrypted private key files for the Malachite BFT consensus engine.
+//!
+//! By default, the private key of a validator is stored in plain text in its
+//! `priv_validator_key.json` file. This crate instead stores it encrypted with a passphrase,
+//! using scrypt to derive the encryption key and XChaCha20-Poly1305 to encrypt it
+//! (see [`EncryptedKey`] for the format of the file).
+//!
+//! The key file is encrypted as a whole, so that this works for the private key of any
+//! signing scheme, and for whatever key file format the application uses.
+//!
+//! ## Loading a key file
+//!
+//! [`load_key_file`] loads both plain and encrypted key files, reading the passphrase of the
+//! latter from the `MALACHITE_KEY_PASSPHRASE` environment variable or, if it is not set,
+//! from the file at the path given in `MALACHITE_KEY_PASSPHRASE_FILE`:
+//!
+//! ```rust,ignore
+//! let private_key_file: PrivateKeyFile = load_key_file(&path)?;
+//! ```
+
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+
+mod error;
+pub use error::Error;
+
+mod encrypted;
+pub use encrypted::{EncryptedKey, VERSION};
+
+mod passphrase;
+pub use passphrase::{Passphrase, PASSPHRASE_ENV_VAR};
+
+pub mod scrypt;
+pub use scrypt::ScryptParams;
+
+/// Whether the given contents of a key file are those of an encrypted key file.
+pub fn is_encrypted(contents: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(contents)
+        .is_ok_and(|value| value.get("ciphertext").is_some() && value.get("kdf").is_some())
+}
+
+/// Parse the given contents of a key file, decrypting them with the given passphrase
+/// if the key file is encrypted.
+///
+/// Fails with [`Error::MissingPassphrase`] if the key file is encrypted but no passphrase is given.
+pub fn parse_key_file<T>(contents: &str, passphrase: Option<&Passphrase>) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    if !is_encrypted(contents) {
+        return Ok(serde_json::from_str(contents)?);
+    }
+
+    let encrypted: EncryptedKey = serde_json::from_str(contents)?;
+    let passphrase =
+        passphrase.ok_or_else(|| Error::MissingPassphrase(PASSPHRASE_ENV_VAR.to_string()))?;
+
+    encrypted.decrypt_json(passphrase)
+}
+
+/// Load the key file at the given path, which may be plain or encrypted.
+///
+/// The passphrase of an encrypted key file is read with [`Passphrase::from_env`].
+pub fn load_key_file<T>(path: impl AsRef<Path>) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    let path = path.as_ref();
+
+    let contents =
+        zeroize::Zeroizing::new(std::fs::read_to_string(path).map_err(|source| Error::Io {
+            path: path.to_path_buf(),
+            source,
+        })?);
+
+    if !is_encrypted(&contents) {
+        return parse_key_file(&contents, None);
+    }
+
+    let passphrase = Passphrase::from_env()?;
+    parse_key_file(&contents, passphrase.as_ref())
+}
@@ -0,0 +1,76 @@
+use core::fmt;
+use std::path::Path;
+
+use zeroize::Zeroizing;
+
+use crate::Error;
+
+/// Name of the environment variable holding the passphrase of the validator key file.
+///
+/// The passphrase can also be read from the file at the path given in `MALACHITE_KEY_PASSPHRASE_FILE`.
+pub const PASSPHRASE_ENV_VAR: &str = "MALACHITE_KEY_PASSPHRASE";
+
+/// A passphrase used to encrypt and decrypt key files, which is wiped from memory when dropped.
+#[derive(Clone)]
+pub struct Passphrase(Zeroizing<String>);
+
+impl Passphrase {
+    /// Create a passphrase, which must not be empty.
+    pub fn new(passphrase: impl Into<String>) -> Result<Self, Error> {
+        let passphrase = Zeroizing::new(passphrase.into());
+
+        if passphrase.is_empty() {
+            return Err(Error::EmptyPassphrase);
+        }
+
+        Ok(Self(passphrase))
+    }
+
+    /// Read the passphrase from the given file, ignoring a trailing newline.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+
+        let mut contents =
+            Zeroizing::new(std::fs::read_to_string(path).map_err(|source| Error::Io {
+                path: path.to_path_buf(),
+                source,
+            })?);
+
+        let len = contents.trim_end_matches(['\r', '\n']).len();
+        contents.truncate(len);
+
+        Self::new(std::mem::take(&mut *contents))
+    }
+
+    /// Read the passphrase from the environment variable with the given name or, if it is not set,
+    /// from the file at the path given in the variable of the same name suffixed with `_FILE`.
+    ///
+    /// Returns `Ok(None)` if neither variable is set.
+    pub fn from_env_var(name: &str) -> Result<Option<Self>, Error> {
+        if let Ok(passphrase) = std::env::var(name) {
+            return Self::new(passphrase).map(Some);
+        }
+
+        match std::env::var_os(format!("{name}_FILE")) {
+            Some(path) => Self::from_file(path).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Read the passphrase from the [`PASSPHRASE_ENV_VAR`] environment variable or,
+    /// if it is not set, from the file given in `MALACHITE_KEY_PASSPHRASE_FILE`.
+    pub fn from_env() -> Result<Option<Self>, Error> {
+        Self::from_env_var(PASSPHRASE_ENV_VAR)
+    }
+
+    /// The passphrase, as bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+}
+
+impl fmt::Debug for Passphrase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Passphrase(<redacted>)")
+    }
+}
@@ -0,0 +1,93 @@
+//! The scrypt key derivation function, as specified in [RFC 7914].
+//!
+//! Keys are derived with the [`scrypt`](::scrypt) crate, this module only adds
+//! serializable parameters which are checked not to need too much memory.
+//!
+//! [RFC 7914]: https://www.rfc-editor.org/rfc/rfc7914
+
+use serde::{Deserialize, Serialize};
+
+use crate::Error;
+
+/// Maximum amount of memory that deriving a key may use, 1 GiB.
+const MAX_MEMORY: u64 = 1 << 30;
+
+/// Parameters of the scrypt key derivation function.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScryptParams {
+    /// Base 2 logarithm of the CPU/memory cost `N`.
+    pub log_n: u8,
+    /// Block size.
+    pub r: u32,
+    /// Parallelization.
+    pub p: u32,
+}
+
+impl ScryptParams {
+    /// Recommended parameters, which take about half a second and 128 MiB of memory
+    /// to derive a key on commodity hardware.
+    pub const RECOMMENDED: Self = Self {
+        log_n: 17,
+        r: 8,
+        p: 1,
+    };
+
+    /// Create scrypt parameters, checking that they are valid,
+    /// and do not need more than 1 GiB of memory to derive a key.
+    pub fn new(log_n: u8, r: u32, p: u32) -> Result<Self, Error> {
+        let params = Self { log_n, r, p };
+        params.validate()?;
+        Ok(params)
+    }
+
+    /// Check that the parameters are valid, and do not need more than 1 GiB of memory.
+    pub fn validate(&self) -> Result<(), Error> {
+        let invalid = |reason: &str| Err(Error::InvalidParams(reason.to_string()));
+
+        if self.log_n == 0 || self.log_n >= 64 {
+            return invalid("log_n must be between 1 and 63");
+        }
+
+        if self.r == 0 || self.p == 0 {
+            return invalid("r and p must be positive");
+        }
+
+        if u64::from(self.r) * u64::from(self.p) >= 1 << 30 {
+            return invalid("r * p must be less than 2^30");
+        }
+
+        let memory = 128 * u64::from(self.r);
+        if memory
+            .checked_shl(u32::from(self.log_n))
+            .unwrap_or(u64::MAX)
+            > MAX_MEMORY
+            || self.log_n >= 40
+        {
+            return invalid("deriving a key would need more than 1 GiB of memory");
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for ScryptParams {
+    fn default() -> Self {
+        Self::RECOMMENDED
+    }
+}
+
+/// Derive a key from the given password and salt with scrypt, filling the whole output.
+pub fn scrypt(
+    password: &[u8],
+    salt: &[u8],
+    params: &ScryptParams,
+    output: &mut [u8],
+) -> Result<(), Error> {
+    params.validate()?;
+
+    let params = ::scrypt::Params::new(params.log_n, params.r, params.p, output.len())
+        .map_err(|e| Error::InvalidParams(e.to_string()))?;
+
+    ::scrypt::scrypt(password, salt, &params, output)
+        .map_err(|e| Error::InvalidParams(e.to_string()))
+}
@@ -0,0 +1,57 @@
+use derive_where::derive_where;
+use thiserror::Error;
+
+use malachitebft_core_types::{BoxError, CertificateError, Context, VotingPower};
+
+/// Represents an error that can occur when verifying light blocks.
+#[derive(Error)]
+#[derive_where(Debug)]
+pub enum Error<Ctx: Context> {
+    /// The untrusted light block is not above the trusted one.
+    #[error("Light block at height {untrusted} is not above the trusted height {trusted}")]
+    NonIncreasingHeight {
+        /// Height of the trusted light block
+        trusted: Ctx::Height,
+        /// Height of the untrusted light block
+        untrusted: Ctx::Height,
+    },
+
+    /// The decided value does not commit to the validator sets of the light block.
+    #[error("Light block at height {0} does not commit to its validator sets")]
+    InvalidValidatorSetCommitment(Ctx::Height),
+
+    /// The validator set of the light block is not the next validator set of the trusted light block.
+    #[error("Validator set of light block at height {0} is not the next validator set of the trusted light block")]
+    ValidatorSetMismatch(Ctx::Height),
+
+    /// Not enough voting power of the trusted validator set signed the certificate
+    /// to skip verification up to that light block.
+    #[error("Not enough trust to verify light block at height {height}: signed: {signed}, total: {total}, expected: {expected}")]
+    NotEnoughTrust {
+        /// Height of the untrusted light block
+        height: Ctx::Height,
+        /// Voting power of the trusted validators which signed the certificate
+        signed: VotingPower,
+        /// Total voting power of the trusted validator set
+        total: VotingPower,
+        /// Voting power required to meet the trust threshold
+        expected: VotingPower,
+    },
+
+    /// The commit certificate of the light block is invalid for its validator set.
+    #[error("Invalid commit certificate at height {0}: {1}")]
+    InvalidCertificate(Ctx::Height, CertificateError<Ctx>),
+
+    /// The provider returned a light block for another height than requested.
+    #[error("Provider returned a light block at height {actual} instead of {expected}")]
+    UnexpectedHeight {
+        /// Requested height
+        expected: Ctx::Height,
+        /// Height of the light block returned by the provider
+        actual: Ctx::Height,
+    },
+
+    /// The provider failed to return a light block.
+    #[error("Failed to fetch light block at height {0}: {1}")]
+    Provider(Ctx::Height, BoxError),
+}
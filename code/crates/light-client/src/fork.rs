@@ -0,0 +1,51 @@
+use alloc::vec::Vec;
+
+use derive_where::derive_where;
+
+use malachitebft_core_types::Context;
+
+use crate::LightBlock;
+
+/// Two verified light blocks deciding on different values at the same height.
+///
+/// A fork can only happen if more than the honest threshold of the voting power misbehaved,
+/// see [`Fork::conflicting_signers`] for the validators which can be held accountable.
+#[derive_where(Clone, Debug, PartialEq, Eq)]
+pub struct Fork<Ctx: Context> {
+    /// The light block of the primary chain.
+    pub primary: LightBlock<Ctx>,
+
+    /// The conflicting light block of the witness chain.
+    pub witness: LightBlock<Ctx>,
+}
+
+impl<Ctx: Context> Fork<Ctx> {
+    /// Create a new fork out of two conflicting light blocks.
+    pub fn new(primary: LightBlock<Ctx>, witness: LightBlock<Ctx>) -> Self {
+        Self { primary, witness }
+    }
+
+    /// The height at which the two chains diverge.
+    pub fn height(&self) -> Ctx::Height {
+        self.primary.height()
+    }
+
+    /// The addresses of the validators which signed both conflicting certificates.
+    ///
+    /// If both certificates are for the same round, these validators equivocated.
+    /// Otherwise, at least some of them voted in violation of their lock.
+    pub fn conflicting_signers(&self) -> Vec<&Ctx::Address> {
+        let witness_signers = self.witness.signers();
+
+        let mut signers: Vec<_> = self
+            .primary
+            .signers()
+            .into_iter()
+            .filter(|address| witness_signers.contains(address))
+            .collect();
+
+        signers.sort();
+        signers.dedup();
+        signers
+    }
+}
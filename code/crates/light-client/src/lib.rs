@@ -0,0 +1,58 @@
+//! Light-client verification of chains of commit certificates.
+//!
+//! A light client tracks a chain without running a full node, by only verifying the
+//! [`CommitCertificate`](malachitebft_core_types::CommitCertificate) of the heights it
+//! is interested in, against the validator sets it has learned to trust along the way.
+//!
+//! Starting from a trusted [`LightBlock`], the [`Verifier`] can verify:
+//! - the next light block, whose validator set must be the next validator set of the trusted one
+//!   (sequential verification),
+//! - any later light block, as long as more than the trust threshold (1/3 by default) of the voting
+//!   power of the trusted next validator set signed its certificate (skipping verification).
+//!
+//! When skipping verification fails for lack of trust, [`Verifier::verify_to_height`] bisects
+//! the range of heights, fetching intermediate light blocks from a [`Provider`],
+//! as in Tendermint light clients.
+//!
+//! Finally, [`Verifier::detect_fork`] compares two chains of light blocks, eg. obtained from
+//! a primary and a witness node, and reports a [`Fork`] if they both verify but decided on
+//! different values at the same height.
+//!
+//! # Validator sets
+//! Malachite does not impose any structure on decided values, it is therefore up to the application
+//! to tie a light block to the validator sets it carries, by providing an implementation of
+//! [`ValidatorSetCommitment`], typically by checking that a header committed to by the decided
+//! value contains the hashes of these validator sets.
+//!
+//! # Trusting period
+//! Commit certificates do not carry a timestamp, so this crate does not enforce a trusting period.
+//! Applications must make sure that the validators of a trusted light block are still bonded,
+//! and hence accountable, when using it to verify later light blocks.
+
+#![no_std]
+#![forbid(unsafe_code)]
+#![deny(trivial_casts, trivial_numeric_casts)]
+#![warn(
+    missing_docs,
+    rustdoc::broken_intra_doc_links,
+    rustdoc::private_intra_doc_links,
+    variant_size_differences
+)]
+#![cfg_attr(not(test), deny(clippy::unwrap_used, clippy::panic))]
+
+extern crate alloc;
+
+mod error;
+pub use error::Error;
+
+mod fork;
+pub use fork::Fork;
+
+mod light_block;
+pub use light_block::{LightBlock, ValidatorSetCommitment};
+
+mod provider;
+pub use provider::Provider;
+
+mod verifier;
+pub use verifier::{Options, Verifier};
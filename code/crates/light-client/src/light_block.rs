@@ -0,0 +1,85 @@
+use alloc::vec::Vec;
+
+use derive_where::derive_where;
+
+use malachitebft_core_types::{CommitCertificate, Context, Validator, ValidatorSet, ValueId};
+
+/// A decided height, as seen by a light client.
+#[derive_where(Clone, Debug, PartialEq, Eq)]
+pub struct LightBlock<Ctx: Context> {
+    /// The certificate for the value decided at that height.
+    pub certificate: CommitCertificate<Ctx>,
+
+    /// The validator set which decided on the value at that height.
+    pub validator_set: Ctx::ValidatorSet,
+
+    /// The validator set for the next height.
+    pub next_validator_set: Ctx::ValidatorSet,
+}
+
+impl<Ctx: Context> LightBlock<Ctx> {
+    /// Create a new light block.
+    pub fn new(
+        certificate: CommitCertificate<Ctx>,
+        validator_set: Ctx::ValidatorSet,
+        next_validator_set: Ctx::ValidatorSet,
+    ) -> Self {
+        Self {
+            certificate,
+            validator_set,
+            next_validator_set,
+        }
+    }
+
+    /// The height of this light block.
+    pub fn height(&self) -> Ctx::Height {
+        self.certificate.height
+    }
+
+    /// The ID of the value decided at that height.
+    pub fn value_id(&self) -> &ValueId<Ctx> {
+        &self.certificate.value_id
+    }
+
+    /// The addresses of the validators which signed the certificate,
+    /// as found in the validator set of this light block if the certificate is aggregated.
+    pub fn signers(&self) -> Vec<&Ctx::Address> {
+        match &self.certificate.aggregated_signature {
+            Some(aggregated) => aggregated
+                .signers
+                .iter()
+                .filter_map(|index| self.validator_set.get_by_index(index))
+                .map(|validator| validator.address())
+                .collect(),
+            None => self
+                .certificate
+                .commit_signatures
+                .iter()
+                .map(|sig| &sig.address)
+                .collect(),
+        }
+    }
+}
+
+/// Application-specific check that the value decided in a light block
+/// commits to the validator sets carried by that light block.
+///
+/// Without this check, anybody could make up a validator set for a light block,
+/// and have the light client trust it from then on.
+///
+/// This trait is implemented for any `Fn(&LightBlock<Ctx>) -> bool`.
+pub trait ValidatorSetCommitment<Ctx: Context> {
+    /// Check that the value decided in the given light block commits to both
+    /// the validator set and the next validator set of that light block.
+    fn verify(&self, light_block: &LightBlock<Ctx>) -> bool;
+}
+
+impl<Ctx, F> ValidatorSetCommitment<Ctx> for F
+where
+    Ctx: Context,
+    F: Fn(&LightBlock<Ctx>) -> bool,
+{
+    fn verify(&self, light_block: &LightBlock<Ctx>) -> bool {
+        self(light_block)
+    }
+}
@@ -0,0 +1,19 @@
+use alloc::boxed::Box;
+
+use async_trait::async_trait;
+
+use malachitebft_core_types::{BoxError, Context};
+
+use crate::LightBlock;
+
+/// A source of light blocks, eg. a full node queried over RPC.
+///
+/// Light blocks returned by a provider are untrusted, and are verified by the [`Verifier`](crate::Verifier).
+#[async_trait]
+pub trait Provider<Ctx>
+where
+    Ctx: Context,
+{
+    /// Fetch the light block at the given height.
+    async fn light_block(&self, height: Ctx::Height) -> Result<LightBlock<Ctx>, BoxError>;
+}
@@ -0,0 +1,307 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use malachitebft_core_types::{
+    Context, Height, ThresholdParam, ThresholdParams, Validator, ValidatorSet,
+};
+use malachitebft_signing::{SigningProvider, SigningProviderExt};
+
+use crate::{Error, Fork, LightBlock, Provider, ValidatorSetCommitment};
+
+/// Light client verification options.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Options {
+    /// Fraction of the voting power of a trusted validator set which must have signed
+    /// the certificate of a later light block to skip verification up to that light block.
+    ///
+    /// Must be at least 1/3 (the default), to ensure that at least one correct validator
+    /// of the trusted validator set vouches for the untrusted light block.
+    pub trust_threshold: ThresholdParam,
+
+    /// The quorum and honest thresholds used by consensus.
+    pub threshold_params: ThresholdParams,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            trust_threshold: ThresholdParam::F_PLUS_ONE,
+            threshold_params: ThresholdParams::default(),
+        }
+    }
+}
+
+/// Verifies light blocks against light blocks which are already trusted.
+pub struct Verifier<Ctx, P, C> {
+    ctx: Ctx,
+    signing_provider: P,
+    commitment: C,
+    options: Options,
+}
+
+impl<Ctx, P, C> Verifier<Ctx, P, C>
+where
+    Ctx: Context,
+    P: SigningProvider<Ctx>,
+    C: ValidatorSetCommitment<Ctx>,
+{
+    /// Create a new verifier.
+    ///
+    /// The signing provider is only used to verify signatures,
+    /// it does not need to have access to a private key.
+    pub fn new(ctx: Ctx, signing_provider: P, commitment: C, options: Options) -> Self {
+        Self {
+            ctx,
+            signing_provider,
+            commitment,
+            options,
+        }
+    }
+
+    /// The verification options.
+    pub fn options(&self) -> &Options {
+        &self.options
+    }
+
+    /// Verify the untrusted light block against the trusted one.
+    ///
+    /// If the untrusted light block is right above the trusted one, its validator set must be
+    /// the next validator set of the trusted light block. Otherwise, more than the trust threshold
+    /// of the voting power of the next validator set of the trusted light block must have signed
+    /// its certificate, failing which [`Error::NotEnoughTrust`] is returned.
+    ///
+    /// In both cases, the certificate must be signed by a quorum of the validator set
+    /// of the untrusted light block, and the decided value must commit to its validator sets.
+    pub async fn verify(
+        &self,
+        trusted: &LightBlock<Ctx>,
+        untrusted: &LightBlock<Ctx>,
+    ) -> Result<(), Error<Ctx>> {
+        let height = untrusted.height();
+
+        if height <= trusted.height() {
+            return Err(Error::NonIncreasingHeight {
+                trusted: trusted.height(),
+                untrusted: height,
+            });
+        }
+
+        if !self.commitment.verify(untrusted) {
+            return Err(Error::InvalidValidatorSetCommitment(height));
+        }
+
+        if height == trusted.height().increment() {
+            if untrusted.validator_set != trusted.next_validator_set {
+                return Err(Error::ValidatorSetMismatch(height));
+            }
+        } else {
+            self.verify_trust(&trusted.next_validator_set, untrusted)
+                .await?;
+        }
+
+        self.signing_provider
+            .verify_commit_certificate(
+                &self.ctx,
+                &untrusted.certificate,
+                &untrusted.validator_set,
+                self.options.threshold_params,
+            )
+            .await
+            .map_err(|e| Error::InvalidCertificate(height, e))
+    }
+
+    /// Check that more than the trust threshold of the voting power of the trusted
+    /// validator set signed the certificate of the untrusted light block.
+    async fn verify_trust(
+        &self,
+        trusted_validator_set: &Ctx::ValidatorSet,
+        untrusted: &LightBlock<Ctx>,
+    ) -> Result<(), Error<Ctx>> {
+        let certificate = &untrusted.certificate;
+
+        let mut signed = 0;
+        let mut seen = Vec::new();
+
+        // The aggregated signature of the certificate is verified against the untrusted
+        // validator set afterwards, so we only need to count the voting power of the signers
+        // which are also part of the trusted validator set, with the same public key.
+        if let Some(aggregated) = &certificate.aggregated_signature {
+            for index in aggregated.signers.iter() {
+                let Some(signer) = untrusted.validator_set.get_by_index(index) else {
+                    continue;
+                };
+
+                if let Some(validator) = trusted_validator_set.get_by_address(signer.address()) {
+                    if validator.public_key() == signer.public_key() {
+                        signed += validator.voting_power();
+                    }
+                }
+            }
+        }
+
+        for commit_sig in &certificate.commit_signatures {
+            if seen.contains(&&commit_sig.address) {
+                continue;
+            }
+
+            seen.push(&commit_sig.address);
+
+            let Some(validator) = trusted_validator_set.get_by_address(&commit_sig.address) else {
+                continue;
+            };
+
+            if let Ok(voting_power) = self
+                .signing_provider
+                .verify_commit_signature(&self.ctx, certificate, commit_sig, validator)
+                .await
+            {
+                signed += voting_power;
+            }
+        }
+
+        let total = trusted_validator_set.total_voting_power();
+        let threshold = self.options.trust_threshold;
+
+        if threshold.is_met(signed, total) {
+            Ok(())
+        } else {
+            Err(Error::NotEnoughTrust {
+                height: untrusted.height(),
+                signed,
+                total,
+                expected: threshold.min_expected(total),
+            })
+        }
+    }
+
+    /// Verify a sequence of light blocks of increasing heights, each one against the last one
+    /// that was verified, starting from the given trusted light block.
+    ///
+    /// Heights may be skipped, in which case skipping verification applies.
+    ///
+    /// ## Return
+    /// Return the last verified light block, which can be trusted from then on.
+    pub async fn verify_chain<'a>(
+        &self,
+        trusted: &'a LightBlock<Ctx>,
+        light_blocks: &'a [LightBlock<Ctx>],
+    ) -> Result<&'a LightBlock<Ctx>, Error<Ctx>> {
+        let mut trusted = trusted;
+
+        for light_block in light_blocks {
+            self.verify(trusted, light_block).await?;
+            trusted = light_block;
+        }
+
+        Ok(trusted)
+    }
+
+    /// Verify the light block at the target height, fetching it from the given provider.
+    ///
+    /// Verification first attempts to skip directly from the trusted light block to the target.
+    /// If there is not enough trust to do so, the light block halfway between the two is fetched
+    /// and verified first, recursively, until the target can be reached.
+    ///
+    /// ## Return
+    /// Return the light blocks that were verified to reach the target, ordered by height
+    /// and ending with the light block at the target height.
+    pub async fn verify_to_height(
+        &self,
+        provider: &dyn Provider<Ctx>,
+        trusted: LightBlock<Ctx>,
+        target: Ctx::Height,
+    ) -> Result<Vec<LightBlock<Ctx>>, Error<Ctx>> {
+        if target <= trusted.height() {
+            return Err(Error::NonIncreasingHeight {
+                trusted: trusted.height(),
+                untrusted: target,
+            });
+        }
+
+        let mut trace = Vec::new();
+        let mut trusted = trusted;
+        let mut pending = vec![fetch(provider, target).await?];
+
+        while let Some(untrusted) = pending.last() {
+            match self.verify(&trusted, untrusted).await {
+                Ok(()) => {
+                    if let Some(verified) = pending.pop() {
+                        trace.push(verified.clone());
+                        trusted = verified;
+                    }
+                }
+
+                Err(Error::NotEnoughTrust { .. }) => {
+                    // Only happens when skipping, so there is at least one height in between
+                    let distance = untrusted.height().as_u64() - trusted.height().as_u64();
+                    let pivot = trusted.height().increment_by(distance / 2);
+
+                    pending.push(fetch(provider, pivot).await?);
+                }
+
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(trace)
+    }
+
+    /// Compare two chains of light blocks, eg. obtained from a primary and a witness node,
+    /// each one verified from the given trusted light block.
+    ///
+    /// ## Return
+    /// Return the first fork between the two chains, ie. the first height at which both chains
+    /// have a light block, deciding on different values. Return an error if either chain
+    /// fails to verify up to that height.
+    pub async fn detect_fork(
+        &self,
+        trusted: &LightBlock<Ctx>,
+        primary: &[LightBlock<Ctx>],
+        witness: &[LightBlock<Ctx>],
+    ) -> Result<Option<Fork<Ctx>>, Error<Ctx>> {
+        let (mut trusted_primary, mut trusted_witness) = (trusted, trusted);
+        let (mut primary, mut witness) = (primary.iter().peekable(), witness.iter().peekable());
+
+        while let (Some(p), Some(w)) = (primary.peek(), witness.peek()) {
+            let (p, w) = (*p, *w);
+
+            // Verify the light blocks in height order, so that the first fork is reported
+            if p.height() <= w.height() {
+                self.verify(trusted_primary, p).await?;
+                trusted_primary = p;
+                primary.next();
+            }
+
+            if w.height() <= p.height() {
+                self.verify(trusted_witness, w).await?;
+                trusted_witness = w;
+                witness.next();
+            }
+
+            if p.height() == w.height() && p.value_id() != w.value_id() {
+                return Ok(Some(Fork::new(p.clone(), w.clone())));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+async fn fetch<Ctx: Context>(
+    provider: &dyn Provider<Ctx>,
+    height: Ctx::Height,
+) -> Result<LightBlock<Ctx>, Error<Ctx>> {
+    let light_block = provider
+        .light_block(height)
+        .await
+        .map_err(|e| Error::Provider(height, e))?;
+
+    if light_block.height() != height {
+        return Err(Error::UnexpectedHeight {
+            expected: height,
+            actual: light_block.height(),
+        });
+    }
+
+    Ok(light_block)
+}
@@ -1,3 +1,4 @@
+use std::convert::Infallible;
 use std::time::Duration;
 
 use eyre::Result;
@@ -6,7 +7,7 @@ use libp2p::kad::{Addresses, KBucketKey, KBucketRef};
 use libp2p::request_response::{OutboundRequestId, ResponseChannel};
 use libp2p::swarm::behaviour::toggle::Toggle;
 use libp2p::swarm::NetworkBehaviour;
-use libp2p::{gossipsub, identify, ping};
+use libp2p::{allow_block_list, gossipsub, identify, ping};
 pub use libp2p::{Multiaddr, PeerId};
 use libp2p_broadcast as broadcast;
 
@@ -14,7 +15,7 @@ use malachitebft_discovery as discovery;
 use malachitebft_metrics::Registry;
 use malachitebft_sync as sync;
 
-use crate::{Config, GossipSubConfig};
+use crate::{gater, validator_mesh, Config, GossipSubConfig};
 #[derive(Debug)]
 pub enum NetworkEvent {
     Identify(Box<identify::Event>),
@@ -23,6 +24,13 @@ pub enum NetworkEvent {
     Broadcast(broadcast::Event),
     Sync(sync::Event),
     Discovery(Box<discovery::NetworkEvent>),
+    ValidatorMesh(validator_mesh::Event),
+}
+
+impl From<Infallible> for NetworkEvent {
+    fn from(event: Infallible) -> Self {
+        match event {}
+    }
 }
 
 impl From<identify::Event> for NetworkEvent {
@@ -55,6 +63,12 @@ impl From<sync::Event> for NetworkEvent {
     }
 }
 
+impl From<validator_mesh::Event> for NetworkEvent {
+    fn from(event: validator_mesh::Event) -> Self {
+        Self::ValidatorMesh(event)
+    }
+}
+
 impl From<discovery::NetworkEvent> for NetworkEvent {
     fn from(network_event: discovery::NetworkEvent) -> Self {
         Self::Discovery(Box::new(network_event))
@@ -64,12 +78,15 @@ impl From<discovery::NetworkEvent> for NetworkEvent {
 #[derive(NetworkBehaviour)]
 #[behaviour(to_swarm = "NetworkEvent")]
 pub struct Behaviour {
+    pub connection_gater: gater::Behaviour,
+    pub blocked_peers: allow_block_list::Behaviour<allow_block_list::BlockedPeers>,
     pub identify: identify::Behaviour,
     pub ping: ping::Behaviour,
     pub gossipsub: Toggle<gossipsub::Behaviour>,
     pub broadcast: Toggle<broadcast::Behaviour>,
     pub sync: Toggle<sync::Behaviour>,
     pub discovery: Toggle<discovery::Behaviour>,
+    pub validator_mesh: Toggle<validator_mesh::Behaviour>,
 }
 
 /// Dummy implementation of Debug for Behaviour.
@@ -90,6 +107,16 @@ impl discovery::DiscoveryClient for Behaviour {
             .add_address(peer, address)
     }
 
+    fn remove_peer(&mut self, peer: &PeerId) {
+        self.discovery
+            .as_mut()
+            .expect("Discovery behaviour should be available")
+            .kademlia
+            .as_mut()
+            .expect("Kademlia behaviour should be available")
+            .remove_peer(peer);
+    }
+
     fn kbuckets(&mut self) -> impl Iterator<Item = KBucketRef<'_, KBucketKey<PeerId>, Addresses>> {
         self.discovery
             .as_mut()
@@ -136,6 +163,7 @@ fn gossipsub_config(config: GossipSubConfig, max_transmit_size: usize) -> gossip
         .opportunistic_graft_ticks(3)
         .heartbeat_interval(Duration::from_secs(1))
         .validation_mode(gossipsub::ValidationMode::Strict)
+        .validate_messages()
         .history_gossip(3)
         .history_length(5)
         .mesh_n_high(config.mesh_n_high)
@@ -153,9 +181,10 @@ impl Behaviour {
         keypair: &Keypair,
         registry: &mut Registry,
     ) -> Result<Self> {
-        let identify = identify::Behaviour::new(identify::Config::new(
+        // Signed peer records are exchanged by the peer exchange bootstrap protocol
+        let identify = identify::Behaviour::new(identify::Config::new_with_signed_peer_record(
             config.protocol_names.consensus.clone(),
-            keypair.public(),
+            keypair,
         ));
 
         let ping = ping::Behaviour::new(ping::Config::new().with_interval(Duration::from_secs(5)));
@@ -205,13 +234,18 @@ impl Behaviour {
             None
         };
 
+        let validator_mesh = config.validator_mesh.then(validator_mesh::new_behaviour);
+
         Ok(Self {
+            connection_gater: gater::Behaviour::new(config.connection_gater.clone()),
+            blocked_peers: Default::default(),
             identify,
             ping,
             sync: Toggle::from(sync),
             gossipsub: Toggle::from(gossipsub),
             broadcast: Toggle::from(broadcast),
             discovery: Toggle::from(discovery),
+            validator_mesh: Toggle::from(validator_mesh),
         })
     }
 }
@@ -10,6 +10,7 @@ pub struct ChannelNames {
     pub proposal_parts: &'static str,
     pub sync: &'static str,
     pub liveness: &'static str,
+    pub evidence: &'static str,
 }
 
 impl Default for ChannelNames {
@@ -19,6 +20,7 @@ impl Default for ChannelNames {
             proposal_parts: "/proposal_parts",
             sync: "/sync",
             liveness: "/liveness",
+            evidence: "/evidence",
         }
     }
 }
@@ -29,6 +31,7 @@ pub enum Channel {
     Liveness,
     ProposalParts,
     Sync,
+    Evidence,
 }
 
 impl Channel {
@@ -38,6 +41,7 @@ impl Channel {
             Channel::ProposalParts,
             Channel::Sync,
             Channel::Liveness,
+            Channel::Evidence,
         ]
     }
 
@@ -46,6 +50,7 @@ impl Channel {
             Channel::Consensus,
             Channel::ProposalParts,
             Channel::Liveness,
+            Channel::Evidence,
         ]
     }
 
@@ -63,6 +68,7 @@ impl Channel {
             Channel::ProposalParts => channel_names.proposal_parts,
             Channel::Sync => channel_names.sync,
             Channel::Liveness => channel_names.liveness,
+            Channel::Evidence => channel_names.evidence,
         }
     }
 
@@ -93,6 +99,8 @@ impl Channel {
             Some(Self::Sync)
         } else if topic == &Self::Liveness.to_gossipsub_topic(channel_names).hash() {
             Some(Self::Liveness)
+        } else if topic == &Self::Evidence.to_gossipsub_topic(channel_names).hash() {
+            Some(Self::Evidence)
         } else {
             None
         }
@@ -110,6 +118,8 @@ impl Channel {
             Some(Self::Sync)
         } else if topic == &Self::Liveness.to_broadcast_topic(channel_names) {
             Some(Self::Liveness)
+        } else if topic == &Self::Evidence.to_broadcast_topic(channel_names) {
+            Some(Self::Evidence)
         } else {
             None
         }
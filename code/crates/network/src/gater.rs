@@ -0,0 +1,297 @@
+//! Connection gating.
+//!
+//! Connections are denied as early as possible: connections from denied IP addresses are refused
+//! before the security handshake, and connections to or from denied peers are closed as soon as
+//! the handshake authenticates the peer, before any protocol runs on the connection.
+//!
+//! A peer or IP address is denied if it is in the corresponding deny list, or if the corresponding
+//! allow list is not empty and does not contain it. With empty lists, every connection is allowed.
+//! Since DNS names are resolved by the transport, addresses without an IP address are denied
+//! when the allowed IP ranges are not empty.
+//!
+//! The lists can be replaced at runtime, in which case the established connections which
+//! are not allowed anymore are closed.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::convert::Infallible;
+use std::net::IpAddr;
+use std::task::{Context, Poll, Waker};
+
+use ipnet::IpNet;
+use libp2p::core::transport::PortUse;
+use libp2p::core::Endpoint;
+use libp2p::multiaddr::Protocol;
+use libp2p::swarm::behaviour::ConnectionEstablished;
+use libp2p::swarm::{
+    dummy, CloseConnection, ConnectionClosed, ConnectionDenied, ConnectionId, FromSwarm,
+    NetworkBehaviour, THandler, THandlerInEvent, THandlerOutEvent, ToSwarm,
+};
+use libp2p::Multiaddr;
+use tracing::{debug, info};
+
+use malachitebft_peer::PeerId;
+
+use crate::PeerIdExt;
+
+/// Connection gating configuration.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Config {
+    /// Peers allowed to connect, or any peer if empty.
+    pub allowed_peers: HashSet<PeerId>,
+
+    /// Peers never allowed to connect.
+    pub denied_peers: HashSet<PeerId>,
+
+    /// IP ranges allowed to connect from or be dialed, or any IP address if empty.
+    pub allowed_ips: Vec<IpNet>,
+
+    /// IP ranges never allowed to connect from or be dialed.
+    pub denied_ips: Vec<IpNet>,
+}
+
+impl Config {
+    /// Check whether connections to or from the given peer are allowed.
+    pub fn check_peer(&self, peer_id: &PeerId) -> Result<(), Denied> {
+        if self.denied_peers.contains(peer_id) {
+            return Err(Denied::DeniedPeer(*peer_id));
+        }
+
+        if !self.allowed_peers.is_empty() && !self.allowed_peers.contains(peer_id) {
+            return Err(Denied::PeerNotAllowed(*peer_id));
+        }
+
+        Ok(())
+    }
+
+    /// Check whether connections to or from the given IP address are allowed.
+    pub fn check_ip(&self, ip: IpAddr) -> Result<(), Denied> {
+        if self.denied_ips.iter().any(|net| net.contains(&ip)) {
+            return Err(Denied::DeniedIp(ip));
+        }
+
+        if !self.allowed_ips.is_empty() && !self.allowed_ips.iter().any(|net| net.contains(&ip)) {
+            return Err(Denied::IpNotAllowed(ip));
+        }
+
+        Ok(())
+    }
+
+    /// Check whether connections to or from the given address are allowed.
+    ///
+    /// Addresses without an IP address, eg. DNS addresses, are only allowed
+    /// if the allowed IP ranges are empty.
+    pub fn check_addr(&self, addr: &Multiaddr) -> Result<(), Denied> {
+        match ip_of(addr) {
+            Some(ip) => self.check_ip(ip),
+            None if !self.allowed_ips.is_empty() => Err(Denied::NoIpAddress),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Why a connection was denied.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum Denied {
+    #[error("peer {0} is denied")]
+    DeniedPeer(PeerId),
+
+    #[error("peer {0} is not in the allowed peers")]
+    PeerNotAllowed(PeerId),
+
+    #[error("IP address {0} is denied")]
+    DeniedIp(IpAddr),
+
+    #[error("IP address {0} is not in the allowed IP ranges")]
+    IpNotAllowed(IpAddr),
+
+    #[error("address without an IP address is not in the allowed IP ranges")]
+    NoIpAddress,
+}
+
+fn ip_of(addr: &Multiaddr) -> Option<IpAddr> {
+    addr.iter().find_map(|protocol| match protocol {
+        Protocol::Ip4(ip) => Some(IpAddr::V4(ip)),
+        Protocol::Ip6(ip) => Some(IpAddr::V6(ip)),
+        _ => None,
+    })
+}
+
+/// Denies the connections which are not allowed by the configuration.
+#[derive(Debug, Default)]
+pub struct Behaviour {
+    config: Config,
+
+    /// Established connections, with the peer and remote address of each
+    connections: HashMap<ConnectionId, (libp2p::PeerId, Multiaddr)>,
+
+    /// Connections to close because they are not allowed anymore
+    close_connections: VecDeque<(libp2p::PeerId, ConnectionId)>,
+
+    waker: Option<Waker>,
+}
+
+impl Behaviour {
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Replace the configuration, closing the established connections which are not allowed anymore.
+    ///
+    /// Returns the number of connections which will be closed.
+    pub fn set_config(&mut self, config: Config) -> usize {
+        self.config = config;
+
+        let denied = self
+            .connections
+            .iter()
+            .filter_map(|(connection_id, (peer_id, addr))| {
+                let denied = self.enforce(peer_id, addr).err()?;
+                info!(%peer_id, %connection_id, "Closing connection: {denied}");
+                Some((*peer_id, *connection_id))
+            })
+            .collect::<Vec<_>>();
+
+        let count = denied.len();
+        self.close_connections.extend(denied);
+
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+
+        count
+    }
+
+    fn enforce(&self, peer_id: &libp2p::PeerId, addr: &Multiaddr) -> Result<(), Denied> {
+        self.config.check_peer(&PeerId::from_libp2p(peer_id))?;
+        self.config.check_addr(addr)
+    }
+}
+
+fn deny(denied: Denied) -> ConnectionDenied {
+    debug!("Denying connection: {denied}");
+    ConnectionDenied::new(denied)
+}
+
+impl NetworkBehaviour for Behaviour {
+    type ConnectionHandler = dummy::ConnectionHandler;
+    type ToSwarm = Infallible;
+
+    fn handle_pending_inbound_connection(
+        &mut self,
+        _: ConnectionId,
+        _: &Multiaddr,
+        remote_addr: &Multiaddr,
+    ) -> Result<(), ConnectionDenied> {
+        self.config.check_addr(remote_addr).map_err(deny)
+    }
+
+    fn handle_established_inbound_connection(
+        &mut self,
+        _: ConnectionId,
+        peer: libp2p::PeerId,
+        _: &Multiaddr,
+        remote_addr: &Multiaddr,
+    ) -> Result<THandler<Self>, ConnectionDenied> {
+        self.enforce(&peer, remote_addr).map_err(deny)?;
+
+        Ok(dummy::ConnectionHandler)
+    }
+
+    /// Deny dialing a denied peer, or a peer at denied addresses only.
+    ///
+    /// Behaviours cannot remove addresses from a dial, so a denied address dialed along with
+    /// allowed ones is only denied once the connection is established.
+    fn handle_pending_outbound_connection(
+        &mut self,
+        _: ConnectionId,
+        peer: Option<libp2p::PeerId>,
+        addresses: &[Multiaddr],
+        _: Endpoint,
+    ) -> Result<Vec<Multiaddr>, ConnectionDenied> {
+        if let Some(peer) = peer {
+            self.config
+                .check_peer(&PeerId::from_libp2p(&peer))
+                .map_err(deny)?;
+        }
+
+        let mut denied = None;
+
+        for addr in addresses {
+            match self.config.check_addr(addr) {
+                Ok(()) => return Ok(vec![]),
+                Err(e) => denied = Some(e),
+            }
+        }
+
+        match denied {
+            Some(denied) => Err(deny(denied)),
+            None => Ok(vec![]),
+        }
+    }
+
+    fn handle_established_outbound_connection(
+        &mut self,
+        _: ConnectionId,
+        peer: libp2p::PeerId,
+        addr: &Multiaddr,
+        _: Endpoint,
+        _: PortUse,
+    ) -> Result<THandler<Self>, ConnectionDenied> {
+        self.enforce(&peer, addr).map_err(deny)?;
+
+        Ok(dummy::ConnectionHandler)
+    }
+
+    fn on_swarm_event(&mut self, event: FromSwarm) {
+        match event {
+            FromSwarm::ConnectionEstablished(ConnectionEstablished {
+                peer_id,
+                connection_id,
+                endpoint,
+                ..
+            }) => {
+                self.connections.insert(
+                    connection_id,
+                    (peer_id, endpoint.get_remote_address().clone()),
+                );
+            }
+
+            FromSwarm::ConnectionClosed(ConnectionClosed { connection_id, .. }) => {
+                self.connections.remove(&connection_id);
+            }
+
+            _ => {}
+        }
+    }
+
+    fn on_connection_handler_event(
+        &mut self,
+        _: libp2p::PeerId,
+        _: ConnectionId,
+        event: THandlerOutEvent<Self>,
+    ) {
+        match event {}
+    }
+
+    fn poll(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<ToSwarm<Self::ToSwarm, THandlerInEvent<Self>>> {
+        if let Some((peer_id, connection_id)) = self.close_connections.pop_front() {
+            return Poll::Ready(ToSwarm::CloseConnection {
+                peer_id,
+                connection: CloseConnection::One(connection_id),
+            });
+        }
+
+        self.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
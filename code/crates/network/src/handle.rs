@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use bytes::Bytes;
 use libp2p::request_response::{InboundRequestId, OutboundRequestId};
 use tokio::sync::{mpsc, oneshot};
@@ -5,7 +7,8 @@ use tokio::task;
 
 use malachitebft_peer::PeerId;
 
-use crate::{Channel, CtrlMsg, Event};
+use crate::validator_mesh::Handshake;
+use crate::{Channel, ConnectionGaterConfig, CtrlMsg, Event, Misbehavior};
 
 pub struct RecvHandle {
     peer_id: PeerId,
@@ -68,6 +71,44 @@ impl CtrlHandle {
         Ok(())
     }
 
+    pub async fn report_peer(
+        &self,
+        peer_id: PeerId,
+        misbehavior: Misbehavior,
+    ) -> Result<(), eyre::Report> {
+        self.tx_ctrl
+            .send(CtrlMsg::ReportPeer(peer_id, misbehavior))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_validator_handshake(&self, handshake: Handshake) -> Result<(), eyre::Report> {
+        self.tx_ctrl
+            .send(CtrlMsg::SetValidatorHandshake(handshake))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_validator_peers(
+        &self,
+        validators: HashSet<PeerId>,
+    ) -> Result<(), eyre::Report> {
+        self.tx_ctrl
+            .send(CtrlMsg::SetValidatorPeers(validators))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_connection_gater(
+        &self,
+        config: ConnectionGaterConfig,
+    ) -> Result<(), eyre::Report> {
+        self.tx_ctrl
+            .send(CtrlMsg::SetConnectionGater(config))
+            .await?;
+        Ok(())
+    }
+
     pub async fn wait_shutdown(self) -> Result<(), eyre::Report> {
         self.shutdown().await?;
         self.join().await?;
@@ -1,7 +1,7 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
 use std::ops::ControlFlow;
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime};
 
 use futures::StreamExt;
 use libp2p::metrics::{Metrics, Recorder};
@@ -22,20 +22,40 @@ pub use bytes::Bytes;
 pub use libp2p::gossipsub::MessageId;
 pub use libp2p::identity::Keypair;
 pub use libp2p::Multiaddr;
+pub use malachitebft_discovery::{address_book, selection};
 
 pub mod behaviour;
+pub mod gater;
 pub mod handle;
 pub mod pubsub;
+pub mod rate_limit;
+pub mod reputation;
+pub mod validator_mesh;
 
 mod channel;
 pub use channel::{Channel, ChannelNames};
 
+use address_book::AddressBook;
 use behaviour::{Behaviour, NetworkEvent};
 use handle::Handle;
+use rate_limit::RateLimiter;
+use reputation::Reputation;
+use validator_mesh::ValidatorMesh;
+
+pub use reputation::Misbehavior;
 
 const METRICS_PREFIX: &str = "malachitebft_network";
 const DISCOVERY_METRICS_PREFIX: &str = "malachitebft_discovery";
 
+/// Interval at which peer scores recover and expired bans are lifted
+const REPUTATION_TICK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Interval at which dead entries are aged out of the address book, and the address book is persisted
+const ADDRESS_BOOK_TICK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Interval at which the rate limiter forgets about peers which have been quiet for a while
+const RATE_LIMIT_PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct ProtocolNames {
     pub consensus: String,
@@ -100,12 +120,20 @@ pub type BoxError = Box<dyn Error + Send + Sync + 'static>;
 pub type DiscoveryConfig = discovery::Config;
 pub type BootstrapProtocol = discovery::config::BootstrapProtocol;
 pub type Selector = discovery::config::Selector;
+pub type ReputationConfig = reputation::Config;
+pub type AddressBookConfig = address_book::Config;
+pub type RateLimitConfig = rate_limit::Config;
+
+pub type ConnectionGaterConfig = gater::Config;
 
 #[derive(Clone, Debug)]
 pub struct Config {
     pub listen_addr: Multiaddr,
     pub persistent_peers: Vec<Multiaddr>,
+    /// Peers which are never advertised to other peers, eg. validators behind a sentry node
+    pub private_peer_ids: Vec<PeerId>,
     pub discovery: DiscoveryConfig,
+    pub address_book: AddressBookConfig,
     pub idle_connection_timeout: Duration,
     pub transport: TransportProtocol,
     pub gossipsub: GossipSubConfig,
@@ -116,6 +144,14 @@ pub struct Config {
     pub enable_consensus: bool,
     pub enable_sync: bool,
     pub protocol_names: ProtocolNames,
+    pub reputation: ReputationConfig,
+    pub rate_limits: RateLimitConfig,
+    /// Peers and IP addresses allowed or denied to connect
+    pub connection_gater: ConnectionGaterConfig,
+    /// Restrict gossip on the consensus channels to the peers which belong to a validator
+    pub validator_mesh: bool,
+    /// Sentry nodes allowed to gossip on the channels restricted by the validator mesh
+    pub sentry_peer_ids: Vec<PeerId>,
 }
 
 impl Config {
@@ -169,6 +205,8 @@ pub enum Event {
     ConsensusMessage(Channel, PeerId, Bytes),
     LivenessMessage(Channel, PeerId, Bytes),
     Sync(sync::RawMessage),
+    /// A peer sent us a handshake proving that it belongs to a validator
+    ValidatorHandshake(PeerId, validator_mesh::Handshake),
 }
 
 #[derive(Debug)]
@@ -177,6 +215,10 @@ pub enum CtrlMsg {
     Broadcast(Channel, Bytes),
     SyncRequest(PeerId, Bytes, oneshot::Sender<OutboundRequestId>),
     SyncReply(InboundRequestId, Bytes),
+    ReportPeer(PeerId, Misbehavior),
+    SetValidatorHandshake(validator_mesh::Handshake),
+    SetValidatorPeers(HashSet<PeerId>),
+    SetConnectionGater(ConnectionGaterConfig),
     Shutdown,
 }
 
@@ -184,13 +226,24 @@ pub enum CtrlMsg {
 pub struct State {
     pub sync_channels: HashMap<InboundRequestId, sync::ResponseChannel>,
     pub discovery: discovery::Discovery<Behaviour>,
+    pub reputation: Reputation,
+    pub rate_limiter: RateLimiter,
+    pub validator_mesh: ValidatorMesh,
 }
 
 impl State {
-    fn new(discovery: discovery::Discovery<Behaviour>) -> Self {
+    fn new(
+        discovery: discovery::Discovery<Behaviour>,
+        reputation: Reputation,
+        rate_limiter: RateLimiter,
+        validator_mesh: ValidatorMesh,
+    ) -> Self {
         Self {
             sync_channels: Default::default(),
             discovery,
+            reputation,
+            rate_limiter,
+            validator_mesh,
         }
     }
 }
@@ -235,11 +288,41 @@ pub async fn spawn(
     let (tx_event, rx_event) = mpsc::channel(32);
     let (tx_ctrl, rx_ctrl) = mpsc::channel(32);
 
+    let address_book = AddressBook::load(config.address_book.clone(), SystemTime::now())
+        .unwrap_or_else(|e| {
+            warn!("Failed to load address book, starting with an empty one: {e}");
+            AddressBook::new(config.address_book.clone())
+        });
+
     let discovery = registry.with_prefix(DISCOVERY_METRICS_PREFIX, |reg| {
-        discovery::Discovery::new(config.discovery, config.persistent_peers.clone(), reg)
+        discovery::Discovery::new(
+            config.discovery,
+            config.persistent_peers.clone(),
+            config
+                .private_peer_ids
+                .iter()
+                .map(PeerIdExt::to_libp2p)
+                .collect(),
+            address_book,
+            reg,
+        )
     });
 
-    let state = State::new(discovery);
+    let reputation =
+        Reputation::load(config.reputation.clone(), SystemTime::now()).unwrap_or_else(|e| {
+            warn!("Failed to load peer bans, starting without any: {e}");
+            Reputation::new(config.reputation.clone())
+        });
+
+    let rate_limiter = RateLimiter::new(config.rate_limits);
+
+    if config.validator_mesh && !config.pubsub_protocol.is_gossipsub() {
+        warn!("Validator mesh is only fully enforced with GossipSub, other peers may still receive consensus messages");
+    }
+
+    let validator_mesh = ValidatorMesh::new(config.sentry_peer_ids.iter().copied());
+
+    let state = State::new(discovery, reputation, rate_limiter, validator_mesh);
 
     let peer_id = PeerId::from_libp2p(swarm.local_peer_id());
     let span = error_span!("network");
@@ -289,6 +372,32 @@ async fn run(
         };
     }
 
+    // Deny connections to and from the peers which were banned before a restart
+    for (peer_id, _) in state.reputation.banned_peers() {
+        swarm
+            .behaviour_mut()
+            .blocked_peers
+            .block_peer(peer_id.to_libp2p());
+    }
+
+    // Timer to periodically recover peer scores and lift expired bans
+    let mut reputation_timer = tokio::time::interval(REPUTATION_TICK_INTERVAL);
+
+    // Rejoin the network through the peers known from previous runs
+    state.discovery.dial_address_book(&mut swarm);
+
+    // Timer to periodically age out dead entries of the address book and persist it
+    let mut address_book_timer = tokio::time::interval(ADDRESS_BOOK_TICK_INTERVAL);
+
+    // Timer to periodically sample peer records from connected peers, with peer exchange,
+    // unless disabled with a zero interval, which `interval` does not accept
+    let pex_enabled = !config.discovery.pex_interval.is_zero();
+    let mut pex_timer =
+        tokio::time::interval(config.discovery.pex_interval.max(Duration::from_secs(1)));
+
+    // Timer to periodically drop the rate limiting state of quiet peers
+    let mut rate_limit_timer = tokio::time::interval(RATE_LIMIT_PRUNE_INTERVAL);
+
     // Timer to periodically try reconnecting to persistent peers
     // TODO: Using 1 second for now, for faster reconnection during testing
     // Maybe adjust via config in the future
@@ -320,10 +429,44 @@ async fn run(
                 ControlFlow::Continue(())
             }
 
+            Some((peer_id, addr)) = state.discovery.controller.probe.recv(), if state.discovery.can_probe() => {
+                state.discovery.probe_addr(&mut swarm, peer_id, addr);
+                ControlFlow::Continue(())
+            }
+
+            Some(()) = state.discovery.controller.peers_response.recv() => {
+                state.discovery.respond_to_deferred_peers_requests(&mut swarm);
+                ControlFlow::Continue(())
+            }
+
             Some(ctrl) = rx_ctrl.recv() => {
                 handle_ctrl_msg(&mut swarm, &mut state, &config, ctrl).await
             }
 
+            _ = reputation_timer.tick() => {
+                for peer_id in state.reputation.tick(REPUTATION_TICK_INTERVAL, SystemTime::now()) {
+                    info!(%peer_id, "Ban expired, unblocking peer");
+                    swarm.behaviour_mut().blocked_peers.unblock_peer(peer_id.to_libp2p());
+                }
+
+                ControlFlow::Continue(())
+            }
+
+            _ = address_book_timer.tick() => {
+                state.discovery.tick_address_book(SystemTime::now());
+                ControlFlow::Continue(())
+            }
+
+            _ = pex_timer.tick(), if pex_enabled => {
+                state.discovery.pex_tick(&swarm);
+                ControlFlow::Continue(())
+            }
+
+            _ = rate_limit_timer.tick() => {
+                state.rate_limiter.prune(Instant::now());
+                ControlFlow::Continue(())
+            }
+
             _ = persistent_peer_timer.tick() => {
                 // Periodically attempt to dial bootstrap nodes
                 state.discovery.dial_bootstrap_nodes(&swarm);
@@ -336,6 +479,8 @@ async fn run(
             ControlFlow::Break(()) => break,
         }
     }
+
+    state.discovery.persist_address_book();
 }
 
 async fn handle_ctrl_msg(
@@ -422,6 +567,55 @@ async fn handle_ctrl_msg(
             ControlFlow::Continue(())
         }
 
+        CtrlMsg::ReportPeer(peer_id, misbehavior) => {
+            let now = SystemTime::now();
+
+            if let Some(until) = state.reputation.report(peer_id, misbehavior, now) {
+                warn!(
+                    %peer_id,
+                    ?misbehavior,
+                    duration = ?until.duration_since(now).unwrap_or_default(),
+                    "Banning misbehaving peer"
+                );
+
+                // Closes all connections to the peer, and denies new ones until the ban expires
+                swarm
+                    .behaviour_mut()
+                    .blocked_peers
+                    .block_peer(peer_id.to_libp2p());
+            } else {
+                debug!(
+                    %peer_id,
+                    ?misbehavior,
+                    score = %state.reputation.score(&peer_id),
+                    "Reported misbehaving peer"
+                );
+            }
+
+            ControlFlow::Continue(())
+        }
+
+        CtrlMsg::SetValidatorHandshake(handshake) => {
+            state.validator_mesh.set_handshake(swarm, handshake);
+            ControlFlow::Continue(())
+        }
+
+        CtrlMsg::SetValidatorPeers(validators) => {
+            debug!(count = %validators.len(), "Updating validator peers");
+            state.validator_mesh.set_validators(validators);
+            ControlFlow::Continue(())
+        }
+
+        CtrlMsg::SetConnectionGater(gater_config) => {
+            let closed = swarm
+                .behaviour_mut()
+                .connection_gater
+                .set_config(gater_config);
+
+            info!(%closed, "Updated connection gating rules");
+            ControlFlow::Continue(())
+        }
+
         CtrlMsg::Shutdown => ControlFlow::Break(()),
     }
 }
@@ -454,10 +648,15 @@ async fn handle_swarm_event(
             peer_id,
             connection_id,
             endpoint,
+            num_established,
             ..
         } => {
             trace!("Connected to {peer_id} with connection id {connection_id}",);
 
+            if config.validator_mesh && num_established.get() == 1 {
+                state.validator_mesh.on_connection(swarm, peer_id);
+            }
+
             state
                 .discovery
                 .handle_connection(swarm, peer_id, connection_id, endpoint);
@@ -468,7 +667,12 @@ async fn handle_swarm_event(
             error,
             ..
         } => {
-            error!("Error dialing peer: {error}");
+            // Probes of unreachable addresses are expected to fail
+            if state.discovery.is_probe_in_progress(&connection_id) {
+                debug!("Error probing address of peer: {error}");
+            } else {
+                error!("Error dialing peer: {error}");
+            }
 
             state
                 .discovery
@@ -497,6 +701,8 @@ async fn handle_swarm_event(
                 .handle_closed_connection(swarm, peer_id, connection_id);
 
             if num_established == 0 {
+                state.validator_mesh.on_disconnection(&peer_id);
+
                 if let Err(e) = tx_event
                     .send(Event::PeerDisconnected(PeerId::from_libp2p(&peer_id)))
                     .await
@@ -560,6 +766,8 @@ async fn handle_swarm_event(
             match &event.result {
                 Ok(rtt) => {
                     trace!("Received pong from {} in {rtt:?}", event.peer);
+
+                    state.discovery.on_rtt(event.peer, *rtt);
                 }
                 Err(e) => {
                     trace!("Received pong from {} with error: {e}", event.peer);
@@ -582,6 +790,18 @@ async fn handle_swarm_event(
             return handle_sync_event(event, metrics, swarm, state, tx_event).await;
         }
 
+        SwarmEvent::Behaviour(NetworkEvent::ValidatorMesh(event)) => {
+            if let Some((peer_id, handshake)) = state.validator_mesh.on_event(swarm, event) {
+                if let Err(e) = tx_event
+                    .send(Event::ValidatorHandshake(peer_id, handshake))
+                    .await
+                {
+                    error!("Error sending validator handshake to handle: {e}");
+                    return ControlFlow::Break(());
+                }
+            }
+        }
+
         SwarmEvent::Behaviour(NetworkEvent::Discovery(network_event)) => {
             state.discovery.on_network_event(swarm, *network_event);
         }
@@ -598,8 +818,8 @@ async fn handle_gossipsub_event(
     event: gossipsub::Event,
     config: &Config,
     _metrics: &Metrics,
-    _swarm: &mut swarm::Swarm<Behaviour>,
-    _state: &mut State,
+    swarm: &mut swarm::Swarm<Behaviour>,
+    state: &mut State,
     tx_event: &mpsc::Sender<Event>,
 ) -> ControlFlow<()> {
     match event {
@@ -622,11 +842,24 @@ async fn handle_gossipsub_event(
         }
 
         gossipsub::Event::Message {
+            propagation_source,
             message_id,
             message,
-            ..
         } => {
-            let Some(peer_id) = message.source else {
+            // Messages are only forwarded to our mesh once they have been accepted here,
+            // so every early return below must report a validation result.
+            let report = |swarm: &mut swarm::Swarm<Behaviour>, acceptance| {
+                if let Some(gossipsub) = swarm.behaviour_mut().gossipsub.as_mut() {
+                    gossipsub.report_message_validation_result(
+                        &message_id,
+                        &propagation_source,
+                        acceptance,
+                    );
+                }
+            };
+
+            let Some(source) = message.source else {
+                report(swarm, gossipsub::MessageAcceptance::Reject);
                 return ControlFlow::Continue(());
             };
 
@@ -634,24 +867,55 @@ async fn handle_gossipsub_event(
                 Channel::from_gossipsub_topic_hash(&message.topic, config.channel_names)
             else {
                 trace!(
-                    "Received message {message_id} from {peer_id} on different channel: {}",
+                    "Received message {message_id} from {propagation_source} on different channel: {}",
                     message.topic
                 );
 
+                report(swarm, gossipsub::MessageAcceptance::Ignore);
                 return ControlFlow::Continue(());
             };
 
             trace!(
-                "Received message {message_id} from {peer_id} on channel {channel} of {} bytes",
+                "Received message {message_id} from {source} via {propagation_source} on channel {channel} of {} bytes",
                 message.data.len()
             );
 
-            let peer_id = PeerId::from_libp2p(&peer_id);
+            let source = PeerId::from_libp2p(&source);
+            let peer_id = PeerId::from_libp2p(&propagation_source);
+
+            // Messages from banned peers may still be relayed to us by other peers
+            let now = SystemTime::now();
+            if state.reputation.is_banned(&source, now) || state.reputation.is_banned(&peer_id, now)
+            {
+                trace!("Dropping message {message_id} from banned peer {source} via {peer_id}");
+                report(swarm, gossipsub::MessageAcceptance::Ignore);
+                return ControlFlow::Continue(());
+            }
+
+            if config.validator_mesh && !state.validator_mesh.is_allowed(&peer_id, channel) {
+                trace!("Dropping message {message_id} from non-validator {peer_id} on channel {channel}");
+                report(swarm, gossipsub::MessageAcceptance::Ignore);
+                return ControlFlow::Continue(());
+            }
+
+            // Later copies of a message are dropped by gossipsub as duplicates, so the limit applies
+            // to the peer which signed it rather than to the one which relayed it, otherwise a relay
+            // exceeding its limit would cause the messages of honest peers to be lost for good
+            if !state
+                .rate_limiter
+                .check(source, channel, message.data.len(), Instant::now())
+            {
+                debug!("Dropping message {message_id} from {source} exceeding the rate limit of channel {channel}");
+                report(swarm, gossipsub::MessageAcceptance::Ignore);
+                return ControlFlow::Continue(());
+            }
+
+            report(swarm, gossipsub::MessageAcceptance::Accept);
 
             let event = if channel == Channel::Liveness {
-                Event::LivenessMessage(channel, peer_id, Bytes::from(message.data))
+                Event::LivenessMessage(channel, source, Bytes::from(message.data))
             } else {
-                Event::ConsensusMessage(channel, peer_id, Bytes::from(message.data))
+                Event::ConsensusMessage(channel, source, Bytes::from(message.data))
             };
 
             if let Err(e) = tx_event.send(event).await {
@@ -683,7 +947,7 @@ async fn handle_broadcast_event(
     config: &Config,
     _metrics: &Metrics,
     _swarm: &mut swarm::Swarm<Behaviour>,
-    _state: &mut State,
+    state: &mut State,
     tx_event: &mpsc::Sender<Event>,
 ) -> ControlFlow<()> {
     match event {
@@ -718,6 +982,21 @@ async fn handle_broadcast_event(
 
             let peer_id = PeerId::from_libp2p(&peer_id);
 
+            if config.validator_mesh && !state.validator_mesh.is_allowed(&peer_id, channel) {
+                trace!("Dropping message from non-validator {peer_id} on channel {channel}");
+                return ControlFlow::Continue(());
+            }
+
+            if !state
+                .rate_limiter
+                .check(peer_id, channel, message.len(), Instant::now())
+            {
+                debug!(
+                    "Dropping message from {peer_id} exceeding the rate limit of channel {channel}"
+                );
+                return ControlFlow::Continue(());
+            }
+
             let event = if channel == Channel::Liveness {
                 Event::LivenessMessage(channel, peer_id, message)
             } else {
@@ -749,12 +1028,25 @@ async fn handle_sync_event(
                     request,
                     channel,
                 } => {
+                    let peer = PeerId::from_libp2p(&peer);
+
+                    // Dropping the response channel lets the peer know that its request failed
+                    if !state.rate_limiter.check(
+                        peer,
+                        Channel::Sync,
+                        request.0.len(),
+                        Instant::now(),
+                    ) {
+                        debug!("Dropping sync request {request_id} from {peer} exceeding the rate limit");
+                        return ControlFlow::Continue(());
+                    }
+
                     state.sync_channels.insert(request_id, channel);
 
                     let _ = tx_event
                         .send(Event::Sync(sync::RawMessage::Request {
                             request_id,
-                            peer: PeerId::from_libp2p(&peer),
+                            peer,
                             body: request.0,
                         }))
                         .await
@@ -0,0 +1,225 @@
+//! Per-peer inbound rate limiting.
+//!
+//! Each peer gets a budget of messages and bytes per second on every channel, which is
+//! replenished continuously and allows for bursts of up to one second worth of traffic.
+//! Messages received from a peer which has exhausted its budget on a channel are dropped
+//! before they reach the application, so that a single peer cannot flood the consensus
+//! or sync actors and starve the traffic of the other peers.
+//!
+//! Gossipsub messages are accounted to the peer which signed them, whichever peer relayed them,
+//! while broadcast messages and sync requests are accounted to the peer which sent them.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use malachitebft_peer::PeerId;
+
+use crate::Channel;
+
+/// Rate limit of a single channel, for each peer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RateLimit {
+    /// Maximum number of messages per second, or zero for no limit
+    pub messages_per_sec: u32,
+
+    /// Maximum number of bytes per second, or zero for no limit
+    pub bytes_per_sec: u64,
+}
+
+impl RateLimit {
+    /// No limit at all.
+    pub const UNLIMITED: Self = Self::new(0, 0);
+
+    pub const fn new(messages_per_sec: u32, bytes_per_sec: u64) -> Self {
+        Self {
+            messages_per_sec,
+            bytes_per_sec,
+        }
+    }
+}
+
+/// Rate limiting configuration.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Config {
+    /// Whether to rate limit inbound messages
+    pub enabled: bool,
+
+    /// Limit for votes and proposals
+    pub consensus: RateLimit,
+
+    /// Limit for proposal parts
+    pub proposal_parts: RateLimit,
+
+    /// Limit for status messages, and inbound sync requests
+    pub sync: RateLimit,
+
+    /// Limit for certificates and rebroadcast votes
+    pub liveness: RateLimit,
+
+    /// Limit for evidence of misbehavior
+    pub evidence: RateLimit,
+}
+
+impl Config {
+    /// The rate limit for the given channel.
+    pub fn limit(&self, channel: Channel) -> RateLimit {
+        match channel {
+            Channel::Consensus => self.consensus,
+            Channel::ProposalParts => self.proposal_parts,
+            Channel::Sync => self.sync,
+            Channel::Liveness => self.liveness,
+            Channel::Evidence => self.evidence,
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        const MIB: u64 = 1024 * 1024;
+
+        Self {
+            enabled: true,
+            consensus: RateLimit::new(200, 4 * MIB),
+            proposal_parts: RateLimit::new(2000, 64 * MIB),
+            sync: RateLimit::new(100, 4 * MIB),
+            liveness: RateLimit::new(100, 16 * MIB),
+            evidence: RateLimit::new(20, MIB),
+        }
+    }
+}
+
+/// A token bucket which holds up to one second worth of tokens.
+#[derive(Clone, Debug)]
+struct Bucket {
+    rate: f64,
+    tokens: f64,
+}
+
+impl Bucket {
+    fn new(rate: f64) -> Self {
+        Self { rate, tokens: rate }
+    }
+
+    fn refill(&mut self, elapsed: Duration) {
+        self.tokens = (self.tokens + self.rate * elapsed.as_secs_f64()).min(self.rate);
+    }
+
+    fn is_full(&self) -> bool {
+        self.tokens >= self.rate
+    }
+}
+
+/// The message and byte budgets of a peer on a channel.
+#[derive(Clone, Debug)]
+struct Budget {
+    messages: Option<Bucket>,
+    bytes: Option<Bucket>,
+    last_refill: Instant,
+}
+
+impl Budget {
+    fn new(limit: RateLimit, now: Instant) -> Self {
+        let bucket = |rate: f64| (rate > 0.0).then(|| Bucket::new(rate));
+
+        Self {
+            messages: bucket(limit.messages_per_sec as f64),
+            bytes: bucket(limit.bytes_per_sec as f64),
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.last_refill = now;
+
+        for bucket in [&mut self.messages, &mut self.bytes].into_iter().flatten() {
+            bucket.refill(elapsed);
+        }
+    }
+
+    /// A message is let through if there is a message token left and the byte budget is not
+    /// exhausted. Its size is then taken from the byte budget, which may go into debt, so that
+    /// messages larger than one second worth of bytes are not rejected forever.
+    fn try_consume(&mut self, bytes: usize) -> bool {
+        let has_message = self.messages.as_ref().is_none_or(|b| b.tokens >= 1.0);
+        let has_bytes = self.bytes.as_ref().is_none_or(|b| b.tokens > 0.0);
+
+        if !has_message || !has_bytes {
+            return false;
+        }
+
+        if let Some(messages) = &mut self.messages {
+            messages.tokens -= 1.0;
+        }
+
+        if let Some(b) = &mut self.bytes {
+            b.tokens -= bytes as f64;
+        }
+
+        true
+    }
+
+    fn is_full(&self) -> bool {
+        [&self.messages, &self.bytes]
+            .into_iter()
+            .flatten()
+            .all(Bucket::is_full)
+    }
+}
+
+/// Keeps track of the inbound traffic budget of every peer on every channel.
+#[derive(Debug)]
+pub struct RateLimiter {
+    config: Config,
+    budgets: HashMap<(PeerId, Channel), Budget>,
+}
+
+impl RateLimiter {
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            budgets: HashMap::new(),
+        }
+    }
+
+    /// The rate limiting configuration.
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Check whether a message of the given size received from the given peer on the given
+    /// channel is within the rate limit of that channel, and if so, account for it.
+    pub fn check(&mut self, peer_id: PeerId, channel: Channel, bytes: usize, now: Instant) -> bool {
+        if !self.config.enabled {
+            return true;
+        }
+
+        let limit = self.config.limit(channel);
+
+        if limit == RateLimit::UNLIMITED {
+            return true;
+        }
+
+        let budget = self
+            .budgets
+            .entry((peer_id, channel))
+            .or_insert_with(|| Budget::new(limit, now));
+
+        budget.refill(now);
+        budget.try_consume(bytes)
+    }
+
+    /// Forget about the peers whose budgets have been fully replenished by `now`,
+    /// as they are no different from peers we have not heard from yet.
+    pub fn prune(&mut self, now: Instant) {
+        self.budgets.retain(|_, budget| {
+            budget.refill(now);
+            !budget.is_full()
+        });
+    }
+
+    /// The number of peer and channel pairs currently tracked.
+    pub fn tracked(&self) -> usize {
+        self.budgets.len()
+    }
+}
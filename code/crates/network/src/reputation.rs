@@ -0,0 +1,249 @@
+//! Peer reputation and banning.
+//!
+//! Each peer starts with a score of zero, which decreases whenever the peer is reported for
+//! misbehaving, and slowly recovers over time. Once the score of a peer falls to the ban
+//! threshold, the peer is disconnected and banned for a while: connections to and from it
+//! are denied until the ban expires.
+//!
+//! Bans are persisted to a file, if configured, so that they survive restarts.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use malachitebft_discovery::file::write_atomically;
+use malachitebft_peer::PeerId;
+
+/// A kind of misbehavior a peer can be reported for.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Misbehavior {
+    /// The peer sent a message which could not be decoded.
+    InvalidMessage,
+
+    /// The peer sent a message with an invalid signature.
+    InvalidSignature,
+
+    /// The peer sent an invalid response to a sync request.
+    InvalidSyncResponse,
+
+    /// The peer sent a message on the wrong channel, or on behalf of another peer.
+    UnexpectedMessage,
+}
+
+impl Misbehavior {
+    /// The amount by which the score of a peer decreases when it is reported for this misbehavior.
+    pub fn penalty(&self) -> f64 {
+        match self {
+            Self::InvalidMessage => 20.0,
+            Self::InvalidSignature => 50.0,
+            Self::InvalidSyncResponse => 20.0,
+            Self::UnexpectedMessage => 10.0,
+        }
+    }
+}
+
+/// Peer reputation configuration.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Config {
+    /// Whether misbehaving peers are banned.
+    pub enabled: bool,
+
+    /// Score at or below which a peer is banned.
+    pub ban_threshold: f64,
+
+    /// How long a peer is banned for.
+    pub ban_duration: Duration,
+
+    /// Amount by which the score of a peer recovers every minute, up to zero.
+    pub score_recovery_per_minute: f64,
+
+    /// File in which bans are persisted across restarts, if any.
+    pub ban_file: Option<PathBuf>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ban_threshold: -100.0,
+            ban_duration: Duration::from_secs(60 * 60),
+            score_recovery_per_minute: 10.0,
+            ban_file: None,
+        }
+    }
+}
+
+/// A peer which is banned until the given time.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Ban {
+    peer_id: PeerId,
+    /// Seconds since the Unix epoch
+    until: u64,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct BanFile {
+    bans: Vec<Ban>,
+}
+
+/// Keeps track of the scores of peers, and of the peers which are banned.
+#[derive(Debug)]
+pub struct Reputation {
+    config: Config,
+    scores: HashMap<PeerId, f64>,
+    bans: HashMap<PeerId, SystemTime>,
+}
+
+impl Reputation {
+    /// Create an empty reputation tracker, which does not load bans from the ban file.
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            scores: HashMap::new(),
+            bans: HashMap::new(),
+        }
+    }
+
+    /// Create a reputation tracker with the bans persisted in the ban file, if any,
+    /// dropping those which have expired by `now`.
+    pub fn load(config: Config, now: SystemTime) -> io::Result<Self> {
+        let mut reputation = Self::new(config);
+
+        let Some(path) = &reputation.config.ban_file else {
+            return Ok(reputation);
+        };
+
+        let file: BanFile = match fs::read(path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => BanFile::default(),
+            Err(e) => return Err(e),
+        };
+
+        for ban in file.bans {
+            let until = UNIX_EPOCH + Duration::from_secs(ban.until);
+
+            if until > now {
+                reputation.bans.insert(ban.peer_id, until);
+            }
+        }
+
+        Ok(reputation)
+    }
+
+    /// The reputation configuration.
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// The current score of the given peer.
+    pub fn score(&self, peer_id: &PeerId) -> f64 {
+        self.scores.get(peer_id).copied().unwrap_or(0.0)
+    }
+
+    /// Whether the given peer is banned at the given time.
+    pub fn is_banned(&self, peer_id: &PeerId, now: SystemTime) -> bool {
+        self.bans.get(peer_id).is_some_and(|until| *until > now)
+    }
+
+    /// The peers which are currently banned, and until when.
+    pub fn banned_peers(&self) -> impl Iterator<Item = (&PeerId, &SystemTime)> {
+        self.bans.iter()
+    }
+
+    /// Report the given peer for misbehaving, decreasing its score.
+    ///
+    /// Returns the time until which the peer is banned, if this report got it banned.
+    pub fn report(
+        &mut self,
+        peer_id: PeerId,
+        misbehavior: Misbehavior,
+        now: SystemTime,
+    ) -> Option<SystemTime> {
+        if !self.config.enabled || self.is_banned(&peer_id, now) {
+            return None;
+        }
+
+        let score = self.scores.entry(peer_id).or_insert(0.0);
+        *score -= misbehavior.penalty();
+
+        if *score > self.config.ban_threshold {
+            return None;
+        }
+
+        let until = now + self.config.ban_duration;
+        self.ban(peer_id, until);
+
+        Some(until)
+    }
+
+    /// Ban the given peer until the given time, and persist the bans.
+    pub fn ban(&mut self, peer_id: PeerId, until: SystemTime) {
+        self.scores.remove(&peer_id);
+        self.bans.insert(peer_id, until);
+        self.persist();
+    }
+
+    /// Recover the scores of the peers by the amount corresponding to the elapsed time,
+    /// and lift the bans which have expired by `now`.
+    ///
+    /// Returns the peers which are no longer banned.
+    pub fn tick(&mut self, elapsed: Duration, now: SystemTime) -> Vec<PeerId> {
+        let recovery = self.config.score_recovery_per_minute * elapsed.as_secs_f64() / 60.0;
+
+        self.scores.retain(|_, score| {
+            *score = (*score + recovery).min(0.0);
+            *score < 0.0
+        });
+
+        let expired = self
+            .bans
+            .iter()
+            .filter(|(_, until)| **until <= now)
+            .map(|(peer_id, _)| *peer_id)
+            .collect::<Vec<_>>();
+
+        if !expired.is_empty() {
+            for peer_id in &expired {
+                self.bans.remove(peer_id);
+            }
+
+            self.persist();
+        }
+
+        expired
+    }
+
+    fn persist(&self) {
+        let Some(path) = &self.config.ban_file else {
+            return;
+        };
+
+        match save_bans(path, &self.bans) {
+            Ok(()) => info!(file = %path.display(), bans = %self.bans.len(), "Saved peer bans"),
+            Err(e) => warn!(file = %path.display(), "Failed to save peer bans: {e}"),
+        }
+    }
+}
+
+fn save_bans(path: &Path, bans: &HashMap<PeerId, SystemTime>) -> io::Result<()> {
+    let mut bans = bans
+        .iter()
+        .map(|(peer_id, until)| Ban {
+            peer_id: *peer_id,
+            until: until
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        })
+        .collect::<Vec<_>>();
+
+    bans.sort_by_key(|ban| ban.until);
+
+    let contents = serde_json::to_vec_pretty(&BanFile { bans })?;
+    write_atomically(path, &contents)
+}
@@ -0,0 +1,179 @@
+//! Validator-only gossip mesh.
+//!
+//! In this mode, gossip on the [`RESTRICTED_CHANNELS`] is restricted to the peers which have
+//! proven that they are operated by a validator of the current validator set. Right after
+//! connecting, each validator sends a handshake to the peer, holding a signature of its own peer ID
+//! made with its consensus key, along with the address of the validator whose key made it.
+//! Handshakes are verified by the consensus engine, which then lets the network know which peers
+//! belong to current validators.
+//!
+//! Messages received on a restricted channel from a peer which is not known to belong to a
+//! validator are ignored, and thus not forwarded to other peers. Other channels are left
+//! untouched. Full nodes may still receive votes and proposals from the validators they are
+//! connected to, but whatever they relay on the restricted channels is ignored.
+//!
+//! Sentry nodes are not validators themselves, so each node must list the sentries it is connected
+//! to as sentry peers to let the messages they relay through, ie. a validator lists its own sentries,
+//! and sentries list the validator behind them along with the sentries of other validators.
+
+use std::collections::HashSet;
+use std::iter;
+use std::time::Duration;
+
+use libp2p::request_response::{self, ProtocolSupport};
+use libp2p::swarm::Swarm;
+use libp2p::StreamProtocol;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, trace};
+
+use malachitebft_peer::PeerId;
+
+use crate::behaviour::Behaviour as NetworkBehaviour;
+use crate::{Channel, PeerIdExt};
+
+/// Name of the handshake protocol.
+pub const PROTOCOL_NAME: &str = "/malachitebft-validator-handshake/v1beta2";
+
+/// The channels which only validators may gossip on.
+pub const RESTRICTED_CHANNELS: &[Channel] = &[Channel::Consensus, Channel::ProposalParts];
+
+/// A handshake proving that the sender is operated by a validator.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Handshake {
+    /// Address of the validator, as displayed, so that the consensus engine
+    /// only has to verify the proof against the public key of that validator
+    pub validator: String,
+    /// Signature of the peer ID of the sender, made with the consensus key of the validator
+    pub proof: Vec<u8>,
+}
+
+/// Acknowledgment of a handshake.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HandshakeAck;
+
+pub type Behaviour = request_response::cbor::Behaviour<Handshake, HandshakeAck>;
+pub type Event = request_response::Event<Handshake, HandshakeAck>;
+
+pub fn new_behaviour() -> Behaviour {
+    Behaviour::new(
+        iter::once((StreamProtocol::new(PROTOCOL_NAME), ProtocolSupport::Full)),
+        request_response::Config::default().with_request_timeout(Duration::from_secs(5)),
+    )
+}
+
+/// Whether the given channel is restricted to validators.
+pub fn is_restricted(channel: Channel) -> bool {
+    RESTRICTED_CHANNELS.contains(&channel)
+}
+
+/// Keeps track of our own handshake, and of the peers which belong to current validators.
+#[derive(Debug, Default)]
+pub struct ValidatorMesh {
+    handshake: Option<Handshake>,
+    validators: HashSet<PeerId>,
+    /// Sentry nodes, which may gossip on the restricted channels without being validators
+    sentries: HashSet<PeerId>,
+    /// Peers which sent us a handshake since they connected,
+    /// as verifying handshakes is expensive and peers only need to send one.
+    handshaken: HashSet<PeerId>,
+}
+
+impl ValidatorMesh {
+    pub fn new(sentries: impl IntoIterator<Item = PeerId>) -> Self {
+        Self {
+            sentries: sentries.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Whether the given peer is known to belong to a current validator.
+    pub fn is_validator(&self, peer_id: &PeerId) -> bool {
+        self.validators.contains(peer_id)
+    }
+
+    /// Whether the given peer may gossip on the given channel,
+    /// ie. if the channel is not restricted, or if the peer is a validator or a sentry.
+    pub fn is_allowed(&self, peer_id: &PeerId, channel: Channel) -> bool {
+        !is_restricted(channel) || self.is_validator(peer_id) || self.sentries.contains(peer_id)
+    }
+
+    /// Send our handshake to a newly connected peer, if we are a validator.
+    pub fn on_connection(&self, swarm: &mut Swarm<NetworkBehaviour>, peer_id: libp2p::PeerId) {
+        if let Some(handshake) = &self.handshake {
+            send_handshake(swarm, &peer_id, handshake);
+        }
+    }
+
+    /// Forget about the handshake of a peer which is no longer connected.
+    pub fn on_disconnection(&mut self, peer_id: &libp2p::PeerId) {
+        self.handshaken.remove(&PeerId::from_libp2p(peer_id));
+    }
+
+    /// Set our own handshake, and send it to the peers we are already connected to.
+    pub fn set_handshake(&mut self, swarm: &mut Swarm<NetworkBehaviour>, handshake: Handshake) {
+        let peers = swarm.connected_peers().copied().collect::<Vec<_>>();
+
+        for peer_id in &peers {
+            send_handshake(swarm, peer_id, &handshake);
+        }
+
+        self.handshake = Some(handshake);
+    }
+
+    /// Set the peers which belong to current validators.
+    pub fn set_validators(&mut self, validators: HashSet<PeerId>) {
+        self.validators = validators;
+    }
+
+    /// Handle an event of the handshake protocol,
+    /// returning the peer which sent us a handshake, if any, along with that handshake.
+    pub fn on_event(
+        &mut self,
+        swarm: &mut Swarm<NetworkBehaviour>,
+        event: Event,
+    ) -> Option<(PeerId, Handshake)> {
+        match event {
+            request_response::Event::Message {
+                peer,
+                message:
+                    request_response::Message::Request {
+                        request, channel, ..
+                    },
+                ..
+            } => {
+                trace!(%peer, "Received validator handshake");
+
+                if let Some(handshake) = swarm.behaviour_mut().validator_mesh.as_mut() {
+                    let _ = handshake.send_response(channel, HandshakeAck);
+                }
+
+                let peer_id = PeerId::from_libp2p(&peer);
+
+                if !self.handshaken.insert(peer_id) {
+                    debug!(%peer, "Ignoring repeated validator handshake");
+                    return None;
+                }
+
+                Some((peer_id, request))
+            }
+
+            request_response::Event::OutboundFailure { peer, error, .. } => {
+                debug!(%peer, "Failed to send validator handshake: {error}");
+                None
+            }
+
+            _ => None,
+        }
+    }
+}
+
+fn send_handshake(
+    swarm: &mut Swarm<NetworkBehaviour>,
+    peer_id: &libp2p::PeerId,
+    handshake: &Handshake,
+) {
+    if let Some(behaviour) = swarm.behaviour_mut().validator_mesh.as_mut() {
+        trace!(%peer_id, "Sending validator handshake");
+        behaviour.send_request(peer_id, handshake.clone());
+    }
+}
@@ -1,13 +1,27 @@
 use core::fmt;
+use std::collections::HashSet;
+use std::iter;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use futures::StreamExt;
+use libp2p::kad::store::MemoryStore;
+use libp2p::request_response::{self, ProtocolSupport};
+use libp2p::swarm::{NetworkBehaviour, SwarmEvent};
+use libp2p::{identify, kad, Multiaddr, StreamProtocol, Swarm, SwarmBuilder};
 use libp2p_identity::PeerId;
 use malachitebft_config::TransportProtocol;
+use malachitebft_discovery::{Request, Response};
 use malachitebft_metrics::SharedRegistry;
-use malachitebft_network::{spawn, Config, DiscoveryConfig, Keypair, PeerIdExt, ProtocolNames};
+use malachitebft_network::address_book::{AddressBook, Source};
+use malachitebft_network::handle::{Handle, RecvHandle};
+use malachitebft_network::{
+    spawn, AddressBookConfig, Config, ConnectionGaterConfig, DiscoveryConfig, Keypair, PeerIdExt,
+    ProtocolNames,
+};
 use malachitebft_starknet_host::types::PrivateKey;
 use rand::{rngs::StdRng, Rng, SeedableRng};
-use tokio::time::sleep;
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, timeout};
 use tracing::{debug, info};
 
 //---------------------------------------------------------------------
@@ -110,6 +124,7 @@ pub struct Test<const N: usize> {
     spawn_delay: Duration,
     timeout: Duration,
     discovery_config: DiscoveryConfig,
+    home_dir: tempfile::TempDir,
 }
 
 impl<const N: usize> Test<N> {
@@ -128,6 +143,39 @@ impl<const N: usize> Test<N> {
             spawn_delay,
             timeout,
             discovery_config,
+            home_dir: tempfile::tempdir().unwrap(),
+        }
+    }
+
+    /// Listen address of the `i`-th node
+    pub fn listen_addr(&self, i: usize) -> malachitebft_network::Multiaddr {
+        TransportProtocol::Quic.multiaddr("127.0.0.1", self.consensus_base_port + i)
+    }
+
+    /// Peer ID of the `i`-th node
+    pub fn peer_id(&self, i: usize) -> PeerId {
+        PeerId::from_public_key(&self.keypairs[i].public())
+    }
+
+    /// Fill the address book of each node with the nodes it is expected to know from a previous run
+    fn write_address_books(&self, configs: &[Config; N]) {
+        for (node, config) in self.nodes.iter().zip(configs) {
+            if node.known_peers.is_empty() {
+                continue;
+            }
+
+            let mut address_book = AddressBook::new(config.address_book.clone());
+
+            for j in &node.known_peers {
+                address_book.record_seen(
+                    self.peer_id(*j),
+                    vec![self.listen_addr(*j)],
+                    Source::Discovered,
+                    SystemTime::now(),
+                );
+            }
+
+            address_book.persist();
         }
     }
 
@@ -146,16 +194,25 @@ impl<const N: usize> Test<N> {
 
     fn generate_default_configs(&self, discovery_config: DiscoveryConfig) -> [Config; N] {
         std::array::from_fn(|i| Config {
-            listen_addr: TransportProtocol::Quic
-                .multiaddr("127.0.0.1", self.consensus_base_port + i),
+            listen_addr: self.listen_addr(i),
             persistent_peers: self.nodes[i]
                 .bootstrap_nodes
                 .iter()
-                .map(|j| {
-                    TransportProtocol::Quic.multiaddr("127.0.0.1", self.consensus_base_port + *j)
-                })
+                .map(|j| self.listen_addr(*j))
                 .collect(),
-            discovery: discovery_config,
+            private_peer_ids: self.nodes[i]
+                .private_peers
+                .iter()
+                .map(|j| malachitebft_network::PeerId::from_libp2p(&self.peer_id(*j)))
+                .collect(),
+            discovery: DiscoveryConfig {
+                enabled: discovery_config.enabled && self.nodes[i].discovery_enabled,
+                ..discovery_config
+            },
+            address_book: AddressBookConfig {
+                file: Some(self.home_dir.path().join(format!("node-{i}.json"))),
+                ..Default::default()
+            },
             idle_connection_timeout: Duration::from_secs(60),
             transport: malachitebft_network::TransportProtocol::Quic,
             gossipsub: malachitebft_network::GossipSubConfig::default(),
@@ -166,16 +223,31 @@ impl<const N: usize> Test<N> {
             enable_consensus: true,
             enable_sync: false,
             protocol_names: ProtocolNames::default(),
+            reputation: Default::default(),
+            rate_limits: Default::default(),
+            connection_gater: ConnectionGaterConfig {
+                denied_peers: self.nodes[i]
+                    .denied_peers
+                    .iter()
+                    .map(|j| malachitebft_network::PeerId::from_libp2p(&self.peer_id(*j)))
+                    .collect(),
+                ..Default::default()
+            },
+            validator_mesh: false,
+            sentry_peer_ids: vec![],
         })
     }
 
-    pub async fn run(self) {
+    /// Spawn the nodes which are expected to start, without waiting for them to discover each other
+    pub async fn spawn(&self) -> Vec<Handle> {
         init_logging();
         info!("Starting test with {} nodes", N);
 
         let configs = self.generate_default_configs(self.discovery_config);
         debug!("Generated configs");
 
+        self.write_address_books(&configs);
+
         let mut handles = Vec::with_capacity(N);
 
         for (i, config) in configs.iter().enumerate().take(N) {
@@ -195,6 +267,12 @@ impl<const N: usize> Test<N> {
             }
         }
 
+        handles
+    }
+
+    pub async fn run(self) {
+        let handles = self.spawn().await;
+
         sleep(self.timeout).await;
 
         let mut tasks = Vec::with_capacity(N);
@@ -274,6 +352,10 @@ pub enum Fault {
 pub struct TestNode {
     _id: usize,
     bootstrap_nodes: Vec<usize>,
+    private_peers: Vec<usize>,
+    denied_peers: Vec<usize>,
+    known_peers: Vec<usize>,
+    discovery_enabled: bool,
     faults: Vec<Fault>,
 }
 
@@ -282,6 +364,10 @@ impl TestNode {
         Self {
             _id: id,
             bootstrap_nodes,
+            private_peers: Vec::new(),
+            denied_peers: Vec::new(),
+            known_peers: Vec::new(),
+            discovery_enabled: true,
             faults: Vec::new(),
         }
     }
@@ -290,10 +376,38 @@ impl TestNode {
         Self {
             _id: id,
             bootstrap_nodes,
+            private_peers: Vec::new(),
+            denied_peers: Vec::new(),
+            known_peers: Vec::new(),
+            discovery_enabled: true,
             faults,
         }
     }
 
+    /// Never advertise the given nodes to other peers, as a sentry does for its validators
+    pub fn with_private_peers(mut self, private_peers: Vec<usize>) -> Self {
+        self.private_peers = private_peers;
+        self
+    }
+
+    /// Deny connections to and from the given nodes
+    pub fn with_denied_peers(mut self, denied_peers: Vec<usize>) -> Self {
+        self.denied_peers = denied_peers;
+        self
+    }
+
+    /// Start this node with the given nodes in its address book, as if it was restarted
+    pub fn with_known_peers(mut self, known_peers: Vec<usize>) -> Self {
+        self.known_peers = known_peers;
+        self
+    }
+
+    /// Disable discovery on this node, as for a validator behind sentries
+    pub fn without_discovery(mut self) -> Self {
+        self.discovery_enabled = false;
+        self
+    }
+
     pub fn bootstrap_nodes(&self) -> &[usize] {
         &self.bootstrap_nodes
     }
@@ -303,6 +417,233 @@ impl TestNode {
     }
 }
 
+//---------------------------------------------------------------------
+// Bare peer
+//---------------------------------------------------------------------
+
+/// Protocols of a [`BarePeer`], which a node uses to identify peers and exchange peers with them
+#[derive(NetworkBehaviour)]
+pub struct BareBehaviour {
+    pub identify: identify::Behaviour,
+    pub kademlia: kad::Behaviour<MemoryStore>,
+    pub request_response: request_response::cbor::Behaviour<Request, Response>,
+}
+
+/// The peer record a [`BarePeer`] identifies with
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PeerRecord {
+    /// A peer record signed by the peer, as sent by nodes
+    Signed,
+    /// No signed peer record
+    Missing,
+    /// A peer record signed by another peer
+    SignedByOther,
+}
+
+/// A peer running libp2p directly instead of a node, to check how nodes handle peers
+/// which do not behave like nodes.
+pub struct BarePeer {
+    pub swarm: Swarm<BareBehaviour>,
+}
+
+impl BarePeer {
+    pub fn new(peer_record: PeerRecord) -> Self {
+        let keypair = Keypair::generate_ed25519();
+        let peer_id = keypair.public().to_peer_id();
+        let protocol_names = ProtocolNames::default();
+
+        let identify_config = match peer_record {
+            PeerRecord::Signed => {
+                identify::Config::new_with_signed_peer_record(protocol_names.consensus, &keypair)
+            }
+            PeerRecord::Missing => {
+                identify::Config::new(protocol_names.consensus, keypair.public())
+            }
+            PeerRecord::SignedByOther => identify::Config::new_with_signed_peer_record(
+                protocol_names.consensus,
+                &Keypair::generate_ed25519(),
+            ),
+        };
+
+        let mut kademlia = kad::Behaviour::with_config(
+            peer_id,
+            MemoryStore::new(peer_id),
+            kad::Config::new(StreamProtocol::try_from_owned(protocol_names.discovery_kad).unwrap()),
+        );
+        kademlia.set_mode(Some(kad::Mode::Server));
+
+        let request_response = request_response::cbor::Behaviour::new(
+            iter::once((
+                StreamProtocol::try_from_owned(protocol_names.discovery_regres).unwrap(),
+                ProtocolSupport::Full,
+            )),
+            request_response::Config::default(),
+        );
+
+        let swarm = SwarmBuilder::with_existing_identity(keypair)
+            .with_tokio()
+            .with_quic()
+            .with_behaviour(|_| BareBehaviour {
+                identify: identify::Behaviour::new(identify_config),
+                kademlia,
+                request_response,
+            })
+            .unwrap()
+            .with_swarm_config(|config| {
+                config.with_idle_connection_timeout(Duration::from_secs(60))
+            })
+            .build();
+
+        Self { swarm }
+    }
+
+    pub fn peer_id(&self) -> PeerId {
+        *self.swarm.local_peer_id()
+    }
+
+    /// Listen at the given address
+    pub async fn listen_on(&mut self, addr: Multiaddr) {
+        self.swarm.listen_on(addr).unwrap();
+
+        self.wait_for(|event| matches!(event, SwarmEvent::NewListenAddr { .. }).then_some(()))
+            .await
+    }
+
+    /// Connect to the given peer at the given address
+    pub async fn connect(&mut self, peer_id: PeerId, addr: Multiaddr) {
+        self.swarm.dial(addr).unwrap();
+
+        self.wait_for(|event| match event {
+            SwarmEvent::ConnectionEstablished { peer_id: id, .. } if id == peer_id => Some(()),
+            _ => None,
+        })
+        .await
+    }
+
+    /// Ask the given connected peer for its peers
+    pub async fn request_peers(
+        &mut self,
+        peer_id: PeerId,
+    ) -> HashSet<(Option<PeerId>, Vec<Multiaddr>)> {
+        let request_id = self
+            .swarm
+            .behaviour_mut()
+            .request_response
+            .send_request(&peer_id, Request::Peers(HashSet::new()));
+
+        self.wait_for(|event| match event {
+            SwarmEvent::Behaviour(BareBehaviourEvent::RequestResponse(
+                request_response::Event::Message {
+                    message:
+                        request_response::Message::Response {
+                            request_id: id,
+                            response: Response::Peers(peers),
+                        },
+                    ..
+                },
+            )) if id == request_id => Some(peers),
+            _ => None,
+        })
+        .await
+    }
+
+    /// Look up the closest peers to the given key through Kademlia, starting from the given peer
+    pub async fn closest_peers(
+        &mut self,
+        peer_id: PeerId,
+        addr: Multiaddr,
+        key: PeerId,
+    ) -> Vec<kad::PeerInfo> {
+        let kademlia = &mut self.swarm.behaviour_mut().kademlia;
+        kademlia.add_address(&peer_id, addr);
+        let query_id = kademlia.get_closest_peers(key);
+
+        self.wait_for(|event| match event {
+            SwarmEvent::Behaviour(BareBehaviourEvent::Kademlia(
+                kad::Event::OutboundQueryProgressed {
+                    id,
+                    result: kad::QueryResult::GetClosestPeers(result),
+                    ..
+                },
+            )) if id == query_id => match result {
+                Ok(ok) => Some(ok.peers),
+                Err(kad::GetClosestPeersError::Timeout { peers, .. }) => Some(peers),
+            },
+            _ => None,
+        })
+        .await
+    }
+
+    /// Handle the events of the swarm until one is mapped to some value by the given function
+    pub async fn wait_for<T>(
+        &mut self,
+        mut f: impl FnMut(SwarmEvent<BareBehaviourEvent>) -> Option<T>,
+    ) -> T {
+        let events = async {
+            loop {
+                if let Some(value) = f(self.swarm.select_next_some().await) {
+                    return value;
+                }
+            }
+        };
+
+        timeout(Duration::from_secs(10), events)
+            .await
+            .expect("Timed out waiting for swarm event")
+    }
+
+    /// Keep handling the events of the swarm in the background, eg. to accept connections
+    pub fn spawn(mut self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                self.swarm.select_next_some().await;
+            }
+        })
+    }
+}
+
+/// Wait until the node reports that it is connected to the given peer,
+/// and return whether it did before the given timeout.
+pub async fn wait_for_peer(events: &mut RecvHandle, peer_id: PeerId, within: Duration) -> bool {
+    wait_for_event(events, within, |event| match event {
+        malachitebft_network::Event::PeerConnected(id) => id.to_libp2p() == peer_id,
+        _ => false,
+    })
+    .await
+}
+
+/// Wait until the node reports that it is disconnected from the given peer,
+/// and return whether it did before the given timeout.
+pub async fn wait_for_disconnected_peer(
+    events: &mut RecvHandle,
+    peer_id: PeerId,
+    within: Duration,
+) -> bool {
+    wait_for_event(events, within, |event| match event {
+        malachitebft_network::Event::PeerDisconnected(id) => id.to_libp2p() == peer_id,
+        _ => false,
+    })
+    .await
+}
+
+async fn wait_for_event(
+    events: &mut RecvHandle,
+    within: Duration,
+    mut f: impl FnMut(&malachitebft_network::Event) -> bool,
+) -> bool {
+    let events = async {
+        while let Some(event) = events.recv().await {
+            if f(&event) {
+                return true;
+            }
+        }
+
+        false
+    };
+
+    timeout(within, events).await.unwrap_or(false)
+}
+
 //---------------------------------------------------------------------
 // Helpers
 //---------------------------------------------------------------------
@@ -0,0 +1,225 @@
+use std::time::{Duration, SystemTime};
+
+use libp2p_identity::PeerId;
+use malachitebft_network::address_book::{AddressBook, Config, Source};
+use malachitebft_network::Multiaddr;
+
+const HOUR: Duration = Duration::from_secs(60 * 60);
+
+fn config() -> Config {
+    Config {
+        file: None,
+        max_age: 24 * HOUR,
+        max_failures: 3,
+        max_entries: 10,
+    }
+}
+
+fn addr(port: u16) -> Multiaddr {
+    format!("/ip4/127.0.0.1/udp/{port}/quic-v1")
+        .parse()
+        .unwrap()
+}
+
+#[test]
+fn seen_peers_are_recorded() {
+    let now = SystemTime::now();
+    let peer = PeerId::random();
+    let mut address_book = AddressBook::new(config());
+
+    address_book.record_seen(peer, vec![addr(1000)], Source::Bootstrap, now);
+    address_book.record_failure(&peer);
+    address_book.record_seen(peer, vec![addr(1001)], Source::Inbound, now + HOUR);
+
+    let entry = address_book.get(&peer).unwrap();
+    assert_eq!(entry.addrs, vec![addr(1001)]);
+    assert_eq!(entry.failures, 0);
+
+    // Bootstrap nodes stay bootstrap nodes
+    assert_eq!(entry.source, Source::Bootstrap);
+
+    // Peers without any address are not recorded
+    address_book.record_seen(PeerId::random(), vec![], Source::Inbound, now);
+    assert_eq!(address_book.len(), 1);
+}
+
+#[test]
+fn unreachable_peers_are_removed() {
+    let now = SystemTime::now();
+    let peer = PeerId::random();
+    let mut address_book = AddressBook::new(config());
+
+    address_book.record_seen(peer, vec![addr(1000)], Source::Discovered, now);
+
+    assert!(!address_book.record_failure(&peer));
+    assert!(!address_book.record_failure(&peer));
+    assert_eq!(address_book.get(&peer).unwrap().failures, 2);
+
+    // Seeing the peer again resets its failures
+    address_book.record_seen(peer, vec![addr(1000)], Source::Discovered, now);
+    assert_eq!(address_book.get(&peer).unwrap().failures, 0);
+
+    assert!(!address_book.record_failure(&peer));
+    assert!(!address_book.record_failure(&peer));
+    assert!(address_book.record_failure(&peer));
+    assert!(address_book.is_empty());
+
+    // Unknown peers are ignored
+    assert!(!address_book.record_failure(&PeerId::random()));
+}
+
+#[test]
+fn old_peers_age_out() {
+    let now = SystemTime::now();
+    let (old, recent) = (PeerId::random(), PeerId::random());
+    let mut address_book = AddressBook::new(config());
+
+    address_book.record_seen(old, vec![addr(1000)], Source::Discovered, now);
+    address_book.record_seen(
+        recent,
+        vec![addr(1001)],
+        Source::Discovered,
+        now + 12 * HOUR,
+    );
+
+    assert_eq!(address_book.prune(now + 24 * HOUR), 0);
+    assert_eq!(address_book.prune(now + 25 * HOUR), 1);
+
+    assert!(address_book.get(&old).is_none());
+    assert!(address_book.get(&recent).is_some());
+}
+
+#[test]
+fn least_recently_seen_peers_are_dropped_when_full() {
+    let now = SystemTime::now();
+    let mut address_book = AddressBook::new(config());
+
+    let peers = (0..12).map(|_| PeerId::random()).collect::<Vec<_>>();
+    for (i, peer) in peers.iter().enumerate() {
+        let seen = now + Duration::from_secs(i as u64);
+        address_book.record_seen(*peer, vec![addr(1000 + i as u16)], Source::Inbound, seen);
+    }
+
+    assert_eq!(address_book.prune(now), 2);
+    assert_eq!(address_book.len(), 10);
+    assert!(address_book.get(&peers[0]).is_none());
+    assert!(address_book.get(&peers[1]).is_none());
+    assert!(address_book.get(&peers[2]).is_some());
+}
+
+#[test]
+fn inbound_peers_do_not_evict_dialed_peers() {
+    let now = SystemTime::now();
+    let mut address_book = AddressBook::new(config());
+
+    let bootstrap = PeerId::random();
+    address_book.record_seen(bootstrap, vec![addr(1000)], Source::Bootstrap, now);
+
+    let discovered = (0..4).map(|_| PeerId::random()).collect::<Vec<_>>();
+    for (i, peer) in discovered.iter().enumerate() {
+        address_book.record_seen(*peer, vec![addr(1001 + i as u16)], Source::Discovered, now);
+    }
+
+    // Many more recently seen inbound peers
+    for i in 0..10 {
+        let seen = now + Duration::from_secs(i + 1);
+        address_book.record_seen(
+            PeerId::random(),
+            vec![addr(2000 + i as u16)],
+            Source::Inbound,
+            seen,
+        );
+    }
+
+    assert_eq!(address_book.prune(now), 5);
+    assert!(address_book.get(&bootstrap).is_some());
+    assert!(discovered
+        .iter()
+        .all(|peer| address_book.get(peer).is_some()));
+}
+
+#[test]
+fn peers_from_crowded_subnets_are_evicted_first() {
+    let now = SystemTime::now();
+    let mut address_book = AddressBook::new(config());
+
+    let global_addr = |subnet: u8, host: u8| -> Multiaddr {
+        format!("/ip4/{subnet}.1.0.{host}/udp/27000/quic-v1")
+            .parse()
+            .unwrap()
+    };
+
+    // The least recently seen peer is alone in its subnet
+    let lone = PeerId::random();
+    address_book.record_seen(lone, vec![global_addr(8, 1)], Source::Inbound, now);
+
+    // While the others all come from the same subnet
+    let crowd = (0..10).map(|_| PeerId::random()).collect::<Vec<_>>();
+    for (i, peer) in crowd.iter().enumerate() {
+        let seen = now + Duration::from_secs(i as u64 + 1);
+        address_book.record_seen(*peer, vec![global_addr(9, i as u8)], Source::Inbound, seen);
+    }
+
+    assert_eq!(address_book.prune(now), 1);
+    assert!(address_book.get(&lone).is_some());
+    assert!(address_book.get(&crowd[0]).is_none());
+}
+
+#[test]
+fn seeds_prefer_reliable_and_recent_peers() {
+    let now = SystemTime::now();
+    let (flaky, old, recent) = (PeerId::random(), PeerId::random(), PeerId::random());
+    let mut address_book = AddressBook::new(config());
+
+    address_book.record_seen(flaky, vec![addr(1000)], Source::Discovered, now + 2 * HOUR);
+    address_book.record_seen(old, vec![addr(1001)], Source::Discovered, now);
+    address_book.record_seen(recent, vec![addr(1002)], Source::Discovered, now + HOUR);
+    address_book.record_failure(&flaky);
+
+    let seeds = |n| {
+        address_book
+            .seeds(n)
+            .iter()
+            .map(|entry| entry.peer_id)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(seeds(3), vec![recent, old, flaky]);
+    assert_eq!(seeds(1), vec![recent]);
+}
+
+#[test]
+fn address_book_is_persisted() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = Config {
+        file: Some(dir.path().join("network").join("address_book.json")),
+        ..config()
+    };
+
+    let now = SystemTime::now();
+    let (old, recent) = (PeerId::random(), PeerId::random());
+
+    // No file yet
+    let mut address_book = AddressBook::load(config.clone(), now).unwrap();
+    assert!(address_book.is_empty());
+
+    address_book.record_seen(old, vec![addr(1000)], Source::Bootstrap, now);
+    address_book.record_seen(
+        recent,
+        vec![addr(1001), addr(1002)],
+        Source::Inbound,
+        now + HOUR,
+    );
+    address_book.record_failure(&recent);
+    address_book.persist();
+
+    let loaded = AddressBook::load(config.clone(), now + HOUR).unwrap();
+    assert_eq!(loaded.len(), 2);
+    assert_eq!(loaded.get(&old), address_book.get(&old));
+    assert_eq!(loaded.get(&recent), address_book.get(&recent));
+
+    // Aged out entries are dropped on load
+    let loaded = AddressBook::load(config, now + 24 * HOUR + Duration::from_secs(1)).unwrap();
+    assert_eq!(loaded.len(), 1);
+    assert!(loaded.get(&recent).is_some());
+}
@@ -1,7 +1,15 @@
 use std::{time::Duration, vec};
 
-use informalsystems_malachitebft_discovery_test::{Expected, Test, TestNode};
-use malachitebft_network::{BootstrapProtocol, DiscoveryConfig, Selector};
+use informalsystems_malachitebft_discovery_test::{
+    wait_for_disconnected_peer, wait_for_peer, BareBehaviourEvent, BarePeer, Expected, PeerRecord,
+    Test, TestNode,
+};
+use libp2p::identify;
+use libp2p::swarm::SwarmEvent;
+use malachitebft_network::{
+    BootstrapProtocol, ConnectionGaterConfig, DiscoveryConfig, PeerIdExt, Selector,
+};
+use tokio::time::sleep;
 
 // Ensuring that having the node's address in the bootstrap set does not cause
 // any issues.
@@ -167,3 +175,358 @@ pub async fn discovery_concurrent_dial() {
 
     test.run().await
 }
+
+// Testing a sentry setup, where node 0 is a validator which only connects to
+// its sentry node 1. The sentry never advertises the validator, so the other
+// nodes only discover each other and the sentry.
+#[tokio::test]
+pub async fn sentry_private_peers() {
+    let test = Test::new(
+        [
+            TestNode::correct(0, vec![1]).without_discovery(),
+            TestNode::correct(1, vec![0]).with_private_peers(vec![0]),
+            TestNode::correct(2, vec![1]),
+            TestNode::correct(3, vec![1]),
+        ],
+        [
+            Expected::Exactly(vec![1]),
+            Expected::Exactly(vec![0, 2, 3]),
+            Expected::Exactly(vec![1, 3]),
+            Expected::Exactly(vec![1, 2]),
+        ],
+        Duration::from_secs(0),
+        Duration::from_secs(10),
+        DiscoveryConfig {
+            enabled: true,
+            bootstrap_protocol: BootstrapProtocol::Full,
+            selector: Selector::Random,
+            ..Default::default()
+        },
+    );
+
+    test.run().await
+}
+
+// Testing that a node without any bootstrap node rejoins the network through
+// the peers in its address book, as after a restart.
+#[tokio::test]
+pub async fn rejoin_from_address_book() {
+    let test = Test::new(
+        [
+            TestNode::correct(0, vec![]),
+            TestNode::correct(1, vec![0]),
+            TestNode::correct(2, vec![]).with_known_peers(vec![1]),
+        ],
+        [
+            Expected::Exactly(vec![1, 2]),
+            Expected::Exactly(vec![0, 2]),
+            Expected::Exactly(vec![0, 1]),
+        ],
+        Duration::from_secs(1),
+        Duration::from_secs(10),
+        DiscoveryConfig {
+            enabled: true,
+            bootstrap_protocol: BootstrapProtocol::Full,
+            selector: Selector::Random,
+            ..Default::default()
+        },
+    );
+
+    test.run().await
+}
+
+// Testing the latency selector, which does not limit the outbound peers on
+// the loopback interface.
+#[tokio::test]
+pub async fn latency_selector() {
+    let test = Test::new(
+        [
+            TestNode::correct(0, vec![]),
+            TestNode::correct(1, vec![0]),
+            TestNode::correct(2, vec![1]),
+            TestNode::correct(3, vec![2]),
+        ],
+        [
+            Expected::Exactly(vec![1, 2, 3]),
+            Expected::Exactly(vec![0, 2, 3]),
+            Expected::Exactly(vec![0, 1, 3]),
+            Expected::Exactly(vec![0, 1, 2]),
+        ],
+        Duration::from_secs(1),
+        Duration::from_secs(10),
+        DiscoveryConfig {
+            enabled: true,
+            bootstrap_protocol: BootstrapProtocol::Full,
+            selector: Selector::Latency,
+            ..Default::default()
+        },
+    );
+
+    test.run().await
+}
+
+// Testing the peer exchange bootstrap protocol on the following bootstrap
+// sets graph, without Kademlia:
+//     0 <--- 1 <--- 2 <--- 3 <--- 4
+#[tokio::test]
+pub async fn peer_exchange() {
+    let test = Test::new(
+        [
+            TestNode::correct(0, vec![]),
+            TestNode::correct(1, vec![0]),
+            TestNode::correct(2, vec![1]),
+            TestNode::correct(3, vec![2]),
+            TestNode::correct(4, vec![3]),
+        ],
+        [
+            Expected::Exactly(vec![1, 2, 3, 4]),
+            Expected::Exactly(vec![0, 2, 3, 4]),
+            Expected::Exactly(vec![0, 1, 3, 4]),
+            Expected::Exactly(vec![0, 1, 2, 4]),
+            Expected::Exactly(vec![0, 1, 2, 3]),
+        ],
+        Duration::from_secs(0),
+        Duration::from_secs(10),
+        DiscoveryConfig {
+            enabled: true,
+            bootstrap_protocol: BootstrapProtocol::Pex,
+            selector: Selector::Random,
+            pex_interval: Duration::from_secs(1),
+            ..Default::default()
+        },
+    );
+
+    test.run().await
+}
+
+// Testing that a node denying a peer neither accepts connections from it
+// nor dials it, even though the other nodes advertise it.
+#[tokio::test]
+pub async fn connection_gater_denied_peers() {
+    let test = Test::new(
+        [
+            TestNode::correct(0, vec![1]).with_denied_peers(vec![3]),
+            TestNode::correct(1, vec![2]),
+            TestNode::correct(2, vec![3]),
+            TestNode::correct(3, vec![]),
+        ],
+        [
+            Expected::Exactly(vec![1, 2]),
+            Expected::Exactly(vec![0, 2, 3]),
+            Expected::Exactly(vec![0, 1, 3]),
+            Expected::Exactly(vec![1, 2]),
+        ],
+        Duration::from_secs(0),
+        Duration::from_secs(10),
+        DiscoveryConfig {
+            enabled: true,
+            bootstrap_protocol: BootstrapProtocol::Full,
+            selector: Selector::Random,
+            ..Default::default()
+        },
+    );
+
+    test.run().await
+}
+
+// Testing that a node disconnects from a peer which does not identify with
+// a signed peer record, and never reports it as connected.
+#[tokio::test]
+pub async fn peers_without_signed_peer_record_are_disconnected() {
+    let test = Test::new(
+        [TestNode::correct(0, vec![])],
+        [Expected::Exactly(vec![])],
+        Duration::from_secs(0),
+        Duration::from_secs(0),
+        full_discovery(),
+    );
+
+    let (mut events, _ctrl) = test.spawn().await.remove(0).split();
+
+    let mut peer = BarePeer::new(PeerRecord::Missing);
+    peer.connect(test.peer_id(0), test.listen_addr(0)).await;
+
+    let node = test.peer_id(0);
+    peer.wait_for(|event| match event {
+        SwarmEvent::ConnectionClosed { peer_id, .. } if peer_id == node => Some(()),
+        _ => None,
+    })
+    .await;
+
+    let peer_id = peer.peer_id();
+    assert!(!wait_for_peer(&mut events, peer_id, Duration::from_secs(1)).await);
+}
+
+// Testing that a node neither reports as connected nor advertises a peer whose
+// peer record was signed by another peer.
+#[tokio::test]
+pub async fn mismatched_peer_records_are_rejected() {
+    let test = Test::new(
+        [TestNode::correct(0, vec![])],
+        [Expected::Exactly(vec![])],
+        Duration::from_secs(0),
+        Duration::from_secs(0),
+        full_discovery(),
+    );
+
+    let (mut events, _ctrl) = test.spawn().await.remove(0).split();
+
+    let mut rogue = BarePeer::new(PeerRecord::SignedByOther);
+    let rogue_id = rogue.peer_id();
+    rogue.listen_on(test.listen_addr(1)).await;
+    rogue.connect(test.peer_id(0), test.listen_addr(0)).await;
+    let _rogue = rogue.spawn();
+
+    assert!(!wait_for_peer(&mut events, rogue_id, Duration::from_secs(2)).await);
+
+    let mut peer = BarePeer::new(PeerRecord::Signed);
+    peer.connect(test.peer_id(0), test.listen_addr(0)).await;
+
+    let peers = peer.request_peers(test.peer_id(0)).await;
+    assert!(
+        peers.iter().all(|(id, _)| *id != Some(rogue_id)),
+        "{peers:?}"
+    );
+}
+
+// Testing that a node only advertises the addresses at which it reached a peer,
+// and defers its response to a peers request until the addresses are probed.
+#[tokio::test]
+pub async fn only_verified_addresses_are_advertised() {
+    let test = Test::new(
+        [TestNode::correct(0, vec![])],
+        [Expected::Exactly(vec![])],
+        Duration::from_secs(0),
+        Duration::from_secs(0),
+        full_discovery(),
+    );
+
+    let (mut events, _ctrl) = test.spawn().await.remove(0).split();
+
+    let mut peer = BarePeer::new(PeerRecord::Signed);
+    peer.connect(test.peer_id(0), test.listen_addr(0)).await;
+
+    // Nothing listens at the second address of the peer
+    let mut advertised = BarePeer::new(PeerRecord::Signed);
+    let advertised_id = advertised.peer_id();
+    advertised.listen_on(test.listen_addr(1)).await;
+    advertised.swarm.add_external_address(test.listen_addr(2));
+    advertised
+        .connect(test.peer_id(0), test.listen_addr(0))
+        .await;
+
+    advertised
+        .wait_for(|event| match event {
+            SwarmEvent::Behaviour(BareBehaviourEvent::Identify(identify::Event::Sent {
+                ..
+            })) => Some(()),
+            _ => None,
+        })
+        .await;
+
+    assert!(wait_for_peer(&mut events, advertised_id, Duration::from_secs(5)).await);
+
+    // Only accept the probes of the peer after asking for peers, so that the peer
+    // is only advertised if the response waits for its addresses to be probed
+    let _advertised = tokio::spawn(async move {
+        sleep(Duration::from_millis(500)).await;
+        advertised.spawn().await
+    });
+
+    let peers = peer.request_peers(test.peer_id(0)).await;
+    assert!(
+        peers.contains(&(Some(advertised_id), vec![test.listen_addr(1)])),
+        "{peers:?}"
+    );
+}
+
+// Testing that a node only adds the addresses at which it reached a peer
+// to its Kademlia routing table.
+#[tokio::test]
+pub async fn only_verified_addresses_are_added_to_kademlia() {
+    let test = Test::new(
+        [TestNode::correct(0, vec![])],
+        [Expected::Exactly(vec![])],
+        Duration::from_secs(0),
+        Duration::from_secs(0),
+        DiscoveryConfig {
+            enabled: true,
+            bootstrap_protocol: BootstrapProtocol::Kademlia,
+            selector: Selector::Kademlia,
+            ..Default::default()
+        },
+    );
+
+    let (mut events, _ctrl) = test.spawn().await.remove(0).split();
+
+    // Nothing listens at the second address of the peer
+    let mut advertised = BarePeer::new(PeerRecord::Signed);
+    let advertised_id = advertised.peer_id();
+    advertised.listen_on(test.listen_addr(1)).await;
+    advertised.swarm.add_external_address(test.listen_addr(2));
+    advertised
+        .connect(test.peer_id(0), test.listen_addr(0))
+        .await;
+    let _advertised = advertised.spawn();
+
+    assert!(wait_for_peer(&mut events, advertised_id, Duration::from_secs(5)).await);
+
+    // Leave time for the addresses of the peer to be probed
+    sleep(Duration::from_secs(1)).await;
+
+    let mut peer = BarePeer::new(PeerRecord::Signed);
+    let closest_peers = peer
+        .closest_peers(test.peer_id(0), test.listen_addr(0), advertised_id)
+        .await;
+
+    let advertised = closest_peers
+        .iter()
+        .find(|info| info.peer_id == advertised_id)
+        .expect("peer not found in Kademlia");
+
+    assert_eq!(
+        advertised.addrs,
+        vec![test.listen_addr(1).with_p2p(advertised_id).unwrap()]
+    );
+}
+
+// Testing that replacing the connection gating rules at runtime closes the
+// connections to the peers which are not allowed anymore.
+#[tokio::test]
+pub async fn connection_gater_hot_reload() {
+    let test = Test::new(
+        [TestNode::correct(0, vec![]), TestNode::correct(1, vec![0])],
+        [Expected::Exactly(vec![]), Expected::Exactly(vec![])],
+        Duration::from_secs(0),
+        Duration::from_secs(0),
+        full_discovery(),
+    );
+
+    let mut handles = test.spawn().await;
+    let _other = handles.pop();
+    let (mut events, ctrl) = handles.remove(0).split();
+
+    let peer_id = test.peer_id(1);
+    assert!(wait_for_peer(&mut events, peer_id, Duration::from_secs(5)).await);
+
+    ctrl.set_connection_gater(ConnectionGaterConfig {
+        denied_peers: [malachitebft_network::PeerId::from_libp2p(&peer_id)].into(),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    assert!(wait_for_disconnected_peer(&mut events, peer_id, Duration::from_secs(5)).await);
+
+    // The peer keeps dialing the node, which denies it
+    assert!(!wait_for_peer(&mut events, peer_id, Duration::from_secs(3)).await);
+}
+
+fn full_discovery() -> DiscoveryConfig {
+    DiscoveryConfig {
+        enabled: true,
+        bootstrap_protocol: BootstrapProtocol::Full,
+        selector: Selector::Random,
+        ..Default::default()
+    }
+}
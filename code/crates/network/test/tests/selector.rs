@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::time::Duration;
+
+use libp2p_identity::PeerId;
+use malachitebft_network::selection::latency::{LatencySelector, Subnet};
+use malachitebft_network::selection::selector::Selection;
+use malachitebft_network::Multiaddr;
+
+fn ms(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+fn ip(ip: &str) -> IpAddr {
+    ip.parse().unwrap()
+}
+
+fn selected(selection: Selection<PeerId>) -> Vec<PeerId> {
+    match selection {
+        Selection::Exactly(peers) | Selection::Only(peers) => peers,
+        Selection::None => vec![],
+    }
+}
+
+#[test]
+fn subnets() {
+    assert_eq!(Subnet::from_ip(ip("8.8.4.4")), Some(Subnet::V4([8, 8])));
+    assert_eq!(
+        Subnet::from_ip(ip("8.8.4.4")),
+        Subnet::from_ip(ip("8.8.200.1"))
+    );
+    assert_ne!(
+        Subnet::from_ip(ip("8.8.4.4")),
+        Subnet::from_ip(ip("8.9.4.4"))
+    );
+
+    assert_eq!(
+        Subnet::from_ip(ip("2001:4860:4860::8888")),
+        Some(Subnet::V6([0x2001, 0x4860]))
+    );
+
+    // Local networks are not limited
+    for local in [
+        "127.0.0.1",
+        "10.1.2.3",
+        "192.168.1.1",
+        "169.254.0.1",
+        "0.0.0.0",
+        "::1",
+        "fd00::1",
+        "fe80::1",
+    ] {
+        assert_eq!(Subnet::from_ip(ip(local)), None, "{local}");
+    }
+
+    let addrs: Vec<Multiaddr> = vec![
+        "/ip4/127.0.0.1/udp/27000/quic-v1".parse().unwrap(),
+        "/dns/example.com/tcp/27000".parse().unwrap(),
+        "/ip4/1.2.3.4/tcp/27000".parse().unwrap(),
+    ];
+    assert_eq!(Subnet::from_addrs(&addrs), Some(Subnet::V4([1, 2])));
+    assert_eq!(Subnet::from_addrs(&addrs[..2]), None);
+    assert_eq!(Subnet::from_addr(&addrs[2]), Some(Subnet::V4([1, 2])));
+    assert_eq!(Subnet::from_addr(&addrs[0]), None);
+}
+
+#[test]
+fn lowest_round_trip_times_first() {
+    let mut selector = LatencySelector::new(0);
+    let peers = (0..4).map(|_| PeerId::random()).collect::<Vec<_>>();
+
+    selector.record_rtt(peers[0], ms(300));
+    selector.record_rtt(peers[1], ms(10));
+    selector.record_rtt(peers[2], ms(100));
+
+    let selection = selector.select(peers.clone(), &[], 3, |_| None);
+    assert_eq!(
+        selection,
+        Selection::Exactly(vec![peers[1], peers[2], peers[0]])
+    );
+
+    // Peers whose round-trip time was never measured come last
+    let selection = selector.select(peers.clone(), &[], 5, |_| None);
+    assert_eq!(
+        selection,
+        Selection::Only(vec![peers[1], peers[2], peers[0], peers[3]])
+    );
+
+    assert_eq!(selector.select(peers, &[], 0, |_| None), Selection::None);
+}
+
+#[test]
+fn round_trip_times_are_smoothed() {
+    let mut selector = LatencySelector::new(0);
+    let peer = PeerId::random();
+
+    selector.record_rtt(peer, ms(100));
+    assert_eq!(selector.rtt(&peer), Some(ms(100)));
+
+    // A single slow pong does not outweigh the previous measurements
+    selector.record_rtt(peer, ms(600));
+    assert_eq!(selector.rtt(&peer), Some(ms(200)));
+}
+
+#[test]
+fn outbound_peers_per_subnet_are_limited() {
+    let mut selector = LatencySelector::new(2);
+
+    let outbound = PeerId::random();
+    let same_subnet = (0..3).map(|_| PeerId::random()).collect::<Vec<_>>();
+    let (other_subnet, local) = (PeerId::random(), PeerId::random());
+
+    let mut subnets = HashMap::new();
+    subnets.insert(outbound, Subnet::from_ip(ip("8.8.0.1")));
+    subnets.insert(other_subnet, Subnet::from_ip(ip("1.1.1.1")));
+    subnets.insert(local, None);
+    for peer in &same_subnet {
+        subnets.insert(*peer, Subnet::from_ip(ip("8.8.1.1")));
+    }
+
+    // The peers in the crowded subnet are the fastest ones
+    for (i, peer) in same_subnet.iter().enumerate() {
+        selector.record_rtt(*peer, ms(i as u64 + 1));
+    }
+    selector.record_rtt(other_subnet, ms(50));
+    selector.record_rtt(local, ms(60));
+
+    let mut candidates = same_subnet.clone();
+    candidates.extend([other_subnet, local]);
+
+    let subnet_of = |peer: &PeerId| subnets[peer];
+
+    // One slot of the subnet is already taken by an outbound peer
+    let selection = selector.select(candidates.clone(), &[outbound], 4, subnet_of);
+    assert_eq!(
+        selection,
+        Selection::Only(vec![same_subnet[0], other_subnet, local])
+    );
+
+    let selection = selector.select(candidates, &[], 4, subnet_of);
+    assert_eq!(
+        selected(selection),
+        vec![same_subnet[0], same_subnet[1], other_subnet, local]
+    );
+}
+
+#[test]
+fn subnets_come_from_connection_remote_addresses() {
+    let mut selector = LatencySelector::new(1);
+    let peer = PeerId::random();
+
+    assert_eq!(selector.subnet(&peer), None);
+
+    let remote_addr: Multiaddr = "/ip4/8.8.4.4/tcp/27000".parse().unwrap();
+    selector.record_connection(peer, &remote_addr);
+    selector.record_rtt(peer, ms(100));
+
+    assert_eq!(selector.subnet(&peer), Some(Subnet::V4([8, 8])));
+    assert_eq!(selector.rtt(&peer), Some(ms(100)));
+
+    // Disconnected peers are forgotten
+    selector.forget(&peer);
+
+    assert_eq!(selector.subnet(&peer), None);
+    assert_eq!(selector.rtt(&peer), None);
+}
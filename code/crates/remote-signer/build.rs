@@ -0,0 +1,17 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let protos = &["proto/remote_signer.proto"];
+
+    for proto in protos {
+        println!("cargo:rerun-if-changed={proto}");
+    }
+
+    let fds = protox::compile(protos, ["proto"])?;
+
+    let mut config = prost_build::Config::new();
+    config.enable_type_names();
+    config.bytes(["."]);
+
+    config.compile_fds(fds)?;
+
+    Ok(())
+}
@@ -0,0 +1,311 @@
+use std::marker::PhantomData;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use tokio::sync::Mutex;
+use tracing::debug;
+
+use malachitebft_codec::Codec;
+use malachitebft_core_types::{Context, PublicKey, Round, Signature, SignedMessage, ValueId};
+use malachitebft_signing::{Error as SigningError, SigningProvider, VerificationResult};
+
+use crate::proto::{self, request, response};
+use crate::transport;
+use crate::PROTOCOL_VERSION;
+use crate::{Address, Error, IdentityKey, IdentityPublicKey, SecureStream, SignerCodec};
+
+/// A [`SigningProvider`] which asks a remote signer to sign messages on its behalf,
+/// so that the private key of the validator never needs to be on the host running consensus.
+///
+/// The connection to the remote signer is opened on the first request, and re-opened
+/// on the next request after any failure to talk to the remote signer.
+/// The node authenticates with its own identity key, and only accepts a signer
+/// with the given identity public key.
+///
+/// Signatures are verified, and aggregated, locally by the given verifier,
+/// whose signing methods are never called.
+pub struct RemoteSigningProvider<Ctx, C, V> {
+    address: Address,
+    identity: IdentityKey,
+    signer_public_key: IdentityPublicKey,
+    codec: C,
+    verifier: V,
+    timeout: Duration,
+    connection: Mutex<Option<SecureStream>>,
+    marker: PhantomData<fn() -> Ctx>,
+}
+
+impl<Ctx, C, V> RemoteSigningProvider<Ctx, C, V>
+where
+    Ctx: Context,
+    C: SignerCodec<Ctx>,
+    V: SigningProvider<Ctx>,
+{
+    /// Default time to wait for the remote signer to respond to a request.
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// Create a provider which talks to the remote signer at the given address,
+    /// identified by the given public key, authenticating with the given identity key,
+    /// encoding messages with the given codec and verifying signatures with the given verifier.
+    pub fn new(
+        address: Address,
+        identity: IdentityKey,
+        signer_public_key: IdentityPublicKey,
+        codec: C,
+        verifier: V,
+    ) -> Self {
+        Self {
+            address,
+            identity,
+            signer_public_key,
+            codec,
+            verifier,
+            timeout: Self::DEFAULT_TIMEOUT,
+            connection: Mutex::new(None),
+            marker: PhantomData,
+        }
+    }
+
+    /// Set the time to wait for the remote signer to respond to a request.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// The address of the remote signer.
+    pub fn address(&self) -> &Address {
+        &self.address
+    }
+
+    /// Send the given request to the remote signer and return the signature it responds with.
+    async fn request(&self, request: request::Request) -> Result<Signature<Ctx>, Error> {
+        let mut connection = self.connection.lock().await;
+
+        let result = tokio::time::timeout(self.timeout, self.exchange(&mut connection, request))
+            .await
+            .unwrap_or(Err(Error::Timeout));
+
+        let response = match result {
+            Ok(response) => response,
+            Err(e) => {
+                // The connection may be in an inconsistent state, eg. with a response to
+                // this request still in flight, so we reconnect on the next request.
+                debug!(address = %self.address, "Closing connection to remote signer: {e}");
+                connection.take();
+                return Err(e);
+            }
+        };
+
+        drop(connection);
+
+        if response.version != PROTOCOL_VERSION {
+            return Err(Error::UnsupportedVersion(response.version));
+        }
+
+        match response.response {
+            Some(response::Response::Signature(response)) => {
+                decode(&self.codec, response.signature)
+            }
+            Some(response::Response::Error(error)) => Err(Error::Remote {
+                kind: error.kind(),
+                message: error.message,
+            }),
+            None => Err(Error::UnexpectedResponse),
+        }
+    }
+
+    async fn exchange(
+        &self,
+        connection: &mut Option<SecureStream>,
+        request: request::Request,
+    ) -> Result<proto::Response, Error> {
+        let stream = match connection {
+            Some(stream) => stream,
+            None => {
+                let stream = transport::connect(&self.address).await?;
+                let stream =
+                    SecureStream::initiate(stream, &self.identity, &[self.signer_public_key])
+                        .await?;
+
+                connection.insert(stream)
+            }
+        };
+
+        let request = proto::Request {
+            version: PROTOCOL_VERSION,
+            request: Some(request),
+        };
+
+        stream.write_message(&request).await?;
+        stream.read_message().await
+    }
+
+    async fn sign<T>(
+        &self,
+        message: T,
+        request: impl FnOnce(Bytes) -> request::Request,
+    ) -> Result<SignedMessage<Ctx, T>, SigningError>
+    where
+        C: Codec<T>,
+    {
+        let bytes = encode(&self.codec, &message).map_err(SigningError::from_source)?;
+
+        let signature = self
+            .request(request(bytes))
+            .await
+            .map_err(SigningError::from_source)?;
+
+        Ok(SignedMessage::new(message, signature))
+    }
+}
+
+fn encode<C: Codec<T>, T>(codec: &C, message: &T) -> Result<Bytes, Error> {
+    codec
+        .encode(message)
+        .map_err(|e| Error::Codec(e.to_string()))
+}
+
+fn decode<C: Codec<T>, T>(codec: &C, bytes: Bytes) -> Result<T, Error> {
+    codec.decode(bytes).map_err(|e| Error::Codec(e.to_string()))
+}
+
+#[async_trait]
+impl<Ctx, C, V> SigningProvider<Ctx> for RemoteSigningProvider<Ctx, C, V>
+where
+    Ctx: Context,
+    C: SignerCodec<Ctx>,
+    V: SigningProvider<Ctx>,
+{
+    async fn sign_vote(
+        &self,
+        vote: Ctx::Vote,
+    ) -> Result<SignedMessage<Ctx, Ctx::Vote>, SigningError> {
+        self.sign(vote, |vote| {
+            request::Request::SignVote(proto::SignVoteRequest { vote })
+        })
+        .await
+    }
+
+    async fn verify_signed_vote(
+        &self,
+        vote: &Ctx::Vote,
+        signature: &Signature<Ctx>,
+        public_key: &PublicKey<Ctx>,
+    ) -> Result<VerificationResult, SigningError> {
+        self.verifier
+            .verify_signed_vote(vote, signature, public_key)
+            .await
+    }
+
+    async fn sign_proposal(
+        &self,
+        proposal: Ctx::Proposal,
+    ) -> Result<SignedMessage<Ctx, Ctx::Proposal>, SigningError> {
+        self.sign(proposal, |proposal| {
+            request::Request::SignProposal(proto::SignProposalRequest { proposal })
+        })
+        .await
+    }
+
+    async fn verify_signed_proposal(
+        &self,
+        proposal: &Ctx::Proposal,
+        signature: &Signature<Ctx>,
+        public_key: &PublicKey<Ctx>,
+    ) -> Result<VerificationResult, SigningError> {
+        self.verifier
+            .verify_signed_proposal(proposal, signature, public_key)
+            .await
+    }
+
+    async fn sign_proposal_part(
+        &self,
+        proposal_part: Ctx::ProposalPart,
+    ) -> Result<SignedMessage<Ctx, Ctx::ProposalPart>, SigningError> {
+        self.sign(proposal_part, |proposal_part| {
+            request::Request::SignProposalPart(proto::SignProposalPartRequest { proposal_part })
+        })
+        .await
+    }
+
+    async fn verify_signed_proposal_part(
+        &self,
+        proposal_part: &Ctx::ProposalPart,
+        signature: &Signature<Ctx>,
+        public_key: &PublicKey<Ctx>,
+    ) -> Result<VerificationResult, SigningError> {
+        self.verifier
+            .verify_signed_proposal_part(proposal_part, signature, public_key)
+            .await
+    }
+
+    async fn sign_vote_extension(
+        &self,
+        extension: Ctx::Extension,
+    ) -> Result<SignedMessage<Ctx, Ctx::Extension>, SigningError> {
+        self.sign(extension, |extension| {
+            request::Request::SignVoteExtension(proto::SignVoteExtensionRequest { extension })
+        })
+        .await
+    }
+
+    async fn verify_signed_vote_extension(
+        &self,
+        extension: &Ctx::Extension,
+        signature: &Signature<Ctx>,
+        public_key: &PublicKey<Ctx>,
+    ) -> Result<VerificationResult, SigningError> {
+        self.verifier
+            .verify_signed_vote_extension(extension, signature, public_key)
+            .await
+    }
+
+    async fn verify_signed_votes(
+        &self,
+        votes: &[(Ctx::Vote, &Signature<Ctx>, &PublicKey<Ctx>)],
+    ) -> Result<Vec<VerificationResult>, SigningError> {
+        self.verifier.verify_signed_votes(votes).await
+    }
+
+    async fn aggregate_signatures(
+        &self,
+        signatures: &[Signature<Ctx>],
+    ) -> Result<Signature<Ctx>, SigningError> {
+        self.verifier.aggregate_signatures(signatures).await
+    }
+
+    async fn verify_aggregated_commit(
+        &self,
+        height: &Ctx::Height,
+        round: Round,
+        value_id: &ValueId<Ctx>,
+        signature: &Signature<Ctx>,
+        public_keys: &[&PublicKey<Ctx>],
+    ) -> Result<VerificationResult, SigningError> {
+        self.verifier
+            .verify_aggregated_commit(height, round, value_id, signature, public_keys)
+            .await
+    }
+
+    async fn sign_peer_identity(&self, peer_id: &[u8]) -> Result<Signature<Ctx>, SigningError> {
+        let request = request::Request::SignPeerIdentity(proto::SignPeerIdentityRequest {
+            peer_id: Bytes::copy_from_slice(peer_id),
+        });
+
+        self.request(request)
+            .await
+            .map_err(SigningError::from_source)
+    }
+
+    async fn verify_peer_identity(
+        &self,
+        peer_id: &[u8],
+        signature: &Signature<Ctx>,
+        public_key: &PublicKey<Ctx>,
+    ) -> Result<VerificationResult, SigningError> {
+        self.verifier
+            .verify_peer_identity(peer_id, signature, public_key)
+            .await
+    }
+}
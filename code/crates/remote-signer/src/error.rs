@@ -0,0 +1,75 @@
+use std::io;
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+use crate::proto::ErrorKind;
+use crate::secure::IdentityPublicKey;
+use crate::PROTOCOL_VERSION;
+
+/// Represents an error that can occur when talking to a remote signer.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// The address of the remote signer is not valid.
+    #[error(
+        "Invalid remote signer address '{0}', expected 'tcp://<host>:<port>' or 'unix://<path>'"
+    )]
+    InvalidAddress(String),
+
+    /// Failed to send or receive a message.
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    /// No response was received from the remote signer in time.
+    #[error("Timed out waiting for a response from the remote signer")]
+    Timeout,
+
+    /// The identity public key is not valid.
+    #[error("Invalid identity public key '{0}', expected 64 hexadecimal characters")]
+    InvalidIdentityKey(String),
+
+    /// The identity key file does not hold a valid identity key.
+    #[error("Invalid identity key file {}, expected 64 hexadecimal characters", .0.display())]
+    InvalidIdentityKeyFile(PathBuf),
+
+    /// The peer on the other side of the connection is not one of the authorized peers.
+    #[error("Unauthorized peer with identity public key {0}")]
+    Unauthorized(IdentityPublicKey),
+
+    /// Failed to set up or use the encrypted connection.
+    #[error("Noise error: {0}")]
+    Noise(#[from] snow::Error),
+
+    /// A frame received over the encrypted connection is malformed.
+    #[error("Invalid frame")]
+    InvalidFrame,
+
+    /// A message exceeds the maximum frame size.
+    #[error("Message of {0} bytes exceeds the maximum frame size")]
+    FrameTooLarge(usize),
+
+    /// A message of the protocol could not be decoded.
+    #[error("Failed to decode message: {0}")]
+    Decode(#[from] prost::DecodeError),
+
+    /// A message to sign or a signature could not be encoded or decoded with the codec.
+    #[error("Codec error: {0}")]
+    Codec(String),
+
+    /// The other side speaks another version of the protocol.
+    #[error("Unsupported protocol version {0}, expected {PROTOCOL_VERSION}")]
+    UnsupportedVersion(u32),
+
+    /// The remote signer sent a response which does not match the request.
+    #[error("Unexpected response from the remote signer")]
+    UnexpectedResponse,
+
+    /// The remote signer failed to handle the request.
+    #[error("Remote signer error ({kind:?}): {message}")]
+    Remote {
+        /// The kind of error
+        kind: ErrorKind,
+        /// A description of the error
+        message: String,
+    },
+}
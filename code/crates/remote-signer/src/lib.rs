@@ -0,0 +1,64 @@
+//! Remote signer for the Malachite BFT consensus engine.
+//!
+//! Keeping the private key of a validator on the same host as the node running consensus
+//! exposes it to any compromise of that host. Instead, a node can use a [`RemoteSigningProvider`]
+//! which asks a separate signer daemon, eg. running on a hardened host, to sign votes, proposals,
+//! proposal parts and vote extensions on its behalf, much like [tmkms] does for CometBFT.
+//!
+//! The node and the signer talk a small versioned Protobuf protocol, defined in [`proto`],
+//! over a TCP or Unix domain socket. The messages to sign are encoded with the codec
+//! of the application, so that the signer can decode and inspect them before signing.
+//! A signer daemon is built on top of a [`SignerServer`], which signs the messages it receives
+//! with any [`SigningProvider`](malachitebft_signing::SigningProvider).
+//!
+//! The connection between the node and the signer is authenticated and encrypted, see [`secure`].
+//! Each side has its own [`IdentityKey`] and is configured with the public key of the other side,
+//! so that a signer only signs for the nodes it knows of, and a node only talks to its own signer.
+//!
+//! [tmkms]: https://github.com/iqlusioninc/tmkms
+
+use malachitebft_codec::Codec;
+use malachitebft_core_types::{Context, Signature};
+
+pub mod proto;
+
+mod client;
+pub use client::RemoteSigningProvider;
+
+mod error;
+pub use error::Error;
+
+pub mod secure;
+pub use secure::{IdentityKey, IdentityPublicKey, SecureStream};
+
+mod server;
+pub use server::SignerServer;
+
+mod transport;
+pub use transport::{connect, Address, Listener, Stream, MAX_FRAME_SIZE};
+
+/// Version of the remote signer protocol implemented by this crate.
+///
+/// Both the node and the signer reject messages carrying any other version.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Codec for the messages which a node sends to its remote signer, and the signatures it gets back.
+pub trait SignerCodec<Ctx: Context>:
+    Codec<Ctx::Vote>
+    + Codec<Ctx::Proposal>
+    + Codec<Ctx::ProposalPart>
+    + Codec<Ctx::Extension>
+    + Codec<Signature<Ctx>>
+{
+}
+
+impl<Ctx, C> SignerCodec<Ctx> for C
+where
+    Ctx: Context,
+    C: Codec<Ctx::Vote>
+        + Codec<Ctx::Proposal>
+        + Codec<Ctx::ProposalPart>
+        + Codec<Ctx::Extension>
+        + Codec<Signature<Ctx>>,
+{
+}
@@ -0,0 +1,10 @@
+//! Messages of the remote signer protocol.
+//!
+//! Each message is sent as a frame made of its length, as a big-endian 32-bit integer,
+//! followed by its Protobuf encoding, over an encrypted [`SecureStream`](crate::SecureStream).
+//! The node sends a [`Request`] and waits for the [`Response`]
+//! of the signer before sending its next request on the same connection.
+
+#![allow(missing_docs)]
+
+include!(concat!(env!("OUT_DIR"), "/malachitebft.remote_signer.rs"));
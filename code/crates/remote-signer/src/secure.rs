@@ -0,0 +1,365 @@
+//! Authenticated and encrypted connections between a node and its remote signer.
+//!
+//! Both sides hold a static X25519 [`IdentityKey`], and each side only accepts connections
+//! from the peers whose [`IdentityPublicKey`] it was configured with. The connection is set up
+//! with a [Noise] `XX` handshake, after which every message is encrypted and authenticated.
+//!
+//! The node, which initiates the connection, checks the identity of the signer before revealing
+//! its own, and the signer rejects the connection unless the node is one of its authorized nodes.
+//!
+//! Once the handshake is complete, each message of the protocol is sent as a sequence of Noise
+//! transport messages, each prefixed by its length as a big-endian 16-bit integer. The plaintext
+//! of the first one starts with the length of the message, as a big-endian 32-bit integer.
+//!
+//! [Noise]: https://noiseprotocol.org/noise.html
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+use bytes::{Buf, BytesMut};
+use curve25519_dalek::MontgomeryPoint;
+use prost::Message;
+use snow::{HandshakeState, TransportState};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use zeroize::Zeroizing;
+
+use crate::transport::Stream;
+use crate::{Error, MAX_FRAME_SIZE};
+
+/// Noise protocol used to set up connections.
+const NOISE_PARAMS: &str = "Noise_XX_25519_ChaChaPoly_SHA256";
+
+/// Prologue of the handshake, which both sides must agree on.
+const NOISE_PROLOGUE: &[u8] = b"malachitebft-remote-signer";
+
+/// Maximum size of a Noise message, in bytes.
+const MAX_NOISE_MESSAGE_SIZE: usize = u16::MAX as usize;
+
+/// Size of the authentication tag appended to every encrypted Noise message, in bytes.
+const TAG_SIZE: usize = 16;
+
+/// Maximum size of the plaintext carried by a single Noise transport message, in bytes.
+const MAX_CHUNK_SIZE: usize = MAX_NOISE_MESSAGE_SIZE - TAG_SIZE;
+
+/// The static X25519 key identifying a node or a signer.
+#[derive(Clone)]
+pub struct IdentityKey {
+    secret: Zeroizing<[u8; 32]>,
+    public_key: IdentityPublicKey,
+}
+
+impl IdentityKey {
+    /// Generate a new random identity key.
+    pub fn generate() -> Self {
+        let keypair = builder()
+            .generate_keypair()
+            .expect("the default resolver supports X25519");
+
+        let mut secret = Zeroizing::new([0; 32]);
+        secret.copy_from_slice(&keypair.private);
+
+        Self::from_bytes(*secret)
+    }
+
+    /// Create an identity key from its 32 secret bytes.
+    pub fn from_bytes(secret: [u8; 32]) -> Self {
+        let public_key = IdentityPublicKey(MontgomeryPoint::mul_base_clamped(secret).to_bytes());
+
+        Self {
+            secret: Zeroizing::new(secret),
+            public_key,
+        }
+    }
+
+    /// Load the identity key stored in the given file, as 64 hexadecimal characters,
+    /// or generate a new one and store it in that file if it does not exist yet.
+    pub fn load_or_generate(path: &Path) -> Result<Self, Error> {
+        match fs::read_to_string(path) {
+            Ok(contents) => {
+                let mut secret = Zeroizing::new([0; 32]);
+                hex::decode_to_slice(contents.trim(), secret.as_mut_slice())
+                    .map_err(|_| Error::InvalidIdentityKeyFile(path.to_path_buf()))?;
+
+                Ok(Self::from_bytes(*secret))
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                let key = Self::generate();
+                write_secret_file(path, hex::encode(*key.secret).as_bytes())?;
+                Ok(key)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// The secret bytes of this key.
+    pub fn to_bytes(&self) -> [u8; 32] {
+        *self.secret
+    }
+
+    /// The public key which the peers of this node or signer must be configured with.
+    pub fn public_key(&self) -> IdentityPublicKey {
+        self.public_key
+    }
+}
+
+impl fmt::Debug for IdentityKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IdentityKey")
+            .field("public_key", &self.public_key)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The public part of an [`IdentityKey`], written as 64 hexadecimal characters.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct IdentityPublicKey([u8; 32]);
+
+impl IdentityPublicKey {
+    /// Create a public key from its 32 bytes.
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    /// The bytes of this public key.
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl fmt::Display for IdentityPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(self.0))
+    }
+}
+
+impl fmt::Debug for IdentityPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "IdentityPublicKey({self})")
+    }
+}
+
+impl FromStr for IdentityPublicKey {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut bytes = [0; 32];
+        hex::decode_to_slice(s, &mut bytes)
+            .map_err(|_| Error::InvalidIdentityKey(s.to_string()))?;
+
+        Ok(Self(bytes))
+    }
+}
+
+/// Create a file readable only by its owner, holding the given secret.
+fn write_secret_file(path: &Path, contents: &[u8]) -> io::Result<()> {
+    use std::io::Write;
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    options.open(path)?.write_all(contents)
+}
+
+fn builder() -> snow::Builder<'static> {
+    snow::Builder::new(NOISE_PARAMS.parse().expect("valid Noise parameters"))
+        .prologue(NOISE_PROLOGUE)
+}
+
+/// An authenticated and encrypted connection between a node and its remote signer.
+pub struct SecureStream {
+    stream: Box<dyn Stream>,
+    noise: TransportState,
+    remote_public_key: IdentityPublicKey,
+    buffer: Vec<u8>,
+}
+
+impl SecureStream {
+    /// Set up a connection over the given stream, as the side which opened it,
+    /// only accepting a peer with one of the given public keys.
+    pub async fn initiate(
+        stream: Box<dyn Stream>,
+        identity: &IdentityKey,
+        authorized: &[IdentityPublicKey],
+    ) -> Result<Self, Error> {
+        Self::handshake(stream, identity, authorized, true).await
+    }
+
+    /// Set up a connection over the given stream, as the side which accepted it,
+    /// only accepting a peer with one of the given public keys.
+    pub async fn respond(
+        stream: Box<dyn Stream>,
+        identity: &IdentityKey,
+        authorized: &[IdentityPublicKey],
+    ) -> Result<Self, Error> {
+        Self::handshake(stream, identity, authorized, false).await
+    }
+
+    /// The public key of the peer on the other side of the connection.
+    pub fn remote_public_key(&self) -> IdentityPublicKey {
+        self.remote_public_key
+    }
+
+    async fn handshake(
+        mut stream: Box<dyn Stream>,
+        identity: &IdentityKey,
+        authorized: &[IdentityPublicKey],
+        initiator: bool,
+    ) -> Result<Self, Error> {
+        let builder = builder().local_private_key(identity.secret.as_slice());
+
+        let mut noise = if initiator {
+            builder.build_initiator()?
+        } else {
+            builder.build_responder()?
+        };
+
+        let mut buf = vec![0; MAX_NOISE_MESSAGE_SIZE];
+
+        // -> e
+        // <- e, ee, s, es
+        // -> s, se
+        if initiator {
+            send_handshake_message(&mut stream, &mut noise, &mut buf).await?;
+            recv_handshake_message(&mut stream, &mut noise, &mut buf).await?;
+
+            // Check the identity of the responder before revealing ours
+            check_remote_public_key(&noise, authorized)?;
+
+            send_handshake_message(&mut stream, &mut noise, &mut buf).await?;
+        } else {
+            recv_handshake_message(&mut stream, &mut noise, &mut buf).await?;
+            send_handshake_message(&mut stream, &mut noise, &mut buf).await?;
+            recv_handshake_message(&mut stream, &mut noise, &mut buf).await?;
+        }
+
+        let remote_public_key = check_remote_public_key(&noise, authorized)?;
+
+        Ok(Self {
+            stream,
+            noise: noise.into_transport_mode()?,
+            remote_public_key,
+            buffer: vec![0; MAX_NOISE_MESSAGE_SIZE],
+        })
+    }
+
+    /// Read the next message from the connection.
+    pub async fn read_message<M>(&mut self) -> Result<M, Error>
+    where
+        M: Message + Default,
+    {
+        let mut plaintext = BytesMut::new();
+        self.read_chunk(&mut plaintext).await?;
+
+        if plaintext.len() < 4 {
+            return Err(Error::InvalidFrame);
+        }
+
+        let len = plaintext.get_u32() as usize;
+
+        if len > MAX_FRAME_SIZE {
+            return Err(Error::FrameTooLarge(len));
+        }
+
+        while plaintext.len() < len {
+            self.read_chunk(&mut plaintext).await?;
+        }
+
+        if plaintext.len() != len {
+            return Err(Error::InvalidFrame);
+        }
+
+        Ok(M::decode(plaintext.freeze())?)
+    }
+
+    /// Write the given message to the connection.
+    pub async fn write_message<M>(&mut self, message: &M) -> Result<(), Error>
+    where
+        M: Message,
+    {
+        let len = message.encoded_len();
+
+        if len > MAX_FRAME_SIZE {
+            return Err(Error::FrameTooLarge(len));
+        }
+
+        let mut plaintext = Vec::with_capacity(4 + len);
+        plaintext.extend_from_slice(&(len as u32).to_be_bytes());
+        message
+            .encode(&mut plaintext)
+            .map_err(|e| Error::Codec(e.to_string()))?;
+
+        let mut frame = Vec::new();
+
+        for chunk in plaintext.chunks(MAX_CHUNK_SIZE) {
+            let n = self.noise.write_message(chunk, &mut self.buffer)?;
+            frame.extend_from_slice(&(n as u16).to_be_bytes());
+            frame.extend_from_slice(&self.buffer[..n]);
+        }
+
+        self.stream.write_all(&frame).await?;
+        self.stream.flush().await?;
+
+        Ok(())
+    }
+
+    async fn read_chunk(&mut self, plaintext: &mut BytesMut) -> Result<(), Error> {
+        let len = self.stream.read_u16().await? as usize;
+
+        let mut ciphertext = vec![0; len];
+        self.stream.read_exact(&mut ciphertext).await?;
+
+        let n = self.noise.read_message(&ciphertext, &mut self.buffer)?;
+        plaintext.extend_from_slice(&self.buffer[..n]);
+
+        Ok(())
+    }
+}
+
+async fn send_handshake_message(
+    stream: &mut Box<dyn Stream>,
+    noise: &mut HandshakeState,
+    buf: &mut [u8],
+) -> Result<(), Error> {
+    let n = noise.write_message(&[], buf)?;
+
+    stream.write_u16(n as u16).await?;
+    stream.write_all(&buf[..n]).await?;
+    stream.flush().await?;
+
+    Ok(())
+}
+
+async fn recv_handshake_message(
+    stream: &mut Box<dyn Stream>,
+    noise: &mut HandshakeState,
+    buf: &mut [u8],
+) -> Result<(), Error> {
+    let len = stream.read_u16().await? as usize;
+
+    let mut message = vec![0; len];
+    stream.read_exact(&mut message).await?;
+
+    noise.read_message(&message, buf)?;
+
+    Ok(())
+}
+
+fn check_remote_public_key(
+    noise: &HandshakeState,
+    authorized: &[IdentityPublicKey],
+) -> Result<IdentityPublicKey, Error> {
+    let remote = noise.get_remote_static().ok_or(Error::InvalidFrame)?;
+    let remote = IdentityPublicKey(remote.try_into().map_err(|_| Error::InvalidFrame)?);
+
+    if authorized.contains(&remote) {
+        Ok(remote)
+    } else {
+        Err(Error::Unauthorized(remote))
+    }
+}
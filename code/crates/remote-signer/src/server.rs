@@ -0,0 +1,236 @@
+use std::io;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tracing::{debug, warn};
+
+use malachitebft_codec::Codec;
+use malachitebft_core_types::{Context, Signature};
+use malachitebft_signing::SigningProvider;
+
+use crate::proto::{self, request, response, ErrorKind};
+use crate::transport::{Listener, Stream};
+use crate::{Error, IdentityKey, IdentityPublicKey, SecureStream, SignerCodec, PROTOCOL_VERSION};
+
+/// A remote signer, which signs the messages it receives from nodes with the given signing provider.
+///
+/// The signer authenticates with its identity key, and only accepts connections
+/// from the nodes with one of the given identity public keys.
+pub struct SignerServer<Ctx, C, P> {
+    identity: IdentityKey,
+    authorized_nodes: Vec<IdentityPublicKey>,
+    codec: C,
+    provider: P,
+    marker: PhantomData<fn() -> Ctx>,
+}
+
+impl<Ctx, C, P> SignerServer<Ctx, C, P>
+where
+    Ctx: Context,
+    C: SignerCodec<Ctx>,
+    P: SigningProvider<Ctx>,
+{
+    /// Create a remote signer which authenticates with the given identity key, only serves
+    /// the nodes with the given identity public keys, decodes the messages to sign with
+    /// the given codec, and signs them with the given signing provider.
+    pub fn new(
+        identity: IdentityKey,
+        authorized_nodes: Vec<IdentityPublicKey>,
+        codec: C,
+        provider: P,
+    ) -> Self {
+        Self {
+            identity,
+            authorized_nodes,
+            codec,
+            provider,
+            marker: PhantomData,
+        }
+    }
+
+    /// The signing provider used to sign messages.
+    pub fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    /// Accept connections from nodes on the given listener, serving each of them in its own task.
+    ///
+    /// Only returns if accepting a connection fails.
+    pub async fn serve(self, listener: Listener) -> io::Result<()> {
+        let server = Arc::new(self);
+
+        loop {
+            let stream = listener.accept().await?;
+            let server = Arc::clone(&server);
+
+            tokio::spawn(async move {
+                if let Err(e) = server.serve_connection(stream).await {
+                    warn!("Connection to node closed with an error: {e}");
+                }
+            });
+        }
+    }
+
+    /// Authenticate the node on the other side of the given connection, then handle
+    /// the requests received on it, until it is closed by the node.
+    pub async fn serve_connection(&self, stream: Box<dyn Stream>) -> Result<(), Error> {
+        let mut stream =
+            SecureStream::respond(stream, &self.identity, &self.authorized_nodes).await?;
+
+        debug!(node = %stream.remote_public_key(), "Accepted connection from node");
+
+        loop {
+            let request = match stream.read_message().await {
+                Ok(request) => request,
+                Err(Error::Io(e)) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+                Err(e) => return Err(e),
+            };
+
+            let response = self.handle(request).await;
+            stream.write_message(&response).await?;
+        }
+    }
+
+    /// Handle the given request, and return the response to send back to the node.
+    pub async fn handle(&self, request: proto::Request) -> proto::Response {
+        if request.version != PROTOCOL_VERSION {
+            return error_response(
+                ErrorKind::UnsupportedVersion,
+                format!(
+                    "unsupported protocol version {}, expected {PROTOCOL_VERSION}",
+                    request.version
+                ),
+            );
+        }
+
+        let result = match request.request {
+            Some(request::Request::SignVote(request)) => self.sign_vote(request.vote).await,
+            Some(request::Request::SignProposal(request)) => {
+                self.sign_proposal(request.proposal).await
+            }
+            Some(request::Request::SignProposalPart(request)) => {
+                self.sign_proposal_part(request.proposal_part).await
+            }
+            Some(request::Request::SignVoteExtension(request)) => {
+                self.sign_vote_extension(request.extension).await
+            }
+            Some(request::Request::SignPeerIdentity(request)) => {
+                self.sign_peer_identity(request.peer_id).await
+            }
+            None => Err(error(ErrorKind::InvalidRequest, "empty request")),
+        };
+
+        let response = match result {
+            Ok(signature) => response::Response::Signature(proto::SignatureResponse { signature }),
+            Err(error) => {
+                warn!(kind = ?error.kind(), "Failed to handle request: {}", error.message);
+                response::Response::Error(error)
+            }
+        };
+
+        proto::Response {
+            version: PROTOCOL_VERSION,
+            response: Some(response),
+        }
+    }
+
+    async fn sign_vote(&self, bytes: Bytes) -> Result<Bytes, proto::ErrorResponse> {
+        let vote: Ctx::Vote = decode(&self.codec, bytes)?;
+        debug!(?vote, "Signing vote");
+
+        let signed = self
+            .provider
+            .sign_vote(vote)
+            .await
+            .map_err(signing_failed)?;
+        self.encode_signature(&signed.signature)
+    }
+
+    async fn sign_proposal(&self, bytes: Bytes) -> Result<Bytes, proto::ErrorResponse> {
+        let proposal: Ctx::Proposal = decode(&self.codec, bytes)?;
+        debug!(?proposal, "Signing proposal");
+
+        let signed = self
+            .provider
+            .sign_proposal(proposal)
+            .await
+            .map_err(signing_failed)?;
+
+        self.encode_signature(&signed.signature)
+    }
+
+    async fn sign_proposal_part(&self, bytes: Bytes) -> Result<Bytes, proto::ErrorResponse> {
+        let proposal_part: Ctx::ProposalPart = decode(&self.codec, bytes)?;
+        debug!("Signing proposal part");
+
+        let signed = self
+            .provider
+            .sign_proposal_part(proposal_part)
+            .await
+            .map_err(signing_failed)?;
+
+        self.encode_signature(&signed.signature)
+    }
+
+    async fn sign_vote_extension(&self, bytes: Bytes) -> Result<Bytes, proto::ErrorResponse> {
+        let extension: Ctx::Extension = decode(&self.codec, bytes)?;
+        debug!("Signing vote extension");
+
+        let signed = self
+            .provider
+            .sign_vote_extension(extension)
+            .await
+            .map_err(signing_failed)?;
+
+        self.encode_signature(&signed.signature)
+    }
+
+    async fn sign_peer_identity(&self, peer_id: Bytes) -> Result<Bytes, proto::ErrorResponse> {
+        debug!("Signing peer identity");
+
+        let signature = self
+            .provider
+            .sign_peer_identity(&peer_id)
+            .await
+            .map_err(signing_failed)?;
+
+        self.encode_signature(&signature)
+    }
+
+    fn encode_signature(&self, signature: &Signature<Ctx>) -> Result<Bytes, proto::ErrorResponse> {
+        self.codec.encode(signature).map_err(|e| {
+            error(
+                ErrorKind::SigningFailed,
+                format!("failed to encode signature: {e}"),
+            )
+        })
+    }
+}
+
+fn decode<C: Codec<T>, T>(codec: &C, bytes: Bytes) -> Result<T, proto::ErrorResponse> {
+    codec.decode(bytes).map_err(|e| {
+        error(
+            ErrorKind::InvalidRequest,
+            format!("failed to decode message: {e}"),
+        )
+    })
+}
+
+fn signing_failed(e: malachitebft_signing::Error) -> proto::ErrorResponse {
+    error(ErrorKind::SigningFailed, e.to_string())
+}
+
+fn error(kind: ErrorKind, message: impl Into<String>) -> proto::ErrorResponse {
+    proto::ErrorResponse {
+        kind: kind.into(),
+        message: message.into(),
+    }
+}
+
+fn error_response(kind: ErrorKind, message: impl Into<String>) -> proto::Response {
+    proto::Response {
+        version: PROTOCOL_VERSION,
+        response: Some(response::Response::Error(error(kind, message))),
+    }
+}
@@ -0,0 +1,129 @@
+//! Transport of the remote signer protocol, over TCP or Unix domain sockets.
+
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+#[cfg(unix)]
+use std::path::PathBuf;
+
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::{TcpListener, TcpStream};
+
+#[cfg(unix)]
+use tokio::net::{UnixListener, UnixStream};
+
+use crate::Error;
+
+/// Maximum size of a message of the protocol, in bytes.
+pub const MAX_FRAME_SIZE: usize = 16 * 1024 * 1024;
+
+/// A bidirectional byte stream over which the protocol is spoken.
+pub trait Stream: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T> Stream for T where T: AsyncRead + AsyncWrite + Unpin + Send {}
+
+/// The address of a remote signer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Address {
+    /// A TCP address, as `tcp://<host>:<port>`
+    Tcp(String),
+
+    /// The path to a Unix domain socket, as `unix://<path>`
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+impl FromStr for Address {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(addr) = s.strip_prefix("tcp://").filter(|addr| !addr.is_empty()) {
+            return Ok(Self::Tcp(addr.to_string()));
+        }
+
+        #[cfg(unix)]
+        if let Some(path) = s.strip_prefix("unix://").filter(|path| !path.is_empty()) {
+            return Ok(Self::Unix(PathBuf::from(path)));
+        }
+
+        Err(Error::InvalidAddress(s.to_string()))
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp(addr) => write!(f, "tcp://{addr}"),
+            #[cfg(unix)]
+            Self::Unix(path) => write!(f, "unix://{}", path.display()),
+        }
+    }
+}
+
+/// Connect to the remote signer, or any other server listening, at the given address.
+pub async fn connect(address: &Address) -> io::Result<Box<dyn Stream>> {
+    match address {
+        Address::Tcp(addr) => {
+            let stream = TcpStream::connect(addr).await?;
+            stream.set_nodelay(true)?;
+            Ok(Box::new(stream))
+        }
+        #[cfg(unix)]
+        Address::Unix(path) => Ok(Box::new(UnixStream::connect(path).await?)),
+    }
+}
+
+/// A listener for connections from nodes to a remote signer.
+pub enum Listener {
+    /// Listener on a TCP socket
+    Tcp(TcpListener),
+
+    /// Listener on a Unix domain socket
+    #[cfg(unix)]
+    Unix(UnixListener),
+}
+
+impl Listener {
+    /// Listen for connections on the given address.
+    pub async fn bind(address: &Address) -> io::Result<Self> {
+        match address {
+            Address::Tcp(addr) => Ok(Self::Tcp(TcpListener::bind(addr).await?)),
+            #[cfg(unix)]
+            Address::Unix(path) => Ok(Self::Unix(UnixListener::bind(path)?)),
+        }
+    }
+
+    /// The address this listener is bound to, eg. to find out which port was picked
+    /// when binding to port 0.
+    pub fn local_address(&self) -> io::Result<Address> {
+        match self {
+            Self::Tcp(listener) => Ok(Address::Tcp(listener.local_addr()?.to_string())),
+            #[cfg(unix)]
+            Self::Unix(listener) => {
+                let addr = listener.local_addr()?;
+                let path = addr
+                    .as_pathname()
+                    .ok_or_else(|| io::Error::other("unnamed Unix domain socket"))?;
+
+                Ok(Address::Unix(path.to_path_buf()))
+            }
+        }
+    }
+
+    /// Accept the next connection.
+    pub async fn accept(&self) -> io::Result<Box<dyn Stream>> {
+        match self {
+            Self::Tcp(listener) => {
+                let (stream, _) = listener.accept().await?;
+                stream.set_nodelay(true)?;
+                Ok(Box::new(stream))
+            }
+            #[cfg(unix)]
+            Self::Unix(listener) => {
+                let (stream, _) = listener.accept().await?;
+                Ok(Box::new(stream))
+            }
+        }
+    }
+}
@@ -0,0 +1,371 @@
+//! BLS12-381 signing scheme for the Malachite BFT consensus engine.
+//!
+//! Public keys are points on G1 (48 bytes when compressed) and signatures are points on G2
+//! (96 bytes when compressed), using the proof-of-possession ciphersuite of the
+//! [IETF BLS signature draft][bls-draft], as implemented in the [`blst`] crate.
+//!
+//! Signatures from many validators can be aggregated into a single signature.
+//! When all of them signed the same message, the aggregate signature can be verified
+//! against the aggregate of their public keys with a single pairing check,
+//! see [`Signature::fast_aggregate_verify`].
+//!
+//! Many signatures over distinct messages can also be verified at once with a [`BatchVerifier`],
+//! which requires the `rand` feature.
+//!
+//! The `provider` feature, which implies `std`, enables [`BlsSigningProvider`], a signing provider
+//! for contexts using this signing scheme, which aggregates the commit signatures of certificates.
+//! Precommits for a value are signed over a commit message which does not include the address
+//! of the validator, so that an aggregated certificate is verified with a single pairing check.
+//!
+//! **NOTE:** Aggregating public keys is only secure against rogue key attacks if each public key
+//! comes with a valid proof of possession of its private key. Such a proof must be checked with
+//! [`PublicKey::verify_proof_of_possession`] before admitting a validator into the validator set.
+//!
+//! [bls-draft]: https://datatracker.ietf.org/doc/html/draft-irtf-cfrg-bls-signature-05
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(coverage_nightly, feature(coverage_attribute))]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use blst::min_pk as bls;
+use blst::BLST_ERROR;
+use malachitebft_core_types::SigningScheme;
+use signature::{Keypair, Signer, Verifier};
+
+pub use signature::Error as SignatureError;
+
+#[cfg(feature = "rand")]
+use rand::{CryptoRng, RngCore};
+
+#[cfg(feature = "serde")]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub mod serializers;
+
+#[cfg(feature = "protobuf")]
+pub mod proto;
+
+#[cfg(feature = "rand")]
+mod batch;
+
+#[cfg(feature = "rand")]
+pub use batch::BatchVerifier;
+
+#[cfg(feature = "provider")]
+mod provider;
+
+#[cfg(feature = "provider")]
+pub use provider::{vote_sign_bytes, BlsSigningProvider};
+
+/// Domain separation tag for signatures, for the proof-of-possession ciphersuite.
+pub const SIGNATURE_DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+/// Domain separation tag for proofs of possession.
+pub const PROOF_OF_POSSESSION_DST: &[u8] = b"BLS_POP_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Bls12381;
+
+impl Bls12381 {
+    #[cfg(feature = "rand")]
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    pub fn generate_keypair<R>(rng: R) -> PrivateKey
+    where
+        R: RngCore + CryptoRng,
+    {
+        PrivateKey::generate(rng)
+    }
+}
+
+impl SigningScheme for Bls12381 {
+    type DecodingError = SignatureError;
+
+    type Signature = Signature;
+    type PublicKey = PublicKey;
+    type PrivateKey = PrivateKey;
+
+    fn encode_signature(signature: &Signature) -> Vec<u8> {
+        signature.to_bytes().to_vec()
+    }
+
+    fn decode_signature(bytes: &[u8]) -> Result<Self::Signature, Self::DecodingError> {
+        Signature::from_bytes(bytes)
+    }
+}
+
+fn check(result: BLST_ERROR) -> Result<(), SignatureError> {
+    match result {
+        BLST_ERROR::BLST_SUCCESS => Ok(()),
+        _ => Err(SignatureError::new()),
+    }
+}
+
+/// A BLS signature, either produced by a single private key or aggregated from many signatures.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Signature(bls::Signature);
+
+impl Signature {
+    pub fn inner(&self) -> &bls::Signature {
+        &self.0
+    }
+
+    /// Encode the signature as a compressed G2 point.
+    pub fn to_bytes(&self) -> [u8; 96] {
+        self.0.compress()
+    }
+
+    /// Decode a signature from a compressed or uncompressed G2 point,
+    /// checking that the point is in the right subgroup.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SignatureError> {
+        bls::Signature::sig_validate(bytes, true)
+            .map(Self)
+            .map_err(|_| SignatureError::new())
+    }
+
+    /// Aggregate the given signatures into a single signature.
+    ///
+    /// Fails if there are no signatures to aggregate.
+    pub fn aggregate(signatures: &[Signature]) -> Result<Self, SignatureError> {
+        let signatures = signatures.iter().map(|s| &s.0).collect::<Vec<_>>();
+
+        bls::AggregateSignature::aggregate(&signatures, false)
+            .map(|aggregate| Self(aggregate.to_signature()))
+            .map_err(|_| SignatureError::new())
+    }
+
+    /// Verify this aggregate signature, produced by the given public keys over the same message,
+    /// with a single pairing check against the aggregate of the public keys.
+    ///
+    /// The public keys must have been checked for a proof of possession beforehand.
+    pub fn fast_aggregate_verify(
+        &self,
+        msg: &[u8],
+        public_keys: &[PublicKey],
+    ) -> Result<(), SignatureError> {
+        let public_keys = public_keys.iter().map(|pk| &pk.0).collect::<Vec<_>>();
+
+        check(
+            self.0
+                .fast_aggregate_verify(true, msg, SIGNATURE_DST, &public_keys),
+        )
+    }
+
+    /// Verify this aggregate signature, where the public key at each index
+    /// signed the message at the same index, which may all be distinct.
+    ///
+    /// This costs one pairing per distinct message, prefer [`Signature::fast_aggregate_verify`]
+    /// whenever all the signers signed the same message.
+    pub fn aggregate_verify(
+        &self,
+        msgs: &[&[u8]],
+        public_keys: &[PublicKey],
+    ) -> Result<(), SignatureError> {
+        if msgs.len() != public_keys.len() {
+            return Err(SignatureError::new());
+        }
+
+        let public_keys = public_keys.iter().map(|pk| &pk.0).collect::<Vec<_>>();
+
+        check(
+            self.0
+                .aggregate_verify(true, msgs, SIGNATURE_DST, &public_keys, false),
+        )
+    }
+}
+
+impl TryFrom<&[u8]> for Signature {
+    type Error = SignatureError;
+
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_bytes(bytes)
+    }
+}
+
+impl PartialOrd for Signature {
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Signature {
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.to_bytes().cmp(&other.to_bytes())
+    }
+}
+
+#[derive(Clone)]
+pub struct PrivateKey(bls::SecretKey);
+
+impl PrivateKey {
+    /// Generate a new private key, from 32 bytes of input key material drawn from the given RNG.
+    #[cfg(feature = "rand")]
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    pub fn generate<R>(mut rng: R) -> Self
+    where
+        R: RngCore + CryptoRng,
+    {
+        let mut ikm = [0; 32];
+        rng.fill_bytes(&mut ikm);
+
+        Self::from(ikm)
+    }
+
+    /// Derive a private key from the given input key material, which must be at least 32 bytes long.
+    pub fn key_gen(ikm: &[u8]) -> Result<Self, SignatureError> {
+        bls::SecretKey::key_gen(ikm, &[])
+            .map(Self)
+            .map_err(|_| SignatureError::new())
+    }
+
+    /// Decode a private key from its big-endian scalar encoding.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SignatureError> {
+        bls::SecretKey::from_bytes(bytes)
+            .map(Self)
+            .map_err(|_| SignatureError::new())
+    }
+
+    /// Encode the private key as a big-endian scalar.
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0.to_bytes()
+    }
+
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey(self.0.sk_to_pk())
+    }
+
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    pub fn sign(&self, msg: &[u8]) -> Signature {
+        Signature(self.0.sign(msg, SIGNATURE_DST, &[]))
+    }
+
+    /// Produce a proof of possession of this private key,
+    /// to be checked with [`PublicKey::verify_proof_of_possession`].
+    pub fn proof_of_possession(&self) -> Signature {
+        let public_key = self.public_key().to_bytes();
+        Signature(self.0.sign(&public_key, PROOF_OF_POSSESSION_DST, &[]))
+    }
+
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    pub fn inner(&self) -> &bls::SecretKey {
+        &self.0
+    }
+}
+
+impl fmt::Debug for PrivateKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PrivateKey")
+            .field(&self.public_key())
+            .finish()
+    }
+}
+
+impl From<[u8; 32]> for PrivateKey {
+    /// Derive a private key from the given 32 bytes of input key material.
+    fn from(ikm: [u8; 32]) -> Self {
+        let mut secret_key = bls::SecretKey::default();
+
+        // Key generation only fails when the input key material is shorter than 32 bytes
+        if let Ok(key) = bls::SecretKey::key_gen(&ikm, &[]) {
+            secret_key = key;
+        }
+
+        Self(secret_key)
+    }
+}
+
+impl Signer<Signature> for PrivateKey {
+    fn try_sign(&self, msg: &[u8]) -> Result<Signature, SignatureError> {
+        Ok(self.sign(msg))
+    }
+}
+
+impl Keypair for PrivateKey {
+    type VerifyingKey = PublicKey;
+
+    fn verifying_key(&self) -> Self::VerifyingKey {
+        self.public_key()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PublicKey(bls::PublicKey);
+
+impl PublicKey {
+    /// Encode the public key as a compressed G1 point.
+    pub fn to_bytes(&self) -> [u8; 48] {
+        self.0.compress()
+    }
+
+    /// Decode a public key from a compressed or uncompressed G1 point,
+    /// checking that the point is in the right subgroup and is not the identity.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SignatureError> {
+        bls::PublicKey::key_validate(bytes)
+            .map(Self)
+            .map_err(|_| SignatureError::new())
+    }
+
+    /// Aggregate the given public keys into a single public key.
+    ///
+    /// Fails if there are no public keys to aggregate.
+    pub fn aggregate(public_keys: &[PublicKey]) -> Result<Self, SignatureError> {
+        let public_keys = public_keys.iter().map(|pk| &pk.0).collect::<Vec<_>>();
+
+        bls::AggregatePublicKey::aggregate(&public_keys, false)
+            .map(|aggregate| Self(aggregate.to_public_key()))
+            .map_err(|_| SignatureError::new())
+    }
+
+    pub fn verify(&self, msg: &[u8], signature: &Signature) -> Result<(), SignatureError> {
+        check(
+            signature
+                .0
+                .verify(true, msg, SIGNATURE_DST, &[], &self.0, false),
+        )
+    }
+
+    /// Check that the given signature is a valid proof of possession
+    /// of the private key corresponding to this public key.
+    pub fn verify_proof_of_possession(&self, proof: &Signature) -> Result<(), SignatureError> {
+        let public_key = self.to_bytes();
+
+        check(proof.0.verify(
+            true,
+            &public_key,
+            PROOF_OF_POSSESSION_DST,
+            &[],
+            &self.0,
+            true,
+        ))
+    }
+
+    pub fn inner(&self) -> &bls::PublicKey {
+        &self.0
+    }
+}
+
+impl PartialOrd for PublicKey {
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PublicKey {
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.to_bytes().cmp(&other.to_bytes())
+    }
+}
+
+impl Verifier<Signature> for PublicKey {
+    fn verify(&self, msg: &[u8], signature: &Signature) -> Result<(), SignatureError> {
+        PublicKey::verify(self, msg, signature)
+    }
+}
@@ -0,0 +1,65 @@
+//! Protobuf encoding of BLS12-381 public keys and signatures.
+//!
+//! Both are encoded as a message with a single `bytes` field holding the compressed point:
+//!
+//! ```protobuf
+//! package malachitebft.bls;
+//!
+//! message PublicKey { bytes bytes = 1; }
+//! message Signature { bytes bytes = 1; }
+//! ```
+
+use bytes::Bytes;
+use malachitebft_proto::{Error as ProtoError, Protobuf};
+
+/// Protobuf message for a BLS12-381 public key, as a compressed G1 point.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct PublicKey {
+    #[prost(bytes = "bytes", tag = "1")]
+    pub bytes: Bytes,
+}
+
+impl prost::Name for PublicKey {
+    const NAME: &'static str = "PublicKey";
+    const PACKAGE: &'static str = "malachitebft.bls";
+}
+
+/// Protobuf message for a BLS12-381 signature, as a compressed G2 point.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Signature {
+    #[prost(bytes = "bytes", tag = "1")]
+    pub bytes: Bytes,
+}
+
+impl prost::Name for Signature {
+    const NAME: &'static str = "Signature";
+    const PACKAGE: &'static str = "malachitebft.bls";
+}
+
+impl Protobuf for crate::PublicKey {
+    type Proto = PublicKey;
+
+    fn from_proto(proto: Self::Proto) -> Result<Self, ProtoError> {
+        Self::from_bytes(&proto.bytes).map_err(|_| ProtoError::invalid_data::<PublicKey>("bytes"))
+    }
+
+    fn to_proto(&self) -> Result<Self::Proto, ProtoError> {
+        Ok(PublicKey {
+            bytes: Bytes::copy_from_slice(&self.to_bytes()),
+        })
+    }
+}
+
+impl Protobuf for crate::Signature {
+    type Proto = Signature;
+
+    fn from_proto(proto: Self::Proto) -> Result<Self, ProtoError> {
+        Self::from_bytes(&proto.bytes).map_err(|_| ProtoError::invalid_data::<Signature>("bytes"))
+    }
+
+    fn to_proto(&self) -> Result<Self::Proto, ProtoError> {
+        Ok(Signature {
+            bytes: Bytes::copy_from_slice(&self.to_bytes()),
+        })
+    }
+}
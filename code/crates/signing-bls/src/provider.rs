@@ -0,0 +1,267 @@
+use alloc::boxed::Box;
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+use std::sync::{PoisonError, RwLock};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+
+use malachitebft_core_types::{Context, NilOrVal, Round, SignedMessage, ValueId, Vote, VoteType};
+use malachitebft_signing::{
+    peer_identity_sign_bytes, Error, SignBytes, SigningProvider, VerificationResult,
+};
+
+use crate::{Bls12381, PrivateKey, PublicKey, Signature, SignatureError};
+
+/// A [`SigningProvider`] for contexts using the [`Bls12381`] signing scheme,
+/// which supports aggregating the commit signatures of a certificate into a single signature.
+///
+/// The bytes to sign for each kind of message are computed with the given `sign_bytes`,
+/// except for precommits for a value, which are signed over the commit message returned by
+/// [`SignBytes::commit`], so that the commit signatures of a certificate can be aggregated
+/// and verified with a single pairing check.
+///
+/// As aggregate signatures are verified against the aggregate of the public keys of the signers,
+/// only the public keys admitted with a valid proof of possession, see [`Self::admit_public_key`],
+/// are accepted when verifying an aggregate signature.
+///
+/// Public keys can be admitted at any time, including after the provider has been handed
+/// to the consensus engine, eg. when the application applies an update to the validator set.
+pub struct BlsSigningProvider<S> {
+    private_key: PrivateKey,
+    sign_bytes: S,
+    admitted_keys: RwLock<BTreeSet<PublicKey>>,
+}
+
+impl<S> BlsSigningProvider<S> {
+    /// Create a provider which signs messages with the given private key,
+    /// computing sign bytes with `sign_bytes`.
+    ///
+    /// Only the public key of the given private key is admitted for verifying aggregate signatures.
+    pub fn new(private_key: PrivateKey, sign_bytes: S) -> Self {
+        let admitted_keys = RwLock::new(BTreeSet::from([private_key.public_key()]));
+
+        Self {
+            private_key,
+            sign_bytes,
+            admitted_keys,
+        }
+    }
+
+    /// Admit the given public key for verifying aggregate signatures,
+    /// provided that the given proof of possession of its private key is valid.
+    ///
+    /// This must be done for the public key of every validator before admitting it into
+    /// the validator set, to prevent rogue key attacks on aggregate signatures.
+    pub fn admit_public_key(
+        &self,
+        public_key: PublicKey,
+        proof_of_possession: &Signature,
+    ) -> Result<(), SignatureError> {
+        public_key.verify_proof_of_possession(proof_of_possession)?;
+
+        self.admitted_keys
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(public_key);
+
+        Ok(())
+    }
+
+    /// Whether the given public key has been admitted for verifying aggregate signatures.
+    pub fn is_admitted(&self, public_key: &PublicKey) -> bool {
+        self.admitted_keys
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .contains(public_key)
+    }
+
+    /// The private key used for signing.
+    pub fn private_key(&self) -> &PrivateKey {
+        &self.private_key
+    }
+
+    /// The public key of the validator, which the signatures verify against.
+    pub fn public_key(&self) -> PublicKey {
+        self.private_key.public_key()
+    }
+
+    fn verify(bytes: &[u8], signature: &Signature, public_key: &PublicKey) -> VerificationResult {
+        VerificationResult::from_bool(public_key.verify(bytes, signature).is_ok())
+    }
+
+    fn vote_sign_bytes<Ctx>(&self, vote: &Ctx::Vote) -> Bytes
+    where
+        Ctx: Context,
+        S: SignBytes<Ctx>,
+    {
+        vote_sign_bytes(&self.sign_bytes, vote)
+    }
+}
+
+/// The bytes to sign for the given vote, which are the commit message for precommits for a value.
+///
+/// Signers which do not hold the private key of a validator, such as threshold cosigners,
+/// must sign these bytes for their signatures to be verified by a [`BlsSigningProvider`].
+pub fn vote_sign_bytes<Ctx, S>(sign_bytes: &S, vote: &Ctx::Vote) -> Bytes
+where
+    Ctx: Context,
+    S: SignBytes<Ctx>,
+{
+    match (vote.vote_type(), vote.value()) {
+        (VoteType::Precommit, NilOrVal::Val(value_id)) => {
+            sign_bytes.commit(&vote.height(), vote.round(), value_id)
+        }
+        _ => sign_bytes.vote(vote),
+    }
+}
+
+#[async_trait]
+impl<Ctx, S> SigningProvider<Ctx> for BlsSigningProvider<S>
+where
+    Ctx: Context<SigningScheme = Bls12381>,
+    S: SignBytes<Ctx>,
+{
+    async fn sign_vote(&self, vote: Ctx::Vote) -> Result<SignedMessage<Ctx, Ctx::Vote>, Error> {
+        let signature = self.private_key.sign(&self.vote_sign_bytes::<Ctx>(&vote));
+        Ok(SignedMessage::new(vote, signature))
+    }
+
+    async fn verify_signed_vote(
+        &self,
+        vote: &Ctx::Vote,
+        signature: &Signature,
+        public_key: &PublicKey,
+    ) -> Result<VerificationResult, Error> {
+        Ok(Self::verify(
+            &self.vote_sign_bytes::<Ctx>(vote),
+            signature,
+            public_key,
+        ))
+    }
+
+    async fn sign_proposal(
+        &self,
+        proposal: Ctx::Proposal,
+    ) -> Result<SignedMessage<Ctx, Ctx::Proposal>, Error> {
+        let signature = self.private_key.sign(&self.sign_bytes.proposal(&proposal));
+        Ok(SignedMessage::new(proposal, signature))
+    }
+
+    async fn verify_signed_proposal(
+        &self,
+        proposal: &Ctx::Proposal,
+        signature: &Signature,
+        public_key: &PublicKey,
+    ) -> Result<VerificationResult, Error> {
+        Ok(Self::verify(
+            &self.sign_bytes.proposal(proposal),
+            signature,
+            public_key,
+        ))
+    }
+
+    async fn sign_proposal_part(
+        &self,
+        proposal_part: Ctx::ProposalPart,
+    ) -> Result<SignedMessage<Ctx, Ctx::ProposalPart>, Error> {
+        let signature = self
+            .private_key
+            .sign(&self.sign_bytes.proposal_part(&proposal_part));
+        Ok(SignedMessage::new(proposal_part, signature))
+    }
+
+    async fn verify_signed_proposal_part(
+        &self,
+        proposal_part: &Ctx::ProposalPart,
+        signature: &Signature,
+        public_key: &PublicKey,
+    ) -> Result<VerificationResult, Error> {
+        Ok(Self::verify(
+            &self.sign_bytes.proposal_part(proposal_part),
+            signature,
+            public_key,
+        ))
+    }
+
+    async fn sign_vote_extension(
+        &self,
+        extension: Ctx::Extension,
+    ) -> Result<SignedMessage<Ctx, Ctx::Extension>, Error> {
+        let signature = self
+            .private_key
+            .sign(&self.sign_bytes.extension(&extension));
+        Ok(SignedMessage::new(extension, signature))
+    }
+
+    async fn verify_signed_vote_extension(
+        &self,
+        extension: &Ctx::Extension,
+        signature: &Signature,
+        public_key: &PublicKey,
+    ) -> Result<VerificationResult, Error> {
+        Ok(Self::verify(
+            &self.sign_bytes.extension(extension),
+            signature,
+            public_key,
+        ))
+    }
+
+    async fn aggregate_signatures(&self, signatures: &[Signature]) -> Result<Signature, Error> {
+        Signature::aggregate(signatures)
+            .map_err(|_| Error::from_source("cannot aggregate an empty set of signatures"))
+    }
+
+    /// Verify the given aggregate signature over the commit message
+    /// with a single pairing check against the aggregate of the public keys.
+    ///
+    /// The signature is invalid if any of the public keys has not been admitted
+    /// with a proof of possession, see [`BlsSigningProvider::admit_public_key`].
+    async fn verify_aggregated_commit(
+        &self,
+        height: &Ctx::Height,
+        round: Round,
+        value_id: &ValueId<Ctx>,
+        signature: &Signature,
+        public_keys: &[&PublicKey],
+    ) -> Result<VerificationResult, Error> {
+        if public_keys.is_empty() {
+            return Ok(VerificationResult::Invalid);
+        }
+
+        {
+            let admitted_keys = self
+                .admitted_keys
+                .read()
+                .unwrap_or_else(PoisonError::into_inner);
+
+            if !public_keys.iter().all(|pk| admitted_keys.contains(pk)) {
+                return Ok(VerificationResult::Invalid);
+            }
+        }
+
+        let msg = self.sign_bytes.commit(height, round, value_id);
+        let public_keys = public_keys.iter().map(|pk| **pk).collect::<Vec<_>>();
+
+        Ok(VerificationResult::from_bool(
+            signature.fast_aggregate_verify(&msg, &public_keys).is_ok(),
+        ))
+    }
+
+    async fn sign_peer_identity(&self, peer_id: &[u8]) -> Result<Signature, Error> {
+        Ok(self.private_key.sign(&peer_identity_sign_bytes(peer_id)))
+    }
+
+    async fn verify_peer_identity(
+        &self,
+        peer_id: &[u8],
+        signature: &Signature,
+        public_key: &PublicKey,
+    ) -> Result<VerificationResult, Error> {
+        Ok(Self::verify(
+            &peer_identity_sign_bytes(peer_id),
+            signature,
+            public_key,
+        ))
+    }
+}
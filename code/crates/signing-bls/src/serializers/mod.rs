@@ -0,0 +1,17 @@
+//! Serde serializers for BLS12-381 keys and signatures.
+//!
+//! Keys are serialized in the same format as CometBFT BLS12-381 keys,
+//! ie. as an object with a `type` field and a base64-encoded `value` field.
+//! Signatures are serialized as base64-encoded strings.
+
+pub mod base64string;
+
+mod signature;
+mod signing_key;
+mod verification_key;
+
+/// Type of private keys, as in CometBFT.
+pub const PRIVATE_KEY_TYPE: &str = "cometbft/PrivKeyBls12_381";
+
+/// Type of public keys, as in CometBFT.
+pub const PUBLIC_KEY_TYPE: &str = "cometbft/PubKeyBls12_381";
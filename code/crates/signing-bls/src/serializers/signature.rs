@@ -0,0 +1,27 @@
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+use crate::Signature;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::base64string;
+
+impl Serialize for Signature {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        base64string::serialize(&self.to_bytes(), serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Signature {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let bytes: Vec<u8> = base64string::deserialize(deserializer)?;
+        Signature::from_bytes(bytes.as_slice()).map_err(|e| serde::de::Error::custom(e.to_string()))
+    }
+}
@@ -0,0 +1,55 @@
+//! Batch verification of Ed25519 signatures.
+
+use ed25519_consensus::{batch, VerificationKeyBytes};
+use rand::{CryptoRng, RngCore};
+
+use crate::{PublicKey, Signature};
+
+/// A batch of signatures, each over its own message and by its own public key,
+/// to be verified all at once.
+///
+/// Verifying a batch combines all of its signatures with random coefficients,
+/// which is significantly faster than verifying them one by one,
+/// but does not tell which signatures are invalid if the batch is not.
+#[derive(Default)]
+pub struct BatchVerifier {
+    verifier: batch::Verifier,
+    len: usize,
+}
+
+impl BatchVerifier {
+    /// Create an empty batch.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the given signature over the given message by the given public key to the batch.
+    pub fn queue(&mut self, msg: &[u8], public_key: &PublicKey, signature: &Signature) {
+        let key_bytes = VerificationKeyBytes::from(*public_key.inner());
+        self.verifier.queue((key_bytes, *signature.inner(), msg));
+        self.len += 1;
+    }
+
+    /// The number of signatures in the batch.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the batch is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Verify all the signatures in the batch, using the given random number generator
+    /// to draw the coefficients used to combine them.
+    ///
+    /// Succeeds if the batch is empty, and fails if any of its signatures is invalid.
+    pub fn verify<R>(self, rng: R) -> Result<(), signature::Error>
+    where
+        R: RngCore + CryptoRng,
+    {
+        self.verifier
+            .verify(rng)
+            .map_err(|_| signature::Error::new())
+    }
+}
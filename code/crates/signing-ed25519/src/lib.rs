@@ -18,6 +18,12 @@ use serde::{Deserialize, Serialize};
 #[cfg_attr(coverage_nightly, coverage(off))]
 mod serializers;
 
+#[cfg(feature = "batch")]
+mod batch;
+
+#[cfg(feature = "batch")]
+pub use batch::BatchVerifier;
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct Ed25519;
 
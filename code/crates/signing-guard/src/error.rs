@@ -0,0 +1,51 @@
+use std::io;
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+use crate::SignState;
+
+/// Represents an error that can occur when checking or updating the sign state.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// The message to sign is for an earlier height, round or step than the last signed message.
+    #[error("Refusing to sign {next}, which comes before the last signed {last}")]
+    Regression {
+        /// State of the last signed message
+        last: SignState,
+        /// State of the message to sign
+        next: SignState,
+    },
+
+    /// The message to sign is for another value than the last signed message,
+    /// at the same height, round and step.
+    #[error("Refusing to sign {next} for another value than the one already signed")]
+    Conflict {
+        /// State of the last signed message
+        last: SignState,
+        /// State of the message to sign
+        next: SignState,
+    },
+
+    /// Failed to read or write the sign state file.
+    #[error("Failed to access sign state file {path}: {source}")]
+    Io {
+        /// Path to the sign state file
+        path: PathBuf,
+        /// The underlying I/O error
+        source: io::Error,
+    },
+
+    /// The sign state file is not valid.
+    #[error("Invalid sign state file {path}: {source}")]
+    InvalidState {
+        /// Path to the sign state file
+        path: PathBuf,
+        /// The underlying decoding error
+        source: serde_json::Error,
+    },
+
+    /// The id of the value to sign could not be encoded.
+    #[error("Failed to encode value id: {0}")]
+    Codec(String),
+}
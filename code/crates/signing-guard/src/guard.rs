@@ -0,0 +1,223 @@
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use tokio::sync::Mutex;
+use tracing::{debug, warn};
+
+use malachitebft_codec::Codec;
+use malachitebft_core_types::{Context, PublicKey, Round, Signature, SignedMessage, ValueId};
+use malachitebft_signing::{Error as SigningError, SigningProvider, VerificationResult};
+
+use crate::{Error, SignState, SignStateFile};
+
+/// A [`SigningProvider`] which refuses to sign conflicting votes and proposals,
+/// keeping track of the last signed message in a [`SignState`] file.
+///
+/// Before a vote or a proposal is signed by the wrapped provider, its state is checked
+/// against the last signed one, and durably stored to the state file if it comes after it.
+/// Signing the exact same vote or proposal again is allowed, eg. to re-broadcast it after a restart.
+///
+/// Proposal parts and vote extensions are signed as is, and verification is left
+/// to the wrapped provider.
+pub struct DoubleSignGuard<Ctx, C, P> {
+    provider: P,
+    codec: C,
+    path: PathBuf,
+    file: Mutex<SignStateFile>,
+    marker: PhantomData<fn() -> Ctx>,
+}
+
+impl<Ctx, C, P> DoubleSignGuard<Ctx, C, P>
+where
+    Ctx: Context,
+    C: Codec<ValueId<Ctx>>,
+    P: SigningProvider<Ctx>,
+{
+    /// Wrap the given provider, with the state stored in the file at the given path,
+    /// and value ids encoded with the given codec.
+    ///
+    /// The state file is created on the first signature if it does not exist yet.
+    pub fn open(path: impl Into<PathBuf>, codec: C, provider: P) -> Result<Self, Error> {
+        let path = path.into();
+        let file = SignStateFile::open(&path)?;
+
+        match file.state() {
+            Some(state) => debug!(path = %path.display(), "Loaded last sign state: {state}"),
+            None => warn!(path = %path.display(), "No sign state file found, starting afresh"),
+        }
+
+        Ok(Self {
+            provider,
+            codec,
+            path,
+            file: Mutex::new(file),
+            marker: PhantomData,
+        })
+    }
+
+    /// The path to the state file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The state of the last signed vote or proposal, if any.
+    pub async fn state(&self) -> Option<SignState> {
+        self.file.lock().await.state().cloned()
+    }
+
+    /// The wrapped signing provider.
+    pub fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    /// Check the given state and, if it can be signed, sign the message with the wrapped provider.
+    async fn guarded<T, F>(
+        &self,
+        next: Result<SignState, Error>,
+        sign: F,
+    ) -> Result<SignedMessage<Ctx, T>, SigningError>
+    where
+        F: std::future::Future<Output = Result<SignedMessage<Ctx, T>, SigningError>>,
+    {
+        let next = next.map_err(SigningError::from_source)?;
+
+        // Hold the lock while signing, so that concurrent requests are checked one after the other
+        let mut file = self.file.lock().await;
+
+        if let Err(e) = file.update(next) {
+            warn!("{e}");
+            return Err(SigningError::from_source(e));
+        }
+
+        sign.await
+    }
+}
+
+#[async_trait]
+impl<Ctx, C, P> SigningProvider<Ctx> for DoubleSignGuard<Ctx, C, P>
+where
+    Ctx: Context,
+    C: Codec<ValueId<Ctx>>,
+    P: SigningProvider<Ctx>,
+{
+    async fn sign_vote(
+        &self,
+        vote: Ctx::Vote,
+    ) -> Result<SignedMessage<Ctx, Ctx::Vote>, SigningError> {
+        let next = SignState::of_vote::<Ctx, _>(&vote, &self.codec);
+
+        self.guarded(next, self.provider.sign_vote(vote)).await
+    }
+
+    async fn verify_signed_vote(
+        &self,
+        vote: &Ctx::Vote,
+        signature: &Signature<Ctx>,
+        public_key: &PublicKey<Ctx>,
+    ) -> Result<VerificationResult, SigningError> {
+        self.provider
+            .verify_signed_vote(vote, signature, public_key)
+            .await
+    }
+
+    async fn sign_proposal(
+        &self,
+        proposal: Ctx::Proposal,
+    ) -> Result<SignedMessage<Ctx, Ctx::Proposal>, SigningError> {
+        let next = SignState::of_proposal::<Ctx, _>(&proposal, &self.codec);
+
+        self.guarded(next, self.provider.sign_proposal(proposal))
+            .await
+    }
+
+    async fn verify_signed_proposal(
+        &self,
+        proposal: &Ctx::Proposal,
+        signature: &Signature<Ctx>,
+        public_key: &PublicKey<Ctx>,
+    ) -> Result<VerificationResult, SigningError> {
+        self.provider
+            .verify_signed_proposal(proposal, signature, public_key)
+            .await
+    }
+
+    async fn sign_proposal_part(
+        &self,
+        proposal_part: Ctx::ProposalPart,
+    ) -> Result<SignedMessage<Ctx, Ctx::ProposalPart>, SigningError> {
+        self.provider.sign_proposal_part(proposal_part).await
+    }
+
+    async fn verify_signed_proposal_part(
+        &self,
+        proposal_part: &Ctx::ProposalPart,
+        signature: &Signature<Ctx>,
+        public_key: &PublicKey<Ctx>,
+    ) -> Result<VerificationResult, SigningError> {
+        self.provider
+            .verify_signed_proposal_part(proposal_part, signature, public_key)
+            .await
+    }
+
+    async fn sign_vote_extension(
+        &self,
+        extension: Ctx::Extension,
+    ) -> Result<SignedMessage<Ctx, Ctx::Extension>, SigningError> {
+        self.provider.sign_vote_extension(extension).await
+    }
+
+    async fn verify_signed_vote_extension(
+        &self,
+        extension: &Ctx::Extension,
+        signature: &Signature<Ctx>,
+        public_key: &PublicKey<Ctx>,
+    ) -> Result<VerificationResult, SigningError> {
+        self.provider
+            .verify_signed_vote_extension(extension, signature, public_key)
+            .await
+    }
+
+    async fn verify_signed_votes(
+        &self,
+        votes: &[(Ctx::Vote, &Signature<Ctx>, &PublicKey<Ctx>)],
+    ) -> Result<Vec<VerificationResult>, SigningError> {
+        self.provider.verify_signed_votes(votes).await
+    }
+
+    async fn aggregate_signatures(
+        &self,
+        signatures: &[Signature<Ctx>],
+    ) -> Result<Signature<Ctx>, SigningError> {
+        self.provider.aggregate_signatures(signatures).await
+    }
+
+    async fn verify_aggregated_commit(
+        &self,
+        height: &Ctx::Height,
+        round: Round,
+        value_id: &ValueId<Ctx>,
+        signature: &Signature<Ctx>,
+        public_keys: &[&PublicKey<Ctx>],
+    ) -> Result<VerificationResult, SigningError> {
+        self.provider
+            .verify_aggregated_commit(height, round, value_id, signature, public_keys)
+            .await
+    }
+
+    // Peer identities are not consensus messages, so there is nothing to guard against here
+    async fn sign_peer_identity(&self, peer_id: &[u8]) -> Result<Signature<Ctx>, SigningError> {
+        self.provider.sign_peer_identity(peer_id).await
+    }
+
+    async fn verify_peer_identity(
+        &self,
+        peer_id: &[u8],
+        signature: &Signature<Ctx>,
+        public_key: &PublicKey<Ctx>,
+    ) -> Result<VerificationResult, SigningError> {
+        self.provider
+            .verify_peer_identity(peer_id, signature, public_key)
+            .await
+    }
+}
@@ -0,0 +1,24 @@
+//! Double-sign protection for signing providers of the Malachite BFT consensus engine.
+//!
+//! The write-ahead log of the consensus engine is not enough to prevent a validator from
+//! equivocating: it is reset when a height is restarted, and it is lost along with the rest
+//! of the node's data, eg. when a validator is restored from a snapshot or run twice by mistake.
+//!
+//! A [`DoubleSignGuard`] wraps a [`SigningProvider`](malachitebft_signing::SigningProvider) and
+//! keeps track of the last height, round and step at which it signed a vote or a proposal,
+//! as well as the value it signed, in a [`SignState`] file on disk. It refuses to sign any
+//! vote or proposal for an earlier height, round or step, or for another value at the same
+//! height, round and step, much like the `priv_validator_state.json` file of CometBFT.
+//!
+//! **NOTE:** The guard only protects against double-signing as long as its state file is kept,
+//! and is not shared with another guard. It must therefore be stored alongside the private key,
+//! eg. on the host of a remote signer.
+
+mod error;
+pub use error::Error;
+
+mod guard;
+pub use guard::DoubleSignGuard;
+
+mod state;
+pub use state::{SignState, SignStateFile, Step};
@@ -0,0 +1,241 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use malachitebft_codec::Codec;
+use malachitebft_core_types::{
+    Context, Height, NilOrVal, Proposal, Value, ValueId, Vote, VoteType,
+};
+
+use crate::Error;
+
+/// The step of a round at which a message is signed.
+///
+/// Steps are ordered as they happen in a round: a proposal comes before a prevote,
+/// which comes before a precommit.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Step {
+    /// Proposal of a value
+    Proposal,
+    /// Prevote for a value or for nil
+    Prevote,
+    /// Precommit for a value or for nil
+    Precommit,
+}
+
+impl From<VoteType> for Step {
+    fn from(vote_type: VoteType) -> Self {
+        match vote_type {
+            VoteType::Prevote => Self::Prevote,
+            VoteType::Precommit => Self::Precommit,
+        }
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Proposal => write!(f, "proposal"),
+            Self::Prevote => write!(f, "prevote"),
+            Self::Precommit => write!(f, "precommit"),
+        }
+    }
+}
+
+/// The height, round and step of a signed message, along with the value it is for.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignState {
+    /// Height of the message
+    pub height: u64,
+
+    /// Round of the message
+    pub round: i64,
+
+    /// Step of the message
+    pub step: Step,
+
+    /// Hex-encoded id of the value, or `None` for a vote for nil
+    pub value: Option<String>,
+}
+
+impl SignState {
+    /// The state of the given vote, with the id of its value encoded with the given codec.
+    pub fn of_vote<Ctx, C>(vote: &Ctx::Vote, codec: &C) -> Result<Self, Error>
+    where
+        Ctx: Context,
+        C: Codec<ValueId<Ctx>>,
+    {
+        Ok(Self {
+            height: vote.height().as_u64(),
+            round: vote.round().as_i64(),
+            step: Step::from(vote.vote_type()),
+            value: encode_value::<Ctx, C>(vote.value(), codec)?,
+        })
+    }
+
+    /// The state of the given proposal, with the id of its value encoded with the given codec.
+    pub fn of_proposal<Ctx, C>(proposal: &Ctx::Proposal, codec: &C) -> Result<Self, Error>
+    where
+        Ctx: Context,
+        C: Codec<ValueId<Ctx>>,
+    {
+        let value = NilOrVal::Val(proposal.value().id());
+
+        Ok(Self {
+            height: proposal.height().as_u64(),
+            round: proposal.round().as_i64(),
+            step: Step::Proposal,
+            value: encode_value::<Ctx, C>(&value, codec)?,
+        })
+    }
+
+    /// Compare the height, round and step of both states, ignoring their values.
+    pub fn cmp_hrs(&self, other: &Self) -> Ordering {
+        (self.height, self.round, self.step).cmp(&(other.height, other.round, other.step))
+    }
+
+    /// Check whether a message with the given state can be signed after one with this state.
+    ///
+    /// Return `true` if the next state comes after this one, and thus must be stored before signing,
+    /// or `false` if it is the same state, for which the message can be signed again.
+    /// Fail if the next state comes before this one, or is for another value at the same
+    /// height, round and step.
+    pub fn check_next(&self, next: &SignState) -> Result<bool, Error> {
+        match next.cmp_hrs(self) {
+            Ordering::Less => Err(Error::Regression {
+                last: self.clone(),
+                next: next.clone(),
+            }),
+            Ordering::Equal if next.value == self.value => Ok(false),
+            Ordering::Equal => Err(Error::Conflict {
+                last: self.clone(),
+                next: next.clone(),
+            }),
+            Ordering::Greater => Ok(true),
+        }
+    }
+
+    /// Load the state from the given file, if it exists.
+    pub fn load(path: &Path) -> Result<Option<Self>, Error> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(source) => {
+                return Err(Error::Io {
+                    path: path.to_path_buf(),
+                    source,
+                })
+            }
+        };
+
+        serde_json::from_str(&contents)
+            .map(Some)
+            .map_err(|source| Error::InvalidState {
+                path: path.to_path_buf(),
+                source,
+            })
+    }
+
+    /// Durably store the state to the given file, replacing it atomically.
+    pub fn store(&self, path: &Path) -> Result<(), Error> {
+        let io_error = |source| Error::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+
+        let contents = serde_json::to_vec_pretty(self).map_err(|source| Error::InvalidState {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        let tmp_path = path.with_extension("tmp");
+
+        let mut file = File::create(&tmp_path).map_err(io_error)?;
+        file.write_all(&contents).map_err(io_error)?;
+        file.sync_all().map_err(io_error)?;
+
+        fs::rename(&tmp_path, path).map_err(io_error)?;
+
+        // Make sure the rename itself is durable
+        #[cfg(unix)]
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            File::open(dir)
+                .and_then(|dir| dir.sync_all())
+                .map_err(io_error)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The state of the last signed message, durably stored in a file.
+#[derive(Debug)]
+pub struct SignStateFile {
+    path: PathBuf,
+    state: Option<SignState>,
+}
+
+impl SignStateFile {
+    /// Load the state from the file at the given path, which is only created
+    /// once a first state is stored if it does not exist yet.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, Error> {
+        let path = path.into();
+        let state = SignState::load(&path)?;
+
+        Ok(Self { path, state })
+    }
+
+    /// The path to the state file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The state of the last signed message, if any.
+    pub fn state(&self) -> Option<&SignState> {
+        self.state.as_ref()
+    }
+
+    /// Check that a message with the given state can be signed after the last signed one,
+    /// and durably store it as the last signed state if it comes after it.
+    pub fn update(&mut self, next: SignState) -> Result<(), Error> {
+        if let Some(last) = &self.state {
+            if !last.check_next(&next)? {
+                return Ok(());
+            }
+        }
+
+        next.store(&self.path)?;
+        self.state = Some(next);
+
+        Ok(())
+    }
+}
+
+fn encode_value<Ctx, C>(value: &NilOrVal<ValueId<Ctx>>, codec: &C) -> Result<Option<String>, Error>
+where
+    Ctx: Context,
+    C: Codec<ValueId<Ctx>>,
+{
+    match value {
+        NilOrVal::Nil => Ok(None),
+        NilOrVal::Val(id) => codec
+            .encode(id)
+            .map(|bytes| Some(hex::encode(bytes)))
+            .map_err(|e| Error::Codec(e.to_string())),
+    }
+}
+
+impl fmt::Display for SignState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at height {}, round {}",
+            self.step, self.height, self.round
+        )
+    }
+}
@@ -0,0 +1,58 @@
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+/// Represents an error that can occur when talking to a PKCS#11 module.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// The PKCS#11 module could not be loaded.
+    #[error("Failed to load PKCS#11 module {path}: {reason}")]
+    Load {
+        /// Path to the module
+        path: PathBuf,
+        /// Why loading the module failed
+        reason: String,
+    },
+
+    /// A PKCS#11 function returned an error.
+    #[error(transparent)]
+    Pkcs11(#[from] cryptoki::error::Error),
+
+    /// No token with the given label was found in any slot of the module.
+    #[error("No token found with label '{0}'")]
+    TokenNotFound(String),
+
+    /// No key with the given label and type was found on the token.
+    #[error("No {kind} found with label '{label}'")]
+    KeyNotFound {
+        /// The kind of key which was looked up
+        kind: &'static str,
+        /// Label of the key
+        label: String,
+    },
+
+    /// Several keys with the given label and type were found on the token.
+    #[error("Several {kind}s found with label '{label}'")]
+    AmbiguousKey {
+        /// The kind of key which was looked up
+        kind: &'static str,
+        /// Label of the key
+        label: String,
+    },
+
+    /// A key with the given label already exists on the token.
+    #[error("A key with label '{0}' already exists")]
+    KeyExists(String),
+
+    /// The public key stored on the token is not a valid key for the signing scheme.
+    #[error("Invalid public key on token: {0}")]
+    InvalidPublicKey(String),
+
+    /// The token returned an invalid signature.
+    #[error("Token returned an invalid signature")]
+    InvalidSignature,
+
+    /// The signing task panicked or was cancelled.
+    #[error("Signing task failed: {0}")]
+    Task(String),
+}
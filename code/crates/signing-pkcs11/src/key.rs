@@ -0,0 +1,120 @@
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use cryptoki::object::ObjectHandle;
+use cryptoki::session::Session;
+
+use crate::module::lock;
+use crate::{Error, Pkcs11Scheme};
+
+/// Message signed when loading a key, to check that its private key matches its public key.
+const CHECK_MESSAGE: &[u8] = b"malachitebft-signing-pkcs11 key check";
+
+/// A key pair for the signing scheme `S` stored on a PKCS#11 token,
+/// whose private key never leaves the token.
+pub struct Pkcs11Key<S: Pkcs11Scheme> {
+    session: Arc<Mutex<Session>>,
+    label: String,
+    private_key: ObjectHandle,
+    public_key: S::PublicKey,
+}
+
+impl<S: Pkcs11Scheme> Pkcs11Key<S> {
+    pub(crate) fn new(
+        session: Arc<Mutex<Session>>,
+        label: &str,
+        private_key: ObjectHandle,
+        ec_point: &[u8],
+    ) -> Result<Self, Error> {
+        // The point should be DER-encoded as an OCTET STRING, but some modules return it as is
+        let public_key = decode_octet_string(ec_point)
+            .and_then(|point| S::public_key_from_ec_point(point).ok())
+            .map_or_else(|| S::public_key_from_ec_point(ec_point), Ok)?;
+
+        Ok(Self {
+            session,
+            label: label.to_string(),
+            private_key,
+            public_key,
+        })
+    }
+
+    /// Label of the key on the token.
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// The public key of the key pair.
+    pub fn public_key(&self) -> &S::PublicKey {
+        &self.public_key
+    }
+
+    /// Sign the given message with the private key, on the token.
+    ///
+    /// This blocks until the token has signed the message, and must therefore
+    /// not be called from an asynchronous context.
+    pub fn sign(&self, message: &[u8]) -> Result<S::Signature, Error> {
+        let data = S::prepare(message);
+        let session = lock(&self.session);
+
+        let signature = session.sign(&S::sign_mechanism(), self.private_key, &data)?;
+
+        S::signature_from_token(&signature)
+    }
+
+    /// Check that the private key signs messages which verify against the public key.
+    pub(crate) fn check(&self) -> Result<(), Error> {
+        let signature = self.sign(CHECK_MESSAGE)?;
+
+        if S::verify(&self.public_key, CHECK_MESSAGE, &signature) {
+            Ok(())
+        } else {
+            Err(Error::InvalidPublicKey(format!(
+                "public key '{}' does not match its private key",
+                self.label
+            )))
+        }
+    }
+}
+
+impl<S: Pkcs11Scheme> Clone for Pkcs11Key<S> {
+    fn clone(&self) -> Self {
+        Self {
+            session: Arc::clone(&self.session),
+            label: self.label.clone(),
+            private_key: self.private_key,
+            public_key: self.public_key.clone(),
+        }
+    }
+}
+
+impl<S: Pkcs11Scheme> fmt::Debug for Pkcs11Key<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pkcs11Key")
+            .field("label", &self.label)
+            .field("public_key", &self.public_key)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The contents of the given DER-encoded OCTET STRING, if it is one.
+fn decode_octet_string(bytes: &[u8]) -> Option<&[u8]> {
+    let (&tag, rest) = bytes.split_first()?;
+    if tag != 0x04 {
+        return None;
+    }
+
+    let (&len, rest) = rest.split_first()?;
+    let (len, rest) = match len {
+        // Short form
+        0..=0x7f => (usize::from(len), rest),
+        // Long form, with the length on one byte
+        0x81 => {
+            let (&len, rest) = rest.split_first()?;
+            (usize::from(len), rest)
+        }
+        _ => return None,
+    };
+
+    (rest.len() == len).then_some(rest)
+}
@@ -0,0 +1,46 @@
+//! A [`SigningProvider`](malachitebft_signing::SigningProvider) for the Malachite BFT consensus
+//! engine backed by a PKCS#11 token, such as a hardware security module (HSM).
+//!
+//! The private key of the validator is generated on, or imported into, the token, and never
+//! leaves it: the [`Pkcs11SigningProvider`] sends the bytes to sign to the token, which signs
+//! them with the private key. Keys are looked up on the token by label.
+//!
+//! The PKCS#11 module is accessed through the [`cryptoki`] crate.
+//!
+//! The following signing schemes are supported, each behind the feature of the same name:
+//! - `ed25519`: Ed25519, with the `CKM_EDDSA` mechanism (enabled by default)
+//! - `p256`: ECDSA over the P-256 curve, with the `CKM_ECDSA` mechanism over SHA-256 digests
+//! - `p384`: ECDSA over the P-384 curve, with the `CKM_ECDSA` mechanism over SHA-384 digests
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! let module = Module::load("/usr/lib/softhsm/libsofthsm2.so")?;
+//! let session = module.open("malachite", &pin)?;
+//! let key = session.find_key::<Ed25519>("validator")?;
+//!
+//! let provider = Pkcs11SigningProvider::new(key, sign_bytes, verifier);
+//! ```
+//!
+//! ## Testing with SoftHSM
+//!
+//! A token for testing can be created with [SoftHSM](https://github.com/opendnssec/SoftHSMv2):
+//!
+//! ```shell
+//! softhsm2-util --init-token --free --label malachite --pin 1234 --so-pin 1234
+//! ```
+
+mod error;
+pub use error::Error;
+
+mod key;
+pub use key::Pkcs11Key;
+
+mod module;
+pub use module::{Module, Session};
+
+mod provider;
+pub use provider::Pkcs11SigningProvider;
+
+mod scheme;
+pub use scheme::Pkcs11Scheme;
@@ -0,0 +1,225 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use cryptoki::context::{CInitializeArgs, Pkcs11};
+use cryptoki::error::{Error as Pkcs11Error, RvError};
+use cryptoki::object::{Attribute, AttributeType, ObjectClass, ObjectHandle};
+use cryptoki::session::{Session as Pkcs11Session, UserType};
+use cryptoki::slot::Slot;
+use cryptoki::types::AuthPin;
+use tracing::debug;
+
+use crate::key::Pkcs11Key;
+use crate::{Error, Pkcs11Scheme};
+
+/// A PKCS#11 module, ie. the shared library provided by the vendor of a
+/// hardware security module, or by SoftHSM.
+///
+/// The module is initialized when loaded, and finalized once the module and all the sessions
+/// opened with it are dropped. Since a module can only be initialized once per process,
+/// it should be loaded once and cloned wherever it is needed.
+#[derive(Clone)]
+pub struct Module {
+    path: PathBuf,
+    context: Pkcs11,
+}
+
+impl Module {
+    /// Load and initialize the PKCS#11 module at the given path,
+    /// eg. `/usr/lib/softhsm/libsofthsm2.so`.
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self, Error> {
+        let path = path.into();
+
+        let context = Pkcs11::new(&path).map_err(|e| Error::Load {
+            path: path.clone(),
+            reason: e.to_string(),
+        })?;
+
+        context.initialize(CInitializeArgs::OsThreads)?;
+
+        Ok(Self { path, context })
+    }
+
+    /// Path to the shared library of the module.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Open a session with the token with the given label, and log in as its user
+    /// with the given PIN.
+    ///
+    /// As per PKCS#11, logging in applies to all the sessions of the process with the token,
+    /// so the PIN is not checked again if another session is already logged in.
+    pub fn open(&self, token_label: &str, pin: &str) -> Result<Session, Error> {
+        let slot = self.find_slot(token_label)?;
+
+        // The session is closed when dropped, eg. if logging in fails
+        let session = self.context.open_rw_session(slot)?;
+
+        match session.login(UserType::User, Some(&AuthPin::new(pin.to_string()))) {
+            Ok(()) | Err(Pkcs11Error::Pkcs11(RvError::UserAlreadyLoggedIn, _)) => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        debug!(token = %token_label, %slot, "Logged in to PKCS#11 token");
+
+        Ok(Session {
+            token_label: token_label.to_string(),
+            session: Arc::new(Mutex::new(session)),
+        })
+    }
+
+    fn find_slot(&self, token_label: &str) -> Result<Slot, Error> {
+        for slot in self.context.get_slots_with_token()? {
+            if self.context.get_token_info(slot)?.label() == token_label {
+                return Ok(slot);
+            }
+        }
+
+        Err(Error::TokenNotFound(token_label.to_string()))
+    }
+}
+
+impl fmt::Debug for Module {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Module").field("path", &self.path).finish()
+    }
+}
+
+/// A session with a token, logged in as its user.
+///
+/// PKCS#11 sessions cannot be used concurrently, so all the operations on a session,
+/// including signing with the keys found or generated with it, are serialized.
+pub struct Session {
+    token_label: String,
+    session: Arc<Mutex<Pkcs11Session>>,
+}
+
+impl Session {
+    /// Label of the token this session was opened with.
+    pub fn token_label(&self) -> &str {
+        &self.token_label
+    }
+
+    /// Find the key pair with the given label for the signing scheme `S` on the token.
+    ///
+    /// The key is checked to sign messages which verify against its public key.
+    pub fn find_key<S: Pkcs11Scheme>(&self, label: &str) -> Result<Pkcs11Key<S>, Error> {
+        let (private_key, public_key) = {
+            let session = lock(&self.session);
+            let private_key = find_key::<S>(&session, ObjectClass::PRIVATE_KEY, label)?;
+            let public_key = find_key::<S>(&session, ObjectClass::PUBLIC_KEY, label)?;
+            (private_key, ec_point(&session, public_key)?)
+        };
+
+        let key = Pkcs11Key::new(Arc::clone(&self.session), label, private_key, &public_key)?;
+        key.check()?;
+
+        Ok(key)
+    }
+
+    /// Generate a new key pair for the signing scheme `S` on the token, with the given label.
+    ///
+    /// The private key is generated as sensitive and non-extractable,
+    /// so that it never leaves the token.
+    pub fn generate_key<S: Pkcs11Scheme>(&self, label: &str) -> Result<Pkcs11Key<S>, Error> {
+        let (private_key, public_key) = {
+            let session = lock(&self.session);
+
+            if !find_objects::<S>(&session, ObjectClass::PRIVATE_KEY, label)?.is_empty() {
+                return Err(Error::KeyExists(label.to_string()));
+            }
+
+            let public_template = [
+                Attribute::Token(true),
+                Attribute::Verify(true),
+                Attribute::Label(label.as_bytes().to_vec()),
+                Attribute::EcParams(S::EC_PARAMS.to_vec()),
+            ];
+
+            let private_template = [
+                Attribute::Token(true),
+                Attribute::Private(true),
+                Attribute::Sensitive(true),
+                Attribute::Extractable(false),
+                Attribute::Sign(true),
+                Attribute::Label(label.as_bytes().to_vec()),
+            ];
+
+            let (public_key, private_key) = session.generate_key_pair(
+                &S::key_gen_mechanism(),
+                &public_template,
+                &private_template,
+            )?;
+
+            (private_key, ec_point(&session, public_key)?)
+        };
+
+        debug!(%label, scheme = S::NAME, "Generated key pair on PKCS#11 token");
+
+        Pkcs11Key::new(Arc::clone(&self.session), label, private_key, &public_key)
+    }
+}
+
+impl fmt::Debug for Session {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Session")
+            .field("token_label", &self.token_label)
+            .finish_non_exhaustive()
+    }
+}
+
+pub(crate) fn lock(session: &Mutex<Pkcs11Session>) -> MutexGuard<'_, Pkcs11Session> {
+    session.lock().expect("poisoned mutex")
+}
+
+fn find_objects<S: Pkcs11Scheme>(
+    session: &Pkcs11Session,
+    class: ObjectClass,
+    label: &str,
+) -> Result<Vec<ObjectHandle>, Error> {
+    let template = [
+        Attribute::Class(class),
+        Attribute::KeyType(S::KEY_TYPE),
+        Attribute::Label(label.as_bytes().to_vec()),
+    ];
+
+    Ok(session.find_objects(&template)?)
+}
+
+fn find_key<S: Pkcs11Scheme>(
+    session: &Pkcs11Session,
+    class: ObjectClass,
+    label: &str,
+) -> Result<ObjectHandle, Error> {
+    let kind = if class == ObjectClass::PRIVATE_KEY {
+        "private key"
+    } else {
+        "public key"
+    };
+
+    match find_objects::<S>(session, class, label)?.as_slice() {
+        [object] => Ok(*object),
+        [] => Err(Error::KeyNotFound {
+            kind,
+            label: label.to_string(),
+        }),
+        _ => Err(Error::AmbiguousKey {
+            kind,
+            label: label.to_string(),
+        }),
+    }
+}
+
+/// The `CKA_EC_POINT` attribute of the given public key.
+fn ec_point(session: &Pkcs11Session, public_key: ObjectHandle) -> Result<Vec<u8>, Error> {
+    session
+        .get_attributes(public_key, &[AttributeType::EcPoint])?
+        .into_iter()
+        .find_map(|attribute| match attribute {
+            Attribute::EcPoint(point) => Some(point),
+            _ => None,
+        })
+        .ok_or_else(|| Error::InvalidPublicKey("missing CKA_EC_POINT attribute".to_string()))
+}
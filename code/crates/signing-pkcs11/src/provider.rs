@@ -0,0 +1,191 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+
+use malachitebft_core_types::{Context, PublicKey, Round, Signature, SignedMessage, ValueId};
+use malachitebft_signing::{
+    peer_identity_sign_bytes, Error as SigningError, SignBytes, SigningProvider, VerificationResult,
+};
+
+use crate::{Error, Pkcs11Key, Pkcs11Scheme};
+
+/// A [`SigningProvider`] which signs messages with a private key stored on a PKCS#11 token,
+/// such as a hardware security module, so that it is never held in memory.
+///
+/// Since the token only sees the bytes to sign, these are computed with the given `sign_bytes`.
+/// Signatures are verified locally by the given verifier, whose signing methods are never called.
+pub struct Pkcs11SigningProvider<Ctx: Context, S, V>
+where
+    Ctx::SigningScheme: Pkcs11Scheme,
+{
+    key: Pkcs11Key<Ctx::SigningScheme>,
+    sign_bytes: S,
+    verifier: V,
+}
+
+impl<Ctx, S, V> Pkcs11SigningProvider<Ctx, S, V>
+where
+    Ctx: Context,
+    Ctx::SigningScheme: Pkcs11Scheme,
+    S: SignBytes<Ctx>,
+    V: SigningProvider<Ctx>,
+{
+    /// Create a provider which signs messages with the given key,
+    /// computing sign bytes with `sign_bytes` and verifying signatures with the given verifier.
+    pub fn new(key: Pkcs11Key<Ctx::SigningScheme>, sign_bytes: S, verifier: V) -> Self {
+        Self {
+            key,
+            sign_bytes,
+            verifier,
+        }
+    }
+
+    /// The key used for signing.
+    pub fn key(&self) -> &Pkcs11Key<Ctx::SigningScheme> {
+        &self.key
+    }
+
+    /// The public key of the validator, which the signatures verify against.
+    pub fn public_key(&self) -> &PublicKey<Ctx> {
+        self.key.public_key()
+    }
+
+    /// Sign the given bytes on the token, without blocking the runtime.
+    async fn sign(&self, bytes: Bytes) -> Result<Signature<Ctx>, SigningError> {
+        let key = self.key.clone();
+
+        tokio::task::spawn_blocking(move || key.sign(&bytes))
+            .await
+            .map_err(|e| SigningError::from_source(Error::Task(e.to_string())))?
+            .map_err(SigningError::from_source)
+    }
+}
+
+#[async_trait]
+impl<Ctx, S, V> SigningProvider<Ctx> for Pkcs11SigningProvider<Ctx, S, V>
+where
+    Ctx: Context,
+    Ctx::SigningScheme: Pkcs11Scheme,
+    S: SignBytes<Ctx>,
+    V: SigningProvider<Ctx>,
+{
+    async fn sign_vote(
+        &self,
+        vote: Ctx::Vote,
+    ) -> Result<SignedMessage<Ctx, Ctx::Vote>, SigningError> {
+        let signature = self.sign(self.sign_bytes.vote(&vote)).await?;
+        Ok(SignedMessage::new(vote, signature))
+    }
+
+    async fn verify_signed_vote(
+        &self,
+        vote: &Ctx::Vote,
+        signature: &Signature<Ctx>,
+        public_key: &PublicKey<Ctx>,
+    ) -> Result<VerificationResult, SigningError> {
+        self.verifier
+            .verify_signed_vote(vote, signature, public_key)
+            .await
+    }
+
+    async fn sign_proposal(
+        &self,
+        proposal: Ctx::Proposal,
+    ) -> Result<SignedMessage<Ctx, Ctx::Proposal>, SigningError> {
+        let signature = self.sign(self.sign_bytes.proposal(&proposal)).await?;
+        Ok(SignedMessage::new(proposal, signature))
+    }
+
+    async fn verify_signed_proposal(
+        &self,
+        proposal: &Ctx::Proposal,
+        signature: &Signature<Ctx>,
+        public_key: &PublicKey<Ctx>,
+    ) -> Result<VerificationResult, SigningError> {
+        self.verifier
+            .verify_signed_proposal(proposal, signature, public_key)
+            .await
+    }
+
+    async fn sign_proposal_part(
+        &self,
+        proposal_part: Ctx::ProposalPart,
+    ) -> Result<SignedMessage<Ctx, Ctx::ProposalPart>, SigningError> {
+        let signature = self
+            .sign(self.sign_bytes.proposal_part(&proposal_part))
+            .await?;
+        Ok(SignedMessage::new(proposal_part, signature))
+    }
+
+    async fn verify_signed_proposal_part(
+        &self,
+        proposal_part: &Ctx::ProposalPart,
+        signature: &Signature<Ctx>,
+        public_key: &PublicKey<Ctx>,
+    ) -> Result<VerificationResult, SigningError> {
+        self.verifier
+            .verify_signed_proposal_part(proposal_part, signature, public_key)
+            .await
+    }
+
+    async fn sign_vote_extension(
+        &self,
+        extension: Ctx::Extension,
+    ) -> Result<SignedMessage<Ctx, Ctx::Extension>, SigningError> {
+        let signature = self.sign(self.sign_bytes.extension(&extension)).await?;
+        Ok(SignedMessage::new(extension, signature))
+    }
+
+    async fn verify_signed_vote_extension(
+        &self,
+        extension: &Ctx::Extension,
+        signature: &Signature<Ctx>,
+        public_key: &PublicKey<Ctx>,
+    ) -> Result<VerificationResult, SigningError> {
+        self.verifier
+            .verify_signed_vote_extension(extension, signature, public_key)
+            .await
+    }
+
+    async fn verify_signed_votes(
+        &self,
+        votes: &[(Ctx::Vote, &Signature<Ctx>, &PublicKey<Ctx>)],
+    ) -> Result<Vec<VerificationResult>, SigningError> {
+        self.verifier.verify_signed_votes(votes).await
+    }
+
+    async fn aggregate_signatures(
+        &self,
+        signatures: &[Signature<Ctx>],
+    ) -> Result<Signature<Ctx>, SigningError> {
+        self.verifier.aggregate_signatures(signatures).await
+    }
+
+    async fn verify_aggregated_commit(
+        &self,
+        height: &Ctx::Height,
+        round: Round,
+        value_id: &ValueId<Ctx>,
+        signature: &Signature<Ctx>,
+        public_keys: &[&PublicKey<Ctx>],
+    ) -> Result<VerificationResult, SigningError> {
+        self.verifier
+            .verify_aggregated_commit(height, round, value_id, signature, public_keys)
+            .await
+    }
+
+    async fn sign_peer_identity(&self, peer_id: &[u8]) -> Result<Signature<Ctx>, SigningError> {
+        self.sign(Bytes::from(peer_identity_sign_bytes(peer_id)))
+            .await
+    }
+
+    async fn verify_peer_identity(
+        &self,
+        peer_id: &[u8],
+        signature: &Signature<Ctx>,
+        public_key: &PublicKey<Ctx>,
+    ) -> Result<VerificationResult, SigningError> {
+        self.verifier
+            .verify_peer_identity(peer_id, signature, public_key)
+            .await
+    }
+}
@@ -0,0 +1,168 @@
+use std::borrow::Cow;
+
+use cryptoki::mechanism::Mechanism;
+use cryptoki::object::KeyType;
+use malachitebft_core_types::SigningScheme;
+
+use crate::Error;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A signing scheme whose keys can be stored on a PKCS#11 token.
+///
+/// This trait is sealed, and implemented for the Ed25519 signing scheme
+/// and the ECDSA signing schemes over the P-256 and P-384 curves,
+/// each behind the feature of the same name.
+pub trait Pkcs11Scheme: SigningScheme + sealed::Sealed + Send + Sync + 'static {
+    /// Name of the signing scheme.
+    const NAME: &'static str;
+
+    /// Type of the keys of this scheme on the token (`CKK_*`).
+    const KEY_TYPE: KeyType;
+
+    /// DER-encoded object identifier of the curve (`CKA_EC_PARAMS`).
+    const EC_PARAMS: &'static [u8];
+
+    /// Mechanism for generating a key pair on the token (`CKM_*`).
+    fn key_gen_mechanism() -> Mechanism<'static>;
+
+    /// Mechanism for signing with a private key on the token (`CKM_*`).
+    fn sign_mechanism() -> Mechanism<'static>;
+
+    /// The data to pass to the token for signing the given message,
+    /// eg. its digest for ECDSA.
+    fn prepare(message: &[u8]) -> Cow<'_, [u8]>;
+
+    /// Decode the given point (`CKA_EC_POINT`) of a public key on the token.
+    fn public_key_from_ec_point(point: &[u8]) -> Result<Self::PublicKey, Error>;
+
+    /// Decode a signature made by the token.
+    fn signature_from_token(bytes: &[u8]) -> Result<Self::Signature, Error>;
+
+    /// Verify the given signature of a message against the given public key.
+    fn verify(public_key: &Self::PublicKey, message: &[u8], signature: &Self::Signature) -> bool;
+}
+
+#[cfg(feature = "ed25519")]
+mod ed25519 {
+    use cryptoki::mechanism::eddsa::{EddsaParams, EddsaSignatureScheme};
+    use malachitebft_signing_ed25519::{Ed25519, PublicKey, Signature};
+
+    use super::*;
+
+    impl sealed::Sealed for Ed25519 {}
+
+    impl Pkcs11Scheme for Ed25519 {
+        const NAME: &'static str = "Ed25519";
+        const KEY_TYPE: KeyType = KeyType::EC_EDWARDS;
+
+        // id-Ed25519 (1.3.101.112)
+        const EC_PARAMS: &'static [u8] = &[0x06, 0x03, 0x2b, 0x65, 0x70];
+
+        fn key_gen_mechanism() -> Mechanism<'static> {
+            Mechanism::EccEdwardsKeyPairGen
+        }
+
+        // Pure EdDSA, without pre-hashing nor context
+        fn sign_mechanism() -> Mechanism<'static> {
+            Mechanism::Eddsa(EddsaParams::new(EddsaSignatureScheme::Pure))
+        }
+
+        // Ed25519 hashes the message itself
+        fn prepare(message: &[u8]) -> Cow<'_, [u8]> {
+            Cow::Borrowed(message)
+        }
+
+        fn public_key_from_ec_point(point: &[u8]) -> Result<PublicKey, Error> {
+            let bytes = <[u8; 32]>::try_from(point).map_err(|_| {
+                Error::InvalidPublicKey(format!("expected 32 bytes, got {}", point.len()))
+            })?;
+
+            ed25519_consensus::VerificationKey::try_from(bytes)
+                .map(PublicKey::new)
+                .map_err(|e| Error::InvalidPublicKey(e.to_string()))
+        }
+
+        fn signature_from_token(bytes: &[u8]) -> Result<Signature, Error> {
+            Signature::try_from(bytes).map_err(|_| Error::InvalidSignature)
+        }
+
+        fn verify(public_key: &PublicKey, message: &[u8], signature: &Signature) -> bool {
+            public_key.verify(message, signature).is_ok()
+        }
+    }
+}
+
+/// Implement [`Pkcs11Scheme`] for the ECDSA signing scheme over the given curve,
+/// signing the digest of messages with the given hash function.
+#[cfg(any(feature = "p256", feature = "p384"))]
+macro_rules! impl_ecdsa {
+    ($config:ty, $name:literal, $ec_params:expr, $digest:ty) => {
+        impl sealed::Sealed for Ecdsa<$config> {}
+
+        impl Pkcs11Scheme for Ecdsa<$config> {
+            const NAME: &'static str = $name;
+            const KEY_TYPE: KeyType = KeyType::EC;
+            const EC_PARAMS: &'static [u8] = $ec_params;
+
+            fn key_gen_mechanism() -> Mechanism<'static> {
+                Mechanism::EccKeyPairGen
+            }
+
+            fn sign_mechanism() -> Mechanism<'static> {
+                Mechanism::Ecdsa
+            }
+
+            // The token only computes the raw ECDSA signature of the digest
+            fn prepare(message: &[u8]) -> Cow<'_, [u8]> {
+                Cow::Owned(<$digest>::digest(message).to_vec())
+            }
+
+            fn public_key_from_ec_point(point: &[u8]) -> Result<PublicKey<$config>, Error> {
+                PublicKey::from_sec1_bytes(point)
+                    .map_err(|_| Error::InvalidPublicKey("invalid SEC1 point".to_string()))
+            }
+
+            // The signature is the concatenation of `r` and `s`
+            fn signature_from_token(bytes: &[u8]) -> Result<Signature<$config>, Error> {
+                Signature::from_slice(bytes).map_err(|_| Error::InvalidSignature)
+            }
+
+            fn verify(
+                public_key: &PublicKey<$config>,
+                message: &[u8],
+                signature: &Signature<$config>,
+            ) -> bool {
+                public_key.verify(message, signature).is_ok()
+            }
+        }
+    };
+}
+
+#[cfg(any(feature = "p256", feature = "p384"))]
+mod ecdsa {
+    use malachitebft_signing_ecdsa::{Ecdsa, PublicKey, Signature};
+    use sha2::Digest;
+
+    use super::*;
+
+    // prime256v1 (1.2.840.10045.3.1.7)
+    #[cfg(feature = "p256")]
+    impl_ecdsa!(
+        malachitebft_signing_ecdsa::P256Config,
+        "ECDSA P-256",
+        &[0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07],
+        sha2::Sha256
+    );
+
+    // secp384r1 (1.3.132.0.34)
+    #[cfg(feature = "p384")]
+    impl_ecdsa!(
+        malachitebft_signing_ecdsa::P384Config,
+        "ECDSA P-384",
+        &[0x06, 0x05, 0x2b, 0x81, 0x04, 0x00, 0x22],
+        sha2::Sha384
+    );
+}
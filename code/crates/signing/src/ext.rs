@@ -3,12 +3,14 @@ use alloc::vec::Vec;
 
 use async_trait::async_trait;
 use malachitebft_core_types::{
-    CertificateError, CommitCertificate, CommitSignature, Context, NilOrVal, PolkaCertificate,
-    PolkaSignature, RoundCertificate, RoundCertificateType, RoundSignature, ThresholdParams,
+    AggregatedSignature, AmnesiaEvidence, CertificateError, CommitCertificate, CommitSignature,
+    Context, DoubleProposalEvidence, DoubleVoteEvidence, Evidence, EvidenceError, NilOrVal,
+    PolkaCertificate, PolkaSignature, PublicKey, RoundCertificate, RoundCertificateType,
+    RoundSignature, Signature, SignedProposal, SignedVote, SignerBitmap, ThresholdParams,
     Validator, ValidatorSet, VoteType, VotingPower,
 };
 
-use crate::SigningProvider;
+use crate::{SigningProvider, VerificationResult};
 
 /// Extension trait providing additional certificate verification functionality for signing providers.
 ///
@@ -58,10 +60,13 @@ where
 
     /// Verify the given certificate against the given validator set.
     ///
-    /// - For each commit signature in the certificate:
-    ///   - Reconstruct the signed precommit and verify its signature
+    /// - For each commit signature in the certificate, reconstruct the signed precommit
+    /// - Verify the signatures of all the precommits at once, with [`SigningProvider::verify_signed_votes`]
     /// - Check that we have 2/3+ of voting power has signed the certificate
     ///
+    /// If the commit signatures of the certificate are aggregated,
+    /// defer to [`SigningProviderExt::verify_aggregated_commit_certificate`] instead.
+    ///
     /// If any of those steps fail, return a [`CertificateError`].
     async fn verify_commit_certificate(
         &self,
@@ -71,10 +76,39 @@ where
         thresholds: ThresholdParams,
     ) -> Result<(), CertificateError<Ctx>>;
 
+    /// Verify the given certificate, whose commit signatures are aggregated,
+    /// against the given validator set.
+    ///
+    /// - Look up each signer of the aggregated signature in the validator set, by index
+    /// - Check that we have 2/3+ of voting power has signed the certificate
+    /// - Verify the aggregated signature over the commit message against the public keys
+    ///   of the signers at once, with [`SigningProvider::verify_aggregated_commit`]
+    ///
+    /// If any of those steps fail, return a [`CertificateError`].
+    async fn verify_aggregated_commit_certificate(
+        &self,
+        ctx: &Ctx,
+        certificate: &CommitCertificate<Ctx>,
+        validator_set: &Ctx::ValidatorSet,
+        thresholds: ThresholdParams,
+    ) -> Result<(), CertificateError<Ctx>>;
+
+    /// Aggregate the commit signatures of the given certificate into a single signature,
+    /// with the signers identified by their index in the given validator set.
+    ///
+    /// The certificate must have been verified against that same validator set beforehand,
+    /// as a single invalid commit signature makes the whole aggregated signature invalid.
+    /// Certificates which are already aggregated are returned as is.
+    async fn aggregate_commit_certificate(
+        &self,
+        certificate: &CommitCertificate<Ctx>,
+        validator_set: &Ctx::ValidatorSet,
+    ) -> Result<CommitCertificate<Ctx>, CertificateError<Ctx>>;
+
     /// Verify the polka certificate against the given validator set.
     ///
-    /// - For each signature in the certificate:
-    ///   - Reconstruct the signed prevote and verify its signature
+    /// - For each signature in the certificate, reconstruct the signed prevote
+    /// - Verify the signatures of all the prevotes at once, with [`SigningProvider::verify_signed_votes`]
     /// - Check that we have 2/3+ of voting power has signed the certificate
     ///
     /// If any of those steps fail, return a [`CertificateError`].
@@ -88,8 +122,8 @@ where
 
     /// Verify the round certificate against the given validator set.
     ///
-    /// - For each signature in the certificate:
-    ///   - Reconstruct the signed vote and verify its signature.
+    /// - For each signature in the certificate, reconstruct the signed vote.
+    /// - Verify the signatures of all the votes at once, with [`SigningProvider::verify_signed_votes`].
     /// - Check that the required voting power has signed the certificate:
     ///   - If `Precommit`, ensure that 2/3+ of the voting power is represented.
     ///   - If `Skip`, ensure that 1/3+ of the voting power is represented.
@@ -102,6 +136,62 @@ where
         validator_set: &Ctx::ValidatorSet,
         thresholds: ThresholdParams,
     ) -> Result<(), CertificateError<Ctx>>;
+
+    /// Verify evidence of a double vote against the given validator set.
+    ///
+    /// - Check that both votes were cast by the same validator, for the same height,
+    ///   round and vote type, but for different values
+    /// - Check that the validator is in the validator set
+    /// - Verify the signatures of both votes
+    ///
+    /// If any of those steps fail, return an [`EvidenceError`].
+    async fn verify_double_vote_evidence(
+        &self,
+        evidence: &DoubleVoteEvidence<Ctx>,
+        validator_set: &Ctx::ValidatorSet,
+    ) -> Result<(), EvidenceError<Ctx>>;
+
+    /// Verify evidence of a double proposal against the given validator set.
+    ///
+    /// - Check that both proposals were signed by the same validator,
+    ///   for the same height and round, and that they differ
+    /// - Check that the validator is in the validator set
+    /// - Verify the signatures of both proposals
+    ///
+    /// If any of those steps fail, return an [`EvidenceError`].
+    async fn verify_double_proposal_evidence(
+        &self,
+        evidence: &DoubleProposalEvidence<Ctx>,
+        validator_set: &Ctx::ValidatorSet,
+    ) -> Result<(), EvidenceError<Ctx>>;
+
+    /// Verify evidence of a lock violation (amnesia attack) against the given validator set.
+    ///
+    /// - Check that both votes were cast by the same validator for the same height,
+    ///   that the first one is a precommit for a value and the second one a prevote
+    ///   for a different value in a later round
+    /// - Check that none of the given polka certificates justifies the prevote
+    /// - Check that the validator is in the validator set
+    /// - Verify the signatures of both votes
+    ///
+    /// If any of those steps fail, return an [`EvidenceError`].
+    async fn verify_amnesia_evidence(
+        &self,
+        evidence: &AmnesiaEvidence<Ctx>,
+        polka_certificates: &[PolkaCertificate<Ctx>],
+        validator_set: &Ctx::ValidatorSet,
+    ) -> Result<(), EvidenceError<Ctx>>;
+
+    /// Verify any kind of evidence of misbehavior against the given validator set,
+    /// by dispatching to the corresponding `verify_*_evidence` method.
+    ///
+    /// The polka certificates are only used to verify evidence of a lock violation.
+    async fn verify_evidence(
+        &self,
+        evidence: &Evidence<Ctx>,
+        polka_certificates: &[PolkaCertificate<Ctx>],
+        validator_set: &Ctx::ValidatorSet,
+    ) -> Result<(), EvidenceError<Ctx>>;
 }
 
 #[async_trait]
@@ -216,8 +306,8 @@ where
 
     /// Verify the commit certificate against the given validator set.
     ///
-    /// - For each commit signature in the certificate:
-    ///   - Reconstruct the signed precommit and verify its signature
+    /// - For each commit signature in the certificate, reconstruct the signed precommit
+    /// - Verify the signatures of all the precommits at once, with [`SigningProvider::verify_signed_votes`]
     /// - Check that we have 2/3+ of voting power has signed the certificate
     ///
     /// If any of those steps fail, return a [`CertificateError`].
@@ -228,10 +318,17 @@ where
         validator_set: &Ctx::ValidatorSet,
         thresholds: ThresholdParams,
     ) -> Result<(), CertificateError<Ctx>> {
-        let mut signed_voting_power = 0;
+        if certificate.is_aggregated() {
+            return self
+                .verify_aggregated_commit_certificate(ctx, certificate, validator_set, thresholds)
+                .await;
+        }
+
+        let mut votes = Vec::with_capacity(certificate.commit_signatures.len());
+        let mut voting_powers = Vec::with_capacity(certificate.commit_signatures.len());
         let mut seen_validators = Vec::new();
 
-        // For each commit signature, reconstruct the signed precommit and verify the signature
+        // For each commit signature, reconstruct the signed precommit
         for commit_sig in &certificate.commit_signatures {
             let validator_address = &commit_sig.address;
 
@@ -246,14 +343,20 @@ where
                 .get_by_address(validator_address)
                 .ok_or_else(|| CertificateError::UnknownValidator(validator_address.clone()))?;
 
-            if let Ok(voting_power) = self
-                .verify_commit_signature(ctx, certificate, commit_sig, validator)
-                .await
-            {
-                signed_voting_power += voting_power;
-            }
+            let vote = ctx.new_precommit(
+                certificate.height,
+                certificate.round,
+                NilOrVal::Val(certificate.value_id.clone()),
+                validator_address.clone(),
+            );
+
+            votes.push((vote, &commit_sig.signature, validator.public_key()));
+            voting_powers.push(validator.voting_power());
         }
 
+        // Verify the signatures of all the precommits at once
+        let signed_voting_power = signed_voting_power(self, &votes, &voting_powers).await;
+
         let total_voting_power = validator_set.total_voting_power();
 
         // Check if we have 2/3+ voting power
@@ -271,10 +374,123 @@ where
         }
     }
 
+    /// Verify the given certificate, whose commit signatures are aggregated,
+    /// against the given validator set.
+    ///
+    /// - Look up each signer of the aggregated signature in the validator set, by index
+    /// - Check that we have 2/3+ of voting power has signed the certificate
+    /// - Verify the aggregated signature over the commit message against the public keys
+    ///   of the signers at once, with [`SigningProvider::verify_aggregated_commit`]
+    ///
+    /// If any of those steps fail, return a [`CertificateError`].
+    async fn verify_aggregated_commit_certificate(
+        &self,
+        _ctx: &Ctx,
+        certificate: &CommitCertificate<Ctx>,
+        validator_set: &Ctx::ValidatorSet,
+        thresholds: ThresholdParams,
+    ) -> Result<(), CertificateError<Ctx>> {
+        let Some(aggregated) = &certificate.aggregated_signature else {
+            return Err(CertificateError::InvalidAggregatedSignature);
+        };
+
+        if !certificate.commit_signatures.is_empty() {
+            return Err(CertificateError::MixedSignatures);
+        }
+
+        let mut signed_voting_power = 0;
+        let mut public_keys = Vec::new();
+
+        for index in aggregated.signers.iter() {
+            // Abort if validator not in validator set
+            let validator = validator_set
+                .get_by_index(index)
+                .ok_or(CertificateError::UnknownSignerIndex(index))?;
+
+            public_keys.push(validator.public_key());
+            signed_voting_power += validator.voting_power();
+        }
+
+        let total_voting_power = validator_set.total_voting_power();
+
+        // Check if we have 2/3+ voting power before going through the more expensive signature check
+        if !thresholds
+            .quorum
+            .is_met(signed_voting_power, total_voting_power)
+        {
+            return Err(CertificateError::NotEnoughVotingPower {
+                signed: signed_voting_power,
+                total: total_voting_power,
+                expected: thresholds.quorum.min_expected(total_voting_power),
+            });
+        }
+
+        // Verify the aggregated signature
+        if self
+            .verify_aggregated_commit(
+                &certificate.height,
+                certificate.round,
+                &certificate.value_id,
+                &aggregated.signature,
+                &public_keys,
+            )
+            .await
+            .map_err(|e| CertificateError::VerificationError(e.into_source()))?
+            .is_invalid()
+        {
+            return Err(CertificateError::InvalidAggregatedSignature);
+        }
+
+        Ok(())
+    }
+
+    /// Aggregate the commit signatures of the given certificate into a single signature,
+    /// with the signers identified by their index in the given validator set.
+    ///
+    /// The certificate must have been verified against that same validator set beforehand,
+    /// as a single invalid commit signature makes the whole aggregated signature invalid.
+    /// Certificates which are already aggregated are returned as is.
+    async fn aggregate_commit_certificate(
+        &self,
+        certificate: &CommitCertificate<Ctx>,
+        validator_set: &Ctx::ValidatorSet,
+    ) -> Result<CommitCertificate<Ctx>, CertificateError<Ctx>> {
+        if certificate.is_aggregated() {
+            return Ok(certificate.clone());
+        }
+
+        let mut signers = SignerBitmap::new();
+        let mut signatures = Vec::with_capacity(certificate.commit_signatures.len());
+
+        for commit_sig in &certificate.commit_signatures {
+            let index = index_of::<Ctx>(validator_set, &commit_sig.address)
+                .ok_or_else(|| CertificateError::UnknownValidator(commit_sig.address.clone()))?;
+
+            if signers.contains(index) {
+                return Err(CertificateError::DuplicateVote(commit_sig.address.clone()));
+            }
+
+            signers.insert(index);
+            signatures.push(commit_sig.signature.clone());
+        }
+
+        let signature = self
+            .aggregate_signatures(&signatures)
+            .await
+            .map_err(|e| CertificateError::VerificationError(e.into_source()))?;
+
+        Ok(CommitCertificate::new_aggregated(
+            certificate.height,
+            certificate.round,
+            certificate.value_id.clone(),
+            AggregatedSignature::new(signers, signature),
+        ))
+    }
+
     /// Verify the polka certificate against the given validator set.
     ///
-    /// - For each signed prevote in the certificate:
-    ///   - Reconstruct the signed prevote and verify its signature
+    /// - For each signature in the certificate, reconstruct the signed prevote
+    /// - Verify the signatures of all the prevotes at once, with [`SigningProvider::verify_signed_votes`]
     /// - Check that we have 2/3+ of voting power has signed the certificate
     ///
     /// If any of those steps fail, return a [`CertificateError`].
@@ -286,7 +502,8 @@ where
         validator_set: &Ctx::ValidatorSet,
         thresholds: ThresholdParams,
     ) -> Result<(), CertificateError<Ctx>> {
-        let mut signed_voting_power = 0;
+        let mut votes = Vec::with_capacity(certificate.polka_signatures.len());
+        let mut voting_powers = Vec::with_capacity(certificate.polka_signatures.len());
         let mut seen_validators = Vec::new();
 
         for signature in &certificate.polka_signatures {
@@ -305,15 +522,21 @@ where
                 .get_by_address(validator_address)
                 .ok_or_else(|| CertificateError::UnknownValidator(validator_address.clone()))?;
 
-            // Check that the vote signature is valid. Do this last and lazily as it is expensive.
-            if let Ok(voting_power) = self
-                .verify_polka_signature(ctx, certificate, signature, validator)
-                .await
-            {
-                signed_voting_power += voting_power;
-            }
+            // Reconstruct the prevote that was signed
+            let vote = ctx.new_prevote(
+                certificate.height,
+                certificate.round,
+                NilOrVal::Val(certificate.value_id.clone()),
+                validator_address.clone(),
+            );
+
+            votes.push((vote, &signature.signature, validator.public_key()));
+            voting_powers.push(validator.voting_power());
         }
 
+        // Check that the vote signatures are valid, all at once. Do this last as it is expensive.
+        let signed_voting_power = signed_voting_power(self, &votes, &voting_powers).await;
+
         let total_voting_power = validator_set.total_voting_power();
 
         // Check if we have 2/3+ voting power
@@ -333,8 +556,8 @@ where
 
     /// Verify the round certificate against the given validator set.
     ///
-    /// - For each signature in the certificate:
-    ///   - Reconstruct the signed vote and verify its signature.
+    /// - For each signature in the certificate, reconstruct the signed vote.
+    /// - Verify the signatures of all the votes at once, with [`SigningProvider::verify_signed_votes`].
     /// - Check that the required voting power has signed the certificate:
     ///   - If `Precommit`, ensure that 2/3+ of the voting power is represented.
     ///   - If `Skip`, ensure that 1/3+ of the voting power is represented.
@@ -347,7 +570,8 @@ where
         validator_set: &Ctx::ValidatorSet,
         thresholds: ThresholdParams,
     ) -> Result<(), CertificateError<Ctx>> {
-        let mut signed_voting_power = 0;
+        let mut votes = Vec::with_capacity(certificate.round_signatures.len());
+        let mut voting_powers = Vec::with_capacity(certificate.round_signatures.len());
         let mut seen_validators = Vec::new();
 
         for signature in &certificate.round_signatures {
@@ -373,15 +597,29 @@ where
                 return Err(CertificateError::InvalidVoteType(validator_address.clone()));
             }
 
-            // Check that the vote signature is valid. Do this last and lazily as it is expensive.
-            if let Ok(voting_power) = self
-                .verify_round_signature(ctx, certificate, signature, validator)
-                .await
-            {
-                signed_voting_power += voting_power;
-            }
+            // Reconstruct the vote that was signed
+            let vote = match signature.vote_type {
+                VoteType::Prevote => ctx.new_prevote(
+                    certificate.height,
+                    certificate.round,
+                    signature.value_id.clone(),
+                    validator_address.clone(),
+                ),
+                VoteType::Precommit => ctx.new_precommit(
+                    certificate.height,
+                    certificate.round,
+                    signature.value_id.clone(),
+                    validator_address.clone(),
+                ),
+            };
+
+            votes.push((vote, &signature.signature, validator.public_key()));
+            voting_powers.push(validator.voting_power());
         }
 
+        // Check that the vote signatures are valid, all at once. Do this last as it is expensive.
+        let signed_voting_power = signed_voting_power(self, &votes, &voting_powers).await;
+
         let total_voting_power = validator_set.total_voting_power();
 
         let threshold = match certificate.cert_type {
@@ -399,4 +637,220 @@ where
             })
         }
     }
+
+    /// Verify evidence of a double vote against the given validator set.
+    ///
+    /// - Check that both votes were cast by the same validator, for the same height,
+    ///   round and vote type, but for different values
+    /// - Check that the validator is in the validator set
+    /// - Verify the signatures of both votes
+    ///
+    /// If any of those steps fail, return an [`EvidenceError`].
+    async fn verify_double_vote_evidence(
+        &self,
+        evidence: &DoubleVoteEvidence<Ctx>,
+        validator_set: &Ctx::ValidatorSet,
+    ) -> Result<(), EvidenceError<Ctx>> {
+        evidence.validate()?;
+
+        let address = evidence.validator_address();
+
+        let validator = validator_set
+            .get_by_address(address)
+            .ok_or_else(|| EvidenceError::UnknownValidator(address.clone()))?;
+
+        for vote in [&evidence.first, &evidence.second] {
+            verify_signed_vote(self, vote, validator).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Verify evidence of a double proposal against the given validator set.
+    ///
+    /// - Check that both proposals were signed by the same validator,
+    ///   for the same height and round, and that they differ
+    /// - Check that the validator is in the validator set
+    /// - Verify the signatures of both proposals
+    ///
+    /// If any of those steps fail, return an [`EvidenceError`].
+    async fn verify_double_proposal_evidence(
+        &self,
+        evidence: &DoubleProposalEvidence<Ctx>,
+        validator_set: &Ctx::ValidatorSet,
+    ) -> Result<(), EvidenceError<Ctx>> {
+        evidence.validate()?;
+
+        let address = evidence.validator_address();
+
+        let validator = validator_set
+            .get_by_address(address)
+            .ok_or_else(|| EvidenceError::UnknownValidator(address.clone()))?;
+
+        for proposal in [&evidence.first, &evidence.second] {
+            verify_signed_proposal(self, proposal, validator).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Verify evidence of a lock violation (amnesia attack) against the given validator set.
+    ///
+    /// - Check that both votes were cast by the same validator for the same height,
+    ///   that the first one is a precommit for a value and the second one a prevote
+    ///   for a different value in a later round
+    /// - Check that none of the given polka certificates justifies the prevote
+    /// - Check that the validator is in the validator set
+    /// - Verify the signatures of both votes
+    ///
+    /// If any of those steps fail, return an [`EvidenceError`].
+    async fn verify_amnesia_evidence(
+        &self,
+        evidence: &AmnesiaEvidence<Ctx>,
+        polka_certificates: &[PolkaCertificate<Ctx>],
+        validator_set: &Ctx::ValidatorSet,
+    ) -> Result<(), EvidenceError<Ctx>> {
+        evidence.validate_against(polka_certificates)?;
+
+        let address = evidence.validator_address();
+
+        let validator = validator_set
+            .get_by_address(address)
+            .ok_or_else(|| EvidenceError::UnknownValidator(address.clone()))?;
+
+        for vote in [&evidence.precommit, &evidence.prevote] {
+            verify_signed_vote(self, vote, validator).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Verify any kind of evidence of misbehavior against the given validator set,
+    /// by dispatching to the corresponding `verify_*_evidence` method.
+    ///
+    /// The polka certificates are only used to verify evidence of a lock violation.
+    async fn verify_evidence(
+        &self,
+        evidence: &Evidence<Ctx>,
+        polka_certificates: &[PolkaCertificate<Ctx>],
+        validator_set: &Ctx::ValidatorSet,
+    ) -> Result<(), EvidenceError<Ctx>> {
+        match evidence {
+            Evidence::DoubleVote(evidence) => {
+                self.verify_double_vote_evidence(evidence, validator_set)
+                    .await
+            }
+            Evidence::DoubleProposal(evidence) => {
+                self.verify_double_proposal_evidence(evidence, validator_set)
+                    .await
+            }
+            Evidence::Amnesia(evidence) => {
+                self.verify_amnesia_evidence(evidence, polka_certificates, validator_set)
+                    .await
+            }
+        }
+    }
+}
+
+/// Verify the signatures of the given votes all at once, and return the sum of the voting powers
+/// of the validators whose signature is valid, where each vote was cast by a validator
+/// with the voting power at the same index.
+///
+/// If the signatures cannot be verified all at once, they are verified one by one instead,
+/// and those which fail to be verified are counted as invalid.
+async fn signed_voting_power<Ctx, P>(
+    provider: &P,
+    votes: &[(Ctx::Vote, &Signature<Ctx>, &PublicKey<Ctx>)],
+    voting_powers: &[VotingPower],
+) -> VotingPower
+where
+    Ctx: Context,
+    P: SigningProvider<Ctx> + ?Sized,
+{
+    if votes.is_empty() {
+        return 0;
+    }
+
+    let results = match provider.verify_signed_votes(votes).await {
+        Ok(results) => results,
+        Err(_) => {
+            let mut results = Vec::with_capacity(votes.len());
+
+            for (vote, signature, public_key) in votes {
+                let result = provider
+                    .verify_signed_vote(vote, signature, public_key)
+                    .await
+                    .unwrap_or(VerificationResult::Invalid);
+
+                results.push(result);
+            }
+
+            results
+        }
+    };
+
+    results
+        .iter()
+        .zip(voting_powers)
+        .filter(|(result, _)| result.is_valid())
+        .map(|(_, voting_power)| voting_power)
+        .sum()
+}
+
+/// The index of the validator with the given address in the given validator set.
+fn index_of<Ctx: Context>(
+    validator_set: &Ctx::ValidatorSet,
+    address: &Ctx::Address,
+) -> Option<usize> {
+    (0..validator_set.count()).find(|&index| {
+        validator_set
+            .get_by_index(index)
+            .is_some_and(|validator| validator.address() == address)
+    })
+}
+
+async fn verify_signed_vote<Ctx, P>(
+    provider: &P,
+    vote: &SignedVote<Ctx>,
+    validator: &Ctx::Validator,
+) -> Result<(), EvidenceError<Ctx>>
+where
+    Ctx: Context,
+    P: SigningProvider<Ctx> + ?Sized,
+{
+    if provider
+        .verify_signed_vote(&vote.message, &vote.signature, validator.public_key())
+        .await
+        .map_err(|e| EvidenceError::VerificationError(e.into_source()))?
+        .is_invalid()
+    {
+        return Err(EvidenceError::InvalidSignature(validator.address().clone()));
+    }
+
+    Ok(())
+}
+
+async fn verify_signed_proposal<Ctx, P>(
+    provider: &P,
+    proposal: &SignedProposal<Ctx>,
+    validator: &Ctx::Validator,
+) -> Result<(), EvidenceError<Ctx>>
+where
+    Ctx: Context,
+    P: SigningProvider<Ctx> + ?Sized,
+{
+    if provider
+        .verify_signed_proposal(
+            &proposal.message,
+            &proposal.signature,
+            validator.public_key(),
+        )
+        .await
+        .map_err(|e| EvidenceError::VerificationError(e.into_source()))?
+        .is_invalid()
+    {
+        return Err(EvidenceError::InvalidSignature(validator.address().clone()));
+    }
+
+    Ok(())
 }
@@ -4,9 +4,10 @@
 extern crate alloc;
 
 use alloc::boxed::Box;
+use alloc::vec::Vec;
 
 use async_trait::async_trait;
-use malachitebft_core_types::{Context, PublicKey, Signature, SignedMessage};
+use malachitebft_core_types::{Context, PublicKey, Round, Signature, SignedMessage, ValueId};
 
 mod error;
 pub use error::Error;
@@ -14,6 +15,12 @@ pub use error::Error;
 mod ext;
 pub use ext::SigningProviderExt;
 
+mod sign_bytes;
+pub use sign_bytes::{SignBytes, COMMIT_DOMAIN};
+
+mod peer_identity;
+pub use peer_identity::{peer_identity_sign_bytes, PEER_IDENTITY_DOMAIN};
+
 /// The result of a signature verification operation.
 pub enum VerificationResult {
     /// The signature is valid.
@@ -111,6 +118,89 @@ where
         signature: &Signature<Ctx>,
         public_key: &PublicKey<Ctx>,
     ) -> Result<VerificationResult, Error>;
+
+    /// Verify the signatures of the given votes, where each vote comes with its signature
+    /// and the public key of the validator which signed it.
+    ///
+    /// Return the result of the verification of each signature, in the same order as the votes.
+    ///
+    /// Signing schemes which support batch verification, eg. Ed25519, can override this method
+    /// to verify all the signatures at once, and only fall back to verifying them one by one
+    /// when the batch is invalid, to find out which of them are.
+    /// The default implementation verifies each signature with [`SigningProvider::verify_signed_vote`].
+    ///
+    /// If this fails, [`SigningProviderExt`] verifies the signatures one by one instead.
+    async fn verify_signed_votes(
+        &self,
+        votes: &[(Ctx::Vote, &Signature<Ctx>, &PublicKey<Ctx>)],
+    ) -> Result<Vec<VerificationResult>, Error> {
+        let mut results = Vec::with_capacity(votes.len());
+
+        for (vote, signature, public_key) in votes {
+            results.push(self.verify_signed_vote(vote, signature, public_key).await?);
+        }
+
+        Ok(results)
+    }
+
+    /// Aggregate the given signatures into a single signature.
+    ///
+    /// Only signing schemes which support signature aggregation, eg. BLS, can implement this method.
+    /// The default implementation returns an error.
+    async fn aggregate_signatures(
+        &self,
+        _signatures: &[Signature<Ctx>],
+    ) -> Result<Signature<Ctx>, Error> {
+        Err(aggregation_not_supported())
+    }
+
+    /// Verify the given aggregate signature over the commit message for the value with the given ID
+    /// at the given height and round, signed by the validators with the given public keys.
+    ///
+    /// As all the signers signed the same message, see [`SignBytes::commit`], the aggregate signature
+    /// is verified against the aggregate of their public keys with a single pairing check.
+    ///
+    /// Only signing schemes which support signature aggregation, eg. BLS, can implement this method.
+    /// The default implementation returns an error.
+    async fn verify_aggregated_commit(
+        &self,
+        _height: &Ctx::Height,
+        _round: Round,
+        _value_id: &ValueId<Ctx>,
+        _signature: &Signature<Ctx>,
+        _public_keys: &[&PublicKey<Ctx>],
+    ) -> Result<VerificationResult, Error> {
+        Err(aggregation_not_supported())
+    }
+
+    /// Sign the ID of our network peer, given as bytes, with our private key, to prove to
+    /// other validators that the peer is operated by this validator.
+    ///
+    /// Implementations must sign the bytes returned by [`peer_identity_sign_bytes`].
+    /// The default implementation returns an error.
+    async fn sign_peer_identity(&self, _peer_id: &[u8]) -> Result<Signature<Ctx>, Error> {
+        Err(peer_identity_not_supported())
+    }
+
+    /// Verify the given signature over the ID of a network peer using the given public key.
+    ///
+    /// The default implementation returns an error.
+    async fn verify_peer_identity(
+        &self,
+        _peer_id: &[u8],
+        _signature: &Signature<Ctx>,
+        _public_key: &PublicKey<Ctx>,
+    ) -> Result<VerificationResult, Error> {
+        Err(peer_identity_not_supported())
+    }
+}
+
+fn aggregation_not_supported() -> Error {
+    Error::from_source("signature aggregation is not supported by this signing provider")
+}
+
+fn peer_identity_not_supported() -> Error {
+    Error::from_source("signing peer identities is not supported by this signing provider")
 }
 
 #[async_trait]
@@ -186,4 +276,46 @@ where
             .verify_signed_vote_extension(extension, signature, public_key)
             .await
     }
+
+    async fn verify_signed_votes(
+        &self,
+        votes: &[(Ctx::Vote, &Signature<Ctx>, &PublicKey<Ctx>)],
+    ) -> Result<Vec<VerificationResult>, Error> {
+        self.as_ref().verify_signed_votes(votes).await
+    }
+
+    async fn aggregate_signatures(
+        &self,
+        signatures: &[Signature<Ctx>],
+    ) -> Result<Signature<Ctx>, Error> {
+        self.as_ref().aggregate_signatures(signatures).await
+    }
+
+    async fn verify_aggregated_commit(
+        &self,
+        height: &Ctx::Height,
+        round: Round,
+        value_id: &ValueId<Ctx>,
+        signature: &Signature<Ctx>,
+        public_keys: &[&PublicKey<Ctx>],
+    ) -> Result<VerificationResult, Error> {
+        self.as_ref()
+            .verify_aggregated_commit(height, round, value_id, signature, public_keys)
+            .await
+    }
+
+    async fn sign_peer_identity(&self, peer_id: &[u8]) -> Result<Signature<Ctx>, Error> {
+        self.as_ref().sign_peer_identity(peer_id).await
+    }
+
+    async fn verify_peer_identity(
+        &self,
+        peer_id: &[u8],
+        signature: &Signature<Ctx>,
+        public_key: &PublicKey<Ctx>,
+    ) -> Result<VerificationResult, Error> {
+        self.as_ref()
+            .verify_peer_identity(peer_id, signature, public_key)
+            .await
+    }
 }
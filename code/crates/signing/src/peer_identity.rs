@@ -0,0 +1,13 @@
+use alloc::vec::Vec;
+
+/// Domain separation tag prepended to the peer identities signed by validators,
+/// so that these signatures can never be mistaken for signatures over consensus messages.
+pub const PEER_IDENTITY_DOMAIN: &[u8] = b"malachitebft/peer-identity/v1:";
+
+/// The bytes to sign to prove that a validator controls the network peer with the given ID.
+pub fn peer_identity_sign_bytes(peer_id: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(PEER_IDENTITY_DOMAIN.len() + peer_id.len());
+    bytes.extend_from_slice(PEER_IDENTITY_DOMAIN);
+    bytes.extend_from_slice(peer_id);
+    bytes
+}
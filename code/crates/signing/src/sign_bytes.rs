@@ -0,0 +1,33 @@
+use bytes::Bytes;
+use malachitebft_core_types::{Context, Round, ValueId};
+
+/// Domain separation tag which should be prepended to the commit messages returned by
+/// [`SignBytes::commit`], so that these can never be mistaken for the sign bytes of a vote.
+pub const COMMIT_DOMAIN: &[u8] = b"malachitebft/commit/v1:";
+
+/// The bytes to sign for each kind of message of a context.
+///
+/// Signing providers which do not have access to the private key, such as threshold signers
+/// or hardware security modules, use this to compute the bytes to sign on their behalf.
+/// These must be the same bytes that the signing provider of the context verifies signatures
+/// against, eg. the sign bytes of a vote excluding its extension.
+pub trait SignBytes<Ctx: Context>: Send + Sync + 'static {
+    /// The bytes to sign for the given vote.
+    fn vote(&self, vote: &Ctx::Vote) -> Bytes;
+
+    /// The bytes to sign for the given proposal.
+    fn proposal(&self, proposal: &Ctx::Proposal) -> Bytes;
+
+    /// The bytes to sign for the given proposal part.
+    fn proposal_part(&self, proposal_part: &Ctx::ProposalPart) -> Bytes;
+
+    /// The bytes to sign for the given vote extension.
+    fn extension(&self, extension: &Ctx::Extension) -> Bytes;
+
+    /// The commit message for the value with the given ID at the given height and round.
+    ///
+    /// Signing providers with aggregatable signatures sign this instead of the sign bytes
+    /// of a precommit for that value. It must not depend on the validator, so that all the
+    /// commit signatures of a certificate are over the same message.
+    fn commit(&self, height: &Ctx::Height, round: Round, value_id: &ValueId<Ctx>) -> Bytes;
+}
@@ -87,6 +87,15 @@ pub fn main() -> color_eyre::Result<()> {
             cmd.run(ProtobufCodec)
                 .wrap_err("Failed to run `dump-wal` command")
         }
+
+        Commands::Key(cmd) => {
+            let _guard = logging::init(LogLevel::Info, LogFormat::Plaintext);
+
+            let node = &StarknetNode::new(home_dir, ConfigSource::Default, None);
+
+            cmd.run(node, &args.get_priv_validator_key_file_path()?)
+                .wrap_err("Failed to run `key` command")
+        }
     }
 }
 
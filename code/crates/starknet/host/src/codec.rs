@@ -4,8 +4,8 @@ use prost::Message;
 use malachitebft_codec::{Codec, HasEncodedLen};
 use malachitebft_core_consensus::{LivenessMsg, PeerId, ProposedValue, SignedConsensusMsg};
 use malachitebft_core_types::{
-    CommitCertificate, CommitSignature, NilOrVal, PolkaCertificate, PolkaSignature, Round,
-    RoundCertificate, RoundCertificateType, RoundSignature, SignedVote, Validity, VoteType,
+    CommitCertificate, CommitSignature, Evidence, NilOrVal, PolkaCertificate, PolkaSignature,
+    Round, RoundCertificate, RoundCertificateType, RoundSignature, SignedVote, Validity, VoteType,
 };
 use malachitebft_engine::util::streaming::{StreamContent, StreamId, StreamMessage};
 use malachitebft_starknet_p2p_types::{Felt, FeltExt, Signature};
@@ -325,6 +325,42 @@ impl Codec<SignedConsensusMsg<MockContext>> for ProtobufCodec {
     }
 }
 
+// NOTE: The Starknet p2p specs do not define any message for evidence of misbehavior,
+//       so evidence can neither be gossiped nor persisted by the Starknet test application.
+impl Codec<Evidence<MockContext>> for ProtobufCodec {
+    type Error = ProtoError;
+
+    fn decode(&self, _bytes: Bytes) -> Result<Evidence<MockContext>, Self::Error> {
+        Err(ProtoError::Other(
+            "evidence not supported by starknet test application".to_string(),
+        ))
+    }
+
+    fn encode(&self, _evidence: &Evidence<MockContext>) -> Result<Bytes, Self::Error> {
+        Err(ProtoError::Other(
+            "evidence not supported by starknet test application".to_string(),
+        ))
+    }
+}
+
+// NOTE: The Starknet p2p specs do not define any message for validator sets, so the Starknet
+//       test application does not support validator set updates, which are persisted in the WAL.
+impl Codec<p2p::ValidatorSet> for ProtobufCodec {
+    type Error = ProtoError;
+
+    fn decode(&self, _bytes: Bytes) -> Result<p2p::ValidatorSet, Self::Error> {
+        Err(ProtoError::Other(
+            "validator sets not supported by starknet test application".to_string(),
+        ))
+    }
+
+    fn encode(&self, _validator_set: &p2p::ValidatorSet) -> Result<Bytes, Self::Error> {
+        Err(ProtoError::Other(
+            "validator sets not supported by starknet test application".to_string(),
+        ))
+    }
+}
+
 pub(crate) fn encode_round_certificate(
     certificate: &RoundCertificate<MockContext>,
 ) -> Result<proto::RoundCertificate, ProtoError> {
@@ -534,6 +570,7 @@ pub fn decode_commit_certificate(
         round: Round::new(certificate.round),
         value_id,
         commit_signatures,
+        aggregated_signature: None,
     };
 
     Ok(certificate)
@@ -542,6 +579,12 @@ pub fn decode_commit_certificate(
 pub fn encode_commit_certificate(
     certificate: &CommitCertificate<MockContext>,
 ) -> Result<proto::sync::CommitCertificate, ProtoError> {
+    if certificate.is_aggregated() {
+        return Err(ProtoError::Other(
+            "Aggregated commit certificates are not supported".to_string(),
+        ));
+    }
+
     Ok(proto::sync::CommitCertificate {
         fork_id: certificate.height.fork_id,
         block_number: certificate.height.block_number,
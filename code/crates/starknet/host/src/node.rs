@@ -1,5 +1,6 @@
 #![allow(clippy::too_many_arguments)]
 
+use std::num::NonZeroU64;
 use std::path::PathBuf;
 
 use ractor::async_trait;
@@ -8,6 +9,7 @@ use serde::{Deserialize, Serialize};
 use tokio::task::JoinHandle;
 
 use malachitebft_app::events::{RxEvent, TxEvent};
+use malachitebft_app::keystore;
 use malachitebft_app::node::{
     CanGeneratePrivateKey, CanMakeConfig, CanMakeDistributedConfig, CanMakeGenesis,
     CanMakePrivateKeyFile, MakeConfigSettings, Node, NodeHandle,
@@ -136,12 +138,12 @@ impl Node for StarknetNode {
     }
 
     fn load_private_key_file(&self) -> eyre::Result<Self::PrivateKeyFile> {
-        let private_key = std::fs::read_to_string(self.private_key_file())?;
-        serde_json::from_str(&private_key).map_err(|e| e.into())
+        keystore::load_key_file(self.private_key_file()).map_err(|e| e.into())
     }
 
-    fn get_signing_provider(&self, private_key: PrivateKey) -> Self::SigningProvider {
-        Self::SigningProvider::new(private_key)
+    fn get_signing_provider(&self) -> eyre::Result<Self::SigningProvider> {
+        let private_key = self.load_private_key(self.load_private_key_file()?);
+        Ok(Self::SigningProvider::new(private_key))
     }
 
     fn load_genesis(&self) -> eyre::Result<Self::Genesis> {
@@ -251,6 +253,11 @@ fn make_config(index: usize, total: usize, settings: MakeConfigSettings) -> Conf
         moniker: format!("starknet-{index}"),
         consensus: ConsensusConfig {
             enabled: true,
+            accountability: false,
+            max_evidence_age: 100,
+            validator_set_update_delay: NonZeroU64::MIN,
+            aggregate_commit_certificates: false,
+            threshold_params: Default::default(),
             value_payload: ValuePayload::PartsOnly,
             queue_capacity: 100, // Deprecated, derived from `sync.parallel_requests`
             timeouts: TimeoutConfig::default(),
@@ -349,6 +356,11 @@ fn make_distributed_config(
         moniker: format!("starknet-{index}"),
         consensus: ConsensusConfig {
             enabled: true,
+            accountability: false,
+            max_evidence_age: 100,
+            validator_set_update_delay: NonZeroU64::MIN,
+            aggregate_commit_certificates: false,
+            threshold_params: Default::default(),
             queue_capacity: 100, // Deprecated, derived from `sync.parallel_requests`
             value_payload: ValuePayload::PartsOnly,
             timeouts: TimeoutConfig::default(),
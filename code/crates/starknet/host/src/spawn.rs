@@ -7,6 +7,7 @@ use tracing::warn;
 use malachitebft_config::{self as config, MempoolConfig, MempoolLoadConfig, ValueSyncConfig};
 use malachitebft_core_types::ValuePayload;
 use malachitebft_engine::consensus::{Consensus, ConsensusParams, ConsensusRef};
+use malachitebft_engine::evidence::{EvidencePool, EvidencePoolRef};
 use malachitebft_engine::host::HostRef;
 use malachitebft_engine::network::{Network, NetworkRef};
 use malachitebft_engine::node::{Node, NodeRef};
@@ -57,7 +58,7 @@ pub async fn spawn_node_actor(
     let mempool_load = spawn_mempool_load_actor(&cfg.mempool.load, mempool.clone(), &span).await;
 
     // Spawn consensus gossip
-    let network = spawn_network_actor(&cfg, &private_key, &registry, &span).await;
+    let network = spawn_network_actor(&cfg, &private_key, &home_dir, &registry, &span).await;
 
     // Spawn the host actor
     let host = spawn_host_actor(
@@ -86,6 +87,8 @@ pub async fn spawn_node_actor(
 
     let wal = spawn_wal_actor(&ctx, ProtobufCodec, &home_dir, &registry, &span).await;
 
+    let evidence_pool = spawn_evidence_pool_actor(ProtobufCodec, &home_dir, &span).await;
+
     // Spawn consensus
     let consensus = spawn_consensus_actor(
         start_height,
@@ -97,6 +100,7 @@ pub async fn spawn_node_actor(
         network.clone(),
         host.clone(),
         wal.clone(),
+        evidence_pool.clone(),
         sync.clone(),
         consensus_metrics,
         tx_event,
@@ -105,7 +109,16 @@ pub async fn spawn_node_actor(
     .await;
 
     // Spawn the node actor
-    let node = Node::new(ctx, network, consensus, wal, sync, host, span);
+    let node = Node::new(
+        ctx,
+        network,
+        consensus,
+        wal,
+        evidence_pool,
+        sync,
+        host,
+        span,
+    );
 
     let (actor_ref, handle) = node.spawn().await.unwrap();
 
@@ -128,6 +141,20 @@ async fn spawn_wal_actor(
         .unwrap()
 }
 
+async fn spawn_evidence_pool_actor(
+    codec: ProtobufCodec,
+    home_dir: &Path,
+    span: &tracing::Span,
+) -> EvidencePoolRef<MockContext> {
+    let evidence_dir = home_dir.join("evidence");
+    std::fs::create_dir_all(&evidence_dir).unwrap();
+    let evidence_file = evidence_dir.join("evidence.log");
+
+    EvidencePool::spawn(codec, evidence_file, span.clone())
+        .await
+        .unwrap()
+}
+
 async fn spawn_sync_actor(
     ctx: MockContext,
     network: NetworkRef<MockContext>,
@@ -187,18 +214,25 @@ async fn spawn_consensus_actor(
     network: NetworkRef<MockContext>,
     host: HostRef<MockContext>,
     wal: WalRef<MockContext>,
+    evidence_pool: EvidencePoolRef<MockContext>,
     sync: Option<SyncRef<MockContext>>,
     consensus_metrics: ConsensusMetrics,
     tx_event: TxEvent<MockContext>,
     span: &tracing::Span,
 ) -> ConsensusRef<MockContext> {
+    cfg.consensus
+        .threshold_params
+        .validate()
+        .expect("Invalid consensus threshold parameters");
+
     let consensus_params = ConsensusParams {
         initial_height,
         initial_validator_set,
         address,
-        threshold_params: Default::default(),
+        threshold_params: cfg.consensus.threshold_params,
         value_payload: ValuePayload::PartsOnly,
         enabled: cfg.consensus.enabled,
+        accountability: cfg.consensus.accountability,
     };
 
     // Derive the consensus queue capacity from `sync.parallel_requests` and `sync.batch_size`
@@ -212,6 +246,7 @@ async fn spawn_consensus_actor(
         network,
         host,
         wal,
+        evidence_pool,
         sync,
         consensus_metrics,
         tx_event,
@@ -224,6 +259,7 @@ async fn spawn_consensus_actor(
 async fn spawn_network_actor(
     cfg: &Config,
     private_key: &PrivateKey,
+    home_dir: &Path,
     registry: &SharedRegistry,
     span: &tracing::Span,
 ) -> NetworkRef<MockContext> {
@@ -232,16 +268,19 @@ async fn spawn_network_actor(
     let bootstrap_protocol = match cfg.consensus.p2p.discovery.bootstrap_protocol {
         config::BootstrapProtocol::Kademlia => gossip::BootstrapProtocol::Kademlia,
         config::BootstrapProtocol::Full => gossip::BootstrapProtocol::Full,
+        config::BootstrapProtocol::Pex => gossip::BootstrapProtocol::Pex,
     };
 
     let selector = match cfg.consensus.p2p.discovery.selector {
         config::Selector::Kademlia => gossip::Selector::Kademlia,
         config::Selector::Random => gossip::Selector::Random,
+        config::Selector::Latency => gossip::Selector::Latency,
     };
 
     let config_gossip = gossip::Config {
         listen_addr: cfg.consensus.p2p.listen_addr.clone(),
         persistent_peers: cfg.consensus.p2p.persistent_peers.clone(),
+        private_peer_ids: cfg.consensus.p2p.private_peer_ids.clone(),
         discovery: gossip::DiscoveryConfig {
             enabled: cfg.consensus.p2p.discovery.enabled,
             bootstrap_protocol,
@@ -249,9 +288,27 @@ async fn spawn_network_actor(
             num_outbound_peers: cfg.consensus.p2p.discovery.num_outbound_peers,
             num_inbound_peers: cfg.consensus.p2p.discovery.num_inbound_peers,
             max_connections_per_peer: cfg.consensus.p2p.discovery.max_connections_per_peer,
+            max_outbound_peers_per_subnet: cfg
+                .consensus
+                .p2p
+                .discovery
+                .max_outbound_peers_per_subnet,
             ephemeral_connection_timeout: cfg.consensus.p2p.discovery.ephemeral_connection_timeout,
+            pex_interval: cfg.consensus.p2p.discovery.pex_interval,
+            require_signed_peer_records: cfg.consensus.p2p.discovery.require_signed_peer_records,
             ..Default::default()
         },
+        address_book: gossip::AddressBookConfig {
+            file: cfg
+                .consensus
+                .p2p
+                .address_book
+                .enabled
+                .then(|| home_dir.join("network").join("address_book.json")),
+            max_age: cfg.consensus.p2p.address_book.max_age,
+            max_failures: cfg.consensus.p2p.address_book.max_failures,
+            max_entries: cfg.consensus.p2p.address_book.max_entries,
+        },
         idle_connection_timeout: Duration::from_secs(15 * 60),
         transport: gossip::TransportProtocol::from_multiaddr(&cfg.consensus.p2p.listen_addr)
             .unwrap_or_else(|| {
@@ -284,6 +341,17 @@ async fn spawn_network_actor(
             discovery_regres: cfg.consensus.p2p.protocol_names.discovery_regres.clone(),
             sync: cfg.consensus.p2p.protocol_names.sync.clone(),
         },
+        reputation: gossip::ReputationConfig {
+            enabled: cfg.consensus.p2p.reputation.enabled,
+            ban_threshold: cfg.consensus.p2p.reputation.ban_threshold,
+            ban_duration: cfg.consensus.p2p.reputation.ban_duration,
+            score_recovery_per_minute: cfg.consensus.p2p.reputation.score_recovery_per_minute,
+            ban_file: Some(home_dir.join("network").join("peer_bans.json")),
+        },
+        rate_limits: make_rate_limits(&cfg.consensus.p2p.rate_limits),
+        connection_gater: make_connection_gater(&cfg.consensus.p2p.connection_gater),
+        validator_mesh: cfg.consensus.p2p.validator_mesh.enabled,
+        sentry_peer_ids: cfg.consensus.p2p.validator_mesh.sentry_peer_ids.clone(),
     };
 
     let keypair = make_keypair(private_key);
@@ -300,6 +368,34 @@ async fn spawn_network_actor(
     .unwrap()
 }
 
+fn make_connection_gater(
+    cfg: &config::ConnectionGaterConfig,
+) -> malachitebft_network::ConnectionGaterConfig {
+    malachitebft_network::ConnectionGaterConfig {
+        allowed_peers: cfg.allowed_peers.iter().copied().collect(),
+        denied_peers: cfg.denied_peers.iter().copied().collect(),
+        allowed_ips: cfg.allowed_ips.clone(),
+        denied_ips: cfg.denied_ips.clone(),
+    }
+}
+
+fn make_rate_limits(cfg: &config::RateLimitConfig) -> malachitebft_network::RateLimitConfig {
+    use malachitebft_network::rate_limit::RateLimit;
+
+    let limit = |limit: config::RateLimit| {
+        RateLimit::new(limit.messages_per_sec, limit.bytes_per_sec.as_u64())
+    };
+
+    malachitebft_network::RateLimitConfig {
+        enabled: cfg.enabled,
+        consensus: limit(cfg.consensus),
+        proposal_parts: limit(cfg.proposal_parts),
+        sync: limit(cfg.sync),
+        liveness: limit(cfg.liveness),
+        evidence: limit(cfg.evidence),
+    }
+}
+
 fn make_keypair(pk: &PrivateKey) -> Keypair {
     Keypair::ed25519_from_bytes(pk.inner().to_bytes()).unwrap()
 }
@@ -97,6 +97,10 @@ impl common::ValidatorSet<MockContext> for ValidatorSet {
     fn get_by_index(&self, index: usize) -> Option<&Validator> {
         self.validators.get(index)
     }
+
+    fn from_validators(validators: Vec<Validator>) -> Self {
+        Self::new(validators)
+    }
 }
 
 impl common::Validator<MockContext> for Validator {
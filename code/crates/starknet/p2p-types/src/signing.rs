@@ -2,7 +2,7 @@ use async_trait::async_trait;
 use bytes::Bytes;
 use malachitebft_core_types::{SignedExtension, SignedProposal, SignedProposalPart, SignedVote};
 
-use malachitebft_signing::{Error, SigningProvider, VerificationResult};
+use malachitebft_signing::{peer_identity_sign_bytes, Error, SigningProvider, VerificationResult};
 pub use malachitebft_signing_ed25519::{Ed25519, PrivateKey, PublicKey, Signature};
 
 use crate::{MockContext, Proposal, ProposalPart, Vote};
@@ -47,6 +47,14 @@ impl SigningProvider<MockContext> for Ed25519Provider {
         Ok(VerificationResult::Valid)
     }
 
+    async fn verify_signed_votes(
+        &self,
+        votes: &[(Vote, &Signature, &PublicKey)],
+    ) -> Result<Vec<VerificationResult>, Error> {
+        // Votes are not signed for now
+        Ok(votes.iter().map(|_| VerificationResult::Valid).collect())
+    }
+
     async fn sign_proposal(
         &self,
         proposal: Proposal,
@@ -100,4 +108,21 @@ impl SigningProvider<MockContext> for Ed25519Provider {
         // Vote extensions are not enabled
         Ok(VerificationResult::Valid)
     }
+
+    async fn sign_peer_identity(&self, peer_id: &[u8]) -> Result<Signature, Error> {
+        Ok(self.sign(&peer_identity_sign_bytes(peer_id)))
+    }
+
+    async fn verify_peer_identity(
+        &self,
+        peer_id: &[u8],
+        signature: &Signature,
+        public_key: &PublicKey,
+    ) -> Result<VerificationResult, Error> {
+        Ok(VerificationResult::from_bool(self.verify(
+            &peer_identity_sign_bytes(peer_id),
+            signature,
+            public_key,
+        )))
+    }
 }
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::fs;
+use std::num::NonZeroU64;
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::time::Duration;
@@ -146,6 +147,11 @@ impl TestRunner {
             logging: LoggingConfig::default(),
             consensus: ConsensusConfig {
                 enabled: true,
+                accountability: false,
+                max_evidence_age: 100,
+                validator_set_update_delay: NonZeroU64::MIN,
+                aggregate_commit_certificates: false,
+                threshold_params: Default::default(),
                 value_payload: ValuePayload::PartsOnly,
                 queue_capacity: 100, // Deprecated, derived from `sync.parallel_requests`
                 timeouts: TimeoutConfig::default(),
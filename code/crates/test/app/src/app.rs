@@ -2,8 +2,9 @@ use std::time::Duration;
 
 use eyre::eyre;
 use malachitebft_app_channel::app::engine::host::Next;
+use tokio::sync::oneshot;
 use tokio::time::sleep;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 // use malachitebft_app_channel::app::config::ValuePayload;
 use malachitebft_app_channel::app::streaming::StreamContent;
@@ -74,7 +75,7 @@ pub async fn run(state: &mut State, channels: &mut Channels<TestContext>) -> eyr
                         .remove_pending_proposal_parts(parts.clone())
                         .await?;
 
-                    match state.validate_proposal_parts(parts) {
+                    match state.validate_proposal_parts(parts).await {
                         Ok(()) => {
                             // Validation passed - convert to ProposedValue and move to undecided
                             let value = State::assemble_value_from_parts(parts.clone())?;
@@ -160,7 +161,7 @@ pub async fn run(state: &mut State, channels: &mut Channels<TestContext>) -> eyr
 
                 // Now what's left to do is to break down the value to propose into parts,
                 // and send those parts over the network to our peers, for them to re-assemble the full value.
-                for stream_message in state.stream_proposal(proposal, pol_round) {
+                for stream_message in state.stream_proposal(proposal, pol_round).await? {
                     debug!(%height, %round, "Streaming proposal part: {stream_message:?}");
 
                     channels
@@ -198,6 +199,7 @@ pub async fn run(state: &mut State, channels: &mut Channels<TestContext>) -> eyr
             AppMsg::Decided {
                 certificate,
                 extensions: _,
+                evidence,
                 reply,
             } => {
                 info!(
@@ -206,21 +208,61 @@ pub async fn run(state: &mut State, channels: &mut Channels<TestContext>) -> eyr
                     value = %certificate.value_id,
                     "Consensus has decided on value, committing..."
                 );
-                assert!(!certificate.commit_signatures.is_empty());
+
+                // Consensus also hands us any evidence of misbehavior (ie. equivocating
+                // proposals or votes) it observed during this height, which the application
+                // may use to penalize the offending validators.
+                if !evidence.is_empty() {
+                    warn!(
+                        height = %certificate.height,
+                        ?evidence,
+                        "Received evidence of misbehavior"
+                    );
+                }
+                assert!(certificate.signature_count() > 0);
+
+                let decided_height = certificate.height;
 
                 // When that happens, we store the decided value in our store
                 match state.commit(certificate).await {
                     Ok(_) => {
-                        // And then we instruct consensus to start the next height
-                        let validator_set = state
-                            .get_validator_set(state.current_height)
-                            .expect("Validator set should be available");
-
-                        if reply
-                            .send(Next::Start(state.current_height, validator_set))
-                            .is_err()
-                        {
-                            error!("Failed to send StartHeight reply");
+                        // If the middleware updates the validator set, we instruct consensus
+                        // to schedule the update and start the next height
+                        if let Some(update) = state.ctx.middleware().get_validator_set_update(
+                            &state.ctx,
+                            decided_height,
+                            &state.genesis,
+                        ) {
+                            let (tx, rx) = oneshot::channel();
+
+                            if reply
+                                .send(Next::StartWithUpdate(
+                                    state.current_height,
+                                    update,
+                                    tx.into(),
+                                ))
+                                .is_err()
+                            {
+                                error!("Failed to send StartHeight reply");
+                            }
+
+                            match rx.await {
+                                Ok(Ok(())) => {}
+                                Ok(Err(e)) => error!("Validator set update was rejected: {e}"),
+                                Err(_) => error!("Failed to receive validator set update result"),
+                            }
+                        } else {
+                            // Otherwise, we instruct consensus to start the next height
+                            let validator_set = state
+                                .get_validator_set(state.current_height)
+                                .expect("Validator set should be available");
+
+                            if reply
+                                .send(Next::Start(state.current_height, validator_set))
+                                .is_err()
+                            {
+                                error!("Failed to send StartHeight reply");
+                            }
                         }
                     }
                     Err(e) => {
@@ -343,7 +385,9 @@ pub async fn run(state: &mut State, channels: &mut Channels<TestContext>) -> eyr
                         value: proposal.value,
                     };
 
-                    for stream_message in state.stream_proposal(locally_proposed_value, valid_round)
+                    for stream_message in state
+                        .stream_proposal(locally_proposed_value, valid_round)
+                        .await?
                     {
                         debug!(%height, %valid_round, "Publishing proposal part: {stream_message:?}");
 
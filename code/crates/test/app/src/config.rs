@@ -5,8 +5,8 @@ use serde::{Deserialize, Serialize};
 use malachitebft_app_channel::app::node::NodeConfig;
 
 pub use malachitebft_app_channel::app::config::{
-    ConsensusConfig, LogFormat, LogLevel, LoggingConfig, MetricsConfig, RuntimeConfig, TestConfig,
-    TimeoutConfig, ValueSyncConfig,
+    ConsensusConfig, LogFormat, LogLevel, LoggingConfig, MetricsConfig, RuntimeConfig,
+    SignerConfig, TestConfig, TimeoutConfig, ValueSyncConfig,
 };
 
 /// Malachite configuration options
@@ -30,6 +30,10 @@ pub struct Config {
     /// Runtime configuration options
     pub runtime: RuntimeConfig,
 
+    /// Signer configuration options
+    #[serde(default)]
+    pub signer: SignerConfig,
+
     /// Test configuration
     pub test: TestConfig,
 }
@@ -1,5 +1,6 @@
 #![allow(clippy::too_many_arguments)]
 
+use std::num::NonZeroU64;
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -17,15 +18,17 @@ use malachitebft_app_channel::app::node::{
     MakeConfigSettings, Node, NodeHandle,
 };
 use malachitebft_app_channel::app::types::core::VotingPower;
+use malachitebft_app_channel::app::types::signing::SigningProvider;
 use malachitebft_app_channel::app::types::Keypair;
 
 use malachitebft_test::middleware::{DefaultMiddleware, Middleware};
+use malachitebft_test::proposer_selector;
 
 // Use the same types used for integration tests.
 // A real application would use its own types and context instead.
 use malachitebft_test::{
-    Address, Ed25519Provider, Genesis, Height, PrivateKey, PublicKey, TestContext, Validator,
-    ValidatorSet,
+    load_network_keypair, load_public_key, make_signing_provider, Address, Genesis, Height,
+    PrivateKey, PublicKey, TestContext, Validator, ValidatorSet,
 };
 
 use crate::config::Config;
@@ -69,7 +72,7 @@ impl Node for App {
     type Config = Config;
     type Genesis = Genesis;
     type PrivateKeyFile = PrivateKey;
-    type SigningProvider = Ed25519Provider;
+    type SigningProvider = Box<dyn SigningProvider<TestContext>>;
     type NodeHandle = Handle;
 
     fn get_home_dir(&self) -> PathBuf {
@@ -80,8 +83,16 @@ impl Node for App {
         Ok(self.config.clone())
     }
 
-    fn get_signing_provider(&self, private_key: PrivateKey) -> Self::SigningProvider {
-        Ed25519Provider::new(private_key)
+    fn load_public_key(&self) -> eyre::Result<PublicKey> {
+        load_public_key(&self.config.signer, || self.load_private_key_file())
+    }
+
+    fn load_keypair(&self) -> eyre::Result<Keypair> {
+        load_network_keypair(&self.config.signer, || self.load_private_key_file())
+    }
+
+    fn get_signing_provider(&self) -> eyre::Result<Self::SigningProvider> {
+        make_signing_provider(&self.config.signer, || self.load_private_key_file())
     }
 
     fn get_address(&self, pk: &PublicKey) -> Address {
@@ -126,11 +137,15 @@ impl Node for App {
             .clone()
             .unwrap_or_else(|| Arc::new(DefaultMiddleware));
 
-        let ctx = TestContext::with_middleware(middleware);
+        let ctx = TestContext::with_middleware(middleware).with_proposer_selector(
+            proposer_selector::from_config(config.test.proposer_selection),
+        );
 
-        let public_key = self.get_public_key(&self.private_key);
+        let public_key = self.load_public_key()?;
         let address = self.get_address(&public_key);
-        let signing_provider = self.get_signing_provider(self.private_key.clone());
+        // The application signs the proposal parts it streams with the signing provider of the node,
+        // which may ask a remote signer to sign them
+        let signing_provider = self.get_signing_provider()?;
         let genesis = self.load_genesis()?;
 
         let (mut channels, engine_handle) = malachitebft_app_channel::start_engine(
@@ -235,6 +250,11 @@ fn make_config(index: usize, total: usize, settings: MakeConfigSettings) -> Conf
         moniker: format!("test-{index}"),
         consensus: ConsensusConfig {
             enabled: true,
+            accountability: false,
+            max_evidence_age: 100,
+            validator_set_update_delay: NonZeroU64::MIN,
+            aggregate_commit_certificates: false,
+            threshold_params: Default::default(),
             // Current test app does not support proposal-only value payload properly as Init does not include valid_round
             value_payload: ValuePayload::ProposalAndParts,
             queue_capacity: 100, // Deprecated, derived from `sync.parallel_requests`
@@ -281,6 +301,7 @@ fn make_config(index: usize, total: usize, settings: MakeConfigSettings) -> Conf
             listen_addr: format!("127.0.0.1:{metrics_port}").parse().unwrap(),
         },
         runtime: settings.runtime,
+        signer: SignerConfig::default(),
         value_sync: ValueSyncConfig::default(),
         logging: LoggingConfig::default(),
         test: TestConfig::default(),
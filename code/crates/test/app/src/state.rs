@@ -15,11 +15,12 @@ use malachitebft_app_channel::app::consensus::{ProposedValue, Role};
 use malachitebft_app_channel::app::streaming::{StreamContent, StreamId, StreamMessage};
 use malachitebft_app_channel::app::types::codec::Codec;
 use malachitebft_app_channel::app::types::core::{CommitCertificate, Round, Validity};
+use malachitebft_app_channel::app::types::signing::SigningProvider;
 use malachitebft_app_channel::app::types::{LocallyProposedValue, PeerId};
 use malachitebft_test::codec::json::JsonCodec;
 use malachitebft_test::{
-    Address, Ed25519Provider, Genesis, Height, ProposalData, ProposalFin, ProposalInit,
-    ProposalPart, TestContext, ValidatorSet, Value, ValueId,
+    Address, Genesis, Height, ProposalData, ProposalFin, ProposalInit, ProposalPart, Signature,
+    TestContext, ValidatorSet, Value, ValueId,
 };
 
 use crate::config::Config;
@@ -44,7 +45,7 @@ pub struct State {
     pub peers: HashSet<PeerId>,
     pub store: Store,
 
-    signing_provider: Ed25519Provider,
+    signing_provider: Box<dyn SigningProvider<TestContext>>,
     streams_map: PartStreamsMap,
     rng: StdRng,
 }
@@ -95,7 +96,7 @@ impl State {
         address: Address,
         height: Height,
         store: Store,
-        signing_provider: Ed25519Provider,
+        signing_provider: Box<dyn SigningProvider<TestContext>>,
     ) -> Self {
         Self {
             ctx,
@@ -133,7 +134,7 @@ impl State {
     }
 
     /// Validates a proposal by checking both proposer and signature
-    pub fn validate_proposal_parts(
+    pub async fn validate_proposal_parts(
         &self,
         parts: &ProposalParts,
     ) -> Result<(), ProposalValidationError> {
@@ -159,17 +160,18 @@ impl State {
 
         // If proposer is correct, verify the signature
         self.verify_proposal_parts_signature(parts)
+            .await
             .map_err(ProposalValidationError::Signature)?;
 
         Ok(())
     }
 
     /// Verify proposal signature
-    fn verify_proposal_parts_signature(
+    async fn verify_proposal_parts_signature(
         &self,
         parts: &ProposalParts,
     ) -> Result<(), SignatureVerificationError> {
-        let mut hasher = sha3::Keccak256::new();
+        let mut hasher = sha3::Keccak512::new();
 
         let init = parts
             .init()
@@ -201,10 +203,17 @@ impl State {
             .ok_or(SignatureVerificationError::ProposerNotFound)?;
 
         // Verify the signature
-        if !self
+        let valid = self
             .signing_provider
-            .verify(&hash, &fin.signature, &proposer.public_key)
-        {
+            .verify_signed_proposal_part(
+                &fin_sign_part(&hash),
+                &fin.signature,
+                &proposer.public_key,
+            )
+            .await
+            .is_ok_and(|result| result.is_valid());
+
+        if !valid {
             return Err(SignatureVerificationError::InvalidSignature);
         }
 
@@ -246,7 +255,7 @@ impl State {
         }
 
         // For current height, validate proposal (proposer + signature)
-        match self.validate_proposal_parts(&parts) {
+        match self.validate_proposal_parts(&parts).await {
             Ok(()) => {
                 // Validation passed - assemble and store as undecided
                 let value = Self::assemble_value_from_parts(parts)?;
@@ -442,12 +451,12 @@ impl State {
 
     /// Creates a stream message containing a proposal part.
     /// Updates internal sequence number and current proposal.
-    pub fn stream_proposal(
+    pub async fn stream_proposal(
         &mut self,
         value: LocallyProposedValue<TestContext>,
         pol_round: Round,
-    ) -> impl Iterator<Item = StreamMessage<ProposalPart>> {
-        let parts = self.value_to_parts(value, pol_round);
+    ) -> eyre::Result<impl Iterator<Item = StreamMessage<ProposalPart>>> {
+        let parts = self.value_to_parts(value, pol_round).await?;
         let stream_id = self.stream_id();
 
         let mut msgs = Vec::with_capacity(parts.len() + 1);
@@ -465,15 +474,15 @@ impl State {
             StreamContent::Fin,
         ));
 
-        msgs.into_iter()
+        Ok(msgs.into_iter())
     }
 
-    fn value_to_parts(
+    async fn value_to_parts(
         &self,
         value: LocallyProposedValue<TestContext>,
         pol_round: Round,
-    ) -> Vec<ProposalPart> {
-        let mut hasher = sha3::Keccak256::new();
+    ) -> eyre::Result<Vec<ProposalPart>> {
+        let mut hasher = sha3::Keccak512::new();
         let mut parts = Vec::new();
 
         // Init
@@ -503,12 +512,15 @@ impl State {
         // Fin
         // Sign the hash of the proposal parts
         {
-            let hash = hasher.finalize().to_vec();
-            let signature = self.signing_provider.sign(&hash);
-            parts.push(ProposalPart::Fin(ProposalFin::new(signature)));
+            let hash = hasher.finalize();
+            let signed = self
+                .signing_provider
+                .sign_proposal_part(fin_sign_part(&hash))
+                .await?;
+            parts.push(ProposalPart::Fin(ProposalFin::new(signed.signature)));
         }
 
-        parts
+        Ok(parts)
     }
 
     /// Re-assemble a [`ProposedValue`] from its [`ProposalParts`].
@@ -539,6 +551,15 @@ impl State {
     }
 }
 
+/// The part signed by the proposer for the `Fin` part of a proposal with the given hash:
+/// a `Fin` part carrying the hash in place of the signature, so that the signature commits
+/// to all the parts while being made with `sign_proposal_part`, eg. by a remote signer.
+fn fin_sign_part(hash: &[u8]) -> ProposalPart {
+    let mut bytes = [0; 64];
+    bytes.copy_from_slice(hash);
+    ProposalPart::Fin(ProposalFin::new(Signature::from_bytes(bytes)))
+}
+
 /// Encode a value to its byte representation
 pub fn encode_value(value: &Value) -> Bytes {
     JsonCodec.encode(value).unwrap()
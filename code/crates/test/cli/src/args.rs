@@ -14,6 +14,7 @@ use directories::BaseDirs;
 use crate::cmd::distributed_testnet::DistributedTestnetCmd;
 use crate::cmd::dump_wal::DumpWalCmd;
 use crate::cmd::init::InitCmd;
+use crate::cmd::key::KeyCmd;
 use crate::cmd::start::StartCmd;
 use crate::cmd::testnet::TestnetCmd;
 use crate::error::Error;
@@ -50,6 +51,9 @@ pub enum Commands {
 
     /// Dump WAL entries
     DumpWal(DumpWalCmd),
+
+    /// Import or export the private validator key, or change its passphrase
+    Key(KeyCmd),
 }
 
 impl Default for Commands {
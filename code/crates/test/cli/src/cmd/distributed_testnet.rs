@@ -49,6 +49,7 @@ pub struct DistributedTestnetCmd {
     /// Possible values:
     /// - "kademlia": Kademlia
     /// - "full": Full mesh (default)
+    /// - "pex": Peer exchange of signed peer records, without Kademlia
     #[clap(long, default_value = "full", verbatim_doc_comment)]
     pub bootstrap_protocol: BootstrapProtocol,
 
@@ -57,6 +58,7 @@ pub struct DistributedTestnetCmd {
     /// Possible values:
     /// - "kademlia": Kademlia-based selection, only available with the Kademlia bootstrap protocol
     /// - "random": Random selection (default)
+    /// - "latency": Lowest round-trip times first, with a limit of outbound peers per subnet
     #[clap(long, default_value = "random", verbatim_doc_comment)]
     pub selector: Selector,
 
@@ -69,11 +71,22 @@ pub struct DistributedTestnetCmd {
     #[clap(long, default_value = "20", verbatim_doc_comment)]
     pub num_inbound_peers: usize,
 
+    /// Maximum number of outbound peers per subnet
+    /// Only used by the latency selector, 0 means no limit
+    #[clap(long, default_value = "3", verbatim_doc_comment)]
+    pub max_outbound_peers_per_subnet: usize,
+
     /// Ephemeral connection timeout
     /// The duration in milliseconds an ephemeral connection is kept alive
     #[clap(long, default_value = "5000", verbatim_doc_comment)]
     pub ephemeral_connection_timeout_ms: u64,
 
+    /// Peer exchange interval
+    /// The interval in milliseconds at which a connected peer is asked for more peers,
+    /// with the peer exchange bootstrap protocol
+    #[clap(long, default_value = "30000", verbatim_doc_comment)]
+    pub pex_interval_ms: u64,
+
     /// The size of the bootstrap set.
     #[clap(long, default_value = "1", verbatim_doc_comment)]
     pub bootstrap_set_size: usize,
@@ -111,9 +124,12 @@ impl DistributedTestnetCmd {
                 num_outbound_peers: self.num_outbound_peers,
                 num_inbound_peers: self.num_inbound_peers,
                 max_connections_per_peer: self.num_outbound_peers + self.num_inbound_peers,
+                max_outbound_peers_per_subnet: self.max_outbound_peers_per_subnet,
                 ephemeral_connection_timeout: Duration::from_millis(
                     self.ephemeral_connection_timeout_ms,
                 ),
+                pex_interval: Duration::from_millis(self.pex_interval_ms),
+                require_signed_peer_records: true,
             },
             value_sync: Default::default(),
         };
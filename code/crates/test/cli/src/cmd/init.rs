@@ -6,6 +6,7 @@ use std::time::Duration;
 use clap::Parser;
 use tracing::{info, warn};
 
+use malachitebft_app::keystore::{Passphrase, PASSPHRASE_ENV_VAR};
 use malachitebft_app::node::{
     CanGeneratePrivateKey, CanMakeConfig, CanMakeGenesis, CanMakePrivateKeyFile,
     MakeConfigSettings, Node,
@@ -14,8 +15,11 @@ use malachitebft_config::{
     BootstrapProtocol, DiscoveryConfig, RuntimeConfig, Selector, TransportProtocol,
 };
 
+use crate::cmd::key::passphrase_from_env;
 use crate::error::Error;
-use crate::file::{save_config, save_genesis, save_priv_validator_key};
+use crate::file::{
+    save_config, save_encrypted_priv_validator_key, save_genesis, save_priv_validator_key,
+};
 use crate::new::{generate_genesis, generate_private_keys};
 
 #[derive(Parser, Debug, Clone, Default, PartialEq)]
@@ -24,6 +28,12 @@ pub struct InitCmd {
     #[clap(long)]
     pub overwrite: bool,
 
+    /// Encrypt the private validator key file with the passphrase read from the
+    /// `MALACHITE_KEY_PASSPHRASE` environment variable or, if it is not set,
+    /// from the file at the path given in `MALACHITE_KEY_PASSPHRASE_FILE`
+    #[clap(long, verbatim_doc_comment)]
+    pub encrypt: bool,
+
     /// Enable peer discovery.
     /// If enabled, the node will attempt to discover other nodes in the network
     #[clap(long, default_value = "true")]
@@ -34,6 +44,7 @@ pub struct InitCmd {
     /// Possible values:
     /// - "kademlia": Kademlia
     /// - "full": Full mesh (default)
+    /// - "pex": Peer exchange of signed peer records, without Kademlia
     #[clap(long, default_value = "full", verbatim_doc_comment)]
     pub bootstrap_protocol: BootstrapProtocol,
 
@@ -42,6 +53,7 @@ pub struct InitCmd {
     /// Possible values:
     /// - "kademlia": Kademlia-based selection, only available with the Kademlia bootstrap protocol
     /// - "random": Random selection (default)
+    /// - "latency": Lowest round-trip times first, with a limit of outbound peers per subnet
     #[clap(long, default_value = "random", verbatim_doc_comment)]
     pub selector: Selector,
 
@@ -59,10 +71,21 @@ pub struct InitCmd {
     #[clap(long, default_value = "5", verbatim_doc_comment)]
     pub max_connections_per_peer: usize,
 
+    /// Maximum number of outbound peers per subnet
+    /// Only used by the latency selector, 0 means no limit
+    #[clap(long, default_value = "3", verbatim_doc_comment)]
+    pub max_outbound_peers_per_subnet: usize,
+
     /// Ephemeral connection timeout
     /// The duration in milliseconds an ephemeral connection is kept alive
     #[clap(long, default_value = "5000", verbatim_doc_comment)]
     pub ephemeral_connection_timeout_ms: u64,
+
+    /// Peer exchange interval
+    /// The interval in milliseconds at which a connected peer is asked for more peers,
+    /// with the peer exchange bootstrap protocol
+    #[clap(long, default_value = "30000", verbatim_doc_comment)]
+    pub pex_interval_ms: u64,
 }
 
 impl InitCmd {
@@ -87,21 +110,30 @@ impl InitCmd {
                 num_outbound_peers: self.num_outbound_peers,
                 num_inbound_peers: self.num_inbound_peers,
                 max_connections_per_peer: self.max_connections_per_peer,
+                max_outbound_peers_per_subnet: self.max_outbound_peers_per_subnet,
                 ephemeral_connection_timeout: Duration::from_millis(
                     self.ephemeral_connection_timeout_ms,
                 ),
+                pex_interval: Duration::from_millis(self.pex_interval_ms),
+                require_signed_peer_records: true,
             },
             value_sync: Default::default(),
         };
 
         let config = N::make_config(0, 1, settings);
 
+        let passphrase = self
+            .encrypt
+            .then(|| passphrase_from_env(PASSPHRASE_ENV_VAR))
+            .transpose()?;
+
         init(
             node,
             &config,
             config_file,
             genesis_file,
             priv_validator_key_file,
+            passphrase.as_ref(),
             self.overwrite,
         )?;
 
@@ -116,6 +148,7 @@ pub fn init<N>(
     config_file: &Path,
     genesis_file: &Path,
     priv_validator_key_file: &Path,
+    passphrase: Option<&Passphrase>,
     overwrite: bool,
 ) -> Result<(), Error>
 where
@@ -136,10 +169,21 @@ where
             "Private key file already exists, skipping",
         );
     } else {
-        info!(file = ?priv_validator_key_file, "Saving private key");
         let private_keys = generate_private_keys(node, 1, false);
         let priv_validator_key = node.make_private_key_file(private_keys[0].clone());
-        save_priv_validator_key(node, priv_validator_key_file, &priv_validator_key)?;
+
+        if let Some(passphrase) = passphrase {
+            info!(file = ?priv_validator_key_file, "Saving encrypted private key");
+            save_encrypted_priv_validator_key(
+                node,
+                priv_validator_key_file,
+                &priv_validator_key,
+                passphrase,
+            )?;
+        } else {
+            info!(file = ?priv_validator_key_file, "Saving private key");
+            save_priv_validator_key(node, priv_validator_key_file, &priv_validator_key)?;
+        }
     }
 
     // Save default genesis
@@ -0,0 +1,146 @@
+//! Key command
+
+use std::path::{Path, PathBuf};
+
+use clap::{Parser, Subcommand};
+use tracing::{info, warn};
+
+use malachitebft_app::keystore::{self, Passphrase, PASSPHRASE_ENV_VAR};
+use malachitebft_app::node::Node;
+
+use crate::error::Error;
+use crate::file::{load_priv_validator_key, save_encrypted_priv_validator_key, save_private};
+
+/// Name of the environment variable holding the new passphrase when changing it.
+///
+/// The passphrase can also be read from the file at the path given in `MALACHITE_NEW_KEY_PASSPHRASE_FILE`.
+pub const NEW_PASSPHRASE_ENV_VAR: &str = "MALACHITE_NEW_KEY_PASSPHRASE";
+
+/// Manage the private validator key file.
+///
+/// The passphrase of the encrypted key file is read from the `MALACHITE_KEY_PASSPHRASE`
+/// environment variable or, if it is not set, from the file at the path given in
+/// `MALACHITE_KEY_PASSPHRASE_FILE`.
+#[derive(Parser, Debug, Clone, PartialEq)]
+#[command(verbatim_doc_comment)]
+pub struct KeyCmd {
+    #[command(subcommand)]
+    pub command: KeySubcommand,
+}
+
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+pub enum KeySubcommand {
+    /// Encrypt a plain text private key file, and save it as the private validator key file
+    Import {
+        /// Path to the private key file to import
+        file: PathBuf,
+
+        /// Overwrite an existing private validator key file
+        #[clap(long)]
+        overwrite: bool,
+    },
+
+    /// Decrypt the private validator key file, and save it in plain text
+    Export {
+        /// Path to the file to save the private key to
+        output: PathBuf,
+
+        /// Overwrite an existing output file
+        #[clap(long)]
+        overwrite: bool,
+    },
+
+    /// Encrypt the private validator key file with a new passphrase, read from the
+    /// `MALACHITE_NEW_KEY_PASSPHRASE` environment variable or, if it is not set,
+    /// from the file at the path given in `MALACHITE_NEW_KEY_PASSPHRASE_FILE`
+    #[command(verbatim_doc_comment)]
+    ChangePassphrase,
+}
+
+impl KeyCmd {
+    /// Execute the key command
+    pub fn run<N: Node>(&self, node: &N, priv_validator_key_file: &Path) -> Result<(), Error> {
+        match &self.command {
+            KeySubcommand::Import { file, overwrite } => {
+                import(node, file, priv_validator_key_file, *overwrite)
+            }
+            KeySubcommand::Export { output, overwrite } => {
+                export(node, priv_validator_key_file, output, *overwrite)
+            }
+            KeySubcommand::ChangePassphrase => change_passphrase(node, priv_validator_key_file),
+        }
+    }
+}
+
+/// Read the passphrase from the environment variable with the given name,
+/// or from the file given in the variable of the same name suffixed with `_FILE`.
+pub fn passphrase_from_env(name: &str) -> Result<Passphrase, Error> {
+    Passphrase::from_env_var(name)?
+        .ok_or_else(|| keystore::Error::MissingPassphrase(name.to_string()).into())
+}
+
+/// Encrypt the given plain text private key file, and save it as the private validator key file.
+pub fn import<N: Node>(
+    node: &N,
+    file: &Path,
+    priv_validator_key_file: &Path,
+    overwrite: bool,
+) -> Result<(), Error> {
+    if priv_validator_key_file.exists() && !overwrite {
+        return Err(Error::FileExists(priv_validator_key_file.to_path_buf()));
+    }
+
+    let passphrase = passphrase_from_env(PASSPHRASE_ENV_VAR)?;
+    let priv_validator_key = load_priv_validator_key(node, file, Some(&passphrase))?;
+
+    info!(file = ?priv_validator_key_file, "Saving encrypted private key");
+    save_encrypted_priv_validator_key(
+        node,
+        priv_validator_key_file,
+        &priv_validator_key,
+        &passphrase,
+    )
+}
+
+/// Decrypt the private validator key file, and save it in plain text to the given output file.
+pub fn export<N: Node>(
+    node: &N,
+    priv_validator_key_file: &Path,
+    output: &Path,
+    overwrite: bool,
+) -> Result<(), Error> {
+    if output.exists() && !overwrite {
+        return Err(Error::FileExists(output.to_path_buf()));
+    }
+
+    let passphrase = Passphrase::from_env()?;
+    let priv_validator_key =
+        load_priv_validator_key(node, priv_validator_key_file, passphrase.as_ref())?;
+
+    warn!(file = ?output, "Saving private key in plain text");
+    save_private(
+        output,
+        &serde_json::to_string_pretty(&priv_validator_key)
+            .map_err(|e| Error::ToJSON(e.to_string()))?,
+    )
+}
+
+/// Encrypt the private validator key file with the new passphrase.
+///
+/// The private key itself is left unchanged, and a plain text private validator key file
+/// is encrypted in place.
+pub fn change_passphrase<N: Node>(node: &N, priv_validator_key_file: &Path) -> Result<(), Error> {
+    let new_passphrase = passphrase_from_env(NEW_PASSPHRASE_ENV_VAR)?;
+
+    let passphrase = Passphrase::from_env()?;
+    let priv_validator_key =
+        load_priv_validator_key(node, priv_validator_key_file, passphrase.as_ref())?;
+
+    info!(file = ?priv_validator_key_file, "Saving private key encrypted with the new passphrase");
+    save_encrypted_priv_validator_key(
+        node,
+        priv_validator_key_file,
+        &priv_validator_key,
+        &new_passphrase,
+    )
+}
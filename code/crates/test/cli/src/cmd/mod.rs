@@ -1,5 +1,6 @@
 pub mod distributed_testnet;
 pub mod dump_wal;
 pub mod init;
+pub mod key;
 pub mod start;
 pub mod testnet;
@@ -8,6 +8,7 @@ use clap::Parser;
 use color_eyre::eyre::{eyre, Result};
 use tracing::info;
 
+use malachitebft_app::keystore::{Passphrase, PASSPHRASE_ENV_VAR};
 use malachitebft_app::node::{
     CanGeneratePrivateKey, CanMakeConfig, CanMakeGenesis, CanMakePrivateKeyFile,
     MakeConfigSettings, Node,
@@ -15,8 +16,11 @@ use malachitebft_app::node::{
 use malachitebft_config::*;
 
 use crate::args::Args;
+use crate::cmd::key::passphrase_from_env;
 use crate::error::Error;
-use crate::file::{save_config, save_genesis, save_priv_validator_key};
+use crate::file::{
+    save_config, save_encrypted_priv_validator_key, save_genesis, save_priv_validator_key,
+};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum RuntimeFlavour {
@@ -74,6 +78,7 @@ pub struct TestnetCmd {
     /// Possible values:
     /// - "kademlia": Kademlia
     /// - "full": Full mesh (default)
+    /// - "pex": Peer exchange of signed peer records, without Kademlia
     #[clap(long, default_value = "full", verbatim_doc_comment)]
     pub bootstrap_protocol: BootstrapProtocol,
 
@@ -82,6 +87,7 @@ pub struct TestnetCmd {
     /// Possible values:
     /// - "kademlia": Kademlia-based selection, only available with the Kademlia bootstrap protocol
     /// - "random": Random selection (default)
+    /// - "latency": Lowest round-trip times first, with a limit of outbound peers per subnet
     #[clap(long, default_value = "random", verbatim_doc_comment)]
     pub selector: Selector,
 
@@ -99,17 +105,34 @@ pub struct TestnetCmd {
     #[clap(long, default_value = "5", verbatim_doc_comment)]
     pub max_connections_per_peer: usize,
 
+    /// Maximum number of outbound peers per subnet
+    /// Only used by the latency selector, 0 means no limit
+    #[clap(long, default_value = "3", verbatim_doc_comment)]
+    pub max_outbound_peers_per_subnet: usize,
+
     /// Ephemeral connection timeout
     /// The duration in milliseconds an ephemeral connection is kept alive
     #[clap(long, default_value = "5000", verbatim_doc_comment)]
     pub ephemeral_connection_timeout_ms: u64,
 
+    /// Peer exchange interval
+    /// The interval in milliseconds at which a connected peer is asked for more peers,
+    /// with the peer exchange bootstrap protocol
+    #[clap(long, default_value = "30000", verbatim_doc_comment)]
+    pub pex_interval_ms: u64,
+
     /// The transport protocol to use for P2P communication
     /// Possible values:
     /// - "tcp": TCP + Noise (default)
     /// - "quic": QUIC
     #[clap(short, long, default_value = "tcp", verbatim_doc_comment)]
     pub transport: TransportProtocol,
+
+    /// Encrypt the private validator key files with the passphrase read from the
+    /// `MALACHITE_KEY_PASSPHRASE` environment variable or, if it is not set,
+    /// from the file at the path given in `MALACHITE_KEY_PASSPHRASE_FILE`
+    #[clap(long, verbatim_doc_comment)]
+    pub encrypt: bool,
 }
 
 impl TestnetCmd {
@@ -133,15 +156,31 @@ impl TestnetCmd {
                 num_outbound_peers: self.num_outbound_peers,
                 num_inbound_peers: self.num_inbound_peers,
                 max_connections_per_peer: self.max_connections_per_peer,
+                max_outbound_peers_per_subnet: self.max_outbound_peers_per_subnet,
                 ephemeral_connection_timeout: Duration::from_millis(
                     self.ephemeral_connection_timeout_ms,
                 ),
+                pex_interval: Duration::from_millis(self.pex_interval_ms),
+                require_signed_peer_records: true,
             },
             value_sync: Default::default(),
         };
 
-        testnet(node, self.nodes, home_dir, self.deterministic, settings)
-            .map_err(|e| eyre!("Failed to generate testnet configuration: {:?}", e))
+        let passphrase = self
+            .encrypt
+            .then(|| passphrase_from_env(PASSPHRASE_ENV_VAR))
+            .transpose()
+            .map_err(|e| eyre!("Failed to read passphrase: {e}"))?;
+
+        testnet(
+            node,
+            self.nodes,
+            home_dir,
+            self.deterministic,
+            settings,
+            passphrase.as_ref(),
+        )
+        .map_err(|e| eyre!("Failed to generate testnet configuration: {:?}", e))
     }
 }
 
@@ -151,6 +190,7 @@ pub fn testnet<N>(
     home_dir: &Path,
     deterministic: bool,
     settings: MakeConfigSettings,
+    passphrase: Option<&Passphrase>,
 ) -> std::result::Result<(), Error>
 where
     N: Node + CanMakeConfig + CanMakePrivateKeyFile + CanGeneratePrivateKey + CanMakeGenesis,
@@ -187,11 +227,18 @@ where
 
         // Save private key
         let priv_validator_key = node.make_private_key_file((*private_key).clone());
-        save_priv_validator_key(
-            node,
-            &args.get_priv_validator_key_file_path()?,
-            &priv_validator_key,
-        )?;
+        let priv_validator_key_file = args.get_priv_validator_key_file_path()?;
+
+        if let Some(passphrase) = passphrase {
+            save_encrypted_priv_validator_key(
+                node,
+                &priv_validator_key_file,
+                &priv_validator_key,
+                passphrase,
+            )?;
+        } else {
+            save_priv_validator_key(node, &priv_validator_key_file, &priv_validator_key)?;
+        }
 
         // Save genesis
         save_genesis(node, &args.get_genesis_file_path()?, &genesis)?;
@@ -21,6 +21,10 @@ pub enum Error {
     #[error("Error loading file: {}", .0.display())]
     LoadFile(PathBuf),
 
+    /// Error overwriting an existing file
+    #[error("File already exists, use --overwrite to replace it: {}", .0.display())]
+    FileExists(PathBuf),
+
     /// Error converting to JSON
     #[error("Error converting to JSON: {0}")]
     ToJSON(String),
@@ -29,6 +33,10 @@ pub enum Error {
     #[error("Error determining home directory path")]
     DirPath,
 
+    /// Error encrypting or decrypting a private key file
+    #[error("Error with private key file: {0}")]
+    Keystore(#[from] malachitebft_app::keystore::Error),
+
     /// Error joining threads
     #[error("Error joining threads")]
     Join,
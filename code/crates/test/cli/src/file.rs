@@ -3,6 +3,7 @@
 use std::fs;
 use std::path::Path;
 
+use malachitebft_app::keystore::{self, EncryptedKey, Passphrase, ScryptParams};
 use malachitebft_app::node::Node;
 
 use crate::error::Error;
@@ -40,14 +41,70 @@ pub fn save_priv_validator_key<N: Node>(
     )
 }
 
+/// Save private validator key to file, encrypted with the given passphrase
+pub fn save_encrypted_priv_validator_key<N: Node>(
+    _node: &N,
+    priv_validator_key_file: &Path,
+    priv_validator_key: &N::PrivateKeyFile,
+    passphrase: &Passphrase,
+) -> Result<(), Error> {
+    let encrypted = EncryptedKey::encrypt_json(
+        priv_validator_key,
+        passphrase,
+        ScryptParams::default(),
+        &mut rand::thread_rng(),
+    )?;
+
+    save_private(
+        priv_validator_key_file,
+        &serde_json::to_string_pretty(&encrypted).map_err(|e| Error::ToJSON(e.to_string()))?,
+    )
+}
+
+/// Load private validator key from file, decrypting it with the given passphrase if it is encrypted
+pub fn load_priv_validator_key<N: Node>(
+    _node: &N,
+    priv_validator_key_file: &Path,
+    passphrase: Option<&Passphrase>,
+) -> Result<N::PrivateKeyFile, Error> {
+    let contents = fs::read_to_string(priv_validator_key_file)
+        .map_err(|_| Error::LoadFile(priv_validator_key_file.to_path_buf()))?;
+
+    Ok(keystore::parse_key_file(&contents, passphrase)?)
+}
+
+/// Save data to a file only readable and writable by its owner
+pub(crate) fn save_private(path: &Path, data: &str) -> Result<(), Error> {
+    let mut options = fs::OpenOptions::new();
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+        // The mode is only applied when creating the file, so restrict an existing one first
+        if path.exists() {
+            fs::set_permissions(path, fs::Permissions::from_mode(0o600))
+                .map_err(|_| Error::WriteFile(path.to_path_buf()))?;
+        }
+
+        options.mode(0o600);
+    }
+
+    write(path, data, &mut options)
+}
+
 fn save(path: &Path, data: &str) -> Result<(), Error> {
+    write(path, data, &mut fs::OpenOptions::new())
+}
+
+fn write(path: &Path, data: &str, options: &mut fs::OpenOptions) -> Result<(), Error> {
     use std::io::Write;
 
     if let Some(parent_dir) = path.parent() {
         fs::create_dir_all(parent_dir).map_err(|_| Error::ParentDir(parent_dir.to_path_buf()))?;
     }
 
-    let mut f = fs::OpenOptions::new()
+    let mut f = options
         .write(true)
         .create(true)
         .truncate(true)
@@ -0,0 +1,170 @@
+//! Reference remote signer daemon for the test application.
+//!
+//! Signs the votes, proposals, proposal parts and vote extensions of a node
+//! with the private key of its validator, loaded from a `priv_validator_key.json` file,
+//! so that this key does not need to be on the host running the node.
+//!
+//! The signer refuses to sign conflicting votes and proposals, keeping track of
+//! the last signed one in a state file which must be kept alongside the private key.
+//!
+//! Alternatively, given a key share of the validator instead of its private key,
+//! the signer acts as one of the cosigners of a threshold signer, only producing
+//! signature shares which are then aggregated by the node.
+
+use std::path::{Path, PathBuf};
+
+use clap::Parser;
+use eyre::{eyre, Result};
+use serde::de::DeserializeOwned;
+use tracing::{info, warn};
+
+use malachitebft_remote_signer::{Address, IdentityKey, IdentityPublicKey, Listener, SignerServer};
+use malachitebft_signing_guard::DoubleSignGuard;
+use malachitebft_test::codec::proto::ProtobufCodec;
+use malachitebft_test::{Ed25519Provider, PrivateKey, TestSignBytes};
+use malachitebft_test_cli::config::{LogFormat, LogLevel};
+use malachitebft_test_cli::logging;
+use malachitebft_threshold_signer::frost::KeyShare;
+use malachitebft_threshold_signer::{CosignerServer, LocalCosigner};
+
+#[derive(Parser, Debug)]
+#[command(version, about)]
+struct Args {
+    /// Address to listen on for connections from the node,
+    /// eg. `tcp://127.0.0.1:26659` or `unix:///var/run/malachite-signer.sock`
+    #[arg(
+        long,
+        env = "MALACHITE_SIGNER_LISTEN",
+        default_value = "tcp://127.0.0.1:26659"
+    )]
+    listen: Address,
+
+    /// Path to the private key file of the validator
+    #[arg(
+        long,
+        env = "MALACHITE_SIGNER_KEY_FILE",
+        required_unless_present = "key_share",
+        conflicts_with = "key_share"
+    )]
+    key_file: Option<PathBuf>,
+
+    /// Path to a key share file of the validator, to run as one of its threshold cosigners
+    #[arg(long, env = "MALACHITE_SIGNER_KEY_SHARE")]
+    key_share: Option<PathBuf>,
+
+    /// Path to the file holding the state of the last signed vote or proposal,
+    /// which is created if it does not exist
+    #[arg(long, env = "MALACHITE_SIGNER_STATE_FILE")]
+    state_file: PathBuf,
+
+    /// Path to the file holding the identity key of the signer, which is generated if it does not exist
+    #[arg(long, env = "MALACHITE_SIGNER_IDENTITY_KEY_FILE")]
+    identity_key_file: PathBuf,
+
+    /// Identity public key of a node allowed to connect to the signer, can be repeated
+    #[arg(
+        long = "authorized-node",
+        env = "MALACHITE_SIGNER_AUTHORIZED_NODES",
+        value_delimiter = ','
+    )]
+    authorized_nodes: Vec<IdentityPublicKey>,
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+
+    let args = Args::parse();
+
+    // This is a drop guard responsible for flushing any remaining logs when the program terminates.
+    // It must be assigned to a binding that is not _, as _ will result in the guard being dropped immediately.
+    let _guard = logging::init(LogLevel::Info, LogFormat::Plaintext);
+
+    let rt = tokio::runtime::Runtime::new()?;
+
+    let identity = IdentityKey::load_or_generate(&args.identity_key_file)?;
+
+    if args.authorized_nodes.is_empty() {
+        warn!("No authorized nodes, all connections will be rejected");
+    }
+
+    let result = match (args.key_file, args.key_share) {
+        (_, Some(key_share)) => {
+            let key_share = load_key_file(&key_share)?;
+
+            rt.block_on(run_cosigner(
+                args.listen,
+                key_share,
+                args.state_file,
+                identity,
+                args.authorized_nodes,
+            ))
+        }
+        (Some(key_file), None) => {
+            let private_key = load_key_file(&key_file)?;
+
+            rt.block_on(run(
+                args.listen,
+                private_key,
+                args.state_file,
+                identity,
+                args.authorized_nodes,
+            ))
+        }
+        (None, None) => unreachable!("either a key file or a key share is required"),
+    };
+
+    result.map_err(|error| eyre!("Failed to run the remote signer: {error}"))
+}
+
+fn load_key_file<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let key = std::fs::read_to_string(path)
+        .map_err(|error| eyre!("Failed to read key file {}: {error}", path.display()))?;
+
+    serde_json::from_str(&key).map_err(Into::into)
+}
+
+async fn run(
+    address: Address,
+    private_key: PrivateKey,
+    state_file: PathBuf,
+    identity: IdentityKey,
+    authorized_nodes: Vec<IdentityPublicKey>,
+) -> Result<()> {
+    let public_key = private_key.public_key();
+    let identity_public_key = identity.public_key();
+
+    let provider =
+        DoubleSignGuard::open(state_file, ProtobufCodec, Ed25519Provider::new(private_key))?;
+
+    let listener = Listener::bind(&address).await?;
+    let server = SignerServer::new(identity, authorized_nodes, ProtobufCodec, provider);
+
+    info!(%address, ?public_key, %identity_public_key, "Listening for connections from the node");
+
+    server.serve(listener).await?;
+
+    Ok(())
+}
+
+async fn run_cosigner(
+    address: Address,
+    key_share: KeyShare,
+    state_file: PathBuf,
+    identity: IdentityKey,
+    authorized_nodes: Vec<IdentityPublicKey>,
+) -> Result<()> {
+    let identifier = key_share.identifier();
+    let public_key = key_share.group_public_key();
+    let identity_public_key = identity.public_key();
+
+    let cosigner = LocalCosigner::open(key_share, state_file, TestSignBytes, ProtobufCodec)?;
+
+    let listener = Listener::bind(&address).await?;
+    let server = CosignerServer::new(identity, authorized_nodes, ProtobufCodec, cosigner);
+
+    info!(%address, %identifier, ?public_key, %identity_public_key, "Listening for connections from the node");
+
+    server.serve(listener).await?;
+
+    Ok(())
+}
@@ -5,13 +5,15 @@ use malachitebft_codec::{Codec, HasEncodedLen};
 use tracing::warn;
 
 use malachitebft_core_consensus::{LivenessMsg, SignedConsensusMsg};
+use malachitebft_core_types::Evidence;
 use malachitebft_engine::util::streaming::StreamMessage;
 use malachitebft_sync::{Request, Response, Status};
 
 use crate::{ProposalPart, TestContext, Value};
 
 use raw::{
-    RawLivenessMsg, RawRequest, RawResponse, RawSignedConsensusMsg, RawStatus, RawStreamMessage,
+    RawEvidence, RawLivenessMsg, RawRequest, RawResponse, RawSignedConsensusMsg, RawStatus,
+    RawStreamMessage,
 };
 
 #[derive(Copy, Clone, Debug)]
@@ -123,3 +125,15 @@ impl Codec<LivenessMsg<TestContext>> for JsonCodec {
         serde_json::to_vec(&RawLivenessMsg::from(msg.clone())).map(Bytes::from)
     }
 }
+
+impl Codec<Evidence<TestContext>> for JsonCodec {
+    type Error = serde_json::Error;
+
+    fn decode(&self, bytes: Bytes) -> Result<Evidence<TestContext>, Self::Error> {
+        serde_json::from_slice::<RawEvidence>(&bytes).map(Into::into)
+    }
+
+    fn encode(&self, msg: &Evidence<TestContext>) -> Result<Bytes, Self::Error> {
+        serde_json::to_vec(&RawEvidence::from(msg.clone())).map(Bytes::from)
+    }
+}
@@ -5,8 +5,10 @@ use serde::{Deserialize, Serialize};
 use malachitebft_app::streaming::StreamId;
 use malachitebft_core_consensus::{LivenessMsg, SignedConsensusMsg};
 use malachitebft_core_types::{
-    CommitCertificate, CommitSignature, NilOrVal, PolkaCertificate, PolkaSignature, Round,
-    RoundCertificate, RoundCertificateType, RoundSignature, SignedProposal, SignedVote, VoteType,
+    AggregatedSignature, AmnesiaEvidence, CommitCertificate, CommitSignature,
+    DoubleProposalEvidence, DoubleVoteEvidence, Evidence, NilOrVal, PolkaCertificate,
+    PolkaSignature, Round, RoundCertificate, RoundCertificateType, RoundSignature, SignedProposal,
+    SignedVote, SignerBitmap, VoteType,
 };
 use malachitebft_engine::util::streaming::{StreamContent, StreamMessage};
 use malachitebft_proto::Protobuf;
@@ -191,12 +193,20 @@ pub struct RawCommitSignatures {
     pub signatures: Vec<RawCommitSignature>,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct RawAggregatedSignature {
+    pub signers: Vec<u8>,
+    pub signature: Signature,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct RawCommitCertificate {
     pub height: Height,
     pub round: Round,
     pub value_id: ValueId,
     pub commit_signatures: RawCommitSignatures,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aggregated_signature: Option<RawAggregatedSignature>,
 }
 
 impl From<RawCommitCertificate> for CommitCertificate<TestContext> {
@@ -214,6 +224,12 @@ impl From<RawCommitCertificate> for CommitCertificate<TestContext> {
                     signature: sig.signature.into(),
                 })
                 .collect(),
+            aggregated_signature: value.aggregated_signature.map(|aggregated| {
+                AggregatedSignature::new(
+                    SignerBitmap::from_bytes(aggregated.signers),
+                    aggregated.signature.into(),
+                )
+            }),
         }
     }
 }
@@ -234,6 +250,12 @@ impl From<CommitCertificate<TestContext>> for RawCommitCertificate {
                     })
                     .collect(),
             },
+            aggregated_signature: value.aggregated_signature.map(|aggregated| {
+                RawAggregatedSignature {
+                    signers: aggregated.signers.as_bytes().to_vec(),
+                    signature: *aggregated.signature.inner(),
+                }
+            }),
         }
     }
 }
@@ -418,3 +440,90 @@ impl From<RawLivenessMsg> for LivenessMsg<TestContext> {
         }
     }
 }
+
+impl From<SignedVote<TestContext>> for RawSignedMessage {
+    fn from(vote: SignedVote<TestContext>) -> Self {
+        Self {
+            message: vote.message.to_sign_bytes(),
+            signature: *vote.signature.inner(),
+        }
+    }
+}
+
+impl From<RawSignedMessage> for SignedVote<TestContext> {
+    fn from(vote: RawSignedMessage) -> Self {
+        SignedVote {
+            message: Vote::from_sign_bytes(&vote.message).unwrap(),
+            signature: vote.signature.into(),
+        }
+    }
+}
+
+impl From<SignedProposal<TestContext>> for RawSignedMessage {
+    fn from(proposal: SignedProposal<TestContext>) -> Self {
+        Self {
+            message: proposal.message.to_sign_bytes(),
+            signature: *proposal.signature.inner(),
+        }
+    }
+}
+
+impl From<RawSignedMessage> for SignedProposal<TestContext> {
+    fn from(proposal: RawSignedMessage) -> Self {
+        SignedProposal {
+            message: Proposal::from_sign_bytes(&proposal.message).unwrap(),
+            signature: proposal.signature.into(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub enum RawEvidence {
+    DoubleVote {
+        first: RawSignedMessage,
+        second: RawSignedMessage,
+    },
+    DoubleProposal {
+        first: RawSignedMessage,
+        second: RawSignedMessage,
+    },
+    Amnesia {
+        precommit: RawSignedMessage,
+        prevote: RawSignedMessage,
+    },
+}
+
+impl From<Evidence<TestContext>> for RawEvidence {
+    fn from(value: Evidence<TestContext>) -> Self {
+        match value {
+            Evidence::DoubleVote(evidence) => Self::DoubleVote {
+                first: evidence.first.into(),
+                second: evidence.second.into(),
+            },
+            Evidence::DoubleProposal(evidence) => Self::DoubleProposal {
+                first: evidence.first.into(),
+                second: evidence.second.into(),
+            },
+            Evidence::Amnesia(evidence) => Self::Amnesia {
+                precommit: evidence.precommit.into(),
+                prevote: evidence.prevote.into(),
+            },
+        }
+    }
+}
+
+impl From<RawEvidence> for Evidence<TestContext> {
+    fn from(value: RawEvidence) -> Self {
+        match value {
+            RawEvidence::DoubleVote { first, second } => {
+                Evidence::DoubleVote(DoubleVoteEvidence::new(first.into(), second.into()))
+            }
+            RawEvidence::DoubleProposal { first, second } => {
+                Evidence::DoubleProposal(DoubleProposalEvidence::new(first.into(), second.into()))
+            }
+            RawEvidence::Amnesia { precommit, prevote } => {
+                Evidence::Amnesia(AmnesiaEvidence::new(precommit.into(), prevote.into()))
+            }
+        }
+    }
+}
@@ -5,16 +5,20 @@ use malachitebft_app::engine::util::streaming::{StreamContent, StreamId, StreamM
 use malachitebft_codec::{Codec, HasEncodedLen};
 use malachitebft_core_consensus::{LivenessMsg, ProposedValue, SignedConsensusMsg};
 use malachitebft_core_types::{
-    CommitCertificate, CommitSignature, NilOrVal, PolkaCertificate, PolkaSignature, Round,
-    RoundCertificate, RoundCertificateType, RoundSignature, SignedExtension, SignedProposal,
-    SignedVote, Validity,
+    AggregatedSignature, AmnesiaEvidence, CommitCertificate, CommitSignature,
+    DoubleProposalEvidence, DoubleVoteEvidence, Evidence, NilOrVal, PolkaCertificate,
+    PolkaSignature, Round, RoundCertificate, RoundCertificateType, RoundSignature, SignedExtension,
+    SignedProposal, SignedVote, SignerBitmap, Validity,
 };
 use malachitebft_proto::{Error as ProtoError, Protobuf};
-use malachitebft_signing_ed25519::Signature;
+use malachitebft_signing_ed25519::{PublicKey, Signature};
 use malachitebft_sync::{self as sync, PeerId};
 
 use crate::{decode_votetype, encode_votetype, proto};
-use crate::{Address, Height, Proposal, ProposalPart, TestContext, Value, ValueId, Vote};
+use crate::{
+    Address, Height, Proposal, ProposalPart, TestContext, Validator, ValidatorSet, Value, ValueId,
+    Vote,
+};
 
 #[derive(Copy, Clone, Debug)]
 pub struct ProtobufCodec;
@@ -43,6 +47,55 @@ impl Codec<ProposalPart> for ProtobufCodec {
     }
 }
 
+impl Codec<ValueId> for ProtobufCodec {
+    type Error = ProtoError;
+
+    fn decode(&self, bytes: Bytes) -> Result<ValueId, Self::Error> {
+        Protobuf::from_bytes(&bytes)
+    }
+
+    fn encode(&self, msg: &ValueId) -> Result<Bytes, Self::Error> {
+        Protobuf::to_bytes(msg)
+    }
+}
+
+impl Codec<Vote> for ProtobufCodec {
+    type Error = ProtoError;
+
+    fn decode(&self, bytes: Bytes) -> Result<Vote, Self::Error> {
+        Protobuf::from_bytes(&bytes)
+    }
+
+    fn encode(&self, msg: &Vote) -> Result<Bytes, Self::Error> {
+        Protobuf::to_bytes(msg)
+    }
+}
+
+impl Codec<Proposal> for ProtobufCodec {
+    type Error = ProtoError;
+
+    fn decode(&self, bytes: Bytes) -> Result<Proposal, Self::Error> {
+        Protobuf::from_bytes(&bytes)
+    }
+
+    fn encode(&self, msg: &Proposal) -> Result<Bytes, Self::Error> {
+        Protobuf::to_bytes(msg)
+    }
+}
+
+/// Vote extensions are opaque bytes, which are encoded as is.
+impl Codec<Bytes> for ProtobufCodec {
+    type Error = ProtoError;
+
+    fn decode(&self, bytes: Bytes) -> Result<Bytes, Self::Error> {
+        Ok(bytes)
+    }
+
+    fn encode(&self, msg: &Bytes) -> Result<Bytes, Self::Error> {
+        Ok(msg.clone())
+    }
+}
+
 impl Codec<Signature> for ProtobufCodec {
     type Error = ProtoError;
 
@@ -61,6 +114,58 @@ impl Codec<Signature> for ProtobufCodec {
     }
 }
 
+impl Codec<ValidatorSet> for ProtobufCodec {
+    type Error = ProtoError;
+
+    fn decode(&self, bytes: Bytes) -> Result<ValidatorSet, Self::Error> {
+        let proto = proto::ValidatorSet::decode(bytes.as_ref())?;
+
+        let validators = proto
+            .validators
+            .into_iter()
+            .map(|validator| {
+                let address = validator
+                    .address
+                    .ok_or_else(|| ProtoError::missing_field::<proto::Validator>("address"))?;
+
+                let public_key = <[u8; 32]>::try_from(validator.public_key.as_ref())
+                    .ok()
+                    .and_then(|bytes| ed25519_consensus::VerificationKey::try_from(bytes).ok())
+                    .ok_or_else(|| ProtoError::Other("Invalid public key".to_string()))?;
+
+                Ok(Validator {
+                    address: Address::from_proto(address)?,
+                    public_key: PublicKey::new(public_key),
+                    voting_power: validator.voting_power,
+                })
+            })
+            .collect::<Result<Vec<_>, ProtoError>>()?;
+
+        if validators.is_empty() {
+            return Err(ProtoError::Other("Empty validator set".to_string()));
+        }
+
+        Ok(ValidatorSet::new(validators))
+    }
+
+    fn encode(&self, validator_set: &ValidatorSet) -> Result<Bytes, Self::Error> {
+        let validators = validator_set
+            .iter()
+            .map(|validator| {
+                Ok(proto::Validator {
+                    address: Some(validator.address.to_proto()?),
+                    public_key: validator.public_key.as_bytes().to_vec().into(),
+                    voting_power: validator.voting_power,
+                })
+            })
+            .collect::<Result<Vec<_>, ProtoError>>()?;
+
+        Ok(Bytes::from(
+            proto::ValidatorSet { validators }.encode_to_vec(),
+        ))
+    }
+}
+
 impl Codec<SignedConsensusMsg<TestContext>> for ProtobufCodec {
     type Error = ProtoError;
 
@@ -114,6 +219,60 @@ impl Codec<SignedConsensusMsg<TestContext>> for ProtobufCodec {
     }
 }
 
+impl Codec<DoubleVoteEvidence<TestContext>> for ProtobufCodec {
+    type Error = ProtoError;
+
+    fn decode(&self, bytes: Bytes) -> Result<DoubleVoteEvidence<TestContext>, Self::Error> {
+        decode_double_vote_evidence(proto::DoubleVoteEvidence::decode(bytes.as_ref())?)
+    }
+
+    fn encode(&self, evidence: &DoubleVoteEvidence<TestContext>) -> Result<Bytes, Self::Error> {
+        Ok(Bytes::from(
+            encode_double_vote_evidence(evidence)?.encode_to_vec(),
+        ))
+    }
+}
+
+impl Codec<DoubleProposalEvidence<TestContext>> for ProtobufCodec {
+    type Error = ProtoError;
+
+    fn decode(&self, bytes: Bytes) -> Result<DoubleProposalEvidence<TestContext>, Self::Error> {
+        decode_double_proposal_evidence(proto::DoubleProposalEvidence::decode(bytes.as_ref())?)
+    }
+
+    fn encode(&self, evidence: &DoubleProposalEvidence<TestContext>) -> Result<Bytes, Self::Error> {
+        Ok(Bytes::from(
+            encode_double_proposal_evidence(evidence)?.encode_to_vec(),
+        ))
+    }
+}
+
+impl Codec<AmnesiaEvidence<TestContext>> for ProtobufCodec {
+    type Error = ProtoError;
+
+    fn decode(&self, bytes: Bytes) -> Result<AmnesiaEvidence<TestContext>, Self::Error> {
+        decode_amnesia_evidence(proto::AmnesiaEvidence::decode(bytes.as_ref())?)
+    }
+
+    fn encode(&self, evidence: &AmnesiaEvidence<TestContext>) -> Result<Bytes, Self::Error> {
+        Ok(Bytes::from(
+            encode_amnesia_evidence(evidence)?.encode_to_vec(),
+        ))
+    }
+}
+
+impl Codec<Evidence<TestContext>> for ProtobufCodec {
+    type Error = ProtoError;
+
+    fn decode(&self, bytes: Bytes) -> Result<Evidence<TestContext>, Self::Error> {
+        decode_evidence(proto::Evidence::decode(bytes.as_ref())?)
+    }
+
+    fn encode(&self, evidence: &Evidence<TestContext>) -> Result<Bytes, Self::Error> {
+        Ok(Bytes::from(encode_evidence(evidence)?.encode_to_vec()))
+    }
+}
+
 pub fn encode_round_certificate(
     certificate: &RoundCertificate<TestContext>,
 ) -> Result<proto::RoundCertificate, ProtoError> {
@@ -555,11 +714,26 @@ pub fn decode_commit_certificate(
         })
         .collect::<Result<Vec<_>, _>>()?;
 
+    let aggregated_signature = certificate
+        .aggregated_signature
+        .map(
+            |aggregated| -> Result<AggregatedSignature<TestContext>, ProtoError> {
+                let signature = aggregated.signature.ok_or_else(|| {
+                    ProtoError::missing_field::<proto::AggregatedSignature>("signature")
+                })?;
+                let signature = decode_signature(signature)?;
+                let signers = SignerBitmap::from_bytes(aggregated.signers.to_vec());
+                Ok(AggregatedSignature::new(signers, signature))
+            },
+        )
+        .transpose()?;
+
     let certificate = CommitCertificate {
         height: Height::new(certificate.height),
         round: Round::new(certificate.round),
         value_id,
         commit_signatures,
+        aggregated_signature,
     };
 
     Ok(certificate)
@@ -584,6 +758,12 @@ pub fn encode_commit_certificate(
                 })
             })
             .collect::<Result<Vec<_>, _>>()?,
+        aggregated_signature: certificate.aggregated_signature.as_ref().map(|aggregated| {
+            proto::AggregatedSignature {
+                signers: Bytes::copy_from_slice(aggregated.signers.as_bytes()),
+                signature: Some(encode_signature(&aggregated.signature)),
+            }
+        }),
     })
 }
 
@@ -631,6 +811,142 @@ pub fn decode_vote(msg: proto::SignedMessage) -> Result<SignedVote<TestContext>,
     Ok(SignedVote::new(vote, signature))
 }
 
+pub fn encode_proposal(
+    proposal: &SignedProposal<TestContext>,
+) -> Result<proto::SignedMessage, ProtoError> {
+    Ok(proto::SignedMessage {
+        message: Some(proto::signed_message::Message::Proposal(
+            proposal.message.to_proto()?,
+        )),
+        signature: Some(encode_signature(&proposal.signature)),
+    })
+}
+
+pub fn decode_proposal(
+    msg: proto::SignedMessage,
+) -> Result<SignedProposal<TestContext>, ProtoError> {
+    let signature = msg
+        .signature
+        .ok_or_else(|| ProtoError::missing_field::<proto::SignedMessage>("signature"))?;
+
+    let proposal = match msg.message {
+        Some(proto::signed_message::Message::Proposal(p)) => Ok(p),
+        _ => Err(ProtoError::Other(
+            "Invalid message type: not a proposal".to_string(),
+        )),
+    }?;
+
+    let signature = decode_signature(signature)?;
+    let proposal = Proposal::from_proto(proposal)?;
+    Ok(SignedProposal::new(proposal, signature))
+}
+
+pub fn encode_double_vote_evidence(
+    evidence: &DoubleVoteEvidence<TestContext>,
+) -> Result<proto::DoubleVoteEvidence, ProtoError> {
+    Ok(proto::DoubleVoteEvidence {
+        first: Some(encode_vote(&evidence.first)?),
+        second: Some(encode_vote(&evidence.second)?),
+    })
+}
+
+pub fn decode_double_vote_evidence(
+    evidence: proto::DoubleVoteEvidence,
+) -> Result<DoubleVoteEvidence<TestContext>, ProtoError> {
+    let first = evidence
+        .first
+        .ok_or_else(|| ProtoError::missing_field::<proto::DoubleVoteEvidence>("first"))
+        .and_then(decode_vote)?;
+
+    let second = evidence
+        .second
+        .ok_or_else(|| ProtoError::missing_field::<proto::DoubleVoteEvidence>("second"))
+        .and_then(decode_vote)?;
+
+    Ok(DoubleVoteEvidence::new(first, second))
+}
+
+pub fn encode_double_proposal_evidence(
+    evidence: &DoubleProposalEvidence<TestContext>,
+) -> Result<proto::DoubleProposalEvidence, ProtoError> {
+    Ok(proto::DoubleProposalEvidence {
+        first: Some(encode_proposal(&evidence.first)?),
+        second: Some(encode_proposal(&evidence.second)?),
+    })
+}
+
+pub fn decode_double_proposal_evidence(
+    evidence: proto::DoubleProposalEvidence,
+) -> Result<DoubleProposalEvidence<TestContext>, ProtoError> {
+    let first = evidence
+        .first
+        .ok_or_else(|| ProtoError::missing_field::<proto::DoubleProposalEvidence>("first"))
+        .and_then(decode_proposal)?;
+
+    let second = evidence
+        .second
+        .ok_or_else(|| ProtoError::missing_field::<proto::DoubleProposalEvidence>("second"))
+        .and_then(decode_proposal)?;
+
+    Ok(DoubleProposalEvidence::new(first, second))
+}
+
+pub fn encode_amnesia_evidence(
+    evidence: &AmnesiaEvidence<TestContext>,
+) -> Result<proto::AmnesiaEvidence, ProtoError> {
+    Ok(proto::AmnesiaEvidence {
+        precommit: Some(encode_vote(&evidence.precommit)?),
+        prevote: Some(encode_vote(&evidence.prevote)?),
+    })
+}
+
+pub fn decode_amnesia_evidence(
+    evidence: proto::AmnesiaEvidence,
+) -> Result<AmnesiaEvidence<TestContext>, ProtoError> {
+    let precommit = evidence
+        .precommit
+        .ok_or_else(|| ProtoError::missing_field::<proto::AmnesiaEvidence>("precommit"))
+        .and_then(decode_vote)?;
+
+    let prevote = evidence
+        .prevote
+        .ok_or_else(|| ProtoError::missing_field::<proto::AmnesiaEvidence>("prevote"))
+        .and_then(decode_vote)?;
+
+    Ok(AmnesiaEvidence::new(precommit, prevote))
+}
+
+pub fn encode_evidence(evidence: &Evidence<TestContext>) -> Result<proto::Evidence, ProtoError> {
+    use proto::evidence::Evidence as Kind;
+
+    let evidence = match evidence {
+        Evidence::DoubleVote(evidence) => Kind::DoubleVote(encode_double_vote_evidence(evidence)?),
+        Evidence::DoubleProposal(evidence) => {
+            Kind::DoubleProposal(encode_double_proposal_evidence(evidence)?)
+        }
+        Evidence::Amnesia(evidence) => Kind::Amnesia(encode_amnesia_evidence(evidence)?),
+    };
+
+    Ok(proto::Evidence {
+        evidence: Some(evidence),
+    })
+}
+
+pub fn decode_evidence(evidence: proto::Evidence) -> Result<Evidence<TestContext>, ProtoError> {
+    use proto::evidence::Evidence as Kind;
+
+    match evidence.evidence {
+        Some(Kind::DoubleVote(evidence)) => {
+            decode_double_vote_evidence(evidence).map(Evidence::DoubleVote)
+        }
+        Some(Kind::DoubleProposal(evidence)) => {
+            decode_double_proposal_evidence(evidence).map(Evidence::DoubleProposal)
+        }
+        Some(Kind::Amnesia(evidence)) => decode_amnesia_evidence(evidence).map(Evidence::Amnesia),
+        None => Err(ProtoError::missing_field::<proto::Evidence>("evidence")),
+    }
+}
+
 pub fn encode_signature(signature: &Signature) -> proto::Signature {
     proto::Signature {
         bytes: Bytes::copy_from_slice(signature.to_bytes().as_ref()),
@@ -695,4 +1011,150 @@ mod tests {
             original_sig.signature.to_bytes()
         );
     }
+
+    #[test]
+    fn test_aggregated_commit_certificate_encode_decode() {
+        let signers = SignerBitmap::from_iter([0, 2, 9]);
+        let signature = Signature::from_bytes([2; 64]);
+
+        let certificate = CommitCertificate::<TestContext>::new_aggregated(
+            Height::new(1),
+            Round::new(2),
+            ValueId::new(42),
+            AggregatedSignature::new(signers, signature),
+        );
+
+        let encoded = encode_commit_certificate(&certificate).unwrap();
+        let decoded = decode_commit_certificate(encoded).unwrap();
+
+        assert_eq!(decoded, certificate);
+        assert_eq!(decoded.signature_count(), 3);
+    }
+
+    #[test]
+    fn test_double_vote_evidence_encode_decode() {
+        let address = Address::new([1; 20]);
+
+        let first = SignedVote::new(
+            Vote::new_prevote(Height::new(1), Round::new(0), NilOrVal::Nil, address),
+            Signature::from_bytes([2; 64]),
+        );
+        let second = SignedVote::new(
+            Vote::new_prevote(
+                Height::new(1),
+                Round::new(0),
+                NilOrVal::Val(ValueId::new(42)),
+                address,
+            ),
+            Signature::from_bytes([3; 64]),
+        );
+
+        let evidence = DoubleVoteEvidence::new(first, second);
+
+        let bytes = ProtobufCodec.encode(&evidence).unwrap();
+        let decoded: DoubleVoteEvidence<TestContext> = ProtobufCodec.decode(bytes).unwrap();
+
+        assert_eq!(decoded, evidence);
+    }
+
+    #[test]
+    fn test_double_proposal_evidence_encode_decode() {
+        let address = Address::new([1; 20]);
+
+        let first = SignedProposal::new(
+            Proposal::new(
+                Height::new(1),
+                Round::new(0),
+                Value::new(1),
+                Round::Nil,
+                address,
+            ),
+            Signature::from_bytes([2; 64]),
+        );
+        let second = SignedProposal::new(
+            Proposal::new(
+                Height::new(1),
+                Round::new(0),
+                Value::new(2),
+                Round::Nil,
+                address,
+            ),
+            Signature::from_bytes([3; 64]),
+        );
+
+        let evidence = DoubleProposalEvidence::new(first, second);
+
+        let bytes = ProtobufCodec.encode(&evidence).unwrap();
+        let decoded: DoubleProposalEvidence<TestContext> = ProtobufCodec.decode(bytes).unwrap();
+
+        assert_eq!(decoded, evidence);
+    }
+
+    #[test]
+    fn test_amnesia_evidence_encode_decode() {
+        let address = Address::new([1; 20]);
+
+        let precommit = SignedVote::new(
+            Vote::new_precommit(
+                Height::new(1),
+                Round::new(0),
+                NilOrVal::Val(ValueId::new(1)),
+                address,
+            ),
+            Signature::from_bytes([2; 64]),
+        );
+        let prevote = SignedVote::new(
+            Vote::new_prevote(
+                Height::new(1),
+                Round::new(1),
+                NilOrVal::Val(ValueId::new(2)),
+                address,
+            ),
+            Signature::from_bytes([3; 64]),
+        );
+
+        let evidence = AmnesiaEvidence::new(precommit, prevote);
+
+        let bytes = ProtobufCodec.encode(&evidence).unwrap();
+        let decoded: AmnesiaEvidence<TestContext> = ProtobufCodec.decode(bytes).unwrap();
+
+        assert_eq!(decoded, evidence);
+    }
+
+    #[test]
+    fn test_evidence_encode_decode() {
+        let address = Address::new([1; 20]);
+
+        let vote = |vote: Vote, sig: u8| SignedVote::new(vote, Signature::from_bytes([sig; 64]));
+        let value = |id: u64| NilOrVal::Val(ValueId::new(id));
+
+        let double_vote = DoubleVoteEvidence::new(
+            vote(
+                Vote::new_precommit(Height::new(1), Round::new(0), value(1), address),
+                2,
+            ),
+            vote(
+                Vote::new_precommit(Height::new(1), Round::new(0), value(2), address),
+                3,
+            ),
+        );
+
+        let amnesia = AmnesiaEvidence::new(
+            vote(
+                Vote::new_precommit(Height::new(1), Round::new(0), value(1), address),
+                2,
+            ),
+            vote(
+                Vote::new_prevote(Height::new(1), Round::new(1), value(2), address),
+                4,
+            ),
+        );
+
+        for evidence in [Evidence::from(double_vote), Evidence::from(amnesia)] {
+            let bytes = ProtobufCodec.encode(&evidence).unwrap();
+            let decoded: Evidence<TestContext> = ProtobufCodec.decode(bytes).unwrap();
+
+            assert_eq!(decoded, evidence);
+        }
+    }
 }
@@ -10,6 +10,7 @@ use crate::middleware;
 use crate::middleware::Middleware;
 use crate::proposal::*;
 use crate::proposal_part::*;
+use crate::proposer_selector::{ProposerSelector, RotateProposer};
 use crate::signing::*;
 use crate::validator_set::*;
 use crate::value::*;
@@ -18,6 +19,7 @@ use crate::vote::*;
 #[derive(Clone, Debug)]
 pub struct TestContext {
     middleware: Arc<dyn Middleware>,
+    proposer_selector: Arc<dyn ProposerSelector<TestContext>>,
 }
 
 impl Default for TestContext {
@@ -32,7 +34,19 @@ impl TestContext {
     }
 
     pub fn with_middleware(middleware: Arc<dyn Middleware>) -> Self {
-        Self { middleware }
+        Self {
+            middleware,
+            proposer_selector: Arc::new(RotateProposer),
+        }
+    }
+
+    /// Select proposers with the given selector instead of rotating through the validators.
+    pub fn with_proposer_selector(
+        mut self,
+        proposer_selector: Arc<dyn ProposerSelector<TestContext>>,
+    ) -> Self {
+        self.proposer_selector = proposer_selector;
+        self
     }
 
     pub fn middleware(&self) -> &Arc<dyn Middleware> {
@@ -46,18 +60,14 @@ impl TestContext {
         round: Round,
     ) -> &'a Validator {
         assert!(validator_set.count() > 0);
-        assert!(round != Round::Nil && round.as_i64() >= 0);
-
-        let proposer_index = {
-            let height = height.as_u64() as usize;
-            let round = round.as_i64() as usize;
 
-            (height - 1 + round) % validator_set.count()
-        };
+        let address = self
+            .proposer_selector
+            .select_proposer(height, round, validator_set);
 
         validator_set
-            .get_by_index(proposer_index)
-            .expect("proposer_index is valid")
+            .get_by_address(&address)
+            .expect("proposer is in the validator set")
     }
 }
 
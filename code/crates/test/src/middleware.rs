@@ -1,7 +1,7 @@
 use core::fmt;
 
 use malachitebft_core_consensus::{LocallyProposedValue, ProposedValue};
-use malachitebft_core_types::{CommitCertificate, NilOrVal, Round};
+use malachitebft_core_types::{CommitCertificate, NilOrVal, Round, ValidatorSetUpdate};
 
 use crate::decided_value::DecidedValue;
 use crate::{Address, Genesis, Height, Proposal, TestContext, ValidatorSet, Value, ValueId, Vote};
@@ -21,6 +21,18 @@ pub trait Middleware: fmt::Debug + Send + Sync {
         Some(genesis.validator_set.clone())
     }
 
+    /// The update to the validator set decided at the given height, if the application
+    /// schedules validator set updates with `Next::StartWithUpdate` rather than providing
+    /// the validator set of the next height with `Next::Start`.
+    fn get_validator_set_update(
+        &self,
+        _ctx: &TestContext,
+        _decided_height: Height,
+        _genesis: &Genesis,
+    ) -> Option<ValidatorSetUpdate<TestContext>> {
+        None
+    }
+
     fn new_proposal(
         &self,
         _ctx: &TestContext,
@@ -172,3 +184,54 @@ impl Middleware for RotateEpochValidators {
         Some(select_validators(genesis, height, self.selection_size))
     }
 }
+
+/// Removes the validator at the given index of the genesis validator set,
+/// with an update decided at `decided_height` and taking effect `delay` heights later.
+#[derive(Copy, Clone, Debug)]
+pub struct RemoveValidator {
+    pub index: usize,
+    pub decided_height: u64,
+    pub delay: u64,
+}
+
+impl RemoveValidator {
+    fn update(&self, genesis: &Genesis) -> ValidatorSetUpdate<TestContext> {
+        let validator = genesis
+            .validator_set
+            .get_by_index(self.index)
+            .expect("validator index should be within the genesis validator set");
+
+        ValidatorSetUpdate::new().remove(validator.address)
+    }
+}
+
+impl Middleware for RemoveValidator {
+    fn get_validator_set(
+        &self,
+        _ctx: &TestContext,
+        _current_height: Height,
+        height: Height,
+        genesis: &Genesis,
+    ) -> Option<ValidatorSet> {
+        if height.as_u64() < self.decided_height + self.delay {
+            return Some(genesis.validator_set.clone());
+        }
+
+        self.update(genesis).apply(&genesis.validator_set).ok()
+    }
+
+    // Every height is started with an update, most of them empty,
+    // so that consensus takes care of applying the scheduled update
+    fn get_validator_set_update(
+        &self,
+        _ctx: &TestContext,
+        decided_height: Height,
+        genesis: &Genesis,
+    ) -> Option<ValidatorSetUpdate<TestContext>> {
+        if decided_height.as_u64() == self.decided_height {
+            Some(self.update(genesis))
+        } else {
+            Some(ValidatorSetUpdate::new())
+        }
+    }
+}
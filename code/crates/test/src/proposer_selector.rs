@@ -1,11 +1,15 @@
-use malachitebft_core_types::{Context, Round};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use malachitebft_config::ProposerSelection;
+use malachitebft_core_types::{Context, ProposerPriorities, Round};
 
 use crate::{Address, Height, TestContext, ValidatorSet};
 
 /// Defines how to select a proposer amongst a validator set for a given round.
 pub trait ProposerSelector<Ctx>
 where
-    Self: Send + Sync,
+    Self: fmt::Debug + Send + Sync,
     Ctx: Context,
 {
     /// Select a proposer from the given validator set for the given round.
@@ -65,3 +69,70 @@ impl ProposerSelector<TestContext> for FixedProposer {
         self.proposer
     }
 }
+
+/// Selects proposers in proportion to their voting power with [`ProposerPriorities`],
+/// as in Tendermint and CometBFT.
+///
+/// The priorities are carried over from one height to the next. When asked for a proposer at
+/// any other height, eg. after a restart, they are computed again from the initial height with
+/// the given validator set, so that nodes only agree on the proposers if it never changes.
+#[derive(Debug, Default)]
+pub struct WeightedProposer {
+    state: Mutex<Option<(Height, ProposerPriorities<TestContext>)>>,
+}
+
+impl WeightedProposer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn priorities_at(
+        height: Height,
+        validator_set: &ValidatorSet,
+    ) -> ProposerPriorities<TestContext> {
+        let mut priorities = ProposerPriorities::new(validator_set);
+
+        for _ in 1..height.as_u64() {
+            priorities.next_height(validator_set);
+        }
+
+        priorities
+    }
+}
+
+impl ProposerSelector<TestContext> for WeightedProposer {
+    fn select_proposer(
+        &self,
+        height: Height,
+        round: Round,
+        validator_set: &ValidatorSet,
+    ) -> Address {
+        assert!(round != Round::Nil && round.as_i64() >= 0);
+
+        let mut state = self.state.lock().expect("lock is not poisoned");
+
+        let mut priorities = match state.take() {
+            Some((current, priorities)) if current == height => priorities,
+            Some((current, mut priorities)) if current.increment() == height => {
+                priorities.next_height(validator_set);
+                priorities
+            }
+            _ => Self::priorities_at(height, validator_set),
+        };
+
+        let proposer = priorities
+            .proposer_at(round)
+            .expect("validator set is not empty");
+
+        *state = Some((height, priorities));
+        proposer
+    }
+}
+
+/// The proposer selector for the given proposer selection strategy of the test configuration.
+pub fn from_config(selection: ProposerSelection) -> Arc<dyn ProposerSelector<TestContext>> {
+    match selection {
+        ProposerSelection::RoundRobin => Arc::new(RotateProposer),
+        ProposerSelection::Weighted => Arc::new(WeightedProposer::new()),
+    }
+}
@@ -1,10 +1,24 @@
+use std::path::Path;
+
 use async_trait::async_trait;
 use bytes::Bytes;
+use rand::RngCore;
+use tracing::info;
 
-use malachitebft_core_types::{SignedExtension, SignedProposal, SignedProposalPart, SignedVote};
-use malachitebft_signing::{Error, SigningProvider, VerificationResult};
+use malachitebft_app::types::Keypair;
+use malachitebft_config::SignerConfig;
+use malachitebft_core_types::{
+    Round, SignedExtension, SignedProposal, SignedProposalPart, SignedVote,
+};
+use malachitebft_remote_signer::{
+    Address as SignerAddress, IdentityKey, IdentityPublicKey, RemoteSigningProvider,
+};
+use malachitebft_signing::{
+    peer_identity_sign_bytes, Error, SignBytes, SigningProvider, VerificationResult, COMMIT_DOMAIN,
+};
 
-use crate::{Proposal, ProposalPart, TestContext, Vote};
+use crate::codec::proto::ProtobufCodec;
+use crate::{Height, Proposal, ProposalPart, TestContext, ValueId, Vote};
 
 pub use malachitebft_signing_ed25519::*;
 
@@ -24,6 +38,104 @@ impl Hashable for PublicKey {
     }
 }
 
+/// Create the signing provider of a node with the given signer configuration,
+/// which either signs with the private key returned by `load_private_key`, or asks a remote signer to sign.
+///
+/// With a remote signer, the private key is never loaded and signatures are verified
+/// against the public keys of the validators only.
+pub fn make_signing_provider(
+    config: &SignerConfig,
+    load_private_key: impl FnOnce() -> eyre::Result<PrivateKey>,
+) -> eyre::Result<Box<dyn SigningProvider<TestContext>>> {
+    let SignerConfig::Remote(config) = config else {
+        return Ok(Box::new(Ed25519Provider::new(load_private_key()?)));
+    };
+
+    let address = config.address.parse::<SignerAddress>()?;
+    let signer_public_key = config.signer_public_key.parse::<IdentityPublicKey>()?;
+    let identity = IdentityKey::load_or_generate(&config.identity_key_file)?;
+
+    info!(
+        %address,
+        %signer_public_key,
+        identity_public_key = %identity.public_key(),
+        "Using remote signer"
+    );
+
+    let provider = RemoteSigningProvider::new(
+        address,
+        identity,
+        signer_public_key,
+        ProtobufCodec,
+        Ed25519Verifier,
+    )
+    .with_timeout(config.timeout);
+
+    Ok(Box::new(provider))
+}
+
+/// The public key of the validator of a node with the given signer configuration,
+/// which is either derived from the private key returned by `load_private_key`,
+/// or read from the configuration of the remote signer.
+pub fn load_public_key(
+    config: &SignerConfig,
+    load_private_key: impl FnOnce() -> eyre::Result<PrivateKey>,
+) -> eyre::Result<PublicKey> {
+    let SignerConfig::Remote(config) = config else {
+        return Ok(load_private_key()?.public_key());
+    };
+
+    let mut bytes = [0; 32];
+    hex::decode_to_slice(config.validator_public_key.trim(), &mut bytes)
+        .map_err(|e| eyre::eyre!("Invalid validator public key: {e}"))?;
+
+    let key = ed25519_consensus::VerificationKey::try_from(bytes)
+        .map_err(|e| eyre::eyre!("Invalid validator public key: {e}"))?;
+
+    Ok(PublicKey::new(key))
+}
+
+/// The keypair identifying a node with the given signer configuration on the network,
+/// which is either the private key returned by `load_private_key`, or, with a remote signer,
+/// the key held in the configured network key file, which is generated if it does not exist.
+pub fn load_network_keypair(
+    config: &SignerConfig,
+    load_private_key: impl FnOnce() -> eyre::Result<PrivateKey>,
+) -> eyre::Result<Keypair> {
+    let SignerConfig::Remote(config) = config else {
+        let private_key = load_private_key()?;
+        return Ok(Keypair::ed25519_from_bytes(private_key.inner().to_bytes())?);
+    };
+
+    let path = &config.network_key_file;
+
+    let mut secret = [0; 32];
+    match std::fs::read_to_string(path) {
+        Ok(contents) => hex::decode_to_slice(contents.trim(), &mut secret)
+            .map_err(|e| eyre::eyre!("Invalid network key file {}: {e}", path.display()))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            rand::thread_rng().fill_bytes(&mut secret);
+            write_secret_file(path, hex::encode(secret).as_bytes())?;
+        }
+        Err(e) => return Err(e.into()),
+    }
+
+    Ok(Keypair::ed25519_from_bytes(secret)?)
+}
+
+/// Create a file readable only by its owner, holding the given secret.
+fn write_secret_file(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    options.open(path)?.write_all(contents)
+}
+
 #[derive(Debug)]
 pub struct Ed25519Provider {
     private_key: PrivateKey,
@@ -43,7 +155,7 @@ impl Ed25519Provider {
     }
 
     pub fn verify(&self, data: &[u8], signature: &Signature, public_key: &PublicKey) -> bool {
-        public_key.verify(data, signature).is_ok()
+        Ed25519Verifier::verify(data, signature, public_key)
     }
 }
 
@@ -60,9 +172,16 @@ impl SigningProvider<TestContext> for Ed25519Provider {
         signature: &Signature,
         public_key: &PublicKey,
     ) -> Result<VerificationResult, Error> {
-        Ok(VerificationResult::from_bool(
-            public_key.verify(&vote.to_sign_bytes(), signature).is_ok(),
-        ))
+        Ed25519Verifier
+            .verify_signed_vote(vote, signature, public_key)
+            .await
+    }
+
+    async fn verify_signed_votes(
+        &self,
+        votes: &[(Vote, &Signature, &PublicKey)],
+    ) -> Result<Vec<VerificationResult>, Error> {
+        Ed25519Verifier.verify_signed_votes(votes).await
     }
 
     async fn sign_proposal(
@@ -73,6 +192,140 @@ impl SigningProvider<TestContext> for Ed25519Provider {
         Ok(SignedProposal::new(proposal, signature))
     }
 
+    async fn verify_signed_proposal(
+        &self,
+        proposal: &Proposal,
+        signature: &Signature,
+        public_key: &PublicKey,
+    ) -> Result<VerificationResult, Error> {
+        Ed25519Verifier
+            .verify_signed_proposal(proposal, signature, public_key)
+            .await
+    }
+
+    async fn sign_proposal_part(
+        &self,
+        proposal_part: ProposalPart,
+    ) -> Result<SignedProposalPart<TestContext>, Error> {
+        let signature = self.private_key.sign(&proposal_part.to_sign_bytes());
+        Ok(SignedProposalPart::new(proposal_part, signature))
+    }
+
+    async fn verify_signed_proposal_part(
+        &self,
+        proposal_part: &ProposalPart,
+        signature: &Signature,
+        public_key: &PublicKey,
+    ) -> Result<VerificationResult, Error> {
+        Ed25519Verifier
+            .verify_signed_proposal_part(proposal_part, signature, public_key)
+            .await
+    }
+
+    async fn sign_vote_extension(
+        &self,
+        extension: Bytes,
+    ) -> Result<SignedExtension<TestContext>, Error> {
+        let signature = self.private_key.sign(extension.as_ref());
+        Ok(malachitebft_core_types::SignedMessage::new(
+            extension, signature,
+        ))
+    }
+
+    async fn verify_signed_vote_extension(
+        &self,
+        extension: &Bytes,
+        signature: &Signature,
+        public_key: &PublicKey,
+    ) -> Result<VerificationResult, Error> {
+        Ed25519Verifier
+            .verify_signed_vote_extension(extension, signature, public_key)
+            .await
+    }
+
+    async fn sign_peer_identity(&self, peer_id: &[u8]) -> Result<Signature, Error> {
+        Ok(self.sign(&peer_identity_sign_bytes(peer_id)))
+    }
+
+    async fn verify_peer_identity(
+        &self,
+        peer_id: &[u8],
+        signature: &Signature,
+        public_key: &PublicKey,
+    ) -> Result<VerificationResult, Error> {
+        Ed25519Verifier
+            .verify_peer_identity(peer_id, signature, public_key)
+            .await
+    }
+}
+
+/// A signing provider for nodes which do not hold the private key of their validator,
+/// eg. because they sign with a remote signer, and which can therefore only verify signatures
+/// against the public keys of the validators.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Ed25519Verifier;
+
+impl Ed25519Verifier {
+    pub fn verify(data: &[u8], signature: &Signature, public_key: &PublicKey) -> bool {
+        public_key.verify(data, signature).is_ok()
+    }
+}
+
+#[async_trait]
+impl SigningProvider<TestContext> for Ed25519Verifier {
+    async fn sign_vote(&self, _vote: Vote) -> Result<SignedVote<TestContext>, Error> {
+        Err(private_key_unavailable())
+    }
+
+    async fn verify_signed_vote(
+        &self,
+        vote: &Vote,
+        signature: &Signature,
+        public_key: &PublicKey,
+    ) -> Result<VerificationResult, Error> {
+        Ok(VerificationResult::from_bool(
+            public_key.verify(&vote.to_sign_bytes(), signature).is_ok(),
+        ))
+    }
+
+    async fn verify_signed_votes(
+        &self,
+        votes: &[(Vote, &Signature, &PublicKey)],
+    ) -> Result<Vec<VerificationResult>, Error> {
+        let sign_bytes = votes
+            .iter()
+            .map(|(vote, _, _)| vote.to_sign_bytes())
+            .collect::<Vec<_>>();
+
+        let mut batch = BatchVerifier::new();
+        for ((_, signature, public_key), bytes) in votes.iter().zip(&sign_bytes) {
+            batch.queue(bytes, public_key, signature);
+        }
+
+        // If the whole batch is valid, then so is each of its signatures
+        if batch.verify(rand::thread_rng()).is_ok() {
+            return Ok(votes.iter().map(|_| VerificationResult::Valid).collect());
+        }
+
+        // Otherwise, verify the signatures one by one to find out which of them are invalid
+        let results = votes
+            .iter()
+            .zip(&sign_bytes)
+            .map(|((_, signature, public_key), bytes)| {
+                VerificationResult::from_bool(Ed25519Verifier::verify(bytes, signature, public_key))
+            })
+            .collect();
+
+        Ok(results)
+    }
+
+    async fn sign_proposal(
+        &self,
+        _proposal: Proposal,
+    ) -> Result<SignedProposal<TestContext>, Error> {
+        Err(private_key_unavailable())
+    }
+
     async fn verify_signed_proposal(
         &self,
         proposal: &Proposal,
@@ -88,10 +341,9 @@ impl SigningProvider<TestContext> for Ed25519Provider {
 
     async fn sign_proposal_part(
         &self,
-        proposal_part: ProposalPart,
+        _proposal_part: ProposalPart,
     ) -> Result<SignedProposalPart<TestContext>, Error> {
-        let signature = self.private_key.sign(&proposal_part.to_sign_bytes());
-        Ok(SignedProposalPart::new(proposal_part, signature))
+        Err(private_key_unavailable())
     }
 
     async fn verify_signed_proposal_part(
@@ -109,12 +361,9 @@ impl SigningProvider<TestContext> for Ed25519Provider {
 
     async fn sign_vote_extension(
         &self,
-        extension: Bytes,
+        _extension: Bytes,
     ) -> Result<SignedExtension<TestContext>, Error> {
-        let signature = self.private_key.sign(extension.as_ref());
-        Ok(malachitebft_core_types::SignedMessage::new(
-            extension, signature,
-        ))
+        Err(private_key_unavailable())
     }
 
     async fn verify_signed_vote_extension(
@@ -127,4 +376,57 @@ impl SigningProvider<TestContext> for Ed25519Provider {
             public_key.verify(extension.as_ref(), signature).is_ok(),
         ))
     }
+
+    async fn sign_peer_identity(&self, _peer_id: &[u8]) -> Result<Signature, Error> {
+        Err(private_key_unavailable())
+    }
+
+    async fn verify_peer_identity(
+        &self,
+        peer_id: &[u8],
+        signature: &Signature,
+        public_key: &PublicKey,
+    ) -> Result<VerificationResult, Error> {
+        Ok(VerificationResult::from_bool(Ed25519Verifier::verify(
+            &peer_identity_sign_bytes(peer_id),
+            signature,
+            public_key,
+        )))
+    }
+}
+
+fn private_key_unavailable() -> Error {
+    Error::from_source("the private key of the validator is not available")
+}
+
+/// The bytes signed for each kind of message of the test context,
+/// for signing them without access to the private key, eg. with a threshold of cosigners.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct TestSignBytes;
+
+impl SignBytes<TestContext> for TestSignBytes {
+    fn vote(&self, vote: &Vote) -> Bytes {
+        vote.to_sign_bytes()
+    }
+
+    fn proposal(&self, proposal: &Proposal) -> Bytes {
+        proposal.to_sign_bytes()
+    }
+
+    fn proposal_part(&self, proposal_part: &ProposalPart) -> Bytes {
+        proposal_part.to_sign_bytes()
+    }
+
+    fn extension(&self, extension: &Bytes) -> Bytes {
+        extension.clone()
+    }
+
+    fn commit(&self, height: &Height, round: Round, value_id: &ValueId) -> Bytes {
+        let mut bytes = Vec::with_capacity(COMMIT_DOMAIN.len() + 24);
+        bytes.extend_from_slice(COMMIT_DOMAIN);
+        bytes.extend_from_slice(&height.as_u64().to_be_bytes());
+        bytes.extend_from_slice(&round.as_i64().to_be_bytes());
+        bytes.extend_from_slice(&value_id.as_u64().to_be_bytes());
+        Bytes::from(bytes)
+    }
 }
@@ -135,6 +135,16 @@ impl malachitebft_core_types::ValidatorSet<TestContext> for ValidatorSet {
     fn get_by_index(&self, index: usize) -> Option<&Validator> {
         self.validators.get(index)
     }
+
+    fn from_validators(mut validators: Vec<Validator>) -> Self {
+        validators.sort_by(|a, b| {
+            b.voting_power
+                .cmp(&a.voting_power)
+                .then_with(|| a.address.cmp(&b.address))
+        });
+
+        Self::new(validators)
+    }
 }
 
 #[cfg(test)]
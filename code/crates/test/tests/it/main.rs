@@ -5,13 +5,16 @@ mod n3f0;
 mod n3f0_consensus_mode;
 mod n3f0_pubsub_protocol;
 mod n3f1;
+mod remote_signer;
 mod reset;
+mod validator_mesh;
 mod validator_set;
 mod value_sync;
 mod vote_rebroadcast;
 mod wal;
 
 use std::collections::HashMap;
+use std::num::NonZeroU64;
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::Arc;
@@ -150,6 +153,11 @@ impl TestRunner {
             logging: LoggingConfig::default(),
             consensus: ConsensusConfig {
                 enabled: true,
+                accountability: false,
+                max_evidence_age: 100,
+                validator_set_update_delay: NonZeroU64::MIN,
+                aggregate_commit_certificates: false,
+                threshold_params: Default::default(),
                 // Current test app does not support proposal-only value payload properly as Init does not include valid_round
                 value_payload: ValuePayload::ProposalAndParts,
                 queue_capacity: 100, // Deprecated, derived from `sync.parallel_requests`
@@ -187,6 +195,7 @@ impl TestRunner {
                     .unwrap(),
             },
             runtime: RuntimeConfig::single_threaded(),
+            signer: SignerConfig::default(),
             test: TestConfig::default(),
         }
     }
@@ -202,10 +211,13 @@ fn transport_from_env(default: TransportProtocol) -> TransportProtocol {
     }
 }
 
+/// Seed of the RNG from which the private keys of the nodes are generated, in order
+const PRIVATE_KEYS_SEED: u64 = 0x42;
+
 fn make_validators<S>(
     nodes: &[TestNode<TestContext, S>],
 ) -> (Vec<Validator>, HashMap<NodeId, PrivateKey>) {
-    let mut rng = StdRng::seed_from_u64(0x42);
+    let mut rng = StdRng::seed_from_u64(PRIVATE_KEYS_SEED);
 
     let mut validators = Vec::new();
     let mut private_keys = HashMap::new();
@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use informalsystems_malachitebft_test::codec::proto::ProtobufCodec;
+use informalsystems_malachitebft_test::Ed25519Provider;
+use malachitebft_config::{RemoteSignerConfig, SignerConfig};
+use malachitebft_remote_signer::{IdentityKey, Listener, SignerServer};
+use malachitebft_signing_ed25519::PrivateKey;
+
+use crate::{TestBuilder, PRIVATE_KEYS_SEED};
+
+#[tokio::test]
+async fn validator_signs_with_remote_signer() {
+    const HEIGHT: u64 = 5;
+
+    let dir = tempfile::tempdir().unwrap();
+
+    // The private key of the first node
+    let private_key = PrivateKey::generate(StdRng::seed_from_u64(PRIVATE_KEYS_SEED));
+
+    let signer_identity = IdentityKey::generate();
+    let node_identity_file = dir.path().join("node_identity_key");
+    let node_identity = IdentityKey::load_or_generate(&node_identity_file).unwrap();
+
+    let listener = Listener::bind(&"tcp://127.0.0.1:0".parse().unwrap())
+        .await
+        .unwrap();
+
+    let signer = SignerConfig::Remote(RemoteSignerConfig {
+        address: listener.local_address().unwrap().to_string(),
+        signer_public_key: signer_identity.public_key().to_string(),
+        identity_key_file: node_identity_file,
+        validator_public_key: hex::encode(private_key.public_key().as_bytes()),
+        network_key_file: dir.path().join("network_key"),
+        timeout: Duration::from_secs(1),
+    });
+
+    let server = SignerServer::new(
+        signer_identity,
+        vec![node_identity.public_key()],
+        ProtobufCodec,
+        Ed25519Provider::new(private_key),
+    );
+
+    tokio::spawn(server.serve(listener));
+
+    let mut test = TestBuilder::<()>::new();
+
+    // Every validator is needed to reach a quorum, including the one using the remote signer
+    test.add_node()
+        .add_config_modifier(move |config| config.signer = signer.clone())
+        .start()
+        .wait_until(HEIGHT)
+        .success();
+
+    for _ in 0..2 {
+        test.add_node().start().wait_until(HEIGHT).success();
+    }
+
+    test.build().run(Duration::from_secs(30)).await
+}
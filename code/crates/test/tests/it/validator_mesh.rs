@@ -0,0 +1,38 @@
+use std::time::Duration;
+
+use crate::{TestBuilder, TestParams};
+
+#[tokio::test]
+pub async fn validators_only_mesh_with_full_node() {
+    const HEIGHT: u64 = 5;
+
+    let mut test = TestBuilder::<()>::new();
+
+    // Validators only gossip votes and proposals among themselves
+    for voting_power in [10, 20, 30] {
+        test.add_node()
+            .with_voting_power(voting_power)
+            .add_config_modifier(|config| config.consensus.p2p.validator_mesh.enabled = true)
+            .start()
+            .wait_until(HEIGHT)
+            .success();
+    }
+
+    // The full node may receive votes and proposals, but whatever it relays is ignored by validators
+    test.add_node()
+        .full_node()
+        .add_config_modifier(|config| config.consensus.p2p.validator_mesh.enabled = true)
+        .start()
+        .wait_until(HEIGHT)
+        .success();
+
+    test.build()
+        .run_with_params(
+            Duration::from_secs(60),
+            TestParams {
+                enable_value_sync: true,
+                ..Default::default()
+            },
+        )
+        .await
+}
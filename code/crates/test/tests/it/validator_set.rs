@@ -1,8 +1,13 @@
+use std::num::NonZeroU64;
 use std::time::Duration;
 
-use informalsystems_malachitebft_test::middleware::RotateValidators;
+use eyre::bail;
 
-use crate::TestBuilder;
+use informalsystems_malachitebft_test::middleware::{RemoveValidator, RotateValidators};
+use malachitebft_core_consensus::Role;
+use malachitebft_engine::util::events::Event;
+
+use crate::{Config, HandlerResult, TestBuilder, TestParams};
 
 #[tokio::test]
 async fn rotate_validator_set() {
@@ -24,3 +29,64 @@ async fn rotate_validator_set() {
 
     test.build().run(Duration::from_secs(50)).await
 }
+
+#[tokio::test]
+async fn validator_set_update_survives_restart() {
+    const HEIGHT: u64 = 8;
+    const DECIDED_HEIGHT: u64 = 2;
+    const DELAY: u64 = 3;
+    const EFFECTIVE_HEIGHT: u64 = DECIDED_HEIGHT + DELAY;
+
+    // Remove the last node from the validator set
+    let middleware = RemoveValidator {
+        index: 3,
+        decided_height: DECIDED_HEIGHT,
+        delay: DELAY,
+    };
+
+    let set_delay = |config: &mut Config| {
+        config.consensus.validator_set_update_delay = NonZeroU64::new(DELAY).unwrap()
+    };
+
+    let mut test = TestBuilder::<()>::new();
+
+    for _ in 0..3 {
+        test.add_node()
+            .with_middleware(middleware)
+            .add_config_modifier(set_delay)
+            .start()
+            .wait_until(HEIGHT)
+            .success();
+    }
+
+    test.add_node()
+        .with_middleware(middleware)
+        .add_config_modifier(set_delay)
+        .start()
+        // Crash after the update was decided, but before it takes effect
+        .wait_until(DECIDED_HEIGHT + 1)
+        .crash()
+        .restart_after(Duration::from_secs(5))
+        // Check that the update is restored from the WAL and takes effect after the restart
+        .on_event(|event, _| match event {
+            Event::StartedRound(height, _, _, role) if height.as_u64() >= EFFECTIVE_HEIGHT => {
+                if role != Role::None {
+                    bail!("Removed validator has role {role:?} at height {height}");
+                }
+
+                Ok(HandlerResult::ContinueTest)
+            }
+            _ => Ok(HandlerResult::WaitForNextEvent),
+        })
+        .success();
+
+    test.build()
+        .run_with_params(
+            Duration::from_secs(60),
+            TestParams {
+                enable_value_sync: true,
+                ..TestParams::default()
+            },
+        )
+        .await
+}
@@ -0,0 +1,776 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::executor::block_on;
+
+use informalsystems_malachitebft_test::ValueId;
+use malachitebft_core_types::{
+    AggregatedSignature, CommitCertificate, CommitSignature, SignedMessage, SignedVote,
+    SignerBitmap,
+};
+use malachitebft_signing::{
+    Error as SigningError, SignBytes, SigningProvider, SigningProviderExt, VerificationResult,
+};
+use malachitebft_signing_bls::{BlsSigningProvider, PrivateKey, PublicKey, Signature};
+
+use self::bls::{
+    BlsContext, BlsProposal, BlsProposalPart, BlsSignBytes, BlsValidator, BlsValidatorSet, BlsVote,
+};
+use super::types::{
+    Address, CertificateError, Context, Height, NilOrVal, Round, ThresholdParams, VotingPower,
+};
+
+/// A context which only differs from the test context by its use of BLS signatures,
+/// to exercise the aggregation of commit signatures with actual BLS keys.
+pub(crate) mod bls {
+    use bytes::Bytes;
+
+    use informalsystems_malachitebft_test::{
+        Address, Height, Proposal, ProposalPart, TestSignBytes, Value, ValueId, Vote,
+    };
+    use malachitebft_core_types::{
+        self as core, NilOrVal, Round, SignedExtension, VoteType, VotingPower,
+    };
+    use malachitebft_proto::{Error as ProtoError, Protobuf};
+    use malachitebft_signing::SignBytes;
+    use malachitebft_signing_bls::{Bls12381, PublicKey};
+
+    #[derive(Copy, Clone, Debug, Default)]
+    pub struct BlsContext;
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct BlsVote(pub Vote);
+
+    impl core::Vote<BlsContext> for BlsVote {
+        fn height(&self) -> Height {
+            self.0.height
+        }
+
+        fn round(&self) -> Round {
+            self.0.round
+        }
+
+        fn value(&self) -> &NilOrVal<ValueId> {
+            &self.0.value
+        }
+
+        fn take_value(self) -> NilOrVal<ValueId> {
+            self.0.value
+        }
+
+        fn vote_type(&self) -> VoteType {
+            self.0.typ
+        }
+
+        fn validator_address(&self) -> &Address {
+            &self.0.validator_address
+        }
+
+        fn extension(&self) -> Option<&SignedExtension<BlsContext>> {
+            None
+        }
+
+        fn take_extension(&mut self) -> Option<SignedExtension<BlsContext>> {
+            None
+        }
+
+        fn extend(self, _extension: SignedExtension<BlsContext>) -> Self {
+            self
+        }
+    }
+
+    impl Protobuf for BlsVote {
+        type Proto = <Vote as Protobuf>::Proto;
+
+        fn from_proto(proto: Self::Proto) -> Result<Self, ProtoError> {
+            Vote::from_proto(proto).map(Self)
+        }
+
+        fn to_proto(&self) -> Result<Self::Proto, ProtoError> {
+            self.0.to_proto()
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct BlsProposal(pub Proposal);
+
+    impl core::Proposal<BlsContext> for BlsProposal {
+        fn height(&self) -> Height {
+            self.0.height
+        }
+
+        fn round(&self) -> Round {
+            self.0.round
+        }
+
+        fn value(&self) -> &Value {
+            &self.0.value
+        }
+
+        fn take_value(self) -> Value {
+            self.0.value
+        }
+
+        fn pol_round(&self) -> Round {
+            self.0.pol_round
+        }
+
+        fn validator_address(&self) -> &Address {
+            &self.0.validator_address
+        }
+    }
+
+    impl Protobuf for BlsProposal {
+        type Proto = <Proposal as Protobuf>::Proto;
+
+        fn from_proto(proto: Self::Proto) -> Result<Self, ProtoError> {
+            Proposal::from_proto(proto).map(Self)
+        }
+
+        fn to_proto(&self) -> Result<Self::Proto, ProtoError> {
+            self.0.to_proto()
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct BlsProposalPart(pub ProposalPart);
+
+    impl core::ProposalPart<BlsContext> for BlsProposalPart {
+        fn is_first(&self) -> bool {
+            matches!(self.0, ProposalPart::Init(_))
+        }
+
+        fn is_last(&self) -> bool {
+            matches!(self.0, ProposalPart::Fin(_))
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct BlsValidator {
+        pub address: Address,
+        pub public_key: PublicKey,
+        pub voting_power: VotingPower,
+    }
+
+    impl core::Validator<BlsContext> for BlsValidator {
+        fn address(&self) -> &Address {
+            &self.address
+        }
+
+        fn public_key(&self) -> &PublicKey {
+            &self.public_key
+        }
+
+        fn voting_power(&self) -> VotingPower {
+            self.voting_power
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct BlsValidatorSet {
+        pub validators: Vec<BlsValidator>,
+    }
+
+    impl core::ValidatorSet<BlsContext> for BlsValidatorSet {
+        fn count(&self) -> usize {
+            self.validators.len()
+        }
+
+        fn total_voting_power(&self) -> VotingPower {
+            self.validators.iter().map(|v| v.voting_power).sum()
+        }
+
+        fn get_by_address(&self, address: &Address) -> Option<&BlsValidator> {
+            self.validators.iter().find(|v| &v.address == address)
+        }
+
+        fn get_by_index(&self, index: usize) -> Option<&BlsValidator> {
+            self.validators.get(index)
+        }
+
+        fn from_validators(validators: Vec<BlsValidator>) -> Self {
+            Self { validators }
+        }
+    }
+
+    impl core::Context for BlsContext {
+        type Address = Address;
+        type Height = Height;
+        type ProposalPart = BlsProposalPart;
+        type Proposal = BlsProposal;
+        type Validator = BlsValidator;
+        type ValidatorSet = BlsValidatorSet;
+        type Value = Value;
+        type Vote = BlsVote;
+        type Extension = Bytes;
+        type SigningScheme = Bls12381;
+
+        fn select_proposer<'a>(
+            &self,
+            validator_set: &'a BlsValidatorSet,
+            _height: Height,
+            _round: Round,
+        ) -> &'a BlsValidator {
+            &validator_set.validators[0]
+        }
+
+        fn new_proposal(
+            &self,
+            height: Height,
+            round: Round,
+            value: Value,
+            pol_round: Round,
+            address: Address,
+        ) -> BlsProposal {
+            BlsProposal(Proposal::new(height, round, value, pol_round, address))
+        }
+
+        fn new_prevote(
+            &self,
+            height: Height,
+            round: Round,
+            value_id: NilOrVal<ValueId>,
+            address: Address,
+        ) -> BlsVote {
+            BlsVote(Vote::new_prevote(height, round, value_id, address))
+        }
+
+        fn new_precommit(
+            &self,
+            height: Height,
+            round: Round,
+            value_id: NilOrVal<ValueId>,
+            address: Address,
+        ) -> BlsVote {
+            BlsVote(Vote::new_precommit(height, round, value_id, address))
+        }
+    }
+
+    /// The sign bytes of the test context, which include the address of the validator in votes.
+    #[derive(Copy, Clone, Debug, Default)]
+    pub struct BlsSignBytes;
+
+    impl SignBytes<BlsContext> for BlsSignBytes {
+        fn vote(&self, vote: &BlsVote) -> Bytes {
+            vote.0.to_sign_bytes()
+        }
+
+        fn proposal(&self, proposal: &BlsProposal) -> Bytes {
+            proposal.0.to_sign_bytes()
+        }
+
+        fn proposal_part(&self, proposal_part: &BlsProposalPart) -> Bytes {
+            proposal_part.0.to_sign_bytes()
+        }
+
+        fn extension(&self, extension: &Bytes) -> Bytes {
+            extension.clone()
+        }
+
+        fn commit(&self, height: &Height, round: Round, value_id: &ValueId) -> Bytes {
+            TestSignBytes.commit(height, round, value_id)
+        }
+    }
+}
+
+type Provider = BlsSigningProvider<BlsSignBytes>;
+
+fn make_validators<const N: usize>(
+    voting_powers: [VotingPower; N],
+) -> (BlsValidatorSet, Vec<Provider>) {
+    let (validators, providers) = voting_powers
+        .into_iter()
+        .enumerate()
+        .map(|(i, voting_power)| {
+            let provider = BlsSigningProvider::new(PrivateKey::from([i as u8; 32]), BlsSignBytes);
+
+            let validator = BlsValidator {
+                address: Address::new([i as u8; 20]),
+                public_key: provider.public_key(),
+                voting_power,
+            };
+
+            (validator, provider)
+        })
+        .unzip();
+
+    (BlsValidatorSet { validators }, providers)
+}
+
+/// A provider which admitted the public keys of the given validators
+/// with their proof of possession, for verifying aggregated certificates.
+fn verifier(validators: &[Provider]) -> Provider {
+    let verifier = BlsSigningProvider::new(PrivateKey::from([0xff; 32]), BlsSignBytes);
+
+    for validator in validators {
+        let proof = validator.private_key().proof_of_possession();
+        verifier
+            .admit_public_key(validator.public_key(), &proof)
+            .unwrap();
+    }
+
+    verifier
+}
+
+fn setup<const N: usize>(
+    voting_powers: [VotingPower; N],
+    signers: impl IntoIterator<Item = usize>,
+) -> (CommitCertificate<BlsContext>, BlsValidatorSet, Provider) {
+    let ctx = BlsContext;
+    let (height, round, value_id) = (Height::new(1), Round::new(0), ValueId::new(42));
+
+    let (validator_set, providers) = make_validators(voting_powers);
+
+    let votes: Vec<SignedVote<BlsContext>> = signers
+        .into_iter()
+        .map(|index| {
+            let vote = ctx.new_precommit(
+                height,
+                round,
+                NilOrVal::Val(value_id),
+                validator_set.validators[index].address,
+            );
+            block_on(providers[index].sign_vote(vote)).unwrap()
+        })
+        .collect();
+
+    let certificate = CommitCertificate::new(height, round, value_id, votes);
+
+    (certificate, validator_set, verifier(&providers))
+}
+
+#[allow(clippy::result_large_err)]
+fn verify(
+    provider: &impl SigningProvider<BlsContext>,
+    certificate: &CommitCertificate<BlsContext>,
+    validator_set: &BlsValidatorSet,
+) -> Result<(), CertificateError<BlsContext>> {
+    block_on(provider.verify_commit_certificate(
+        &BlsContext,
+        certificate,
+        validator_set,
+        ThresholdParams::default(),
+    ))
+}
+
+#[test]
+fn signer_bitmap() {
+    let mut bitmap = SignerBitmap::new();
+    assert!(bitmap.is_empty());
+
+    bitmap.insert(0);
+    bitmap.insert(9);
+    bitmap.insert(9);
+
+    assert_eq!(bitmap.as_bytes(), &[0b0000_0001, 0b0000_0010]);
+    assert_eq!(bitmap.count(), 2);
+    assert!(bitmap.contains(0));
+    assert!(!bitmap.contains(1));
+    assert!(bitmap.contains(9));
+    assert!(!bitmap.contains(100));
+    assert_eq!(bitmap.iter().collect::<Vec<_>>(), vec![0, 9]);
+
+    assert_eq!(SignerBitmap::from_iter([9, 0]), bitmap);
+    assert_eq!(SignerBitmap::from_bytes(bitmap.as_bytes().to_vec()), bitmap);
+}
+
+#[test]
+fn aggregated_commit_certificate_is_valid() {
+    let (certificate, validator_set, provider) = setup([20, 20, 30, 30], [0, 2, 3]);
+
+    // The plain certificate is valid to begin with
+    assert_eq!(verify(&provider, &certificate, &validator_set), Ok(()));
+
+    let aggregated =
+        block_on(provider.aggregate_commit_certificate(&certificate, &validator_set)).unwrap();
+
+    assert!(aggregated.is_aggregated());
+    assert!(aggregated.commit_signatures.is_empty());
+    assert_eq!(aggregated.signature_count(), 3);
+
+    let signers = &aggregated.aggregated_signature.as_ref().unwrap().signers;
+    for (index, validator) in validator_set.validators.iter().enumerate() {
+        let signed = certificate
+            .commit_signatures
+            .iter()
+            .any(|sig| sig.address == validator.address);
+
+        assert_eq!(signers.contains(index), signed);
+    }
+
+    assert_eq!(verify(&provider, &aggregated, &validator_set), Ok(()));
+
+    // Aggregating an aggregated certificate leaves it unchanged
+    let again =
+        block_on(provider.aggregate_commit_certificate(&aggregated, &validator_set)).unwrap();
+    assert_eq!(again, aggregated);
+}
+
+#[test]
+fn aggregated_commit_certificate_single_pairing() {
+    let (certificate, validator_set, provider) = setup([10, 10, 10, 10], 0..4);
+
+    let aggregated =
+        block_on(provider.aggregate_commit_certificate(&certificate, &validator_set)).unwrap();
+
+    // All the signers signed the same commit message, so that the aggregated signature
+    // verifies against the aggregate of their public keys, with a single pairing check
+    let public_keys = validator_set
+        .validators
+        .iter()
+        .map(|v| v.public_key)
+        .collect::<Vec<_>>();
+
+    let msg = BlsSignBytes.commit(
+        &certificate.height,
+        certificate.round,
+        &certificate.value_id,
+    );
+    let signature = aggregated.aggregated_signature.as_ref().unwrap().signature;
+
+    assert!(PublicKey::aggregate(&public_keys)
+        .unwrap()
+        .verify(&msg, &signature)
+        .is_ok());
+
+    // Verifying the certificate only checks the aggregated signature once,
+    // and never checks the signatures of the signers one by one
+    let counting = CountingProvider::new(provider);
+    assert_eq!(verify(&counting, &aggregated, &validator_set), Ok(()));
+    assert_eq!(counting.aggregated_checks.load(Ordering::SeqCst), 1);
+    assert_eq!(counting.vote_checks.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn aggregated_commit_certificate_requires_proof_of_possession() {
+    let (certificate, validator_set, provider) = setup([10, 10, 10, 10], 0..4);
+
+    let aggregated =
+        block_on(provider.aggregate_commit_certificate(&certificate, &validator_set)).unwrap();
+
+    // The plain certificate does not need any proof of possession
+    let (_, providers) = make_validators([10, 10, 10, 10]);
+    let partial = Arc::new(verifier(&providers[..3]));
+    assert_eq!(verify(&*partial, &certificate, &validator_set), Ok(()));
+
+    // But the aggregated one is rejected as long as one of the signers has not been admitted
+    assert_eq!(
+        verify(&*partial, &aggregated, &validator_set),
+        Err(CertificateError::InvalidAggregatedSignature)
+    );
+
+    // Which requires a valid proof of possession
+    let wrong_proof = providers[0].private_key().proof_of_possession();
+    assert!(partial
+        .admit_public_key(providers[3].public_key(), &wrong_proof)
+        .is_err());
+    assert!(!partial.is_admitted(&providers[3].public_key()));
+
+    // Keys can be admitted while the provider is shared, eg. with the consensus engine,
+    // such as the key of a validator added by an update to the validator set
+    let shared = Arc::clone(&partial);
+    let proof = providers[3].private_key().proof_of_possession();
+    shared
+        .admit_public_key(providers[3].public_key(), &proof)
+        .unwrap();
+
+    assert_eq!(verify(&*partial, &aggregated, &validator_set), Ok(()));
+}
+
+#[test]
+fn precommits_for_a_value_are_signed_over_the_commit_message() {
+    let ctx = BlsContext;
+    let (validator_set, providers) = make_validators([10, 10]);
+    let (height, round, value_id) = (Height::new(1), Round::new(0), ValueId::new(42));
+
+    let commit = BlsSignBytes.commit(&height, round, &value_id);
+
+    for (validator, provider) in validator_set.validators.iter().zip(&providers) {
+        let precommit =
+            ctx.new_precommit(height, round, NilOrVal::Val(value_id), validator.address);
+        let signed = block_on(provider.sign_vote(precommit.clone())).unwrap();
+
+        assert!(validator
+            .public_key
+            .verify(&commit, &signed.signature)
+            .is_ok());
+        assert!(block_on(provider.verify_signed_vote(
+            &precommit,
+            &signed.signature,
+            &validator.public_key
+        ))
+        .unwrap()
+        .is_valid());
+
+        // Which cannot be passed off as a prevote for the same value
+        let prevote = ctx.new_prevote(height, round, NilOrVal::Val(value_id), validator.address);
+        assert!(block_on(provider.verify_signed_vote(
+            &prevote,
+            &signed.signature,
+            &validator.public_key
+        ))
+        .unwrap()
+        .is_invalid());
+
+        // Other votes are still signed over their own sign bytes
+        let nil = ctx.new_precommit(height, round, NilOrVal::Nil, validator.address);
+        let signed = block_on(provider.sign_vote(nil.clone())).unwrap();
+
+        assert!(validator
+            .public_key
+            .verify(&BlsSignBytes.vote(&nil), &signed.signature)
+            .is_ok());
+    }
+}
+
+#[test]
+fn aggregated_commit_certificate_insufficient_voting_power() {
+    let (certificate, validator_set, provider) = setup([10, 20, 30, 40], [0, 1, 2]);
+
+    let aggregated =
+        block_on(provider.aggregate_commit_certificate(&certificate, &validator_set)).unwrap();
+
+    assert_eq!(
+        verify(&provider, &aggregated, &validator_set),
+        Err(CertificateError::NotEnoughVotingPower {
+            signed: 60,
+            total: 100,
+            expected: 67,
+        })
+    );
+}
+
+#[test]
+fn aggregated_commit_certificate_invalid_signature() {
+    let (certificate, validator_set, provider) = setup([10, 10, 10, 10], 0..4);
+
+    let mut aggregated =
+        block_on(provider.aggregate_commit_certificate(&certificate, &validator_set)).unwrap();
+
+    aggregated.aggregated_signature.as_mut().unwrap().signature =
+        certificate.commit_signatures[0].signature;
+
+    assert_eq!(
+        verify(&provider, &aggregated, &validator_set),
+        Err(CertificateError::InvalidAggregatedSignature)
+    );
+
+    // The aggregated signature must cover exactly the signers in the bitmap
+    let mut aggregated =
+        block_on(provider.aggregate_commit_certificate(&certificate, &validator_set)).unwrap();
+
+    aggregated.aggregated_signature.as_mut().unwrap().signers = SignerBitmap::from_iter(0..3);
+
+    assert_eq!(
+        verify(&provider, &aggregated, &validator_set),
+        Err(CertificateError::InvalidAggregatedSignature)
+    );
+}
+
+#[test]
+fn aggregated_commit_certificate_unknown_signer() {
+    let (certificate, validator_set, provider) = setup([10, 10, 10, 10], 0..4);
+
+    let signers = SignerBitmap::from_iter([0, 1, 2, 3, 4]);
+    let aggregated = CommitCertificate::new_aggregated(
+        certificate.height,
+        certificate.round,
+        certificate.value_id,
+        AggregatedSignature::new(signers, certificate.commit_signatures[0].signature),
+    );
+
+    assert_eq!(
+        verify(&provider, &aggregated, &validator_set),
+        Err(CertificateError::UnknownSignerIndex(4))
+    );
+}
+
+#[test]
+fn aggregated_commit_certificate_mixed_signatures() {
+    let (certificate, validator_set, provider) = setup([10, 10, 10, 10], 0..4);
+
+    let mut aggregated =
+        block_on(provider.aggregate_commit_certificate(&certificate, &validator_set)).unwrap();
+
+    aggregated.commit_signatures = vec![CommitSignature::new(
+        certificate.commit_signatures[0].address,
+        certificate.commit_signatures[0].signature,
+    )];
+
+    assert_eq!(
+        verify(&provider, &aggregated, &validator_set),
+        Err(CertificateError::MixedSignatures)
+    );
+}
+
+#[test]
+fn aggregation_not_supported() {
+    use super::types::{Ed25519Provider, TestContext, ValidatorSet};
+
+    let ctx = TestContext::new();
+    let (height, round, value_id) = (Height::new(1), Round::new(0), ValueId::new(42));
+
+    let (validators, providers) = super::make_validators([10, 10, 10, 10], super::DEFAULT_SEED);
+
+    let votes = validators
+        .iter()
+        .zip(&providers)
+        .map(|(validator, provider)| {
+            let vote = ctx.new_precommit(height, round, NilOrVal::Val(value_id), validator.address);
+            block_on(provider.sign_vote(vote)).unwrap()
+        })
+        .collect();
+
+    let certificate = CommitCertificate::new(height, round, value_id, votes);
+    let validator_set = ValidatorSet::new(validators);
+    let ed25519: &Ed25519Provider = &providers[0];
+
+    let result = block_on(ed25519.aggregate_commit_certificate(&certificate, &validator_set));
+    assert!(matches!(
+        result,
+        Err(CertificateError::VerificationError(Some(_)))
+    ));
+
+    let aggregated = CommitCertificate::<TestContext>::new_aggregated(
+        height,
+        round,
+        value_id,
+        AggregatedSignature::new(
+            SignerBitmap::from_iter(0..4),
+            certificate.commit_signatures[0].signature,
+        ),
+    );
+
+    assert!(matches!(
+        block_on(ed25519.verify_commit_certificate(
+            &ctx,
+            &aggregated,
+            &validator_set,
+            ThresholdParams::default(),
+        )),
+        Err(CertificateError::VerificationError(Some(_)))
+    ));
+}
+
+#[test]
+fn empty_signatures_cannot_be_aggregated() {
+    let provider = verifier(&[]);
+
+    let result: Result<Signature, _> = block_on(
+        SigningProvider::<BlsContext>::aggregate_signatures(&provider, &[]),
+    );
+    assert!(result.is_err());
+}
+
+/// A provider which counts how many times it verifies signatures of votes and aggregated commits.
+struct CountingProvider {
+    inner: Provider,
+    vote_checks: AtomicUsize,
+    aggregated_checks: AtomicUsize,
+}
+
+impl CountingProvider {
+    fn new(inner: Provider) -> Self {
+        Self {
+            inner,
+            vote_checks: AtomicUsize::new(0),
+            aggregated_checks: AtomicUsize::new(0),
+        }
+    }
+}
+
+#[async_trait]
+impl SigningProvider<BlsContext> for CountingProvider {
+    async fn sign_vote(&self, vote: BlsVote) -> Result<SignedVote<BlsContext>, SigningError> {
+        self.inner.sign_vote(vote).await
+    }
+
+    async fn verify_signed_vote(
+        &self,
+        vote: &BlsVote,
+        signature: &Signature,
+        public_key: &PublicKey,
+    ) -> Result<VerificationResult, SigningError> {
+        self.vote_checks.fetch_add(1, Ordering::SeqCst);
+        self.inner
+            .verify_signed_vote(vote, signature, public_key)
+            .await
+    }
+
+    async fn sign_proposal(
+        &self,
+        proposal: BlsProposal,
+    ) -> Result<SignedMessage<BlsContext, BlsProposal>, SigningError> {
+        self.inner.sign_proposal(proposal).await
+    }
+
+    async fn verify_signed_proposal(
+        &self,
+        proposal: &BlsProposal,
+        signature: &Signature,
+        public_key: &PublicKey,
+    ) -> Result<VerificationResult, SigningError> {
+        self.inner
+            .verify_signed_proposal(proposal, signature, public_key)
+            .await
+    }
+
+    async fn sign_proposal_part(
+        &self,
+        proposal_part: BlsProposalPart,
+    ) -> Result<SignedMessage<BlsContext, BlsProposalPart>, SigningError> {
+        self.inner.sign_proposal_part(proposal_part).await
+    }
+
+    async fn verify_signed_proposal_part(
+        &self,
+        proposal_part: &BlsProposalPart,
+        signature: &Signature,
+        public_key: &PublicKey,
+    ) -> Result<VerificationResult, SigningError> {
+        self.inner
+            .verify_signed_proposal_part(proposal_part, signature, public_key)
+            .await
+    }
+
+    async fn sign_vote_extension(
+        &self,
+        extension: Bytes,
+    ) -> Result<SignedMessage<BlsContext, Bytes>, SigningError> {
+        self.inner.sign_vote_extension(extension).await
+    }
+
+    async fn verify_signed_vote_extension(
+        &self,
+        extension: &Bytes,
+        signature: &Signature,
+        public_key: &PublicKey,
+    ) -> Result<VerificationResult, SigningError> {
+        self.inner
+            .verify_signed_vote_extension(extension, signature, public_key)
+            .await
+    }
+
+    async fn aggregate_signatures(
+        &self,
+        signatures: &[Signature],
+    ) -> Result<Signature, SigningError> {
+        self.inner.aggregate_signatures(signatures).await
+    }
+
+    async fn verify_aggregated_commit(
+        &self,
+        height: &Height,
+        round: Round,
+        value_id: &ValueId,
+        signature: &Signature,
+        public_keys: &[&PublicKey],
+    ) -> Result<VerificationResult, SigningError> {
+        self.aggregated_checks.fetch_add(1, Ordering::SeqCst);
+        self.inner
+            .verify_aggregated_commit(height, round, value_id, signature, public_keys)
+            .await
+    }
+}
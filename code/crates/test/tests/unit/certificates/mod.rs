@@ -1,5 +1,6 @@
 #![allow(dead_code)]
 
+pub(crate) mod aggregated;
 mod commit;
 mod polka;
 mod round;
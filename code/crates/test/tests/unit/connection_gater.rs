@@ -0,0 +1,126 @@
+use std::net::IpAddr;
+
+use ipnet::IpNet;
+use malachitebft_network::gater::{Config, Denied};
+use malachitebft_network::Multiaddr;
+use malachitebft_peer::PeerId;
+
+fn ip(s: &str) -> IpAddr {
+    s.parse().unwrap()
+}
+
+fn net(s: &str) -> IpNet {
+    s.parse().unwrap()
+}
+
+fn addr(s: &str) -> Multiaddr {
+    s.parse().unwrap()
+}
+
+#[test]
+fn everything_is_allowed_by_default() {
+    let config = Config::default();
+
+    assert_eq!(config.check_peer(&PeerId::random()), Ok(()));
+    assert_eq!(config.check_ip(ip("1.2.3.4")), Ok(()));
+    assert_eq!(config.check_ip(ip("::1")), Ok(()));
+}
+
+#[test]
+fn denied_peers_are_denied() {
+    let denied = PeerId::random();
+    let other = PeerId::random();
+
+    let config = Config {
+        denied_peers: [denied].into(),
+        ..Default::default()
+    };
+
+    assert_eq!(config.check_peer(&denied), Err(Denied::DeniedPeer(denied)));
+    assert_eq!(config.check_peer(&other), Ok(()));
+}
+
+#[test]
+fn only_allowed_peers_are_allowed() {
+    let allowed = PeerId::random();
+    let other = PeerId::random();
+
+    let config = Config {
+        allowed_peers: [allowed].into(),
+        ..Default::default()
+    };
+
+    assert_eq!(config.check_peer(&allowed), Ok(()));
+    assert_eq!(
+        config.check_peer(&other),
+        Err(Denied::PeerNotAllowed(other))
+    );
+}
+
+#[test]
+fn denied_peers_take_precedence_over_allowed_peers() {
+    let peer = PeerId::random();
+
+    let config = Config {
+        allowed_peers: [peer].into(),
+        denied_peers: [peer].into(),
+        ..Default::default()
+    };
+
+    assert_eq!(config.check_peer(&peer), Err(Denied::DeniedPeer(peer)));
+}
+
+#[test]
+fn ip_ranges() {
+    let config = Config {
+        allowed_ips: vec![net("10.0.0.0/8"), net("fd00::/8")],
+        denied_ips: vec![net("10.1.0.0/16")],
+        ..Default::default()
+    };
+
+    assert_eq!(config.check_ip(ip("10.2.3.4")), Ok(()));
+    assert_eq!(config.check_ip(ip("fd12::1")), Ok(()));
+
+    assert_eq!(
+        config.check_ip(ip("10.1.2.3")),
+        Err(Denied::DeniedIp(ip("10.1.2.3")))
+    );
+    assert_eq!(
+        config.check_ip(ip("192.168.1.1")),
+        Err(Denied::IpNotAllowed(ip("192.168.1.1")))
+    );
+    assert_eq!(
+        config.check_ip(ip("2001:db8::1")),
+        Err(Denied::IpNotAllowed(ip("2001:db8::1")))
+    );
+}
+
+#[test]
+fn addresses_are_checked_by_ip() {
+    let config = Config {
+        allowed_ips: vec![net("127.0.0.0/8")],
+        ..Default::default()
+    };
+
+    assert_eq!(config.check_addr(&addr("/ip4/127.0.0.1/tcp/27000")), Ok(()));
+    assert_eq!(
+        config.check_addr(&addr("/ip4/1.2.3.4/udp/27000/quic-v1")),
+        Err(Denied::IpNotAllowed(ip("1.2.3.4")))
+    );
+
+    // Addresses without an IP address cannot be checked against the allowed IP ranges
+    assert_eq!(
+        config.check_addr(&addr("/dns4/example.com/tcp/27000")),
+        Err(Denied::NoIpAddress)
+    );
+
+    let config = Config {
+        denied_ips: vec![net("127.0.0.0/8")],
+        ..Default::default()
+    };
+
+    assert_eq!(
+        config.check_addr(&addr("/dns4/example.com/tcp/27000")),
+        Ok(())
+    );
+}
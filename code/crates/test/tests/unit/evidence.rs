@@ -0,0 +1,720 @@
+use futures::executor::block_on;
+
+use informalsystems_malachitebft_test::{
+    utils, Address, Ed25519Provider, Height, Proposal, TestContext, Validator, ValidatorSet, Value,
+    ValueId,
+};
+use malachitebft_core_consensus::MisbehaviorEvidence;
+use malachitebft_core_types::{
+    AmnesiaEvidence, Context, DoubleProposalEvidence, DoubleVoteEvidence, Evidence, EvidenceError,
+    NilOrVal, PolkaCertificate, Round, SignedProposal, SignedVote, VoteType,
+};
+use malachitebft_engine::evidence::misbehavior_of_invalid_evidence;
+use malachitebft_engine::network::Misbehavior;
+use malachitebft_proto::Protobuf;
+use malachitebft_signing::{SigningProvider, SigningProviderExt};
+use malachitebft_signing_bls::{BlsSigningProvider, PrivateKey};
+use malachitebft_signing_ed25519::Signature;
+
+use super::certificates::aggregated::bls::{BlsContext, BlsSignBytes};
+
+const SEED: u64 = 0xfeedbeef;
+
+fn setup() -> (
+    TestContext,
+    Vec<Validator>,
+    Vec<Ed25519Provider>,
+    ValidatorSet,
+) {
+    let (validators, signers): (Vec<_>, Vec<_>) =
+        utils::validators::make_validators_seeded([10, 20, 30], SEED)
+            .into_iter()
+            .map(|(v, pk)| (v, Ed25519Provider::new(pk)))
+            .unzip();
+
+    let validator_set = ValidatorSet::new(validators.clone());
+
+    (TestContext::new(), validators, signers, validator_set)
+}
+
+fn prevote(
+    ctx: &TestContext,
+    signer: &Ed25519Provider,
+    round: Round,
+    value_id: NilOrVal<ValueId>,
+    address: Address,
+) -> SignedVote<TestContext> {
+    let vote = ctx.new_prevote(Height::new(1), round, value_id, address);
+    block_on(signer.sign_vote(vote)).unwrap()
+}
+
+fn precommit(
+    ctx: &TestContext,
+    signer: &Ed25519Provider,
+    round: Round,
+    value_id: NilOrVal<ValueId>,
+    address: Address,
+) -> SignedVote<TestContext> {
+    let vote = ctx.new_precommit(Height::new(1), round, value_id, address);
+    block_on(signer.sign_vote(vote)).unwrap()
+}
+
+fn proposal(
+    signer: &Ed25519Provider,
+    round: Round,
+    value: u64,
+    address: Address,
+) -> SignedProposal<TestContext> {
+    let proposal = Proposal::new(
+        Height::new(1),
+        round,
+        Value::new(value),
+        Round::Nil,
+        address,
+    );
+    block_on(signer.sign_proposal(proposal)).unwrap()
+}
+
+#[test]
+fn valid_double_vote_evidence() {
+    let (ctx, validators, signers, validator_set) = setup();
+    let address = validators[0].address;
+
+    let evidence = DoubleVoteEvidence::new(
+        prevote(
+            &ctx,
+            &signers[0],
+            Round::new(0),
+            NilOrVal::Val(ValueId::new(1)),
+            address,
+        ),
+        prevote(&ctx, &signers[0], Round::new(0), NilOrVal::Nil, address),
+    );
+
+    assert_eq!(evidence.validate(), Ok(()));
+    assert_eq!(
+        block_on(signers[1].verify_double_vote_evidence(&evidence, &validator_set)),
+        Ok(())
+    );
+}
+
+#[test]
+fn double_vote_evidence_not_conflicting() {
+    let (ctx, validators, signers, validator_set) = setup();
+    let address = validators[0].address;
+
+    let vote = prevote(&ctx, &signers[0], Round::new(0), NilOrVal::Nil, address);
+    let evidence = DoubleVoteEvidence::new(vote.clone(), vote);
+
+    assert_eq!(
+        block_on(signers[1].verify_double_vote_evidence(&evidence, &validator_set)),
+        Err(EvidenceError::NotConflicting)
+    );
+}
+
+#[test]
+fn double_vote_evidence_mismatched_round() {
+    let (ctx, validators, signers, validator_set) = setup();
+    let address = validators[0].address;
+
+    let evidence = DoubleVoteEvidence::new(
+        prevote(&ctx, &signers[0], Round::new(0), NilOrVal::Nil, address),
+        prevote(
+            &ctx,
+            &signers[0],
+            Round::new(1),
+            NilOrVal::Val(ValueId::new(1)),
+            address,
+        ),
+    );
+
+    assert_eq!(
+        block_on(signers[1].verify_double_vote_evidence(&evidence, &validator_set)),
+        Err(EvidenceError::MismatchedRound {
+            first: Round::new(0),
+            second: Round::new(1),
+        })
+    );
+}
+
+#[test]
+fn double_vote_evidence_mismatched_validator() {
+    let (ctx, validators, signers, validator_set) = setup();
+
+    let evidence = DoubleVoteEvidence::new(
+        prevote(
+            &ctx,
+            &signers[0],
+            Round::new(0),
+            NilOrVal::Nil,
+            validators[0].address,
+        ),
+        prevote(
+            &ctx,
+            &signers[1],
+            Round::new(0),
+            NilOrVal::Nil,
+            validators[1].address,
+        ),
+    );
+
+    assert_eq!(
+        block_on(signers[1].verify_double_vote_evidence(&evidence, &validator_set)),
+        Err(EvidenceError::MismatchedValidator {
+            first: validators[0].address,
+            second: validators[1].address,
+        })
+    );
+}
+
+#[test]
+fn double_vote_evidence_invalid_signature() {
+    let (ctx, validators, signers, validator_set) = setup();
+    let address = validators[0].address;
+
+    let mut second = prevote(&ctx, &signers[0], Round::new(0), NilOrVal::Nil, address);
+    second.signature = Signature::test();
+
+    let evidence = DoubleVoteEvidence::new(
+        prevote(
+            &ctx,
+            &signers[0],
+            Round::new(0),
+            NilOrVal::Val(ValueId::new(1)),
+            address,
+        ),
+        second,
+    );
+
+    assert_eq!(
+        block_on(signers[1].verify_double_vote_evidence(&evidence, &validator_set)),
+        Err(EvidenceError::InvalidSignature(address))
+    );
+}
+
+#[test]
+fn double_vote_evidence_unknown_validator() {
+    let (ctx, _, signers, validator_set) = setup();
+
+    let (outsider, outsider_key) = utils::validators::make_validators_seeded([10], SEED + 1)
+        .into_iter()
+        .next()
+        .unwrap();
+    let outsider_signer = Ed25519Provider::new(outsider_key);
+
+    let evidence = DoubleVoteEvidence::new(
+        prevote(
+            &ctx,
+            &outsider_signer,
+            Round::new(0),
+            NilOrVal::Val(ValueId::new(1)),
+            outsider.address,
+        ),
+        prevote(
+            &ctx,
+            &outsider_signer,
+            Round::new(0),
+            NilOrVal::Nil,
+            outsider.address,
+        ),
+    );
+
+    assert_eq!(
+        block_on(signers[0].verify_double_vote_evidence(&evidence, &validator_set)),
+        Err(EvidenceError::UnknownValidator(outsider.address))
+    );
+}
+
+#[test]
+fn valid_double_proposal_evidence() {
+    let (_, validators, signers, validator_set) = setup();
+    let address = validators[2].address;
+
+    let evidence = DoubleProposalEvidence::new(
+        proposal(&signers[2], Round::new(0), 1, address),
+        proposal(&signers[2], Round::new(0), 2, address),
+    );
+
+    assert_eq!(
+        block_on(signers[0].verify_double_proposal_evidence(&evidence, &validator_set)),
+        Ok(())
+    );
+}
+
+#[test]
+fn double_proposal_evidence_not_conflicting() {
+    let (_, validators, signers, validator_set) = setup();
+    let address = validators[2].address;
+
+    let signed = proposal(&signers[2], Round::new(0), 1, address);
+    let evidence = DoubleProposalEvidence::new(signed.clone(), signed);
+
+    assert_eq!(
+        block_on(signers[0].verify_double_proposal_evidence(&evidence, &validator_set)),
+        Err(EvidenceError::NotConflicting)
+    );
+}
+
+#[test]
+fn double_proposal_evidence_invalid_signature() {
+    let (_, validators, signers, validator_set) = setup();
+    let address = validators[2].address;
+
+    // Second proposal is signed by another validator's key
+    let evidence = DoubleProposalEvidence::new(
+        proposal(&signers[2], Round::new(0), 1, address),
+        proposal(&signers[1], Round::new(0), 2, address),
+    );
+
+    assert_eq!(
+        block_on(signers[0].verify_double_proposal_evidence(&evidence, &validator_set)),
+        Err(EvidenceError::InvalidSignature(address))
+    );
+}
+
+fn amnesia_evidence(
+    ctx: &TestContext,
+    signer: &Ed25519Provider,
+    address: Address,
+) -> AmnesiaEvidence<TestContext> {
+    AmnesiaEvidence::new(
+        precommit(
+            ctx,
+            signer,
+            Round::new(0),
+            NilOrVal::Val(ValueId::new(1)),
+            address,
+        ),
+        prevote(
+            ctx,
+            signer,
+            Round::new(2),
+            NilOrVal::Val(ValueId::new(2)),
+            address,
+        ),
+    )
+}
+
+fn polka_certificate(
+    ctx: &TestContext,
+    signers: &[Ed25519Provider],
+    validators: &[Validator],
+    round: Round,
+    value_id: ValueId,
+) -> PolkaCertificate<TestContext> {
+    let votes = signers
+        .iter()
+        .zip(validators)
+        .map(|(signer, validator)| {
+            prevote(
+                ctx,
+                signer,
+                round,
+                NilOrVal::Val(value_id),
+                validator.address,
+            )
+        })
+        .collect();
+
+    PolkaCertificate::new(Height::new(1), round, value_id, votes)
+}
+
+#[test]
+fn valid_amnesia_evidence() {
+    let (ctx, validators, signers, validator_set) = setup();
+    let evidence = amnesia_evidence(&ctx, &signers[0], validators[0].address);
+
+    // Polkas for another value, or outside of the range [locked_round, prevote_round)
+    let certificates = [
+        polka_certificate(&ctx, &signers, &validators, Round::new(1), ValueId::new(3)),
+        polka_certificate(&ctx, &signers, &validators, Round::new(2), ValueId::new(2)),
+    ];
+
+    assert_eq!(
+        block_on(signers[1].verify_amnesia_evidence(&evidence, &certificates, &validator_set)),
+        Ok(())
+    );
+}
+
+#[test]
+fn amnesia_evidence_justified_by_polka() {
+    let (ctx, validators, signers, validator_set) = setup();
+    let evidence = amnesia_evidence(&ctx, &signers[0], validators[0].address);
+
+    let certificate =
+        polka_certificate(&ctx, &signers, &validators, Round::new(1), ValueId::new(2));
+
+    assert!(evidence.is_justified_by(&certificate));
+    assert_eq!(
+        block_on(signers[1].verify_amnesia_evidence(&evidence, &[certificate], &validator_set)),
+        Err(EvidenceError::JustifiedByPolka(Round::new(1)))
+    );
+}
+
+#[test]
+fn amnesia_evidence_not_conflicting() {
+    let (ctx, validators, signers, validator_set) = setup();
+    let address = validators[0].address;
+
+    // Prevote for the locked value
+    let same_value = AmnesiaEvidence::new(
+        precommit(
+            &ctx,
+            &signers[0],
+            Round::new(0),
+            NilOrVal::Val(ValueId::new(1)),
+            address,
+        ),
+        prevote(
+            &ctx,
+            &signers[0],
+            Round::new(1),
+            NilOrVal::Val(ValueId::new(1)),
+            address,
+        ),
+    );
+
+    // Prevote for another value in an earlier round
+    let earlier_round = AmnesiaEvidence::new(
+        precommit(
+            &ctx,
+            &signers[0],
+            Round::new(1),
+            NilOrVal::Val(ValueId::new(1)),
+            address,
+        ),
+        prevote(
+            &ctx,
+            &signers[0],
+            Round::new(0),
+            NilOrVal::Val(ValueId::new(2)),
+            address,
+        ),
+    );
+
+    // Nil prevote
+    let nil_prevote = AmnesiaEvidence::new(
+        precommit(
+            &ctx,
+            &signers[0],
+            Round::new(0),
+            NilOrVal::Val(ValueId::new(1)),
+            address,
+        ),
+        prevote(&ctx, &signers[0], Round::new(1), NilOrVal::Nil, address),
+    );
+
+    for evidence in [same_value, earlier_round, nil_prevote] {
+        assert_eq!(
+            block_on(signers[1].verify_amnesia_evidence(&evidence, &[], &validator_set)),
+            Err(EvidenceError::NotConflicting)
+        );
+    }
+}
+
+#[test]
+fn amnesia_evidence_unexpected_vote_type() {
+    let (ctx, validators, signers, validator_set) = setup();
+    let address = validators[0].address;
+
+    let evidence = AmnesiaEvidence::new(
+        prevote(
+            &ctx,
+            &signers[0],
+            Round::new(0),
+            NilOrVal::Val(ValueId::new(1)),
+            address,
+        ),
+        prevote(
+            &ctx,
+            &signers[0],
+            Round::new(1),
+            NilOrVal::Val(ValueId::new(2)),
+            address,
+        ),
+    );
+
+    assert_eq!(
+        block_on(signers[1].verify_amnesia_evidence(&evidence, &[], &validator_set)),
+        Err(EvidenceError::UnexpectedVoteType {
+            expected: VoteType::Precommit,
+            actual: VoteType::Prevote,
+        })
+    );
+}
+
+#[test]
+fn amnesia_evidence_invalid_signature() {
+    let (ctx, validators, signers, validator_set) = setup();
+    let address = validators[0].address;
+
+    let mut evidence = amnesia_evidence(&ctx, &signers[0], address);
+    evidence.prevote.signature = Signature::test();
+
+    assert_eq!(
+        block_on(signers[1].verify_amnesia_evidence(&evidence, &[], &validator_set)),
+        Err(EvidenceError::InvalidSignature(address))
+    );
+}
+
+#[test]
+fn verify_any_evidence() {
+    let (ctx, validators, signers, validator_set) = setup();
+    let address = validators[0].address;
+
+    let double_vote = DoubleVoteEvidence::new(
+        prevote(
+            &ctx,
+            &signers[0],
+            Round::new(0),
+            NilOrVal::Val(ValueId::new(1)),
+            address,
+        ),
+        prevote(&ctx, &signers[0], Round::new(0), NilOrVal::Nil, address),
+    );
+
+    let double_proposal = DoubleProposalEvidence::new(
+        proposal(&signers[0], Round::new(0), 1, address),
+        proposal(&signers[0], Round::new(0), 2, address),
+    );
+
+    let amnesia = amnesia_evidence(&ctx, &signers[0], address);
+
+    for evidence in [
+        Evidence::from(double_vote),
+        Evidence::from(double_proposal),
+        Evidence::from(amnesia.clone()),
+    ] {
+        assert_eq!(evidence.validator_address(), &address);
+        assert_eq!(
+            block_on(signers[1].verify_evidence(&evidence, &[], &validator_set)),
+            Ok(())
+        );
+    }
+
+    // Polka certificates are taken into account for evidence of lock violation
+    let certificate =
+        polka_certificate(&ctx, &signers, &validators, Round::new(1), ValueId::new(2));
+
+    assert_eq!(
+        block_on(signers[1].verify_evidence(
+            &Evidence::Amnesia(amnesia),
+            &[certificate],
+            &validator_set
+        )),
+        Err(EvidenceError::JustifiedByPolka(Round::new(1)))
+    );
+}
+
+/// The misbehavior the sender of the given evidence is reported for,
+/// which must fail verification against the given polka certificates.
+fn reported_misbehavior(
+    signer: &Ed25519Provider,
+    evidence: impl Into<Evidence<TestContext>>,
+    polka_certificates: &[PolkaCertificate<TestContext>],
+    validator_set: &ValidatorSet,
+) -> Option<Misbehavior> {
+    let error =
+        block_on(signer.verify_evidence(&evidence.into(), polka_certificates, validator_set))
+            .unwrap_err();
+
+    misbehavior_of_invalid_evidence(&error)
+}
+
+#[test]
+fn evidence_with_invalid_signature_reports_sender() {
+    let (ctx, validators, signers, validator_set) = setup();
+    let address = validators[0].address;
+
+    let mut evidence = amnesia_evidence(&ctx, &signers[0], address);
+    evidence.prevote.signature = Signature::test();
+
+    assert_eq!(
+        reported_misbehavior(&signers[1], evidence, &[], &validator_set),
+        Some(Misbehavior::InvalidSignature)
+    );
+}
+
+#[test]
+fn malformed_evidence_reports_sender() {
+    let (ctx, validators, signers, validator_set) = setup();
+    let address = validators[0].address;
+
+    let nil_prevote = |round| prevote(&ctx, &signers[0], Round::new(round), NilOrVal::Nil, address);
+    let value_prevote = |round| {
+        prevote(
+            &ctx,
+            &signers[0],
+            Round::new(round),
+            NilOrVal::Val(ValueId::new(1)),
+            address,
+        )
+    };
+
+    let next_height_prevote = block_on(signers[0].sign_vote(ctx.new_prevote(
+        Height::new(2),
+        Round::new(0),
+        NilOrVal::Val(ValueId::new(1)),
+        address,
+    )))
+    .unwrap();
+
+    let malformed = [
+        // Mismatched validator
+        Evidence::from(DoubleVoteEvidence::new(
+            nil_prevote(0),
+            prevote(
+                &ctx,
+                &signers[1],
+                Round::new(0),
+                NilOrVal::Val(ValueId::new(1)),
+                validators[1].address,
+            ),
+        )),
+        // Mismatched height
+        Evidence::from(DoubleVoteEvidence::new(nil_prevote(0), next_height_prevote)),
+        // Mismatched round
+        Evidence::from(DoubleVoteEvidence::new(nil_prevote(0), value_prevote(1))),
+        // Mismatched vote type
+        Evidence::from(DoubleVoteEvidence::new(
+            nil_prevote(0),
+            precommit(
+                &ctx,
+                &signers[0],
+                Round::new(0),
+                NilOrVal::Val(ValueId::new(1)),
+                address,
+            ),
+        )),
+        // Unexpected vote type
+        Evidence::from(AmnesiaEvidence::new(value_prevote(0), value_prevote(1))),
+        // Not conflicting
+        Evidence::from(DoubleVoteEvidence::new(nil_prevote(0), nil_prevote(0))),
+    ];
+
+    for evidence in malformed {
+        assert_eq!(
+            reported_misbehavior(&signers[1], evidence, &[], &validator_set),
+            Some(Misbehavior::InvalidMessage)
+        );
+    }
+}
+
+#[test]
+fn evidence_justified_by_polka_is_dropped() {
+    let (ctx, validators, signers, validator_set) = setup();
+    let evidence = amnesia_evidence(&ctx, &signers[0], validators[0].address);
+
+    // The sender may not have observed the polka which justifies the prevote
+    let certificate =
+        polka_certificate(&ctx, &signers, &validators, Round::new(1), ValueId::new(2));
+
+    assert_eq!(
+        reported_misbehavior(&signers[1], evidence, &[certificate], &validator_set),
+        None
+    );
+}
+
+#[test]
+fn evidence_for_unknown_validator_is_dropped() {
+    let (ctx, _, signers, validator_set) = setup();
+
+    let (outsider, outsider_key) = utils::validators::make_validators_seeded([10], SEED + 1)
+        .into_iter()
+        .next()
+        .unwrap();
+
+    let evidence = amnesia_evidence(&ctx, &Ed25519Provider::new(outsider_key), outsider.address);
+
+    assert_eq!(
+        reported_misbehavior(&signers[0], evidence, &[], &validator_set),
+        None
+    );
+}
+
+#[test]
+fn evidence_failing_verification_is_dropped() {
+    // The signing provider of this node failed to verify the evidence
+    let error = EvidenceError::<TestContext>::VerificationError(None);
+
+    assert_eq!(misbehavior_of_invalid_evidence(&error), None);
+}
+
+#[test]
+fn misbehavior_evidence_dedup_by_misbehavior() {
+    let (ctx, validators, signers, _) = setup();
+    let address = validators[0].address;
+
+    let double_vote = |round: u32, vote_type: VoteType, values: [u64; 2]| {
+        let [first, second] = values.map(|value| {
+            let value_id = NilOrVal::Val(ValueId::new(value));
+            match vote_type {
+                VoteType::Prevote => {
+                    prevote(&ctx, &signers[0], Round::new(round), value_id, address)
+                }
+                VoteType::Precommit => {
+                    precommit(&ctx, &signers[0], Round::new(round), value_id, address)
+                }
+            }
+        });
+
+        Evidence::from(DoubleVoteEvidence::new(first, second))
+    };
+
+    let double_proposal = |round: u32, values: [u64; 2]| {
+        let [first, second] =
+            values.map(|value| proposal(&signers[0], Round::new(round), value, address));
+
+        Evidence::from(DoubleProposalEvidence::new(first, second))
+    };
+
+    let mut evidence = MisbehaviorEvidence::<TestContext>::default();
+
+    evidence.add(double_vote(0, VoteType::Prevote, [1, 2]));
+    evidence.add(double_proposal(0, [1, 2]));
+
+    // Other messages proving the same misbehavior are ignored
+    evidence.add(double_vote(0, VoteType::Prevote, [1, 3]));
+    evidence.add(double_vote(0, VoteType::Prevote, [2, 1]));
+    evidence.add(double_proposal(0, [3, 4]));
+
+    assert_eq!(evidence.votes.get(&address).map(Vec::len), Some(1));
+    assert_eq!(evidence.proposals.get(&address).map(Vec::len), Some(1));
+
+    // But not the evidence of another misbehavior of the same validator
+    evidence.add(double_vote(0, VoteType::Precommit, [1, 2]));
+    evidence.add(double_vote(1, VoteType::Prevote, [1, 2]));
+    evidence.add(double_proposal(1, [1, 2]));
+
+    assert_eq!(evidence.votes.get(&address).map(Vec::len), Some(3));
+    assert_eq!(evidence.proposals.get(&address).map(Vec::len), Some(2));
+}
+
+#[test]
+fn double_vote_and_double_proposal_evidence_protobuf_roundtrip() {
+    let ctx = BlsContext;
+    let signer = BlsSigningProvider::new(PrivateKey::from([1; 32]), BlsSignBytes);
+    let address = Address::new([1; 20]);
+    let (height, round) = (Height::new(1), Round::new(0));
+
+    let [first, second] = [1, 2].map(|value| {
+        let vote = ctx.new_prevote(height, round, NilOrVal::Val(ValueId::new(value)), address);
+        block_on(signer.sign_vote(vote)).unwrap()
+    });
+
+    let evidence = DoubleVoteEvidence::<BlsContext>::new(first, second);
+    let bytes = evidence.to_bytes().unwrap();
+    assert_eq!(DoubleVoteEvidence::from_bytes(&bytes).unwrap(), evidence);
+
+    let [first, second] = [1, 2].map(|value| {
+        let proposal = ctx.new_proposal(height, round, Value::new(value), Round::Nil, address);
+        block_on(signer.sign_proposal(proposal)).unwrap()
+    });
+
+    let evidence = DoubleProposalEvidence::<BlsContext>::new(first, second);
+    let bytes = evidence.to_bytes().unwrap();
+    assert_eq!(
+        DoubleProposalEvidence::from_bytes(&bytes).unwrap(),
+        evidence
+    );
+}
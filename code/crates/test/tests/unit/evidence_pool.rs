@@ -0,0 +1,240 @@
+use std::path::Path;
+
+use ractor::{Actor, ActorRef};
+use tokio::task::JoinHandle;
+
+use informalsystems_malachitebft_test::codec::proto::ProtobufCodec;
+use informalsystems_malachitebft_test::{Address, Height, Signature, TestContext, ValueId, Vote};
+use malachitebft_core_types::{
+    AmnesiaEvidence, DoubleVoteEvidence, Evidence, NilOrVal, Round, SignedVote,
+};
+use malachitebft_engine::evidence::{
+    Args, EvidenceOrigin, EvidencePool, Msg, MAX_EVIDENCE_PER_VALIDATOR_AND_HEIGHT,
+};
+
+type PoolRef = ActorRef<Msg<TestContext>>;
+
+async fn spawn_pool(path: &Path) -> (PoolRef, JoinHandle<()>) {
+    let args = Args {
+        path: path.join("evidence.log"),
+        codec: ProtobufCodec,
+    };
+
+    let pool = EvidencePool::<TestContext, ProtobufCodec>::new(tracing::Span::none());
+    Actor::spawn(None, pool, args).await.unwrap()
+}
+
+async fn restart_pool(
+    pool: PoolRef,
+    handle: JoinHandle<()>,
+    path: &Path,
+) -> (PoolRef, JoinHandle<()>) {
+    pool.stop(None);
+    handle.await.unwrap();
+    spawn_pool(path).await
+}
+
+fn vote(vote: Vote, sig: u8) -> SignedVote<TestContext> {
+    SignedVote::new(vote, Signature::from_bytes([sig; 64]))
+}
+
+fn double_vote(address: Address) -> Evidence<TestContext> {
+    double_vote_at(Height::new(1), address)
+}
+
+fn double_vote_at(height: Height, address: Address) -> Evidence<TestContext> {
+    let value = NilOrVal::Val(ValueId::new(1));
+
+    Evidence::from(DoubleVoteEvidence::new(
+        vote(Vote::new_prevote(height, Round::new(0), value, address), 1),
+        vote(
+            Vote::new_prevote(height, Round::new(0), NilOrVal::Nil, address),
+            2,
+        ),
+    ))
+}
+
+fn amnesia(address: Address) -> Evidence<TestContext> {
+    Evidence::from(AmnesiaEvidence::new(
+        vote(
+            Vote::new_precommit(
+                Height::new(1),
+                Round::new(0),
+                NilOrVal::Val(ValueId::new(1)),
+                address,
+            ),
+            1,
+        ),
+        vote(
+            Vote::new_prevote(
+                Height::new(1),
+                Round::new(1),
+                NilOrVal::Val(ValueId::new(2)),
+                address,
+            ),
+            2,
+        ),
+    ))
+}
+
+async fn insert(pool: &PoolRef, evidence: &Evidence<TestContext>, origin: EvidenceOrigin) -> bool {
+    ractor::call!(pool, Msg::Insert, evidence.clone(), origin).unwrap()
+}
+
+async fn contains(pool: &PoolRef, evidence: &Evidence<TestContext>) -> bool {
+    ractor::call!(pool, Msg::Contains, evidence.clone()).unwrap()
+}
+
+async fn take_pending(pool: &PoolRef) -> Vec<Evidence<TestContext>> {
+    ractor::call!(pool, Msg::TakePending).unwrap()
+}
+
+async fn prune(pool: &PoolRef, min_height: Height) {
+    pool.cast(Msg::Prune(min_height)).unwrap();
+}
+
+#[tokio::test]
+async fn evidence_is_deduplicated() {
+    let dir = tempfile::tempdir().unwrap();
+    let (pool, _) = spawn_pool(dir.path()).await;
+
+    let evidence = double_vote(Address::new([1; 20]));
+
+    assert!(!contains(&pool, &evidence).await);
+    assert!(insert(&pool, &evidence, EvidenceOrigin::Peer).await);
+    assert!(contains(&pool, &evidence).await);
+    assert!(!insert(&pool, &evidence, EvidenceOrigin::Peer).await);
+    assert!(!insert(&pool, &evidence, EvidenceOrigin::Local).await);
+}
+
+#[tokio::test]
+async fn swapped_double_votes_are_deduplicated() {
+    let dir = tempfile::tempdir().unwrap();
+    let (pool, _) = spawn_pool(dir.path()).await;
+
+    let Evidence::DoubleVote(evidence) = double_vote(Address::new([1; 20])) else {
+        unreachable!()
+    };
+
+    let swapped = Evidence::from(DoubleVoteEvidence::new(
+        evidence.second.clone(),
+        evidence.first.clone(),
+    ));
+
+    assert!(insert(&pool, &Evidence::from(evidence), EvidenceOrigin::Peer).await);
+    assert!(contains(&pool, &swapped).await);
+    assert!(!insert(&pool, &swapped, EvidenceOrigin::Peer).await);
+    assert_eq!(take_pending(&pool).await.len(), 1);
+}
+
+#[tokio::test]
+async fn evidence_is_capped_per_validator_and_height() {
+    let dir = tempfile::tempdir().unwrap();
+    let (pool, _) = spawn_pool(dir.path()).await;
+
+    let address = Address::new([1; 20]);
+
+    let double_vote_in_round = |round: u32| {
+        let height = Height::new(1);
+        let round = Round::new(round);
+
+        Evidence::from(DoubleVoteEvidence::new(
+            vote(
+                Vote::new_prevote(height, round, NilOrVal::Val(ValueId::new(1)), address),
+                1,
+            ),
+            vote(Vote::new_prevote(height, round, NilOrVal::Nil, address), 2),
+        ))
+    };
+
+    let max = MAX_EVIDENCE_PER_VALIDATOR_AND_HEIGHT as u32;
+
+    for round in 0..max {
+        assert!(insert(&pool, &double_vote_in_round(round), EvidenceOrigin::Peer).await);
+    }
+
+    let extra = double_vote_in_round(max);
+    assert!(!insert(&pool, &extra, EvidenceOrigin::Peer).await);
+    assert!(!contains(&pool, &extra).await);
+
+    // Evidence for other validators and heights is still accepted
+    assert!(
+        insert(
+            &pool,
+            &double_vote(Address::new([2; 20])),
+            EvidenceOrigin::Peer
+        )
+        .await
+    );
+    assert!(
+        insert(
+            &pool,
+            &double_vote_at(Height::new(2), address),
+            EvidenceOrigin::Peer
+        )
+        .await
+    );
+}
+
+#[tokio::test]
+async fn only_evidence_from_peers_is_pending() {
+    let dir = tempfile::tempdir().unwrap();
+    let (pool, _) = spawn_pool(dir.path()).await;
+
+    let local = double_vote(Address::new([1; 20]));
+    let peer = amnesia(Address::new([2; 20]));
+
+    assert!(insert(&pool, &local, EvidenceOrigin::Local).await);
+    assert!(insert(&pool, &peer, EvidenceOrigin::Peer).await);
+
+    assert_eq!(take_pending(&pool).await, vec![peer.clone()]);
+    assert_eq!(take_pending(&pool).await, vec![]);
+
+    // Reported evidence is still known to the pool
+    assert!(contains(&pool, &peer).await);
+}
+
+#[tokio::test]
+async fn evidence_is_persisted_across_restarts() {
+    let dir = tempfile::tempdir().unwrap();
+    let (pool, handle) = spawn_pool(dir.path()).await;
+
+    let reported = double_vote(Address::new([1; 20]));
+    let pending = amnesia(Address::new([2; 20]));
+
+    assert!(insert(&pool, &reported, EvidenceOrigin::Peer).await);
+    assert_eq!(take_pending(&pool).await, vec![reported.clone()]);
+    assert!(insert(&pool, &pending, EvidenceOrigin::Peer).await);
+
+    let (pool, _) = restart_pool(pool, handle, dir.path()).await;
+
+    assert!(contains(&pool, &reported).await);
+    assert!(contains(&pool, &pending).await);
+    assert_eq!(take_pending(&pool).await, vec![pending]);
+}
+
+#[tokio::test]
+async fn old_evidence_is_pruned() {
+    let dir = tempfile::tempdir().unwrap();
+    let (pool, handle) = spawn_pool(dir.path()).await;
+
+    let old = double_vote_at(Height::new(1), Address::new([1; 20]));
+    let recent = double_vote_at(Height::new(5), Address::new([2; 20]));
+    let pending = double_vote_at(Height::new(6), Address::new([3; 20]));
+
+    assert!(insert(&pool, &old, EvidenceOrigin::Peer).await);
+    assert!(insert(&pool, &recent, EvidenceOrigin::Local).await);
+    assert!(insert(&pool, &pending, EvidenceOrigin::Peer).await);
+
+    prune(&pool, Height::new(5)).await;
+
+    assert!(!contains(&pool, &old).await);
+    assert!(contains(&pool, &recent).await);
+
+    // Pruned evidence stays pruned after a restart, and the status of the rest is kept
+    let (pool, _) = restart_pool(pool, handle, dir.path()).await;
+
+    assert!(!contains(&pool, &old).await);
+    assert!(contains(&pool, &recent).await);
+    assert_eq!(take_pending(&pool).await, vec![pending]);
+}
@@ -0,0 +1,199 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use informalsystems_malachitebft_test::PrivateKey;
+use malachitebft_keystore::scrypt::scrypt;
+use malachitebft_keystore::{
+    is_encrypted, parse_key_file, EncryptedKey, Error, Passphrase, ScryptParams,
+};
+use malachitebft_signing_bls::PrivateKey as BlsPrivateKey;
+
+/// Cheap parameters, so that the tests run quickly.
+const PARAMS: ScryptParams = ScryptParams {
+    log_n: 4,
+    r: 1,
+    p: 1,
+};
+
+fn passphrase(passphrase: &str) -> Passphrase {
+    Passphrase::new(passphrase).unwrap()
+}
+
+fn rng() -> StdRng {
+    StdRng::seed_from_u64(0x42)
+}
+
+#[track_caller]
+fn check_scrypt(password: &str, salt: &str, params: ScryptParams, expected: &str) {
+    let mut output = [0; 64];
+    scrypt(password.as_bytes(), salt.as_bytes(), &params, &mut output).unwrap();
+    assert_eq!(hex::encode(output), expected);
+}
+
+#[test]
+fn scrypt_test_vectors() {
+    // From section 12 of RFC 7914
+    check_scrypt(
+        "",
+        "",
+        ScryptParams::new(4, 1, 1).unwrap(),
+        "77d6576238657b203b19ca42c18a0497f16b4844e3074ae8dfdffa3fede21442\
+         fcd0069ded0948f8326a753a0fc81f17e8d3e0fb2e0d3628cf35e20c38d18906",
+    );
+
+    check_scrypt(
+        "password",
+        "NaCl",
+        ScryptParams::new(10, 8, 16).unwrap(),
+        "fdbabe1c9d3472007856e7190d01e9fe7c6ad7cbc8237830e77376634b373162\
+         2eaf30d92e22a3886ff109279d9830dac727afb94a83ee6d8360cbdfa2cc0640",
+    );
+}
+
+#[test]
+fn invalid_params() {
+    let cases = [(0, 8, 1), (4, 0, 1), (4, 8, 0), (64, 8, 1), (21, 8, 1)];
+
+    for (log_n, r, p) in cases {
+        let result = ScryptParams::new(log_n, r, p);
+        assert!(
+            matches!(result, Err(Error::InvalidParams(_))),
+            "log_n={log_n}, r={r}, p={p}"
+        );
+    }
+
+    assert!(ScryptParams::RECOMMENDED.validate().is_ok());
+}
+
+#[test]
+fn encrypt_decrypt() {
+    let plaintext = b"secret key material";
+    let encrypted =
+        EncryptedKey::encrypt(plaintext, &passphrase("hunter2"), PARAMS, &mut rng()).unwrap();
+
+    let decrypted = encrypted.decrypt(&passphrase("hunter2")).unwrap();
+    assert_eq!(decrypted.as_slice(), plaintext);
+    assert_eq!(encrypted.params(), &PARAMS);
+
+    // The salt and nonce are fresh for each encryption
+    let other = EncryptedKey::encrypt(
+        plaintext,
+        &passphrase("hunter2"),
+        PARAMS,
+        &mut rand::thread_rng(),
+    )
+    .unwrap();
+    assert_ne!(encrypted, other);
+}
+
+#[test]
+fn wrong_passphrase() {
+    let encrypted =
+        EncryptedKey::encrypt(b"secret", &passphrase("hunter2"), PARAMS, &mut rng()).unwrap();
+
+    let result = encrypted.decrypt(&passphrase("hunter3"));
+    assert!(matches!(result, Err(Error::Decryption)));
+}
+
+#[test]
+fn tampered_key_file() {
+    let encrypted =
+        EncryptedKey::encrypt(b"secret", &passphrase("hunter2"), PARAMS, &mut rng()).unwrap();
+    let json = serde_json::to_value(&encrypted).unwrap();
+
+    let tamper = |pointer: &str| {
+        let mut json = json.clone();
+        let field = json.pointer_mut(pointer).unwrap();
+        let mut bytes = hex::decode(field.as_str().unwrap()).unwrap();
+        bytes[0] ^= 1;
+        *field = hex::encode(bytes).into();
+
+        let tampered: EncryptedKey = serde_json::from_value(json).unwrap();
+        tampered.decrypt(&passphrase("hunter2"))
+    };
+
+    assert!(matches!(tamper("/ciphertext"), Err(Error::Decryption)));
+    assert!(matches!(tamper("/cipher/nonce"), Err(Error::Decryption)));
+    assert!(matches!(tamper("/kdf/salt"), Err(Error::Decryption)));
+
+    let mut json = json.clone();
+    json["version"] = 2.into();
+    let encrypted: EncryptedKey = serde_json::from_value(json.clone()).unwrap();
+    assert!(matches!(
+        encrypted.decrypt(&passphrase("hunter2")),
+        Err(Error::UnsupportedVersion(2))
+    ));
+
+    json["version"] = 1.into();
+    json["cipher"]["function"] = "aes-256-gcm".into();
+    let encrypted: EncryptedKey = serde_json::from_value(json).unwrap();
+    assert!(matches!(
+        encrypted.decrypt(&passphrase("hunter2")),
+        Err(Error::UnsupportedAlgorithm { .. })
+    ));
+}
+
+#[test]
+fn ed25519_key_file() {
+    let private_key = PrivateKey::generate(rng());
+    let encrypted =
+        EncryptedKey::encrypt_json(&private_key, &passphrase("hunter2"), PARAMS, &mut rng())
+            .unwrap();
+
+    let contents = serde_json::to_string_pretty(&encrypted).unwrap();
+    assert!(is_encrypted(&contents));
+
+    let decrypted: PrivateKey = parse_key_file(&contents, Some(&passphrase("hunter2"))).unwrap();
+    assert_eq!(decrypted.public_key(), private_key.public_key());
+
+    let result = parse_key_file::<PrivateKey>(&contents, None);
+    assert!(matches!(result, Err(Error::MissingPassphrase(_))));
+}
+
+#[test]
+fn bls_key_file() {
+    let private_key = BlsPrivateKey::generate(rng());
+    let encrypted =
+        EncryptedKey::encrypt_json(&private_key, &passphrase("hunter2"), PARAMS, &mut rng())
+            .unwrap();
+
+    let contents = serde_json::to_string(&encrypted).unwrap();
+    let decrypted: BlsPrivateKey = parse_key_file(&contents, Some(&passphrase("hunter2"))).unwrap();
+    assert_eq!(decrypted.to_bytes(), private_key.to_bytes());
+}
+
+#[test]
+fn plain_key_file() {
+    let private_key = PrivateKey::generate(rng());
+    let contents = serde_json::to_string_pretty(&private_key).unwrap();
+    assert!(!is_encrypted(&contents));
+
+    // A plain key file does not need a passphrase
+    let loaded: PrivateKey = parse_key_file(&contents, None).unwrap();
+    assert_eq!(loaded.public_key(), private_key.public_key());
+}
+
+#[test]
+fn passphrase_sources() {
+    assert!(matches!(Passphrase::new(""), Err(Error::EmptyPassphrase)));
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("passphrase");
+    std::fs::write(&path, "hunter2\n").unwrap();
+
+    let from_file = Passphrase::from_file(&path).unwrap();
+    assert_eq!(from_file.as_bytes(), b"hunter2");
+    assert_eq!(format!("{from_file:?}"), "Passphrase(<redacted>)");
+
+    // Use variables specific to this test, as the environment is shared by all of them
+    let name = "MALACHITE_TEST_KEYSTORE_PASSPHRASE";
+    assert!(Passphrase::from_env_var(name).unwrap().is_none());
+
+    std::env::set_var(format!("{name}_FILE"), &path);
+    let from_env = Passphrase::from_env_var(name).unwrap().unwrap();
+    assert_eq!(from_env.as_bytes(), b"hunter2");
+
+    std::env::set_var(name, "correct horse");
+    let from_env = Passphrase::from_env_var(name).unwrap().unwrap();
+    assert_eq!(from_env.as_bytes(), b"correct horse");
+}
@@ -0,0 +1,285 @@
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use futures::executor::block_on;
+
+use informalsystems_malachitebft_test::utils::validators::make_validators_seeded;
+use informalsystems_malachitebft_test::{
+    Address, Ed25519Provider, Height, PrivateKey, TestContext, Validator, ValidatorSet, ValueId,
+    Vote,
+};
+use malachitebft_core_types::{BoxError, CommitCertificate, NilOrVal, Round};
+use malachitebft_light_client::{Error, LightBlock, Options, Provider, Verifier};
+use malachitebft_signing::SigningProvider;
+
+type Block = LightBlock<TestContext>;
+
+/// A pool of validators, each with voting power 1,
+/// out of which the validator set of each height is picked.
+struct Pool {
+    validators: Vec<(Validator, Ed25519Provider)>,
+}
+
+impl Pool {
+    fn new() -> Self {
+        let validators = make_validators_seeded([1; 20], 0xfeedbeef)
+            .into_iter()
+            .map(|(v, pk)| (v, Ed25519Provider::new(pk)))
+            .collect();
+
+        Self { validators }
+    }
+
+    fn validator_set(&self, indices: impl IntoIterator<Item = usize>) -> ValidatorSet {
+        ValidatorSet::new(indices.into_iter().map(|i| self.validators[i].0.clone()))
+    }
+
+    /// A signing provider to verify signatures with.
+    fn signing_provider(&self) -> Ed25519Provider {
+        Ed25519Provider::new(PrivateKey::from([0; 32]))
+    }
+
+    fn signer(&self, address: &Address) -> &Ed25519Provider {
+        &self
+            .validators
+            .iter()
+            .find(|(v, _)| &v.address == address)
+            .unwrap()
+            .1
+    }
+
+    /// Build a light block at the given height, whose certificate is signed by every validator
+    /// of its validator set, for the given value.
+    fn light_block(
+        &self,
+        height: u64,
+        value: u64,
+        validator_set: &ValidatorSet,
+        next_validator_set: &ValidatorSet,
+    ) -> Block {
+        let (height, round, value_id) = (Height::new(height), Round::new(0), ValueId::new(value));
+
+        let votes = validator_set
+            .iter()
+            .map(|v| {
+                let vote = Vote::new_precommit(height, round, NilOrVal::Val(value_id), v.address);
+                block_on(self.signer(&v.address).sign_vote(vote)).unwrap()
+            })
+            .collect();
+
+        LightBlock::new(
+            CommitCertificate::new(height, round, value_id, votes),
+            validator_set.clone(),
+            next_validator_set.clone(),
+        )
+    }
+
+    /// Build a chain of light blocks from height 1 up to the given height, where the validator set
+    /// at height `h` is made of the validators `h..h + 4` of the pool, ie. one validator leaves
+    /// and a new one joins at every height.
+    fn rotating_chain(&self, up_to: u64) -> Vec<Block> {
+        let validator_set = |h: u64| self.validator_set(h as usize..h as usize + 4);
+
+        (1..=up_to)
+            .map(|h| self.light_block(h, h, &validator_set(h), &validator_set(h + 1)))
+            .collect()
+    }
+
+    /// Build a chain of light blocks from height 1 up to the given height, with a fixed validator set
+    /// and where the value decided at height `h` is `value(h)`.
+    fn fixed_chain(&self, up_to: u64, value: impl Fn(u64) -> u64) -> Vec<Block> {
+        let validator_set = self.validator_set(0..4);
+
+        (1..=up_to)
+            .map(|h| self.light_block(h, value(h), &validator_set, &validator_set))
+            .collect()
+    }
+
+    fn verifier(&self) -> Verifier<TestContext, Ed25519Provider, impl Fn(&Block) -> bool> {
+        Verifier::new(
+            TestContext::new(),
+            self.signing_provider(),
+            |_: &Block| true,
+            Options::default(),
+        )
+    }
+}
+
+struct ChainProvider {
+    light_blocks: BTreeMap<Height, Block>,
+}
+
+impl ChainProvider {
+    fn new(chain: &[Block]) -> Self {
+        Self {
+            light_blocks: chain.iter().map(|b| (b.height(), b.clone())).collect(),
+        }
+    }
+}
+
+#[async_trait]
+impl Provider<TestContext> for ChainProvider {
+    async fn light_block(&self, height: Height) -> Result<Block, BoxError> {
+        self.light_blocks
+            .get(&height)
+            .cloned()
+            .ok_or_else(|| BoxError::new("no light block at that height".into()))
+    }
+}
+
+#[test]
+fn verify_sequential_chain() {
+    let pool = Pool::new();
+    let chain = pool.rotating_chain(10);
+    let verifier = pool.verifier();
+
+    let trusted = block_on(verifier.verify_chain(&chain[0], &chain[1..])).unwrap();
+    assert_eq!(trusted, &chain[9]);
+}
+
+#[test]
+fn reject_non_increasing_height() {
+    let pool = Pool::new();
+    let chain = pool.rotating_chain(3);
+    let verifier = pool.verifier();
+
+    let result = block_on(verifier.verify(&chain[1], &chain[0]));
+    assert!(matches!(result, Err(Error::NonIncreasingHeight { .. })));
+}
+
+#[test]
+fn reject_unexpected_validator_set() {
+    let pool = Pool::new();
+    let chain = pool.rotating_chain(2);
+    let verifier = pool.verifier();
+
+    // Signed by a quorum of its own validator set, which is not the one expected by the trusted block
+    let other_set = pool.validator_set(8..12);
+    let forged = pool.light_block(2, 2, &other_set, &other_set);
+
+    let result = block_on(verifier.verify(&chain[0], &forged));
+    assert!(matches!(result, Err(Error::ValidatorSetMismatch(h)) if h == Height::new(2)));
+}
+
+#[test]
+fn reject_missing_signatures() {
+    let pool = Pool::new();
+    let chain = pool.rotating_chain(2);
+    let verifier = pool.verifier();
+
+    let mut untrusted = chain[1].clone();
+    untrusted.certificate.commit_signatures.truncate(2);
+
+    let result = block_on(verifier.verify(&chain[0], &untrusted));
+    assert!(matches!(result, Err(Error::InvalidCertificate(..))));
+}
+
+#[test]
+fn reject_invalid_validator_set_commitment() {
+    let pool = Pool::new();
+    let chain = pool.rotating_chain(2);
+
+    let verifier = Verifier::new(
+        TestContext::new(),
+        pool.signing_provider(),
+        |b: &Block| b.height() < Height::new(2),
+        Options::default(),
+    );
+
+    let result = block_on(verifier.verify(&chain[0], &chain[1]));
+    assert!(matches!(
+        result,
+        Err(Error::InvalidValidatorSetCommitment(_))
+    ));
+}
+
+#[test]
+fn skip_verification_with_enough_trust() {
+    let pool = Pool::new();
+    let chain = pool.rotating_chain(10);
+    let verifier = pool.verifier();
+
+    // The next validator set of height 1 is 2..6, and height 4 is signed by 4..8,
+    // ie. 2 out of 4 trusted validators
+    block_on(verifier.verify(&chain[0], &chain[3])).unwrap();
+
+    // Height 5 is signed by 5..9, ie. 1 out of 4 trusted validators
+    let result = block_on(verifier.verify(&chain[0], &chain[4]));
+
+    assert!(matches!(
+        result,
+        Err(Error::NotEnoughTrust {
+            signed: 1,
+            total: 4,
+            expected: 2,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn bisection_to_target_height() {
+    let pool = Pool::new();
+    let chain = pool.rotating_chain(12);
+    let verifier = pool.verifier();
+    let provider = ChainProvider::new(&chain);
+
+    let trace =
+        block_on(verifier.verify_to_height(&provider, chain[0].clone(), Height::new(12))).unwrap();
+
+    let heights: Vec<_> = trace.iter().map(|b| b.height().as_u64()).collect();
+
+    // Each step can skip at most 2 heights ahead
+    assert_eq!(heights.last(), Some(&12));
+    assert!(heights.len() < 11);
+    assert!(heights.windows(2).all(|w| w[1] - w[0] <= 3));
+    assert!(heights[0] - 1 <= 3);
+}
+
+#[test]
+fn bisection_fails_on_missing_light_block() {
+    let pool = Pool::new();
+    let chain = pool.rotating_chain(12);
+    let verifier = pool.verifier();
+
+    let provider = ChainProvider::new(&[chain[0].clone(), chain[11].clone()]);
+
+    let result = block_on(verifier.verify_to_height(&provider, chain[0].clone(), Height::new(12)));
+    assert!(matches!(result, Err(Error::Provider(..))));
+}
+
+#[test]
+fn no_fork_between_identical_chains() {
+    let pool = Pool::new();
+    let chain = pool.fixed_chain(5, |h| h);
+    let verifier = pool.verifier();
+
+    let fork = block_on(verifier.detect_fork(&chain[0], &chain[1..], &chain[2..])).unwrap();
+    assert_eq!(fork, None);
+}
+
+#[test]
+fn detect_fork_between_chains() {
+    let pool = Pool::new();
+    let verifier = pool.verifier();
+
+    let primary = pool.fixed_chain(5, |h| h);
+    let witness = pool.fixed_chain(5, |h| if h < 3 { h } else { 100 + h });
+
+    // The witness skips height 2
+    let fork = block_on(verifier.detect_fork(&primary[0], &primary[1..], &witness[2..]))
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(fork.height(), Height::new(3));
+    assert_eq!(fork.primary, primary[2]);
+    assert_eq!(fork.witness, witness[2]);
+
+    let mut signers: Vec<_> = pool.validator_set(0..4).iter().map(|v| v.address).collect();
+    signers.sort();
+
+    assert_eq!(
+        fork.conflicting_signers(),
+        signers.iter().collect::<Vec<_>>()
+    );
+}
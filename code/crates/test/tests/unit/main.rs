@@ -1,2 +1,19 @@
 mod certificates;
+mod connection_gater;
+mod evidence;
+mod evidence_pool;
+mod keystore;
+mod light_client;
+mod network_config;
+mod peer_reputation;
+mod proposer;
+mod rate_limit;
+mod remote_signer;
+mod signing_bls;
+mod signing_ed25519;
+mod signing_guard;
+mod signing_pkcs11;
 mod sync;
+mod threshold_signer;
+mod validator_mesh;
+mod validator_set_update;
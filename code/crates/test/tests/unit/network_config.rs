@@ -0,0 +1,60 @@
+use std::path::Path;
+use std::time::Duration;
+
+use malachitebft_app::spawn::make_gossip_config;
+use malachitebft_config::{self as config, ConsensusConfig};
+use malachitebft_network::{BootstrapProtocol, Selector};
+
+#[test]
+fn discovery_settings_are_passed_to_the_network() {
+    let mut cfg = ConsensusConfig::default();
+    cfg.p2p.listen_addr = "/ip4/127.0.0.1/udp/27000/quic-v1".parse().unwrap();
+
+    let discovery = &mut cfg.p2p.discovery;
+    discovery.enabled = true;
+    discovery.bootstrap_protocol = config::BootstrapProtocol::Full;
+    discovery.selector = config::Selector::Latency;
+    discovery.num_outbound_peers = 7;
+    discovery.num_inbound_peers = 11;
+    discovery.max_connections_per_peer = 2;
+    discovery.max_outbound_peers_per_subnet = 1;
+    discovery.ephemeral_connection_timeout = Duration::from_secs(9);
+
+    let network = make_gossip_config(&cfg, Path::new("/tmp/node")).discovery;
+
+    assert!(network.enabled);
+    assert_eq!(network.bootstrap_protocol, BootstrapProtocol::Full);
+    assert_eq!(network.selector, Selector::Latency);
+    assert_eq!(network.num_outbound_peers, 7);
+    assert_eq!(network.num_inbound_peers, 11);
+    assert_eq!(network.max_connections_per_peer, 2);
+    assert_eq!(network.max_outbound_peers_per_subnet, 1);
+    assert_eq!(network.ephemeral_connection_timeout, Duration::from_secs(9));
+}
+
+#[test]
+fn peer_exchange_settings_are_passed_to_the_network() {
+    let mut cfg = ConsensusConfig::default();
+    cfg.p2p.listen_addr = "/ip4/127.0.0.1/udp/27000/quic-v1".parse().unwrap();
+    cfg.p2p.discovery.bootstrap_protocol = config::BootstrapProtocol::Pex;
+    cfg.p2p.discovery.pex_interval = Duration::from_secs(5);
+
+    let network = make_gossip_config(&cfg, Path::new("/tmp/node")).discovery;
+
+    assert_eq!(network.bootstrap_protocol, BootstrapProtocol::Pex);
+    assert_eq!(network.pex_interval, Duration::from_secs(5));
+}
+
+#[test]
+fn signed_peer_records_can_be_optional() {
+    let mut cfg = ConsensusConfig::default();
+    cfg.p2p.listen_addr = "/ip4/127.0.0.1/udp/27000/quic-v1".parse().unwrap();
+
+    let network = make_gossip_config(&cfg, Path::new("/tmp/node")).discovery;
+    assert!(network.require_signed_peer_records);
+
+    cfg.p2p.discovery.require_signed_peer_records = false;
+
+    let network = make_gossip_config(&cfg, Path::new("/tmp/node")).discovery;
+    assert!(!network.require_signed_peer_records);
+}
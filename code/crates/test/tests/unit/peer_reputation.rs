@@ -0,0 +1,170 @@
+use std::time::{Duration, SystemTime};
+
+use malachitebft_network::reputation::{Config, Reputation};
+use malachitebft_network::Misbehavior;
+use malachitebft_peer::PeerId;
+
+const MINUTE: Duration = Duration::from_secs(60);
+
+fn config() -> Config {
+    Config {
+        enabled: true,
+        ban_threshold: -100.0,
+        ban_duration: 10 * MINUTE,
+        score_recovery_per_minute: 10.0,
+        ban_file: None,
+    }
+}
+
+#[test]
+fn reports_decrease_score() {
+    let now = SystemTime::now();
+    let peer = PeerId::random();
+    let mut reputation = Reputation::new(config());
+
+    assert_eq!(reputation.score(&peer), 0.0);
+
+    assert_eq!(
+        reputation.report(peer, Misbehavior::InvalidMessage, now),
+        None
+    );
+    assert_eq!(reputation.score(&peer), -20.0);
+
+    assert_eq!(
+        reputation.report(peer, Misbehavior::UnexpectedMessage, now),
+        None
+    );
+    assert_eq!(reputation.score(&peer), -30.0);
+    assert!(!reputation.is_banned(&peer, now));
+}
+
+#[test]
+fn peer_is_banned_at_threshold() {
+    let now = SystemTime::now();
+    let peer = PeerId::random();
+    let other = PeerId::random();
+    let mut reputation = Reputation::new(config());
+
+    assert_eq!(
+        reputation.report(peer, Misbehavior::InvalidSignature, now),
+        None
+    );
+    assert_eq!(
+        reputation.report(peer, Misbehavior::InvalidSignature, now),
+        Some(now + 10 * MINUTE)
+    );
+
+    assert!(reputation.is_banned(&peer, now));
+    assert!(!reputation.is_banned(&other, now));
+
+    // Further reports do not extend the ban
+    assert_eq!(
+        reputation.report(peer, Misbehavior::InvalidSignature, now + MINUTE),
+        None
+    );
+    assert_eq!(
+        reputation.banned_peers().collect::<Vec<_>>(),
+        vec![(&peer, &(now + 10 * MINUTE))]
+    );
+}
+
+#[test]
+fn score_recovers_over_time() {
+    let now = SystemTime::now();
+    let peer = PeerId::random();
+    let mut reputation = Reputation::new(config());
+
+    reputation.report(peer, Misbehavior::InvalidSignature, now);
+    assert_eq!(reputation.score(&peer), -50.0);
+
+    reputation.tick(2 * MINUTE, now + 2 * MINUTE);
+    assert_eq!(reputation.score(&peer), -30.0);
+
+    // Scores never recover above zero
+    reputation.tick(10 * MINUTE, now + 12 * MINUTE);
+    assert_eq!(reputation.score(&peer), 0.0);
+}
+
+#[test]
+fn ban_expires() {
+    let now = SystemTime::now();
+    let peer = PeerId::random();
+    let mut reputation = Reputation::new(config());
+
+    reputation.ban(peer, now + 10 * MINUTE);
+
+    assert!(reputation.tick(MINUTE, now + 9 * MINUTE).is_empty());
+    assert!(reputation.is_banned(&peer, now + 9 * MINUTE));
+
+    assert_eq!(reputation.tick(MINUTE, now + 10 * MINUTE), vec![peer]);
+    assert!(!reputation.is_banned(&peer, now + 10 * MINUTE));
+    assert_eq!(reputation.banned_peers().count(), 0);
+
+    // The peer starts over with a clean score
+    assert_eq!(reputation.score(&peer), 0.0);
+}
+
+#[test]
+fn disabled_reputation_never_bans() {
+    let now = SystemTime::now();
+    let peer = PeerId::random();
+    let mut reputation = Reputation::new(Config {
+        enabled: false,
+        ..config()
+    });
+
+    for _ in 0..10 {
+        assert_eq!(
+            reputation.report(peer, Misbehavior::InvalidSignature, now),
+            None
+        );
+    }
+
+    assert_eq!(reputation.score(&peer), 0.0);
+    assert!(!reputation.is_banned(&peer, now));
+}
+
+#[test]
+fn bans_persist_across_restarts() {
+    let dir = tempfile::tempdir().unwrap();
+    let ban_file = dir.path().join("network").join("peer_bans.json");
+
+    let config = Config {
+        ban_file: Some(ban_file.clone()),
+        ..config()
+    };
+
+    let now = SystemTime::now();
+    let banned = PeerId::random();
+    let expired = PeerId::random();
+
+    let mut reputation = Reputation::load(config.clone(), now).unwrap();
+    assert_eq!(reputation.banned_peers().count(), 0);
+
+    reputation.ban(banned, now + 10 * MINUTE);
+    reputation.ban(expired, now + MINUTE);
+    assert!(ban_file.exists());
+
+    let reputation = Reputation::load(config.clone(), now).unwrap();
+    assert!(reputation.is_banned(&banned, now));
+    assert!(reputation.is_banned(&expired, now));
+
+    // Bans which expired while the node was down are dropped
+    let reputation = Reputation::load(config, now + 2 * MINUTE).unwrap();
+    assert!(reputation.is_banned(&banned, now + 2 * MINUTE));
+    assert_eq!(reputation.banned_peers().count(), 1);
+}
+
+#[test]
+fn corrupted_ban_file_is_an_error() {
+    let dir = tempfile::tempdir().unwrap();
+    let ban_file = dir.path().join("peer_bans.json");
+    std::fs::write(&ban_file, "not json").unwrap();
+
+    let config = Config {
+        ban_file: Some(ban_file),
+        ..config()
+    };
+
+    assert!(Reputation::load(config, SystemTime::now()).is_err());
+}
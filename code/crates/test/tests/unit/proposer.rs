@@ -0,0 +1,190 @@
+use std::collections::BTreeMap;
+
+use informalsystems_malachitebft_test::proposer_selector::{ProposerSelector, WeightedProposer};
+use informalsystems_malachitebft_test::utils::validators::make_validators;
+use informalsystems_malachitebft_test::{Address, Height, TestContext, Validator, ValidatorSet};
+use malachitebft_core_types::{ProposerPriorities, Round};
+
+fn validator_set<const N: usize>(voting_powers: [u64; N]) -> (Vec<Validator>, ValidatorSet) {
+    let validators: Vec<_> = make_validators(voting_powers)
+        .into_iter()
+        .map(|(v, _)| v)
+        .collect();
+
+    (validators.clone(), ValidatorSet::new(validators))
+}
+
+/// Count how many times each validator is the proposer for round 0 over the given number of heights.
+fn count_proposers(
+    priorities: &mut ProposerPriorities<TestContext>,
+    validator_set: &ValidatorSet,
+    heights: usize,
+) -> BTreeMap<Address, u64> {
+    let mut counts = BTreeMap::new();
+
+    for _ in 0..heights {
+        let proposer = *priorities.proposer().unwrap();
+        *counts.entry(proposer).or_default() += 1;
+        priorities.next_height(validator_set);
+    }
+
+    counts
+}
+
+#[test]
+fn equal_voting_power_is_round_robin() {
+    let (validators, validator_set) = validator_set([1, 1, 1, 1]);
+    let mut priorities = ProposerPriorities::<TestContext>::new(&validator_set);
+
+    let counts = count_proposers(&mut priorities, &validator_set, 4 * 25);
+
+    for validator in &validators {
+        assert_eq!(counts[&validator.address], 25);
+    }
+}
+
+#[test]
+fn selection_is_proportional_to_voting_power() {
+    let (validators, validator_set) = validator_set([1, 2, 3, 4]);
+    let mut priorities = ProposerPriorities::<TestContext>::new(&validator_set);
+
+    let counts = count_proposers(&mut priorities, &validator_set, 10 * 10);
+
+    for validator in &validators {
+        assert_eq!(counts[&validator.address], 10 * validator.voting_power);
+    }
+}
+
+#[test]
+fn highest_voting_power_proposes_first() {
+    let (validators, validator_set) = validator_set([1, 5, 2]);
+    let priorities = ProposerPriorities::<TestContext>::new(&validator_set);
+
+    assert_eq!(priorities.proposer(), Some(&validators[1].address));
+}
+
+#[test]
+fn rounds_advance_priorities_of_a_copy() {
+    let (validators, validator_set) = validator_set([1, 1, 1]);
+    let mut priorities = ProposerPriorities::<TestContext>::new(&validator_set);
+
+    let proposers: Vec<_> = (0..3)
+        .map(|round| priorities.proposer_at(Round::new(round)).unwrap())
+        .collect();
+
+    // Every validator proposes once over three rounds
+    for validator in &validators {
+        assert!(proposers.contains(&validator.address));
+    }
+
+    // Selecting the proposer for a later round does not modify the priorities
+    let proposer = priorities.proposer().copied();
+    assert_eq!(priorities.proposer_at(Round::new(0)), proposer);
+    assert_eq!(priorities.proposer_at(Round::Nil), proposer);
+
+    let proposer = priorities
+        .select_proposer(&validator_set, Round::new(1))
+        .unwrap();
+
+    assert_eq!(proposer.address, proposers[1]);
+}
+
+#[test]
+fn cached_rounds_match_rounds_selected_afresh() {
+    let (_, validator_set) = validator_set([5, 3, 2, 1]);
+    let mut cached = ProposerPriorities::<TestContext>::new(&validator_set);
+
+    // Rounds in increasing order advance the cached priorities, earlier ones start over
+    for round in [1, 2, 5, 5, 3, 0, 9, 4, 12] {
+        let mut fresh = ProposerPriorities::<TestContext>::new(&validator_set);
+
+        assert_eq!(
+            cached.proposer_at(Round::new(round)),
+            fresh.proposer_at(Round::new(round)),
+            "round {round}"
+        );
+    }
+
+    // The cache is not part of the state of the priorities
+    assert_eq!(cached, ProposerPriorities::new(&validator_set));
+}
+
+#[test]
+fn rounds_do_not_affect_next_height() {
+    let (_, validator_set) = validator_set([3, 2, 1]);
+
+    let mut a = ProposerPriorities::<TestContext>::new(&validator_set);
+    let mut b = a.clone();
+
+    // Height decided at round 0 on one node, and at round 5 on another
+    let _ = a.proposer_at(Round::new(0));
+    let _ = b.proposer_at(Round::new(5));
+
+    a.next_height(&validator_set);
+    b.next_height(&validator_set);
+
+    assert_eq!(a, b);
+}
+
+#[test]
+fn joining_validator_starts_with_low_priority() {
+    let [(v1, _), (v2, _), (v3, _)] = make_validators([10, 10, 10]);
+
+    let before = ValidatorSet::new(vec![v1.clone(), v2.clone()]);
+    let after = ValidatorSet::new(vec![v1.clone(), v2.clone(), v3.clone()]);
+
+    let mut priorities = ProposerPriorities::<TestContext>::new(&before);
+    priorities.next_height(&after);
+
+    let priority = priorities.priority(&v3.address).unwrap();
+    assert!(priority < priorities.priority(&v1.address).unwrap());
+    assert!(priority < priorities.priority(&v2.address).unwrap());
+    assert_ne!(priorities.proposer(), Some(&v3.address));
+}
+
+#[test]
+fn leaving_validator_is_never_selected() {
+    let [(v1, _), (v2, _), (v3, _)] = make_validators([10, 20, 30]);
+
+    let before = ValidatorSet::new(vec![v1.clone(), v2.clone(), v3.clone()]);
+    let after = ValidatorSet::new(vec![v1.clone(), v2.clone()]);
+
+    let mut priorities = ProposerPriorities::<TestContext>::new(&before);
+    priorities.next_height(&after);
+
+    assert_eq!(priorities.priority(&v3.address), None);
+
+    let counts = count_proposers(&mut priorities, &after, 30);
+    assert!(!counts.contains_key(&v3.address));
+    assert_eq!(counts[&v1.address], 10);
+    assert_eq!(counts[&v2.address], 20);
+}
+
+#[test]
+fn weighted_proposer_follows_priorities() {
+    let (_, validator_set) = validator_set([1, 2, 3, 4]);
+    let selector = WeightedProposer::new();
+    let mut priorities = ProposerPriorities::<TestContext>::new(&validator_set);
+
+    for height in 1..=20 {
+        let height = Height::new(height);
+
+        for round in [0, 1, 2, 1] {
+            let round = Round::new(round);
+
+            assert_eq!(
+                selector.select_proposer(height, round, &validator_set),
+                priorities.proposer_at(round).unwrap(),
+            );
+        }
+
+        priorities.next_height(&validator_set);
+    }
+
+    // Jumping to another height, eg. after a restart, gives the same proposers
+    let restarted = WeightedProposer::new();
+    assert_eq!(
+        restarted.select_proposer(Height::new(21), Round::new(3), &validator_set),
+        priorities.proposer_at(Round::new(3)).unwrap(),
+    );
+}
@@ -0,0 +1,158 @@
+use std::time::{Duration, Instant};
+
+use malachitebft_network::rate_limit::{Config, RateLimit, RateLimiter};
+use malachitebft_network::Channel;
+use malachitebft_peer::PeerId;
+
+const MS: Duration = Duration::from_millis(1);
+
+fn config() -> Config {
+    Config {
+        enabled: true,
+        consensus: RateLimit::new(10, 0),
+        proposal_parts: RateLimit::new(0, 1000),
+        sync: RateLimit::new(2, 100),
+        liveness: RateLimit::UNLIMITED,
+        evidence: RateLimit::new(1, 0),
+    }
+}
+
+#[test]
+fn messages_per_sec_limit() {
+    let now = Instant::now();
+    let peer = PeerId::random();
+    let mut limiter = RateLimiter::new(config());
+
+    // Bursts of up to one second worth of messages are allowed
+    for _ in 0..10 {
+        assert!(limiter.check(peer, Channel::Consensus, 100, now));
+    }
+
+    assert!(!limiter.check(peer, Channel::Consensus, 100, now));
+
+    // One message every 100ms
+    assert!(!limiter.check(peer, Channel::Consensus, 100, now + 50 * MS));
+    assert!(limiter.check(peer, Channel::Consensus, 100, now + 100 * MS));
+    assert!(!limiter.check(peer, Channel::Consensus, 100, now + 100 * MS));
+}
+
+#[test]
+fn bytes_per_sec_limit() {
+    let now = Instant::now();
+    let peer = PeerId::random();
+    let mut limiter = RateLimiter::new(config());
+
+    assert!(limiter.check(peer, Channel::ProposalParts, 600, now));
+    assert!(limiter.check(peer, Channel::ProposalParts, 300, now));
+    assert!(limiter.check(peer, Channel::ProposalParts, 300, now));
+
+    // The budget is now 200 bytes in debt
+    assert!(!limiter.check(peer, Channel::ProposalParts, 1, now));
+    assert!(!limiter.check(peer, Channel::ProposalParts, 1, now + 200 * MS));
+    assert!(limiter.check(peer, Channel::ProposalParts, 1, now + 201 * MS));
+}
+
+#[test]
+fn messages_larger_than_the_byte_budget_are_not_rejected_forever() {
+    let now = Instant::now();
+    let peer = PeerId::random();
+    let mut limiter = RateLimiter::new(config());
+
+    assert!(limiter.check(peer, Channel::ProposalParts, 5000, now));
+
+    // Paying off the debt takes 5 seconds
+    assert!(!limiter.check(peer, Channel::ProposalParts, 5000, now + 4 * 1000 * MS));
+    assert!(limiter.check(peer, Channel::ProposalParts, 5000, now + 5001 * MS));
+}
+
+#[test]
+fn both_limits_apply() {
+    let now = Instant::now();
+    let peer = PeerId::random();
+    let mut limiter = RateLimiter::new(config());
+
+    assert!(limiter.check(peer, Channel::Sync, 10, now));
+    assert!(limiter.check(peer, Channel::Sync, 10, now));
+    assert!(!limiter.check(peer, Channel::Sync, 10, now));
+
+    let later = now + Duration::from_secs(1);
+    assert!(limiter.check(peer, Channel::Sync, 200, later));
+    assert!(!limiter.check(peer, Channel::Sync, 10, later));
+}
+
+#[test]
+fn limits_are_per_peer_and_per_channel() {
+    let now = Instant::now();
+    let peer = PeerId::random();
+    let other = PeerId::random();
+    let mut limiter = RateLimiter::new(config());
+
+    assert!(limiter.check(peer, Channel::Evidence, 10, now));
+    assert!(!limiter.check(peer, Channel::Evidence, 10, now));
+
+    assert!(limiter.check(other, Channel::Evidence, 10, now));
+    assert!(limiter.check(peer, Channel::Consensus, 10, now));
+}
+
+#[test]
+fn unlimited_and_disabled() {
+    let now = Instant::now();
+    let peer = PeerId::random();
+
+    let mut limiter = RateLimiter::new(config());
+
+    for _ in 0..1000 {
+        assert!(limiter.check(peer, Channel::Liveness, 1_000_000, now));
+    }
+
+    assert_eq!(limiter.tracked(), 0);
+
+    let mut limiter = RateLimiter::new(Config {
+        enabled: false,
+        ..config()
+    });
+
+    for _ in 0..1000 {
+        assert!(limiter.check(peer, Channel::Evidence, 1_000_000, now));
+    }
+}
+
+#[test]
+fn prune_forgets_quiet_peers() {
+    let now = Instant::now();
+    let quiet = PeerId::random();
+    let busy = PeerId::random();
+    let mut limiter = RateLimiter::new(config());
+
+    assert!(limiter.check(quiet, Channel::Consensus, 10, now));
+    assert!(limiter.check(busy, Channel::Consensus, 10, now + 950 * MS));
+    assert_eq!(limiter.tracked(), 2);
+
+    limiter.prune(now + Duration::from_secs(1));
+    assert_eq!(limiter.tracked(), 1);
+
+    limiter.prune(now + Duration::from_secs(2));
+    assert_eq!(limiter.tracked(), 0);
+}
+
+#[test]
+fn gossip_messages_dropped_by_the_limit_of_their_source_are_delivered_later() {
+    let now = Instant::now();
+    let flooder = PeerId::random();
+    let honest = PeerId::random();
+    let mut limiter = RateLimiter::new(config());
+
+    // Gossip messages are accounted to the peer which signed them, so the messages of
+    // an honest peer are still delivered through a relay which also forwards a flood
+    for _ in 0..10 {
+        assert!(limiter.check(flooder, Channel::Consensus, 100, now));
+    }
+
+    assert!(!limiter.check(flooder, Channel::Consensus, 100, now));
+    assert!(limiter.check(honest, Channel::Consensus, 100, now));
+
+    // A message dropped for exceeding the limit of its source is ignored rather than rejected,
+    // and is delivered when republished once the budget of that source has been replenished
+    assert!(!limiter.check(flooder, Channel::Consensus, 100, now + 50 * MS));
+    assert!(limiter.check(flooder, Channel::Consensus, 100, now + 100 * MS));
+}
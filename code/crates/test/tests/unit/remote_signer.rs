@@ -0,0 +1,295 @@
+use std::time::Duration;
+
+use bytes::Bytes;
+
+use informalsystems_malachitebft_test::codec::proto::ProtobufCodec;
+use informalsystems_malachitebft_test::{
+    Address, Ed25519Provider, Ed25519Verifier, Height, PrivateKey, Proposal, ProposalData,
+    ProposalPart, TestContext, Value, ValueId, Vote,
+};
+use malachitebft_core_types::{NilOrVal, Round};
+use malachitebft_remote_signer::proto::{self, request, response, ErrorKind};
+use malachitebft_remote_signer::{
+    Address as SignerAddress, Error, IdentityKey, IdentityPublicKey, Listener,
+    RemoteSigningProvider, SignerServer, PROTOCOL_VERSION,
+};
+use malachitebft_signing::SigningProvider;
+
+type Server = SignerServer<TestContext, ProtobufCodec, Ed25519Provider>;
+type Client = RemoteSigningProvider<TestContext, ProtobufCodec, Ed25519Verifier>;
+
+fn signer_key() -> PrivateKey {
+    PrivateKey::from([1; 32])
+}
+
+fn signer_identity() -> IdentityKey {
+    IdentityKey::from_bytes([3; 32])
+}
+
+fn node_identity() -> IdentityKey {
+    IdentityKey::from_bytes([4; 32])
+}
+
+fn server() -> Server {
+    SignerServer::new(
+        signer_identity(),
+        vec![node_identity().public_key()],
+        ProtobufCodec,
+        Ed25519Provider::new(signer_key()),
+    )
+}
+
+/// A client which does not hold the private key of the remote signer,
+/// and only verifies signatures locally.
+fn client(address: SignerAddress) -> Client {
+    client_with(address, node_identity(), signer_identity().public_key())
+}
+
+fn client_with(
+    address: SignerAddress,
+    identity: IdentityKey,
+    signer_public_key: IdentityPublicKey,
+) -> Client {
+    RemoteSigningProvider::new(
+        address,
+        identity,
+        signer_public_key,
+        ProtobufCodec,
+        Ed25519Verifier,
+    )
+    .with_timeout(Duration::from_secs(1))
+}
+
+async fn spawn_server(address: &SignerAddress) -> SignerAddress {
+    let listener = Listener::bind(address).await.unwrap();
+    let address = listener.local_address().unwrap();
+    tokio::spawn(server().serve(listener));
+    address
+}
+
+fn vote() -> Vote {
+    let address = Address::from_public_key(&signer_key().public_key());
+    Vote::new_precommit(
+        Height::new(1),
+        Round::new(0),
+        NilOrVal::Val(ValueId::new(42)),
+        address,
+    )
+}
+
+async fn sign_all(client: &Client) {
+    let public_key = signer_key().public_key();
+    let address = Address::from_public_key(&public_key);
+
+    let vote = client.sign_vote(vote()).await.unwrap();
+    assert_eq!(
+        vote.signature,
+        signer_key().sign(&vote.message.to_sign_bytes())
+    );
+    assert!(client
+        .verify_signed_vote(&vote.message, &vote.signature, &public_key)
+        .await
+        .unwrap()
+        .is_valid());
+
+    let proposal = Proposal::new(
+        Height::new(1),
+        Round::new(0),
+        Value::new(42),
+        Round::Nil,
+        address,
+    );
+    let proposal = client.sign_proposal(proposal).await.unwrap();
+    assert!(client
+        .verify_signed_proposal(&proposal.message, &proposal.signature, &public_key)
+        .await
+        .unwrap()
+        .is_valid());
+
+    let part = ProposalPart::Data(ProposalData::new(42));
+    let part = client.sign_proposal_part(part).await.unwrap();
+    assert!(client
+        .verify_signed_proposal_part(&part.message, &part.signature, &public_key)
+        .await
+        .unwrap()
+        .is_valid());
+
+    let extension = client
+        .sign_vote_extension(Bytes::from_static(b"extension"))
+        .await
+        .unwrap();
+    assert!(client
+        .verify_signed_vote_extension(&extension.message, &extension.signature, &public_key)
+        .await
+        .unwrap()
+        .is_valid());
+
+    let peer_id = b"peer id";
+    let signature = client.sign_peer_identity(peer_id).await.unwrap();
+    assert!(client
+        .verify_peer_identity(peer_id, &signature, &public_key)
+        .await
+        .unwrap()
+        .is_valid());
+}
+
+#[tokio::test]
+async fn sign_over_tcp() {
+    let address = spawn_server(&"tcp://127.0.0.1:0".parse().unwrap()).await;
+    sign_all(&client(address)).await;
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn sign_over_unix_socket() {
+    let dir = tempfile::tempdir().unwrap();
+    let address = SignerAddress::Unix(dir.path().join("signer.sock"));
+
+    let address = spawn_server(&address).await;
+    sign_all(&client(address)).await;
+}
+
+#[tokio::test]
+async fn sign_large_message() {
+    let address = spawn_server(&"tcp://127.0.0.1:0".parse().unwrap()).await;
+    let client = client(address);
+
+    // Spans several encrypted Noise messages
+    let extension = Bytes::from(vec![42; 200 * 1024]);
+    let signed = client.sign_vote_extension(extension.clone()).await.unwrap();
+
+    assert_eq!(signed.signature, signer_key().sign(&extension));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn reconnect_to_signer() {
+    let dir = tempfile::tempdir().unwrap();
+    let address = SignerAddress::Unix(dir.path().join("signer.sock"));
+
+    let client = client(address.clone());
+
+    // The signer is not running yet
+    assert!(client.sign_vote(vote()).await.is_err());
+
+    spawn_server(&address).await;
+    assert!(client.sign_vote(vote()).await.is_ok());
+}
+
+#[tokio::test]
+async fn reject_unauthorized_node() {
+    let address = spawn_server(&"tcp://127.0.0.1:0".parse().unwrap()).await;
+
+    let identity = IdentityKey::from_bytes([5; 32]);
+    let client = client_with(address, identity, signer_identity().public_key());
+
+    // The signer closes the connection during the handshake
+    assert!(client.sign_vote(vote()).await.is_err());
+}
+
+#[tokio::test]
+async fn reject_unknown_signer() {
+    let address = spawn_server(&"tcp://127.0.0.1:0".parse().unwrap()).await;
+
+    let signer_public_key = IdentityKey::from_bytes([5; 32]).public_key();
+    let client = client_with(address, node_identity(), signer_public_key);
+
+    // The node refuses to reveal its identity to the signer
+    let error = client.sign_vote(vote()).await.unwrap_err();
+    let source = std::error::Error::source(&error).and_then(|e| e.downcast_ref::<Error>());
+    assert!(matches!(
+        source,
+        Some(Error::Unauthorized(key)) if *key == signer_identity().public_key()
+    ));
+}
+
+#[test]
+fn load_or_generate_identity_key() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("identity_key");
+
+    let generated = IdentityKey::load_or_generate(&path).unwrap();
+    let loaded = IdentityKey::load_or_generate(&path).unwrap();
+    assert_eq!(generated.public_key(), loaded.public_key());
+
+    std::fs::write(&path, "not a key").unwrap();
+    assert!(matches!(
+        IdentityKey::load_or_generate(&path),
+        Err(Error::InvalidIdentityKeyFile(_))
+    ));
+}
+
+#[test]
+fn parse_identity_public_key() {
+    let public_key = signer_identity().public_key();
+    assert_eq!(
+        public_key.to_string().parse::<IdentityPublicKey>().unwrap(),
+        public_key
+    );
+
+    assert!("abcd".parse::<IdentityPublicKey>().is_err());
+}
+
+#[tokio::test]
+async fn unsupported_version() {
+    let request = proto::Request {
+        version: PROTOCOL_VERSION + 1,
+        request: Some(request::Request::SignVoteExtension(
+            proto::SignVoteExtensionRequest {
+                extension: Bytes::from_static(b"extension"),
+            },
+        )),
+    };
+
+    let response = server().handle(request).await;
+    let Some(response::Response::Error(error)) = response.response else {
+        panic!("expected an error response, got {response:?}");
+    };
+
+    assert_eq!(error.kind(), ErrorKind::UnsupportedVersion);
+}
+
+#[tokio::test]
+async fn invalid_request() {
+    let request = proto::Request {
+        version: PROTOCOL_VERSION,
+        request: Some(request::Request::SignVote(proto::SignVoteRequest {
+            vote: Bytes::from_static(b"not a vote"),
+        })),
+    };
+
+    let response = server().handle(request).await;
+    let Some(response::Response::Error(error)) = response.response else {
+        panic!("expected an error response, got {response:?}");
+    };
+
+    assert_eq!(error.kind(), ErrorKind::InvalidRequest);
+
+    let request = proto::Request {
+        version: PROTOCOL_VERSION,
+        request: None,
+    };
+
+    let response = server().handle(request).await;
+    assert!(matches!(
+        response.response,
+        Some(response::Response::Error(error)) if error.kind() == ErrorKind::InvalidRequest
+    ));
+}
+
+#[test]
+fn parse_address() {
+    let address: SignerAddress = "tcp://127.0.0.1:26659".parse().unwrap();
+    assert_eq!(address, SignerAddress::Tcp("127.0.0.1:26659".to_string()));
+    assert_eq!(address.to_string(), "tcp://127.0.0.1:26659");
+
+    #[cfg(unix)]
+    {
+        let address: SignerAddress = "unix:///var/run/signer.sock".parse().unwrap();
+        assert_eq!(address, SignerAddress::Unix("/var/run/signer.sock".into()));
+        assert_eq!(address.to_string(), "unix:///var/run/signer.sock");
+    }
+
+    assert!("127.0.0.1:26659".parse::<SignerAddress>().is_err());
+    assert!("tcp://".parse::<SignerAddress>().is_err());
+}
@@ -0,0 +1,248 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use malachitebft_core_types::{NilOrVal, Round, SigningScheme};
+use malachitebft_proto::Protobuf;
+use malachitebft_signing_bls::{BatchVerifier, Bls12381, PrivateKey, PublicKey, Signature};
+
+use informalsystems_malachitebft_test::{Address, Height, ValueId, Vote};
+
+fn private_keys(count: u8) -> Vec<PrivateKey> {
+    (0..count).map(|i| PrivateKey::from([i; 32])).collect()
+}
+
+/// Sign bytes of a precommit which do not depend on the validator,
+/// so that all the precommits for a value can be verified at once.
+fn precommit_sign_bytes(value_id: u64) -> Vec<u8> {
+    Vote::new_precommit(
+        Height::new(1),
+        Round::new(0),
+        NilOrVal::Val(ValueId::new(value_id)),
+        Address::new([0; 20]),
+    )
+    .to_sign_bytes()
+    .to_vec()
+}
+
+#[test]
+fn sign_and_verify() {
+    let [private_key] = private_keys(1).try_into().unwrap();
+    let public_key = private_key.public_key();
+
+    let msg = precommit_sign_bytes(42);
+    let signature = private_key.sign(&msg);
+
+    assert!(public_key.verify(&msg, &signature).is_ok());
+    assert!(public_key
+        .verify(&precommit_sign_bytes(43), &signature)
+        .is_err());
+
+    let other = PrivateKey::from([0xff; 32]).public_key();
+    assert!(other.verify(&msg, &signature).is_err());
+}
+
+#[test]
+fn encode_decode() {
+    let [private_key] = private_keys(1).try_into().unwrap();
+    let signature = private_key.sign(b"hello");
+    let public_key = private_key.public_key();
+
+    let encoded = Bls12381::encode_signature(&signature);
+    assert_eq!(encoded.len(), 96);
+    assert_eq!(Bls12381::decode_signature(&encoded).unwrap(), signature);
+    assert!(Bls12381::decode_signature(&[0; 96]).is_err());
+
+    assert_eq!(
+        PublicKey::from_bytes(&public_key.to_bytes()).unwrap(),
+        public_key
+    );
+
+    let decoded = PrivateKey::from_bytes(&private_key.to_bytes()).unwrap();
+    assert_eq!(decoded.public_key(), public_key);
+}
+
+#[test]
+fn fast_aggregate_verify() {
+    let private_keys = private_keys(10);
+    let public_keys: Vec<_> = private_keys.iter().map(|pk| pk.public_key()).collect();
+
+    let msg = precommit_sign_bytes(42);
+    let signatures: Vec<_> = private_keys.iter().map(|pk| pk.sign(&msg)).collect();
+
+    let aggregate = Signature::aggregate(&signatures).unwrap();
+    assert!(aggregate.fast_aggregate_verify(&msg, &public_keys).is_ok());
+
+    // Wrong message
+    let other_msg = precommit_sign_bytes(43);
+    assert!(aggregate
+        .fast_aggregate_verify(&other_msg, &public_keys)
+        .is_err());
+
+    // Missing signer
+    assert!(aggregate
+        .fast_aggregate_verify(&msg, &public_keys[1..])
+        .is_err());
+
+    // Missing signature
+    let partial = Signature::aggregate(&signatures[1..]).unwrap();
+    assert!(partial.fast_aggregate_verify(&msg, &public_keys).is_err());
+    assert!(partial
+        .fast_aggregate_verify(&msg, &public_keys[1..])
+        .is_ok());
+
+    // Same as verifying against the aggregate public key
+    let aggregate_key = PublicKey::aggregate(&public_keys).unwrap();
+    assert!(aggregate_key.verify(&msg, &aggregate).is_ok());
+
+    assert!(Signature::aggregate(&[]).is_err());
+}
+
+#[test]
+fn aggregate_verify_distinct_messages() {
+    let private_keys = private_keys(4);
+    let public_keys: Vec<_> = private_keys.iter().map(|pk| pk.public_key()).collect();
+
+    let msgs: Vec<_> = (0..4).map(precommit_sign_bytes).collect();
+    let msgs: Vec<&[u8]> = msgs.iter().map(|msg| msg.as_slice()).collect();
+
+    let signatures: Vec<_> = private_keys
+        .iter()
+        .zip(&msgs)
+        .map(|(pk, msg)| pk.sign(msg))
+        .collect();
+
+    let aggregate = Signature::aggregate(&signatures).unwrap();
+    assert!(aggregate.aggregate_verify(&msgs, &public_keys).is_ok());
+
+    let mut swapped = msgs.clone();
+    swapped.swap(0, 1);
+    assert!(aggregate.aggregate_verify(&swapped, &public_keys).is_err());
+
+    assert!(aggregate
+        .aggregate_verify(&msgs[1..], &public_keys)
+        .is_err());
+}
+
+#[test]
+fn proof_of_possession() {
+    let [alice, bob] = private_keys(2).try_into().unwrap();
+
+    let proof = alice.proof_of_possession();
+    assert!(alice
+        .public_key()
+        .verify_proof_of_possession(&proof)
+        .is_ok());
+    assert!(bob.public_key().verify_proof_of_possession(&proof).is_err());
+
+    // A proof of possession is not a signature over the public key
+    let signature = alice.sign(&alice.public_key().to_bytes());
+    assert!(alice
+        .public_key()
+        .verify_proof_of_possession(&signature)
+        .is_err());
+}
+
+#[test]
+fn generate_keypair() {
+    let mut rng = StdRng::seed_from_u64(0x42);
+
+    let first = Bls12381::generate_keypair(&mut rng);
+    let second = Bls12381::generate_keypair(&mut rng);
+    assert_ne!(first.public_key(), second.public_key());
+
+    let signature = first.sign(b"hello");
+    assert!(first.public_key().verify(b"hello", &signature).is_ok());
+
+    // Key generation is deterministic given the RNG
+    let again = Bls12381::generate_keypair(StdRng::seed_from_u64(0x42));
+    assert_eq!(again.public_key(), first.public_key());
+}
+
+#[test]
+fn serde_roundtrip() {
+    let private_key = PrivateKey::from([7; 32]);
+    let public_key = private_key.public_key();
+    let signature = private_key.sign(b"hello");
+
+    let json = serde_json::to_value(public_key).unwrap();
+    assert_eq!(json["type"], "cometbft/PubKeyBls12_381");
+    assert_eq!(
+        serde_json::from_value::<PublicKey>(json).unwrap(),
+        public_key
+    );
+
+    let json = serde_json::to_value(&private_key).unwrap();
+    assert_eq!(json["type"], "cometbft/PrivKeyBls12_381");
+    let decoded: PrivateKey = serde_json::from_value(json).unwrap();
+    assert_eq!(decoded.to_bytes(), private_key.to_bytes());
+
+    let json = serde_json::to_string(&signature).unwrap();
+    assert_eq!(serde_json::from_str::<Signature>(&json).unwrap(), signature);
+
+    // Keys of another type are rejected
+    let json = serde_json::json!({
+        "type": "tendermint/PubKeyEd25519",
+        "value": serde_json::to_value(public_key).unwrap()["value"],
+    });
+    assert!(serde_json::from_value::<PublicKey>(json).is_err());
+}
+
+#[test]
+fn protobuf_roundtrip() {
+    let private_key = PrivateKey::from([7; 32]);
+    let public_key = private_key.public_key();
+    let signature = private_key.sign(b"hello");
+
+    let proto = public_key.to_proto().unwrap();
+    assert_eq!(PublicKey::from_proto(proto).unwrap(), public_key);
+
+    let proto = signature.to_proto().unwrap();
+    assert_eq!(Signature::from_proto(proto).unwrap(), signature);
+
+    let invalid = malachitebft_signing_bls::proto::Signature {
+        bytes: vec![0; 96].into(),
+    };
+    assert!(Signature::from_proto(invalid).is_err());
+}
+
+#[test]
+fn batch_verify() {
+    let mut rng = StdRng::seed_from_u64(0x42);
+
+    let private_keys = private_keys(8);
+
+    let mut batch = BatchVerifier::new();
+    for (i, private_key) in private_keys.iter().enumerate() {
+        let msg = precommit_sign_bytes(i as u64);
+        batch.queue(
+            msg.clone(),
+            private_key.public_key(),
+            private_key.sign(&msg),
+        );
+    }
+
+    assert_eq!(batch.len(), 8);
+    assert!(batch.clone().verify(&mut rng).is_ok());
+
+    // A single invalid signature makes the whole batch invalid
+    let mut invalid = batch.clone();
+    let msg = precommit_sign_bytes(100);
+    invalid.queue(
+        msg,
+        private_keys[0].public_key(),
+        private_keys[0].sign(b"other"),
+    );
+    assert!(invalid.verify(&mut rng).is_err());
+
+    // Two invalid signatures which would cancel each other out once aggregated
+    // are still detected, thanks to the random coefficients
+    let mut cancelling = BatchVerifier::new();
+    let (a, b) = (&private_keys[0], &private_keys[1]);
+    let sig_a = a.sign(b"a");
+    let sig_b = b.sign(b"b");
+    cancelling.queue(&b"a"[..], a.public_key(), sig_b);
+    cancelling.queue(&b"b"[..], b.public_key(), sig_a);
+    assert!(cancelling.verify(&mut rng).is_err());
+
+    assert!(BatchVerifier::new().verify(&mut rng).is_ok());
+}
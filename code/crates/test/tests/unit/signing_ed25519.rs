@@ -0,0 +1,253 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::executor::block_on;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use informalsystems_malachitebft_test::{
+    Address, Ed25519Provider, Height, Proposal, ProposalPart, TestContext, Validator, ValidatorSet,
+    ValueId, Vote,
+};
+use malachitebft_core_types::{
+    CommitCertificate, NilOrVal, Round, SignedExtension, SignedProposal, SignedProposalPart,
+    SignedVote, ThresholdParams,
+};
+use malachitebft_signing::{
+    Error as SigningError, SigningProvider, SigningProviderExt, VerificationResult,
+};
+use malachitebft_signing_ed25519::{BatchVerifier, PrivateKey, PublicKey, Signature};
+
+fn private_keys(count: u8) -> Vec<PrivateKey> {
+    (0..count).map(|i| PrivateKey::from([i; 32])).collect()
+}
+
+fn precommit(private_key: &PrivateKey, value_id: u64) -> Vote {
+    Vote::new_precommit(
+        Height::new(1),
+        Round::new(0),
+        NilOrVal::Val(ValueId::new(value_id)),
+        Address::from_public_key(&private_key.public_key()),
+    )
+}
+
+#[test]
+fn batch_verify() {
+    let mut rng = StdRng::seed_from_u64(0x42);
+
+    let private_keys = private_keys(8);
+
+    let mut batch = BatchVerifier::new();
+    for private_key in &private_keys {
+        let msg = precommit(private_key, 42).to_sign_bytes();
+        batch.queue(&msg, &private_key.public_key(), &private_key.sign(&msg));
+    }
+
+    assert_eq!(batch.len(), 8);
+    assert!(batch.verify(&mut rng).is_ok());
+
+    // A single invalid signature makes the whole batch invalid
+    let mut batch = BatchVerifier::new();
+    for (i, private_key) in private_keys.iter().enumerate() {
+        let msg = precommit(private_key, 42).to_sign_bytes();
+        let signature = if i == 3 {
+            private_key.sign(b"other")
+        } else {
+            private_key.sign(&msg)
+        };
+
+        batch.queue(&msg, &private_key.public_key(), &signature);
+    }
+
+    assert!(batch.verify(&mut rng).is_err());
+
+    assert!(BatchVerifier::new().is_empty());
+    assert!(BatchVerifier::new().verify(&mut rng).is_ok());
+}
+
+#[test]
+fn verify_signed_votes() {
+    let private_keys = private_keys(8);
+    let public_keys = private_keys
+        .iter()
+        .map(|private_key| private_key.public_key())
+        .collect::<Vec<_>>();
+
+    let signed_votes = private_keys
+        .iter()
+        .map(|private_key| {
+            let provider = Ed25519Provider::new(private_key.clone());
+            block_on(provider.sign_vote(precommit(private_key, 42))).unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    let provider = Ed25519Provider::new(private_keys[0].clone());
+
+    let votes = signed_votes
+        .iter()
+        .zip(&public_keys)
+        .map(|(vote, public_key)| (vote.message.clone(), &vote.signature, public_key))
+        .collect::<Vec<_>>();
+
+    let results = block_on(provider.verify_signed_votes(&votes)).unwrap();
+    assert_eq!(results.len(), 8);
+    assert!(results.iter().all(|result| result.is_valid()));
+
+    // Swap the signatures of two validators, which are then both invalid
+    let mut votes = votes;
+    let (first, second) = (votes[2].1, votes[5].1);
+    votes[2].1 = second;
+    votes[5].1 = first;
+
+    let results = block_on(provider.verify_signed_votes(&votes)).unwrap();
+    let invalid = results
+        .iter()
+        .enumerate()
+        .filter(|(_, result)| result.is_invalid())
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+
+    assert_eq!(invalid, vec![2, 5]);
+
+    // The results agree with verifying each signature on its own
+    for ((vote, signature, public_key), result) in votes.iter().zip(&results) {
+        let expected = block_on(provider.verify_signed_vote(vote, signature, public_key)).unwrap();
+        assert_eq!(expected.is_valid(), result.is_valid());
+    }
+
+    assert!(block_on(provider.verify_signed_votes(&[]))
+        .unwrap()
+        .is_empty());
+}
+
+/// A provider which fails to verify signatures all at once.
+struct NoBatchProvider(Ed25519Provider);
+
+#[async_trait]
+impl SigningProvider<TestContext> for NoBatchProvider {
+    async fn sign_vote(&self, vote: Vote) -> Result<SignedVote<TestContext>, SigningError> {
+        self.0.sign_vote(vote).await
+    }
+
+    async fn verify_signed_vote(
+        &self,
+        vote: &Vote,
+        signature: &Signature,
+        public_key: &PublicKey,
+    ) -> Result<VerificationResult, SigningError> {
+        self.0.verify_signed_vote(vote, signature, public_key).await
+    }
+
+    async fn sign_proposal(
+        &self,
+        proposal: Proposal,
+    ) -> Result<SignedProposal<TestContext>, SigningError> {
+        self.0.sign_proposal(proposal).await
+    }
+
+    async fn verify_signed_proposal(
+        &self,
+        proposal: &Proposal,
+        signature: &Signature,
+        public_key: &PublicKey,
+    ) -> Result<VerificationResult, SigningError> {
+        self.0
+            .verify_signed_proposal(proposal, signature, public_key)
+            .await
+    }
+
+    async fn sign_proposal_part(
+        &self,
+        proposal_part: ProposalPart,
+    ) -> Result<SignedProposalPart<TestContext>, SigningError> {
+        self.0.sign_proposal_part(proposal_part).await
+    }
+
+    async fn verify_signed_proposal_part(
+        &self,
+        proposal_part: &ProposalPart,
+        signature: &Signature,
+        public_key: &PublicKey,
+    ) -> Result<VerificationResult, SigningError> {
+        self.0
+            .verify_signed_proposal_part(proposal_part, signature, public_key)
+            .await
+    }
+
+    async fn sign_vote_extension(
+        &self,
+        extension: Bytes,
+    ) -> Result<SignedExtension<TestContext>, SigningError> {
+        self.0.sign_vote_extension(extension).await
+    }
+
+    async fn verify_signed_vote_extension(
+        &self,
+        extension: &Bytes,
+        signature: &Signature,
+        public_key: &PublicKey,
+    ) -> Result<VerificationResult, SigningError> {
+        self.0
+            .verify_signed_vote_extension(extension, signature, public_key)
+            .await
+    }
+
+    async fn verify_signed_votes(
+        &self,
+        _votes: &[(Vote, &Signature, &PublicKey)],
+    ) -> Result<Vec<VerificationResult>, SigningError> {
+        Err(SigningError::new())
+    }
+}
+
+#[test]
+fn certificate_verified_one_by_one_when_batch_fails() {
+    let private_keys = private_keys(4);
+
+    let validators = private_keys
+        .iter()
+        .map(|private_key| Validator::new(private_key.public_key(), 1))
+        .collect::<Vec<_>>();
+
+    let validator_set = ValidatorSet::new(validators);
+
+    let mut votes = private_keys
+        .iter()
+        .map(|private_key| {
+            let provider = Ed25519Provider::new(private_key.clone());
+            block_on(provider.sign_vote(precommit(private_key, 42))).unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    // The invalid signature is not counted, but the other ones still make a quorum
+    votes[3].signature = private_keys[3].sign(b"other");
+
+    let certificate = CommitCertificate::new(
+        Height::new(1),
+        Round::new(0),
+        ValueId::new(42),
+        votes.clone(),
+    );
+
+    let provider = NoBatchProvider(Ed25519Provider::new(private_keys[0].clone()));
+
+    assert!(block_on(provider.verify_commit_certificate(
+        &TestContext::new(),
+        &certificate,
+        &validator_set,
+        ThresholdParams::default(),
+    ))
+    .is_ok());
+
+    votes[2].signature = private_keys[2].sign(b"other");
+
+    let certificate =
+        CommitCertificate::new(Height::new(1), Round::new(0), ValueId::new(42), votes);
+
+    assert!(block_on(provider.verify_commit_certificate(
+        &TestContext::new(),
+        &certificate,
+        &validator_set,
+        ThresholdParams::default(),
+    ))
+    .is_err());
+}
@@ -0,0 +1,224 @@
+use std::path::Path;
+
+use informalsystems_malachitebft_test::codec::proto::ProtobufCodec;
+use informalsystems_malachitebft_test::{
+    Address, Ed25519Provider, Height, PrivateKey, Proposal, TestContext, Value, ValueId, Vote,
+};
+use malachitebft_core_types::{NilOrVal, Round, VoteType};
+use malachitebft_signing::SigningProvider;
+use malachitebft_signing_guard::{DoubleSignGuard, Error, SignState, Step};
+
+type Guard = DoubleSignGuard<TestContext, ProtobufCodec, Ed25519Provider>;
+
+fn private_key() -> PrivateKey {
+    PrivateKey::from([1; 32])
+}
+
+fn address() -> Address {
+    Address::from_public_key(&private_key().public_key())
+}
+
+fn guard(path: &Path) -> Guard {
+    DoubleSignGuard::open(path, ProtobufCodec, Ed25519Provider::new(private_key())).unwrap()
+}
+
+fn vote(vote_type: VoteType, height: u64, round: u32, value: Option<u64>) -> Vote {
+    let value = value.map_or(NilOrVal::Nil, |v| NilOrVal::Val(ValueId::new(v)));
+    let (height, round) = (Height::new(height), Round::new(round));
+
+    match vote_type {
+        VoteType::Prevote => Vote::new_prevote(height, round, value, address()),
+        VoteType::Precommit => Vote::new_precommit(height, round, value, address()),
+    }
+}
+
+fn proposal(height: u64, round: u32, value: u64) -> Proposal {
+    Proposal::new(
+        Height::new(height),
+        Round::new(round),
+        Value::new(value),
+        Round::Nil,
+        address(),
+    )
+}
+
+/// Check that signing failed because of the guard, with the given kind of error.
+fn assert_refused<T: std::fmt::Debug>(
+    result: Result<T, malachitebft_signing::Error>,
+    check: impl Fn(&Error) -> bool,
+) {
+    let error = result.unwrap_err();
+    let source = error.into_source().expect("error has a source");
+    let error = source
+        .0
+        .downcast_ref::<Error>()
+        .expect("error is a guard error");
+    assert!(check(error), "unexpected error: {error}");
+}
+
+#[tokio::test]
+async fn sign_in_order() {
+    let dir = tempfile::tempdir().unwrap();
+    let guard = guard(&dir.path().join("state.json"));
+    assert_eq!(guard.state().await, None);
+
+    guard.sign_proposal(proposal(1, 0, 42)).await.unwrap();
+    guard
+        .sign_vote(vote(VoteType::Prevote, 1, 0, Some(42)))
+        .await
+        .unwrap();
+    guard
+        .sign_vote(vote(VoteType::Precommit, 1, 0, None))
+        .await
+        .unwrap();
+    guard
+        .sign_vote(vote(VoteType::Prevote, 1, 1, Some(43)))
+        .await
+        .unwrap();
+    guard
+        .sign_vote(vote(VoteType::Prevote, 2, 0, Some(44)))
+        .await
+        .unwrap();
+
+    let state = guard.state().await.unwrap();
+    assert_eq!(
+        (state.height, state.round, state.step),
+        (2, 0, Step::Prevote)
+    );
+    assert!(state.value.is_some());
+
+    // The state is persisted
+    assert_eq!(SignState::load(guard.path()).unwrap(), Some(state));
+}
+
+#[tokio::test]
+async fn sign_same_vote_again() {
+    let dir = tempfile::tempdir().unwrap();
+    let guard = guard(&dir.path().join("state.json"));
+
+    let first = guard
+        .sign_vote(vote(VoteType::Prevote, 1, 0, Some(42)))
+        .await
+        .unwrap();
+
+    let second = guard
+        .sign_vote(vote(VoteType::Prevote, 1, 0, Some(42)))
+        .await
+        .unwrap();
+
+    assert_eq!(first, second);
+}
+
+#[tokio::test]
+async fn refuse_conflicting_vote() {
+    let dir = tempfile::tempdir().unwrap();
+    let guard = guard(&dir.path().join("state.json"));
+
+    guard
+        .sign_vote(vote(VoteType::Prevote, 1, 0, Some(42)))
+        .await
+        .unwrap();
+
+    assert_refused(
+        guard
+            .sign_vote(vote(VoteType::Prevote, 1, 0, Some(43)))
+            .await,
+        |e| matches!(e, Error::Conflict { .. }),
+    );
+
+    assert_refused(
+        guard.sign_vote(vote(VoteType::Prevote, 1, 0, None)).await,
+        |e| matches!(e, Error::Conflict { .. }),
+    );
+}
+
+#[tokio::test]
+async fn refuse_conflicting_proposal() {
+    let dir = tempfile::tempdir().unwrap();
+    let guard = guard(&dir.path().join("state.json"));
+
+    guard.sign_proposal(proposal(1, 0, 42)).await.unwrap();
+    guard.sign_proposal(proposal(1, 0, 42)).await.unwrap();
+
+    assert_refused(guard.sign_proposal(proposal(1, 0, 43)).await, |e| {
+        matches!(e, Error::Conflict { .. })
+    });
+}
+
+#[tokio::test]
+async fn refuse_regression() {
+    let dir = tempfile::tempdir().unwrap();
+    let guard = guard(&dir.path().join("state.json"));
+
+    guard
+        .sign_vote(vote(VoteType::Precommit, 2, 1, Some(42)))
+        .await
+        .unwrap();
+
+    let earlier = [
+        vote(VoteType::Precommit, 1, 1, Some(42)),
+        vote(VoteType::Precommit, 2, 0, Some(42)),
+        vote(VoteType::Prevote, 2, 1, Some(42)),
+    ];
+
+    for vote in earlier {
+        assert_refused(guard.sign_vote(vote).await, |e| {
+            matches!(e, Error::Regression { .. })
+        });
+    }
+
+    assert_refused(guard.sign_proposal(proposal(2, 1, 42)).await, |e| {
+        matches!(e, Error::Regression { .. })
+    });
+}
+
+#[tokio::test]
+async fn state_survives_restart() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("state.json");
+
+    let guard = guard(&path);
+    guard
+        .sign_vote(vote(VoteType::Precommit, 5, 0, Some(42)))
+        .await
+        .unwrap();
+
+    drop(guard);
+
+    // A restarted validator, eg. with its WAL reset, must not sign a conflicting vote
+    let guard = self::guard(&path);
+
+    assert_refused(
+        guard
+            .sign_vote(vote(VoteType::Precommit, 5, 0, Some(43)))
+            .await,
+        |e| matches!(e, Error::Conflict { .. }),
+    );
+
+    assert_refused(
+        guard
+            .sign_vote(vote(VoteType::Prevote, 5, 0, Some(43)))
+            .await,
+        |e| matches!(e, Error::Regression { .. }),
+    );
+
+    guard
+        .sign_vote(vote(VoteType::Prevote, 6, 0, Some(43)))
+        .await
+        .unwrap();
+}
+
+#[test]
+fn invalid_state_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("state.json");
+    std::fs::write(&path, "not json").unwrap();
+
+    let result = DoubleSignGuard::<TestContext, _, _>::open(
+        &path,
+        ProtobufCodec,
+        Ed25519Provider::new(private_key()),
+    );
+
+    assert!(matches!(result, Err(Error::InvalidState { .. })));
+}
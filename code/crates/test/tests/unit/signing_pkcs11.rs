@@ -0,0 +1,179 @@
+//! Tests of the PKCS#11 signing provider against a real token.
+//!
+//! Most of these tests are ignored by default, as they need a PKCS#11 module with an
+//! initialized token, eg. with SoftHSM:
+//!
+//! ```shell
+//! softhsm2-util --init-token --free --label malachite --pin 1234 --so-pin 1234
+//! MALACHITE_PKCS11_MODULE=/usr/lib/softhsm/libsofthsm2.so \
+//!     cargo test -p informalsystems-malachitebft-test --test unit -- signing_pkcs11 --ignored
+//! ```
+//!
+//! The label and PIN of the token default to `malachite` and `1234`, and can be set with the
+//! `MALACHITE_PKCS11_TOKEN` and `MALACHITE_PKCS11_PIN` environment variables.
+//!
+//! They are run against SoftHSM by the `pkcs11` job of the CI.
+
+use std::sync::OnceLock;
+
+use bytes::Bytes;
+
+use informalsystems_malachitebft_test::{
+    Address, Ed25519, Ed25519Provider, Height, PrivateKey, Proposal, ProposalData, ProposalPart,
+    TestContext, TestSignBytes, Value, ValueId, Vote,
+};
+use malachitebft_core_types::{NilOrVal, Round};
+use malachitebft_signing::SigningProvider;
+use malachitebft_signing_ecdsa::{Ecdsa, P256Config, P384Config};
+use malachitebft_signing_pkcs11::{Error, Module, Pkcs11Scheme, Pkcs11SigningProvider, Session};
+
+fn env_or(name: &str, default: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| default.to_string())
+}
+
+/// The module can only be initialized once per process, so it is shared by all the tests.
+fn module() -> &'static Module {
+    static MODULE: OnceLock<Module> = OnceLock::new();
+
+    MODULE.get_or_init(|| {
+        let path = std::env::var("MALACHITE_PKCS11_MODULE")
+            .expect("MALACHITE_PKCS11_MODULE must be set to the path of a PKCS#11 module");
+
+        Module::load(path).unwrap()
+    })
+}
+
+fn token_label() -> String {
+    env_or("MALACHITE_PKCS11_TOKEN", "malachite")
+}
+
+fn session() -> Session {
+    let pin = env_or("MALACHITE_PKCS11_PIN", "1234");
+    module().open(&token_label(), &pin).unwrap()
+}
+
+/// A label which is not used by any key on the token yet, so that the tests can be run again.
+fn fresh_label(prefix: &str) -> String {
+    format!("{prefix}-{:016x}", rand::random::<u64>())
+}
+
+#[test]
+fn load_missing_module() {
+    let result = Module::load("/nonexistent/libpkcs11.so");
+    assert!(matches!(result, Err(Error::Load { .. })));
+}
+
+#[tokio::test]
+#[ignore = "requires a PKCS#11 token, see the module docs"]
+async fn sign_with_ed25519_key() {
+    let session = session();
+    let label = fresh_label("ed25519");
+
+    let key = session.generate_key::<Ed25519>(&label).unwrap();
+    let public_key = *key.public_key();
+
+    let verifier = Ed25519Provider::new(PrivateKey::from([0xff; 32]));
+    let provider: Pkcs11SigningProvider<TestContext, _, _> =
+        Pkcs11SigningProvider::new(key, TestSignBytes, verifier);
+
+    let address = Address::from_public_key(&public_key);
+
+    let proposal = Proposal::new(
+        Height::new(1),
+        Round::new(0),
+        Value::new(42),
+        Round::Nil,
+        address,
+    );
+    let proposal = provider.sign_proposal(proposal).await.unwrap();
+    assert!(provider
+        .verify_signed_proposal(&proposal.message, &proposal.signature, &public_key)
+        .await
+        .unwrap()
+        .is_valid());
+
+    let part = ProposalPart::Data(ProposalData::new(42));
+    let part = provider.sign_proposal_part(part).await.unwrap();
+    assert!(provider
+        .verify_signed_proposal_part(&part.message, &part.signature, &public_key)
+        .await
+        .unwrap()
+        .is_valid());
+
+    let vote = Vote::new_prevote(
+        Height::new(1),
+        Round::new(0),
+        NilOrVal::Val(ValueId::new(42)),
+        address,
+    );
+    let vote = provider.sign_vote(vote).await.unwrap();
+    assert!(provider
+        .verify_signed_vote(&vote.message, &vote.signature, &public_key)
+        .await
+        .unwrap()
+        .is_valid());
+
+    let extension = provider
+        .sign_vote_extension(Bytes::from_static(b"extension"))
+        .await
+        .unwrap();
+    assert!(provider
+        .verify_signed_vote_extension(&extension.message, &extension.signature, &public_key)
+        .await
+        .unwrap()
+        .is_valid());
+
+    // The key can be found again, eg. after a restart
+    let key = session.find_key::<Ed25519>(&label).unwrap();
+    assert_eq!(key.public_key(), &public_key);
+}
+
+/// Generate a key for the given scheme, and check that it can be found again and signs messages.
+fn sign_with_key<S: Pkcs11Scheme>(prefix: &str) {
+    let session = session();
+    let label = fresh_label(prefix);
+
+    let key = session.generate_key::<S>(&label).unwrap();
+    let found = session.find_key::<S>(&label).unwrap();
+    assert_eq!(found.public_key(), key.public_key());
+
+    let signature = found.sign(b"hello").unwrap();
+    assert!(S::verify(key.public_key(), b"hello", &signature));
+    assert!(!S::verify(key.public_key(), b"other", &signature));
+
+    let result = session.generate_key::<S>(&label);
+    assert!(matches!(result, Err(Error::KeyExists(_))));
+}
+
+#[test]
+#[ignore = "requires a PKCS#11 token, see the module docs"]
+fn sign_with_p256_key() {
+    sign_with_key::<Ecdsa<P256Config>>("p256");
+}
+
+#[test]
+#[ignore = "requires a PKCS#11 token, see the module docs"]
+fn sign_with_p384_key() {
+    sign_with_key::<Ecdsa<P384Config>>("p384");
+}
+
+#[test]
+#[ignore = "requires a PKCS#11 token, see the module docs"]
+fn missing_key() {
+    let result = session().find_key::<Ed25519>(&fresh_label("missing"));
+    assert!(matches!(result, Err(Error::KeyNotFound { .. })));
+
+    // Keys are looked up by type as well as by label
+    let label = fresh_label("typed");
+    session().generate_key::<Ecdsa<P256Config>>(&label).unwrap();
+
+    let result = session().find_key::<Ed25519>(&label);
+    assert!(matches!(result, Err(Error::KeyNotFound { .. })));
+}
+
+#[test]
+#[ignore = "requires a PKCS#11 token, see the module docs"]
+fn unknown_token() {
+    let result = module().open("no-such-token", "1234");
+    assert!(matches!(result, Err(Error::TokenNotFound(_))));
+}
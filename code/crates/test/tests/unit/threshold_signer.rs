@@ -0,0 +1,636 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use informalsystems_malachitebft_test::codec::proto::ProtobufCodec;
+use informalsystems_malachitebft_test::{
+    Address, Ed25519Provider, Height, PrivateKey, Proposal, ProposalData, ProposalPart, PublicKey,
+    TestContext, TestSignBytes, Value, ValueId, Vote,
+};
+use malachitebft_core_types::{NilOrVal, Round};
+use malachitebft_remote_signer::{
+    Address as CosignerAddress, IdentityKey, IdentityPublicKey, Listener,
+};
+use malachitebft_signing::SigningProvider;
+use malachitebft_signing_bls::{BlsSigningProvider, PrivateKey as BlsPrivateKey};
+use malachitebft_threshold_signer::bls;
+use malachitebft_threshold_signer::frost::{
+    self, Identifier, KeyShare, PublicKeyPackage, SignatureShare, SigningCommitments, SigningNonces,
+};
+use malachitebft_threshold_signer::{
+    BlsCosigner, BlsThresholdSigningProvider, Cosigner, CosignerServer, Error, LocalBlsCosigner,
+    LocalCosigner, Message, RemoteCosigner, ThresholdSigningProvider,
+};
+
+use crate::certificates::aggregated::bls::{BlsContext, BlsSignBytes, BlsVote};
+
+type Local = LocalCosigner<TestContext, TestSignBytes, ProtobufCodec>;
+type Provider<K> = ThresholdSigningProvider<TestContext, K, TestSignBytes, Ed25519Provider>;
+
+fn rng() -> StdRng {
+    StdRng::seed_from_u64(0x42)
+}
+
+fn id(id: u16) -> Identifier {
+    Identifier::new(id).unwrap()
+}
+
+fn vote(round: u32, value: u64, public_key: &PublicKey) -> Vote {
+    Vote::new_prevote(
+        Height::new(1),
+        Round::new(round),
+        NilOrVal::Val(ValueId::new(value)),
+        Address::from_public_key(public_key),
+    )
+}
+
+/// A verifier whose own key is never used, as all the signatures are made by the cosigners.
+fn verifier() -> Ed25519Provider {
+    Ed25519Provider::new(PrivateKey::from([0xff; 32]))
+}
+
+fn local_cosigners(key_shares: Vec<KeyShare>, dir: &Path) -> Vec<Local> {
+    key_shares
+        .into_iter()
+        .map(|share| {
+            let path = dir.join(format!("state-{}.json", share.identifier()));
+            LocalCosigner::open(share, path, TestSignBytes, ProtobufCodec).unwrap()
+        })
+        .collect()
+}
+
+fn provider<K: Cosigner<TestContext>>(package: PublicKeyPackage, cosigners: Vec<K>) -> Provider<K> {
+    ThresholdSigningProvider::new(package, cosigners, TestSignBytes, verifier())
+        .unwrap()
+        .with_timeout(Duration::from_secs(1))
+}
+
+/// Sign the given message with the given key shares, as in each round of the protocol.
+fn frost_sign(
+    message: &[u8],
+    key_shares: &[&KeyShare],
+    package: &PublicKeyPackage,
+) -> Result<informalsystems_malachitebft_test::Signature, Error> {
+    let nonces = key_shares
+        .iter()
+        .map(|share| SigningNonces::new(share, rng()))
+        .collect::<Vec<_>>();
+
+    let commitments = key_shares
+        .iter()
+        .zip(&nonces)
+        .map(|(share, nonces)| (share.identifier(), *nonces.commitments()))
+        .collect::<BTreeMap<_, _>>();
+
+    let mut shares = BTreeMap::new();
+    for (share, nonces) in key_shares.iter().zip(nonces) {
+        let signature_share = frost::sign(message, &commitments, nonces, share)?;
+        shares.insert(share.identifier(), signature_share);
+    }
+
+    frost::aggregate(message, &commitments, &shares, package)
+}
+
+#[test]
+fn frost_sign_with_any_quorum() {
+    let (key_shares, package) = frost::generate_with_dealer(3, 5, rng()).unwrap();
+    let public_key = package.group_public_key();
+
+    for (i, j, k) in [(0, 1, 2), (0, 2, 4), (1, 3, 4), (2, 3, 4)] {
+        let quorum = [&key_shares[i], &key_shares[j], &key_shares[k]];
+        let signature = frost_sign(b"hello", &quorum, &package).unwrap();
+        assert!(public_key.verify(b"hello", &signature).is_ok());
+        assert!(public_key.verify(b"other", &signature).is_err());
+    }
+
+    // More than the threshold can sign as well
+    let all = key_shares.iter().collect::<Vec<_>>();
+    let signature = frost_sign(b"hello", &all, &package).unwrap();
+    assert!(public_key.verify(b"hello", &signature).is_ok());
+
+    // But less cannot
+    let result = frost_sign(b"hello", &all[..2], &package);
+    assert!(matches!(result, Err(Error::NotEnoughCosigners { .. })));
+}
+
+#[test]
+fn frost_split_private_key() {
+    let private_key = PrivateKey::from([1; 32]);
+
+    let (key_shares, package) = frost::split_private_key(&private_key, 2, 3, rng()).unwrap();
+    assert_eq!(package.group_public_key(), private_key.public_key());
+
+    let signature = frost_sign(b"hello", &[&key_shares[0], &key_shares[2]], &package).unwrap();
+    assert!(private_key
+        .public_key()
+        .verify(b"hello", &signature)
+        .is_ok());
+}
+
+#[test]
+fn frost_invalid_share() {
+    let (key_shares, package) = frost::generate_with_dealer(2, 3, rng()).unwrap();
+
+    let nonces = key_shares[..2]
+        .iter()
+        .map(|share| SigningNonces::new(share, rng()))
+        .collect::<Vec<_>>();
+
+    let commitments = key_shares
+        .iter()
+        .zip(&nonces)
+        .map(|(share, nonces)| (share.identifier(), *nonces.commitments()))
+        .collect::<BTreeMap<_, _>>();
+
+    let mut nonces = nonces.into_iter();
+    let share = frost::sign(
+        b"hello",
+        &commitments,
+        nonces.next().unwrap(),
+        &key_shares[0],
+    );
+
+    // A share computed for another message
+    let other = frost::sign(
+        b"other",
+        &commitments,
+        nonces.next().unwrap(),
+        &key_shares[1],
+    );
+
+    let shares = BTreeMap::from([(id(1), share.unwrap()), (id(2), other.unwrap())]);
+    let result = frost::aggregate(b"hello", &commitments, &shares, &package);
+    assert!(matches!(result, Err(Error::InvalidShare(id)) if id.get() == 2));
+}
+
+#[test]
+fn frost_invalid_threshold() {
+    for (threshold, total) in [(0, 3), (1, 1), (1, 2), (2, 4), (4, 3)] {
+        let result = frost::generate_with_dealer(threshold, total, rng());
+        assert!(
+            matches!(result, Err(Error::InvalidThreshold { .. })),
+            "{threshold} of {total}"
+        );
+    }
+}
+
+#[test]
+fn key_files_roundtrip() {
+    let (key_shares, package) = frost::generate_with_dealer(2, 3, rng()).unwrap();
+
+    let json = serde_json::to_string(&key_shares[1]).unwrap();
+    let key_share: KeyShare = serde_json::from_str(&json).unwrap();
+    assert_eq!(key_share.identifier(), id(2));
+    assert_eq!(key_share.verifying_share(), key_shares[1].verifying_share());
+    assert!(package.contains(&key_share));
+
+    let json = serde_json::to_string(&package).unwrap();
+    assert_eq!(
+        serde_json::from_str::<PublicKeyPackage>(&json).unwrap(),
+        package
+    );
+
+    let (_, other_package) =
+        frost::generate_with_dealer(2, 3, StdRng::seed_from_u64(0x43)).unwrap();
+    assert!(!other_package.contains(&key_share));
+}
+
+#[tokio::test]
+async fn sign_with_local_cosigners() {
+    let dir = tempfile::tempdir().unwrap();
+    let (key_shares, package) = frost::generate_with_dealer(2, 3, rng()).unwrap();
+    let public_key = package.group_public_key();
+
+    let provider = provider(package, local_cosigners(key_shares, dir.path()));
+    assert_eq!(provider.public_key(), public_key);
+
+    let proposal = Proposal::new(
+        Height::new(1),
+        Round::new(0),
+        Value::new(42),
+        Round::Nil,
+        Address::from_public_key(&public_key),
+    );
+    let proposal = provider.sign_proposal(proposal).await.unwrap();
+    assert!(provider
+        .verify_signed_proposal(&proposal.message, &proposal.signature, &public_key)
+        .await
+        .unwrap()
+        .is_valid());
+
+    let vote = provider.sign_vote(vote(0, 42, &public_key)).await.unwrap();
+    assert!(provider
+        .verify_signed_vote(&vote.message, &vote.signature, &public_key)
+        .await
+        .unwrap()
+        .is_valid());
+
+    let part = ProposalPart::Data(ProposalData::new(42));
+    let part = provider.sign_proposal_part(part).await.unwrap();
+    assert!(provider
+        .verify_signed_proposal_part(&part.message, &part.signature, &public_key)
+        .await
+        .unwrap()
+        .is_valid());
+
+    let extension = provider
+        .sign_vote_extension(Bytes::from_static(b"extension"))
+        .await
+        .unwrap();
+    assert!(provider
+        .verify_signed_vote_extension(&extension.message, &extension.signature, &public_key)
+        .await
+        .unwrap()
+        .is_valid());
+
+    let peer_id = b"peer id";
+    let signature = provider.sign_peer_identity(peer_id).await.unwrap();
+    assert!(provider
+        .verify_peer_identity(peer_id, &signature, &public_key)
+        .await
+        .unwrap()
+        .is_valid());
+}
+
+/// A cosigner which is down.
+struct Offline(Identifier);
+
+#[async_trait]
+impl Cosigner<TestContext> for Offline {
+    fn identifier(&self) -> Identifier {
+        self.0
+    }
+
+    async fn commit(&self) -> Result<SigningCommitments, Error> {
+        Err(Error::Timeout)
+    }
+
+    async fn sign(
+        &self,
+        _message: Message<'_, TestContext>,
+        _commitments: &BTreeMap<Identifier, SigningCommitments>,
+    ) -> Result<SignatureShare, Error> {
+        Err(Error::Timeout)
+    }
+}
+
+/// A cosigner which commits to its nonces but then sends a garbage signature share.
+struct Byzantine(Local);
+
+#[async_trait]
+impl Cosigner<TestContext> for Byzantine {
+    fn identifier(&self) -> Identifier {
+        self.0.identifier()
+    }
+
+    async fn commit(&self) -> Result<SigningCommitments, Error> {
+        self.0.commit().await
+    }
+
+    async fn sign(
+        &self,
+        _message: Message<'_, TestContext>,
+        _commitments: &BTreeMap<Identifier, SigningCommitments>,
+    ) -> Result<SignatureShare, Error> {
+        SignatureShare::from_bytes(&[1; 32])
+    }
+}
+
+#[tokio::test]
+async fn sign_despite_faulty_cosigners() {
+    let dir = tempfile::tempdir().unwrap();
+    let (key_shares, package) = frost::generate_with_dealer(3, 5, rng()).unwrap();
+    let public_key = package.group_public_key();
+
+    let mut locals = local_cosigners(key_shares.clone(), dir.path()).into_iter();
+    let _ = locals.next();
+    let second = locals.next().unwrap();
+
+    let mut cosigners: Vec<Box<dyn Cosigner<TestContext>>> =
+        vec![Box::new(Offline(id(1))), Box::new(Byzantine(second))];
+    cosigners.extend(locals.map(|local| Box::new(local) as Box<dyn Cosigner<TestContext>>));
+
+    // The offline cosigner never commits, so the byzantine one is asked to sign,
+    // and the message is then signed again without it
+    let provider = provider(package.clone(), cosigners);
+    let vote = provider.sign_vote(vote(0, 42, &public_key)).await.unwrap();
+    assert!(public_key
+        .verify(&vote.message.to_sign_bytes(), &vote.signature)
+        .is_ok());
+
+    // Not enough cosigners are left once more than `n - t` of them are faulty
+    let mut locals = local_cosigners(key_shares, dir.path()).into_iter().skip(3);
+    let cosigners: Vec<Box<dyn Cosigner<TestContext>>> = vec![
+        Box::new(Offline(id(1))),
+        Box::new(Offline(id(2))),
+        Box::new(Offline(id(3))),
+        Box::new(locals.next().unwrap()),
+        Box::new(locals.next().unwrap()),
+    ];
+
+    let provider = self::provider(package, cosigners);
+    let error = provider
+        .sign_vote(vote.message.clone())
+        .await
+        .unwrap_err()
+        .into_source()
+        .unwrap();
+    assert!(matches!(
+        error.0.downcast_ref::<Error>(),
+        Some(Error::NotEnoughCosigners { .. })
+    ));
+}
+
+#[tokio::test]
+async fn invalid_cosigners() {
+    let (_, package) = frost::generate_with_dealer(2, 3, rng()).unwrap();
+
+    let new = |cosigners: Vec<Offline>| {
+        ThresholdSigningProvider::new(package.clone(), cosigners, TestSignBytes, verifier())
+    };
+
+    let result = new(vec![Offline(id(1)), Offline(id(4))]);
+    assert!(matches!(result, Err(Error::UnknownCosigner(id)) if id.get() == 4));
+
+    let result = new(vec![Offline(id(1)), Offline(id(1))]);
+    assert!(matches!(result, Err(Error::DuplicateCosigner(id)) if id.get() == 1));
+
+    let result = new(vec![Offline(id(3))]);
+    assert!(matches!(result, Err(Error::NotEnoughCosigners { .. })));
+}
+
+fn node_identity() -> IdentityKey {
+    IdentityKey::from_bytes([4; 32])
+}
+
+fn cosigner_identity(identifier: Identifier) -> IdentityKey {
+    IdentityKey::from_bytes([0xc0 + identifier.get() as u8; 32])
+}
+
+async fn spawn_cosigner(
+    key_share: KeyShare,
+    dir: &Path,
+) -> (Identifier, CosignerAddress, IdentityPublicKey) {
+    let identifier = key_share.identifier();
+    let path = dir.join(format!("state-{identifier}.json"));
+    let cosigner = LocalCosigner::open(key_share, path, TestSignBytes, ProtobufCodec).unwrap();
+
+    let listener = Listener::bind(&"tcp://127.0.0.1:0".parse().unwrap())
+        .await
+        .unwrap();
+    let address = listener.local_address().unwrap();
+
+    let identity = cosigner_identity(identifier);
+    let public_key = identity.public_key();
+    let authorized_nodes = vec![node_identity().public_key()];
+
+    let server = CosignerServer::new(identity, authorized_nodes, ProtobufCodec, cosigner);
+    tokio::spawn(server.serve(listener));
+
+    (identifier, address, public_key)
+}
+
+fn remote_cosigner(
+    (identifier, address, public_key): &(Identifier, CosignerAddress, IdentityPublicKey),
+    identity: IdentityKey,
+) -> RemoteCosigner<TestContext, ProtobufCodec> {
+    RemoteCosigner::new(
+        *identifier,
+        address.clone(),
+        identity,
+        *public_key,
+        ProtobufCodec,
+    )
+}
+
+#[tokio::test]
+async fn remote_cosigners_refuse_conflicting_votes() {
+    let dir = tempfile::tempdir().unwrap();
+    let (key_shares, package) = frost::generate_with_dealer(2, 3, rng()).unwrap();
+    let public_key = package.group_public_key();
+
+    let mut cosigners = Vec::new();
+    for key_share in key_shares {
+        cosigners.push(spawn_cosigner(key_share, dir.path()).await);
+    }
+
+    // Two nodes of the same validator, running at the same time with the same cosigners
+    let remote_cosigners = || {
+        cosigners
+            .iter()
+            .map(|cosigner| remote_cosigner(cosigner, node_identity()))
+            .collect::<Vec<_>>()
+    };
+
+    let first = provider(package.clone(), remote_cosigners());
+    let second = provider(package, remote_cosigners());
+
+    let signed = first.sign_vote(vote(0, 42, &public_key)).await.unwrap();
+    assert!(public_key
+        .verify(&signed.message.to_sign_bytes(), &signed.signature)
+        .is_ok());
+
+    // The same vote can be signed again, eg. by the other node
+    second.sign_vote(vote(0, 42, &public_key)).await.unwrap();
+
+    // But not a vote for another value at the same height, round and step
+    let error = second
+        .sign_vote(vote(0, 43, &public_key))
+        .await
+        .unwrap_err()
+        .into_source()
+        .unwrap();
+    assert!(matches!(
+        error.0.downcast_ref::<Error>(),
+        Some(Error::NotEnoughCosigners { .. })
+    ));
+
+    // Moving on to the next round is fine
+    second.sign_vote(vote(1, 43, &public_key)).await.unwrap();
+    first.sign_vote(vote(1, 43, &public_key)).await.unwrap();
+
+    // Peer identities are signed regardless of the votes signed so far
+    let signature = first.sign_peer_identity(b"peer id").await.unwrap();
+    assert!(first
+        .verify_peer_identity(b"peer id", &signature, &public_key)
+        .await
+        .unwrap()
+        .is_valid());
+}
+
+#[tokio::test]
+async fn remote_cosigners_reject_unauthorized_nodes() {
+    let dir = tempfile::tempdir().unwrap();
+    let (key_shares, package) = frost::generate_with_dealer(2, 3, rng()).unwrap();
+    let public_key = package.group_public_key();
+
+    let mut cosigners = Vec::new();
+    for key_share in key_shares {
+        cosigners.push(spawn_cosigner(key_share, dir.path()).await);
+    }
+
+    // A node with an unknown identity key
+    let unauthorized = cosigners
+        .iter()
+        .map(|cosigner| remote_cosigner(cosigner, IdentityKey::from_bytes([5; 32])))
+        .collect::<Vec<_>>();
+
+    let error = provider(package.clone(), unauthorized)
+        .sign_vote(vote(0, 42, &public_key))
+        .await
+        .unwrap_err()
+        .into_source()
+        .unwrap();
+    assert!(matches!(
+        error.0.downcast_ref::<Error>(),
+        Some(Error::NotEnoughCosigners { .. })
+    ));
+
+    // A cosigner with another identity than the one the node expects
+    let (identifier, address, _) = &cosigners[0];
+    let impostor = RemoteCosigner::<TestContext, _>::new(
+        *identifier,
+        address.clone(),
+        node_identity(),
+        cosigners[1].2,
+        ProtobufCodec,
+    );
+
+    assert!(matches!(
+        impostor.commit().await,
+        Err(Error::Transport(
+            malachitebft_remote_signer::Error::Unauthorized(_)
+        ))
+    ));
+}
+
+type BlsLocal = LocalBlsCosigner<BlsContext, BlsSignBytes, ProtobufCodec>;
+
+fn bls_precommit(round: u32, value: u64) -> BlsVote {
+    BlsVote(Vote::new_precommit(
+        Height::new(1),
+        Round::new(round),
+        NilOrVal::Val(ValueId::new(value)),
+        Address::new([1; 20]),
+    ))
+}
+
+fn bls_local_cosigners(key_shares: Vec<bls::KeyShare>, dir: &Path) -> Vec<BlsLocal> {
+    key_shares
+        .into_iter()
+        .map(|share| {
+            let path = dir.join(format!("bls-state-{}.json", share.identifier()));
+            LocalBlsCosigner::open(share, path, BlsSignBytes, ProtobufCodec).unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn bls_combine_with_any_quorum() {
+    let private_key = BlsPrivateKey::from([1; 32]);
+
+    let (key_shares, package) = bls::split_private_key(&private_key, 3, 5, rng()).unwrap();
+    assert_eq!(package.group_public_key(), private_key.public_key());
+
+    let sign = |indices: &[usize], message: &[u8]| {
+        indices
+            .iter()
+            .map(|&i| {
+                (
+                    key_shares[i].identifier(),
+                    bls::sign(message, &key_shares[i]),
+                )
+            })
+            .collect::<BTreeMap<_, _>>()
+    };
+
+    // Any quorum combines its shares into the signature of the private key itself
+    for quorum in [[0, 1, 2], [0, 2, 4], [1, 3, 4], [2, 3, 4]] {
+        let signature = bls::aggregate(b"hello", &sign(&quorum, b"hello"), &package).unwrap();
+        assert_eq!(signature, private_key.sign(b"hello"));
+    }
+
+    // But less cannot
+    let result = bls::aggregate(b"hello", &sign(&[0, 1], b"hello"), &package);
+    assert!(matches!(result, Err(Error::NotEnoughCosigners { .. })));
+
+    // And a share of another message is told apart
+    let mut shares = sign(&[0, 1, 2], b"hello");
+    shares.extend(sign(&[1], b"other"));
+    let result = bls::aggregate(b"hello", &shares, &package);
+    assert!(matches!(result, Err(Error::InvalidShare(id)) if id.get() == 2));
+}
+
+/// A BLS cosigner which sends the signature share of another message.
+struct BlsByzantine(BlsLocal);
+
+#[async_trait]
+impl BlsCosigner<BlsContext> for BlsByzantine {
+    fn identifier(&self) -> Identifier {
+        self.0.identifier()
+    }
+
+    async fn sign(&self, _message: Message<'_, BlsContext>) -> Result<bls::SignatureShare, Error> {
+        Ok(bls::sign(b"garbage", self.0.key_share()))
+    }
+}
+
+#[tokio::test]
+async fn bls_sign_with_local_cosigners() {
+    let dir = tempfile::tempdir().unwrap();
+    let private_key = BlsPrivateKey::from([1; 32]);
+    let public_key = private_key.public_key();
+
+    let (key_shares, package) = bls::split_private_key(&private_key, 2, 3, rng()).unwrap();
+
+    let mut locals = bls_local_cosigners(key_shares, dir.path()).into_iter();
+    let first = locals.next().unwrap();
+
+    let mut cosigners: Vec<Box<dyn BlsCosigner<BlsContext>>> = vec![Box::new(BlsByzantine(first))];
+    cosigners.extend(locals.map(|local| Box::new(local) as Box<dyn BlsCosigner<BlsContext>>));
+
+    let verifier = BlsSigningProvider::new(BlsPrivateKey::from([0xff; 32]), BlsSignBytes);
+    let provider = BlsThresholdSigningProvider::new(package, cosigners, BlsSignBytes, verifier)
+        .unwrap()
+        .with_timeout(Duration::from_secs(1));
+    assert_eq!(provider.public_key(), public_key);
+
+    // The invalid share is left out, and the precommit is signed over the commit message,
+    // just like the private key of the validator would, so that it can be aggregated
+    let vote = provider.sign_vote(bls_precommit(0, 42)).await.unwrap();
+    let expected = BlsSigningProvider::new(private_key, BlsSignBytes)
+        .sign_vote(bls_precommit(0, 42))
+        .await
+        .unwrap();
+    assert_eq!(vote.signature, expected.signature);
+    assert!(provider
+        .verify_signed_vote(&vote.message, &vote.signature, &public_key)
+        .await
+        .unwrap()
+        .is_valid());
+
+    // A conflicting precommit is refused by the cosigners
+    let error = provider
+        .sign_vote(bls_precommit(0, 43))
+        .await
+        .unwrap_err()
+        .into_source()
+        .unwrap();
+    assert!(matches!(
+        error.0.downcast_ref::<Error>(),
+        Some(Error::NotEnoughCosigners { .. })
+    ));
+
+    let peer_id = b"peer id";
+    let signature = provider.sign_peer_identity(peer_id).await.unwrap();
+    assert!(provider
+        .verify_peer_identity(peer_id, &signature, &public_key)
+        .await
+        .unwrap()
+        .is_valid());
+}
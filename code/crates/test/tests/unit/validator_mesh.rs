@@ -0,0 +1,242 @@
+use std::collections::BTreeSet;
+
+use futures::executor::block_on;
+
+use informalsystems_malachitebft_test::utils::validators::{
+    make_validators, make_validators_seeded,
+};
+use informalsystems_malachitebft_test::{
+    Address, Ed25519Provider, PrivateKey, Signature, TestContext, Validator, ValidatorSet,
+};
+use malachitebft_engine::consensus::validator_mesh::{InvalidSignature, ValidatorPeers};
+use malachitebft_network::validator_mesh::ValidatorMesh;
+use malachitebft_network::Channel;
+use malachitebft_peer::PeerId;
+use malachitebft_signing::{peer_identity_sign_bytes, SigningProvider};
+
+fn provider(private_key: &PrivateKey) -> Ed25519Provider {
+    Ed25519Provider::new(private_key.clone())
+}
+
+fn handshake(private_key: &PrivateKey, peer_id: PeerId) -> Signature {
+    block_on(provider(private_key).sign_peer_identity(&peer_id.to_bytes())).unwrap()
+}
+
+/// Receive a handshake from `peer_id`, in which the given validator signed the ID of `signed`.
+fn receive(
+    peers: &mut ValidatorPeers<TestContext>,
+    verifier: &Ed25519Provider,
+    peer_id: PeerId,
+    (validator, private_key): &(Validator, PrivateKey),
+    signed: PeerId,
+) -> Result<bool, InvalidSignature> {
+    let proof = handshake(private_key, signed);
+    block_on(peers.on_handshake(verifier, peer_id, validator.address.to_string(), proof))
+}
+
+fn validator_set(validators: &[&(Validator, PrivateKey)]) -> ValidatorSet {
+    ValidatorSet::new(validators.iter().map(|(v, _)| v.clone()))
+}
+
+#[test]
+fn peer_identity_signature() {
+    let [(v1, sk1), (v2, _)] = make_validators([1, 1]);
+    let peer_id = PeerId::random();
+    let other_peer_id = PeerId::random();
+
+    let signature = handshake(&sk1, peer_id);
+    let verifier = provider(&sk1);
+
+    let verify = |peer_id: PeerId, validator: &Validator| {
+        block_on(verifier.verify_peer_identity(
+            &peer_id.to_bytes(),
+            &signature,
+            &validator.public_key,
+        ))
+        .unwrap()
+    };
+
+    assert!(verify(peer_id, &v1).is_valid());
+    assert!(verify(other_peer_id, &v1).is_invalid());
+    assert!(verify(peer_id, &v2).is_invalid());
+
+    // Peer identities are signed in their own domain, so that the signature
+    // cannot be replayed as a signature of a consensus message
+    assert_ne!(
+        peer_identity_sign_bytes(&peer_id.to_bytes()),
+        peer_id.to_bytes()
+    );
+}
+
+#[test]
+fn handshakes_are_verified_against_the_validator_set() {
+    let [val1, val2, full_node] = make_validators([1, 1, 1]);
+    let verifier = provider(&val1.1);
+
+    let (peer1, peer2, peer3) = (PeerId::random(), PeerId::random(), PeerId::random());
+
+    let mut peers = ValidatorPeers::<TestContext>::default();
+    assert!(!block_on(peers.update_validator_set(
+        &verifier,
+        &validator_set(&[&val1, &val2])
+    )));
+
+    assert_eq!(
+        receive(&mut peers, &verifier, peer1, &val1, peer1),
+        Ok(true)
+    );
+    assert_eq!(
+        receive(&mut peers, &verifier, peer3, &full_node, peer3),
+        Ok(false)
+    );
+
+    // A validator cannot sign on behalf of another peer
+    assert_eq!(
+        receive(&mut peers, &verifier, peer2, &val2, peer1),
+        Err(InvalidSignature)
+    );
+
+    assert_eq!(peers.peers(), BTreeSet::from([peer1]));
+    assert_eq!(
+        peers.validator_of(&peer1),
+        Some(&Address::from_public_key(&val1.0.public_key))
+    );
+}
+
+#[test]
+fn handshakes_are_only_verified_against_the_named_validator() {
+    let [val1, val2] = make_validators([1, 1]);
+    let verifier = provider(&val1.1);
+    let peer1 = PeerId::random();
+
+    let mut peers = ValidatorPeers::<TestContext>::default();
+    block_on(peers.update_validator_set(&verifier, &validator_set(&[&val1, &val2])));
+
+    // Signed by validator 1, but naming validator 2
+    let proof = handshake(&val1.1, peer1);
+    assert_eq!(
+        block_on(peers.on_handshake(&verifier, peer1, val2.0.address.to_string(), proof)),
+        Err(InvalidSignature)
+    );
+
+    // Naming a validator which is not in the set
+    assert_eq!(
+        block_on(peers.on_handshake(&verifier, peer1, "unknown".to_string(), proof)),
+        Ok(false)
+    );
+
+    assert!(peers.peers().is_empty());
+}
+
+#[test]
+fn handshakes_received_before_the_validator_set_are_verified_later() {
+    let [val1] = make_validators([1]);
+    let verifier = provider(&val1.1);
+    let peer1 = PeerId::random();
+
+    let mut peers = ValidatorPeers::<TestContext>::default();
+    assert_eq!(
+        receive(&mut peers, &verifier, peer1, &val1, peer1),
+        Ok(false)
+    );
+    assert!(peers.peers().is_empty());
+
+    assert!(block_on(
+        peers.update_validator_set(&verifier, &validator_set(&[&val1]))
+    ));
+    assert_eq!(peers.peers(), BTreeSet::from([peer1]));
+}
+
+#[test]
+fn validator_set_changes() {
+    let [val1, val2, val3] = make_validators([1, 1, 1]);
+    let verifier = provider(&val1.1);
+
+    let (peer1, peer2, peer3) = (PeerId::random(), PeerId::random(), PeerId::random());
+
+    let mut peers = ValidatorPeers::<TestContext>::default();
+    block_on(peers.update_validator_set(&verifier, &validator_set(&[&val1, &val2])));
+
+    receive(&mut peers, &verifier, peer1, &val1, peer1).unwrap();
+    receive(&mut peers, &verifier, peer2, &val2, peer2).unwrap();
+    receive(&mut peers, &verifier, peer3, &val3, peer3).unwrap();
+    assert_eq!(peers.peers(), BTreeSet::from([peer1, peer2]));
+
+    // Same validator set, nothing changes
+    assert!(!block_on(peers.update_validator_set(
+        &verifier,
+        &validator_set(&[&val1, &val2])
+    )));
+
+    // Validator 2 leaves the set and validator 3 joins it
+    assert!(block_on(peers.update_validator_set(
+        &verifier,
+        &validator_set(&[&val1, &val3])
+    )));
+    assert_eq!(peers.peers(), BTreeSet::from([peer1, peer3]));
+}
+
+#[test]
+fn disconnected_peers_are_forgotten() {
+    let [val1] = make_validators([1]);
+    let verifier = provider(&val1.1);
+    let peer1 = PeerId::random();
+
+    let mut peers = ValidatorPeers::<TestContext>::default();
+    block_on(peers.update_validator_set(&verifier, &validator_set(&[&val1])));
+    receive(&mut peers, &verifier, peer1, &val1, peer1).unwrap();
+
+    assert!(peers.on_disconnection(&peer1));
+    assert!(!peers.on_disconnection(&peer1));
+    assert!(peers.peers().is_empty());
+
+    // The handshake is not verified again when the validator set changes
+    let [val2] = make_validators_seeded([1], 7);
+    assert!(!block_on(peers.update_validator_set(
+        &verifier,
+        &validator_set(&[&val1, &val2])
+    )));
+    assert!(peers.peers().is_empty());
+}
+
+#[test]
+fn invalid_handshakes_revoke_validator_peers() {
+    let [val1, val2] = make_validators([1, 1]);
+    let verifier = provider(&val1.1);
+    let peer1 = PeerId::random();
+
+    let mut peers = ValidatorPeers::<TestContext>::default();
+    block_on(peers.update_validator_set(&verifier, &validator_set(&[&val1, &val2])));
+
+    assert_eq!(
+        receive(&mut peers, &verifier, peer1, &val1, peer1),
+        Ok(true)
+    );
+
+    let proof = handshake(&val2.1, peer1);
+    assert_eq!(
+        block_on(peers.on_handshake(&verifier, peer1, val1.0.address.to_string(), proof)),
+        Err(InvalidSignature)
+    );
+    assert!(peers.peers().is_empty());
+}
+
+#[test]
+fn only_restricted_channels_require_a_validator_or_sentry() {
+    let validator = PeerId::random();
+    let sentry = PeerId::random();
+    let full_node = PeerId::random();
+
+    let mut mesh = ValidatorMesh::new([sentry]);
+    mesh.set_validators([validator].into_iter().collect());
+
+    for channel in [Channel::Consensus, Channel::ProposalParts] {
+        assert!(mesh.is_allowed(&validator, channel));
+        assert!(mesh.is_allowed(&sentry, channel));
+        assert!(!mesh.is_allowed(&full_node, channel));
+    }
+
+    for channel in [Channel::Liveness, Channel::Evidence, Channel::Sync] {
+        assert!(mesh.is_allowed(&full_node, channel));
+    }
+}
@@ -0,0 +1,127 @@
+use informalsystems_malachitebft_test::utils::validators::make_validators;
+use informalsystems_malachitebft_test::{TestContext, Validator, ValidatorSet};
+use malachitebft_core_types::{
+    ValidatorSet as _, ValidatorSetUpdate, ValidatorSetUpdateError, VotingPower,
+};
+
+type Update = ValidatorSetUpdate<TestContext>;
+
+fn validators<const N: usize>(voting_powers: [VotingPower; N]) -> [Validator; N] {
+    make_validators(voting_powers).map(|(v, _)| v)
+}
+
+#[test]
+fn empty_update_leaves_set_unchanged() {
+    let [v1, v2, v3] = validators([1, 2, 3]);
+    let vs = ValidatorSet::from_validators(vec![v1, v2, v3]);
+
+    let update = Update::new();
+    assert!(update.is_empty());
+    assert_eq!(update.voting_power_change(&vs), 0);
+    assert_eq!(update.apply(&vs), Ok(vs));
+}
+
+#[test]
+fn add_update_and_remove_validators() {
+    let [v1, v2, v3, v4, v5] = validators([10, 10, 10, 5, 2]);
+    let vs = ValidatorSet::from_validators(vec![v1.clone(), v2.clone(), v3.clone(), v4.clone()]);
+
+    let mut v2_updated = v2.clone();
+    v2_updated.voting_power = 13;
+
+    let update = Update::new()
+        .upsert(v5.clone())
+        .upsert(v2_updated.clone())
+        .remove(v4.address);
+
+    // 2 added + 3 increased + 5 removed
+    assert_eq!(update.voting_power_change(&vs), 10);
+
+    let new_vs = update.apply(&vs).unwrap();
+
+    assert_eq!(new_vs.count(), 4);
+    assert_eq!(new_vs.total_voting_power(), 35);
+    assert_eq!(new_vs.get_by_address(&v2.address), Some(&v2_updated));
+    assert_eq!(new_vs.get_by_address(&v5.address), Some(&v5));
+    assert_eq!(new_vs.get_by_address(&v4.address), None);
+
+    // Validators are sorted by descending voting power
+    assert_eq!(new_vs.get_by_index(0), Some(&v2_updated));
+    assert_eq!(new_vs.get_by_index(3), Some(&v5));
+}
+
+#[test]
+fn reject_change_of_more_than_one_third_of_voting_power() {
+    let [v1, v2, v3, v4, v5] = validators([1, 1, 1, 1, 1]);
+    let vs = ValidatorSet::from_validators(vec![v1.clone(), v2.clone(), v3.clone()]);
+
+    // Exactly 1/3 is accepted
+    assert!(Update::new().upsert(v4.clone()).apply(&vs).is_ok());
+    assert!(Update::new().remove(v1.address).apply(&vs).is_ok());
+
+    // More than 1/3 is rejected
+    assert_eq!(
+        Update::new().upsert(v4).upsert(v5).validate(&vs),
+        Err(ValidatorSetUpdateError::TooMuchVotingPowerChange {
+            change: 2,
+            total: 3
+        })
+    );
+
+    let mut v1_updated = v1.clone();
+    v1_updated.voting_power = 3;
+
+    assert_eq!(
+        Update::new().upsert(v1_updated).validate(&vs),
+        Err(ValidatorSetUpdateError::TooMuchVotingPowerChange {
+            change: 2,
+            total: 3
+        })
+    );
+}
+
+#[test]
+fn reject_malformed_updates() {
+    let [v1, v2, v3, v4] = validators([1, 1, 1, 1]);
+    let vs = ValidatorSet::from_validators(vec![v1.clone(), v2.clone(), v3.clone()]);
+
+    let mut v4_zero = v4.clone();
+    v4_zero.voting_power = 0;
+
+    assert_eq!(
+        Update::new().upsert(v4_zero).validate(&vs),
+        Err(ValidatorSetUpdateError::ZeroVotingPower(v4.address))
+    );
+
+    assert_eq!(
+        Update::new()
+            .upsert(v1.clone())
+            .remove(v1.address)
+            .validate(&vs),
+        Err(ValidatorSetUpdateError::DuplicateValidator(v1.address))
+    );
+
+    assert_eq!(
+        Update::new()
+            .remove(v2.address)
+            .remove(v2.address)
+            .validate(&vs),
+        Err(ValidatorSetUpdateError::DuplicateValidator(v2.address))
+    );
+
+    assert_eq!(
+        Update::new().remove(v4.address).validate(&vs),
+        Err(ValidatorSetUpdateError::UnknownValidator(v4.address))
+    );
+}
+
+#[test]
+fn reject_update_emptying_the_set() {
+    let [v1] = validators([1]);
+    let vs = ValidatorSet::from_validators(vec![v1.clone()]);
+
+    assert_eq!(
+        Update::new().remove(v1.address).validate(&vs),
+        Err(ValidatorSetUpdateError::EmptyValidatorSet)
+    );
+}
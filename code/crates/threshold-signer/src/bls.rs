@@ -0,0 +1,500 @@
+//! Threshold BLS signatures, combining the signatures of the shares of a secret key
+//! with Lagrange interpolation, as in the [threshold BLS scheme] of Boldyreva.
+//!
+//! The secret key of a validator is split by a trusted dealer into `n` key shares with Shamir's
+//! secret sharing, any `t` of which can sign a message together, while fewer than `t` of them
+//! learn nothing about the secret key.
+//!
+//! Unlike with [FROST](crate::frost), signing takes a single round: each participant signs the
+//! message with its key share, and any `t` of those [`SignatureShare`]s are combined with
+//! [`aggregate`] into the signature of the message by the secret key itself. As BLS signatures
+//! are deterministic, it is the very same signature as the one made with the secret key,
+//! so it verifies against the public key of the validator, and can be aggregated with the
+//! signatures of other validators.
+//!
+//! The proof of possession of the secret key, needed to admit the public key of the validator
+//! for verifying aggregate signatures, is made by the dealer and kept in the [`PublicKeyPackage`].
+//!
+//! [threshold BLS scheme]: https://www.iacr.org/archive/pkc2003/25670031/25670031.pdf
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use blst::{blst_fr, blst_scalar, MultiPoint};
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use malachitebft_signing_bls::{PrivateKey, PublicKey, Signature};
+
+use crate::frost::{check_threshold, Identifier};
+use crate::Error;
+
+/// The share of the secret key of a validator held by a participant.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(try_from = "RawKeyShare", into = "RawKeyShare")]
+pub struct KeyShare {
+    identifier: Identifier,
+    threshold: u16,
+    secret: PrivateKey,
+    group_public_key: PublicKey,
+}
+
+impl KeyShare {
+    /// Identifier of the participant holding this share.
+    pub fn identifier(&self) -> Identifier {
+        self.identifier
+    }
+
+    /// Number of participants needed to sign a message.
+    pub fn threshold(&self) -> u16 {
+        self.threshold
+    }
+
+    /// Public key of the validator, which the signatures verify against.
+    pub fn group_public_key(&self) -> PublicKey {
+        self.group_public_key
+    }
+
+    /// Public key of this share, used to verify the signature shares of its participant.
+    pub fn verifying_share(&self) -> PublicKey {
+        self.secret.public_key()
+    }
+}
+
+impl fmt::Debug for KeyShare {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyShare")
+            .field("identifier", &self.identifier)
+            .field("threshold", &self.threshold)
+            .field("group_public_key", &self.group_public_key)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Serialize, Deserialize, Zeroize, ZeroizeOnDrop)]
+struct RawKeyShare {
+    #[zeroize(skip)]
+    identifier: Identifier,
+    threshold: u16,
+    #[serde(with = "hex::serde")]
+    secret: [u8; 32],
+    #[serde(with = "hex::serde")]
+    group_public_key: [u8; 48],
+}
+
+impl TryFrom<RawKeyShare> for KeyShare {
+    type Error = Error;
+
+    fn try_from(raw: RawKeyShare) -> Result<Self, Self::Error> {
+        Ok(Self {
+            identifier: raw.identifier,
+            threshold: raw.threshold,
+            secret: PrivateKey::from_bytes(&raw.secret)
+                .map_err(|_| Error::InvalidEncoding("key share"))?,
+            group_public_key: PublicKey::from_bytes(&raw.group_public_key)
+                .map_err(|_| Error::InvalidEncoding("group public key"))?,
+        })
+    }
+}
+
+impl From<KeyShare> for RawKeyShare {
+    fn from(share: KeyShare) -> Self {
+        Self {
+            identifier: share.identifier,
+            threshold: share.threshold,
+            secret: share.secret.to_bytes(),
+            group_public_key: share.group_public_key.to_bytes(),
+        }
+    }
+}
+
+/// The public keys of a validator and of each of the shares of its secret key,
+/// used to verify and combine the signature shares of the participants.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "RawPublicKeyPackage", into = "RawPublicKeyPackage")]
+pub struct PublicKeyPackage {
+    threshold: u16,
+    group_public_key: PublicKey,
+    proof_of_possession: Signature,
+    verifying_shares: BTreeMap<Identifier, PublicKey>,
+}
+
+impl PublicKeyPackage {
+    /// Number of participants needed to sign a message.
+    pub fn threshold(&self) -> u16 {
+        self.threshold
+    }
+
+    /// Public key of the validator, which the signatures verify against.
+    pub fn group_public_key(&self) -> PublicKey {
+        self.group_public_key
+    }
+
+    /// Proof of possession of the secret key of the validator, made by the dealer.
+    pub fn proof_of_possession(&self) -> Signature {
+        self.proof_of_possession
+    }
+
+    /// Identifiers of all the participants.
+    pub fn identifiers(&self) -> impl Iterator<Item = Identifier> + '_ {
+        self.verifying_shares.keys().copied()
+    }
+
+    /// Public key of the share of the given participant, if any.
+    pub fn verifying_share(&self, identifier: Identifier) -> Option<&PublicKey> {
+        self.verifying_shares.get(&identifier)
+    }
+
+    /// Whether the given key share belongs to this package.
+    pub fn contains(&self, key_share: &KeyShare) -> bool {
+        key_share.group_public_key == self.group_public_key
+            && key_share.threshold == self.threshold
+            && self.verifying_shares.get(&key_share.identifier)
+                == Some(&key_share.verifying_share())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct RawPublicKeyPackage {
+    threshold: u16,
+    #[serde(with = "hex::serde")]
+    group_public_key: [u8; 48],
+    #[serde(with = "hex::serde")]
+    proof_of_possession: [u8; 96],
+    verifying_shares: BTreeMap<Identifier, HexPublicKey>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+struct HexPublicKey(#[serde(with = "hex::serde")] [u8; 48]);
+
+impl TryFrom<RawPublicKeyPackage> for PublicKeyPackage {
+    type Error = Error;
+
+    fn try_from(raw: RawPublicKeyPackage) -> Result<Self, Self::Error> {
+        check_threshold(raw.threshold, raw.verifying_shares.len())?;
+
+        let verifying_shares = raw
+            .verifying_shares
+            .into_iter()
+            .map(|(id, share)| {
+                let share = PublicKey::from_bytes(&share.0)
+                    .map_err(|_| Error::InvalidEncoding("verifying share"))?;
+
+                Ok((id, share))
+            })
+            .collect::<Result<_, Error>>()?;
+
+        let group_public_key = PublicKey::from_bytes(&raw.group_public_key)
+            .map_err(|_| Error::InvalidEncoding("group public key"))?;
+
+        let proof_of_possession = Signature::from_bytes(&raw.proof_of_possession)
+            .map_err(|_| Error::InvalidEncoding("proof of possession"))?;
+
+        group_public_key
+            .verify_proof_of_possession(&proof_of_possession)
+            .map_err(|_| Error::InvalidEncoding("proof of possession"))?;
+
+        Ok(Self {
+            threshold: raw.threshold,
+            group_public_key,
+            proof_of_possession,
+            verifying_shares,
+        })
+    }
+}
+
+impl From<PublicKeyPackage> for RawPublicKeyPackage {
+    fn from(package: PublicKeyPackage) -> Self {
+        Self {
+            threshold: package.threshold,
+            group_public_key: package.group_public_key.to_bytes(),
+            proof_of_possession: package.proof_of_possession.to_bytes(),
+            verifying_shares: package
+                .verifying_shares
+                .into_iter()
+                .map(|(id, share)| (id, HexPublicKey(share.to_bytes())))
+                .collect(),
+        }
+    }
+}
+
+/// Generate a fresh secret key, and split it into `total` shares, any `threshold` of which can sign.
+///
+/// The threshold must be a majority of the shares, so that two disjoint sets of participants
+/// can never sign conflicting messages.
+pub fn generate_with_dealer<R>(
+    threshold: u16,
+    total: u16,
+    mut rng: R,
+) -> Result<(Vec<KeyShare>, PublicKeyPackage), Error>
+where
+    R: RngCore + CryptoRng,
+{
+    let secret = random_private_key(&mut rng);
+    split_private_key(&secret, threshold, total, rng)
+}
+
+/// Split the given BLS private key into `total` shares, any `threshold` of which can sign,
+/// eg. to turn an existing validator into a threshold validator without changing its public key.
+///
+/// The threshold must be a majority of the shares, so that two disjoint sets of participants
+/// can never sign conflicting messages.
+///
+/// **NOTE:** The private key must be destroyed once the shares are distributed,
+/// otherwise it can still be used to sign on its own.
+pub fn split_private_key<R>(
+    private_key: &PrivateKey,
+    threshold: u16,
+    total: u16,
+    mut rng: R,
+) -> Result<(Vec<KeyShare>, PublicKeyPackage), Error>
+where
+    R: RngCore + CryptoRng,
+{
+    check_threshold(threshold, usize::from(total))?;
+
+    // The secret sharing polynomial, whose constant term is the secret key
+    let mut coefficients = Vec::with_capacity(usize::from(threshold));
+    coefficients.push(Scalar::from_private_key(private_key));
+    for _ in 1..threshold {
+        coefficients.push(Scalar::from_private_key(&random_private_key(&mut rng)));
+    }
+
+    let group_public_key = private_key.public_key();
+
+    let key_shares = (1..=total)
+        .map(|id| {
+            let identifier = Identifier::new(id).expect("identifiers start at 1");
+
+            // Evaluate the polynomial at the identifier, with Horner's method
+            let x = Scalar::from_u64(u64::from(id));
+            let mut y = Scalar::default();
+            for coefficient in coefficients.iter().rev() {
+                y = y.mul(&x).add(coefficient);
+            }
+
+            let secret = y.to_private_key();
+            y.zeroize();
+
+            Ok(KeyShare {
+                identifier,
+                threshold,
+                secret: secret?,
+                group_public_key,
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>();
+
+    coefficients.iter_mut().for_each(Scalar::zeroize);
+    let key_shares = key_shares?;
+
+    let public_key_package = PublicKeyPackage {
+        threshold,
+        group_public_key,
+        proof_of_possession: private_key.proof_of_possession(),
+        verifying_shares: key_shares
+            .iter()
+            .map(|share| (share.identifier, share.verifying_share()))
+            .collect(),
+    };
+
+    Ok((key_shares, public_key_package))
+}
+
+/// The share of a signature computed by a participant with its key share.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SignatureShare(Signature);
+
+impl SignatureShare {
+    /// Decode a signature share from a compressed G2 point.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Signature::from_bytes(bytes)
+            .map(Self)
+            .map_err(|_| Error::InvalidEncoding("signature share"))
+    }
+
+    /// Encode the signature share as a compressed G2 point.
+    pub fn to_bytes(&self) -> [u8; 96] {
+        self.0.to_bytes()
+    }
+}
+
+/// Compute the share of the signature of the given message with the given key share.
+pub fn sign(message: &[u8], key_share: &KeyShare) -> SignatureShare {
+    SignatureShare(key_share.secret.sign(message))
+}
+
+/// Combine the signature shares of at least `threshold` participants into a signature
+/// of the given message, which verifies against the public key of the validator.
+///
+/// Each signature share is first verified against the public key of its key share,
+/// so that participants sending invalid shares can be told apart. Only the shares of
+/// the first `threshold` participants, by identifier, are combined.
+pub fn aggregate(
+    message: &[u8],
+    signature_shares: &BTreeMap<Identifier, SignatureShare>,
+    public_key_package: &PublicKeyPackage,
+) -> Result<Signature, Error> {
+    let threshold = public_key_package.threshold;
+
+    if signature_shares.len() < usize::from(threshold) {
+        return Err(Error::NotEnoughCosigners {
+            available: signature_shares.len(),
+            threshold,
+        });
+    }
+
+    for (identifier, share) in signature_shares {
+        let verifying_share = public_key_package
+            .verifying_share(*identifier)
+            .ok_or(Error::UnknownCosigner(*identifier))?;
+
+        if verifying_share.verify(message, &share.0).is_err() {
+            return Err(Error::InvalidShare(*identifier));
+        }
+    }
+
+    let shares = signature_shares
+        .iter()
+        .take(usize::from(threshold))
+        .collect::<Vec<_>>();
+
+    let signature = combine(&shares)?;
+
+    // Combining valid shares of the same polynomial always yields the signature of the secret key
+    if public_key_package
+        .group_public_key
+        .verify(message, &signature)
+        .is_err()
+    {
+        return Err(Error::InvalidEncoding("public key package"));
+    }
+
+    Ok(signature)
+}
+
+/// Interpolate the signatures of the given key shares at zero, ie. compute the sum
+/// of the signature shares weighted by their Lagrange coefficients.
+fn combine(shares: &[(&Identifier, &SignatureShare)]) -> Result<Signature, Error> {
+    let xs = shares
+        .iter()
+        .map(|(id, _)| Scalar::from_u64(u64::from(id.get())))
+        .collect::<Vec<_>>();
+
+    let mut scalars = Vec::with_capacity(32 * shares.len());
+
+    for (i, x_i) in xs.iter().enumerate() {
+        let mut numerator = Scalar::from_u64(1);
+        let mut denominator = Scalar::from_u64(1);
+
+        for (j, x_j) in xs.iter().enumerate() {
+            if i != j {
+                numerator = numerator.mul(x_j);
+                denominator = denominator.mul(&x_j.sub(x_i));
+            }
+        }
+
+        let coefficient = numerator.mul(&denominator.inverse());
+        scalars.extend_from_slice(&coefficient.to_le_bytes());
+    }
+
+    let points = shares
+        .iter()
+        .map(|(_, share)| *share.0.inner())
+        .collect::<Vec<_>>();
+
+    let signature = points.as_slice().mult(&scalars, 255).to_signature();
+
+    Signature::from_bytes(&signature.compress())
+        .map_err(|_| Error::InvalidEncoding("combined signature"))
+}
+
+fn random_private_key<R: RngCore + CryptoRng>(rng: &mut R) -> PrivateKey {
+    let mut ikm = [0; 32];
+    rng.fill_bytes(&mut ikm);
+
+    let private_key = PrivateKey::from(ikm);
+    ikm.zeroize();
+
+    private_key
+}
+
+/// An element of the scalar field of BLS12-381, ie. integers modulo the order of the groups.
+#[derive(Copy, Clone, Default)]
+struct Scalar(blst_fr);
+
+// SAFETY: The `blst` functions below only read from their inputs and write to their output,
+// which are all valid references to initialized values of the expected types and sizes.
+impl Scalar {
+    fn from_u64(n: u64) -> Self {
+        let mut fr = blst_fr::default();
+        unsafe { blst::blst_fr_from_uint64(&mut fr, [n, 0, 0, 0].as_ptr()) };
+        Self(fr)
+    }
+
+    fn from_private_key(private_key: &PrivateKey) -> Self {
+        let mut bytes = private_key.to_bytes();
+        let mut scalar = blst_scalar::default();
+        let mut fr = blst_fr::default();
+
+        unsafe {
+            blst::blst_scalar_from_bendian(&mut scalar, bytes.as_ptr());
+            blst::blst_fr_from_scalar(&mut fr, &scalar);
+        }
+
+        bytes.zeroize();
+        Self(fr)
+    }
+
+    fn to_private_key(self) -> Result<PrivateKey, Error> {
+        let mut scalar = blst_scalar::default();
+        let mut bytes = [0; 32];
+
+        unsafe {
+            blst::blst_scalar_from_fr(&mut scalar, &self.0);
+            blst::blst_bendian_from_scalar(bytes.as_mut_ptr(), &scalar);
+        }
+
+        let private_key =
+            PrivateKey::from_bytes(&bytes).map_err(|_| Error::InvalidEncoding("key share"));
+        bytes.zeroize();
+
+        private_key
+    }
+
+    fn to_le_bytes(self) -> [u8; 32] {
+        let mut scalar = blst_scalar::default();
+        unsafe { blst::blst_scalar_from_fr(&mut scalar, &self.0) };
+        scalar.b
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        let mut fr = blst_fr::default();
+        unsafe { blst::blst_fr_add(&mut fr, &self.0, &other.0) };
+        Self(fr)
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        let mut fr = blst_fr::default();
+        unsafe { blst::blst_fr_sub(&mut fr, &self.0, &other.0) };
+        Self(fr)
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        let mut fr = blst_fr::default();
+        unsafe { blst::blst_fr_mul(&mut fr, &self.0, &other.0) };
+        Self(fr)
+    }
+
+    fn inverse(&self) -> Self {
+        let mut fr = blst_fr::default();
+        unsafe { blst::blst_fr_inverse(&mut fr, &self.0) };
+        Self(fr)
+    }
+
+    fn zeroize(&mut self) {
+        self.0.l.zeroize();
+    }
+}
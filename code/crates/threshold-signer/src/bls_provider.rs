@@ -0,0 +1,304 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::future::Future;
+use std::marker::PhantomData;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::stream::{FuturesUnordered, StreamExt};
+use tracing::warn;
+
+use malachitebft_core_types::{Context, Round, SignedMessage, ValueId};
+use malachitebft_signing::{Error as SigningError, SigningProvider, VerificationResult};
+use malachitebft_signing_bls::{Bls12381, PublicKey, Signature};
+
+use crate::bls::{self, PublicKeyPackage};
+use crate::{BlsCosigner, Error, Message, SignBytes};
+
+/// A [`SigningProvider`] which signs messages on behalf of a validator whose BLS private key
+/// is split among several cosigners, any `threshold` of which are needed to produce a signature.
+///
+/// Each message is signed in a single round: all the cosigners are asked to sign the message,
+/// and the signature shares of the first `threshold` of them to respond with a valid one
+/// are combined into the signature of the validator.
+///
+/// Signatures are verified, and aggregated, locally by the given verifier,
+/// whose signing methods are never called.
+pub struct BlsThresholdSigningProvider<Ctx, K, S, V> {
+    public_key_package: PublicKeyPackage,
+    cosigners: Vec<K>,
+    sign_bytes: S,
+    verifier: V,
+    timeout: Duration,
+    marker: PhantomData<fn() -> Ctx>,
+}
+
+impl<Ctx, K, S, V> BlsThresholdSigningProvider<Ctx, K, S, V>
+where
+    Ctx: Context<SigningScheme = Bls12381>,
+    K: BlsCosigner<Ctx>,
+    S: SignBytes<Ctx>,
+    V: SigningProvider<Ctx>,
+{
+    /// Default time to wait for a cosigner to respond to a request.
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(1);
+
+    /// Create a provider which signs messages with the given cosigners, which must hold distinct
+    /// key shares from the given package, computing sign bytes with `sign_bytes` and verifying
+    /// signatures with the given verifier.
+    pub fn new(
+        public_key_package: PublicKeyPackage,
+        cosigners: Vec<K>,
+        sign_bytes: S,
+        verifier: V,
+    ) -> Result<Self, Error> {
+        let mut seen = BTreeSet::new();
+
+        for identifier in cosigners.iter().map(|cosigner| cosigner.identifier()) {
+            if public_key_package.verifying_share(identifier).is_none() {
+                return Err(Error::UnknownCosigner(identifier));
+            }
+
+            if !seen.insert(identifier) {
+                return Err(Error::DuplicateCosigner(identifier));
+            }
+        }
+
+        if cosigners.len() < usize::from(public_key_package.threshold()) {
+            return Err(Error::NotEnoughCosigners {
+                available: cosigners.len(),
+                threshold: public_key_package.threshold(),
+            });
+        }
+
+        Ok(Self {
+            public_key_package,
+            cosigners,
+            sign_bytes,
+            verifier,
+            timeout: Self::DEFAULT_TIMEOUT,
+            marker: PhantomData,
+        })
+    }
+
+    /// Set the time to wait for a cosigner to respond to a request.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// The public key of the validator, which the signatures verify against.
+    pub fn public_key(&self) -> PublicKey {
+        self.public_key_package.group_public_key()
+    }
+
+    /// The public keys of the validator and of its key shares.
+    pub fn public_key_package(&self) -> &PublicKeyPackage {
+        &self.public_key_package
+    }
+
+    /// The cosigners of the validator.
+    pub fn cosigners(&self) -> &[K] {
+        &self.cosigners
+    }
+
+    async fn timed<T>(&self, request: impl Future<Output = Result<T, Error>>) -> Result<T, Error> {
+        tokio::time::timeout(self.timeout, request)
+            .await
+            .unwrap_or(Err(Error::Timeout))
+    }
+
+    /// Sign the given message with all the cosigners, and combine the shares of the first
+    /// `threshold` of them to respond, leaving out the ones which sent an invalid share.
+    async fn sign_message(&self, message: Message<'_, Ctx>) -> Result<Signature, Error> {
+        let threshold = self.public_key_package.threshold();
+        let needed = usize::from(threshold);
+
+        let sign_bytes = message.bls_sign_bytes(&self.sign_bytes);
+
+        let mut pending = self
+            .cosigners
+            .iter()
+            .map(|cosigner| async move {
+                let result = self.timed(cosigner.sign(message)).await;
+                (cosigner.identifier(), result)
+            })
+            .collect::<FuturesUnordered<_>>();
+
+        let mut shares = BTreeMap::new();
+
+        while let Some((identifier, result)) = pending.next().await {
+            match result {
+                Ok(share) => {
+                    shares.insert(identifier, share);
+                }
+                Err(e) => {
+                    warn!(cosigner = %identifier, "Cosigner failed to sign: {e}");
+                    continue;
+                }
+            }
+
+            if shares.len() < needed {
+                continue;
+            }
+
+            match bls::aggregate(&sign_bytes, &shares, &self.public_key_package) {
+                Ok(signature) => return Ok(signature),
+                Err(Error::InvalidShare(identifier)) => {
+                    warn!(cosigner = %identifier, "Cosigner sent an invalid signature share");
+                    shares.remove(&identifier);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(Error::NotEnoughCosigners {
+            available: shares.len(),
+            threshold,
+        })
+    }
+}
+
+#[async_trait]
+impl<Ctx, K, S, V> SigningProvider<Ctx> for BlsThresholdSigningProvider<Ctx, K, S, V>
+where
+    Ctx: Context<SigningScheme = Bls12381>,
+    K: BlsCosigner<Ctx>,
+    S: SignBytes<Ctx>,
+    V: SigningProvider<Ctx>,
+{
+    async fn sign_vote(
+        &self,
+        vote: Ctx::Vote,
+    ) -> Result<SignedMessage<Ctx, Ctx::Vote>, SigningError> {
+        let signature = self
+            .sign_message(Message::Vote(&vote))
+            .await
+            .map_err(SigningError::from_source)?;
+
+        Ok(SignedMessage::new(vote, signature))
+    }
+
+    async fn verify_signed_vote(
+        &self,
+        vote: &Ctx::Vote,
+        signature: &Signature,
+        public_key: &PublicKey,
+    ) -> Result<VerificationResult, SigningError> {
+        self.verifier
+            .verify_signed_vote(vote, signature, public_key)
+            .await
+    }
+
+    async fn sign_proposal(
+        &self,
+        proposal: Ctx::Proposal,
+    ) -> Result<SignedMessage<Ctx, Ctx::Proposal>, SigningError> {
+        let signature = self
+            .sign_message(Message::Proposal(&proposal))
+            .await
+            .map_err(SigningError::from_source)?;
+
+        Ok(SignedMessage::new(proposal, signature))
+    }
+
+    async fn verify_signed_proposal(
+        &self,
+        proposal: &Ctx::Proposal,
+        signature: &Signature,
+        public_key: &PublicKey,
+    ) -> Result<VerificationResult, SigningError> {
+        self.verifier
+            .verify_signed_proposal(proposal, signature, public_key)
+            .await
+    }
+
+    async fn sign_proposal_part(
+        &self,
+        proposal_part: Ctx::ProposalPart,
+    ) -> Result<SignedMessage<Ctx, Ctx::ProposalPart>, SigningError> {
+        let signature = self
+            .sign_message(Message::ProposalPart(&proposal_part))
+            .await
+            .map_err(SigningError::from_source)?;
+
+        Ok(SignedMessage::new(proposal_part, signature))
+    }
+
+    async fn verify_signed_proposal_part(
+        &self,
+        proposal_part: &Ctx::ProposalPart,
+        signature: &Signature,
+        public_key: &PublicKey,
+    ) -> Result<VerificationResult, SigningError> {
+        self.verifier
+            .verify_signed_proposal_part(proposal_part, signature, public_key)
+            .await
+    }
+
+    async fn sign_vote_extension(
+        &self,
+        extension: Ctx::Extension,
+    ) -> Result<SignedMessage<Ctx, Ctx::Extension>, SigningError> {
+        let signature = self
+            .sign_message(Message::Extension(&extension))
+            .await
+            .map_err(SigningError::from_source)?;
+
+        Ok(SignedMessage::new(extension, signature))
+    }
+
+    async fn verify_signed_vote_extension(
+        &self,
+        extension: &Ctx::Extension,
+        signature: &Signature,
+        public_key: &PublicKey,
+    ) -> Result<VerificationResult, SigningError> {
+        self.verifier
+            .verify_signed_vote_extension(extension, signature, public_key)
+            .await
+    }
+
+    async fn verify_signed_votes(
+        &self,
+        votes: &[(Ctx::Vote, &Signature, &PublicKey)],
+    ) -> Result<Vec<VerificationResult>, SigningError> {
+        self.verifier.verify_signed_votes(votes).await
+    }
+
+    async fn aggregate_signatures(
+        &self,
+        signatures: &[Signature],
+    ) -> Result<Signature, SigningError> {
+        self.verifier.aggregate_signatures(signatures).await
+    }
+
+    async fn verify_aggregated_commit(
+        &self,
+        height: &Ctx::Height,
+        round: Round,
+        value_id: &ValueId<Ctx>,
+        signature: &Signature,
+        public_keys: &[&PublicKey],
+    ) -> Result<VerificationResult, SigningError> {
+        self.verifier
+            .verify_aggregated_commit(height, round, value_id, signature, public_keys)
+            .await
+    }
+
+    async fn sign_peer_identity(&self, peer_id: &[u8]) -> Result<Signature, SigningError> {
+        self.sign_message(Message::PeerIdentity(peer_id))
+            .await
+            .map_err(SigningError::from_source)
+    }
+
+    async fn verify_peer_identity(
+        &self,
+        peer_id: &[u8],
+        signature: &Signature,
+        public_key: &PublicKey,
+    ) -> Result<VerificationResult, SigningError> {
+        self.verifier
+            .verify_peer_identity(peer_id, signature, public_key)
+            .await
+    }
+}
@@ -0,0 +1,190 @@
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use tokio::sync::Mutex;
+use tracing::debug;
+
+use malachitebft_codec::Codec;
+use malachitebft_core_types::Context;
+use malachitebft_remote_signer::{
+    connect, Address, IdentityKey, IdentityPublicKey, SecureStream, SignerCodec,
+};
+
+use crate::frost::{Identifier, SignatureShare, SigningCommitments};
+use crate::proto::{self, request, response, sign_request};
+use crate::{Cosigner, Error, Message, PROTOCOL_VERSION};
+
+/// A [`Cosigner`] running on another host, behind a [`CosignerServer`](crate::CosignerServer).
+///
+/// The connection to the remote cosigner is opened on the first request, and re-opened
+/// on the next request after any failure to talk to the remote cosigner.
+///
+/// The node authenticates with its own identity key, and only accepts a cosigner
+/// with the given identity public key, as with a remote signer.
+pub struct RemoteCosigner<Ctx, C> {
+    identifier: Identifier,
+    address: Address,
+    identity: IdentityKey,
+    cosigner_public_key: IdentityPublicKey,
+    codec: C,
+    connection: Mutex<Option<SecureStream>>,
+    marker: PhantomData<fn() -> Ctx>,
+}
+
+impl<Ctx, C> RemoteCosigner<Ctx, C>
+where
+    Ctx: Context,
+    C: SignerCodec<Ctx>,
+{
+    /// Create a cosigner which talks to the remote cosigner holding the key share
+    /// with the given identifier at the given address, identified by the given public key,
+    /// authenticating with the given identity key and encoding messages with the given codec.
+    pub fn new(
+        identifier: Identifier,
+        address: Address,
+        identity: IdentityKey,
+        cosigner_public_key: IdentityPublicKey,
+        codec: C,
+    ) -> Self {
+        Self {
+            identifier,
+            address,
+            identity,
+            cosigner_public_key,
+            codec,
+            connection: Mutex::new(None),
+            marker: PhantomData,
+        }
+    }
+
+    /// The address of the remote cosigner.
+    pub fn address(&self) -> &Address {
+        &self.address
+    }
+
+    /// Send the given request to the remote cosigner and return its response.
+    async fn request(&self, request: request::Request) -> Result<response::Response, Error> {
+        let mut connection = self.connection.lock().await;
+
+        // Take the connection while it is in use, so that it is dropped if this request fails
+        // or is cancelled, eg. after timing out, instead of being left with a response in flight.
+        let mut stream = match connection.take() {
+            Some(stream) => stream,
+            None => {
+                let stream = connect(&self.address)
+                    .await
+                    .map_err(malachitebft_remote_signer::Error::from)?;
+
+                SecureStream::initiate(stream, &self.identity, &[self.cosigner_public_key]).await?
+            }
+        };
+
+        let request = proto::Request {
+            version: PROTOCOL_VERSION,
+            request: Some(request),
+        };
+
+        let result = async {
+            stream.write_message(&request).await?;
+            stream.read_message::<proto::Response>().await
+        }
+        .await;
+
+        let response = match result {
+            Ok(response) => response,
+            Err(e) => {
+                debug!(address = %self.address, "Closing connection to remote cosigner: {e}");
+                return Err(e.into());
+            }
+        };
+
+        *connection = Some(stream);
+        drop(connection);
+
+        if response.version != PROTOCOL_VERSION {
+            return Err(Error::UnsupportedVersion(response.version));
+        }
+
+        match response.response {
+            Some(response::Response::Error(error)) => Err(Error::Remote {
+                kind: error.kind(),
+                message: error.message,
+            }),
+            Some(response) => Ok(response),
+            None => Err(Error::UnexpectedResponse),
+        }
+    }
+}
+
+fn encode<C: Codec<T>, T>(codec: &C, message: &T) -> Result<Bytes, Error> {
+    codec
+        .encode(message)
+        .map_err(|e| Error::Codec(e.to_string()))
+}
+
+#[async_trait]
+impl<Ctx, C> Cosigner<Ctx> for RemoteCosigner<Ctx, C>
+where
+    Ctx: Context,
+    C: SignerCodec<Ctx>,
+{
+    fn identifier(&self) -> Identifier {
+        self.identifier
+    }
+
+    async fn commit(&self) -> Result<SigningCommitments, Error> {
+        let request = request::Request::Commit(proto::CommitRequest {});
+
+        match self.request(request).await? {
+            response::Response::Commitment(commitment) => {
+                let (identifier, commitments) = commitment.decode()?;
+
+                if identifier != self.identifier {
+                    return Err(Error::UnexpectedResponse);
+                }
+
+                Ok(commitments)
+            }
+            _ => Err(Error::UnexpectedResponse),
+        }
+    }
+
+    async fn sign(
+        &self,
+        message: Message<'_, Ctx>,
+        commitments: &BTreeMap<Identifier, SigningCommitments>,
+    ) -> Result<SignatureShare, Error> {
+        let message = match message {
+            Message::Vote(vote) => sign_request::Message::Vote(encode(&self.codec, vote)?),
+            Message::Proposal(proposal) => {
+                sign_request::Message::Proposal(encode(&self.codec, proposal)?)
+            }
+            Message::ProposalPart(proposal_part) => {
+                sign_request::Message::ProposalPart(encode(&self.codec, proposal_part)?)
+            }
+            Message::Extension(extension) => {
+                sign_request::Message::Extension(encode(&self.codec, extension)?)
+            }
+            Message::PeerIdentity(peer_id) => {
+                sign_request::Message::PeerId(Bytes::copy_from_slice(peer_id))
+            }
+        };
+
+        let commitments = commitments
+            .iter()
+            .map(|(identifier, commitments)| proto::Commitment::new(*identifier, commitments))
+            .collect();
+
+        let request = request::Request::Sign(proto::SignRequest {
+            message: Some(message),
+            commitments,
+        });
+
+        match self.request(request).await? {
+            response::Response::SignatureShare(share) => SignatureShare::from_bytes(&share.share),
+            _ => Err(Error::UnexpectedResponse),
+        }
+    }
+}
@@ -0,0 +1,316 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::marker::PhantomData;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use rand::rngs::OsRng;
+use tokio::sync::Mutex;
+use tracing::{debug, warn};
+
+use malachitebft_codec::Codec;
+use malachitebft_core_types::{Context, ValueId};
+use malachitebft_signing_guard::{SignState, SignStateFile};
+
+use crate::bls;
+use crate::frost::{self, Identifier, KeyShare, SignatureShare, SigningCommitments, SigningNonces};
+use crate::{Error, Message, SignBytes};
+
+/// Maximum number of nonces a cosigner keeps around, waiting to be used to sign a message.
+///
+/// Nonces are generated for every message to sign, but only used when the cosigner is among
+/// the first ones to commit to them, so the oldest ones are dropped once this limit is reached.
+const MAX_PENDING_NONCES: usize = 64;
+
+/// A participant in the threshold signature of messages, holding one of the key shares of a validator.
+#[async_trait]
+pub trait Cosigner<Ctx>
+where
+    Ctx: Context,
+    Self: Send + Sync + 'static,
+{
+    /// Identifier of the key share held by this cosigner.
+    fn identifier(&self) -> Identifier;
+
+    /// Generate fresh nonces for signing a message, and return the commitments to them.
+    async fn commit(&self) -> Result<SigningCommitments, Error>;
+
+    /// Sign the given message with our key share, given the commitments of all the cosigners
+    /// taking part, including the ones to the nonces we generated for it.
+    async fn sign(
+        &self,
+        message: Message<'_, Ctx>,
+        commitments: &BTreeMap<Identifier, SigningCommitments>,
+    ) -> Result<SignatureShare, Error>;
+}
+
+#[async_trait]
+impl<Ctx> Cosigner<Ctx> for Box<dyn Cosigner<Ctx>>
+where
+    Ctx: Context,
+{
+    fn identifier(&self) -> Identifier {
+        self.as_ref().identifier()
+    }
+
+    async fn commit(&self) -> Result<SigningCommitments, Error> {
+        self.as_ref().commit().await
+    }
+
+    async fn sign(
+        &self,
+        message: Message<'_, Ctx>,
+        commitments: &BTreeMap<Identifier, SigningCommitments>,
+    ) -> Result<SignatureShare, Error> {
+        self.as_ref().sign(message, commitments).await
+    }
+}
+
+/// A [`Cosigner`] holding its key share in memory.
+///
+/// Before signing a vote or a proposal, its state is checked against the last signed one,
+/// and durably stored to the state file if it comes after it, as done by a
+/// [`DoubleSignGuard`](malachitebft_signing_guard::DoubleSignGuard).
+pub struct LocalCosigner<Ctx, S, C> {
+    key_share: KeyShare,
+    sign_bytes: S,
+    codec: C,
+    state: Mutex<SignStateFile>,
+    nonces: Mutex<VecDeque<SigningNonces>>,
+    marker: PhantomData<fn() -> Ctx>,
+}
+
+impl<Ctx, S, C> LocalCosigner<Ctx, S, C>
+where
+    Ctx: Context,
+    S: SignBytes<Ctx>,
+    C: Codec<ValueId<Ctx>>,
+{
+    /// Create a cosigner signing with the given key share, with its sign state stored in the file
+    /// at the given path, sign bytes computed by `sign_bytes` and value ids encoded with the given codec.
+    ///
+    /// The state file is created on the first signature if it does not exist yet.
+    pub fn open(
+        key_share: KeyShare,
+        state_file: impl Into<PathBuf>,
+        sign_bytes: S,
+        codec: C,
+    ) -> Result<Self, Error> {
+        let state = open_state(key_share.identifier(), state_file.into())?;
+
+        Ok(Self {
+            key_share,
+            sign_bytes,
+            codec,
+            state: Mutex::new(state),
+            nonces: Mutex::new(VecDeque::new()),
+            marker: PhantomData,
+        })
+    }
+
+    /// The key share held by this cosigner.
+    pub fn key_share(&self) -> &KeyShare {
+        &self.key_share
+    }
+
+    /// The state of the last signed vote or proposal, if any.
+    pub async fn state(&self) -> Option<SignState> {
+        self.state.lock().await.state().cloned()
+    }
+
+    /// Take the nonces committed to with the given commitments out of the pending ones,
+    /// so that they can never be used again.
+    async fn take_nonces(&self, commitments: &SigningCommitments) -> Option<SigningNonces> {
+        let mut nonces = self.nonces.lock().await;
+
+        let index = nonces
+            .iter()
+            .position(|nonces| nonces.commitments() == commitments)?;
+
+        nonces.remove(index)
+    }
+}
+
+#[async_trait]
+impl<Ctx, S, C> Cosigner<Ctx> for LocalCosigner<Ctx, S, C>
+where
+    Ctx: Context,
+    S: SignBytes<Ctx>,
+    C: Codec<ValueId<Ctx>>,
+{
+    fn identifier(&self) -> Identifier {
+        self.key_share.identifier()
+    }
+
+    async fn commit(&self) -> Result<SigningCommitments, Error> {
+        let nonces = SigningNonces::new(&self.key_share, OsRng);
+        let commitments = *nonces.commitments();
+
+        let mut pending = self.nonces.lock().await;
+        if pending.len() >= MAX_PENDING_NONCES {
+            pending.pop_front();
+        }
+        pending.push_back(nonces);
+
+        Ok(commitments)
+    }
+
+    async fn sign(
+        &self,
+        message: Message<'_, Ctx>,
+        commitments: &BTreeMap<Identifier, SigningCommitments>,
+    ) -> Result<SignatureShare, Error> {
+        let identifier = self.key_share.identifier();
+
+        let ours = commitments
+            .get(&identifier)
+            .ok_or(Error::MissingCommitment(identifier))?;
+
+        let nonces = self.take_nonces(ours).await.ok_or(Error::UnknownNonces)?;
+
+        check(identifier, &self.state, &self.codec, message).await?;
+
+        debug!(%identifier, ?message, "Signing message with key share");
+
+        let sign_bytes = message.sign_bytes(&self.sign_bytes);
+        frost::sign(&sign_bytes, commitments, nonces, &self.key_share)
+    }
+}
+
+/// A participant in the threshold BLS signature of messages, holding one of the key shares of a validator.
+///
+/// Unlike with a [`Cosigner`], messages are signed in a single round, without any nonces.
+#[async_trait]
+pub trait BlsCosigner<Ctx>
+where
+    Ctx: Context,
+    Self: Send + Sync + 'static,
+{
+    /// Identifier of the key share held by this cosigner.
+    fn identifier(&self) -> Identifier;
+
+    /// Sign the given message with our key share.
+    async fn sign(&self, message: Message<'_, Ctx>) -> Result<bls::SignatureShare, Error>;
+}
+
+#[async_trait]
+impl<Ctx> BlsCosigner<Ctx> for Box<dyn BlsCosigner<Ctx>>
+where
+    Ctx: Context,
+{
+    fn identifier(&self) -> Identifier {
+        self.as_ref().identifier()
+    }
+
+    async fn sign(&self, message: Message<'_, Ctx>) -> Result<bls::SignatureShare, Error> {
+        self.as_ref().sign(message).await
+    }
+}
+
+/// A [`BlsCosigner`] holding its key share in memory.
+///
+/// Votes and proposals are checked against the sign state file just like by a [`LocalCosigner`].
+pub struct LocalBlsCosigner<Ctx, S, C> {
+    key_share: bls::KeyShare,
+    sign_bytes: S,
+    codec: C,
+    state: Mutex<SignStateFile>,
+    marker: PhantomData<fn() -> Ctx>,
+}
+
+impl<Ctx, S, C> LocalBlsCosigner<Ctx, S, C>
+where
+    Ctx: Context,
+    S: SignBytes<Ctx>,
+    C: Codec<ValueId<Ctx>>,
+{
+    /// Create a cosigner signing with the given key share, with its sign state stored in the file
+    /// at the given path, sign bytes computed by `sign_bytes` and value ids encoded with the given codec.
+    ///
+    /// The state file is created on the first signature if it does not exist yet.
+    pub fn open(
+        key_share: bls::KeyShare,
+        state_file: impl Into<PathBuf>,
+        sign_bytes: S,
+        codec: C,
+    ) -> Result<Self, Error> {
+        let state = open_state(key_share.identifier(), state_file.into())?;
+
+        Ok(Self {
+            key_share,
+            sign_bytes,
+            codec,
+            state: Mutex::new(state),
+            marker: PhantomData,
+        })
+    }
+
+    /// The key share held by this cosigner.
+    pub fn key_share(&self) -> &bls::KeyShare {
+        &self.key_share
+    }
+
+    /// The state of the last signed vote or proposal, if any.
+    pub async fn state(&self) -> Option<SignState> {
+        self.state.lock().await.state().cloned()
+    }
+}
+
+#[async_trait]
+impl<Ctx, S, C> BlsCosigner<Ctx> for LocalBlsCosigner<Ctx, S, C>
+where
+    Ctx: Context,
+    S: SignBytes<Ctx>,
+    C: Codec<ValueId<Ctx>>,
+{
+    fn identifier(&self) -> Identifier {
+        self.key_share.identifier()
+    }
+
+    async fn sign(&self, message: Message<'_, Ctx>) -> Result<bls::SignatureShare, Error> {
+        let identifier = self.key_share.identifier();
+
+        check(identifier, &self.state, &self.codec, message).await?;
+
+        debug!(%identifier, ?message, "Signing message with key share");
+
+        let sign_bytes = message.bls_sign_bytes(&self.sign_bytes);
+        Ok(bls::sign(&sign_bytes, &self.key_share))
+    }
+}
+
+fn open_state(identifier: Identifier, state_file: PathBuf) -> Result<SignStateFile, Error> {
+    let state = SignStateFile::open(state_file)?;
+
+    match state.state() {
+        Some(state) => debug!(%identifier, "Loaded last sign state: {state}"),
+        None => warn!(%identifier, "No sign state file found, starting afresh"),
+    }
+
+    Ok(state)
+}
+
+/// Check that the given message can be signed after the last signed one,
+/// and store its state if it is a vote or a proposal.
+async fn check<Ctx, C>(
+    identifier: Identifier,
+    state: &Mutex<SignStateFile>,
+    codec: &C,
+    message: Message<'_, Ctx>,
+) -> Result<(), Error>
+where
+    Ctx: Context,
+    C: Codec<ValueId<Ctx>>,
+{
+    let next = match message {
+        Message::Vote(vote) => SignState::of_vote::<Ctx, _>(vote, codec)?,
+        Message::Proposal(proposal) => SignState::of_proposal::<Ctx, _>(proposal, codec)?,
+        Message::ProposalPart(_) | Message::Extension(_) | Message::PeerIdentity(_) => {
+            return Ok(())
+        }
+    };
+
+    state.lock().await.update(next).map_err(|e| {
+        warn!(%identifier, "{e}");
+        Error::from(e)
+    })
+}
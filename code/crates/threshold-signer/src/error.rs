@@ -0,0 +1,92 @@
+use thiserror::Error;
+
+use crate::frost::Identifier;
+use crate::proto::ErrorKind;
+use crate::PROTOCOL_VERSION;
+
+/// Represents an error that can occur when signing a message with threshold signatures.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// The threshold is not a majority of the key shares, or is less than two.
+    #[error("Invalid threshold {threshold} for {total} key shares, must be a majority of them and at least 2")]
+    InvalidThreshold {
+        /// Number of participants needed to sign
+        threshold: usize,
+        /// Total number of key shares
+        total: usize,
+    },
+
+    /// A key, nonce commitment or signature share is not validly encoded.
+    #[error("Invalid encoding of {0}")]
+    InvalidEncoding(&'static str),
+
+    /// A cosigner does not hold any of the key shares of the validator.
+    #[error("Unknown cosigner {0}")]
+    UnknownCosigner(Identifier),
+
+    /// Several cosigners hold the same key share.
+    #[error("Duplicate cosigner {0}")]
+    DuplicateCosigner(Identifier),
+
+    /// Not enough cosigners are available to sign a message.
+    #[error("Only {available} cosigners available, but {threshold} are needed to sign")]
+    NotEnoughCosigners {
+        /// Number of cosigners available
+        available: usize,
+        /// Number of cosigners needed to sign
+        threshold: u16,
+    },
+
+    /// The commitments to sign with do not include the ones of the given cosigner.
+    #[error("Missing or mismatching commitments of cosigner {0}")]
+    MissingCommitment(Identifier),
+
+    /// The cosigner did not generate, or already used, the nonces it is asked to sign with.
+    #[error("Unknown or already used nonces")]
+    UnknownNonces,
+
+    /// The signature share of the given cosigner is missing.
+    #[error("Missing signature share of cosigner {0}")]
+    MissingShare(Identifier),
+
+    /// The signature share of the given cosigner is not valid.
+    #[error("Invalid signature share from cosigner {0}")]
+    InvalidShare(Identifier),
+
+    /// The FROST protocol failed.
+    #[error("FROST error: {0}")]
+    Frost(#[from] frost_ed25519::Error),
+
+    /// The cosigner refused to sign a message which conflicts with one it already signed.
+    #[error(transparent)]
+    Guard(#[from] malachitebft_signing_guard::Error),
+
+    /// Failed to send or receive a message to or from a remote cosigner.
+    #[error(transparent)]
+    Transport(#[from] malachitebft_remote_signer::Error),
+
+    /// A message to sign could not be encoded or decoded with the codec.
+    #[error("Codec error: {0}")]
+    Codec(String),
+
+    /// No response was received from a cosigner in time.
+    #[error("Timed out waiting for a response from the cosigner")]
+    Timeout,
+
+    /// The other side speaks another version of the protocol.
+    #[error("Unsupported protocol version {0}, expected {PROTOCOL_VERSION}")]
+    UnsupportedVersion(u32),
+
+    /// The remote cosigner sent a response which does not match the request.
+    #[error("Unexpected response from the remote cosigner")]
+    UnexpectedResponse,
+
+    /// The remote cosigner failed to handle the request.
+    #[error("Remote cosigner error ({kind:?}): {message}")]
+    Remote {
+        /// The kind of error
+        kind: ErrorKind,
+        /// A description of the error
+        message: String,
+    },
+}
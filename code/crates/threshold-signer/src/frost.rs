@@ -0,0 +1,578 @@
+//! Threshold Ed25519 signatures, using the two-round FROST protocol as specified in [RFC 9591]
+//! for the `FROST(Ed25519, SHA-512)` ciphersuite, as implemented by the [`frost_ed25519`] crate.
+//!
+//! The secret key of a validator is split by a trusted dealer into `n` key shares, any `t` of which
+//! can sign a message together, while fewer than `t` of them learn nothing about the secret key.
+//! The resulting signatures are plain Ed25519 signatures, which verify against the public key
+//! of the validator.
+//!
+//! Signing a message takes two rounds:
+//! 1. Each participant generates fresh [`SigningNonces`], and sends the [`SigningCommitments`] to them.
+//! 2. Given the message and the commitments of all the participants, each of them computes
+//!    its [`SignatureShare`] with [`sign`], after which the shares are combined with [`aggregate`].
+//!
+//! On top of [`frost_ed25519`], this module identifies participants by small integers,
+//! requires the threshold to be a majority of the key shares, and defines the format
+//! of the key share and public key files.
+//!
+//! **NOTE:** Nonces must never be used to sign more than one message,
+//! otherwise the key share of the participant can be recovered from its signature shares.
+//!
+//! [RFC 9591]: https://www.rfc-editor.org/rfc/rfc9591.html
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::num::NonZeroU16;
+
+use curve25519_dalek::scalar::Scalar;
+use frost_ed25519 as frost;
+use frost_ed25519::keys::{IdentifierList, KeyPackage, SigningShare, VerifyingShare};
+use frost_ed25519::round1::NonceCommitment;
+use frost_ed25519::{SigningKey, SigningPackage, VerifyingKey};
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha512};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use malachitebft_signing_ed25519::{PrivateKey, PublicKey, Signature};
+
+use crate::Error;
+
+/// Identifier of a participant, ie. the point at which the secret sharing polynomial
+/// is evaluated to get its key share, which is never zero.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Identifier(NonZeroU16);
+
+impl Identifier {
+    /// Create an identifier, which must not be zero.
+    pub fn new(id: u16) -> Option<Self> {
+        NonZeroU16::new(id).map(Self)
+    }
+
+    /// The identifier as an integer.
+    pub fn get(&self) -> u16 {
+        self.0.get()
+    }
+
+    fn to_frost(self) -> frost::Identifier {
+        frost::Identifier::try_from(self.get()).expect("identifier is not zero")
+    }
+}
+
+impl TryFrom<u32> for Identifier {
+    type Error = Error;
+
+    fn try_from(id: u32) -> Result<Self, Self::Error> {
+        u16::try_from(id)
+            .ok()
+            .and_then(Self::new)
+            .ok_or(Error::InvalidEncoding("identifier"))
+    }
+}
+
+impl fmt::Display for Identifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// The share of the secret key of a validator held by a participant.
+#[derive(Clone, Serialize, Deserialize, Zeroize, ZeroizeOnDrop)]
+#[serde(try_from = "RawKeyShare", into = "RawKeyShare")]
+pub struct KeyShare {
+    #[zeroize(skip)]
+    identifier: Identifier,
+    key_package: KeyPackage,
+}
+
+impl KeyShare {
+    fn new(identifier: Identifier, key_package: KeyPackage) -> Self {
+        Self {
+            identifier,
+            key_package,
+        }
+    }
+
+    /// Identifier of the participant holding this share.
+    pub fn identifier(&self) -> Identifier {
+        self.identifier
+    }
+
+    /// Number of participants needed to sign a message.
+    pub fn threshold(&self) -> u16 {
+        *self.key_package.min_signers()
+    }
+
+    /// Public key of the validator, which the signatures verify against.
+    pub fn group_public_key(&self) -> PublicKey {
+        to_public_key(self.key_package.verifying_key())
+    }
+
+    /// Public key of this share, used to verify the signature shares of its participant.
+    pub fn verifying_share(&self) -> [u8; 32] {
+        encode_point(self.key_package.verifying_share().serialize())
+    }
+}
+
+impl fmt::Debug for KeyShare {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyShare")
+            .field("identifier", &self.identifier)
+            .field("threshold", &self.threshold())
+            .field("group_public_key", &self.group_public_key())
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct RawKeyShare {
+    identifier: Identifier,
+    threshold: u16,
+    #[serde(with = "hex::serde")]
+    signing_share: [u8; 32],
+    #[serde(with = "hex::serde")]
+    group_public_key: [u8; 32],
+}
+
+impl TryFrom<RawKeyShare> for KeyShare {
+    type Error = Error;
+
+    fn try_from(mut raw: RawKeyShare) -> Result<Self, Self::Error> {
+        let signing_share = SigningShare::deserialize(&raw.signing_share)
+            .map_err(|_| Error::InvalidEncoding("signing share"))?;
+        raw.signing_share.zeroize();
+
+        let verifying_key = VerifyingKey::deserialize(&raw.group_public_key)
+            .map_err(|_| Error::InvalidEncoding("group public key"))?;
+
+        let key_package = KeyPackage::new(
+            raw.identifier.to_frost(),
+            signing_share,
+            VerifyingShare::from(signing_share),
+            verifying_key,
+            raw.threshold,
+        );
+
+        Ok(Self::new(raw.identifier, key_package))
+    }
+}
+
+impl From<KeyShare> for RawKeyShare {
+    fn from(share: KeyShare) -> Self {
+        let mut signing_share = [0; 32];
+        signing_share.copy_from_slice(&share.key_package.signing_share().serialize());
+
+        Self {
+            identifier: share.identifier,
+            threshold: share.threshold(),
+            signing_share,
+            group_public_key: share.group_public_key().as_bytes().to_owned(),
+        }
+    }
+}
+
+/// The public keys of a validator and of each of the shares of its secret key,
+/// used to aggregate the signature shares of the participants.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "RawPublicKeyPackage", into = "RawPublicKeyPackage")]
+pub struct PublicKeyPackage {
+    threshold: u16,
+    identifiers: BTreeSet<Identifier>,
+    package: frost::keys::PublicKeyPackage,
+}
+
+impl PublicKeyPackage {
+    /// Number of participants needed to sign a message.
+    pub fn threshold(&self) -> u16 {
+        self.threshold
+    }
+
+    /// Public key of the validator, which the signatures verify against.
+    pub fn group_public_key(&self) -> PublicKey {
+        to_public_key(self.package.verifying_key())
+    }
+
+    /// Identifiers of all the participants.
+    pub fn identifiers(&self) -> impl Iterator<Item = Identifier> + '_ {
+        self.identifiers.iter().copied()
+    }
+
+    /// Whether the given key share belongs to this package.
+    pub fn contains(&self, key_share: &KeyShare) -> bool {
+        key_share.key_package.verifying_key() == self.package.verifying_key()
+            && key_share.threshold() == self.threshold
+            && self.identifiers.contains(&key_share.identifier)
+            && self
+                .package
+                .verifying_shares()
+                .get(&key_share.identifier.to_frost())
+                == Some(key_share.key_package.verifying_share())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct RawPublicKeyPackage {
+    threshold: u16,
+    #[serde(with = "hex::serde")]
+    group_public_key: [u8; 32],
+    verifying_shares: BTreeMap<Identifier, HexPoint>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+struct HexPoint(#[serde(with = "hex::serde")] [u8; 32]);
+
+impl TryFrom<RawPublicKeyPackage> for PublicKeyPackage {
+    type Error = Error;
+
+    fn try_from(raw: RawPublicKeyPackage) -> Result<Self, Self::Error> {
+        check_threshold(raw.threshold, raw.verifying_shares.len())?;
+
+        let verifying_shares = raw
+            .verifying_shares
+            .iter()
+            .map(|(id, share)| {
+                let share = VerifyingShare::deserialize(&share.0)
+                    .map_err(|_| Error::InvalidEncoding("verifying share"))?;
+
+                Ok((id.to_frost(), share))
+            })
+            .collect::<Result<_, Error>>()?;
+
+        let verifying_key = VerifyingKey::deserialize(&raw.group_public_key)
+            .map_err(|_| Error::InvalidEncoding("group public key"))?;
+
+        Ok(Self {
+            threshold: raw.threshold,
+            identifiers: raw.verifying_shares.into_keys().collect(),
+            package: frost::keys::PublicKeyPackage::new(verifying_shares, verifying_key),
+        })
+    }
+}
+
+impl From<PublicKeyPackage> for RawPublicKeyPackage {
+    fn from(package: PublicKeyPackage) -> Self {
+        let verifying_shares = package
+            .identifiers
+            .iter()
+            .map(|id| {
+                let share = &package.package.verifying_shares()[&id.to_frost()];
+                (*id, HexPoint(encode_point(share.serialize())))
+            })
+            .collect();
+
+        Self {
+            threshold: package.threshold,
+            group_public_key: package.group_public_key().as_bytes().to_owned(),
+            verifying_shares,
+        }
+    }
+}
+
+/// Generate a fresh secret key, and split it into `total` shares, any `threshold` of which can sign.
+///
+/// The threshold must be a majority of the shares, so that two disjoint sets of participants
+/// can never sign conflicting messages.
+pub fn generate_with_dealer<R>(
+    threshold: u16,
+    total: u16,
+    mut rng: R,
+) -> Result<(Vec<KeyShare>, PublicKeyPackage), Error>
+where
+    R: RngCore + CryptoRng,
+{
+    let secret = SigningKey::new(&mut rng);
+    split(&secret, threshold, total, rng)
+}
+
+/// Split the given Ed25519 private key into `total` shares, any `threshold` of which can sign,
+/// eg. to turn an existing validator into a threshold validator without changing its public key.
+///
+/// The threshold must be a majority of the shares, so that two disjoint sets of participants
+/// can never sign conflicting messages.
+///
+/// **NOTE:** The private key must be destroyed once the shares are distributed,
+/// otherwise it can still be used to sign on its own.
+pub fn split_private_key<R>(
+    private_key: &PrivateKey,
+    threshold: u16,
+    total: u16,
+    rng: R,
+) -> Result<(Vec<KeyShare>, PublicKeyPackage), Error>
+where
+    R: RngCore + CryptoRng,
+{
+    // Derive the secret scalar from the seed, as specified in RFC 8032
+    let mut hash: [u8; 64] = Sha512::digest(private_key.inner().as_bytes()).into();
+    let mut scalar_bytes = [0; 32];
+    scalar_bytes.copy_from_slice(&hash[..32]);
+    scalar_bytes[0] &= 248;
+    scalar_bytes[31] &= 127;
+    scalar_bytes[31] |= 64;
+
+    let secret = SigningKey::from_scalar(Scalar::from_bytes_mod_order(scalar_bytes))?;
+
+    hash.zeroize();
+    scalar_bytes.zeroize();
+
+    split(&secret, threshold, total, rng)
+}
+
+fn split<R>(
+    secret: &SigningKey,
+    threshold: u16,
+    total: u16,
+    mut rng: R,
+) -> Result<(Vec<KeyShare>, PublicKeyPackage), Error>
+where
+    R: RngCore + CryptoRng,
+{
+    check_threshold(threshold, usize::from(total))?;
+
+    let identifiers = (1..=total)
+        .map(|id| Identifier::new(id).expect("identifiers start at 1"))
+        .collect::<BTreeSet<_>>();
+
+    let frost_identifiers = identifiers
+        .iter()
+        .map(|id| id.to_frost())
+        .collect::<Vec<_>>();
+
+    let (mut secret_shares, package) = frost::keys::split(
+        secret,
+        total,
+        threshold,
+        IdentifierList::Custom(&frost_identifiers),
+        &mut rng,
+    )?;
+
+    let key_shares = identifiers
+        .iter()
+        .map(|id| {
+            let secret_share = secret_shares
+                .remove(&id.to_frost())
+                .expect("a secret share is generated for each identifier");
+
+            Ok(KeyShare::new(*id, KeyPackage::try_from(secret_share)?))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let public_key_package = PublicKeyPackage {
+        threshold,
+        identifiers,
+        package,
+    };
+
+    Ok((key_shares, public_key_package))
+}
+
+/// Check that the threshold is a majority of the key shares, and that
+/// at least two participants are needed to sign, as required by FROST.
+pub(crate) fn check_threshold(threshold: u16, total: usize) -> Result<(), Error> {
+    let threshold = usize::from(threshold);
+
+    if threshold < 2 || threshold > total || threshold * 2 <= total {
+        return Err(Error::InvalidThreshold { threshold, total });
+    }
+
+    Ok(())
+}
+
+/// The nonces generated by a participant in the first round of signing a message.
+///
+/// Must only be used to sign a single message.
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct SigningNonces {
+    nonces: frost::round1::SigningNonces,
+    #[zeroize(skip)]
+    commitments: SigningCommitments,
+}
+
+impl SigningNonces {
+    /// Generate fresh nonces for the given key share.
+    pub fn new<R>(key_share: &KeyShare, mut rng: R) -> Self
+    where
+        R: RngCore + CryptoRng,
+    {
+        let nonces =
+            frost::round1::SigningNonces::new(key_share.key_package.signing_share(), &mut rng);
+        let commitments = SigningCommitments(*nonces.commitments());
+
+        Self {
+            nonces,
+            commitments,
+        }
+    }
+
+    /// The commitments to these nonces, to be sent to the other participants.
+    pub fn commitments(&self) -> &SigningCommitments {
+        &self.commitments
+    }
+}
+
+impl fmt::Debug for SigningNonces {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SigningNonces")
+            .field("commitments", &self.commitments)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The commitments of a participant to its nonces for signing a message.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SigningCommitments(frost::round1::SigningCommitments);
+
+impl SigningCommitments {
+    /// Decode commitments from the given compressed points.
+    pub fn from_bytes(hiding: &[u8], binding: &[u8]) -> Result<Self, Error> {
+        let hiding = NonceCommitment::deserialize(hiding)
+            .map_err(|_| Error::InvalidEncoding("hiding commitment"))?;
+        let binding = NonceCommitment::deserialize(binding)
+            .map_err(|_| Error::InvalidEncoding("binding commitment"))?;
+
+        Ok(Self(frost::round1::SigningCommitments::new(
+            hiding, binding,
+        )))
+    }
+
+    /// The commitment to the hiding nonce, as a compressed point.
+    pub fn hiding(&self) -> [u8; 32] {
+        encode_point(self.0.hiding().serialize())
+    }
+
+    /// The commitment to the binding nonce, as a compressed point.
+    pub fn binding(&self) -> [u8; 32] {
+        encode_point(self.0.binding().serialize())
+    }
+}
+
+/// The share of a signature computed by a participant in the second round of signing a message.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct SignatureShare(frost::round2::SignatureShare);
+
+impl SignatureShare {
+    /// Decode a signature share from its canonical encoding.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        frost::round2::SignatureShare::deserialize(bytes)
+            .map(Self)
+            .map_err(|_| Error::InvalidEncoding("signature share"))
+    }
+
+    /// Encode the signature share as a little-endian scalar.
+    pub fn to_bytes(&self) -> [u8; 32] {
+        let mut bytes = [0; 32];
+        bytes.copy_from_slice(&self.0.serialize());
+        bytes
+    }
+}
+
+impl fmt::Debug for SignatureShare {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SignatureShare({})", hex::encode(self.to_bytes()))
+    }
+}
+
+/// Compute the share of the signature of the given message with the given key share and nonces,
+/// given the commitments of all the participants taking part, including ours.
+///
+/// Consumes the nonces, which must not be used again.
+pub fn sign(
+    message: &[u8],
+    commitments: &BTreeMap<Identifier, SigningCommitments>,
+    nonces: SigningNonces,
+    key_share: &KeyShare,
+) -> Result<SignatureShare, Error> {
+    let identifier = key_share.identifier;
+
+    if commitments.get(&identifier) != Some(&nonces.commitments) {
+        return Err(Error::MissingCommitment(identifier));
+    }
+
+    if commitments.len() < usize::from(key_share.threshold()) {
+        return Err(Error::NotEnoughCosigners {
+            available: commitments.len(),
+            threshold: key_share.threshold(),
+        });
+    }
+
+    let signing_package = signing_package(message, commitments);
+    let share = frost::round2::sign(&signing_package, &nonces.nonces, &key_share.key_package)?;
+
+    Ok(SignatureShare(share))
+}
+
+/// Combine the signature shares of all the participants taking part into a signature
+/// of the given message, which verifies against the public key of the validator.
+///
+/// If the signature does not verify, each signature share is verified against the public key
+/// of its key share, so that participants sending invalid shares can be told apart.
+pub fn aggregate(
+    message: &[u8],
+    commitments: &BTreeMap<Identifier, SigningCommitments>,
+    signature_shares: &BTreeMap<Identifier, SignatureShare>,
+    public_key_package: &PublicKeyPackage,
+) -> Result<Signature, Error> {
+    if commitments.len() < usize::from(public_key_package.threshold) {
+        return Err(Error::NotEnoughCosigners {
+            available: commitments.len(),
+            threshold: public_key_package.threshold,
+        });
+    }
+
+    let mut shares = BTreeMap::new();
+
+    for identifier in commitments.keys() {
+        let share = signature_shares
+            .get(identifier)
+            .ok_or(Error::MissingShare(*identifier))?;
+
+        if !public_key_package.identifiers.contains(identifier) {
+            return Err(Error::UnknownCosigner(*identifier));
+        }
+
+        shares.insert(identifier.to_frost(), share.0);
+    }
+
+    let signing_package = signing_package(message, commitments);
+
+    let signature = frost::aggregate(&signing_package, &shares, &public_key_package.package)
+        .map_err(|e| match e {
+            frost::Error::InvalidSignatureShare { culprit } => commitments
+                .keys()
+                .find(|id| id.to_frost() == culprit)
+                .map_or(Error::Frost(e), |id| Error::InvalidShare(*id)),
+            e => Error::Frost(e),
+        })?;
+
+    let mut bytes = [0; 64];
+    bytes.copy_from_slice(&signature.serialize()?);
+
+    Ok(Signature::from_bytes(bytes))
+}
+
+fn signing_package(
+    message: &[u8],
+    commitments: &BTreeMap<Identifier, SigningCommitments>,
+) -> SigningPackage {
+    let commitments = commitments
+        .iter()
+        .map(|(id, commitments)| (id.to_frost(), commitments.0))
+        .collect();
+
+    SigningPackage::new(commitments, message)
+}
+
+/// Encode a point serialized by [`frost_ed25519`], which fails only for the identity,
+/// never held by keys, verifying shares or commitments.
+fn encode_point(bytes: Result<Vec<u8>, frost::Error>) -> [u8; 32] {
+    let bytes = bytes.expect("point is not the identity");
+
+    let mut point = [0; 32];
+    point.copy_from_slice(&bytes);
+    point
+}
+
+fn to_public_key(verifying_key: &VerifyingKey) -> PublicKey {
+    PublicKey::from_bytes(encode_point(verifying_key.serialize()))
+}
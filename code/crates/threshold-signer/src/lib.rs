@@ -0,0 +1,105 @@
+//! Threshold signing for the Malachite BFT consensus engine.
+//!
+//! Copying the private key of a validator to several hosts, to keep the validator running
+//! when one of them fails, is the main source of equivocation in practice: as soon as two of
+//! those hosts are up at the same time, they sign conflicting votes.
+//!
+//! Instead, the private key of a validator can be split into `n` key shares, each held by a
+//! [`Cosigner`], so that any `t` of them are needed to produce a signature, as done by
+//! [Horcrux] for CometBFT. A [`ThresholdSigningProvider`] asks the cosigners to sign each
+//! message with their key share, using the two-round [FROST](frost) protocol as implemented by
+//! the [`frost_ed25519`] crate, and combines their signature shares into a plain Ed25519
+//! signature, which verifies against the public key of the validator.
+//!
+//! Each [`LocalCosigner`] keeps track of the last height, round and step at which it signed
+//! a vote or a proposal, and refuses to sign another value for the same height, round and step,
+//! just like a [`DoubleSignGuard`](malachitebft_signing_guard::DoubleSignGuard).
+//! Since the threshold must be a majority of the key shares, two conflicting votes or proposals
+//! can never both gather enough signature shares, even if several nodes of the validator
+//! are running at the same time.
+//!
+//! Cosigners can run on other hosts behind a [`CosignerServer`], which nodes talk to with a
+//! [`RemoteCosigner`], using the authenticated and encrypted transport of the
+//! [remote signer](malachitebft_remote_signer).
+//!
+//! Validators using the BLS12-381 signing scheme are signed for by a
+//! [`BlsThresholdSigningProvider`] instead, which combines the [threshold BLS](bls) signature
+//! shares of its [`BlsCosigner`]s in a single round. The combined signature is the very one the
+//! private key of the validator would make, so it can be aggregated into commit certificates.
+//! BLS cosigners are only available in-process, as [`LocalBlsCosigner`]s.
+//!
+//! [Horcrux]: https://github.com/strangelove-ventures/horcrux
+
+use bytes::Bytes;
+use derive_where::derive_where;
+
+use malachitebft_core_types::Context;
+use malachitebft_signing::peer_identity_sign_bytes;
+use malachitebft_signing_bls::vote_sign_bytes;
+
+pub use malachitebft_signing::SignBytes;
+
+pub mod bls;
+pub mod frost;
+pub mod proto;
+
+mod client;
+pub use client::RemoteCosigner;
+
+mod cosigner;
+pub use cosigner::{BlsCosigner, Cosigner, LocalBlsCosigner, LocalCosigner};
+
+mod error;
+pub use error::Error;
+
+mod provider;
+pub use provider::ThresholdSigningProvider;
+
+mod bls_provider;
+pub use bls_provider::BlsThresholdSigningProvider;
+
+mod server;
+pub use server::CosignerServer;
+
+/// Version of the protocol between nodes and remote cosigners implemented by this crate.
+///
+/// Both the node and the cosigner reject messages carrying any other version.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// A message to be signed by the cosigners of a validator.
+#[derive_where(Copy, Clone, Debug)]
+pub enum Message<'a, Ctx: Context> {
+    /// A vote
+    Vote(&'a Ctx::Vote),
+    /// A proposal
+    Proposal(&'a Ctx::Proposal),
+    /// A part of a proposal
+    ProposalPart(&'a Ctx::ProposalPart),
+    /// A vote extension
+    Extension(&'a Ctx::Extension),
+    /// The ID of the network peer of the validator, for the validator-only gossip mesh
+    PeerIdentity(&'a [u8]),
+}
+
+impl<Ctx: Context> Message<'_, Ctx> {
+    /// The bytes to sign for this message.
+    pub fn sign_bytes(&self, sign_bytes: &impl SignBytes<Ctx>) -> Bytes {
+        match self {
+            Self::Vote(vote) => sign_bytes.vote(vote),
+            Self::Proposal(proposal) => sign_bytes.proposal(proposal),
+            Self::ProposalPart(proposal_part) => sign_bytes.proposal_part(proposal_part),
+            Self::Extension(extension) => sign_bytes.extension(extension),
+            Self::PeerIdentity(peer_id) => Bytes::from(peer_identity_sign_bytes(peer_id)),
+        }
+    }
+
+    /// The bytes to sign for this message with a BLS key share, which are the commit message
+    /// for precommits for a value, as expected by a
+    /// [`BlsSigningProvider`](malachitebft_signing_bls::BlsSigningProvider).
+    pub fn bls_sign_bytes(&self, sign_bytes: &impl SignBytes<Ctx>) -> Bytes {
+        match self {
+            Self::Vote(vote) => vote_sign_bytes::<Ctx, _>(sign_bytes, vote),
+            _ => self.sign_bytes(sign_bytes),
+        }
+    }
+}
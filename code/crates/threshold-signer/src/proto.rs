@@ -0,0 +1,35 @@
+//! Messages of the protocol between the node of a validator and its remote cosigners.
+//!
+//! Messages are framed and encrypted as in the remote signer protocol,
+//! see [`malachitebft_remote_signer::proto`].
+//! The node sends a [`Request`] and waits for the [`Response`] of the cosigner before sending
+//! its next request on the same connection.
+
+#![allow(missing_docs)]
+
+include!(concat!(
+    env!("OUT_DIR"),
+    "/malachitebft.threshold_signer.rs"
+));
+
+use bytes::Bytes;
+
+use crate::frost::{Identifier, SigningCommitments};
+use crate::Error;
+
+impl Commitment {
+    pub(crate) fn new(identifier: Identifier, commitments: &SigningCommitments) -> Self {
+        Self {
+            identifier: u32::from(identifier.get()),
+            hiding: Bytes::copy_from_slice(&commitments.hiding()),
+            binding: Bytes::copy_from_slice(&commitments.binding()),
+        }
+    }
+
+    pub(crate) fn decode(&self) -> Result<(Identifier, SigningCommitments), Error> {
+        let identifier = Identifier::try_from(self.identifier)?;
+        let commitments = SigningCommitments::from_bytes(&self.hiding, &self.binding)?;
+
+        Ok((identifier, commitments))
+    }
+}
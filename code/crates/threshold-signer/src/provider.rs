@@ -0,0 +1,351 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::future::Future;
+use std::marker::PhantomData;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::stream::{FuturesUnordered, StreamExt};
+use tracing::{debug, warn};
+
+use malachitebft_core_types::{Context, Round, SignedMessage, ValueId};
+use malachitebft_signing::{Error as SigningError, SigningProvider, VerificationResult};
+use malachitebft_signing_ed25519::{Ed25519, PublicKey, Signature};
+
+use crate::frost::{self, PublicKeyPackage};
+use crate::{Cosigner, Error, Message, SignBytes};
+
+/// A [`SigningProvider`] which signs messages on behalf of a validator whose private key is split
+/// among several cosigners, any `threshold` of which are needed to produce a signature.
+///
+/// Each message is signed in two rounds: first, the cosigners are asked to commit to fresh nonces,
+/// and the first `threshold` of them to respond are then asked to sign the message. If any of them
+/// fails or refuses to sign, or sends an invalid signature share, the message is signed again
+/// without it, for as long as enough cosigners remain.
+///
+/// Signatures are verified, and aggregated, locally by the given verifier,
+/// whose signing methods are never called.
+pub struct ThresholdSigningProvider<Ctx, K, S, V> {
+    public_key_package: PublicKeyPackage,
+    cosigners: Vec<K>,
+    sign_bytes: S,
+    verifier: V,
+    timeout: Duration,
+    marker: PhantomData<fn() -> Ctx>,
+}
+
+impl<Ctx, K, S, V> ThresholdSigningProvider<Ctx, K, S, V>
+where
+    Ctx: Context<SigningScheme = Ed25519>,
+    K: Cosigner<Ctx>,
+    S: SignBytes<Ctx>,
+    V: SigningProvider<Ctx>,
+{
+    /// Default time to wait for a cosigner to respond to a request.
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(1);
+
+    /// Create a provider which signs messages with the given cosigners, which must hold distinct
+    /// key shares from the given package, computing sign bytes with `sign_bytes` and verifying
+    /// signatures with the given verifier.
+    pub fn new(
+        public_key_package: PublicKeyPackage,
+        cosigners: Vec<K>,
+        sign_bytes: S,
+        verifier: V,
+    ) -> Result<Self, Error> {
+        let known = public_key_package.identifiers().collect::<BTreeSet<_>>();
+        let mut seen = BTreeSet::new();
+
+        for identifier in cosigners.iter().map(|cosigner| cosigner.identifier()) {
+            if !known.contains(&identifier) {
+                return Err(Error::UnknownCosigner(identifier));
+            }
+
+            if !seen.insert(identifier) {
+                return Err(Error::DuplicateCosigner(identifier));
+            }
+        }
+
+        if cosigners.len() < usize::from(public_key_package.threshold()) {
+            return Err(Error::NotEnoughCosigners {
+                available: cosigners.len(),
+                threshold: public_key_package.threshold(),
+            });
+        }
+
+        Ok(Self {
+            public_key_package,
+            cosigners,
+            sign_bytes,
+            verifier,
+            timeout: Self::DEFAULT_TIMEOUT,
+            marker: PhantomData,
+        })
+    }
+
+    /// Set the time to wait for a cosigner to respond to a request.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// The public key of the validator, which the signatures verify against.
+    pub fn public_key(&self) -> PublicKey {
+        self.public_key_package.group_public_key()
+    }
+
+    /// The public keys of the validator and of its key shares.
+    pub fn public_key_package(&self) -> &PublicKeyPackage {
+        &self.public_key_package
+    }
+
+    /// The cosigners of the validator.
+    pub fn cosigners(&self) -> &[K] {
+        &self.cosigners
+    }
+
+    async fn timed<T>(&self, request: impl Future<Output = Result<T, Error>>) -> Result<T, Error> {
+        tokio::time::timeout(self.timeout, request)
+            .await
+            .unwrap_or(Err(Error::Timeout))
+    }
+
+    /// Sign the given message with the first `threshold` cosigners to respond,
+    /// retrying without the ones which failed to sign for as long as enough of them remain.
+    async fn sign_message(&self, message: Message<'_, Ctx>) -> Result<Signature, Error> {
+        let threshold = self.public_key_package.threshold();
+        let needed = usize::from(threshold);
+
+        let sign_bytes = message.sign_bytes(&self.sign_bytes);
+        let mut available = self.cosigners.iter().collect::<Vec<_>>();
+
+        loop {
+            if available.len() < needed {
+                return Err(Error::NotEnoughCosigners {
+                    available: available.len(),
+                    threshold,
+                });
+            }
+
+            let mut failed = BTreeSet::new();
+
+            // Round one: wait for the first `threshold` cosigners to commit to fresh nonces
+            let mut pending = available
+                .iter()
+                .map(|cosigner| async move { (*cosigner, self.timed(cosigner.commit()).await) })
+                .collect::<FuturesUnordered<_>>();
+
+            let mut signers = Vec::with_capacity(needed);
+            let mut commitments = BTreeMap::new();
+
+            while let Some((cosigner, result)) = pending.next().await {
+                match result {
+                    Ok(commitment) => {
+                        commitments.insert(cosigner.identifier(), commitment);
+                        signers.push(cosigner);
+
+                        if signers.len() == needed {
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        warn!(cosigner = %cosigner.identifier(), "Cosigner failed to commit to nonces: {e}");
+                        failed.insert(cosigner.identifier());
+                    }
+                }
+            }
+
+            drop(pending);
+
+            if signers.len() < needed {
+                return Err(Error::NotEnoughCosigners {
+                    available: signers.len(),
+                    threshold,
+                });
+            }
+
+            // Round two: ask each of them to sign the message with its key share
+            let results = futures::future::join_all(signers.iter().map(|cosigner| async {
+                let result = self.timed(cosigner.sign(message, &commitments)).await;
+                (cosigner.identifier(), result)
+            }))
+            .await;
+
+            let mut shares = BTreeMap::new();
+
+            for (identifier, result) in results {
+                match result {
+                    Ok(share) => {
+                        shares.insert(identifier, share);
+                    }
+                    Err(e) => {
+                        warn!(cosigner = %identifier, "Cosigner failed to sign: {e}");
+                        failed.insert(identifier);
+                    }
+                }
+            }
+
+            if shares.len() == needed {
+                match frost::aggregate(&sign_bytes, &commitments, &shares, &self.public_key_package)
+                {
+                    Ok(signature) => return Ok(signature),
+                    Err(Error::InvalidShare(identifier)) => {
+                        warn!(cosigner = %identifier, "Cosigner sent an invalid signature share");
+                        failed.insert(identifier);
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+
+            debug!(
+                ?failed,
+                "Signing again without the cosigners which failed to sign"
+            );
+            available.retain(|cosigner| !failed.contains(&cosigner.identifier()));
+        }
+    }
+}
+
+#[async_trait]
+impl<Ctx, K, S, V> SigningProvider<Ctx> for ThresholdSigningProvider<Ctx, K, S, V>
+where
+    Ctx: Context<SigningScheme = Ed25519>,
+    K: Cosigner<Ctx>,
+    S: SignBytes<Ctx>,
+    V: SigningProvider<Ctx>,
+{
+    async fn sign_vote(
+        &self,
+        vote: Ctx::Vote,
+    ) -> Result<SignedMessage<Ctx, Ctx::Vote>, SigningError> {
+        let signature = self
+            .sign_message(Message::Vote(&vote))
+            .await
+            .map_err(SigningError::from_source)?;
+
+        Ok(SignedMessage::new(vote, signature))
+    }
+
+    async fn verify_signed_vote(
+        &self,
+        vote: &Ctx::Vote,
+        signature: &Signature,
+        public_key: &PublicKey,
+    ) -> Result<VerificationResult, SigningError> {
+        self.verifier
+            .verify_signed_vote(vote, signature, public_key)
+            .await
+    }
+
+    async fn sign_proposal(
+        &self,
+        proposal: Ctx::Proposal,
+    ) -> Result<SignedMessage<Ctx, Ctx::Proposal>, SigningError> {
+        let signature = self
+            .sign_message(Message::Proposal(&proposal))
+            .await
+            .map_err(SigningError::from_source)?;
+
+        Ok(SignedMessage::new(proposal, signature))
+    }
+
+    async fn verify_signed_proposal(
+        &self,
+        proposal: &Ctx::Proposal,
+        signature: &Signature,
+        public_key: &PublicKey,
+    ) -> Result<VerificationResult, SigningError> {
+        self.verifier
+            .verify_signed_proposal(proposal, signature, public_key)
+            .await
+    }
+
+    async fn sign_proposal_part(
+        &self,
+        proposal_part: Ctx::ProposalPart,
+    ) -> Result<SignedMessage<Ctx, Ctx::ProposalPart>, SigningError> {
+        let signature = self
+            .sign_message(Message::ProposalPart(&proposal_part))
+            .await
+            .map_err(SigningError::from_source)?;
+
+        Ok(SignedMessage::new(proposal_part, signature))
+    }
+
+    async fn verify_signed_proposal_part(
+        &self,
+        proposal_part: &Ctx::ProposalPart,
+        signature: &Signature,
+        public_key: &PublicKey,
+    ) -> Result<VerificationResult, SigningError> {
+        self.verifier
+            .verify_signed_proposal_part(proposal_part, signature, public_key)
+            .await
+    }
+
+    async fn sign_vote_extension(
+        &self,
+        extension: Ctx::Extension,
+    ) -> Result<SignedMessage<Ctx, Ctx::Extension>, SigningError> {
+        let signature = self
+            .sign_message(Message::Extension(&extension))
+            .await
+            .map_err(SigningError::from_source)?;
+
+        Ok(SignedMessage::new(extension, signature))
+    }
+
+    async fn verify_signed_vote_extension(
+        &self,
+        extension: &Ctx::Extension,
+        signature: &Signature,
+        public_key: &PublicKey,
+    ) -> Result<VerificationResult, SigningError> {
+        self.verifier
+            .verify_signed_vote_extension(extension, signature, public_key)
+            .await
+    }
+
+    async fn verify_signed_votes(
+        &self,
+        votes: &[(Ctx::Vote, &Signature, &PublicKey)],
+    ) -> Result<Vec<VerificationResult>, SigningError> {
+        self.verifier.verify_signed_votes(votes).await
+    }
+
+    async fn aggregate_signatures(
+        &self,
+        signatures: &[Signature],
+    ) -> Result<Signature, SigningError> {
+        self.verifier.aggregate_signatures(signatures).await
+    }
+
+    async fn verify_aggregated_commit(
+        &self,
+        height: &Ctx::Height,
+        round: Round,
+        value_id: &ValueId<Ctx>,
+        signature: &Signature,
+        public_keys: &[&PublicKey],
+    ) -> Result<VerificationResult, SigningError> {
+        self.verifier
+            .verify_aggregated_commit(height, round, value_id, signature, public_keys)
+            .await
+    }
+
+    async fn sign_peer_identity(&self, peer_id: &[u8]) -> Result<Signature, SigningError> {
+        self.sign_message(Message::PeerIdentity(peer_id))
+            .await
+            .map_err(SigningError::from_source)
+    }
+
+    async fn verify_peer_identity(
+        &self,
+        peer_id: &[u8],
+        signature: &Signature,
+        public_key: &PublicKey,
+    ) -> Result<VerificationResult, SigningError> {
+        self.verifier
+            .verify_peer_identity(peer_id, signature, public_key)
+            .await
+    }
+}
@@ -0,0 +1,226 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tracing::{debug, warn};
+
+use malachitebft_codec::Codec;
+use malachitebft_core_types::Context;
+use malachitebft_remote_signer::{
+    IdentityKey, IdentityPublicKey, Listener, SecureStream, SignerCodec, Stream,
+};
+
+use crate::frost::{Identifier, SigningCommitments};
+use crate::proto::{self, request, response, sign_request, ErrorKind};
+use crate::{Cosigner, Error, Message, PROTOCOL_VERSION};
+
+/// A remote cosigner, which handles the requests it receives from the nodes of a validator
+/// with the given [`Cosigner`], typically a [`LocalCosigner`](crate::LocalCosigner).
+///
+/// The cosigner authenticates with its identity key, and only accepts connections
+/// from the nodes with one of the given identity public keys.
+pub struct CosignerServer<Ctx, C, K> {
+    identity: IdentityKey,
+    authorized_nodes: Vec<IdentityPublicKey>,
+    codec: C,
+    cosigner: K,
+    marker: PhantomData<fn() -> Ctx>,
+}
+
+impl<Ctx, C, K> CosignerServer<Ctx, C, K>
+where
+    Ctx: Context,
+    C: SignerCodec<Ctx>,
+    K: Cosigner<Ctx>,
+{
+    /// Create a remote cosigner which authenticates with the given identity key, only serves
+    /// the nodes with the given identity public keys, decodes the messages to sign with
+    /// the given codec, and handles requests with the given cosigner.
+    pub fn new(
+        identity: IdentityKey,
+        authorized_nodes: Vec<IdentityPublicKey>,
+        codec: C,
+        cosigner: K,
+    ) -> Self {
+        Self {
+            identity,
+            authorized_nodes,
+            codec,
+            cosigner,
+            marker: PhantomData,
+        }
+    }
+
+    /// The cosigner handling requests.
+    pub fn cosigner(&self) -> &K {
+        &self.cosigner
+    }
+
+    /// Accept connections from nodes on the given listener, serving each of them in its own task.
+    ///
+    /// Only returns if accepting a connection fails.
+    pub async fn serve(self, listener: Listener) -> io::Result<()> {
+        let server = Arc::new(self);
+
+        loop {
+            let stream = listener.accept().await?;
+            let server = Arc::clone(&server);
+
+            tokio::spawn(async move {
+                if let Err(e) = server.serve_connection(stream).await {
+                    warn!("Connection to node closed with an error: {e}");
+                }
+            });
+        }
+    }
+
+    /// Authenticate the node on the other side of the given connection, then handle
+    /// the requests received on it, until it is closed by the node.
+    pub async fn serve_connection(&self, stream: Box<dyn Stream>) -> Result<(), Error> {
+        let mut stream =
+            SecureStream::respond(stream, &self.identity, &self.authorized_nodes).await?;
+
+        debug!(node = %stream.remote_public_key(), "Accepted connection from node");
+
+        loop {
+            let request = match stream.read_message().await {
+                Ok(request) => request,
+                Err(malachitebft_remote_signer::Error::Io(e))
+                    if e.kind() == io::ErrorKind::UnexpectedEof =>
+                {
+                    return Ok(())
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            let response = self.handle(request).await;
+            stream.write_message(&response).await?;
+        }
+    }
+
+    /// Handle the given request, and return the response to send back to the node.
+    pub async fn handle(&self, request: proto::Request) -> proto::Response {
+        if request.version != PROTOCOL_VERSION {
+            return error_response(
+                ErrorKind::UnsupportedVersion,
+                format!(
+                    "unsupported protocol version {}, expected {PROTOCOL_VERSION}",
+                    request.version
+                ),
+            );
+        }
+
+        let result = match request.request {
+            Some(request::Request::Commit(_)) => self.commit().await,
+            Some(request::Request::Sign(request)) => self.sign(request).await,
+            None => Err(error(ErrorKind::InvalidRequest, "empty request")),
+        };
+
+        let response = result.unwrap_or_else(|error| {
+            warn!(kind = ?error.kind(), "Failed to handle request: {}", error.message);
+            response::Response::Error(error)
+        });
+
+        proto::Response {
+            version: PROTOCOL_VERSION,
+            response: Some(response),
+        }
+    }
+
+    async fn commit(&self) -> Result<response::Response, proto::ErrorResponse> {
+        let commitments = self.cosigner.commit().await.map_err(signing_failed)?;
+        let identifier = self.cosigner.identifier();
+
+        Ok(response::Response::Commitment(proto::Commitment::new(
+            identifier,
+            &commitments,
+        )))
+    }
+
+    async fn sign(
+        &self,
+        request: proto::SignRequest,
+    ) -> Result<response::Response, proto::ErrorResponse> {
+        let commitments = request
+            .commitments
+            .iter()
+            .map(proto::Commitment::decode)
+            .collect::<Result<BTreeMap<Identifier, SigningCommitments>, _>>()
+            .map_err(|e| error(ErrorKind::InvalidRequest, e.to_string()))?;
+
+        let share = match request.message {
+            Some(sign_request::Message::Vote(bytes)) => {
+                let vote: Ctx::Vote = decode(&self.codec, bytes)?;
+                self.sign_message(Message::Vote(&vote), &commitments)
+                    .await?
+            }
+            Some(sign_request::Message::Proposal(bytes)) => {
+                let proposal: Ctx::Proposal = decode(&self.codec, bytes)?;
+                self.sign_message(Message::Proposal(&proposal), &commitments)
+                    .await?
+            }
+            Some(sign_request::Message::ProposalPart(bytes)) => {
+                let proposal_part: Ctx::ProposalPart = decode(&self.codec, bytes)?;
+                self.sign_message(Message::ProposalPart(&proposal_part), &commitments)
+                    .await?
+            }
+            Some(sign_request::Message::Extension(bytes)) => {
+                let extension: Ctx::Extension = decode(&self.codec, bytes)?;
+                self.sign_message(Message::Extension(&extension), &commitments)
+                    .await?
+            }
+            Some(sign_request::Message::PeerId(peer_id)) => {
+                self.sign_message(Message::PeerIdentity(&peer_id), &commitments)
+                    .await?
+            }
+            None => return Err(error(ErrorKind::InvalidRequest, "missing message")),
+        };
+
+        Ok(response::Response::SignatureShare(proto::SignatureShare {
+            share: Bytes::copy_from_slice(&share),
+        }))
+    }
+
+    async fn sign_message(
+        &self,
+        message: Message<'_, Ctx>,
+        commitments: &BTreeMap<Identifier, SigningCommitments>,
+    ) -> Result<[u8; 32], proto::ErrorResponse> {
+        debug!(?message, "Signing message");
+
+        self.cosigner
+            .sign(message, commitments)
+            .await
+            .map(|share| share.to_bytes())
+            .map_err(signing_failed)
+    }
+}
+
+fn decode<C: Codec<T>, T>(codec: &C, bytes: Bytes) -> Result<T, proto::ErrorResponse> {
+    codec.decode(bytes).map_err(|e| {
+        error(
+            ErrorKind::InvalidRequest,
+            format!("failed to decode message: {e}"),
+        )
+    })
+}
+
+fn signing_failed(e: Error) -> proto::ErrorResponse {
+    error(ErrorKind::SigningFailed, e.to_string())
+}
+
+fn error(kind: ErrorKind, message: impl Into<String>) -> proto::ErrorResponse {
+    proto::ErrorResponse {
+        kind: kind.into(),
+        message: message.into(),
+    }
+}
+
+fn error_response(kind: ErrorKind, message: impl Into<String>) -> proto::Response {
+    proto::Response {
+        version: PROTOCOL_VERSION,
+        response: Some(response::Response::Error(error(kind, message))),
+    }
+}
@@ -3,7 +3,7 @@ use std::time::Duration;
 use eyre::eyre;
 use tokio::sync::mpsc;
 use tokio::time::sleep;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 use malachitebft_app_channel::app::engine::host::Next;
 use malachitebft_app_channel::app::streaming::StreamContent;
@@ -102,7 +102,7 @@ pub async fn run(state: &mut State, channels: &mut Channels<TestContext>) -> eyr
                         .remove_pending_proposal_parts(parts.clone())
                         .await?;
 
-                    match state.validate_proposal_parts(parts) {
+                    match state.validate_proposal_parts(parts).await {
                         Ok(()) => {
                             // Validation passed - convert to ProposedValue and move to undecided
                             let value = State::assemble_value_from_parts(parts.clone())?;
@@ -177,7 +177,7 @@ pub async fn run(state: &mut State, channels: &mut Channels<TestContext>) -> eyr
 
                 // Now what's left to do is to break down the value to propose into parts,
                 // and send those parts over the network to our peers, for them to re-assemble the full value.
-                for stream_message in state.stream_proposal(proposal, pol_round) {
+                for stream_message in state.stream_proposal(proposal, pol_round).await? {
                     info!(%height, %round, "Streaming proposal part: {stream_message:?}");
 
                     channels
@@ -240,6 +240,7 @@ pub async fn run(state: &mut State, channels: &mut Channels<TestContext>) -> eyr
             AppMsg::Decided {
                 certificate,
                 extensions,
+                evidence,
                 reply,
             } => {
                 info!(
@@ -249,6 +250,17 @@ pub async fn run(state: &mut State, channels: &mut Channels<TestContext>) -> eyr
                     "Consensus has decided on value, committing..."
                 );
 
+                // Consensus also hands us any evidence of misbehavior (ie. equivocating
+                // proposals or votes) it observed during this height, which the application
+                // may use to penalize the offending validators.
+                if !evidence.is_empty() {
+                    warn!(
+                        height = %certificate.height,
+                        ?evidence,
+                        "Received evidence of misbehavior"
+                    );
+                }
+
                 // When that happens, we store the decided value in our store
                 match state.commit(certificate, extensions).await {
                     Ok(_) => {
@@ -382,7 +394,9 @@ pub async fn run(state: &mut State, channels: &mut Channels<TestContext>) -> eyr
                         value: proposal.value,
                     };
 
-                    for stream_message in state.stream_proposal(locally_proposed_value, valid_round)
+                    for stream_message in state
+                        .stream_proposal(locally_proposed_value, valid_round)
+                        .await?
                     {
                         info!(%height, %valid_round, "Publishing proposal part: {stream_message:?}");
 
@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 
 pub use malachitebft_app_channel::app::config::{
     ConsensusConfig, LogFormat, LogLevel, LoggingConfig, MetricsConfig, RuntimeConfig,
-    TimeoutConfig, ValueSyncConfig,
+    SignerConfig, TimeoutConfig, ValueSyncConfig,
 };
 
 use malachitebft_app_channel::app::node::NodeConfig;
@@ -31,6 +31,10 @@ pub struct Config {
 
     /// Runtime configuration options
     pub runtime: RuntimeConfig,
+
+    /// Signer configuration options
+    #[serde(default)]
+    pub signer: SignerConfig,
 }
 
 impl NodeConfig for Config {
@@ -10,6 +10,7 @@ use malachitebft_test::Height;
 use malachitebft_test_cli::args::{Args, Commands};
 use malachitebft_test_cli::cmd::dump_wal::DumpWalCmd;
 use malachitebft_test_cli::cmd::init::InitCmd;
+use malachitebft_test_cli::cmd::key::KeyCmd;
 use malachitebft_test_cli::cmd::start::StartCmd;
 use malachitebft_test_cli::cmd::testnet::TestnetCmd;
 use malachitebft_test_cli::config::{LogFormat, LogLevel};
@@ -45,6 +46,7 @@ fn main() -> Result<()> {
         Commands::Init(cmd) => init(&args, cmd),
         Commands::Testnet(cmd) => testnet(&args, cmd),
         Commands::DumpWal(cmd) => dump_wal(&args, cmd),
+        Commands::Key(cmd) => key(&args, cmd),
         Commands::DistributedTestnet(_) => unimplemented!(),
     }
 }
@@ -123,3 +125,21 @@ fn dump_wal(_args: &Args, cmd: &DumpWalCmd) -> Result<()> {
     cmd.run(ProtobufCodec)
         .map_err(|error| eyre!("Failed to run dump-wal command {:?}", error))
 }
+
+fn key(args: &Args, cmd: &KeyCmd) -> Result<()> {
+    // This is a drop guard responsible for flushing any remaining logs when the program terminates.
+    // It must be assigned to a binding that is not _, as _ will result in the guard being dropped immediately.
+    let _guard = logging::init(LogLevel::Info, LogFormat::Plaintext);
+
+    // Setup the application
+    let app = App {
+        home_dir: args.get_home_dir()?,
+        config_file: args.get_config_file_path()?,
+        genesis_file: args.get_genesis_file_path()?,
+        private_key_file: args.get_priv_validator_key_file_path()?,
+        start_height: None,
+    };
+
+    cmd.run(&app, &args.get_priv_validator_key_file_path()?)
+        .map_err(|error| eyre!("Failed to run key command {error:?}"))
+}
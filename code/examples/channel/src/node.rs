@@ -1,6 +1,7 @@
 //! The Application (or Node) definition. The Node trait implements the Consensus context and the
 //! cryptographic library used for signing.
 
+use std::num::NonZeroU64;
 use std::path::PathBuf;
 
 use async_trait::async_trait;
@@ -9,20 +10,22 @@ use tokio::task::JoinHandle;
 use tracing::Instrument;
 
 use malachitebft_app_channel::app::events::{RxEvent, TxEvent};
+use malachitebft_app_channel::app::keystore;
 use malachitebft_app_channel::app::metrics::SharedRegistry;
 use malachitebft_app_channel::app::node::{
     CanGeneratePrivateKey, CanMakeConfig, CanMakeGenesis, CanMakePrivateKeyFile, EngineHandle,
     MakeConfigSettings, Node, NodeHandle,
 };
 use malachitebft_app_channel::app::types::core::{Height as _, VotingPower};
+use malachitebft_app_channel::app::types::signing::SigningProvider;
 use malachitebft_app_channel::app::types::Keypair;
 
 // Use the same types used for integration tests.
 // A real application would use its own types and context instead.
 use malachitebft_test::codec::proto::ProtobufCodec;
 use malachitebft_test::{
-    Address, Ed25519Provider, Genesis, Height, PrivateKey, PublicKey, TestContext, Validator,
-    ValidatorSet,
+    load_network_keypair, load_public_key, make_signing_provider, Address, Genesis, Height,
+    PrivateKey, PublicKey, TestContext, Validator, ValidatorSet,
 };
 use malachitebft_test_cli::metrics;
 
@@ -67,7 +70,7 @@ impl Node for App {
     type Config = Config;
     type Genesis = Genesis;
     type PrivateKeyFile = PrivateKey;
-    type SigningProvider = Ed25519Provider;
+    type SigningProvider = Box<dyn SigningProvider<TestContext>>;
     type NodeHandle = Handle;
 
     fn get_home_dir(&self) -> PathBuf {
@@ -95,12 +98,22 @@ impl Node for App {
     }
 
     fn load_private_key_file(&self) -> eyre::Result<Self::PrivateKeyFile> {
-        let private_key = std::fs::read_to_string(&self.private_key_file)?;
-        serde_json::from_str(&private_key).map_err(Into::into)
+        keystore::load_key_file(&self.private_key_file).map_err(Into::into)
     }
 
-    fn get_signing_provider(&self, private_key: PrivateKey) -> Self::SigningProvider {
-        Ed25519Provider::new(private_key)
+    fn load_public_key(&self) -> eyre::Result<PublicKey> {
+        let config = self.load_config()?;
+        load_public_key(&config.signer, || self.load_private_key_file())
+    }
+
+    fn load_keypair(&self) -> eyre::Result<Keypair> {
+        let config = self.load_config()?;
+        load_network_keypair(&config.signer, || self.load_private_key_file())
+    }
+
+    fn get_signing_provider(&self) -> eyre::Result<Self::SigningProvider> {
+        let config = self.load_config()?;
+        make_signing_provider(&config.signer, || self.load_private_key_file())
     }
 
     fn load_genesis(&self) -> eyre::Result<Self::Genesis> {
@@ -114,11 +127,11 @@ impl Node for App {
         let span = tracing::error_span!("node", moniker = %config.moniker);
         let _enter = span.enter();
 
-        let private_key_file = self.load_private_key_file()?;
-        let private_key = self.load_private_key(private_key_file);
-        let public_key = self.get_public_key(&private_key);
+        let public_key = self.load_public_key()?;
         let address = self.get_address(&public_key);
-        let signing_provider = self.get_signing_provider(private_key);
+        // The application signs the proposal parts it streams with the signing provider of the node,
+        // which may ask a remote signer to sign them
+        let signing_provider = self.get_signing_provider()?;
         let ctx = TestContext::new();
 
         let genesis = self.load_genesis()?;
@@ -225,6 +238,11 @@ fn make_config(index: usize, total: usize, settings: MakeConfigSettings) -> Conf
         moniker: format!("app-{index}"),
         consensus: ConsensusConfig {
             enabled: true,
+            accountability: false,
+            max_evidence_age: 100,
+            validator_set_update_delay: NonZeroU64::MIN,
+            aggregate_commit_certificates: false,
+            threshold_params: Default::default(),
             // Current channel app does not support parts-only value payload properly as Init does not include valid_round
             value_payload: ValuePayload::ProposalAndParts,
             queue_capacity: 100, // Deprecated, derived from `sync.parallel_requests`
@@ -271,6 +289,7 @@ fn make_config(index: usize, total: usize, settings: MakeConfigSettings) -> Conf
             listen_addr: format!("127.0.0.1:{metrics_port}").parse().unwrap(),
         },
         runtime: settings.runtime,
+        signer: SignerConfig::default(),
         logging: LoggingConfig::default(),
         value_sync: ValueSyncConfig::default(),
     }
@@ -19,11 +19,12 @@ use malachitebft_app_channel::app::types::codec::Codec;
 use malachitebft_app_channel::app::types::core::{
     CommitCertificate, Round, Validity, VoteExtensions,
 };
+use malachitebft_app_channel::app::types::signing::SigningProvider;
 use malachitebft_app_channel::app::types::{LocallyProposedValue, PeerId};
 use malachitebft_test::codec::proto::ProtobufCodec;
 use malachitebft_test::{
-    Address, Ed25519Provider, Genesis, Height, ProposalData, ProposalFin, ProposalInit,
-    ProposalPart, TestContext, ValidatorSet, Value,
+    Address, Genesis, Height, ProposalData, ProposalFin, ProposalInit, ProposalPart, Signature,
+    TestContext, ValidatorSet, Value,
 };
 
 use crate::store::{DecidedValue, Store};
@@ -37,7 +38,7 @@ const HISTORY_LENGTH: u64 = 1000;
 pub struct State {
     #[allow(dead_code)]
     ctx: TestContext,
-    signing_provider: Ed25519Provider,
+    signing_provider: Box<dyn SigningProvider<TestContext>>,
     genesis: Genesis,
     address: Address,
     vote_extensions: HashMap<Height, VoteExtensions<TestContext>>,
@@ -105,7 +106,7 @@ impl State {
     /// Creates a new State instance with the given validator address and starting height
     pub fn new(
         ctx: TestContext,
-        signing_provider: Ed25519Provider,
+        signing_provider: Box<dyn SigningProvider<TestContext>>,
         genesis: Genesis,
         address: Address,
         height: Height,
@@ -135,7 +136,7 @@ impl State {
     }
 
     /// Validates a proposal by checking both proposer and signature
-    pub fn validate_proposal_parts(
+    pub async fn validate_proposal_parts(
         &self,
         parts: &ProposalParts,
     ) -> Result<(), ProposalValidationError> {
@@ -159,17 +160,18 @@ impl State {
 
         // If proposer is correct, verify the signature
         self.verify_proposal_parts_signature(parts)
+            .await
             .map_err(ProposalValidationError::Signature)?;
 
         Ok(())
     }
 
     /// Verify proposal signature
-    fn verify_proposal_parts_signature(
+    async fn verify_proposal_parts_signature(
         &self,
         parts: &ProposalParts,
     ) -> Result<(), SignatureVerificationError> {
-        let mut hasher = sha3::Keccak256::new();
+        let mut hasher = sha3::Keccak512::new();
 
         let init = parts
             .init()
@@ -199,10 +201,17 @@ impl State {
             .ok_or(SignatureVerificationError::ProposerNotFound)?;
 
         // Verify the signature
-        if !self
+        let valid = self
             .signing_provider
-            .verify(&hash, &fin.signature, &proposer.public_key)
-        {
+            .verify_signed_proposal_part(
+                &fin_sign_part(&hash),
+                &fin.signature,
+                &proposer.public_key,
+            )
+            .await
+            .is_ok_and(|result| result.is_valid());
+
+        if !valid {
             return Err(SignatureVerificationError::InvalidSignature);
         }
 
@@ -244,7 +253,7 @@ impl State {
         }
 
         // For current height, validate proposal (proposer + signature)
-        match self.validate_proposal_parts(&parts) {
+        match self.validate_proposal_parts(&parts).await {
             Ok(()) => {
                 // Validation passed - assemble and store as undecided
                 let value = Self::assemble_value_from_parts(parts)?;
@@ -414,12 +423,12 @@ impl State {
 
     /// Creates a stream message containing a proposal part.
     /// Updates internal sequence number and current proposal.
-    pub fn stream_proposal(
+    pub async fn stream_proposal(
         &mut self,
         value: LocallyProposedValue<TestContext>,
         pol_round: Round,
-    ) -> impl Iterator<Item = StreamMessage<ProposalPart>> {
-        let parts = self.value_to_parts(value, pol_round);
+    ) -> eyre::Result<impl Iterator<Item = StreamMessage<ProposalPart>>> {
+        let parts = self.value_to_parts(value, pol_round).await?;
         let stream_id = self.stream_id();
 
         let mut msgs = Vec::with_capacity(parts.len() + 1);
@@ -437,7 +446,7 @@ impl State {
             StreamContent::Fin,
         ));
 
-        msgs.into_iter()
+        Ok(msgs.into_iter())
     }
 
     fn stream_id(&self) -> StreamId {
@@ -447,12 +456,12 @@ impl State {
         StreamId::new(bytes.into())
     }
 
-    fn value_to_parts(
+    async fn value_to_parts(
         &self,
         value: LocallyProposedValue<TestContext>,
         pol_round: Round,
-    ) -> Vec<ProposalPart> {
-        let mut hasher = sha3::Keccak256::new();
+    ) -> eyre::Result<Vec<ProposalPart>> {
+        let mut hasher = sha3::Keccak512::new();
         let mut parts = Vec::new();
 
         // Init
@@ -482,12 +491,15 @@ impl State {
         // Fin
         // Sign the hash of the proposal parts
         {
-            let hash = hasher.finalize().to_vec();
-            let signature = self.signing_provider.sign(&hash);
-            parts.push(ProposalPart::Fin(ProposalFin::new(signature)));
+            let hash = hasher.finalize();
+            let signed = self
+                .signing_provider
+                .sign_proposal_part(fin_sign_part(&hash))
+                .await?;
+            parts.push(ProposalPart::Fin(ProposalFin::new(signed.signature)));
         }
 
-        parts
+        Ok(parts)
     }
 
     /// Returns the validator set for the given height.
@@ -538,6 +550,15 @@ impl State {
     }
 }
 
+/// The part signed by the proposer for the `Fin` part of a proposal with the given hash:
+/// a `Fin` part carrying the hash in place of the signature, so that the signature commits
+/// to all the parts while being made with `sign_proposal_part`, eg. by a remote signer.
+fn fin_sign_part(hash: &[u8]) -> ProposalPart {
+    let mut bytes = [0; 64];
+    bytes.copy_from_slice(hash);
+    ProposalPart::Fin(ProposalFin::new(Signature::from_bytes(bytes)))
+}
+
 /// Encode a Value to its byte representation
 pub fn encode_value(value: &Value) -> Bytes {
     ProtobufCodec.encode(value).unwrap()